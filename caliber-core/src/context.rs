@@ -786,6 +786,27 @@ impl ContextAssembler {
             sections.push(section);
         }
 
+        // Add conversation history section (turns, in sequence order)
+        if !pkg.conversation_turns.is_empty() {
+            let content = self.format_turns(&pkg.conversation_turns);
+            let sources: Vec<SourceRef> = pkg
+                .conversation_turns
+                .iter()
+                .map(|t| SourceRef {
+                    source_type: EntityType::Turn,
+                    id: Some(t.turn_id.as_uuid()),
+                    relevance_score: None,
+                })
+                .collect();
+            let section = ContextSection::new(
+                SectionType::ConversationHistory,
+                content,
+                self.config.section_priorities.history,
+            )
+            .with_sources(sources);
+            sections.push(section);
+        }
+
         // Add history section (scope summaries)
         if !pkg.scope_summaries.is_empty() {
             let content = self.format_scope_summaries(&pkg.scope_summaries);
@@ -848,6 +869,15 @@ impl ContextAssembler {
             .join("\n\n")
     }
 
+    /// Format conversation turns into a string.
+    fn format_turns(&self, turns: &[Turn]) -> String {
+        turns
+            .iter()
+            .map(|t| format!("{}: {}", t.role, t.content))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     /// Format scope summaries into a string.
     fn format_scope_summaries(&self, summaries: &[ScopeSummary]) -> String {
         summaries
@@ -1186,7 +1216,8 @@ pub enum SegmentBudgetError {
 mod tests {
     use super::*;
     use crate::{
-        ContextPersistence, NoteId, NoteType, RetryConfig, SectionPriorities, ValidationMode, TTL,
+        ContextPersistence, NoteId, NoteType, RetryConfig, SectionPriorities, TurnId, TurnRole,
+        ValidationMode, TTL,
     };
     use std::time::Duration;
 
@@ -1243,6 +1274,21 @@ mod tests {
         }
     }
 
+    fn make_test_turn(scope_id: ScopeId, sequence: i32, role: TurnRole, content: &str) -> Turn {
+        Turn {
+            turn_id: TurnId::now_v7(),
+            scope_id,
+            sequence,
+            role,
+            content: content.to_string(),
+            token_count: estimate_tokens(content),
+            created_at: Utc::now(),
+            tool_calls: None,
+            tool_results: None,
+            metadata: None,
+        }
+    }
+
     #[test]
     fn test_estimate_tokens_empty() {
         assert_eq!(estimate_tokens(""), 0);
@@ -1333,6 +1379,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_context_assembler_with_turns() -> CaliberResult<()> {
+        let config = make_test_config(10000);
+        let assembler = ContextAssembler::new(config)?;
+
+        let scope_id = ScopeId::now_v7();
+        let turns = vec![
+            make_test_turn(scope_id, 1, TurnRole::User, "Hello"),
+            make_test_turn(scope_id, 2, TurnRole::Assistant, "Hi there"),
+        ];
+
+        let pkg = ContextPackage::new(TrajectoryId::now_v7(), scope_id).with_turns(turns);
+
+        let window = assembler.assemble(pkg)?;
+        let history = window
+            .sections
+            .iter()
+            .find(|s| s.section_type == SectionType::ConversationHistory);
+        assert!(history.is_some_and(|s| s.sources.len() == 2 && s.content.contains("Hi there")));
+        Ok(())
+    }
+
     #[test]
     fn test_context_assembler_respects_budget() -> CaliberResult<()> {
         // Very small budget
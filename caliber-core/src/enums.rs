@@ -675,6 +675,54 @@ impl fmt::Display for SummarizationTrigger {
     }
 }
 
+// ============================================================================
+// TTL STRING CONVERSION
+// ============================================================================
+
+/// Convert a TTL to its storage string (e.g. `"persistent"`, `"duration:60000"`).
+///
+/// This is the canonical form written to `ttl` columns and JSON; it round-trips
+/// through [`ttl_from_str`].
+pub fn ttl_to_str(ttl: &TTL) -> String {
+    match ttl {
+        TTL::Persistent => "persistent".to_string(),
+        TTL::Session => "session".to_string(),
+        TTL::Scope => "scope".to_string(),
+        TTL::Duration(ms) => format!("duration:{}", ms),
+        TTL::Ephemeral => "ephemeral".to_string(),
+        TTL::ShortTerm => "short_term".to_string(),
+        TTL::MediumTerm => "medium_term".to_string(),
+        TTL::LongTerm => "long_term".to_string(),
+        TTL::Permanent => "permanent".to_string(),
+        TTL::Max(n) => format!("max:{}", n),
+    }
+}
+
+/// Parse a TTL storage string produced by [`ttl_to_str`].
+///
+/// Returns `None` for unknown values or malformed `duration:<ms>` / `max:<n>`.
+pub fn ttl_from_str(s: &str) -> Option<TTL> {
+    match s {
+        "persistent" => Some(TTL::Persistent),
+        "session" => Some(TTL::Session),
+        "scope" => Some(TTL::Scope),
+        "ephemeral" => Some(TTL::Ephemeral),
+        "short_term" => Some(TTL::ShortTerm),
+        "medium_term" => Some(TTL::MediumTerm),
+        "long_term" => Some(TTL::LongTerm),
+        "permanent" => Some(TTL::Permanent),
+        _ => {
+            if let Some(ms) = s.strip_prefix("duration:") {
+                ms.parse::<DurationMs>().ok().map(TTL::Duration)
+            } else if let Some(n) = s.strip_prefix("max:") {
+                n.parse::<usize>().ok().map(TTL::Max)
+            } else {
+                None
+            }
+        }
+    }
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -1380,6 +1428,39 @@ mod tests {
         }
     }
 
+    // ========================================================================
+    // TTL String Conversion Tests
+    // ========================================================================
+
+    #[test]
+    fn test_ttl_str_roundtrip() {
+        let variants = [
+            TTL::Persistent,
+            TTL::Session,
+            TTL::Scope,
+            TTL::Duration(60_000),
+            TTL::Ephemeral,
+            TTL::ShortTerm,
+            TTL::MediumTerm,
+            TTL::LongTerm,
+            TTL::Permanent,
+            TTL::Max(25),
+        ];
+
+        for original in variants {
+            let string = ttl_to_str(&original);
+            assert_eq!(ttl_from_str(&string), Some(original));
+        }
+    }
+
+    #[test]
+    fn test_ttl_from_str_rejects_unknown() {
+        assert_eq!(ttl_from_str("forever"), None);
+        assert_eq!(ttl_from_str("duration:"), None);
+        assert_eq!(ttl_from_str("duration:abc"), None);
+        assert_eq!(ttl_from_str("max:-1"), None);
+    }
+
     // ========================================================================
     // FromStr with Aliases Tests
     // ========================================================================
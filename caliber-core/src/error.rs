@@ -11,6 +11,9 @@ pub enum StorageError {
     #[error("Entity not found: {entity_type:?} with id {id}")]
     NotFound { entity_type: EntityType, id: Uuid },
 
+    #[error("Entity already exists: {entity_type:?} with id {id}")]
+    AlreadyExists { entity_type: EntityType, id: Uuid },
+
     #[error("Insert failed for {entity_type:?}: {reason}")]
     InsertFailed {
         entity_type: EntityType,
@@ -202,6 +205,18 @@ mod tests {
         assert!(msg.contains("00000000-0000-0000-0000-000000000000"));
     }
 
+    #[test]
+    fn test_storage_error_display_already_exists() {
+        let err = StorageError::AlreadyExists {
+            entity_type: EntityType::Turn,
+            id: Uuid::nil(),
+        };
+        let msg = format!("{}", err);
+        assert!(msg.contains("already exists"));
+        assert!(msg.contains("Turn"));
+        assert!(msg.contains("00000000-0000-0000-0000-000000000000"));
+    }
+
     #[test]
     fn test_validation_error_display_stale_data() {
         let err = ValidationError::StaleData {
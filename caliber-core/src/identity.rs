@@ -258,6 +258,23 @@ pub fn compute_content_hash(content: &[u8]) -> ContentHash {
     hash
 }
 
+/// Decode the creation time embedded in a UUIDv7 entity ID.
+///
+/// Returns `None` for IDs of any other version (e.g. `new_v4` or `nil`),
+/// whose leading bits carry no timestamp.
+pub fn entity_id_timestamp<T: EntityIdType>(id: T) -> Option<Timestamp> {
+    let uuid = id.as_uuid();
+    if uuid.get_version_num() != 7 {
+        return None;
+    }
+    // The first 48 bits are big-endian Unix milliseconds
+    let bytes = uuid.as_bytes();
+    let millis = bytes[..6]
+        .iter()
+        .fold(0i64, |acc, b| (acc << 8) | i64::from(*b));
+    DateTime::from_timestamp_millis(millis)
+}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -317,6 +334,26 @@ mod tests {
         assert_eq!(id, deserialized);
     }
 
+    #[test]
+    fn test_entity_id_timestamp() {
+        let before = Utc::now().timestamp_millis();
+        let id = NoteId::now_v7();
+        let after = Utc::now().timestamp_millis();
+
+        let ts = entity_id_timestamp(id).expect("v7 ids embed a timestamp");
+        assert!((before..=after).contains(&ts.timestamp_millis()));
+
+        // Known value: 0x017F22E279B0 ms = 2022-02-22T19:22:22Z
+        let id = TenantId::from_str("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").unwrap();
+        assert_eq!(
+            entity_id_timestamp(id).map(|ts| ts.to_rfc3339()),
+            Some("2022-02-22T19:22:22+00:00".to_string())
+        );
+
+        assert_eq!(entity_id_timestamp(TenantId::new_v4()), None);
+        assert_eq!(entity_id_timestamp(TenantId::nil()), None);
+    }
+
     #[test]
     fn test_entity_id_default() {
         let id = TenantId::default();
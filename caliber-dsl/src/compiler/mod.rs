@@ -263,12 +263,34 @@ pub struct PolicyConfig {
 }
 
 /// Compiled policy rule.
+///
+/// Configs compiled before rules could carry several triggers stored a single
+/// `trigger`; they still deserialize, into a one-element `triggers` list.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CompiledPolicyRule {
-    pub trigger: CompiledTrigger,
+    #[serde(alias = "trigger", deserialize_with = "deserialize_triggers")]
+    pub triggers: Vec<CompiledTrigger>,
     pub actions: Vec<CompiledAction>,
 }
 
+/// Deserialize a trigger list, accepting a single legacy trigger as well.
+fn deserialize_triggers<'de, D>(deserializer: D) -> Result<Vec<CompiledTrigger>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        Many(Vec<CompiledTrigger>),
+        One(CompiledTrigger),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::Many(triggers) => triggers,
+        OneOrMany::One(trigger) => vec![trigger],
+    })
+}
+
 /// Compiled injection configuration.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InjectionConfig {
@@ -790,6 +812,12 @@ impl DslCompiler {
     /// Compile a memory definition.
     fn compile_memory(def: &MemoryDef) -> CompileResult<MemoryConfig> {
         let memory_type = Self::compile_memory_type(&def.memory_type)?;
+        for field in &def.schema {
+            if let (FieldType::Enum(variants), Some(default)) = (&field.field_type, &field.default)
+            {
+                Self::check_enum_default(&def.name, &field.name, variants, default)?;
+            }
+        }
         let schema = def
             .schema
             .iter()
@@ -1025,13 +1053,22 @@ impl DslCompiler {
     }
 
     fn compile_policy_rule(rule: &PolicyRule) -> CompileResult<CompiledPolicyRule> {
-        let trigger = Self::compile_trigger(&rule.trigger)?;
+        if rule.triggers.is_empty() {
+            return Err(CompileError::MissingField {
+                field: "policy rule triggers".to_string(),
+            });
+        }
+        let triggers = rule
+            .triggers
+            .iter()
+            .map(Self::compile_trigger)
+            .collect::<CompileResult<Vec<_>>>()?;
         let actions = rule
             .actions
             .iter()
             .map(Self::compile_action)
             .collect::<CompileResult<Vec<_>>>()?;
-        Ok(CompiledPolicyRule { trigger, actions })
+        Ok(CompiledPolicyRule { triggers, actions })
     }
 
     /// Compile an injection definition.
@@ -1344,6 +1381,85 @@ impl DslCompiler {
         Ok(Duration::from_millis((num * multiplier as f64) as u64))
     }
 
+    /// Check that an enum field's default is one of its declared variants.
+    fn check_enum_default(
+        memory: &str,
+        field: &str,
+        variants: &[String],
+        default: &FilterValue,
+    ) -> CompileResult<()> {
+        let valid = match default {
+            FilterValue::String(s) => variants.contains(s),
+            FilterValue::Null => true,
+            _ => false,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(CompileError::InvalidValue {
+                field: format!("{}.{}.default", memory, field),
+                reason: format!(
+                    "{:?} is not one of the enum variants [{}]",
+                    default,
+                    variants.join(", ")
+                ),
+            })
+        }
+    }
+
+    /// Check every comparison in `filter` against enum fields of `memory`.
+    ///
+    /// `location` names where the filter appears (e.g. `policy 'cleanup'`) so
+    /// the error points back at the offending definition. Pattern operators
+    /// (`contains`, `regex`) are skipped since their value is not a variant.
+    fn check_enum_filter(
+        memory: &MemoryConfig,
+        filter: &CompiledFilter,
+        location: &str,
+    ) -> CompileResult<()> {
+        match filter {
+            CompiledFilter::Comparison { field, op, value } => {
+                if matches!(op, CompiledOperator::Contains | CompiledOperator::Regex) {
+                    return Ok(());
+                }
+                let variants = match memory.schema.iter().find(|f| &f.name == field) {
+                    Some(FieldConfig {
+                        field_type: caliber_core::FieldType::Enum { variants },
+                        ..
+                    }) => variants,
+                    _ => return Ok(()),
+                };
+                let values = match value {
+                    CompiledFilterValue::Array(items) => items.iter().collect(),
+                    single => vec![single],
+                };
+                for value in values {
+                    let valid = match value {
+                        CompiledFilterValue::String(s) => variants.contains(s),
+                        CompiledFilterValue::Null => true,
+                        _ => false,
+                    };
+                    if !valid {
+                        return Err(CompileError::InvalidValue {
+                            field: format!("{}.{}", memory.name, field),
+                            reason: format!(
+                                "filter in {} compares against {:?}, which is not one of the enum variants [{}]",
+                                location,
+                                value,
+                                variants.join(", ")
+                            ),
+                        });
+                    }
+                }
+                Ok(())
+            }
+            CompiledFilter::And(filters) | CompiledFilter::Or(filters) => filters
+                .iter()
+                .try_for_each(|f| Self::check_enum_filter(memory, f, location)),
+            CompiledFilter::Not(inner) => Self::check_enum_filter(memory, inner, location),
+        }
+    }
+
     /// Final validation pass - check cross-references.
     fn validate(&self) -> CompileResult<()> {
         // Validate agent references in trajectories
@@ -1407,6 +1523,35 @@ impl DslCompiler {
             // Target could be a context slot, not necessarily a memory
         }
 
+        // Validate filter values against enum fields of the filtered memory
+        for injection in &self.config.injections {
+            if let (Some(filter), Some(memory)) = (
+                &injection.filter,
+                self.config
+                    .memories
+                    .iter()
+                    .find(|m| m.name == injection.source),
+            ) {
+                let location =
+                    format!("injection '{}' -> '{}'", injection.source, injection.target);
+                Self::check_enum_filter(memory, filter, &location)?;
+            }
+        }
+        for policy in &self.config.policies {
+            for rule in &policy.rules {
+                for action in &rule.actions {
+                    if let CompiledAction::Prune { target, criteria } = action {
+                        if let Some(memory) =
+                            self.config.memories.iter().find(|m| &m.name == target)
+                        {
+                            let location = format!("policy '{}'", policy.name);
+                            Self::check_enum_filter(memory, criteria, &location)?;
+                        }
+                    }
+                }
+            }
+        }
+
         // Validate evolution baseline/candidate references
         // (These reference snapshot names which are runtime entities, skip for now)
 
@@ -1574,4 +1719,121 @@ mod tests {
             CompileError::InvalidValue { field, .. } if field == "benchmark_queries"
         ));
     }
+
+    fn status_memory(default: Option<FilterValue>) -> Definition {
+        Definition::Memory(MemoryDef {
+            name: "tickets".to_string(),
+            memory_type: MemoryType::Episodic,
+            schema: vec![FieldDef {
+                name: "status".to_string(),
+                field_type: FieldType::Enum(vec!["open".to_string(), "closed".to_string()]),
+                nullable: false,
+                default,
+                security: None,
+            }],
+            retention: Retention::Persistent,
+            lifecycle: Lifecycle::Explicit,
+            parent: None,
+            indexes: vec![],
+            inject_on: vec![],
+            artifacts: vec![],
+            modifiers: vec![],
+        })
+    }
+
+    fn status_filter(value: FilterValue) -> FilterExpr {
+        FilterExpr::Comparison {
+            field: "status".to_string(),
+            op: CompareOp::Eq,
+            value,
+        }
+    }
+
+    #[test]
+    fn test_compile_enum_default_not_a_variant() {
+        let ast = CaliberAst {
+            version: "1.0".to_string(),
+            definitions: vec![status_memory(Some(FilterValue::String(
+                "pending".to_string(),
+            )))],
+        };
+        let err = DslCompiler::compile(&ast).unwrap_err();
+        assert!(matches!(
+            err,
+            CompileError::InvalidValue { field, .. } if field == "tickets.status.default"
+        ));
+    }
+
+    #[test]
+    fn test_compile_enum_filter_values_checked() {
+        let valid = CaliberAst {
+            version: "1.0".to_string(),
+            definitions: vec![
+                status_memory(Some(FilterValue::String("open".to_string()))),
+                Definition::Injection(InjectionDef {
+                    source: "tickets".to_string(),
+                    target: "context".to_string(),
+                    mode: InjectionMode::Full,
+                    priority: 1,
+                    max_tokens: None,
+                    filter: Some(status_filter(FilterValue::String("open".to_string()))),
+                }),
+            ],
+        };
+        assert!(DslCompiler::compile(&valid).is_ok());
+
+        let invalid = CaliberAst {
+            version: "1.0".to_string(),
+            definitions: vec![
+                status_memory(None),
+                Definition::Policy(PolicyDef {
+                    name: "cleanup".to_string(),
+                    rules: vec![PolicyRule {
+                        triggers: vec![Trigger::TaskEnd],
+                        actions: vec![Action::Prune {
+                            target: "tickets".to_string(),
+                            criteria: FilterExpr::Not(Box::new(status_filter(FilterValue::Array(
+                                vec![
+                                    FilterValue::String("open".to_string()),
+                                    FilterValue::String("archived".to_string()),
+                                ],
+                            )))),
+                        }],
+                    }],
+                }),
+            ],
+        };
+        match DslCompiler::compile(&invalid).unwrap_err() {
+            CompileError::InvalidValue { field, reason } => {
+                assert_eq!(field, "tickets.status");
+                assert!(reason.contains("policy 'cleanup'"), "{}", reason);
+                assert!(reason.contains("archived"), "{}", reason);
+            }
+            other => panic!("Expected InvalidValue, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compiled_policy_rule_reads_legacy_trigger() {
+        let legacy = serde_json::json!({
+            "name": "cleanup",
+            "rules": [{"trigger": "TaskEnd", "actions": []}]
+        });
+        let policy: PolicyConfig = serde_json::from_value(legacy).expect("legacy policy");
+        assert_eq!(policy.rules[0].triggers, vec![CompiledTrigger::TaskEnd]);
+
+        let rule = CompiledPolicyRule {
+            triggers: vec![
+                CompiledTrigger::TaskEnd,
+                CompiledTrigger::TurnCount { count: 3 },
+            ],
+            actions: vec![],
+        };
+        let json = serde_json::to_value(&rule).expect("serialize");
+        assert!(json.get("trigger").is_none());
+        assert_eq!(
+            serde_json::from_value::<CompiledPolicyRule>(json).expect("round trip"),
+            rule
+        );
+    }
 }
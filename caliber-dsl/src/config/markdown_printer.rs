@@ -145,7 +145,10 @@ pub fn ast_to_markdown(ast: &CaliberAst) -> String {
             ));
             output.push_str(&format!("    nullable: {}\n", field.nullable));
             if let Some(default) = &field.default {
-                output.push_str(&format!("    default: {}\n", yaml_safe_string(default)));
+                output.push_str(&format!(
+                    "    default: {}\n",
+                    default_value_to_yaml(default)
+                ));
             }
         }
         output.push_str(&format!(
@@ -194,10 +197,14 @@ pub fn ast_to_markdown(ast: &CaliberAst) -> String {
         output.push_str(&format!("```policy {}\n", policy.name));
         output.push_str("rules:\n");
         for rule in &policy.rules {
-            output.push_str(&format!(
-                "  - trigger: {}\n",
-                trigger_to_string(&rule.trigger)
-            ));
+            if let [trigger] = rule.triggers.as_slice() {
+                output.push_str(&format!("  - trigger: {}\n", trigger_to_string(trigger)));
+            } else {
+                output.push_str("  - triggers:\n");
+                for trigger in &rule.triggers {
+                    output.push_str(&format!("      - {}\n", trigger_to_string(trigger)));
+                }
+            }
             output.push_str("    actions:\n");
             for action in &rule.actions {
                 output.push_str(&action_to_yaml(action));
@@ -426,6 +433,36 @@ fn field_type_to_string(t: &FieldType) -> String {
     }
 }
 
+/// Render a typed field default as a YAML scalar or flow sequence.
+///
+/// Strings are quoted when needed (see `yaml_safe_string`) so a text default
+/// such as `"42"` is not re-read as a number. Strings inside a list are always
+/// quoted because commas and brackets are significant in flow sequences.
+fn default_value_to_yaml(value: &FilterValue) -> String {
+    match value {
+        FilterValue::Array(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| match item {
+                    FilterValue::String(s) => {
+                        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+                    }
+                    other => default_value_to_yaml(other),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        FilterValue::String(s) => yaml_safe_string(s),
+        FilterValue::Number(n) => n.to_string(),
+        FilterValue::Bool(b) => b.to_string(),
+        FilterValue::Null => "null".to_string(),
+        FilterValue::CurrentTrajectory => "current_trajectory".to_string(),
+        FilterValue::CurrentScope => "current_scope".to_string(),
+        FilterValue::Now => "now".to_string(),
+    }
+}
+
 /// Convert a Retention value into its canonical string form.
 ///
 /// Produces one of: "persistent", "session", "scope", "duration(<n>)", or "max(<n>)" depending on the variant.
@@ -109,7 +109,7 @@ pub struct FieldConfig {
     #[serde(default)]
     pub nullable: bool,
     #[serde(default)]
-    pub default: Option<String>,
+    pub default: Option<serde_yaml::Value>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
@@ -134,7 +134,11 @@ pub struct PolicyConfig {
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct PolicyRuleConfig {
-    pub trigger: String,
+    /// Single trigger shorthand; mutually exclusive with `triggers`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub triggers: Vec<String>,
     pub actions: Vec<ActionConfig>,
 }
 
@@ -434,6 +438,18 @@ pub fn parse_memory_block(
 /// let def = parse_policy_block(None, yaml).unwrap();
 /// assert_eq!(def.name, "example-policy");
 /// assert_eq!(def.rules.len(), 1);
+///
+/// // A rule may fire on several triggers
+/// let yaml = r#"
+/// name: multi
+/// rules:
+///   - triggers: [task_end, scope_close]
+///     actions:
+///       - summarize:
+///           target: "summary-target"
+/// "#;
+/// let def = parse_policy_block(None, yaml).unwrap();
+/// assert_eq!(def.rules[0].triggers.len(), 2);
 /// ```
 pub fn parse_policy_block(
     header_name: Option<String>,
@@ -934,12 +950,15 @@ fn parse_trigger(s: &str) -> Result<Trigger, ConfigError> {
 /// Converts a YAML-deserialized FieldConfig into the internal FieldDef.
 ///
 /// The function parses the textual `field_type` into a `FieldType` and
-/// constructs a `FieldDef` preserving `name`, `nullable`, and `default`.
-/// The `security` field is set to `None` because YAML-based security is not supported yet.
+/// constructs a `FieldDef` preserving `name` and `nullable`. A `default`, if
+/// present, is converted to a typed `FilterValue` and checked against the
+/// field type. The `security` field is set to `None` because YAML-based
+/// security is not supported yet.
 ///
 /// # Returns
 ///
-/// `FieldDef` built from the given config; `Err(ConfigError)` if the `field_type` is invalid.
+/// `FieldDef` built from the given config; `Err(ConfigError)` if the `field_type`
+/// is invalid or the default is not compatible with it.
 ///
 /// # Examples
 ///
@@ -955,21 +974,107 @@ fn parse_trigger(s: &str) -> Result<Trigger, ConfigError> {
 /// ```
 fn parse_field_def(config: FieldConfig) -> Result<FieldDef, ConfigError> {
     let field_type = parse_field_type(&config.field_type)?;
+    let default = config
+        .default
+        .as_ref()
+        .map(|value| parse_field_default(&config.name, &field_type, config.nullable, value))
+        .transpose()?;
     Ok(FieldDef {
         name: config.name,
         field_type,
         nullable: config.nullable,
-        default: config.default,
+        default,
         security: None, // Not supported in YAML yet
     })
 }
 
+/// Converts a YAML default value into a `FilterValue` compatible with `field_type`.
+///
+/// Integers are required for `int`, any number for `float`, booleans for `bool`,
+/// and strings for `text`, `uuid`, `json`, and enum members. A `timestamp`
+/// default may be any string, with `now` mapping to `FilterValue::Now`. Arrays
+/// check each element against the inner type. `null` is only accepted when the
+/// field is nullable, and embeddings cannot have defaults.
+///
+/// # Examples
+///
+/// ```ignore
+/// let value = serde_yaml::Value::from(3);
+/// assert_eq!(
+///     parse_field_default("count", &FieldType::Int, false, &value).unwrap(),
+///     FilterValue::Number(3.0)
+/// );
+/// let text = serde_yaml::Value::from("three");
+/// assert!(parse_field_default("count", &FieldType::Int, false, &text).is_err());
+/// ```
+fn parse_field_default(
+    field: &str,
+    field_type: &FieldType,
+    nullable: bool,
+    value: &serde_yaml::Value,
+) -> Result<FilterValue, ConfigError> {
+    use serde_yaml::Value;
+
+    let mismatch = |expected: &str| {
+        ConfigError::InvalidValue(format!(
+            "Default for field '{}' must be {}, got {:?}",
+            field, expected, value
+        ))
+    };
+
+    if value.is_null() {
+        return if nullable {
+            Ok(FilterValue::Null)
+        } else {
+            Err(mismatch("non-null (field is not nullable)"))
+        };
+    }
+
+    match (field_type, value) {
+        (FieldType::Int, Value::Number(n)) if n.is_i64() || n.is_u64() => n
+            .as_f64()
+            .map(FilterValue::Number)
+            .ok_or_else(|| mismatch("an integer")),
+        (FieldType::Int, _) => Err(mismatch("an integer")),
+        (FieldType::Float, Value::Number(n)) => n
+            .as_f64()
+            .map(FilterValue::Number)
+            .ok_or_else(|| mismatch("a number")),
+        (FieldType::Float, _) => Err(mismatch("a number")),
+        (FieldType::Bool, Value::Bool(b)) => Ok(FilterValue::Bool(*b)),
+        (FieldType::Bool, _) => Err(mismatch("a boolean")),
+        (FieldType::Text | FieldType::Uuid | FieldType::Json, Value::String(s)) => {
+            Ok(FilterValue::String(s.clone()))
+        }
+        (FieldType::Text | FieldType::Uuid | FieldType::Json, _) => Err(mismatch("a string")),
+        (FieldType::Timestamp, Value::String(s)) if s.eq_ignore_ascii_case("now") => {
+            Ok(FilterValue::Now)
+        }
+        (FieldType::Timestamp, Value::String(s)) => Ok(FilterValue::String(s.clone())),
+        (FieldType::Timestamp, _) => Err(mismatch("a timestamp string or 'now'")),
+        (FieldType::Enum(variants), Value::String(s)) if variants.contains(s) => {
+            Ok(FilterValue::String(s.clone()))
+        }
+        (FieldType::Enum(variants), _) => {
+            Err(mismatch(&format!("one of [{}]", variants.join(", "))))
+        }
+        (FieldType::Array(inner), Value::Sequence(items)) => items
+            .iter()
+            .map(|item| parse_field_default(field, inner, false, item))
+            .collect::<Result<Vec<_>, _>>()
+            .map(FilterValue::Array),
+        (FieldType::Array(_), _) => Err(mismatch("a list")),
+        (FieldType::Embedding(_), _) => Err(mismatch("absent (embeddings have no default)")),
+    }
+}
+
 /// Parses a field type identifier into a `FieldType`.
 ///
 /// Accepts case-insensitive names: `uuid`, `text`, `int`, `float`, `bool`,
 /// `timestamp`, and `json`. Recognizes `embedding:<dim>` where `<dim>` is an
 /// integer; if the dimension fails to parse the embedding's dimension will be
-/// `None` (the embedding type is still returned). Returns `Err(ConfigError::InvalidValue(_))`
+/// `None` (the embedding type is still returned). Recognizes `enum(a, b, ...)`;
+/// variant names keep their original case. Returns `Err(ConfigError::InvalidValue(_))`
 /// for unknown type strings.
 ///
 /// # Examples
@@ -978,6 +1083,10 @@ fn parse_field_def(config: FieldConfig) -> Result<FieldDef, ConfigError> {
 /// assert_eq!(parse_field_type("text").unwrap(), FieldType::Text);
 /// assert_eq!(parse_field_type("EMBEDDING:128").unwrap(), FieldType::Embedding(Some(128)));
 /// assert_eq!(parse_field_type("embedding:bad").unwrap(), FieldType::Embedding(None));
+/// assert_eq!(
+///     parse_field_type("enum(Open, Closed)").unwrap(),
+///     FieldType::Enum(vec!["Open".to_string(), "Closed".to_string()])
+/// );
 /// assert!(matches!(parse_field_type("unknown"), Err(ConfigError::InvalidValue(_))));
 /// ```
 fn parse_field_type(s: &str) -> Result<FieldType, ConfigError> {
@@ -993,6 +1102,19 @@ fn parse_field_type(s: &str) -> Result<FieldType, ConfigError> {
             if let Some(dim_str) = other.strip_prefix("embedding:") {
                 let dim = dim_str.parse().ok();
                 Ok(FieldType::Embedding(dim))
+            } else if other.starts_with("enum(") && other.ends_with(')') {
+                // Slice the original string so variant names keep their case
+                let variants: Vec<String> = s[5..s.len() - 1]
+                    .split(',')
+                    .map(|v| v.trim().to_string())
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                if variants.is_empty() {
+                    return Err(ConfigError::InvalidValue(
+                        "Enum field type requires at least one variant".to_string(),
+                    ));
+                }
+                Ok(FieldType::Enum(variants))
             } else {
                 Err(ConfigError::InvalidValue(format!(
                     "Unknown field type '{}'",
@@ -1143,27 +1265,46 @@ fn parse_modifier(s: String) -> Result<ModifierDef, ConfigError> {
 
 /// Converts a deserialized PolicyRuleConfig into a validated PolicyRule.
 ///
-/// Returns an error if the rule's trigger or any contained action is invalid.
+/// A rule names either a single `trigger` or a non-empty `triggers` list, never both.
+/// Returns an error if that doesn't hold or if any trigger or contained action is invalid.
 ///
 /// # Examples
 ///
 /// ```
 /// let cfg = PolicyRuleConfig {
-///     trigger: "task_end".to_string(),
+///     trigger: None,
+///     triggers: vec!["task_end".to_string(), "scope_close".to_string()],
 ///     actions: vec![ActionConfig::Summarize { target: "log".to_string() }],
 /// };
 /// let rule = parse_policy_rule(cfg).expect("valid policy rule");
-/// assert_eq!(rule.trigger.to_string(), "task_end");
+/// assert_eq!(rule.triggers, vec![Trigger::TaskEnd, Trigger::ScopeClose]);
 /// assert_eq!(rule.actions.len(), 1);
 /// ```
 fn parse_policy_rule(config: PolicyRuleConfig) -> Result<PolicyRule, ConfigError> {
-    let trigger = parse_trigger(&config.trigger)?;
+    let triggers = match (config.trigger, config.triggers.is_empty()) {
+        (Some(trigger), true) => vec![parse_trigger(&trigger)?],
+        (None, false) => config
+            .triggers
+            .iter()
+            .map(|t| parse_trigger(t))
+            .collect::<Result<Vec<_>, _>>()?,
+        (Some(_), false) => {
+            return Err(ConfigError::InvalidValue(
+                "policy rule cannot set both 'trigger' and 'triggers'".to_string(),
+            ));
+        }
+        (None, true) => {
+            return Err(ConfigError::InvalidValue(
+                "policy rule requires 'trigger' or a non-empty 'triggers' list".to_string(),
+            ));
+        }
+    };
     let actions = config
         .actions
         .into_iter()
         .map(parse_action)
         .collect::<Result<Vec<_>, _>>()?;
-    Ok(PolicyRule { trigger, actions })
+    Ok(PolicyRule { triggers, actions })
 }
 
 /// Converts an ActionConfig (parsed from YAML) into the corresponding internal Action.
@@ -1517,4 +1658,132 @@ default_freshness:
             _ => panic!("Expected BestEffort variant"),
         }
     }
+
+    #[test]
+    fn test_memory_field_defaults_are_typed() {
+        let yaml = r#"
+memory_type: semantic
+retention: persistent
+lifecycle: explicit
+schema:
+  - name: count
+    type: int
+    default: 3
+  - name: score
+    type: float
+    default: 0.5
+  - name: active
+    type: bool
+    default: true
+  - name: label
+    type: text
+    default: "42"
+  - name: seen_at
+    type: timestamp
+    default: now
+"#;
+        let result = parse_memory_block(Some("facts".to_string()), yaml);
+        assert!(result.is_ok(), "Failed to parse: {:?}", result.err());
+
+        let memory = result.expect("memory parsing verified above");
+        let defaults: Vec<_> = memory.schema.iter().map(|f| f.default.clone()).collect();
+        assert_eq!(
+            defaults,
+            vec![
+                Some(FilterValue::Number(3.0)),
+                Some(FilterValue::Number(0.5)),
+                Some(FilterValue::Bool(true)),
+                Some(FilterValue::String("42".to_string())),
+                Some(FilterValue::Now),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_memory_field_default_type_mismatch() {
+        let cases = [
+            ("int", "\"three\""),
+            ("int", "2.5"),
+            ("bool", "\"yes\""),
+            ("text", "7"),
+            ("timestamp", "5"),
+        ];
+        for (field_type, default) in cases {
+            let yaml = format!(
+                "memory_type: semantic\nretention: persistent\nlifecycle: explicit\nschema:\n  - name: f\n    type: {}\n    default: {}\n",
+                field_type, default
+            );
+            match parse_memory_block(Some("facts".to_string()), &yaml) {
+                Err(ConfigError::InvalidValue(msg)) => {
+                    assert!(msg.contains("'f'"), "Expected field name in: {}", msg)
+                }
+                other => panic!(
+                    "Expected InvalidValue for {} default {}, got: {:?}",
+                    field_type, default, other
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_type_enum_parsing() {
+        assert_eq!(
+            parse_field_type("enum(Open, Closed)").expect("valid enum type"),
+            FieldType::Enum(vec!["Open".to_string(), "Closed".to_string()])
+        );
+        assert!(matches!(
+            parse_field_type("enum()"),
+            Err(ConfigError::InvalidValue(_))
+        ));
+    }
+
+    #[test]
+    fn test_policy_rule_multiple_triggers() {
+        let yaml = r#"
+rules:
+  - triggers: [task_end, scope_close]
+    actions:
+      - type: summarize
+        target: notes
+  - trigger: turn_end
+    actions:
+      - type: checkpoint
+        target: scope
+"#;
+        let policy =
+            parse_policy_block(Some("cleanup".to_string()), yaml).expect("valid policy block");
+        assert_eq!(
+            policy.rules[0].triggers,
+            vec![Trigger::TaskEnd, Trigger::ScopeClose]
+        );
+        assert_eq!(policy.rules[1].triggers, vec![Trigger::TurnEnd]);
+
+        let markdown = crate::ast_to_markdown(&CaliberAst {
+            version: "1.0".to_string(),
+            definitions: vec![Definition::Policy(policy.clone())],
+        });
+        assert!(markdown.contains("  - triggers:\n      - task_end\n      - scope_close\n"));
+        assert!(markdown.contains("  - trigger: turn_end\n"));
+    }
+
+    #[test]
+    fn test_policy_rule_trigger_conflicts() {
+        let both = r#"
+rules:
+  - trigger: task_end
+    triggers: [scope_close]
+    actions: []
+"#;
+        let neither = r#"
+rules:
+  - triggers: []
+    actions: []
+"#;
+        for yaml in [both, neither] {
+            assert!(matches!(
+                parse_policy_block(Some("p".to_string()), yaml),
+                Err(ConfigError::InvalidValue(_))
+            ));
+        }
+    }
 }
@@ -650,7 +650,10 @@ fn build_policies(manifest: &PackManifest) -> Result<Vec<PolicyDef>, PackError>
         }
         policies.push(PolicyDef {
             name: name.clone(),
-            rules: vec![PolicyRule { trigger, actions }],
+            rules: vec![PolicyRule {
+                triggers: vec![trigger],
+                actions,
+            }],
         });
     }
     Ok(policies)
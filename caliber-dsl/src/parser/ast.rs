@@ -9,10 +9,16 @@ use serde::{Deserialize, Serialize};
 /// The root AST node for a CALIBER configuration.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CaliberAst {
+    #[serde(default = "default_ast_version")]
     pub version: String,
+    #[serde(default)]
     pub definitions: Vec<Definition>,
 }
 
+fn default_ast_version() -> String {
+    "1.0".to_string()
+}
+
 /// A top-level definition in the DSL.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Definition {
@@ -37,6 +43,7 @@ pub struct AdapterDef {
     pub name: String,
     pub adapter_type: AdapterType,
     pub connection: String,
+    #[serde(default)]
     pub options: Vec<(String, String)>,
 }
 
@@ -53,14 +60,19 @@ pub enum AdapterType {
 pub struct MemoryDef {
     pub name: String,
     pub memory_type: MemoryType,
+    #[serde(default)]
     pub schema: Vec<FieldDef>,
     pub retention: Retention,
     pub lifecycle: Lifecycle,
     pub parent: Option<String>,
+    #[serde(default)]
     pub indexes: Vec<IndexDef>,
+    #[serde(default)]
     pub inject_on: Vec<Trigger>,
+    #[serde(default)]
     pub artifacts: Vec<String>,
     /// DSL-first: Memory modifiers (embeddable, summarizable, lockable)
+    #[serde(default)]
     pub modifiers: Vec<ModifierDef>,
 }
 
@@ -80,8 +92,10 @@ pub enum MemoryType {
 pub struct FieldDef {
     pub name: String,
     pub field_type: FieldType,
+    #[serde(default)]
     pub nullable: bool,
-    pub default: Option<String>,
+    /// Typed default value, validated against `field_type` at parse time.
+    pub default: Option<FilterValue>,
     /// Optional security configuration for PII fields.
     pub security: Option<FieldSecurity>,
 }
@@ -134,6 +148,7 @@ pub enum Trigger {
 pub struct IndexDef {
     pub field: String,
     pub index_type: IndexType,
+    #[serde(default)]
     pub options: Vec<(String, String)>,
 }
 
@@ -151,13 +166,15 @@ pub enum IndexType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PolicyDef {
     pub name: String,
+    #[serde(default)]
     pub rules: Vec<PolicyRule>,
 }
 
-/// A single policy rule.
+/// A single policy rule. The actions run when any of its triggers fires.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PolicyRule {
-    pub trigger: Trigger,
+    pub triggers: Vec<Trigger>,
+    #[serde(default)]
     pub actions: Vec<Action>,
 }
 
@@ -180,6 +197,7 @@ pub enum Action {
     AutoSummarize {
         source_level: AbstractionLevelDsl,
         target_level: AbstractionLevelDsl,
+        #[serde(default)]
         create_edges: bool,
     },
 }
@@ -277,10 +295,12 @@ pub struct EvolutionDef {
     /// Snapshot name to compare against
     pub baseline: String,
     /// Candidate config names to test
+    #[serde(default)]
     pub candidates: Vec<String>,
     /// Number of queries to benchmark
     pub benchmark_queries: i32,
     /// Metrics to track
+    #[serde(default)]
     pub metrics: Vec<String>,
 }
 
@@ -318,10 +338,12 @@ pub enum SummarizationTriggerDsl {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SummarizationPolicyDef {
     pub name: String,
+    #[serde(default)]
     pub triggers: Vec<SummarizationTriggerDsl>,
     pub source_level: AbstractionLevelDsl,
     pub target_level: AbstractionLevelDsl,
     pub max_sources: i32,
+    #[serde(default)]
     pub create_edges: bool,
 }
 
@@ -346,6 +368,7 @@ pub struct TrajectoryDef {
     pub description: Option<String>,
     pub agent_type: String,
     pub token_budget: i32,
+    #[serde(default)]
     pub memory_refs: Vec<String>,
     pub metadata: Option<serde_json::Value>,
 }
@@ -374,13 +397,17 @@ pub struct TrajectoryDef {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AgentDef {
     pub name: String,
+    #[serde(default)]
     pub capabilities: Vec<String>,
+    #[serde(default)]
     pub constraints: AgentConstraints,
+    #[serde(default)]
     pub permissions: PermissionMatrix,
 }
 
 /// Agent runtime constraints.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AgentConstraints {
     pub max_concurrent: i32,
     pub timeout_ms: i64,
@@ -397,6 +424,7 @@ impl Default for AgentConstraints {
 
 /// Permission matrix for agent access control.
 #[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PermissionMatrix {
     pub read: Vec<String>,
     pub write: Vec<String>,
@@ -423,6 +451,7 @@ pub struct CacheDef {
     pub backend: CacheBackendType,
     pub path: Option<String>,
     pub size_mb: i32,
+    #[serde(default)]
     pub default_freshness: FreshnessDef,
     pub max_entries: Option<i32>,
     pub ttl: Option<String>,
@@ -472,6 +501,7 @@ pub struct ProviderDef {
     pub provider_type: ProviderType,
     pub api_key: EnvValue,
     pub model: String,
+    #[serde(default)]
     pub options: Vec<(String, String)>,
 }
 
@@ -514,6 +544,7 @@ pub enum ModifierDef {
     /// Summarizable modifier - enables auto-summarization
     Summarizable {
         style: SummaryStyle,
+        #[serde(default)]
         on_triggers: Vec<Trigger>,
     },
     /// Lockable modifier - enables distributed locking
@@ -558,6 +589,7 @@ pub enum PIIClassification {
 ///
 /// Controls how agents and systems can interact with sensitive fields.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct FieldSecurity {
     /// Sensitivity classification
     pub classification: PIIClassification,
@@ -0,0 +1,196 @@
+//! Versioned JSON representation of the AST
+//!
+//! Editors and external tools exchange `CaliberAst` values through this
+//! schema instead of the raw serde output. The document carries a
+//! `schema_version` next to the AST's own `version` (the DSL config version).
+//! Decoding ignores unknown fields and defaults missing collections, so tools
+//! built against older or newer schema versions keep interoperating.
+//!
+//! Schema history:
+//! - 1: initial serde layout
+//! - 2: policy rules carry a `triggers` list instead of a single `trigger`
+
+use super::ast::{CaliberAst, ParseError};
+
+/// Current version of the JSON schema written by [`ast_to_json`].
+pub const AST_JSON_SCHEMA_VERSION: u64 = 2;
+
+/// Serialize an AST to its stable JSON form, tagged with `schema_version`.
+pub fn ast_to_json(ast: &CaliberAst) -> Result<serde_json::Value, ParseError> {
+    let mut value = serde_json::to_value(ast).map_err(|e| ParseError {
+        message: format!("failed to serialize AST to JSON: {}", e),
+        line: 0,
+        column: 0,
+    })?;
+    if let serde_json::Value::Object(obj) = &mut value {
+        obj.insert(
+            "schema_version".to_string(),
+            serde_json::json!(AST_JSON_SCHEMA_VERSION),
+        );
+    }
+    Ok(value)
+}
+
+/// Deserialize an AST from the JSON form produced by [`ast_to_json`].
+///
+/// A missing `schema_version` is read as version 1 (untagged serde output), and
+/// version 1 policy rules are upgraded to the current layout. Documents from
+/// newer schema versions are decoded on a best-effort basis: fields this
+/// version doesn't know are ignored.
+pub fn ast_from_json(value: &serde_json::Value) -> Result<CaliberAst, ParseError> {
+    let obj = value.as_object().ok_or_else(|| ParseError {
+        message: "AST JSON must be an object".to_string(),
+        line: 0,
+        column: 0,
+    })?;
+
+    let schema_version = match obj.get("schema_version") {
+        None => 1,
+        Some(v) => match v.as_u64() {
+            Some(n) if n >= 1 => n,
+            _ => {
+                return Err(ParseError {
+                    message: format!("invalid schema_version {}, expected a positive integer", v),
+                    line: 0,
+                    column: 0,
+                });
+            }
+        },
+    };
+
+    let mut value = value.clone();
+    if schema_version < 2 {
+        upgrade_policy_triggers(&mut value);
+    }
+
+    serde_json::from_value(value).map_err(|e| ParseError {
+        message: format!("invalid AST JSON: {}", e),
+        line: e.line(),
+        column: e.column(),
+    })
+}
+
+/// Rewrite version 1 policy rules (`trigger`) into the `triggers` list layout.
+fn upgrade_policy_triggers(value: &mut serde_json::Value) {
+    let Some(definitions) = value.get_mut("definitions").and_then(|d| d.as_array_mut()) else {
+        return;
+    };
+    let rules = definitions
+        .iter_mut()
+        .filter_map(|def| def.pointer_mut("/Policy/rules"))
+        .filter_map(|rules| rules.as_array_mut())
+        .flatten();
+    for rule in rules {
+        if let Some(rule) = rule.as_object_mut() {
+            if let Some(trigger) = rule.remove("trigger") {
+                rule.entry("triggers")
+                    .or_insert_with(|| serde_json::json!([trigger]));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ast::*;
+
+    fn sample_ast() -> CaliberAst {
+        CaliberAst {
+            version: "1.0".to_string(),
+            definitions: vec![
+                Definition::Adapter(AdapterDef {
+                    name: "main".to_string(),
+                    adapter_type: AdapterType::Postgres,
+                    connection: "postgresql://localhost/caliber".to_string(),
+                    options: vec![("pool".to_string(), "10".to_string())],
+                }),
+                Definition::Agent(AgentDef {
+                    name: "support".to_string(),
+                    capabilities: vec!["search_kb".to_string()],
+                    constraints: AgentConstraints::default(),
+                    permissions: PermissionMatrix::default(),
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_ast_json_round_trip() {
+        let ast = sample_ast();
+        let json = ast_to_json(&ast).expect("serialize");
+        assert_eq!(json["schema_version"], AST_JSON_SCHEMA_VERSION);
+        assert_eq!(ast_from_json(&json).expect("round trip"), ast);
+    }
+
+    #[test]
+    fn test_ast_json_accepts_untagged_serde_output() {
+        let ast = sample_ast();
+        let json = serde_json::to_value(&ast).expect("serialize");
+        assert_eq!(ast_from_json(&json).expect("untagged"), ast);
+    }
+
+    #[test]
+    fn test_ast_json_ignores_unknown_and_defaults_missing() {
+        let json = serde_json::json!({
+            "schema_version": 3,
+            "future_field": true,
+            "definitions": [
+                {"Agent": {"name": "support", "added_later": 1}},
+                {"Policy": {"name": "cleanup"}}
+            ]
+        });
+        let ast = ast_from_json(&json).expect("best-effort decode");
+        assert_eq!(ast.version, "1.0");
+        assert_eq!(
+            ast.definitions,
+            vec![
+                Definition::Agent(AgentDef {
+                    name: "support".to_string(),
+                    capabilities: vec![],
+                    constraints: AgentConstraints::default(),
+                    permissions: PermissionMatrix::default(),
+                }),
+                Definition::Policy(PolicyDef {
+                    name: "cleanup".to_string(),
+                    rules: vec![],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ast_json_upgrades_v1_policy_trigger() {
+        let json = serde_json::json!({
+            "schema_version": 1,
+            "version": "1.0",
+            "definitions": [
+                {"Policy": {"name": "cleanup", "rules": [
+                    {"trigger": "TaskEnd", "actions": []}
+                ]}}
+            ]
+        });
+        let ast = ast_from_json(&json).expect("v1 document");
+        assert_eq!(
+            ast.definitions,
+            vec![Definition::Policy(PolicyDef {
+                name: "cleanup".to_string(),
+                rules: vec![PolicyRule {
+                    triggers: vec![Trigger::TaskEnd],
+                    actions: vec![],
+                }],
+            })]
+        );
+    }
+
+    #[test]
+    fn test_ast_json_rejects_malformed() {
+        assert!(ast_from_json(&serde_json::json!([])).is_err());
+        assert!(ast_from_json(&serde_json::json!({"schema_version": "one"})).is_err());
+        assert!(ast_from_json(&serde_json::json!({"schema_version": 0})).is_err());
+
+        // Required fields without a sensible default are still enforced
+        let missing_name = serde_json::json!({"definitions": [{"Policy": {}}]});
+        assert!(ast_from_json(&missing_name).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Parser module for CALIBER DSL
-//! Contains AST definitions (config parsing moved to config module)
+//! Contains AST definitions and their versioned JSON form (config parsing moved to config module)
 
 pub mod ast;
+pub mod ast_json;
 
 pub use ast::*;
+pub use ast_json::*;
@@ -371,7 +371,7 @@ fn assert_provider_eq(
 /// # Examples
 ///
 /// ```
-/// // Given an AST `ast` containing a policy named "my_policy" whose first rule trigger is `Trigger::OnEvent`:
+/// // Given an AST `ast` containing a policy named "my_policy" whose first rule fires only on `Trigger::OnEvent`:
 /// assert_policy_trigger(&ast, "my_policy", Trigger::OnEvent);
 /// ```
 fn assert_policy_trigger(ast: &CaliberAst, name: &str, expected_trigger: Trigger) {
@@ -393,7 +393,8 @@ fn assert_policy_trigger(ast: &CaliberAst, name: &str, expected_trigger: Trigger
         "Policy should have at least one rule"
     );
     assert_eq!(
-        policy.rules[0].trigger, expected_trigger,
+        policy.rules[0].triggers,
+        vec![expected_trigger],
         "Policy trigger mismatch"
     );
 }
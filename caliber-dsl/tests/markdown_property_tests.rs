@@ -224,8 +224,8 @@ fn arb_provider_def() -> impl Strategy<Value = ProviderDef> {
 
 /// Generates an arbitrary PolicyDef suitable for property-based tests.
 ///
-/// The produced PolicyDef has a name matching `[A-Za-z][A-Za-z0-9_]*`, a randomly chosen trigger,
-/// and 1–2 actions wrapped in a single PolicyRule.
+/// The produced PolicyDef has a name matching `[A-Za-z][A-Za-z0-9_]*`, 1–2 randomly chosen
+/// triggers, and 1–2 actions wrapped in a single PolicyRule.
 ///
 /// # Examples
 ///
@@ -242,12 +242,12 @@ fn arb_provider_def() -> impl Strategy<Value = ProviderDef> {
 fn arb_policy_def() -> impl Strategy<Value = PolicyDef> {
     (
         "[a-zA-Z][a-zA-Z0-9_]*",
-        arb_trigger(),
+        prop::collection::vec(arb_trigger(), 1..3),
         prop::collection::vec(arb_action(), 1..3),
     )
-        .prop_map(|(name, trigger, actions)| PolicyDef {
+        .prop_map(|(name, triggers, actions)| PolicyDef {
             name,
-            rules: vec![PolicyRule { trigger, actions }],
+            rules: vec![PolicyRule { triggers, actions }],
         })
 }
 
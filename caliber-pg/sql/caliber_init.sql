@@ -18,10 +18,8 @@ INSERT INTO caliber_schema_version (version, description, checksum)
 VALUES (1, 'Initial schema - CALIBER 0.4.4', 'base')
 ON CONFLICT DO NOTHING;
 
-CREATE OR REPLACE FUNCTION caliber_schema_version()
-RETURNS INTEGER AS $$
-    SELECT COALESCE(MAX(version), 0) FROM caliber_schema_version;
-$$ LANGUAGE SQL STABLE;
+-- caliber_schema_version() is provided by the extension (#[pg_extern]); it
+-- returns the recorded version as TEXT, or NULL before initialization
 
 -- ============================================================================
 -- CONFIGURATION TABLE (Required by caliber-api)
@@ -501,6 +499,8 @@ CREATE INDEX IF NOT EXISTS idx_summarization_source ON caliber_summarization_pol
 -- TRIGGERS FOR UPDATED_AT
 -- ============================================================================
 
+-- Triggers are dropped before being created so re-running the bootstrap
+-- (e.g. via caliber_init()) does not fail on existing triggers.
 CREATE OR REPLACE FUNCTION caliber_update_timestamp()
 RETURNS TRIGGER AS $$
 BEGIN
@@ -509,18 +509,22 @@ BEGIN
 END;
 $$ LANGUAGE plpgsql;
 
+DROP TRIGGER IF EXISTS trajectory_updated_at ON caliber_trajectory;
 CREATE TRIGGER trajectory_updated_at
     BEFORE UPDATE ON caliber_trajectory
     FOR EACH ROW EXECUTE FUNCTION caliber_update_timestamp();
 
+DROP TRIGGER IF EXISTS artifact_updated_at ON caliber_artifact;
 CREATE TRIGGER artifact_updated_at
     BEFORE UPDATE ON caliber_artifact
     FOR EACH ROW EXECUTE FUNCTION caliber_update_timestamp();
 
+DROP TRIGGER IF EXISTS note_updated_at ON caliber_note;
 CREATE TRIGGER note_updated_at
     BEFORE UPDATE ON caliber_note
     FOR EACH ROW EXECUTE FUNCTION caliber_update_timestamp();
 
+DROP TRIGGER IF EXISTS tenant_updated_at ON caliber_tenant;
 CREATE TRIGGER tenant_updated_at
     BEFORE UPDATE ON caliber_tenant
     FOR EACH ROW EXECUTE FUNCTION caliber_update_timestamp();
@@ -537,13 +541,14 @@ DECLARE
     channel TEXT;
     payload JSONB;
 BEGIN
-    -- Determine channel
+    -- Determine channel (same names as caliber_agent_channel() and
+    -- caliber_agent_type_channel())
     IF NEW.to_agent_id IS NOT NULL THEN
         channel := 'caliber_agent_' || NEW.to_agent_id::TEXT;
     ELSIF NEW.to_agent_type IS NOT NULL THEN
-        channel := 'caliber_type_' || NEW.to_agent_type;
+        channel := 'caliber_agent_type_' || NEW.to_agent_type;
     ELSE
-        channel := 'caliber_broadcast';
+        channel := 'caliber_agent_broadcast';
     END IF;
     
     -- Build payload
@@ -561,6 +566,7 @@ BEGIN
 END;
 $$ LANGUAGE plpgsql;
 
+DROP TRIGGER IF EXISTS message_notify ON caliber_message;
 CREATE TRIGGER message_notify
     AFTER INSERT ON caliber_message
     FOR EACH ROW EXECUTE FUNCTION caliber_notify_message();
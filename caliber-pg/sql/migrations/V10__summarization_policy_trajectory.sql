@@ -0,0 +1,20 @@
+-- ============================================================================
+-- CALIBER SUMMARIZATION POLICY TRAJECTORY
+-- Version: 10
+-- Description: Scope summarization policies to a trajectory so they can be
+--              evaluated against that trajectory's scopes, turns and artifacts
+-- ============================================================================
+
+ALTER TABLE caliber_summarization_policy ADD COLUMN IF NOT EXISTS trajectory_id UUID
+    REFERENCES caliber_trajectory(trajectory_id) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS idx_summarization_policy_trajectory
+    ON caliber_summarization_policy(tenant_id, trajectory_id)
+    WHERE trajectory_id IS NOT NULL;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (10, 'Trajectory-scoped summarization policies', 'summarization-policy-trajectory-v10')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- CALIBER AGENT EXTERNAL KEY
+-- Version: 11
+-- Description: Stable caller-supplied agent identity so re-registering with
+--              the same key returns the existing agent
+-- ============================================================================
+
+-- Appended after tenant_id so heap column positions stay stable
+ALTER TABLE caliber_agent ADD COLUMN IF NOT EXISTS external_key TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_agent_external_key
+    ON caliber_agent(tenant_id, external_key)
+    WHERE external_key IS NOT NULL;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (11, 'Agent external key for idempotent registration', 'agent-external-key-v11')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
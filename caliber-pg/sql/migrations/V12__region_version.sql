@@ -0,0 +1,16 @@
+-- ============================================================================
+-- CALIBER REGION VERSION
+-- Version: 12
+-- Description: Version counter for version-tracked regions so collaborating
+--              agents can detect concurrent modifications
+-- ============================================================================
+
+-- Appended after tenant_id so column positions stay stable
+ALTER TABLE caliber_region ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 0;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (12, 'Region version counter for optimistic concurrency', 'region-version-v12')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,20 @@
+-- ============================================================================
+-- CALIBER MESSAGE REPLY-TO
+-- Version: 13
+-- Description: Link replies to the message they answer so agents can follow
+--              request/response threads
+-- ============================================================================
+
+-- Appended after tenant_id so heap column positions stay stable
+ALTER TABLE caliber_message ADD COLUMN IF NOT EXISTS reply_to UUID
+    REFERENCES caliber_message(message_id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_message_reply_to
+    ON caliber_message(reply_to) WHERE reply_to IS NOT NULL;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (13, 'Reply-to link on messages for threading', 'message-reply-to-v13')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- CALIBER AGENT CURRENT TRAJECTORY INDEX
+-- Version: 14
+-- Description: Index agents by the trajectory they are currently working on
+--              so supervisors can list who is engaged on a task
+-- ============================================================================
+
+CREATE INDEX IF NOT EXISTS idx_agent_current_trajectory
+    ON caliber_agent(tenant_id, current_trajectory_id)
+    WHERE current_trajectory_id IS NOT NULL;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (14, 'Index on agent current trajectory', 'agent-current-trajectory-index-v14')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,25 @@
+-- ============================================================================
+-- CALIBER EMBEDDING SETTINGS
+-- Version: 15
+-- Description: Per-tenant expected embedding dimension so stored vectors can
+--              be validated before they reach the artifact/note embedding column
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS caliber_embedding_setting (
+    tenant_id UUID PRIMARY KEY,
+    dimensions INTEGER NOT NULL CHECK (dimensions > 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE caliber_embedding_setting ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation_embedding_setting ON caliber_embedding_setting
+    FOR ALL
+    USING (tenant_id = caliber_current_tenant_id() OR caliber_current_tenant_id() IS NULL);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (15, 'Embedding dimension settings', 'embedding-settings-v15')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,35 @@
+-- ============================================================================
+-- CALIBER SCHEDULED POLICIES
+-- Version: 16
+-- Description: Registry of schedule-triggered policies so an external
+--              scheduler can poll for due policies and advance their clock
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS caliber_scheduled_policy (
+    policy_id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    name TEXT NOT NULL,
+    -- Trigger::Schedule expression (cron or duration), interpreted by the scheduler
+    schedule TEXT NOT NULL,
+    actions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    next_run_at TIMESTAMPTZ NOT NULL,
+    last_run_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_policy_due
+    ON caliber_scheduled_policy(tenant_id, next_run_at);
+
+ALTER TABLE caliber_scheduled_policy ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation_scheduled_policy ON caliber_scheduled_policy
+    FOR ALL
+    USING (tenant_id = caliber_current_tenant_id() OR caliber_current_tenant_id() IS NULL);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (16, 'Scheduled policy registry', 'scheduled-policy-v16')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- CALIBER LOCK ACQUIRE COUNT
+-- Version: 17
+-- Description: Reference count on lock records so a holder can re-acquire a
+--              lock it already holds and only the final release frees it
+-- ============================================================================
+
+-- Appended after version so heap column positions stay stable
+ALTER TABLE caliber_lock ADD COLUMN IF NOT EXISTS acquire_count INTEGER NOT NULL DEFAULT 1
+    CHECK (acquire_count > 0);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (17, 'Reentrant lock acquire count', 'lock-acquire-count-v17')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
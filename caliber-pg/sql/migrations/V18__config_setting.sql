@@ -0,0 +1,28 @@
+-- ============================================================================
+-- CALIBER CONFIG SETTINGS
+-- Version: 18
+-- Description: Per-tenant runtime settings (token_budget, contradiction_threshold,
+--              stale_threshold) read by extension functions in place of
+--              hardcoded CaliberConfig defaults
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS caliber_config_setting (
+    tenant_id UUID NOT NULL,
+    key TEXT NOT NULL,
+    value JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, key)
+);
+
+ALTER TABLE caliber_config_setting ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation_config_setting ON caliber_config_setting
+    FOR ALL
+    USING (tenant_id = caliber_current_tenant_id() OR caliber_current_tenant_id() IS NULL);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (18, 'Per-tenant config settings', 'config-setting-v18')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
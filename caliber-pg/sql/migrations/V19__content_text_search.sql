@@ -0,0 +1,21 @@
+-- ============================================================================
+-- CALIBER CONTENT TEXT SEARCH
+-- Version: 19
+-- Description: Full-text indexes over artifact and note content so
+--              caliber_text_search can recall exact terms (error codes,
+--              identifiers) that embeddings miss
+-- ============================================================================
+
+-- Expression indexes rather than stored tsvector columns so heap column
+-- positions stay stable; the expression must match caliber_text_search
+CREATE INDEX IF NOT EXISTS idx_artifact_content_fts
+    ON caliber_artifact USING gin(to_tsvector('english', content));
+CREATE INDEX IF NOT EXISTS idx_note_content_fts
+    ON caliber_note USING gin(to_tsvector('english', content));
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (19, 'Full-text search over artifact and note content', 'content-text-search-v19')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
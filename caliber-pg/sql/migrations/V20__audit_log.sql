@@ -0,0 +1,34 @@
+-- ============================================================================
+-- CALIBER AUDIT LOG
+-- Version: 20
+-- Description: Per-entity history of mutations (who changed what, and when)
+--              for debugging agents that interfere on shared memory
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS caliber_audit (
+    audit_id BIGSERIAL PRIMARY KEY,
+    tenant_id UUID NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id UUID NOT NULL,
+    operation TEXT NOT NULL,
+    -- Acting agent, when the mutating call identifies one
+    agent_id UUID,
+    -- clock_timestamp so entries written in one transaction keep their order
+    at TIMESTAMPTZ NOT NULL DEFAULT clock_timestamp()
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_entity
+    ON caliber_audit(tenant_id, entity_id, audit_id);
+
+ALTER TABLE caliber_audit ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY tenant_isolation_audit ON caliber_audit
+    FOR ALL
+    USING (tenant_id = caliber_current_tenant_id() OR caliber_current_tenant_id() IS NULL);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (20, 'Audit log of entity mutations', 'audit-log-v20')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
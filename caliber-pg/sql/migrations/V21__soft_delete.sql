@@ -0,0 +1,17 @@
+-- ============================================================================
+-- CALIBER SOFT DELETE
+-- Version: 21
+-- Description: Tombstone column on artifacts and notes so agents can forget
+--              memories without leaving edges pointing at missing rows
+-- ============================================================================
+
+-- Appended after memory_category so heap column positions stay stable
+ALTER TABLE caliber_artifact ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+ALTER TABLE caliber_note ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (21, 'Soft delete tombstones for artifacts and notes', 'soft-delete-v21')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
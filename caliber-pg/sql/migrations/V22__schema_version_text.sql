@@ -0,0 +1,18 @@
+-- ============================================================================
+-- CALIBER SCHEMA VERSION FUNCTION
+-- Version: 22
+-- Description: Replace the SQL caliber_schema_version() returning INTEGER
+--              with the extension function returning TEXT
+-- ============================================================================
+
+-- Installs created from the 0.4.x scripts define caliber_schema_version() as
+-- an INTEGER SQL function. CREATE OR REPLACE cannot change a return type, so
+-- drop it before the extension defines the TEXT version.
+DROP FUNCTION IF EXISTS caliber_schema_version();
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (22, 'caliber_schema_version() returns TEXT', 'schema-version-text-v22')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,41 @@
+-- ============================================================================
+-- CALIBER MESSAGE NOTIFY CHANNELS
+-- Version: 23
+-- Description: Notify the canonical agent and agent type channels from the
+--              message insert trigger
+-- ============================================================================
+
+-- Same channel names as caliber_agent_channel() and caliber_agent_type_channel()
+CREATE OR REPLACE FUNCTION caliber_notify_message()
+RETURNS TRIGGER AS $$
+DECLARE
+    channel TEXT;
+    payload JSONB;
+BEGIN
+    IF NEW.to_agent_id IS NOT NULL THEN
+        channel := 'caliber_agent_' || NEW.to_agent_id::TEXT;
+    ELSIF NEW.to_agent_type IS NOT NULL THEN
+        channel := 'caliber_agent_type_' || NEW.to_agent_type;
+    ELSE
+        channel := 'caliber_agent_broadcast';
+    END IF;
+
+    payload := jsonb_build_object(
+        'message_id', NEW.message_id,
+        'type', NEW.message_type,
+        'priority', NEW.priority,
+        'from_agent_id', NEW.from_agent_id
+    );
+
+    PERFORM pg_notify(channel, payload::TEXT);
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (23, 'Canonical message notify channels', 'message-notify-channels-v23')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,17 @@
+-- ============================================================================
+-- CALIBER SUMMARIZATION SCOPE CLOSE TRACKING
+-- Version: 24
+-- Description: Record how many closed scopes each summarization policy has
+--              already seen, so a ScopeClose trigger fires once per newly
+--              closed scope instead of on every evaluation
+-- ============================================================================
+
+ALTER TABLE caliber_summarization_policy
+    ADD COLUMN IF NOT EXISTS closed_scopes_seen BIGINT NOT NULL DEFAULT 0;
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (24, 'Summarization scope close tracking', 'summarization-scope-close-seen-v24')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
@@ -0,0 +1,50 @@
+-- ============================================================================
+-- CALIBER MEMORY CATEGORY
+-- Version: 9
+-- Description: Add memory_category to notes and artifacts so the hierarchical
+--              memory organization (MemoryCategory) is queryable
+-- ============================================================================
+
+-- Appended after tenant_id so heap column positions stay stable
+ALTER TABLE caliber_note ADD COLUMN IF NOT EXISTS memory_category TEXT
+    CHECK (memory_category IN ('ephemeral', 'working', 'episodic', 'semantic', 'procedural', 'meta'));
+ALTER TABLE caliber_artifact ADD COLUMN IF NOT EXISTS memory_category TEXT
+    CHECK (memory_category IN ('ephemeral', 'working', 'episodic', 'semantic', 'procedural', 'meta'));
+
+-- Backfill existing rows (must match note_type_memory_category/artifact_type_memory_category)
+UPDATE caliber_note SET memory_category = CASE note_type
+    WHEN 'convention' THEN 'procedural'
+    WHEN 'strategy' THEN 'procedural'
+    WHEN 'gotcha' THEN 'procedural'
+    WHEN 'procedure' THEN 'procedural'
+    WHEN 'summary' THEN 'episodic'
+    WHEN 'meta' THEN 'meta'
+    ELSE 'semantic'
+END
+WHERE memory_category IS NULL;
+
+UPDATE caliber_artifact SET memory_category = CASE artifact_type
+    WHEN 'error_log' THEN 'episodic'
+    WHEN 'tool_result' THEN 'episodic'
+    WHEN 'log' THEN 'episodic'
+    WHEN 'summary' THEN 'episodic'
+    WHEN 'code_patch' THEN 'procedural'
+    WHEN 'code' THEN 'procedural'
+    WHEN 'intermediate_output' THEN 'working'
+    WHEN 'plan' THEN 'working'
+    WHEN 'custom' THEN 'working'
+    ELSE 'semantic'
+END
+WHERE memory_category IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_note_memory_category
+    ON caliber_note(tenant_id, memory_category);
+CREATE INDEX IF NOT EXISTS idx_artifact_memory_category
+    ON caliber_artifact(tenant_id, memory_category);
+
+INSERT INTO caliber_schema_version (version, description, checksum)
+VALUES (9, 'Memory category for notes and artifacts', 'memory-category-v9')
+ON CONFLICT (version) DO UPDATE SET
+    applied_at = NOW(),
+    description = EXCLUDED.description,
+    checksum = EXCLUDED.checksum;
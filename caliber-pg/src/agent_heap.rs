@@ -38,16 +38,32 @@ impl From<AgentRow> for Agent {
     }
 }
 
+/// Parameters for registering an agent.
+pub struct AgentRegisterParams<'a> {
+    pub agent_id: AgentId,
+    pub agent_type: &'a str,
+    pub capabilities: &'a [String],
+    pub memory_access: &'a MemoryAccess,
+    pub can_delegate_to: &'a [String],
+    pub reports_to: Option<AgentId>,
+    /// Caller-supplied stable identity (unique per tenant), if any.
+    pub external_key: Option<&'a str>,
+    pub tenant_id: TenantId,
+}
+
 /// Register a new agent by inserting an agent record using direct heap operations.
-pub fn agent_register_heap(
-    agent_id: AgentId,
-    agent_type: &str,
-    capabilities: &[String],
-    memory_access: &MemoryAccess,
-    can_delegate_to: &[String],
-    reports_to: Option<AgentId>,
-    tenant_id: TenantId,
-) -> CaliberResult<AgentId> {
+pub fn agent_register_heap(params: AgentRegisterParams<'_>) -> CaliberResult<AgentId> {
+    let AgentRegisterParams {
+        agent_id,
+        agent_type,
+        capabilities,
+        memory_access,
+        can_delegate_to,
+        reports_to,
+        external_key,
+        tenant_id,
+    } = params;
+
     let rel = open_relation(agent::TABLE_NAME, HeapLockMode::RowExclusive)?;
     validate_agent_relation(&rel)?;
 
@@ -112,6 +128,12 @@ pub fn agent_register_heap(
     // Set tenant_id
     values[agent::TENANT_ID as usize - 1] = uuid_to_datum(tenant_id.as_uuid());
 
+    // Set optional external_key
+    match external_key {
+        Some(key) => values[agent::EXTERNAL_KEY as usize - 1] = string_to_datum(key),
+        None => nulls[agent::EXTERNAL_KEY as usize - 1] = true,
+    }
+
     let tuple = form_tuple(&rel, &values, &nulls)?;
     let _tid = unsafe { insert_tuple(&rel, tuple)? };
     unsafe { update_indexes_for_insert(&rel, tuple, &values, &nulls)? };
@@ -194,6 +216,7 @@ pub fn agent_heartbeat_heap(agent_id: AgentId, tenant_id: TenantId) -> CaliberRe
         })?;
 
         unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+        unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
         Ok(true)
     } else {
         Ok(false)
@@ -230,14 +253,7 @@ pub fn agent_set_status_heap(
         let (mut values, nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
 
         // Update status field
-        let status_str = match status {
-            AgentStatus::Idle => "idle",
-            AgentStatus::Active => "active",
-            AgentStatus::Blocked => "blocked",
-            AgentStatus::Failed => "failed",
-            AgentStatus::Offline => "offline",
-        };
-        values[agent::STATUS as usize - 1] = string_to_datum(status_str);
+        values[agent::STATUS as usize - 1] = string_to_datum(agent_status_to_str(status));
 
         let new_tuple = form_tuple(&rel, &values, &nulls)?;
         let old_tid = scanner.current_tid().ok_or_else(|| {
@@ -247,12 +263,167 @@ pub fn agent_set_status_heap(
         })?;
 
         unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+        unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
         Ok(true)
     } else {
         Ok(false)
     }
 }
 
+/// Partial update of an agent's mutable configuration.
+///
+/// Fields left as `None` are unchanged; `reports_to: Some(None)` clears the
+/// reporting relationship.
+pub struct AgentUpdateHeapParams<'a> {
+    pub agent_id: AgentId,
+    pub tenant_id: TenantId,
+    pub capabilities: Option<&'a [String]>,
+    pub can_delegate_to: Option<&'a [String]>,
+    pub reports_to: Option<Option<AgentId>>,
+    pub memory_access: Option<&'a MemoryAccess>,
+}
+
+/// Update agent capabilities, delegation targets, reporting line and memory
+/// access using direct heap operations.
+pub fn agent_update_heap(params: AgentUpdateHeapParams<'_>) -> CaliberResult<bool> {
+    let AgentUpdateHeapParams {
+        agent_id,
+        tenant_id,
+        capabilities,
+        can_delegate_to,
+        reports_to,
+        memory_access,
+    } = params;
+
+    let rel = open_relation(agent::TABLE_NAME, HeapLockMode::RowExclusive)?;
+    let index_rel = open_index(agent::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(agent_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, agent::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(false);
+    }
+    let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+
+    if let Some(caps) = capabilities {
+        if caps.is_empty() {
+            nulls[agent::CAPABILITIES as usize - 1] = true;
+        } else {
+            values[agent::CAPABILITIES as usize - 1] = text_array_to_datum(caps);
+            nulls[agent::CAPABILITIES as usize - 1] = false;
+        }
+    }
+
+    if let Some(targets) = can_delegate_to {
+        if targets.is_empty() {
+            nulls[agent::CAN_DELEGATE_TO as usize - 1] = true;
+        } else {
+            values[agent::CAN_DELEGATE_TO as usize - 1] = text_array_to_datum(targets);
+            nulls[agent::CAN_DELEGATE_TO as usize - 1] = false;
+        }
+    }
+
+    if let Some(new_reports_to) = reports_to {
+        let (datum, is_null) = build_optional_agent_uuid(new_reports_to);
+        values[agent::REPORTS_TO as usize - 1] = datum;
+        nulls[agent::REPORTS_TO as usize - 1] = is_null;
+    }
+
+    if let Some(access) = memory_access {
+        let memory_access_json = serde_json::to_value(access).map_err(|e| {
+            CaliberError::Storage(StorageError::UpdateFailed {
+                entity_type: EntityType::Agent,
+                id: agent_id.as_uuid(),
+                reason: format!("Failed to serialize memory_access: {}", e),
+            })
+        })?;
+        values[agent::MEMORY_ACCESS as usize - 1] = json_to_datum(&memory_access_json);
+    }
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    let old_tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::TransactionFailed {
+            reason: "Failed to get TID of agent tuple".to_string(),
+        })
+    })?;
+
+    unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+    Ok(true)
+}
+
+/// Set the trajectory and scope an agent is currently working on using
+/// direct heap operations. `None` clears the corresponding field.
+pub fn agent_set_context_heap(
+    agent_id: AgentId,
+    trajectory_id: Option<TrajectoryId>,
+    scope_id: Option<ScopeId>,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    let rel = open_relation(agent::TABLE_NAME, HeapLockMode::RowExclusive)?;
+    let index_rel = open_index(agent::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(agent_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, agent::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(false);
+    }
+    let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+
+    values[agent::CURRENT_TRAJECTORY_ID as usize - 1] =
+        option_uuid_to_datum(trajectory_id.map(|id| id.as_uuid()));
+    nulls[agent::CURRENT_TRAJECTORY_ID as usize - 1] = trajectory_id.is_none();
+
+    values[agent::CURRENT_SCOPE_ID as usize - 1] =
+        option_uuid_to_datum(scope_id.map(|id| id.as_uuid()));
+    nulls[agent::CURRENT_SCOPE_ID as usize - 1] = scope_id.is_none();
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    let old_tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::TransactionFailed {
+            reason: "Failed to get TID of agent tuple".to_string(),
+        })
+    })?;
+
+    unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+    Ok(true)
+}
+
 /// List agents by type using direct heap operations.
 pub fn agent_list_by_type_heap(
     agent_type: &str,
@@ -286,6 +457,54 @@ pub fn agent_list_by_type_heap(
     Ok(results)
 }
 
+/// List agents in any of the given statuses, scanning the status index once
+/// per status.
+pub fn agent_list_by_status_heap(
+    statuses: &[AgentStatus],
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<AgentRow>> {
+    let rel = open_relation(agent::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let index_rel = open_index(agent::STATUS_INDEX)?;
+    let tuple_desc = rel.tuple_desc();
+    let mut results = Vec::new();
+
+    for status in statuses {
+        let snapshot = get_active_snapshot();
+
+        let mut scan_key = pg_sys::ScanKeyData::default();
+        init_scan_key(
+            &mut scan_key,
+            1,
+            BTreeStrategy::Equal,
+            operator_oids::TEXT_EQ,
+            string_to_datum(agent_status_to_str(*status)),
+        );
+
+        let mut scanner =
+            unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+        for tuple in &mut scanner {
+            let row = unsafe { tuple_to_agent(tuple, tuple_desc) }?;
+            if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) {
+                results.push(row);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// Convert AgentStatus to its stored string form.
+pub fn agent_status_to_str(status: AgentStatus) -> &'static str {
+    match status {
+        AgentStatus::Idle => "idle",
+        AgentStatus::Active => "active",
+        AgentStatus::Blocked => "blocked",
+        AgentStatus::Failed => "failed",
+        AgentStatus::Offline => "offline",
+    }
+}
+
 /// Validate that a HeapRelation is suitable for agent operations.
 fn validate_agent_relation(rel: &HeapRelation) -> CaliberResult<()> {
     let natts = rel.natts();
@@ -538,15 +757,16 @@ mod tests {
                         let tenant_id = TenantId::now_v7();
 
                         // Insert via heap
-                        let result = agent_register_heap(
+                        let result = agent_register_heap(AgentRegisterParams {
                             agent_id,
-                            &agent_type,
-                            &capabilities,
-                            &memory_access,
-                            &can_delegate_to,
+                            agent_type: &agent_type,
+                            capabilities: &capabilities,
+                            memory_access: &memory_access,
+                            can_delegate_to: &can_delegate_to,
                             reports_to,
+                            external_key: None,
                             tenant_id,
-                        );
+                        });
                         prop_assert!(result.is_ok(), "Insert should succeed: {:?}", result.err());
                         prop_assert_eq!(result.unwrap(), agent_id);
 
@@ -653,15 +873,16 @@ mod tests {
                         let tenant_id = TenantId::now_v7();
 
                         // Insert via heap
-                        let insert_result = agent_register_heap(
+                        let insert_result = agent_register_heap(AgentRegisterParams {
                             agent_id,
-                            &agent_type,
-                            &capabilities,
-                            &memory_access,
-                            &can_delegate_to,
+                            agent_type: &agent_type,
+                            capabilities: &capabilities,
+                            memory_access: &memory_access,
+                            can_delegate_to: &can_delegate_to,
                             reports_to,
+                            external_key: None,
                             tenant_id,
-                        );
+                        });
                         prop_assert!(insert_result.is_ok(), "Insert should succeed");
 
                         // Get initial heartbeat
@@ -742,15 +963,16 @@ mod tests {
                         let tenant_id = TenantId::now_v7();
 
                         // Insert via heap
-                        let insert_result = agent_register_heap(
+                        let insert_result = agent_register_heap(AgentRegisterParams {
                             agent_id,
-                            &agent_type,
-                            &capabilities,
-                            &memory_access,
-                            &can_delegate_to,
+                            agent_type: &agent_type,
+                            capabilities: &capabilities,
+                            memory_access: &memory_access,
+                            can_delegate_to: &can_delegate_to,
                             reports_to,
+                            external_key: None,
                             tenant_id,
-                        );
+                        });
                         prop_assert!(insert_result.is_ok(), "Insert should succeed");
 
                         // Verify initial status is Idle
@@ -868,15 +1090,16 @@ mod tests {
                         let tenant_id = TenantId::now_v7();
 
                         // Insert via heap
-                        let insert_result = agent_register_heap(
+                        let insert_result = agent_register_heap(AgentRegisterParams {
                             agent_id,
-                            &agent_type,
-                            &capabilities,
-                            &memory_access,
-                            &can_delegate_to,
+                            agent_type: &agent_type,
+                            capabilities: &capabilities,
+                            memory_access: &memory_access,
+                            can_delegate_to: &can_delegate_to,
                             reports_to,
+                            external_key: None,
                             tenant_id,
-                        );
+                        });
                         prop_assert!(insert_result.is_ok(), "Insert should succeed");
 
                         // Query via type index
@@ -10,20 +10,21 @@
 //! - `artifact_query_by_type_heap` - Query artifacts by type
 //! - `artifact_query_by_scope_heap` - Query artifacts by scope
 //! - `artifact_update_heap` - Update artifact fields
+//! - `artifact_soft_delete_heap` - Tombstone an artifact
 
 use pgrx::pg_sys;
 use pgrx::prelude::*;
 
 use caliber_core::{
-    Artifact, ArtifactId, ArtifactType, CaliberError, CaliberResult, ContentHash, EmbeddingVector,
-    EntityIdType, EntityType, ExtractionMethod, Provenance, ScopeId, StorageError, TenantId,
-    TrajectoryId, TTL,
+    ttl_from_str, ttl_to_str, Artifact, ArtifactId, ArtifactType, CaliberError, CaliberResult,
+    ContentHash, EmbeddingVector, EntityIdType, EntityType, ExtractionMethod, Provenance, ScopeId,
+    StorageError, TenantId, TrajectoryId, TTL,
 };
 
 use crate::column_maps::artifact;
 use crate::heap_ops::{
     current_timestamp, form_tuple, get_active_snapshot, insert_tuple, open_relation,
-    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as LockMode,
+    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as LockMode, SoftDeleteOutcome,
 };
 use crate::index_ops::{
     init_scan_key, open_index, operator_oids, update_indexes_for_insert, BTreeStrategy,
@@ -40,6 +41,8 @@ use crate::tuple_extract::{
 pub struct ArtifactRow {
     pub artifact: Artifact,
     pub tenant_id: Option<TenantId>,
+    /// Soft delete tombstone; tombstoned artifacts are skipped by queries.
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl From<ArtifactRow> for Artifact {
@@ -156,7 +159,7 @@ pub fn artifact_create_heap(params: ArtifactCreateParams<'_>) -> CaliberResult<A
     values[artifact::PROVENANCE as usize - 1] = json_to_datum(&provenance_json);
 
     // Column 10: ttl (TEXT, NOT NULL)
-    values[artifact::TTL as usize - 1] = string_to_datum(ttl_to_str(ttl));
+    values[artifact::TTL as usize - 1] = string_to_datum(&ttl_to_str(&ttl));
 
     // Column 11: created_at (TIMESTAMPTZ, NOT NULL)
     values[artifact::CREATED_AT as usize - 1] = now_datum;
@@ -173,6 +176,14 @@ pub fn artifact_create_heap(params: ArtifactCreateParams<'_>) -> CaliberResult<A
     // Column 15: tenant_id (UUID, NOT NULL)
     values[artifact::TENANT_ID as usize - 1] = uuid_to_datum(tenant_id.as_uuid());
 
+    // Column 16: memory_category (TEXT, nullable) - derived from artifact type
+    values[artifact::MEMORY_CATEGORY as usize - 1] = string_to_datum(
+        crate::memory_category_to_str(crate::artifact_type_memory_category(artifact_type)),
+    );
+
+    // Column 17: deleted_at (TIMESTAMPTZ, nullable) - not tombstoned
+    nulls[artifact::DELETED_AT as usize - 1] = true;
+
     // Form the heap tuple
     let tuple = form_tuple(&rel, &values, &nulls)?;
 
@@ -282,8 +293,9 @@ pub fn artifact_query_by_type_heap(
     // Collect all matching tuples, filtering out expired ones
     for tuple in &mut scanner {
         let row = unsafe { tuple_to_artifact(tuple, tuple_desc) }?;
-        // Enforce TTL - skip expired artifacts
+        // Enforce TTL and tombstones - skip expired and deleted artifacts
         if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid())
+            && row.deleted_at.is_none()
             && !is_artifact_expired(&row.artifact.ttl, row.artifact.created_at)
         {
             results.push(row);
@@ -336,8 +348,9 @@ pub fn artifact_query_by_scope_heap(
     // Collect all matching tuples, filtering out expired ones
     for tuple in &mut scanner {
         let row = unsafe { tuple_to_artifact(tuple, tuple_desc) }?;
-        // Enforce TTL - skip expired artifacts
+        // Enforce TTL and tombstones - skip expired and deleted artifacts
         if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid())
+            && row.deleted_at.is_none()
             && !is_artifact_expired(&row.artifact.ttl, row.artifact.created_at)
         {
             results.push(row);
@@ -388,6 +401,7 @@ pub fn artifact_query_by_trajectory_heap(
     for tuple in &mut scanner {
         let row = unsafe { tuple_to_artifact(tuple, tuple_desc) }?;
         if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid())
+            && row.deleted_at.is_none()
             && !is_artifact_expired(&row.artifact.ttl, row.artifact.created_at)
         {
             results.push(row);
@@ -410,6 +424,7 @@ pub fn artifact_query_by_trajectory_heap(
 /// # Returns
 /// * `Ok(true)` - If the artifact was found and updated
 /// * `Ok(false)` - If no artifact with that ID exists
+/// * `Err(StorageError::UpdateFailed)` - If the artifact is tombstoned
 /// * `Err(CaliberError)` - On failure
 ///
 /// # Requirements
@@ -468,6 +483,15 @@ pub fn artifact_update_heap(
     // Extract current values and nulls
     let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
 
+    // Tombstoned artifacts are read-only
+    if !nulls[artifact::DELETED_AT as usize - 1] {
+        return Err(CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Artifact,
+            id: id.as_uuid(),
+            reason: "artifact is deleted".to_string(),
+        }));
+    }
+
     // Apply updates
     if let Some(new_content) = content {
         values[artifact::CONTENT as usize - 1] = string_to_datum(new_content);
@@ -536,6 +560,79 @@ pub fn artifact_update_heap(
     Ok(true)
 }
 
+/// Tombstone an artifact using direct heap operations.
+///
+/// Sets `deleted_at` (and `updated_at`) to now. An artifact that is already
+/// tombstoned keeps its original `deleted_at`.
+///
+/// # Returns
+/// * `Ok(SoftDeleteOutcome::Deleted)` - If the artifact was live and is now tombstoned
+/// * `Ok(SoftDeleteOutcome::AlreadyDeleted)` - If the artifact was already tombstoned
+/// * `Ok(SoftDeleteOutcome::NotFound)` - If no artifact with that ID exists for this tenant
+/// * `Err(CaliberError)` - On failure
+pub fn artifact_soft_delete_heap(
+    id: ArtifactId,
+    tenant_id: TenantId,
+) -> CaliberResult<SoftDeleteOutcome> {
+    let rel = open_relation(artifact::TABLE_NAME, LockMode::RowExclusive)?;
+    let index_rel = open_index(artifact::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(SoftDeleteOutcome::NotFound),
+    };
+
+    let tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Artifact,
+            id: id.as_uuid(),
+            reason: "Failed to get TID of existing tuple".to_string(),
+        })
+    })?;
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, artifact::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(SoftDeleteOutcome::NotFound);
+    }
+
+    let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+    if !nulls[artifact::DELETED_AT as usize - 1] {
+        return Ok(SoftDeleteOutcome::AlreadyDeleted);
+    }
+
+    let now_datum = timestamp_to_pgrx(current_timestamp())?
+        .into_datum()
+        .ok_or_else(|| {
+            CaliberError::Storage(StorageError::UpdateFailed {
+                entity_type: EntityType::Artifact,
+                id: id.as_uuid(),
+                reason: "Failed to convert timestamp to datum".to_string(),
+            })
+        })?;
+    values[artifact::DELETED_AT as usize - 1] = now_datum;
+    nulls[artifact::DELETED_AT as usize - 1] = false;
+    values[artifact::UPDATED_AT as usize - 1] = now_datum;
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    unsafe { update_tuple(&rel, &tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+
+    Ok(SoftDeleteOutcome::Deleted)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -611,7 +708,7 @@ pub unsafe fn extract_binary_data(
 }
 
 /// Convert an ArtifactType enum to its string representation.
-fn artifact_type_to_str(t: ArtifactType) -> &'static str {
+pub(crate) fn artifact_type_to_str(t: ArtifactType) -> &'static str {
     match t {
         // Core types
         ArtifactType::ErrorLog => "error_log",
@@ -671,51 +768,12 @@ fn str_to_artifact_type(s: &str) -> ArtifactType {
     }
 }
 
-/// Convert a TTL enum to its string representation.
-/// Returns a static string for simple variants, or a leaked string for Duration.
-fn ttl_to_str(ttl: TTL) -> &'static str {
-    match ttl {
-        // Canonical variants
-        TTL::Persistent => "persistent",
-        TTL::Session => "session",
-        TTL::Scope => "scope",
-        TTL::Duration(ms) => Box::leak(format!("duration:{}", ms).into_boxed_str()),
-        // Semantic aliases
-        TTL::Ephemeral => "ephemeral",
-        TTL::ShortTerm => "short_term",
-        TTL::MediumTerm => "medium_term",
-        TTL::LongTerm => "long_term",
-        TTL::Permanent => "permanent",
-        TTL::Max(n) => Box::leak(format!("max:{}", n).into_boxed_str()),
-    }
-}
-
 /// Parse a TTL string to TTL enum.
 fn str_to_ttl(s: &str) -> TTL {
-    match s {
-        // Canonical variants
-        "persistent" => TTL::Persistent,
-        "session" => TTL::Session,
-        "scope" => TTL::Scope,
-        // Semantic aliases
-        "ephemeral" => TTL::Ephemeral,
-        "short_term" => TTL::ShortTerm,
-        "medium_term" => TTL::MediumTerm,
-        "long_term" => TTL::LongTerm,
-        "permanent" => TTL::Permanent,
-        // Duration parsing
-        s if s.starts_with("duration:") => {
-            let ms_str = &s[9..];
-            ms_str
-                .parse::<i64>()
-                .map(TTL::Duration)
-                .unwrap_or(TTL::Session)
-        }
-        _ => {
-            pgrx::warning!("CALIBER: Unknown TTL value '{}', defaulting to Session", s);
-            TTL::Session
-        }
-    }
+    ttl_from_str(s).unwrap_or_else(|| {
+        pgrx::warning!("CALIBER: Unknown TTL value '{}', defaulting to Session", s);
+        TTL::Session
+    })
 }
 
 /// Check if an artifact has expired based on its TTL and creation time.
@@ -865,6 +923,8 @@ unsafe fn tuple_to_artifact(
 
     let metadata = extract_jsonb(tuple, tuple_desc, artifact::METADATA)?;
     let tenant_id = extract_uuid(tuple, tuple_desc, artifact::TENANT_ID)?.map(TenantId::new);
+    let deleted_at =
+        extract_timestamp(tuple, tuple_desc, artifact::DELETED_AT)?.map(timestamp_to_chrono);
 
     Ok(ArtifactRow {
         artifact: Artifact {
@@ -884,6 +944,7 @@ unsafe fn tuple_to_artifact(
             metadata,
         },
         tenant_id,
+        deleted_at,
     })
 }
 
@@ -1178,7 +1239,7 @@ mod tests {
 
                         // Vector search should return the artifact
                         let query = serde_json::json!(embedding.data);
-                        let search = crate::caliber_vector_search(pgrx::JsonB(query), 10);
+                        let search = crate::caliber_vector_search(pgrx::JsonB(query), 10, "cosine");
                         let results: Vec<serde_json::Value> =
                             serde_json::from_value(search.0).unwrap_or_default();
                         let contains_artifact = results.iter().any(|row| {
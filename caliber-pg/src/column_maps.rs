@@ -170,7 +170,9 @@ pub mod scope {
 ///     updated_at TIMESTAMPTZ NOT NULL,          -- 12
 ///     superseded_by UUID,                       -- 13
 ///     metadata JSONB,                           -- 14
-///     tenant_id UUID                            -- 15
+///     tenant_id UUID,                           -- 15
+///     memory_category TEXT,                     -- 16 (V9)
+///     deleted_at TIMESTAMPTZ                    -- 17 (V21: soft delete)
 /// );
 /// ```
 pub mod artifact {
@@ -204,9 +206,13 @@ pub mod artifact {
     pub const METADATA: i16 = 14;
     /// tenant_id UUID (FK)
     pub const TENANT_ID: i16 = 15;
+    /// memory_category TEXT (V9)
+    pub const MEMORY_CATEGORY: i16 = 16;
+    /// deleted_at TIMESTAMPTZ (V21: soft delete tombstone)
+    pub const DELETED_AT: i16 = 17;
 
     /// Total number of columns in the artifact table
-    pub const NUM_COLS: usize = 15;
+    pub const NUM_COLS: usize = 17;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_artifact";
@@ -246,7 +252,9 @@ pub mod artifact {
 ///     metadata JSONB,                           -- 15
 ///     abstraction_level TEXT NOT NULL,          -- 16 (Battle Intel Feature 2)
 ///     source_note_ids UUID[],                   -- 17 (Battle Intel Feature 2)
-///     tenant_id UUID                            -- 18
+///     tenant_id UUID,                           -- 18
+///     memory_category TEXT,                     -- 19 (V9)
+///     deleted_at TIMESTAMPTZ                    -- 20 (V21: soft delete)
 /// );
 /// ```
 pub mod note {
@@ -286,9 +294,13 @@ pub mod note {
     pub const SOURCE_NOTE_IDS: i16 = 17;
     /// tenant_id UUID (FK)
     pub const TENANT_ID: i16 = 18;
+    /// memory_category TEXT (V9)
+    pub const MEMORY_CATEGORY: i16 = 19;
+    /// deleted_at TIMESTAMPTZ (V21: soft delete tombstone)
+    pub const DELETED_AT: i16 = 20;
 
     /// Total number of columns in the note table
-    pub const NUM_COLS: usize = 18;
+    pub const NUM_COLS: usize = 20;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_note";
@@ -383,7 +395,8 @@ pub mod turn {
 ///     reports_to UUID,                          -- 9
 ///     created_at TIMESTAMPTZ NOT NULL,          -- 10
 ///     last_heartbeat TIMESTAMPTZ NOT NULL,      -- 11
-///     tenant_id UUID                            -- 12
+///     tenant_id UUID,                           -- 12
+///     external_key TEXT                         -- 13 (V11)
 /// );
 /// ```
 pub mod agent {
@@ -411,9 +424,11 @@ pub mod agent {
     pub const LAST_HEARTBEAT: i16 = 11;
     /// tenant_id UUID (FK)
     pub const TENANT_ID: i16 = 12;
+    /// external_key TEXT (V11)
+    pub const EXTERNAL_KEY: i16 = 13;
 
     /// Total number of columns in the agent table
-    pub const NUM_COLS: usize = 12;
+    pub const NUM_COLS: usize = 13;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_agent";
@@ -442,7 +457,8 @@ pub mod agent {
 ///     expires_at TIMESTAMPTZ NOT NULL,          -- 6
 ///     mode TEXT NOT NULL,                       -- 7
 ///     tenant_id UUID,                           -- 8
-///     version BIGINT NOT NULL                   -- 9 (V3: CAS)
+///     version BIGINT NOT NULL,                  -- 9 (V3: CAS)
+///     acquire_count INTEGER NOT NULL            -- 10 (V17)
 /// );
 /// ```
 pub mod lock {
@@ -464,9 +480,11 @@ pub mod lock {
     pub const TENANT_ID: i16 = 8;
     /// version BIGINT NOT NULL (V3: Compare-And-Swap version for optimistic locking)
     pub const VERSION: i16 = 9;
+    /// acquire_count INTEGER NOT NULL (V17: reentrant acquisitions)
+    pub const ACQUIRE_COUNT: i16 = 10;
 
     /// Total number of columns in the lock table
-    pub const NUM_COLS: usize = 9;
+    pub const NUM_COLS: usize = 10;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_lock";
@@ -501,7 +519,8 @@ pub mod lock {
 ///     acknowledged_at TIMESTAMPTZ,              -- 12
 ///     priority TEXT NOT NULL,                   -- 13
 ///     expires_at TIMESTAMPTZ,                   -- 14
-///     tenant_id UUID                            -- 15
+///     tenant_id UUID,                           -- 15
+///     reply_to UUID                             -- 16 (V13)
 /// );
 /// ```
 pub mod message {
@@ -535,9 +554,11 @@ pub mod message {
     pub const EXPIRES_AT: i16 = 14;
     /// tenant_id UUID (FK)
     pub const TENANT_ID: i16 = 15;
+    /// reply_to UUID (FK) (V13)
+    pub const REPLY_TO: i16 = 16;
 
     /// Total number of columns in the message table
-    pub const NUM_COLS: usize = 15;
+    pub const NUM_COLS: usize = 16;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_message";
@@ -824,7 +845,8 @@ pub mod conflict {
 ///     version_tracking BOOLEAN NOT NULL,        -- 9
 ///     created_at TIMESTAMPTZ NOT NULL,          -- 10
 ///     updated_at TIMESTAMPTZ NOT NULL,          -- 11
-///     tenant_id UUID                            -- 12
+///     tenant_id UUID,                           -- 12
+///     version BIGINT NOT NULL                   -- 13
 /// );
 /// ```
 pub mod region {
@@ -852,9 +874,11 @@ pub mod region {
     pub const UPDATED_AT: i16 = 11;
     /// tenant_id UUID (FK)
     pub const TENANT_ID: i16 = 12;
+    /// version BIGINT NOT NULL (V12)
+    pub const VERSION: i16 = 13;
 
     /// Total number of columns in the region table
-    pub const NUM_COLS: usize = 12;
+    pub const NUM_COLS: usize = 13;
 
     /// Table name
     pub const TABLE_NAME: &str = "caliber_region";
@@ -1162,12 +1186,12 @@ mod tests {
 
     #[test]
     fn test_artifact_column_count() {
-        assert_eq!(artifact::NUM_COLS, 15);
+        assert_eq!(artifact::NUM_COLS, 17); // Updated for V9: +memory_category, V21: +deleted_at
     }
 
     #[test]
     fn test_note_column_count() {
-        assert_eq!(note::NUM_COLS, 18); // Updated for Battle Intel Feature 2
+        assert_eq!(note::NUM_COLS, 20); // Updated for Battle Intel Feature 2, V9: +memory_category, V21: +deleted_at
     }
 
     #[test]
@@ -1177,17 +1201,17 @@ mod tests {
 
     #[test]
     fn test_agent_column_count() {
-        assert_eq!(agent::NUM_COLS, 12);
+        assert_eq!(agent::NUM_COLS, 13); // Updated for V11: +external_key
     }
 
     #[test]
     fn test_lock_column_count() {
-        assert_eq!(lock::NUM_COLS, 9); // Updated for V3: +version
+        assert_eq!(lock::NUM_COLS, 10); // Updated for V3: +version, V17: +acquire_count
     }
 
     #[test]
     fn test_message_column_count() {
-        assert_eq!(message::NUM_COLS, 15);
+        assert_eq!(message::NUM_COLS, 16); // Updated for V13: +reply_to
     }
 
     #[test]
@@ -1207,7 +1231,7 @@ mod tests {
 
     #[test]
     fn test_region_column_count() {
-        assert_eq!(region::NUM_COLS, 12);
+        assert_eq!(region::NUM_COLS, 13); // Updated for V12: +version
     }
 
     // Battle Intel Feature 1: Graph Edges
@@ -5,6 +5,7 @@
 
 use pgrx::pg_sys;
 use pgrx::prelude::*;
+use std::ptr;
 
 use caliber_core::{
     AgentId, CaliberError, CaliberResult, Conflict, ConflictId, ConflictResolutionRecord,
@@ -14,7 +15,7 @@ use caliber_core::{
 use crate::column_maps::conflict;
 use crate::heap_ops::{
     current_timestamp, form_tuple, get_active_snapshot, insert_tuple, open_relation,
-    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as HeapLockMode,
+    timestamp_to_pgrx, update_tuple, HeapRelation, HeapScanner, PgLockMode as HeapLockMode,
 };
 use crate::index_ops::{
     init_scan_key, open_index, operator_oids, update_indexes_for_insert, BTreeStrategy,
@@ -173,6 +174,27 @@ pub fn conflict_resolve_heap(
     conflict_id: ConflictId,
     resolution: &ConflictResolutionRecord,
     tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    conflict_record_outcome_heap(conflict_id, "resolved", resolution, tenant_id)
+}
+
+/// Escalate a conflict by updating status and resolution using direct heap
+/// operations. resolved_at stays NULL since nothing was decided.
+pub fn conflict_escalate_heap(
+    conflict_id: ConflictId,
+    resolution: &ConflictResolutionRecord,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    conflict_record_outcome_heap(conflict_id, "escalated", resolution, tenant_id)
+}
+
+/// Set a conflict's status and resolution record; resolved_at is stamped
+/// only when the status is "resolved".
+fn conflict_record_outcome_heap(
+    conflict_id: ConflictId,
+    status: &str,
+    resolution: &ConflictResolutionRecord,
+    tenant_id: TenantId,
 ) -> CaliberResult<bool> {
     let rel = open_relation(conflict::TABLE_NAME, HeapLockMode::RowExclusive)?;
     let index_rel = open_index(conflict::PK_INDEX)?;
@@ -197,8 +219,7 @@ pub fn conflict_resolve_heap(
         }
         let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
 
-        // Update status to "resolved"
-        values[conflict::STATUS as usize - 1] = string_to_datum("resolved");
+        values[conflict::STATUS as usize - 1] = string_to_datum(status);
 
         // Serialize resolution to JSON
         let resolution_json = serde_json::to_value(resolution).map_err(|e| {
@@ -213,17 +234,19 @@ pub fn conflict_resolve_heap(
         nulls[conflict::RESOLUTION as usize - 1] = false;
 
         // Update resolved_at to current timestamp
-        let now = current_timestamp();
-        let now_datum = timestamp_to_pgrx(now)?.into_datum().ok_or_else(|| {
-            CaliberError::Storage(StorageError::UpdateFailed {
-                entity_type: EntityType::Conflict,
-                id: conflict_id.as_uuid(),
-                reason: "Failed to convert timestamp to datum".to_string(),
-            })
-        })?;
+        if status == "resolved" {
+            let now = current_timestamp();
+            let now_datum = timestamp_to_pgrx(now)?.into_datum().ok_or_else(|| {
+                CaliberError::Storage(StorageError::UpdateFailed {
+                    entity_type: EntityType::Conflict,
+                    id: conflict_id.as_uuid(),
+                    reason: "Failed to convert timestamp to datum".to_string(),
+                })
+            })?;
 
-        values[conflict::RESOLVED_AT as usize - 1] = now_datum;
-        nulls[conflict::RESOLVED_AT as usize - 1] = false;
+            values[conflict::RESOLVED_AT as usize - 1] = now_datum;
+            nulls[conflict::RESOLVED_AT as usize - 1] = false;
+        }
 
         let new_tuple = form_tuple(&rel, &values, &nulls)?;
         let old_tid = scanner.current_tid().ok_or_else(|| {
@@ -291,6 +314,46 @@ pub fn conflict_list_pending_heap(tenant_id: TenantId) -> CaliberResult<Vec<Conf
     Ok(results)
 }
 
+/// List all conflicts in a trajectory using a heap scan.
+pub fn conflict_list_by_trajectory_heap(
+    trajectory_id: TrajectoryId,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<ConflictRow>> {
+    conflict_scan_heap(tenant_id, |c| c.trajectory_id == Some(trajectory_id))
+}
+
+/// List all conflicts involving an agent on either side using a heap scan.
+pub fn conflict_list_by_agent_heap(
+    agent_id: AgentId,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<ConflictRow>> {
+    conflict_scan_heap(tenant_id, |c| {
+        c.agent_a_id == Some(agent_id) || c.agent_b_id == Some(agent_id)
+    })
+}
+
+/// Scan every conflict of a tenant, keeping those matching `filter`.
+fn conflict_scan_heap(
+    tenant_id: TenantId,
+    filter: impl Fn(&Conflict) -> bool,
+) -> CaliberResult<Vec<ConflictRow>> {
+    let rel = open_relation(conflict::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let snapshot = get_active_snapshot();
+    let mut scanner = unsafe { HeapScanner::new(&rel, snapshot, 0, ptr::null_mut()) };
+    let tuple_desc = rel.tuple_desc();
+
+    let mut results = Vec::new();
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_conflict(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) && filter(&row.conflict)
+        {
+            results.push(row);
+        }
+    }
+
+    Ok(results)
+}
+
 /// Validate that a HeapRelation is suitable for conflict operations.
 fn validate_conflict_relation(rel: &HeapRelation) -> CaliberResult<()> {
     let natts = rel.natts();
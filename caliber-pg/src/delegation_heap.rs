@@ -5,6 +5,7 @@
 
 use pgrx::pg_sys;
 use pgrx::prelude::*;
+use std::ptr;
 
 use caliber_core::{
     AgentId, ArtifactId, CaliberError, CaliberResult, DelegatedTask, DelegationId,
@@ -15,7 +16,7 @@ use caliber_core::{
 use crate::column_maps::delegation;
 use crate::heap_ops::{
     current_timestamp, form_tuple, get_active_snapshot, insert_tuple, open_relation,
-    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as HeapLockMode,
+    timestamp_to_pgrx, update_tuple, HeapRelation, HeapScanner, PgLockMode as HeapLockMode,
 };
 use crate::index_ops::{
     init_scan_key, open_index, operator_oids, update_indexes_for_insert, BTreeStrategy,
@@ -222,6 +223,8 @@ pub fn delegation_get_heap(
 /// - The child trajectory created for the delegated work (child_trajectory_id)
 /// - The acceptance timestamp
 /// - Status change to "accepted"
+///
+/// Returns Ok(false) if the delegation is targeted at a different agent.
 pub fn delegation_accept_heap(
     delegation_id: DelegationId,
     delegatee_agent_id: AgentId,
@@ -250,6 +253,12 @@ pub fn delegation_accept_heap(
         if existing_tenant != Some(tenant_id.as_uuid()) {
             return Ok(false);
         }
+        // A delegation targeted at a specific agent can only be accepted by it
+        let existing_delegatee =
+            unsafe { extract_uuid(old_tuple, tuple_desc, delegation::DELEGATEE_AGENT_ID)? };
+        if existing_delegatee.is_some_and(|id| id != delegatee_agent_id.as_uuid()) {
+            return Ok(false);
+        }
         let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
 
         // Update delegatee_agent_id - the agent accepting the delegation
@@ -286,6 +295,81 @@ pub fn delegation_accept_heap(
         })?;
 
         unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+        unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+/// Mark an accepted delegation as in progress using direct heap operations.
+///
+/// Only succeeds when the delegation is in `accepted` status and was accepted
+/// by `delegatee_agent_id`; returns `Ok(false)` otherwise. Also records
+/// `last_progress_at` so saga timeout tracking sees the work has started.
+pub fn delegation_start_heap(
+    delegation_id: DelegationId,
+    delegatee_agent_id: AgentId,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    let rel = open_relation(delegation::TABLE_NAME, HeapLockMode::RowExclusive)?;
+    let index_rel = open_index(delegation::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(delegation_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    if let Some(old_tuple) = scanner.next() {
+        let tuple_desc = rel.tuple_desc();
+        let existing_tenant =
+            unsafe { extract_uuid(old_tuple, tuple_desc, delegation::TENANT_ID)? };
+        if existing_tenant != Some(tenant_id.as_uuid()) {
+            return Ok(false);
+        }
+        let status = unsafe { extract_text(old_tuple, tuple_desc, delegation::STATUS)? };
+        if status.as_deref() != Some("accepted") {
+            return Ok(false);
+        }
+        let delegatee =
+            unsafe { extract_uuid(old_tuple, tuple_desc, delegation::DELEGATEE_AGENT_ID)? };
+        if delegatee != Some(delegatee_agent_id.as_uuid()) {
+            return Ok(false);
+        }
+        let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+
+        // Update status to "in_progress"
+        values[delegation::STATUS as usize - 1] = string_to_datum("in_progress");
+
+        // Record progress so timeout tracking sees the work has started
+        let now = current_timestamp();
+        let now_datum = timestamp_to_pgrx(now)?.into_datum().ok_or_else(|| {
+            CaliberError::Storage(StorageError::UpdateFailed {
+                entity_type: EntityType::Delegation,
+                id: delegation_id.as_uuid(),
+                reason: "Failed to convert timestamp to datum".to_string(),
+            })
+        })?;
+
+        values[delegation::LAST_PROGRESS_AT as usize - 1] = now_datum;
+        nulls[delegation::LAST_PROGRESS_AT as usize - 1] = false;
+
+        let new_tuple = form_tuple(&rel, &values, &nulls)?;
+        let old_tid = scanner.current_tid().ok_or_else(|| {
+            CaliberError::Storage(StorageError::TransactionFailed {
+                reason: "Failed to get TID of delegation tuple".to_string(),
+            })
+        })?;
+
+        unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+        unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
         Ok(true)
     } else {
         Ok(false)
@@ -358,6 +442,7 @@ pub fn delegation_complete_heap(
         })?;
 
         unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+        unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
         Ok(true)
     } else {
         Ok(false)
@@ -394,6 +479,73 @@ pub fn delegation_list_pending_heap(tenant_id: TenantId) -> CaliberResult<Vec<De
     Ok(results)
 }
 
+/// List delegations created by a delegator agent using the delegator index.
+///
+/// Results are ordered by creation time, oldest first, with the ID breaking ties.
+pub fn delegation_list_by_delegator_heap(
+    delegator_agent_id: AgentId,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<DelegationRow>> {
+    let rel = open_relation(delegation::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let index_rel = open_index(delegation::DELEGATOR_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(delegator_agent_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let tuple_desc = rel.tuple_desc();
+    let mut results = Vec::new();
+
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_delegation(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) {
+            results.push(row);
+        }
+    }
+
+    results.sort_by_key(|row| {
+        (
+            row.delegation.created_at,
+            row.delegation.delegation_id.as_uuid(),
+        )
+    });
+    Ok(results)
+}
+
+/// List every delegation of a tenant using a heap scan.
+///
+/// Results are ordered by creation time, oldest first, with the ID breaking ties.
+pub fn delegation_list_heap(tenant_id: TenantId) -> CaliberResult<Vec<DelegationRow>> {
+    let rel = open_relation(delegation::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let snapshot = get_active_snapshot();
+    let mut scanner = unsafe { HeapScanner::new(&rel, snapshot, 0, ptr::null_mut()) };
+    let tuple_desc = rel.tuple_desc();
+
+    let mut results = Vec::new();
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_delegation(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) {
+            results.push(row);
+        }
+    }
+
+    results.sort_by_key(|row| {
+        (
+            row.delegation.created_at,
+            row.delegation.delegation_id.as_uuid(),
+        )
+    });
+    Ok(results)
+}
+
 /// Validate that a HeapRelation is suitable for delegation operations.
 fn validate_delegation_relation(rel: &HeapRelation) -> CaliberResult<()> {
     let natts = rel.natts();
@@ -197,6 +197,36 @@ pub fn handoff_get_heap(
     }
 }
 
+/// List initiated (not yet accepted) handoffs using the status index.
+pub fn handoff_list_initiated_heap(tenant_id: TenantId) -> CaliberResult<Vec<HandoffRow>> {
+    let rel = open_relation(handoff::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let index_rel = open_index(handoff::STATUS_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::TEXT_EQ,
+        string_to_datum("initiated"),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let tuple_desc = rel.tuple_desc();
+    let mut results = Vec::new();
+
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_handoff(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) {
+            results.push(row);
+        }
+    }
+
+    Ok(results)
+}
+
 /// Accept a handoff by updating status, accepted_at, and to_agent_id.
 ///
 /// This function updates the handoff to record:
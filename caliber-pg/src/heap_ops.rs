@@ -117,6 +117,17 @@ impl HeapRelation {
     }
 }
 
+/// Outcome of tombstoning a row through its `deleted_at` column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoftDeleteOutcome {
+    /// The row was live and is now tombstoned
+    Deleted,
+    /// The row was already tombstoned and was left unchanged
+    AlreadyDeleted,
+    /// No row with that ID exists for the tenant
+    NotFound,
+}
+
 /// Open a relation by name with the specified lock mode.
 ///
 /// # Arguments
@@ -72,6 +72,12 @@ pub mod operator_oids {
     /// Int4 equality operator (int4 = int4)
     pub const INT4_EQ: pg_sys::Oid = pg_sys::Oid::from_u32(96);
 
+    /// Int4 less-than-or-equal function (int4le)
+    pub const INT4_LE: pg_sys::Oid = pg_sys::Oid::from_u32(149);
+
+    /// Int4 greater-than-or-equal function (int4ge)
+    pub const INT4_GE: pg_sys::Oid = pg_sys::Oid::from_u32(150);
+
     /// Int8 equality operator (int8 = int8)
     pub const INT8_EQ: pg_sys::Oid = pg_sys::Oid::from_u32(410);
 
@@ -258,6 +264,8 @@ pub struct IndexScanner {
     #[allow(dead_code)]
     heap_rel: pg_sys::Relation,
     slot: *mut pg_sys::TupleTableSlot,
+    /// Walk the index from the end instead of the start.
+    backward: bool,
 }
 
 impl IndexScanner {
@@ -307,9 +315,18 @@ impl IndexScanner {
             scan,
             heap_rel: heap_rel.as_ref().as_ptr(),
             slot,
+            backward: false,
         }
     }
 
+    /// Return tuples in descending index order.
+    ///
+    /// Must be called before the first `next()`.
+    pub fn backward(mut self) -> Self {
+        self.backward = true;
+        self
+    }
+
     // next() provided by Iterator impl
 
     /// Get the TID of the current tuple (after calling next()).
@@ -334,8 +351,12 @@ impl Iterator for IndexScanner {
     fn next(&mut self) -> Option<Self::Item> {
         unsafe {
             // Get the next TID from the index
-            let tid =
-                pg_sys::index_getnext_tid(self.scan, pg_sys::ScanDirection::ForwardScanDirection);
+            let direction = if self.backward {
+                pg_sys::ScanDirection::BackwardScanDirection
+            } else {
+                pg_sys::ScanDirection::ForwardScanDirection
+            };
+            let tid = pg_sys::index_getnext_tid(self.scan, direction);
 
             if tid.is_null() {
                 return None;
@@ -29,6 +29,8 @@ pub mod pg_test {
 use caliber_core::{
     compute_content_hash,
     compute_lock_key,
+    ttl_from_str,
+    ttl_to_str,
     AbstractionLevel,
     Agent,
     AgentError,
@@ -58,6 +60,7 @@ use caliber_core::{
     EdgeType,
     EmbeddingVector,
     EntityIdType,
+    EntityRef,
     EntityType,
     ExtractionMethod,
     HandoffId,
@@ -76,6 +79,7 @@ use caliber_core::{
     Note,
     NoteId,
     NoteType,
+    OutcomeStatus,
     Provenance,
     RawContent,
     ResolutionStrategy,
@@ -104,10 +108,12 @@ use pgrx::datum::TimestampWithTimeZone;
 use chrono::Utc;
 use once_cell::sync::Lazy;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::RwLock;
 use uuid::Uuid;
 
+use crate::heap_ops::SoftDeleteOutcome;
+
 #[cfg(test)]
 mod row_conversion_tests;
 
@@ -200,6 +206,12 @@ fn int8_datum(n: i64) -> DatumWithOid<'static> {
     unsafe { DatumWithOid::new(n, pgrx::pg_sys::INT8OID) }
 }
 
+/// Convert an f64 to DatumWithOid for SPI calls.
+#[inline]
+fn float8_datum(n: f64) -> DatumWithOid<'static> {
+    unsafe { DatumWithOid::new(n, pgrx::pg_sys::FLOAT8OID) }
+}
+
 /// Convert a chrono DateTime<Utc> to DatumWithOid for SPI calls.
 #[inline]
 fn timestamp_datum(dt: chrono::DateTime<chrono::Utc>) -> CaliberResult<DatumWithOid<'static>> {
@@ -279,6 +291,102 @@ pgrx::extension_sql_file!(
     name = "dsl_pack_source_v8",
     requires = ["fix_shared_locks_v7"],
 );
+// V9: Memory category column on notes and artifacts
+pgrx::extension_sql_file!(
+    "../sql/migrations/V9__memory_category.sql",
+    name = "memory_category_v9",
+    requires = ["dsl_pack_source_v8"],
+);
+// V10: Trajectory column on summarization policies
+pgrx::extension_sql_file!(
+    "../sql/migrations/V10__summarization_policy_trajectory.sql",
+    name = "summarization_policy_trajectory_v10",
+    requires = ["memory_category_v9"],
+);
+// V11: External key on agents for idempotent registration
+pgrx::extension_sql_file!(
+    "../sql/migrations/V11__agent_external_key.sql",
+    name = "agent_external_key_v11",
+    requires = ["summarization_policy_trajectory_v10"],
+);
+// V12: Version counter on regions
+pgrx::extension_sql_file!(
+    "../sql/migrations/V12__region_version.sql",
+    name = "region_version_v12",
+    requires = ["agent_external_key_v11"],
+);
+// V13: Reply-to link on messages
+pgrx::extension_sql_file!(
+    "../sql/migrations/V13__message_reply_to.sql",
+    name = "message_reply_to_v13",
+    requires = ["region_version_v12"],
+);
+// V14: Index on agent current trajectory
+pgrx::extension_sql_file!(
+    "../sql/migrations/V14__agent_current_trajectory_index.sql",
+    name = "agent_current_trajectory_index_v14",
+    requires = ["message_reply_to_v13"],
+);
+// V15: Per-tenant embedding dimension setting
+pgrx::extension_sql_file!(
+    "../sql/migrations/V15__embedding_settings.sql",
+    name = "embedding_settings_v15",
+    requires = ["agent_current_trajectory_index_v14"],
+);
+// V16: Scheduled policy registry
+pgrx::extension_sql_file!(
+    "../sql/migrations/V16__scheduled_policy.sql",
+    name = "scheduled_policy_v16",
+    requires = ["embedding_settings_v15"],
+);
+// V17: Reentrant lock acquire count
+pgrx::extension_sql_file!(
+    "../sql/migrations/V17__lock_acquire_count.sql",
+    name = "lock_acquire_count_v17",
+    requires = ["scheduled_policy_v16"],
+);
+// V18: Per-tenant runtime config settings
+pgrx::extension_sql_file!(
+    "../sql/migrations/V18__config_setting.sql",
+    name = "config_setting_v18",
+    requires = ["lock_acquire_count_v17"],
+);
+// V19: Full-text search indexes over artifact and note content
+pgrx::extension_sql_file!(
+    "../sql/migrations/V19__content_text_search.sql",
+    name = "content_text_search_v19",
+    requires = ["config_setting_v18"],
+);
+// V20: Audit log of entity mutations
+pgrx::extension_sql_file!(
+    "../sql/migrations/V20__audit_log.sql",
+    name = "audit_log_v20",
+    requires = ["content_text_search_v19"],
+);
+// V21: Soft delete tombstones for artifacts and notes
+pgrx::extension_sql_file!(
+    "../sql/migrations/V21__soft_delete.sql",
+    name = "soft_delete_v21",
+    requires = ["audit_log_v20"],
+);
+// V22: Drop the INTEGER caliber_schema_version() before the TEXT one is created
+pgrx::extension_sql_file!(
+    "../sql/migrations/V22__schema_version_text.sql",
+    name = "schema_version_text_v22",
+    requires = ["soft_delete_v21"],
+);
+// V23: Canonical NOTIFY channel names in the message insert trigger
+pgrx::extension_sql_file!(
+    "../sql/migrations/V23__message_notify_channels.sql",
+    name = "message_notify_channels_v23",
+    requires = ["schema_version_text_v22"],
+);
+// V24: Closed scopes already seen by each summarization policy
+pgrx::extension_sql_file!(
+    "../sql/migrations/V24__summarization_scope_close_seen.sql",
+    name = "summarization_scope_close_seen_v24",
+    requires = ["message_notify_channels_v23"],
+);
 
 // ============================================================================
 // DIRECT HEAP OPERATION MODULES (Hot Path - NO SQL)
@@ -338,7 +446,7 @@ pub mod edge_heap;
 // ============================================================================
 
 /// Current schema version. Increment this when adding migrations.
-const SCHEMA_VERSION: i32 = 8;
+const SCHEMA_VERSION: i32 = 24;
 
 /// Extension initialization hook.
 /// Called when the extension is loaded.
@@ -373,8 +481,96 @@ pub extern "C-unwind" fn _PG_fini() {
 ///
 /// Migrations are idempotent and safe to run multiple times.
 fn run_pending_migrations() -> Result<(), String> {
+    let current_version = match recorded_schema_version()? {
+        Some(version) => version,
+        None => {
+            // Schema not initialized yet, skip migrations
+            pgrx::log!("CALIBER: Schema not initialized, skipping migrations");
+            return Ok(());
+        }
+    };
+
+    if current_version > SCHEMA_VERSION {
+        pgrx::warning!(
+            "CALIBER: Database schema v{} is newer than extension schema v{}",
+            current_version,
+            SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+
+    if current_version == SCHEMA_VERSION {
+        pgrx::log!(
+            "CALIBER: Schema is current (v{}), no migrations needed",
+            current_version
+        );
+        return Ok(());
+    }
+
+    pgrx::log!(
+        "CALIBER: Running migrations from v{} to v{}",
+        current_version,
+        SCHEMA_VERSION
+    );
+
+    // Run each migration in sequence (inlined to avoid borrow issues with pgrx SpiClient)
+    for version in (current_version + 1)..=SCHEMA_VERSION {
+        let start = std::time::Instant::now();
+
+        // Migration SQL based on version
+        let (description, migration_sql): (&str, Option<&str>) = match version {
+            1 => (
+                "Initial schema - CALIBER 0.4.4",
+                // Version 1 is the base schema, no migration needed
+                // (it's created by caliber_init())
+                None,
+            ),
+            // Future migrations go here:
+            // 2 => ("Add new feature X", Some("ALTER TABLE ...")),
+            _ => {
+                return Err(format!("Unknown migration version: {}", version));
+            }
+        };
+
+        // Run migration SQL if present
+        if let Some(sql) = migration_sql {
+            pgrx::log!("CALIBER: Running migration v{}: {}", version, description);
+            Spi::run(sql).map_err(|e| format!("Migration v{} failed: {:?}", version, e))?;
+        }
+
+        let elapsed = start.elapsed().as_millis() as i32;
+
+        // Record migration in schema_version table
+        // Using formatted SQL since this is trusted internal code
+        let checksum = format!("v{}-{:x}", version, elapsed);
+        let insert_sql = format!(
+            "INSERT INTO caliber_schema_version (version, description, checksum, execution_time_ms) \
+             VALUES ({}, '{}', '{}', {}) ON CONFLICT (version) DO NOTHING",
+            version,
+            description.replace('\'', "''"),
+            checksum.replace('\'', "''"),
+            elapsed
+        );
+        Spi::run(&insert_sql)
+            .map_err(|e| format!("Failed to record migration v{}: {:?}", version, e))?;
+
+        pgrx::log!(
+            "CALIBER: Migration v{} complete: {} ({}ms)",
+            version,
+            description,
+            elapsed
+        );
+    }
+
+    Ok(())
+}
+
+/// Read the highest schema version recorded in `caliber_schema_version`.
+///
+/// Returns `None` when the table does not exist (schema not initialized yet),
+/// and `Some(0)` when it exists but is empty.
+fn recorded_schema_version() -> Result<Option<i32>, String> {
     Spi::connect(|client| {
-        // Check if schema_version table exists (schema may not be initialized yet)
         let table_exists = client
             .select(
                 "SELECT EXISTS (
@@ -391,13 +587,10 @@ fn run_pending_migrations() -> Result<(), String> {
             .unwrap_or(false);
 
         if !table_exists {
-            // Schema not initialized yet, skip migrations
-            pgrx::log!("CALIBER: Schema not initialized, skipping migrations");
-            return Ok(());
+            return Ok(None);
         }
 
-        // Get current schema version
-        let current_version = client
+        let version = client
             .select(
                 "SELECT COALESCE(MAX(version), 0) FROM caliber_schema_version",
                 None,
@@ -409,70 +602,7 @@ fn run_pending_migrations() -> Result<(), String> {
             .map_err(|e| e.to_string())?
             .unwrap_or(0);
 
-        if current_version >= SCHEMA_VERSION {
-            pgrx::log!(
-                "CALIBER: Schema is current (v{}), no migrations needed",
-                current_version
-            );
-            return Ok(());
-        }
-
-        pgrx::log!(
-            "CALIBER: Running migrations from v{} to v{}",
-            current_version,
-            SCHEMA_VERSION
-        );
-
-        // Run each migration in sequence (inlined to avoid borrow issues with pgrx SpiClient)
-        for version in (current_version + 1)..=SCHEMA_VERSION {
-            let start = std::time::Instant::now();
-
-            // Migration SQL based on version
-            let (description, migration_sql): (&str, Option<&str>) = match version {
-                1 => (
-                    "Initial schema - CALIBER 0.4.4",
-                    // Version 1 is the base schema, no migration needed
-                    // (it's created by caliber_init())
-                    None,
-                ),
-                // Future migrations go here:
-                // 2 => ("Add new feature X", Some("ALTER TABLE ...")),
-                _ => {
-                    return Err(format!("Unknown migration version: {}", version));
-                }
-            };
-
-            // Run migration SQL if present
-            if let Some(sql) = migration_sql {
-                pgrx::log!("CALIBER: Running migration v{}: {}", version, description);
-                Spi::run(sql).map_err(|e| format!("Migration v{} failed: {:?}", version, e))?;
-            }
-
-            let elapsed = start.elapsed().as_millis() as i32;
-
-            // Record migration in schema_version table
-            // Using formatted SQL since this is trusted internal code
-            let checksum = format!("v{}-{:x}", version, elapsed);
-            let insert_sql = format!(
-                "INSERT INTO caliber_schema_version (version, description, checksum, execution_time_ms) \
-                 VALUES ({}, '{}', '{}', {}) ON CONFLICT (version) DO NOTHING",
-                version,
-                description.replace('\'', "''"),
-                checksum.replace('\'', "''"),
-                elapsed
-            );
-            Spi::run(&insert_sql)
-                .map_err(|e| format!("Failed to record migration v{}: {:?}", version, e))?;
-
-            pgrx::log!(
-                "CALIBER: Migration v{} complete: {} ({}ms)",
-                version,
-                description,
-                elapsed
-            );
-        }
-
-        Ok(())
+        Ok(Some(version))
     })
 }
 
@@ -573,11 +703,11 @@ fn safe_to_json_array<T: Serialize>(values: &[T]) -> serde_json::Value {
 
 /// Create a CaliberConfig for the extension.
 /// NOTE: CaliberConfig has NO default - all values must be provided explicitly.
-/// This helper creates a minimal valid config for internal use.
-#[allow(dead_code)]
-fn create_config(token_budget: i32) -> CaliberConfig {
+/// This helper creates a minimal valid config for internal use, with the
+/// tenant's runtime settings (see `caliber_config_set`) applied on top.
+fn create_config(token_budget: i32, settings: &ConfigSettings) -> CaliberResult<CaliberConfig> {
     use std::time::Duration;
-    CaliberConfig {
+    Ok(CaliberConfig {
         token_budget,
         section_priorities: caliber_core::SectionPriorities {
             user: 100,
@@ -589,8 +719,8 @@ fn create_config(token_budget: i32) -> CaliberConfig {
             custom: vec![],
         },
         checkpoint_retention: 10,
-        stale_threshold: Duration::from_secs(3600),
-        contradiction_threshold: 0.8,
+        stale_threshold: settings.stale_threshold,
+        contradiction_threshold: settings.contradiction_threshold,
         context_window_persistence: caliber_core::ContextPersistence::Ephemeral,
         validation_mode: caliber_core::ValidationMode::OnMutation,
         embedding_provider: None,
@@ -604,7 +734,7 @@ fn create_config(token_budget: i32) -> CaliberConfig {
         lock_timeout: Duration::from_secs(30),
         message_retention: Duration::from_secs(86400),
         delegation_timeout: Duration::from_secs(300),
-    }
+    })
 }
 
 /// Create a checkpoint from a scope's current state.
@@ -619,21 +749,70 @@ fn create_checkpoint(data: serde_json::Value, recoverable: bool) -> Checkpoint {
     }
 }
 
-/// Categorize content by memory category.
-#[allow(dead_code)]
-fn categorize_memory(category: &str) -> MemoryCategory {
+/// Parse a memory category name (as stored in the `memory_category` column).
+/// Returns None for unknown categories so callers can reject them.
+fn categorize_memory(category: &str) -> Option<MemoryCategory> {
     match category {
-        "working" => MemoryCategory::Working,
-        "episodic" => MemoryCategory::Episodic,
-        "semantic" => MemoryCategory::Semantic,
-        "procedural" => MemoryCategory::Procedural,
-        _ => {
-            pgrx::warning!(
-                "CALIBER: Unknown memory category '{}', defaulting to Working",
-                category
-            );
+        "ephemeral" => Some(MemoryCategory::Ephemeral),
+        "working" => Some(MemoryCategory::Working),
+        "episodic" => Some(MemoryCategory::Episodic),
+        "semantic" => Some(MemoryCategory::Semantic),
+        "procedural" => Some(MemoryCategory::Procedural),
+        "meta" => Some(MemoryCategory::Meta),
+        _ => None,
+    }
+}
+
+/// Convert a MemoryCategory to its storage string.
+pub(crate) fn memory_category_to_str(category: MemoryCategory) -> &'static str {
+    match category {
+        MemoryCategory::Ephemeral => "ephemeral",
+        MemoryCategory::Working => "working",
+        MemoryCategory::Episodic => "episodic",
+        MemoryCategory::Semantic => "semantic",
+        MemoryCategory::Procedural => "procedural",
+        MemoryCategory::Meta => "meta",
+    }
+}
+
+/// Memory category a note is filed under, derived from its type.
+/// Keep in sync with the backfill in V9__memory_category.sql.
+pub(crate) fn note_type_memory_category(note_type: NoteType) -> MemoryCategory {
+    match note_type {
+        NoteType::Convention | NoteType::Strategy | NoteType::Gotcha | NoteType::Procedure => {
+            MemoryCategory::Procedural
+        }
+        NoteType::Summary => MemoryCategory::Episodic,
+        NoteType::Meta => MemoryCategory::Meta,
+        NoteType::Fact
+        | NoteType::Preference
+        | NoteType::Relationship
+        | NoteType::Insight
+        | NoteType::Correction => MemoryCategory::Semantic,
+    }
+}
+
+/// Memory category an artifact is filed under, derived from its type.
+/// Keep in sync with the backfill in V9__memory_category.sql.
+pub(crate) fn artifact_type_memory_category(artifact_type: ArtifactType) -> MemoryCategory {
+    match artifact_type {
+        ArtifactType::ErrorLog
+        | ArtifactType::ToolResult
+        | ArtifactType::Log
+        | ArtifactType::Summary => MemoryCategory::Episodic,
+        ArtifactType::CodePatch | ArtifactType::Code => MemoryCategory::Procedural,
+        ArtifactType::IntermediateOutput | ArtifactType::Plan | ArtifactType::Custom => {
             MemoryCategory::Working
         }
+        ArtifactType::DesignDecision
+        | ArtifactType::UserPreference
+        | ArtifactType::Fact
+        | ArtifactType::Constraint
+        | ArtifactType::Document
+        | ArtifactType::Data
+        | ArtifactType::Model
+        | ArtifactType::Config
+        | ArtifactType::Decision => MemoryCategory::Semantic,
     }
 }
 
@@ -737,35 +916,360 @@ fn create_memory_region(region: MemoryRegion) -> serde_json::Value {
 /// This is the complete schema from caliber_init.sql.
 const BOOTSTRAP_SQL: &str = include_str!("../sql/caliber_init.sql");
 
+/// Split a SQL script into individual statements on top-level semicolons.
+///
+/// Semicolons inside single-quoted strings, double-quoted identifiers,
+/// `--` and `/* */` comments, and dollar-quoted bodies (`$$ ... $$` or
+/// `$tag$ ... $tag$`) do not end a statement. Statements consisting only of
+/// whitespace and comments are dropped; the rest are returned trimmed and
+/// without the trailing semicolon.
+fn split_sql_statements(sql: &str) -> Vec<&str> {
+    let bytes = sql.as_bytes();
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut has_code = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+                continue;
+            }
+            quote @ (b'\'' | b'"') => {
+                // Doubled quotes are escapes and are consumed as two quoted sections
+                has_code = true;
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += 1;
+                }
+            }
+            b'$' => {
+                has_code = true;
+                let tag_end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+                    .map(|p| i + 1 + p);
+                let is_tag = match tag_end {
+                    Some(end) => {
+                        bytes[end] == b'$' && (end == i + 1 || !bytes[i + 1].is_ascii_digit())
+                    }
+                    None => false,
+                };
+                if let (true, Some(end)) = (is_tag, tag_end) {
+                    let tag = &sql[i..=end];
+                    match sql[end + 1..].find(tag) {
+                        Some(close) => i = end + 1 + close + tag.len() - 1,
+                        None => i = bytes.len(),
+                    }
+                }
+            }
+            b';' => {
+                if has_code {
+                    statements.push(sql[start..i].trim());
+                }
+                start = i + 1;
+                has_code = false;
+            }
+            b if !b.is_ascii_whitespace() => has_code = true,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if has_code {
+        statements.push(sql[start..].trim());
+    }
+    statements
+}
+
+/// Short single-line label for a statement, used in init reports and logs.
+fn statement_summary(statement: &str) -> String {
+    let line = statement
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with("--"))
+        .unwrap_or("");
+    if line.chars().count() > 120 {
+        format!("{}...", line.chars().take(120).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Execute a single statement inside an internal subtransaction.
+///
+/// A failing statement rolls back only its own subtransaction, so the
+/// surrounding transaction stays usable for the statements that follow.
+fn run_statement_isolated(statement: &str) -> Result<(), String> {
+    use pgrx::pg_sys::panic::CaughtError;
+
+    let (old_context, old_owner) = unsafe {
+        (
+            pgrx::pg_sys::CurrentMemoryContext,
+            pgrx::pg_sys::CurrentResourceOwner,
+        )
+    };
+    unsafe {
+        pgrx::pg_sys::BeginInternalSubTransaction(std::ptr::null());
+        pgrx::pg_sys::MemoryContextSwitchTo(old_context);
+    }
+
+    let rollback = || unsafe {
+        pgrx::pg_sys::MemoryContextSwitchTo(old_context);
+        pgrx::pg_sys::RollbackAndReleaseCurrentSubTransaction();
+        pgrx::pg_sys::MemoryContextSwitchTo(old_context);
+        pgrx::pg_sys::CurrentResourceOwner = old_owner;
+    };
+
+    pgrx::PgTryBuilder::new(|| match Spi::run(statement) {
+        Ok(()) => {
+            unsafe {
+                pgrx::pg_sys::ReleaseCurrentSubTransaction();
+                pgrx::pg_sys::MemoryContextSwitchTo(old_context);
+                pgrx::pg_sys::CurrentResourceOwner = old_owner;
+            }
+            Ok(())
+        }
+        Err(e) => {
+            rollback();
+            Err(e.to_string())
+        }
+    })
+    .catch_others(|e| {
+        rollback();
+        Err(match e {
+            CaughtError::PostgresError(report) | CaughtError::ErrorReport(report) => {
+                report.message().to_string()
+            }
+            CaughtError::RustPanic { ereport, .. } => ereport.message().to_string(),
+        })
+    })
+    .execute()
+}
+
 /// Initialize the CALIBER schema.
 /// This creates all tables, indexes, and functions needed by the extension.
 /// This SQL runs ONCE at extension install, NOT in hot path.
 ///
 /// The schema is idempotent - all CREATE statements use IF NOT EXISTS.
+/// Initialization is refused when the recorded schema version is newer than
+/// SCHEMA_VERSION, since this build's bootstrap SQL could not be trusted to
+/// leave a newer schema intact.
+///
+/// The bootstrap SQL is split into statements and each runs in its own
+/// subtransaction, so one failing statement does not abort the others.
 ///
 /// # Returns
-/// - Success message with table count on success
-/// - Error message on failure
+/// JSON report: `success`, `message`, counts of `succeeded` and `failed`
+/// statements, and a `statements` list with each statement's `index`,
+/// `summary`, `ok` flag, and `error` (if any). Earlier releases returned only
+/// the status text, which is now the `message` field.
 #[pg_extern]
-fn caliber_init() -> String {
+fn caliber_init() -> pgrx::JsonB {
     pgrx::log!("CALIBER: Initializing schema...");
 
-    // Execute the bootstrap SQL via SPI (using connect_mut for update operations)
-    match Spi::connect_mut(|client| {
-        // Split the SQL into individual statements and execute each
-        // This handles the multi-statement SQL file properly
-        client.update(BOOTSTRAP_SQL, None, &[])?;
-        Ok::<_, pgrx::spi::SpiError>(())
-    }) {
-        Ok(()) => {
-            pgrx::log!("CALIBER: Schema initialization complete");
-            "CALIBER schema initialized successfully".to_string()
+    let refuse = |message: String| {
+        pgrx::JsonB(serde_json::json!({
+            "success": false,
+            "message": message,
+            "succeeded": 0,
+            "failed": 0,
+            "statements": [],
+        }))
+    };
+
+    match recorded_schema_version() {
+        Ok(Some(version)) if version > SCHEMA_VERSION => {
+            pgrx::warning!(
+                "CALIBER: Refusing to initialize: database schema v{} is newer than extension schema v{}",
+                version,
+                SCHEMA_VERSION
+            );
+            return refuse(format!(
+                "CALIBER schema initialization refused: database schema v{} is newer than extension schema v{}",
+                version, SCHEMA_VERSION
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => {
+            pgrx::warning!("CALIBER: Schema version check failed: {}", e);
+            return refuse(format!("CALIBER schema initialization failed: {}", e));
+        }
+    }
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for (index, statement) in split_sql_statements(BOOTSTRAP_SQL).into_iter().enumerate() {
+        let summary = statement_summary(statement);
+        match run_statement_isolated(statement) {
+            Ok(()) => results.push(serde_json::json!({
+                "index": index,
+                "summary": summary,
+                "ok": true,
+            })),
+            Err(e) => {
+                pgrx::warning!(
+                    "CALIBER: Bootstrap statement {} failed: {}: {}",
+                    index,
+                    summary,
+                    e
+                );
+                failed += 1;
+                results.push(serde_json::json!({
+                    "index": index,
+                    "summary": summary,
+                    "ok": false,
+                    "error": e,
+                }));
+            }
         }
+    }
+
+    let succeeded = results.len() - failed;
+    let message = if failed == 0 {
+        pgrx::log!("CALIBER: Schema initialization complete");
+        "CALIBER schema initialized successfully".to_string()
+    } else {
+        pgrx::warning!(
+            "CALIBER: Schema initialization finished with {} failed statement(s)",
+            failed
+        );
+        format!(
+            "CALIBER schema initialization failed: {} of {} statements failed",
+            failed,
+            results.len()
+        )
+    };
+
+    pgrx::JsonB(serde_json::json!({
+        "success": failed == 0,
+        "message": message,
+        "succeeded": succeeded,
+        "failed": failed,
+        "statements": results,
+    }))
+}
+
+/// Drop every CALIBER table, view, and SQL function in the current schema.
+///
+/// Refuses unless `confirm` is true. Views are dropped first, then tables
+/// (CASCADE takes their indexes, triggers, policies, and FKs with them), then
+/// SQL/PL/pgSQL functions. Objects owned by an extension are detached from it
+/// first so they can be dropped individually. Functions implemented by the
+/// extension library itself are left for `DROP EXTENSION`.
+///
+/// # Returns
+/// A summary line followed by one `<KIND> <name>` line per dropped object,
+/// or a refusal/error message.
+#[pg_extern]
+fn caliber_drop_schema(confirm: bool) -> String {
+    if !confirm {
+        return "CALIBER schema drop refused: call caliber_drop_schema(true) to confirm"
+            .to_string();
+    }
+
+    let objects: Result<Vec<(String, String, Option<String>)>, pgrx::spi::SpiError> =
+        Spi::connect(|client| {
+            let table = client.select(
+                "SELECT kind, name, extname FROM (
+                    SELECT CASE c.relkind
+                               WHEN 'v' THEN 'VIEW'
+                               WHEN 'm' THEN 'MATERIALIZED VIEW'
+                               ELSE 'TABLE'
+                           END AS kind,
+                           quote_ident(c.relname) AS name,
+                           quote_ident(e.extname) AS extname
+                    FROM pg_class c
+                    JOIN pg_namespace n ON n.oid = c.relnamespace
+                    LEFT JOIN pg_depend d ON d.classid = 'pg_class'::regclass
+                        AND d.objid = c.oid AND d.deptype = 'e'
+                    LEFT JOIN pg_extension e ON e.oid = d.refobjid
+                    WHERE n.nspname = current_schema()
+                      AND c.relkind IN ('r', 'p', 'v', 'm')
+                      AND c.relname LIKE 'caliber\\_%'
+                    UNION ALL
+                    SELECT 'FUNCTION', p.oid::regprocedure::text, quote_ident(e.extname)
+                    FROM pg_proc p
+                    JOIN pg_namespace n ON n.oid = p.pronamespace
+                    JOIN pg_language l ON l.oid = p.prolang
+                    LEFT JOIN pg_depend d ON d.classid = 'pg_proc'::regclass
+                        AND d.objid = p.oid AND d.deptype = 'e'
+                    LEFT JOIN pg_extension e ON e.oid = d.refobjid
+                    WHERE n.nspname = current_schema()
+                      AND l.lanname IN ('sql', 'plpgsql')
+                      AND p.proname LIKE 'caliber\\_%'
+                ) objects
+                ORDER BY CASE kind WHEN 'TABLE' THEN 1 WHEN 'FUNCTION' THEN 2 ELSE 0 END, name",
+                None,
+                &[],
+            )?;
+
+            let mut objects = Vec::new();
+            for row in table {
+                let kind: Option<String> = row.get(1)?;
+                let name: Option<String> = row.get(2)?;
+                let extname: Option<String> = row.get(3)?;
+                if let (Some(kind), Some(name)) = (kind, name) {
+                    objects.push((kind, name, extname));
+                }
+            }
+            Ok(objects)
+        });
+
+    let objects = match objects {
+        Ok(objects) => objects,
         Err(e) => {
-            pgrx::warning!("CALIBER: Schema initialization failed: {}", e);
-            format!("CALIBER schema initialization failed: {}", e)
+            pgrx::warning!("CALIBER: Failed to list schema objects: {}", e);
+            return format!("CALIBER schema drop failed: {}", e);
+        }
+    };
+
+    // Detach everything before dropping anything, since a CASCADE drop may
+    // remove a dependent object that is still listed below
+    for (kind, name, extname) in &objects {
+        if let Some(extname) = extname {
+            let detach = format!("ALTER EXTENSION {} DROP {} {}", extname, kind, name);
+            if let Err(e) = Spi::run(&detach) {
+                pgrx::warning!("CALIBER: Failed to detach {} {}: {}", kind, name, e);
+                return format!("CALIBER schema drop failed at {} {}: {}", kind, name, e);
+            }
+        }
+    }
+
+    let mut dropped = Vec::new();
+    for (kind, name, _) in &objects {
+        let drop = format!("DROP {} IF EXISTS {} CASCADE", kind, name);
+        if let Err(e) = Spi::run(&drop) {
+            pgrx::warning!("CALIBER: Failed to drop {} {}: {}", kind, name, e);
+            return format!("CALIBER schema drop failed at {} {}: {}", kind, name, e);
         }
+        dropped.push(format!("{} {}", kind, name));
     }
+
+    let count = |kind: &str| objects.iter().filter(|(k, _, _)| k == kind).count();
+    pgrx::log!("CALIBER: Dropped {} schema objects", dropped.len());
+    let mut report = format!(
+        "CALIBER schema dropped: {} tables, {} views, {} functions",
+        count("TABLE"),
+        count("VIEW") + count("MATERIALIZED VIEW"),
+        count("FUNCTION")
+    );
+    for line in dropped {
+        report.push('\n');
+        report.push_str(&line);
+    }
+    report
 }
 
 /// Check if the CALIBER schema is initialized.
@@ -802,34 +1306,364 @@ fn caliber_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
 
-// ============================================================================
-// ENTITY ID GENERATION
-// ============================================================================
-
-/// Generate a new UUIDv7 entity ID.
-/// UUIDv7 is timestamp-sortable, making it ideal for time-ordered data.
-#[pg_extern]
-fn caliber_new_id() -> pgrx::Uuid {
-    let id = Uuid::now_v7();
-    pgrx::Uuid::from_bytes(*id.as_bytes())
+/// Get the schema version recorded in the database.
+/// Returns None if the schema has not been initialized.
+#[pg_extern(requires = ["schema_version_text_v22"])]
+fn caliber_schema_version() -> Option<String> {
+    match recorded_schema_version() {
+        Ok(version) => version.map(|v| v.to_string()),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to read schema version: {}", e);
+            None
+        }
+    }
 }
 
 // ============================================================================
-// TRAJECTORY OPERATIONS (Task 12.3)
+// RUNTIME CONFIGURATION
 // ============================================================================
 
-/// Create a new trajectory.
-#[pg_extern]
-fn caliber_trajectory_create(
-    name: &str,
-    description: Option<&str>,
-    agent_id: Option<pgrx::Uuid>,
-    tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
-    // Record operation for metrics
-    storage_write().record_op("trajectory_create");
-
-    let trajectory_id = TrajectoryId::now_v7();
+/// Keys accepted by `caliber_config_set` / `caliber_config_get`.
+const CONFIG_KEYS: &[&str] = &[
+    "token_budget",
+    "contradiction_threshold",
+    "stale_threshold",
+    "embedding_dimension",
+];
+
+/// Effective runtime settings for a tenant: stored values from
+/// `caliber_config_setting` over the `CaliberConfig::default_context` values.
+struct ConfigSettings {
+    token_budget: i32,
+    contradiction_threshold: f32,
+    stale_threshold: std::time::Duration,
+}
+
+impl ConfigSettings {
+    /// Default token budget when neither the caller nor the tenant sets one.
+    const DEFAULT_TOKEN_BUDGET: i32 = 8000;
+
+    /// Load the tenant's settings with a single query over its stored keys.
+    fn load(tenant_id: TenantId) -> CaliberResult<Self> {
+        let defaults = CaliberConfig::default_context(Self::DEFAULT_TOKEN_BUDGET);
+        let mut settings = Self {
+            token_budget: defaults.token_budget,
+            contradiction_threshold: defaults.contradiction_threshold,
+            stale_threshold: defaults.stale_threshold,
+        };
+        for (key, value) in config_settings_spi(pgrx_uuid_from_id(tenant_id))? {
+            match key.as_str() {
+                "token_budget" => {
+                    settings.token_budget = config_value_checked(&key, &value)?
+                        .as_i64()
+                        .and_then(|v| i32::try_from(v).ok())
+                        .unwrap_or(settings.token_budget);
+                }
+                "contradiction_threshold" => {
+                    settings.contradiction_threshold = config_value_checked(&key, &value)?
+                        .as_f64()
+                        .map(|v| v as f32)
+                        .unwrap_or(settings.contradiction_threshold);
+                }
+                "stale_threshold" => {
+                    settings.stale_threshold = config_value_checked(&key, &value)?
+                        .as_u64()
+                        .map(std::time::Duration::from_secs)
+                        .unwrap_or(settings.stale_threshold);
+                }
+                _ => {}
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Validate a config value for `key`, returning it in canonical form.
+///
+/// `token_budget` and `embedding_dimension` are positive integers,
+/// `contradiction_threshold` is a number in 0.0..=1.0, and `stale_threshold`
+/// is a positive number of seconds.
+fn config_value_checked(key: &str, value: &serde_json::Value) -> CaliberResult<serde_json::Value> {
+    let invalid = |reason: &str| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: key.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+    match key {
+        "token_budget" | "embedding_dimension" => value
+            .as_i64()
+            .filter(|v| *v > 0 && *v <= i64::from(i32::MAX))
+            .map(serde_json::Value::from)
+            .ok_or_else(|| invalid("must be a positive integer")),
+        "contradiction_threshold" => value
+            .as_f64()
+            .filter(|v| (0.0..=1.0).contains(v))
+            .map(serde_json::Value::from)
+            .ok_or_else(|| invalid("must be a number between 0.0 and 1.0")),
+        "stale_threshold" => value
+            .as_u64()
+            .filter(|v| *v > 0)
+            .map(serde_json::Value::from)
+            .ok_or_else(|| invalid("must be a positive number of seconds")),
+        _ => Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "key".to_string(),
+            reason: format!(
+                "unknown config key '{}'. Valid keys: {}",
+                key,
+                CONFIG_KEYS.join(", ")
+            ),
+        })),
+    }
+}
+
+/// Read all stored config values for a tenant as (key, value) pairs.
+fn config_settings_spi(tenant_id: pgrx::Uuid) -> CaliberResult<Vec<(String, serde_json::Value)>> {
+    Spi::connect(|client| {
+        let table = client.select(
+            "SELECT key, value FROM caliber_config_setting WHERE tenant_id = $1",
+            None,
+            &[pgrx_uuid_datum(tenant_id)],
+        )?;
+        let mut settings = Vec::new();
+        for row in table {
+            if let (Some(key), Some(value)) = (row.get::<String>(1)?, row.get::<pgrx::JsonB>(2)?) {
+                settings.push((key, value.0));
+            }
+        }
+        Ok(settings)
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+fn config_set_checked(
+    key: &str,
+    value: &serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<()> {
+    let value = config_value_checked(key, value)?;
+
+    // The embedding dimension has its own table, which embedding writes validate against
+    if key == "embedding_dimension" {
+        let dimensions = value.as_i64().unwrap_or_default() as i32;
+        return if caliber_embedding_dimension_set(dimensions, tenant_id) {
+            Ok(())
+        } else {
+            Err(CaliberError::Storage(StorageError::SpiError {
+                reason: "failed to store embedding dimension".to_string(),
+            }))
+        };
+    }
+
+    Spi::connect_mut(|client| {
+        client.update(
+            "INSERT INTO caliber_config_setting (tenant_id, key, value)
+             VALUES ($1, $2, $3)
+             ON CONFLICT (tenant_id, key) DO UPDATE SET
+                 value = EXCLUDED.value,
+                 updated_at = NOW()",
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                text_datum(key),
+                jsonb_datum(&value),
+            ],
+        )?;
+        Ok(())
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Set a runtime config value for a tenant.
+///
+/// Valid keys are `token_budget`, `contradiction_threshold`,
+/// `stale_threshold` (seconds without a heartbeat before
+/// `caliber_agent_list_stale` reports an agent) and `embedding_dimension`.
+/// Values are validated per key before being stored.
+#[pg_extern]
+fn caliber_config_set(key: &str, value: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    match config_set_checked(key, &value.0, tenant_id) {
+        Ok(()) => true,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to set config '{}': {}", key, e);
+            false
+        }
+    }
+}
+
+/// Get the effective runtime config value for a tenant.
+///
+/// Returns the stored value, or the built-in default when unset. Returns
+/// NULL for unknown keys and for `embedding_dimension` when unset.
+#[pg_extern]
+fn caliber_config_get(key: &str, tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
+    let value = match key {
+        "embedding_dimension" => {
+            caliber_embedding_dimension_get(tenant_id).map(serde_json::Value::from)
+        }
+        "token_budget" | "contradiction_threshold" | "stale_threshold" => {
+            match ConfigSettings::load(id_from_pgrx::<TenantId>(tenant_id)) {
+                Ok(settings) => Some(match key {
+                    "token_budget" => serde_json::Value::from(settings.token_budget),
+                    // Go through Display so 0.8f32 reads back as 0.8, not 0.800000011920929
+                    "contradiction_threshold" => settings
+                        .contradiction_threshold
+                        .to_string()
+                        .parse::<f64>()
+                        .map(serde_json::Value::from)
+                        .unwrap_or_default(),
+                    _ => serde_json::Value::from(settings.stale_threshold.as_secs()),
+                }),
+                Err(e) => {
+                    pgrx::warning!("CALIBER: Failed to read config '{}': {}", key, e);
+                    None
+                }
+            }
+        }
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Unknown config key '{}'. Valid keys: {}",
+                key,
+                CONFIG_KEYS.join(", ")
+            );
+            None
+        }
+    };
+    value.map(pgrx::JsonB)
+}
+
+// ============================================================================
+// ENTITY ID GENERATION
+// ============================================================================
+
+/// Generate a new UUIDv7 entity ID.
+/// UUIDv7 is timestamp-sortable, making it ideal for time-ordered data.
+#[pg_extern]
+fn caliber_new_id() -> pgrx::Uuid {
+    let id = Uuid::now_v7();
+    pgrx::Uuid::from_bytes(*id.as_bytes())
+}
+
+/// Creation time embedded in a UUIDv7 entity ID, or NULL for other versions.
+#[pg_extern]
+fn caliber_id_timestamp(id: pgrx::Uuid) -> Option<TimestampWithTimeZone> {
+    // Every entity ID kind shares the same UUID layout
+    let ts = caliber_core::entity_id_timestamp(id_from_pgrx::<TrajectoryId>(id))?;
+    match tuple_extract::chrono_to_timestamp(ts) {
+        Ok(pg_ts) => Some(pg_ts),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert ID timestamp: {}", e);
+            None
+        }
+    }
+}
+
+// ============================================================================
+// AUDIT LOG
+// ============================================================================
+
+/// Append a mutation to `caliber_audit`.
+///
+/// Auditing is best effort: a failed insert is logged as a warning and does
+/// not undo the mutation it describes.
+fn audit_record<T: EntityIdType>(
+    entity_type: EntityType,
+    entity_id: T,
+    operation: &str,
+    agent_id: Option<AgentId>,
+    tenant_id: TenantId,
+) {
+    let entity_type = entity_type.to_string();
+    let result = Spi::connect_mut(|client| {
+        client.update(
+            "INSERT INTO caliber_audit (tenant_id, entity_type, entity_id, operation, agent_id)
+             VALUES ($1, $2, $3, $4, $5)",
+            None,
+            &[
+                id_datum(tenant_id),
+                text_datum(&entity_type),
+                id_datum(entity_id),
+                text_datum(operation),
+                opt_id_datum(agent_id),
+            ],
+        )?;
+        Ok::<(), pgrx::spi::SpiError>(())
+    });
+
+    if let Err(e) = result {
+        pgrx::warning!(
+            "CALIBER: Failed to audit {} on {} {}: {}",
+            operation,
+            entity_type,
+            entity_id,
+            e
+        );
+    }
+}
+
+/// Change history of an entity, oldest first.
+///
+/// Each entry has `entity_type`, `entity_id`, `operation`, the acting
+/// `agent_id` (null when the call did not identify one) and `at`.
+#[pg_extern]
+fn caliber_audit_query(entity_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let result = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT entity_type, entity_id, operation, agent_id, at
+             FROM caliber_audit
+             WHERE tenant_id = $1 AND entity_id = $2
+             ORDER BY audit_id",
+            None,
+            &[pgrx_uuid_datum(tenant_id), pgrx_uuid_datum(entity_id)],
+        )?;
+
+        let mut entries = Vec::new();
+        for row in table {
+            let at = row.get::<TimestampWithTimeZone>(5)?;
+            entries.push(serde_json::json!({
+                "entity_type": row.get::<String>(1)?,
+                "entity_id": row.get::<pgrx::Uuid>(2)?.map(|id| id.to_string()),
+                "operation": row.get::<String>(3)?,
+                "agent_id": row.get::<pgrx::Uuid>(4)?.map(|id| id.to_string()),
+                "at": at.map(|ts| tuple_extract::timestamp_to_chrono(ts).to_rfc3339()),
+            }));
+        }
+        Ok::<_, pgrx::spi::SpiError>(entries)
+    });
+
+    match result {
+        Ok(entries) => pgrx::JsonB(serde_json::json!(entries)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query audit log: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+// ============================================================================
+// TRAJECTORY OPERATIONS (Task 12.3)
+// ============================================================================
+
+/// Create a new trajectory.
+#[pg_extern]
+fn caliber_trajectory_create(
+    name: &str,
+    description: Option<&str>,
+    agent_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::Uuid {
+    // Record operation for metrics
+    storage_write().record_op("trajectory_create");
+
+    let trajectory_id = TrajectoryId::now_v7();
 
     // Convert pgrx::Uuid to AgentId if provided
     let agent_entity_id = opt_id_from_pgrx::<AgentId>(agent_id);
@@ -845,8 +1679,15 @@ fn caliber_trajectory_create(
         tenant_entity_id,
     );
 
-    if let Err(e) = result {
-        pgrx::warning!("CALIBER: Failed to insert trajectory: {}", e);
+    match result {
+        Ok(_) => audit_record(
+            EntityType::Trajectory,
+            trajectory_id,
+            "create",
+            agent_entity_id,
+            tenant_entity_id,
+        ),
+        Err(e) => pgrx::warning!("CALIBER: Failed to insert trajectory: {}", e),
     }
 
     pgrx_uuid_from_id(trajectory_id)
@@ -877,6 +1718,20 @@ caliber_pg_get!(trajectory, trajectory_heap, TrajectoryId, |row| {
     })
 });
 
+/// Create a new trajectory and return the full created row as JSON.
+/// Saves clients a second `caliber_trajectory_get` round trip to read
+/// server-assigned fields such as created_at/updated_at.
+#[pg_extern]
+fn caliber_trajectory_create_returning(
+    name: &str,
+    description: Option<&str>,
+    agent_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    let id = caliber_trajectory_create(name, description, agent_id, tenant_id);
+    caliber_trajectory_get(id, tenant_id)
+}
+
 /// Update trajectory status.
 /// Returns None if status is invalid.
 #[pg_extern]
@@ -888,6 +1743,26 @@ fn caliber_trajectory_set_status(
     let entity_id = id_from_pgrx::<TrajectoryId>(id);
     let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
 
+    match trajectory_set_status_checked(entity_id, status, tenant_entity_id) {
+        Ok(updated) => Some(updated),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update trajectory status: {}", e);
+            Some(false)
+        }
+    }
+}
+
+/// Validate a trajectory status and apply it using direct heap operations.
+/// Returns Ok(false) if the trajectory does not exist.
+fn trajectory_set_status_checked(
+    id: TrajectoryId,
+    status: &str,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
     // Validate status - reject unknown values instead of returning false silently (REQ-12)
     let trajectory_status = match status {
         "active" => TrajectoryStatus::Active,
@@ -895,84 +1770,347 @@ fn caliber_trajectory_set_status(
         "failed" => TrajectoryStatus::Failed,
         "suspended" => TrajectoryStatus::Suspended,
         _ => {
-            let validation_err = ValidationError::InvalidValue {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
                 field: "status".to_string(),
                 reason: format!(
                     "unknown value '{}'. Valid values: active, completed, failed, suspended",
                     status
                 ),
-            };
-            pgrx::warning!("CALIBER: {:?}", validation_err);
-            return None;
+            }));
         }
     };
 
     // Use direct heap operations instead of SPI
-    match trajectory_heap::trajectory_set_status_heap(
-        entity_id,
-        trajectory_status,
-        tenant_entity_id,
+    let updated = trajectory_heap::trajectory_set_status_heap(id, trajectory_status, tenant_id)?;
+    if updated {
+        audit_record(EntityType::Trajectory, id, "set_status", None, tenant_id);
+    }
+    Ok(updated)
+}
+
+/// Suspend an active trajectory, recording `suspend_reason` and
+/// `suspended_at` in its metadata.
+/// Returns false if the trajectory does not exist or cannot be suspended.
+#[pg_extern]
+fn caliber_trajectory_suspend(id: pgrx::Uuid, reason: &str, tenant_id: pgrx::Uuid) -> bool {
+    match trajectory_suspend_checked(
+        id_from_pgrx::<TrajectoryId>(id),
+        reason,
+        id_from_pgrx::<TenantId>(tenant_id),
     ) {
-        Ok(updated) => Some(updated),
+        Ok(updated) => updated,
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            false
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to update trajectory status: {}", e);
-            Some(false)
+            pgrx::warning!("CALIBER: Failed to suspend trajectory: {}", e);
+            false
         }
     }
 }
 
-/// Update a trajectory with the provided fields.
-/// Accepts a JSON object with optional fields: name, description, status,
-/// parent_trajectory_id, root_trajectory_id, agent_id, completed_at, outcome, metadata.
-/// Only provided fields are updated; null/missing fields are left unchanged.
-/// Returns true if the trajectory was found and updated, false otherwise.
+/// Resume a suspended trajectory, clearing the suspend metadata and
+/// recording `resumed_at`. Completed and failed trajectories cannot be resumed.
+/// Returns false if the trajectory does not exist or cannot be resumed.
 #[pg_extern]
-fn caliber_trajectory_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
-    let entity_id = id_from_pgrx::<TrajectoryId>(id);
-    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
-    let update_obj = &updates.0;
+fn caliber_trajectory_resume(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    match trajectory_resume_checked(
+        id_from_pgrx::<TrajectoryId>(id),
+        id_from_pgrx::<TenantId>(tenant_id),
+    ) {
+        Ok(updated) => updated,
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            false
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to resume trajectory: {}", e);
+            false
+        }
+    }
+}
 
-    // Parse updates from JSON
-    let name = update_obj.get("name").and_then(|v| v.as_str());
+/// Move a trajectory from Active to Suspended, storing the reason and time.
+/// Returns Ok(false) if the trajectory does not exist.
+fn trajectory_suspend_checked(
+    id: TrajectoryId,
+    reason: &str,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    // Validate reason - a suspension must say why (REQ-12)
+    if reason.trim().is_empty() {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "reason".to_string(),
+            reason: "must not be empty".to_string(),
+        }));
+    }
 
-    let description =
-        update_obj
-            .get("description")
-            .map(|v| if v.is_null() { None } else { v.as_str() });
+    let Some(row) = trajectory_heap::trajectory_get_heap(id, tenant_id)? else {
+        return Ok(false);
+    };
+    let trajectory = row.trajectory;
+    if trajectory.status != TrajectoryStatus::Active {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "status".to_string(),
+            reason: format!(
+                "only active trajectories can be suspended, trajectory is {}",
+                trajectory.status
+            ),
+        }));
+    }
 
-    let status = update_obj
-        .get("status")
-        .and_then(|v| v.as_str())
-        .and_then(|s| match s {
-            "active" => Some(TrajectoryStatus::Active),
-            "completed" => Some(TrajectoryStatus::Completed),
-            "failed" => Some(TrajectoryStatus::Failed),
-            "suspended" => Some(TrajectoryStatus::Suspended),
-            _ => {
-                pgrx::warning!("CALIBER: Invalid trajectory status: {}", s);
-                None
-            }
-        });
+    let mut metadata = match trajectory.metadata {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    metadata.insert("suspend_reason".to_string(), serde_json::json!(reason));
+    metadata.insert(
+        "suspended_at".to_string(),
+        serde_json::json!(Utc::now().to_rfc3339()),
+    );
+    let metadata = serde_json::Value::Object(metadata);
 
-    let parent_trajectory_id = update_obj.get("parent_trajectory_id").map(|v| {
-        if v.is_null() {
-            None
-        } else {
-            v.as_str()
-                .and_then(|s| Uuid::parse_str(s).ok())
-                .map(TrajectoryId::new)
-        }
-    });
+    let updated =
+        trajectory_heap::trajectory_update_heap(trajectory_heap::TrajectoryUpdateHeapParams {
+            id,
+            tenant_id,
+            name: None,
+            description: None,
+            status: Some(TrajectoryStatus::Suspended),
+            parent_trajectory_id: None,
+            root_trajectory_id: None,
+            agent_id: None,
+            outcome: None,
+            metadata: Some(Some(&metadata)),
+        })?;
+    if updated {
+        audit_record(EntityType::Trajectory, id, "suspend", None, tenant_id);
+    }
+    Ok(updated)
+}
 
-    let root_trajectory_id = update_obj.get("root_trajectory_id").map(|v| {
-        if v.is_null() {
-            None
-        } else {
-            v.as_str()
-                .and_then(|s| Uuid::parse_str(s).ok())
-                .map(TrajectoryId::new)
-        }
-    });
+/// Move a trajectory from Suspended back to Active.
+/// Returns Ok(false) if the trajectory does not exist.
+fn trajectory_resume_checked(id: TrajectoryId, tenant_id: TenantId) -> CaliberResult<bool> {
+    let Some(row) = trajectory_heap::trajectory_get_heap(id, tenant_id)? else {
+        return Ok(false);
+    };
+    let trajectory = row.trajectory;
+    if trajectory.status != TrajectoryStatus::Suspended {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "status".to_string(),
+            reason: format!(
+                "only suspended trajectories can be resumed, trajectory is {}",
+                trajectory.status
+            ),
+        }));
+    }
+
+    let mut metadata = match trajectory.metadata {
+        Some(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    metadata.remove("suspend_reason");
+    metadata.remove("suspended_at");
+    metadata.insert(
+        "resumed_at".to_string(),
+        serde_json::json!(Utc::now().to_rfc3339()),
+    );
+    let metadata = serde_json::Value::Object(metadata);
+
+    let updated =
+        trajectory_heap::trajectory_update_heap(trajectory_heap::TrajectoryUpdateHeapParams {
+            id,
+            tenant_id,
+            name: None,
+            description: None,
+            status: Some(TrajectoryStatus::Active),
+            parent_trajectory_id: None,
+            root_trajectory_id: None,
+            agent_id: None,
+            outcome: None,
+            metadata: Some(Some(&metadata)),
+        })?;
+    if updated {
+        audit_record(EntityType::Trajectory, id, "resume", None, tenant_id);
+    }
+    Ok(updated)
+}
+
+/// Finish a trajectory and record its outcome.
+///
+/// `outcome_status` is success, partial or failure. A failure outcome marks
+/// the trajectory Failed, anything else marks it Completed; `completed_at` is
+/// set either way. The outcome lists the trajectory's current artifacts and
+/// notes as produced. Returns false if the trajectory does not exist or has
+/// already finished.
+#[pg_extern]
+fn caliber_trajectory_complete(
+    id: pgrx::Uuid,
+    outcome_status: &str,
+    summary: &str,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    match trajectory_complete_checked(
+        id_from_pgrx::<TrajectoryId>(id),
+        outcome_status,
+        summary,
+        id_from_pgrx::<TenantId>(tenant_id),
+    ) {
+        Ok(updated) => updated,
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            false
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to complete trajectory: {}", e);
+            false
+        }
+    }
+}
+
+/// Set a trajectory's terminal status and outcome in one heap update.
+/// Returns Ok(false) if the trajectory does not exist.
+fn trajectory_complete_checked(
+    id: TrajectoryId,
+    outcome_status: &str,
+    summary: &str,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    // Validate outcome status - reject unknown values (REQ-12)
+    let status = outcome_status.parse::<OutcomeStatus>().map_err(|_| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "outcome_status".to_string(),
+            reason: format!(
+                "unknown value '{}'. Valid values: success, partial, failure",
+                outcome_status
+            ),
+        })
+    })?;
+
+    let Some(row) = trajectory_heap::trajectory_get_heap(id, tenant_id)? else {
+        return Ok(false);
+    };
+    if matches!(
+        row.trajectory.status,
+        TrajectoryStatus::Completed | TrajectoryStatus::Failed
+    ) {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "status".to_string(),
+            reason: format!("trajectory is already {}", row.trajectory.status),
+        }));
+    }
+
+    let produced_artifacts = artifact_heap::artifact_query_by_trajectory_heap(id, tenant_id)?
+        .into_iter()
+        .filter(|row| row.artifact.superseded_by.is_none())
+        .map(|row| row.artifact.artifact_id)
+        .collect();
+    let produced_notes = note_heap::note_query_by_trajectory_heap(id, tenant_id)?
+        .into_iter()
+        .filter(|row| row.note.superseded_by.is_none())
+        .map(|row| row.note.note_id)
+        .collect();
+    let outcome = TrajectoryOutcome {
+        status,
+        summary: summary.to_string(),
+        produced_artifacts,
+        produced_notes,
+        error: None,
+    };
+    let trajectory_status = if status == OutcomeStatus::Failure {
+        TrajectoryStatus::Failed
+    } else {
+        TrajectoryStatus::Completed
+    };
+
+    let updated =
+        trajectory_heap::trajectory_update_heap(trajectory_heap::TrajectoryUpdateHeapParams {
+            id,
+            tenant_id,
+            name: None,
+            description: None,
+            status: Some(trajectory_status),
+            parent_trajectory_id: None,
+            root_trajectory_id: None,
+            agent_id: None,
+            outcome: Some(Some(&outcome)),
+            metadata: None,
+        })?;
+    if updated {
+        audit_record(EntityType::Trajectory, id, "complete", None, tenant_id);
+    }
+    Ok(updated)
+}
+
+/// Update a trajectory with the provided fields.
+/// Accepts a JSON object with optional fields: name, description, status,
+/// parent_trajectory_id, root_trajectory_id, agent_id, completed_at, outcome, metadata.
+/// Only provided fields are updated; null/missing fields are left unchanged.
+/// Returns true if the trajectory was found and updated, false otherwise.
+#[pg_extern]
+fn caliber_trajectory_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<TrajectoryId>(id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+
+    match trajectory_update_checked(entity_id, &updates.0, tenant_entity_id) {
+        Ok(updated) => updated,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update trajectory: {}", e);
+            false
+        }
+    }
+}
+
+/// Parse a trajectory update object and apply it using direct heap operations.
+/// Returns Ok(false) if the trajectory does not exist.
+fn trajectory_update_checked(
+    id: TrajectoryId,
+    update_obj: &serde_json::Value,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    // Parse updates from JSON
+    let name = update_obj.get("name").and_then(|v| v.as_str());
+
+    let description =
+        update_obj
+            .get("description")
+            .map(|v| if v.is_null() { None } else { v.as_str() });
+
+    let status = update_obj
+        .get("status")
+        .and_then(|v| v.as_str())
+        .and_then(|s| match s {
+            "active" => Some(TrajectoryStatus::Active),
+            "completed" => Some(TrajectoryStatus::Completed),
+            "failed" => Some(TrajectoryStatus::Failed),
+            "suspended" => Some(TrajectoryStatus::Suspended),
+            _ => {
+                pgrx::warning!("CALIBER: Invalid trajectory status: {}", s);
+                None
+            }
+        });
+
+    let parent_trajectory_id = update_obj.get("parent_trajectory_id").map(|v| {
+        if v.is_null() {
+            None
+        } else {
+            v.as_str()
+                .and_then(|s| Uuid::parse_str(s).ok())
+                .map(TrajectoryId::new)
+        }
+    });
+
+    let root_trajectory_id = update_obj.get("root_trajectory_id").map(|v| {
+        if v.is_null() {
+            None
+        } else {
+            v.as_str()
+                .and_then(|s| Uuid::parse_str(s).ok())
+                .map(TrajectoryId::new)
+        }
+    });
 
     let agent_id = update_obj.get("agent_id").map(|v| {
         if v.is_null() {
@@ -1006,8 +2144,10 @@ fn caliber_trajectory_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pg
         && outcome.is_none()
         && metadata.is_none()
     {
-        pgrx::warning!("CALIBER: No valid fields to update in trajectory");
-        return false;
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "updates".to_string(),
+            reason: "no valid fields to update in trajectory".to_string(),
+        }));
     }
 
     // Use direct heap operations instead of SPI
@@ -1016,8 +2156,8 @@ fn caliber_trajectory_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pg
     let metadata_ref = metadata.as_ref().map(|m| m.as_ref());
 
     let params = trajectory_heap::TrajectoryUpdateHeapParams {
-        id: entity_id,
-        tenant_id: tenant_entity_id,
+        id,
+        tenant_id,
         name,
         description,
         status,
@@ -1028,10 +2168,270 @@ fn caliber_trajectory_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pg
         metadata: metadata_ref,
     };
 
-    match trajectory_heap::trajectory_update_heap(params) {
+    let updated = trajectory_heap::trajectory_update_heap(params)?;
+    if updated {
+        audit_record(EntityType::Trajectory, id, "update", None, tenant_id);
+    }
+    Ok(updated)
+}
+
+/// Shallow-merge a JSON object patch into an entity's metadata with a single
+/// UPDATE, so concurrent merges touching different keys don't clobber each
+/// other. Keys whose patch value is null are removed.
+///
+/// Returns Ok(None) if the entity does not exist.
+fn metadata_merge_spi(
+    table: &str,
+    id_column: &str,
+    id: pgrx::Uuid,
+    patch: &serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<Option<serde_json::Value>> {
+    if !patch.is_object() {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "patch".to_string(),
+            reason: "must be a JSON object".to_string(),
+        }));
+    }
+
+    let query = format!(
+        "UPDATE {table}
+         SET metadata = (COALESCE(metadata, '{{}}'::jsonb) || $1)
+                 - ARRAY(SELECT key FROM jsonb_each($1) WHERE value = 'null'::jsonb)
+         WHERE {id_column} = $2 AND tenant_id = $3
+         RETURNING metadata"
+    );
+
+    Spi::connect_mut(|client| {
+        let mut table = client.update(
+            &query,
+            None,
+            &[
+                jsonb_datum(patch),
+                pgrx_uuid_datum(id),
+                pgrx_uuid_datum(tenant_id),
+            ],
+        )?;
+        match table.next() {
+            Some(row) => Ok(Some(
+                row.get::<pgrx::JsonB>(1)?
+                    .map(|m| m.0)
+                    .unwrap_or_else(|| serde_json::json!({})),
+            )),
+            None => Ok(None),
+        }
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Merge a patch into a trajectory's metadata (JSONB `||`, null deletes a key).
+/// Returns the merged metadata, or None if the trajectory does not exist.
+#[pg_extern]
+fn caliber_trajectory_merge_metadata(
+    id: pgrx::Uuid,
+    patch: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    match metadata_merge_spi(
+        "caliber_trajectory",
+        "trajectory_id",
+        id,
+        &patch.0,
+        tenant_id,
+    ) {
+        Ok(merged) => merged.map(pgrx::JsonB),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to merge trajectory metadata: {}", e);
+            None
+        }
+    }
+}
+
+/// Parse a metadata path given as a JSON array of keys.
+/// Integer elements are accepted as array indexes.
+fn parse_metadata_path(path: &serde_json::Value) -> CaliberResult<Vec<String>> {
+    let invalid = |reason: &str| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "path".to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    let elements = path
+        .as_array()
+        .ok_or_else(|| invalid("must be a JSON array of keys"))?;
+    if elements.is_empty() {
+        return Err(invalid("must contain at least one key"));
+    }
+
+    elements
+        .iter()
+        .map(|e| match e {
+            serde_json::Value::String(key) => Ok(key.clone()),
+            serde_json::Value::Number(n) if n.is_i64() => Ok(n.to_string()),
+            _ => Err(invalid("keys must be strings or integer indexes")),
+        })
+        .collect()
+}
+
+/// Look up one path element in a JSON container, following `jsonb`
+/// semantics for array indexes (negative indexes count from the end).
+fn metadata_path_child<'a>(
+    node: &'a serde_json::Value,
+    key: &str,
+) -> Option<&'a serde_json::Value> {
+    match node {
+        serde_json::Value::Object(map) => map.get(key),
+        serde_json::Value::Array(items) => {
+            let index: i64 = key.parse().ok()?;
+            let index = if index < 0 {
+                items.len() as i64 + index
+            } else {
+                index
+            };
+            usize::try_from(index).ok().and_then(|i| items.get(i))
+        }
+        _ => None,
+    }
+}
+
+/// Set a nested metadata value with `jsonb_set`, creating missing
+/// intermediate objects along the path. The row is locked first, so the
+/// whole path update is applied atomically with respect to other writers.
+///
+/// Existing intermediate elements must be objects or arrays; a scalar in the
+/// middle of the path is rejected rather than overwritten.
+///
+/// Returns Ok(false) if the entity does not exist.
+fn metadata_set_path_spi(
+    table: &str,
+    id_column: &str,
+    id: pgrx::Uuid,
+    path: &[String],
+    value: &serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<bool> {
+    use pgrx::datum::DatumWithOid;
+
+    let lock_row = format!(
+        "SELECT COALESCE(metadata, '{{}}'::jsonb) FROM {table}
+         WHERE {id_column} = $1 AND tenant_id = $2
+         FOR UPDATE"
+    );
+    let ensure_container = format!(
+        "UPDATE {table}
+         SET metadata = jsonb_set(COALESCE(metadata, '{{}}'::jsonb), $1, '{{}}'::jsonb, true)
+         WHERE {id_column} = $2 AND tenant_id = $3
+           AND COALESCE(metadata, '{{}}'::jsonb) #> $1 IS NULL"
+    );
+    let set_value = format!(
+        "UPDATE {table}
+         SET metadata = jsonb_set(COALESCE(metadata, '{{}}'::jsonb), $1, $4, true)
+         WHERE {id_column} = $2 AND tenant_id = $3"
+    );
+
+    let result: Result<CaliberResult<bool>, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let mut locked = client.update(
+            &lock_row,
+            None,
+            &[pgrx_uuid_datum(id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        let metadata = match locked.next() {
+            Some(row) => row
+                .get::<pgrx::JsonB>(1)?
+                .map(|m| m.0)
+                .unwrap_or_else(|| serde_json::json!({})),
+            None => return Ok(Ok(false)),
+        };
+
+        // Walk the existing prefix of the path; everything past the first
+        // missing element is created below
+        let mut node = Some(&metadata);
+        for (depth, key) in path[..path.len() - 1].iter().enumerate() {
+            let Some(current) = node else { break };
+            node = metadata_path_child(current, key);
+            if let Some(child) = node {
+                if !(child.is_object() || child.is_array()) {
+                    return Ok(Err(CaliberError::Validation(
+                        ValidationError::InvalidValue {
+                            field: "path".to_string(),
+                            reason: format!(
+                                "element '{}' is a scalar and cannot hold nested keys",
+                                path[..=depth].join(".")
+                            ),
+                        },
+                    )));
+                }
+            }
+        }
+
+        for depth in 1..path.len() {
+            let prefix = path[..depth].to_vec();
+            client.update(
+                &ensure_container,
+                None,
+                &[
+                    unsafe { DatumWithOid::new(prefix, pgrx::pg_sys::TEXTARRAYOID) },
+                    pgrx_uuid_datum(id),
+                    pgrx_uuid_datum(tenant_id),
+                ],
+            )?;
+        }
+
+        let table = client.update(
+            &set_value,
+            None,
+            &[
+                unsafe { DatumWithOid::new(path.to_vec(), pgrx::pg_sys::TEXTARRAYOID) },
+                pgrx_uuid_datum(id),
+                pgrx_uuid_datum(tenant_id),
+                jsonb_datum(value),
+            ],
+        )?;
+        Ok(Ok(!table.is_empty()))
+    });
+
+    result.map_err(|e| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })?
+}
+
+/// Set a nested key in a trajectory's metadata without rewriting the document.
+/// `path` is a JSON array of keys, e.g. `["review", "status"]`; missing
+/// intermediate objects are created.
+/// Returns true if the trajectory was found and updated.
+#[pg_extern]
+fn caliber_trajectory_set_metadata_path(
+    id: pgrx::Uuid,
+    path: pgrx::JsonB,
+    value: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    let keys = match parse_metadata_path(&path.0) {
+        Ok(keys) => keys,
+        Err(e) => {
+            pgrx::warning!("CALIBER: {}", e);
+            return false;
+        }
+    };
+
+    match metadata_set_path_spi(
+        "caliber_trajectory",
+        "trajectory_id",
+        id,
+        &keys,
+        &value.0,
+        tenant_id,
+    ) {
         Ok(updated) => updated,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to update trajectory: {}", e);
+            pgrx::warning!("CALIBER: Failed to set trajectory metadata path: {}", e);
             false
         }
     }
@@ -1095,33 +2495,199 @@ fn caliber_trajectory_list_by_status(status: &str, tenant_id: pgrx::Uuid) -> pgr
     }
 }
 
-// ============================================================================
-// SCOPE OPERATIONS (Task 12.3)
-// ============================================================================
-
-/// Create a new scope within a trajectory.
+/// List trajectories created within a UTC window, oldest first.
+///
+/// `from_ms` is inclusive and `to_ms` exclusive, both epoch milliseconds.
+/// `status` optionally narrows the result like `caliber_trajectory_list_by_status`.
+/// The window is resolved against the `created_at` index rather than the
+/// UUIDv7 id range, since imported trajectories may carry foreign ids.
 #[pg_extern]
-fn caliber_scope_create(
-    trajectory_id: pgrx::Uuid,
-    name: &str,
-    purpose: Option<&str>,
-    token_budget: i32,
+fn caliber_trajectory_list_by_timerange(
+    from_ms: i64,
+    to_ms: i64,
+    status: Option<&str>,
     tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
-    let scope_id = ScopeId::now_v7();
-    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-
-    // Use direct heap operations instead of SPI
-    let result =
-        scope_heap::scope_create_heap(scope_id, traj_id, name, purpose, token_budget, tenant_uuid);
-
-    if let Err(e) = result {
-        pgrx::warning!("CALIBER: Failed to insert scope: {}", e);
+) -> pgrx::JsonB {
+    // Validate window and status - reject unknown values (REQ-12)
+    if from_ms > to_ms {
+        let validation_err = ValidationError::InvalidValue {
+            field: "from_ms".to_string(),
+            reason: format!("window start {} is after end {}", from_ms, to_ms),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return pgrx::JsonB(serde_json::json!([]));
+    }
+    if let Some(s) = status {
+        if !matches!(s, "active" | "completed" | "failed" | "suspended") {
+            let validation_err = ValidationError::InvalidValue {
+                field: "status".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: active, completed, failed, suspended",
+                    s
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
     }
 
-    pgrx_uuid_from_id(scope_id)
-}
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+    let ids: Result<Vec<TrajectoryId>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT trajectory_id
+                 FROM caliber_trajectory
+                 WHERE tenant_id = $1
+                   AND created_at >= to_timestamp($2 / 1000.0)
+                   AND created_at < to_timestamp($3 / 1000.0)
+                   AND ($4::text IS NULL OR status = $4)
+                 ORDER BY created_at, trajectory_id",
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                int8_datum(from_ms),
+                int8_datum(to_ms),
+                opt_text_datum(status),
+            ],
+        )?;
+
+        Ok(table
+            .filter_map(|row| row.get::<pgrx::Uuid>(1).ok().flatten())
+            .map(id_from_pgrx::<TrajectoryId>)
+            .collect())
+    });
+
+    let ids = match ids {
+        Ok(ids) => ids,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list trajectories: {}", e);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    let mut json_trajectories = Vec::with_capacity(ids.len());
+    for id in ids {
+        match trajectory_heap::trajectory_get_heap(id, tenant_entity_id) {
+            Ok(Some(row)) => {
+                let t = row.trajectory;
+                json_trajectories.push(serde_json::json!({
+                    "trajectory_id": t.trajectory_id.to_string(),
+                    "name": t.name,
+                    "description": t.description,
+                    "status": match t.status {
+                        TrajectoryStatus::Active => "active",
+                        TrajectoryStatus::Completed => "completed",
+                        TrajectoryStatus::Failed => "failed",
+                        TrajectoryStatus::Suspended => "suspended",
+                    },
+                    "parent_trajectory_id": t.parent_trajectory_id.map(|id| id.to_string()),
+                    "root_trajectory_id": t.root_trajectory_id.map(|id| id.to_string()),
+                    "agent_id": t.agent_id.map(|id| id.to_string()),
+                    "created_at": t.created_at.to_rfc3339(),
+                    "updated_at": t.updated_at.to_rfc3339(),
+                    "completed_at": t.completed_at.map(|dt| dt.to_rfc3339()),
+                    "outcome": t.outcome.as_ref().map(safe_to_json),
+                    "metadata": t.metadata,
+                    "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                }));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to load trajectory {}: {}", id, e);
+            }
+        }
+    }
+
+    pgrx::JsonB(serde_json::json!(json_trajectories))
+}
+
+/// Per-trajectory usage rollup for dashboards.
+///
+/// Returns `{scope_count, active_scopes, artifact_count, note_count,
+/// turn_count, total_tokens_used, total_token_budget}`. Notes are counted when
+/// the trajectory is one of their sources. Unlike `caliber_debug_stats` this
+/// is available without the debug feature.
+#[pg_extern]
+fn caliber_trajectory_stats(trajectory_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let result: Result<serde_json::Value, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let row = client
+            .select(
+                "SELECT
+                     (SELECT COUNT(*)
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2),
+                     (SELECT COUNT(*)
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2 AND is_active),
+                     (SELECT COUNT(*)
+                        FROM caliber_artifact
+                        WHERE trajectory_id = $1 AND tenant_id = $2 AND deleted_at IS NULL),
+                     (SELECT COUNT(*)
+                        FROM caliber_note
+                        WHERE tenant_id = $2 AND $1 = ANY(source_trajectory_ids)
+                          AND deleted_at IS NULL),
+                     (SELECT COUNT(*)
+                        FROM caliber_turn t
+                        JOIN caliber_scope s ON s.scope_id = t.scope_id
+                        WHERE s.trajectory_id = $1 AND s.tenant_id = $2),
+                     (SELECT COALESCE(SUM(tokens_used), 0)::bigint
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2),
+                     (SELECT COALESCE(SUM(token_budget), 0)::bigint
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2)",
+                None,
+                &[pgrx_uuid_datum(trajectory_id), pgrx_uuid_datum(tenant_id)],
+            )?
+            .first();
+
+        Ok(serde_json::json!({
+            "scope_count": row.get::<i64>(1)?.unwrap_or(0),
+            "active_scopes": row.get::<i64>(2)?.unwrap_or(0),
+            "artifact_count": row.get::<i64>(3)?.unwrap_or(0),
+            "note_count": row.get::<i64>(4)?.unwrap_or(0),
+            "turn_count": row.get::<i64>(5)?.unwrap_or(0),
+            "total_tokens_used": row.get::<i64>(6)?.unwrap_or(0),
+            "total_token_budget": row.get::<i64>(7)?.unwrap_or(0),
+        }))
+    });
+
+    match result {
+        Ok(stats) => pgrx::JsonB(stats),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to compute trajectory stats: {}", e);
+            pgrx::JsonB(serde_json::json!({}))
+        }
+    }
+}
+
+// ============================================================================
+// SCOPE OPERATIONS (Task 12.3)
+// ============================================================================
+
+/// Create a new scope within a trajectory.
+#[pg_extern]
+fn caliber_scope_create(
+    trajectory_id: pgrx::Uuid,
+    name: &str,
+    purpose: Option<&str>,
+    token_budget: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::Uuid {
+    let scope_id = ScopeId::now_v7();
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Use direct heap operations instead of SPI
+    let result =
+        scope_heap::scope_create_heap(scope_id, traj_id, name, purpose, token_budget, tenant_uuid);
+
+    match result {
+        Ok(_) => audit_record(EntityType::Scope, scope_id, "create", None, tenant_uuid),
+        Err(e) => pgrx::warning!("CALIBER: Failed to insert scope: {}", e),
+    }
+
+    pgrx_uuid_from_id(scope_id)
+}
 
 // Get a scope by ID.
 caliber_pg_get!(scope, scope_heap, ScopeId, |row| {
@@ -1143,6 +2709,286 @@ caliber_pg_get!(scope, scope_heap, ScopeId, |row| {
     })
 });
 
+/// Create a new scope and return the full created row as JSON.
+#[pg_extern]
+fn caliber_scope_create_returning(
+    trajectory_id: pgrx::Uuid,
+    name: &str,
+    purpose: Option<&str>,
+    token_budget: i32,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    let id = caliber_scope_create(trajectory_id, name, purpose, token_budget, tenant_id);
+    caliber_scope_get(id, tenant_id)
+}
+
+/// Start a task by creating a trajectory and its first scope atomically.
+///
+/// Both inserts run in the caller's transaction; a failure raises an ERROR so
+/// the trajectory is rolled back rather than left without a scope.
+/// Returns `{trajectory_id, scope_id}`.
+#[pg_extern]
+fn caliber_task_start(
+    name: &str,
+    description: Option<&str>,
+    agent_id: Option<pgrx::Uuid>,
+    scope_name: &str,
+    token_budget: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    // Record operation for metrics
+    storage_write().record_op("task_start");
+
+    if token_budget <= 0 {
+        let validation_err = ValidationError::InvalidValue {
+            field: "token_budget".to_string(),
+            reason: format!("must be positive, got {}", token_budget),
+        };
+        pgrx::error!("CALIBER: {:?}", validation_err);
+    }
+
+    let trajectory_id = TrajectoryId::now_v7();
+    let scope_id = ScopeId::now_v7();
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Use direct heap operations instead of SPI
+    if let Err(e) = trajectory_heap::trajectory_create_heap(
+        trajectory_id,
+        name,
+        description,
+        opt_id_from_pgrx::<AgentId>(agent_id),
+        tenant_uuid,
+    ) {
+        pgrx::error!("CALIBER: Failed to insert trajectory: {}", e);
+    }
+
+    if let Err(e) = scope_heap::scope_create_heap(
+        scope_id,
+        trajectory_id,
+        scope_name,
+        None,
+        token_budget,
+        tenant_uuid,
+    ) {
+        // Aborts the transaction, rolling back the trajectory insert
+        pgrx::error!("CALIBER: Failed to insert scope: {}", e);
+    }
+
+    let agent_entity_id = opt_id_from_pgrx::<AgentId>(agent_id);
+    audit_record(
+        EntityType::Trajectory,
+        trajectory_id,
+        "create",
+        agent_entity_id,
+        tenant_uuid,
+    );
+    audit_record(
+        EntityType::Scope,
+        scope_id,
+        "create",
+        agent_entity_id,
+        tenant_uuid,
+    );
+
+    pgrx::JsonB(serde_json::json!({
+        "trajectory_id": trajectory_id.to_string(),
+        "scope_id": scope_id.to_string(),
+    }))
+}
+
+/// Create a new scope, closing the trajectory's currently active scopes first.
+///
+/// A trajectory is expected to have a single active scope at a time (see
+/// `caliber_scope_get_current`). When `close_previous` is true, every active
+/// scope of the trajectory is closed before the new one is inserted, within
+/// the caller's transaction. When false this behaves like `caliber_scope_create`.
+/// Returns None if token_budget is not positive or a storage operation fails;
+/// after a storage failure some previous scopes may already be closed, so the
+/// caller should roll back.
+#[pg_extern]
+fn caliber_scope_create_exclusive(
+    trajectory_id: pgrx::Uuid,
+    name: &str,
+    purpose: Option<&str>,
+    token_budget: i32,
+    close_previous: bool,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Validate token_budget before closing anything (REQ-12)
+    if token_budget <= 0 {
+        let validation_err = ValidationError::InvalidValue {
+            field: "token_budget".to_string(),
+            reason: "must be positive".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return None;
+    }
+
+    if close_previous {
+        // Use direct heap operations instead of SPI
+        let active = match scope_heap::scope_list_by_trajectory_heap(traj_id, tenant_uuid) {
+            Ok(scopes) => scopes.into_iter().filter(|row| row.scope.is_active),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to list scopes: {}", e);
+                return None;
+            }
+        };
+
+        for row in active {
+            let closed_id = row.scope.scope_id;
+            if let Err(e) = scope_heap::scope_close_heap(closed_id, tenant_uuid) {
+                pgrx::warning!("CALIBER: Failed to close scope: {}", e);
+                return None;
+            }
+            audit_record(EntityType::Scope, closed_id, "close", None, tenant_uuid);
+        }
+    }
+
+    let scope_id = ScopeId::now_v7();
+    match scope_heap::scope_create_heap(scope_id, traj_id, name, purpose, token_budget, tenant_uuid)
+    {
+        Ok(_) => {
+            audit_record(EntityType::Scope, scope_id, "create", None, tenant_uuid);
+            Some(pgrx_uuid_from_id(scope_id))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert scope: {}", e);
+            None
+        }
+    }
+}
+
+/// Copy a scope into a new scope of the same trajectory.
+///
+/// Returns Ok(None) if the source scope does not exist. With `include_contents`
+/// the source's turns and its non-superseded artifacts are copied under fresh
+/// ids and `tokens_used` carries over; otherwise the clone starts empty.
+fn scope_clone_checked(
+    source_id: ScopeId,
+    new_name: &str,
+    include_contents: bool,
+    tenant_id: TenantId,
+) -> CaliberResult<Option<ScopeId>> {
+    let Some(source) = scope_heap::scope_get_heap(source_id, tenant_id)? else {
+        return Ok(None);
+    };
+    let source = source.scope;
+
+    let clone_id = ScopeId::now_v7();
+    scope_heap::scope_create_heap(
+        clone_id,
+        source.trajectory_id,
+        new_name,
+        source.purpose.as_deref(),
+        source.token_budget,
+        tenant_id,
+    )?;
+    if let Some(checkpoint) = &source.checkpoint {
+        scope_heap::scope_update_checkpoint_heap(clone_id, Some(checkpoint), tenant_id)?;
+    }
+    audit_record(EntityType::Scope, clone_id, "clone", None, tenant_id);
+
+    if !include_contents {
+        return Ok(Some(clone_id));
+    }
+
+    for row in turn_heap::turn_get_by_scope_heap(source_id, tenant_id)? {
+        let turn = row.turn;
+        let turn_id = turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
+            turn_id: TurnId::now_v7(),
+            scope_id: clone_id,
+            sequence: turn.sequence,
+            role: turn.role,
+            content: &turn.content,
+            token_count: turn.token_count,
+            tool_calls: turn.tool_calls.as_ref(),
+            tool_results: turn.tool_results.as_ref(),
+            tenant_id,
+        })?;
+        if let Some(metadata) = &turn.metadata {
+            turn_heap::turn_update_heap(turn_heap::TurnUpdateHeapParams {
+                turn_id,
+                tenant_id,
+                content: None,
+                token_count: None,
+                tool_calls: None,
+                tool_results: None,
+                metadata: Some(Some(metadata)),
+            })?;
+        }
+        audit_record(EntityType::Turn, turn_id, "create", None, tenant_id);
+    }
+
+    for row in artifact_heap::artifact_query_by_scope_heap(source_id, tenant_id)? {
+        let artifact = row.artifact;
+        if artifact.superseded_by.is_some() {
+            continue;
+        }
+        let artifact_id =
+            artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
+                artifact_id: ArtifactId::now_v7(),
+                trajectory_id: artifact.trajectory_id,
+                scope_id: clone_id,
+                artifact_type: artifact.artifact_type,
+                name: &artifact.name,
+                content: &artifact.content,
+                content_hash: artifact.content_hash,
+                embedding: artifact.embedding.as_ref(),
+                provenance: &artifact.provenance,
+                ttl: artifact.ttl,
+                tenant_id,
+            })?;
+        if let Some(metadata) = &artifact.metadata {
+            artifact_heap::artifact_update_heap(
+                artifact_id,
+                None,
+                None,
+                None,
+                None,
+                Some(Some(metadata)),
+                tenant_id,
+            )?;
+            metadata_bump_region_version(Some(metadata), tenant_id);
+        }
+        audit_record(EntityType::Artifact, artifact_id, "create", None, tenant_id);
+    }
+
+    scope_heap::scope_update_tokens_heap(clone_id, source.tokens_used, tenant_id)?;
+    Ok(Some(clone_id))
+}
+
+/// Branch a scope: create a new scope under the same trajectory with the
+/// source's purpose, token budget and checkpoint.
+///
+/// With `include_contents` the source's turns and (non-superseded) artifacts
+/// are copied too, under fresh ids, so the branch can diverge from the
+/// original. Returns the new scope id, or None if the source does not exist.
+/// A failure while copying raises an ERROR so no partial clone is left behind.
+#[pg_extern]
+fn caliber_scope_clone(
+    scope_id: pgrx::Uuid,
+    new_name: &str,
+    include_contents: bool,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    let source_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    match scope_clone_checked(source_id, new_name, include_contents, tenant_uuid) {
+        Ok(Some(clone_id)) => Some(pgrx_uuid_from_id(clone_id)),
+        Ok(None) => {
+            pgrx::warning!("CALIBER: Scope {} not found", source_id);
+            None
+        }
+        Err(e) => {
+            pgrx::error!("CALIBER: Failed to clone scope: {}", e);
+        }
+    }
+}
+
 /// Get the current active scope for a trajectory.
 #[pg_extern]
 fn caliber_scope_get_current(
@@ -1197,7 +3043,18 @@ fn caliber_scope_close(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
 
     // Use direct heap operations instead of SPI
     match scope_heap::scope_close_heap(entity_id, tenant_entity_id) {
-        Ok(updated) => updated,
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Scope,
+                    entity_id,
+                    "close",
+                    None,
+                    tenant_entity_id,
+                );
+            }
+            updated
+        }
         Err(e) => {
             pgrx::warning!("CALIBER: Failed to close scope: {}", e);
             false
@@ -1221,6 +3078,163 @@ fn caliber_scope_update_tokens(id: pgrx::Uuid, tokens_used: i32, tenant_id: pgrx
     }
 }
 
+/// Add tokens to a scope's usage and report its budget status.
+///
+/// Increments `tokens_used` by `delta` and returns
+/// `{tokens_used, token_budget, over_budget, remaining}`. When the increment
+/// crosses the budget and `notify_on_overflow` is set, a NOTIFY is sent on the
+/// `caliber_scope_budget` channel with the scope ID as payload.
+#[pg_extern]
+fn caliber_scope_add_tokens(
+    id: pgrx::Uuid,
+    delta: i32,
+    notify_on_overflow: bool,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    // Lock the row and increment in one statement so concurrent callers
+    // cannot lose each other's increments
+    let updated: Result<Option<(i32, i32, i32)>, pgrx::spi::SpiError> =
+        Spi::connect_mut(|client| {
+            let mut table = client.update(
+                "WITH previous AS (
+                     SELECT scope_id, tokens_used FROM caliber_scope
+                     WHERE scope_id = $2 AND tenant_id = $3
+                     FOR UPDATE
+                 )
+                 UPDATE caliber_scope s
+                 SET tokens_used = GREATEST(LEAST(s.tokens_used::bigint + $1, 2147483647), 0)::int
+                 FROM previous
+                 WHERE s.scope_id = previous.scope_id
+                 RETURNING previous.tokens_used, s.tokens_used, s.token_budget",
+                None,
+                &[
+                    int4_datum(delta),
+                    pgrx_uuid_datum(id),
+                    pgrx_uuid_datum(tenant_id),
+                ],
+            )?;
+            match table.next() {
+                Some(row) => match (row.get::<i32>(1)?, row.get::<i32>(2)?, row.get::<i32>(3)?) {
+                    (Some(previous), Some(used), Some(budget)) => {
+                        Ok(Some((previous, used, budget)))
+                    }
+                    _ => Ok(None),
+                },
+                None => Ok(None),
+            }
+        });
+
+    let (previous, tokens_used, token_budget) = match updated {
+        Ok(Some(values)) => values,
+        Ok(None) => return None,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update scope tokens: {}", e);
+            return None;
+        }
+    };
+
+    let over_budget = tokens_used > token_budget;
+    let crossed = over_budget && previous <= token_budget;
+
+    if crossed && notify_on_overflow {
+        let payload = Uuid::from_bytes(*id.as_bytes()).to_string();
+        let notify_result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+            client.update(
+                "SELECT pg_notify($1, $2)",
+                None,
+                &[text_datum("caliber_scope_budget"), text_datum(&payload)],
+            )?;
+            Ok::<_, pgrx::spi::SpiError>(())
+        });
+
+        if let Err(e) = notify_result {
+            pgrx::warning!("CALIBER: pg_notify failed: {}", e);
+        }
+    }
+
+    Some(pgrx::JsonB(serde_json::json!({
+        "tokens_used": tokens_used,
+        "token_budget": token_budget,
+        "over_budget": over_budget,
+        "remaining": token_budget.saturating_sub(tokens_used),
+    })))
+}
+
+/// Sum `token_count` over a scope's turns using direct heap operations.
+fn scope_turn_tokens(scope_id: ScopeId, tenant_id: TenantId) -> CaliberResult<i64> {
+    let turns = turn_heap::turn_get_by_scope_heap(scope_id, tenant_id)?;
+    Ok(turns
+        .iter()
+        .map(|row| i64::from(row.turn.token_count))
+        .sum())
+}
+
+/// Recompute a scope's `tokens_used` from its turns.
+///
+/// Writes the sum of the scope's turn `token_count`s to `tokens_used` and
+/// returns it, reconciling the counter maintained by `caliber_scope_update_tokens`
+/// and `caliber_scope_add_tokens`. Returns NULL if the scope does not exist.
+#[pg_extern]
+fn caliber_scope_recount_tokens(scope_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<i32> {
+    let entity_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+
+    let total = match scope_turn_tokens(entity_id, tenant_entity_id) {
+        Ok(total) => i32::try_from(total).unwrap_or(i32::MAX),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to count scope turn tokens: {}", e);
+            return None;
+        }
+    };
+
+    match scope_heap::scope_update_tokens_heap(entity_id, total, tenant_entity_id) {
+        Ok(true) => Some(total),
+        Ok(false) => None,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update scope tokens: {}", e);
+            None
+        }
+    }
+}
+
+/// Sum turn token counts over a trajectory's scopes, or None if the
+/// trajectory does not exist.
+fn trajectory_total_tokens_checked(
+    trajectory_id: TrajectoryId,
+    tenant_id: TenantId,
+) -> CaliberResult<Option<i64>> {
+    if trajectory_heap::trajectory_get_heap(trajectory_id, tenant_id)?.is_none() {
+        return Ok(None);
+    }
+    let mut total = 0i64;
+    for row in scope_heap::scope_list_by_trajectory_heap(trajectory_id, tenant_id)? {
+        total += scope_turn_tokens(row.scope.scope_id, tenant_id)?;
+    }
+    Ok(Some(total))
+}
+
+/// Sum turn `token_count`s across every scope of a trajectory.
+///
+/// Reads the turns themselves rather than each scope's `tokens_used`, so the
+/// result is unaffected by counter drift. Returns NULL if the trajectory does
+/// not exist.
+#[pg_extern]
+fn caliber_trajectory_total_tokens(
+    trajectory_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> Option<i64> {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+
+    match trajectory_total_tokens_checked(traj_id, tenant_entity_id) {
+        Ok(total) => total,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to count trajectory tokens: {}", e);
+            None
+        }
+    }
+}
+
 /// Update a scope with the provided fields.
 /// Accepts a JSON object with optional fields: name, purpose, is_active, closed_at,
 /// checkpoint, token_budget, tokens_used, parent_scope_id, metadata.
@@ -1228,12 +3242,25 @@ fn caliber_scope_update_tokens(id: pgrx::Uuid, tokens_used: i32, tenant_id: pgrx
 /// Returns true if the scope was found and updated, false otherwise.
 #[pg_extern]
 fn caliber_scope_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
-
-    let entity_id = Uuid::from_bytes(*id.as_bytes());
-    let update_obj = &updates.0;
-
+    match scope_update_checked(id, &updates.0, tenant_id) {
+        Ok(updated) => updated,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update scope: {}", e);
+            false
+        }
+    }
+}
+
+/// Parse a scope update object and apply it via SPI.
+/// Returns Ok(false) if the scope does not exist.
+fn scope_update_checked(
+    id: pgrx::Uuid,
+    update_obj: &serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<bool> {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
     // Build dynamic UPDATE query based on provided fields
     // We'll collect values and build params at the end
     let mut set_clauses: Vec<String> = Vec::new();
@@ -1263,18 +3290,14 @@ fn caliber_scope_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::U
                 {
                     Some(dt) => dt,
                     None => {
-                        pgrx::warning!("CALIBER: Failed to parse closed_at timestamp");
-                        return false;
+                        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                            field: "closed_at".to_string(),
+                            reason: "expected an RFC 3339 timestamp".to_string(),
+                        }));
                     }
                 };
 
-                match chrono_to_timestamp(parsed.with_timezone(&Utc)) {
-                    Ok(ts) => Some(Some(ts)),
-                    Err(e) => {
-                        pgrx::warning!("CALIBER: Failed to convert closed_at timestamp: {}", e);
-                        return false;
-                    }
-                }
+                Some(Some(chrono_to_timestamp(parsed.with_timezone(&Utc))?))
             }
         } else {
             None
@@ -1350,10 +3373,12 @@ fn caliber_scope_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::U
         param_idx += 1;
     }
 
-    // If no fields to update, return false
+    // If no fields to update, reject the request
     if set_clauses.is_empty() {
-        pgrx::warning!("CALIBER: No valid fields to update in scope");
-        return false;
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "updates".to_string(),
+            reason: "no valid fields to update in scope".to_string(),
+        }));
     }
 
     let query = format!(
@@ -1426,8 +3451,7 @@ fn caliber_scope_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::U
     }
 
     // Add the WHERE clause parameter (entity_id)
-    let pg_entity_id = pgrx::Uuid::from_bytes(*entity_id.as_bytes());
-    params.push(unsafe { DatumWithOid::new(pg_entity_id, pgrx::pg_sys::UUIDOID) });
+    params.push(unsafe { DatumWithOid::new(id, pgrx::pg_sys::UUIDOID) });
     params.push(unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) });
 
     let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
@@ -1435,11 +3459,36 @@ fn caliber_scope_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::U
         Ok::<_, pgrx::spi::SpiError>(table.len())
     });
 
-    match result {
-        Ok(len) => len > 0,
+    let updated = result.map(|len| len > 0).map_err(|e| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })?;
+    if updated {
+        audit_record(
+            EntityType::Scope,
+            id_from_pgrx::<ScopeId>(id),
+            "update",
+            None,
+            id_from_pgrx::<TenantId>(tenant_id),
+        );
+    }
+    Ok(updated)
+}
+
+/// Merge a patch into a scope's metadata (JSONB `||`, null deletes a key).
+/// Returns the merged metadata, or None if the scope does not exist.
+#[pg_extern]
+fn caliber_scope_merge_metadata(
+    id: pgrx::Uuid,
+    patch: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    match metadata_merge_spi("caliber_scope", "scope_id", id, &patch.0, tenant_id) {
+        Ok(merged) => merged.map(pgrx::JsonB),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to update scope: {}", e);
-            false
+            pgrx::warning!("CALIBER: Failed to merge scope metadata: {}", e);
+            None
         }
     }
 }
@@ -1463,6 +3512,182 @@ fn caliber_artifact_create(
     ttl: &str,
     tenant_id: pgrx::Uuid,
 ) -> Option<pgrx::Uuid> {
+    let result = artifact_create_checked(
+        id_from_pgrx::<TrajectoryId>(trajectory_id),
+        id_from_pgrx::<ScopeId>(scope_id),
+        artifact_type,
+        name,
+        content,
+        source_turn,
+        extraction_method,
+        confidence,
+        ttl,
+        None,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+
+    match result {
+        Ok(artifact_id) => Some(pgrx_uuid_from_id(artifact_id)),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert artifact: {}", e);
+            None
+        }
+    }
+}
+
+/// Create a new artifact with an embedding attached at creation time.
+///
+/// `embedding` is an optional JSON array of floats. TTL, provenance
+/// (source_turn, extraction_method, confidence) and artifact_type are
+/// validated as in `caliber_artifact_create`.
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_artifact_create_full(
+    trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
+    artifact_type: &str,
+    name: &str,
+    content: &str,
+    ttl: &str,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    embedding: Option<pgrx::JsonB>,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    let embedding = match embedding.map(|e| embedding_from_json_checked(e.0, tenant_id)) {
+        None => None,
+        Some(Ok(vector)) => Some(vector),
+        Some(Err(CaliberError::Validation(validation_err))) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return None;
+        }
+        Some(Err(e)) => {
+            pgrx::warning!("CALIBER: Failed to validate embedding: {}", e);
+            return None;
+        }
+    };
+
+    let result = artifact_create_checked(
+        id_from_pgrx::<TrajectoryId>(trajectory_id),
+        id_from_pgrx::<ScopeId>(scope_id),
+        artifact_type,
+        name,
+        content,
+        source_turn,
+        extraction_method,
+        confidence,
+        ttl,
+        embedding.as_ref(),
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+
+    match result {
+        Ok(artifact_id) => Some(pgrx_uuid_from_id(artifact_id)),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert artifact: {}", e);
+            None
+        }
+    }
+}
+
+/// Create several artifacts in a scope in one call.
+///
+/// `artifacts` is a JSON array of `{artifact_type, name, content, ttl?}`;
+/// `ttl` defaults to persistent and provenance is recorded as explicit with
+/// source turn 0. Each entry is validated and inserted independently, so the
+/// result array holds the created ID or null for each entry, in order. A
+/// non-array argument returns an empty array.
+#[pg_extern]
+fn caliber_artifact_create_batch(
+    trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
+    artifacts: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let Some(entries) = artifacts.0.as_array() else {
+        let validation_err = ValidationError::InvalidValue {
+            field: "artifacts".to_string(),
+            reason: "must be a JSON array".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return pgrx::JsonB(serde_json::json!([]));
+    };
+
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+
+    let ids: Vec<serde_json::Value> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| {
+            let field = |key: &str| entry.get(key).and_then(|v| v.as_str());
+            let (Some(artifact_type), Some(name), Some(content)) =
+                (field("artifact_type"), field("name"), field("content"))
+            else {
+                pgrx::warning!(
+                    "CALIBER: {:?}",
+                    ValidationError::InvalidValue {
+                        field: format!("artifacts[{}]", i),
+                        reason: "artifact_type, name and content are required strings".to_string(),
+                    }
+                );
+                return serde_json::Value::Null;
+            };
+
+            match artifact_create_checked(
+                traj_id,
+                scp_id,
+                artifact_type,
+                name,
+                content,
+                0,
+                "explicit",
+                None,
+                field("ttl").unwrap_or("persistent"),
+                None,
+                tenant,
+            ) {
+                Ok(artifact_id) => serde_json::json!(artifact_id.to_string()),
+                Err(CaliberError::Validation(validation_err)) => {
+                    pgrx::warning!("CALIBER: artifacts[{}]: {:?}", i, validation_err);
+                    serde_json::Value::Null
+                }
+                Err(e) => {
+                    pgrx::warning!("CALIBER: Failed to insert artifacts[{}]: {}", i, e);
+                    serde_json::Value::Null
+                }
+            }
+        })
+        .collect();
+
+    pgrx::JsonB(serde_json::json!(ids))
+}
+
+/// Validate artifact fields and insert the artifact using direct heap operations.
+#[allow(clippy::too_many_arguments)]
+fn artifact_create_checked(
+    trajectory_id: TrajectoryId,
+    scope_id: ScopeId,
+    artifact_type: &str,
+    name: &str,
+    content: &str,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    ttl: &str,
+    embedding: Option<&EmbeddingVector>,
+    tenant_id: TenantId,
+) -> CaliberResult<ArtifactId> {
     // Record operation for metrics
     storage_write().record_op("artifact_create");
 
@@ -1479,18 +3704,14 @@ fn caliber_artifact_create(
         "custom" => ArtifactType::Custom,
         "model" => ArtifactType::Model,
         _ => {
-            let validation_err = ValidationError::InvalidValue {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
                 field: "artifact_type".to_string(),
                 reason: format!("unknown value '{}'. Valid values: error_log, code_patch, design_decision, user_preference, fact, constraint, tool_result, intermediate_output, custom, model", artifact_type),
-            };
-            pgrx::warning!("CALIBER: {:?}", validation_err);
-            return None;
+            }));
         }
     };
 
     let artifact_id = ArtifactId::now_v7();
-    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
-    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
 
     // Compute content hash
     let content_hash = compute_content_hash(content.as_bytes());
@@ -1501,42 +3722,24 @@ fn caliber_artifact_create(
         "inferred" => ExtractionMethod::Inferred,
         "user_provided" => ExtractionMethod::UserProvided,
         _ => {
-            let validation_err = ValidationError::InvalidValue {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
                 field: "extraction_method".to_string(),
                 reason: format!(
                     "unknown value '{}'. Valid values: explicit, inferred, user_provided",
                     extraction_method
                 ),
-            };
-            pgrx::warning!("CALIBER: {:?}", validation_err);
-            return None;
+            }));
         }
     };
 
     // Parse TTL
-    let ttl_enum = match ttl {
-        "persistent" => TTL::Persistent,
-        "session" => TTL::Session,
-        "scope" => TTL::Scope,
-        "ephemeral" => TTL::Ephemeral,
-        "short_term" => TTL::ShortTerm,
-        "medium_term" => TTL::MediumTerm,
-        "long_term" => TTL::LongTerm,
-        "permanent" => TTL::Permanent,
-        _ => {
-            if let Some(ms) = ttl
-                .strip_prefix("duration:")
-                .and_then(|v| v.parse::<i64>().ok())
-            {
-                TTL::Duration(ms)
-            } else {
-                let validation_err = ValidationError::InvalidValue {
-                    field: "ttl".to_string(),
-                    reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
-                };
-                pgrx::warning!("CALIBER: {:?}", validation_err);
-                return None;
-            }
+    let ttl_enum = match ttl_from_str(ttl) {
+        Some(t) => t,
+        None => {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "ttl".to_string(),
+                reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, max:<n>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
+            }));
         }
     };
 
@@ -1548,33 +3751,25 @@ fn caliber_artifact_create(
     };
 
     // Use direct heap operations instead of SPI
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-
-    let result = artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
+    let artifact_id = artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
         artifact_id,
-        trajectory_id: traj_id,
-        scope_id: scp_id,
+        trajectory_id,
+        scope_id,
         artifact_type: artifact_type_enum,
         name,
         content,
         content_hash,
-        embedding: None, // No embedding
+        embedding,
         provenance: &provenance,
         ttl: ttl_enum,
-        tenant_id: tenant_uuid,
-    });
-
-    match result {
-        Ok(_) => Some(pgrx_uuid_from_id(artifact_id)),
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to insert artifact: {}", e);
-            None
-        }
-    }
+        tenant_id,
+    })?;
+    audit_record(EntityType::Artifact, artifact_id, "create", None, tenant_id);
+    Ok(artifact_id)
 }
 
 // Get an artifact by ID.
-caliber_pg_get!(artifact, artifact_heap, ArtifactId, |row| {
+caliber_pg_get!(artifact, artifact_heap, ArtifactId, tombstoned, |row| {
     let a = row.artifact;
     serde_json::json!({
         "artifact_id": a.artifact_id.to_string(),
@@ -1605,121 +3800,516 @@ caliber_pg_get!(artifact, artifact_heap, ArtifactId, |row| {
         "content_hash": hex::encode(a.content_hash),
         "embedding": a.embedding,
         "provenance": safe_to_json(&a.provenance),
-        "ttl": match a.ttl {
-            TTL::Persistent => "persistent",
-            TTL::Session => "session",
-            TTL::Scope => "scope",
-            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-            TTL::Ephemeral => "ephemeral",
-            TTL::ShortTerm => "short_term",
-            TTL::MediumTerm => "medium_term",
-            TTL::LongTerm => "long_term",
-            TTL::Permanent => "permanent",
-            TTL::Max(n) => format!("max:{}", n).leak(),
-        },
+        "ttl": ttl_to_str(&a.ttl),
         "created_at": a.created_at.to_rfc3339(),
         "updated_at": a.updated_at.to_rfc3339(),
         "superseded_by": a.superseded_by.map(|id| id.to_string()),
         "metadata": a.metadata,
         "tenant_id": row.tenant_id.map(|id| id.to_string()),
+        "deleted_at": row.deleted_at.map(|ts| ts.to_rfc3339()),
     })
 });
 
-/// Query artifacts by type within a trajectory.
+/// Create a new artifact and return the full created row as JSON.
+#[allow(clippy::too_many_arguments)]
 #[pg_extern]
-fn caliber_artifact_query_by_type(
+fn caliber_artifact_create_returning(
     trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
     artifact_type: &str,
+    name: &str,
+    content: &str,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    ttl: &str,
     tenant_id: pgrx::Uuid,
-) -> pgrx::JsonB {
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
-    // Validate and convert artifact_type
-    let artifact_type_enum = match artifact_type {
-        "code" => ArtifactType::Code,
-        "document" => ArtifactType::Document,
-        "data" => ArtifactType::Data,
-        "model" => ArtifactType::Model,
-        "config" => ArtifactType::Config,
-        "log" => ArtifactType::Log,
-        "summary" => ArtifactType::Summary,
-        "decision" => ArtifactType::Decision,
-        "plan" => ArtifactType::Plan,
-        _ => {
-            pgrx::warning!("CALIBER: Invalid artifact type: {}", artifact_type);
-            return pgrx::JsonB(serde_json::json!([]));
-        }
-    };
-
-    // Use direct heap operations instead of SPI
-    match artifact_heap::artifact_query_by_type_heap(artifact_type_enum, tenant_uuid) {
-        Ok(artifacts) => {
-            // Filter by trajectory_id and convert to JSON
-            let json_artifacts: Vec<serde_json::Value> = artifacts
-                .into_iter()
-                .filter(|row| row.artifact.trajectory_id == traj_id)
-                .map(|row| {
-                    let artifact = row.artifact;
-                    serde_json::json!({
-                        "artifact_id": artifact.artifact_id.to_string(),
-                        "trajectory_id": artifact.trajectory_id.to_string(),
-                        "scope_id": artifact.scope_id.to_string(),
-                        "artifact_type": match artifact.artifact_type {
-                            ArtifactType::Code => "code",
-                            ArtifactType::Document => "document",
-                            ArtifactType::Data => "data",
-                            ArtifactType::Model => "model",
-                            ArtifactType::Config => "config",
-                            ArtifactType::Log => "log",
-                            ArtifactType::Summary => "summary",
-                            ArtifactType::Decision => "decision",
-                            ArtifactType::Plan => "plan",
-                            ArtifactType::ErrorLog => "error_log",
-                            ArtifactType::CodePatch => "code_patch",
-                            ArtifactType::DesignDecision => "design_decision",
-                            ArtifactType::UserPreference => "user_preference",
-                            ArtifactType::Fact => "fact",
-                            ArtifactType::Constraint => "constraint",
-                            ArtifactType::ToolResult => "tool_result",
-                            ArtifactType::IntermediateOutput => "intermediate_output",
-                            ArtifactType::Custom => "custom",
-                        },
-                        "name": artifact.name,
-                        "content": artifact.content,
-                        "content_hash": hex::encode(artifact.content_hash),
-                        "embedding": artifact.embedding,
-                        "provenance": safe_to_json(&artifact.provenance),
-                        "ttl": match artifact.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
-                        "created_at": artifact.created_at.to_rfc3339(),
-                        "updated_at": artifact.updated_at.to_rfc3339(),
-                        "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
-                        "metadata": artifact.metadata,
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-                    })
-                })
-                .collect();
-
-            pgrx::JsonB(serde_json::json!(json_artifacts))
-        }
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to query artifacts by type: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
-        }
-    }
+) -> Option<pgrx::JsonB> {
+    let id = caliber_artifact_create(
+        trajectory_id,
+        scope_id,
+        artifact_type,
+        name,
+        content,
+        source_turn,
+        extraction_method,
+        confidence,
+        ttl,
+        tenant_id,
+    )?;
+    caliber_artifact_get(id, tenant_id, false)
 }
 
-/// Query artifacts by scope.
+/// Create a new artifact unless the scope already holds one with identical
+/// content. Returns the existing artifact's id when the content hash matches.
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_artifact_create_dedup(
+    trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
+    artifact_type: &str,
+    name: &str,
+    content: &str,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    ttl: &str,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let content_hash = compute_content_hash(content.as_bytes());
+
+    // Use direct heap operations instead of SPI
+    match artifact_heap::artifact_query_by_scope_heap(scp_id, tenant_uuid) {
+        Ok(rows) => {
+            if let Some(existing) = rows
+                .iter()
+                .find(|row| row.artifact.content_hash == content_hash)
+            {
+                return Some(pgrx_uuid_from_id(existing.artifact.artifact_id));
+            }
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to check for duplicate artifact: {}", e);
+            return None;
+        }
+    }
+
+    caliber_artifact_create(
+        trajectory_id,
+        scope_id,
+        artifact_type,
+        name,
+        content,
+        source_turn,
+        extraction_method,
+        confidence,
+        ttl,
+        tenant_id,
+    )
+}
+
+/// Hex-encoded SHA-256 of `content`, identical to the hash stored on
+/// artifacts and notes, so clients can dedup before calling create.
+#[pg_extern]
+fn caliber_content_hash(content: &str) -> String {
+    hex::encode(compute_content_hash(content.as_bytes()))
+}
+
+/// Find artifacts whose content matches a hex-encoded SHA-256 hash.
+/// Returns matching artifact, trajectory and scope ids across trajectories.
+#[pg_extern]
+fn caliber_artifact_find_by_hash(hash_hex: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    // Validate hash - must be exactly 32 bytes of hex (REQ-12)
+    let hash_hex = hash_hex.trim().to_ascii_lowercase();
+    let valid = hash_hex.len() == 64 && matches!(hex::decode(&hash_hex), Ok(b) if b.len() == 32);
+    if !valid {
+        let validation_err = ValidationError::InvalidValue {
+            field: "hash_hex".to_string(),
+            reason: format!(
+                "invalid value '{}'. Expected 64 hex characters (SHA-256)",
+                hash_hex
+            ),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return pgrx::JsonB(serde_json::json!([]));
+    }
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT artifact_id, trajectory_id, scope_id
+             FROM caliber_artifact
+             WHERE tenant_id = $1
+               AND content_hash = decode($2, 'hex')
+               AND deleted_at IS NULL
+             ORDER BY created_at",
+            None,
+            &[pgrx_uuid_datum(tenant_id), text_datum(&hash_hex)],
+        )?;
+
+        let mut matches = Vec::new();
+        for row in table {
+            let artifact_id: Option<pgrx::Uuid> = row.get(1)?;
+            let trajectory_id: Option<pgrx::Uuid> = row.get(2)?;
+            let scope_id: Option<pgrx::Uuid> = row.get(3)?;
+            if let Some(artifact_id) = artifact_id {
+                matches.push(serde_json::json!({
+                    "artifact_id": id_from_pgrx::<ArtifactId>(artifact_id).to_string(),
+                    "trajectory_id": opt_id_from_pgrx::<TrajectoryId>(trajectory_id).map(|id| id.to_string()),
+                    "scope_id": opt_id_from_pgrx::<ScopeId>(scope_id).map(|id| id.to_string()),
+                }));
+            }
+        }
+        Ok(matches)
+    });
+
+    match result {
+        Ok(matches) => pgrx::JsonB(serde_json::json!(matches)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to find artifacts by hash: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Update an artifact with the provided fields.
+/// Accepts a JSON object with optional fields: content, embedding, superseded_by, metadata.
+/// Changing content recomputes content_hash. A JSON null clears embedding,
+/// superseded_by or metadata; missing fields are left unchanged.
+/// Returns true if the artifact was found and updated, false otherwise.
+#[pg_extern]
+fn caliber_artifact_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<ArtifactId>(id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+
+    match artifact_update_checked(entity_id, &updates.0, tenant_entity_id) {
+        Ok(updated) => updated,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update artifact: {}", e);
+            false
+        }
+    }
+}
+
+/// Parse an artifact update object and apply it using direct heap operations.
+/// Returns Ok(false) if the artifact does not exist.
+fn artifact_update_checked(
+    id: ArtifactId,
+    update_obj: &serde_json::Value,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    // Parse updates from JSON
+    let content = update_obj.get("content").and_then(|v| v.as_str());
+    let content_hash = content.map(|c| compute_content_hash(c.as_bytes()));
+
+    let embedding: Option<Option<EmbeddingVector>> = match update_obj.get("embedding") {
+        None => None,
+        Some(v) if v.is_null() => Some(None),
+        Some(v) => Some(Some(embedding_from_json_checked(
+            v.clone(),
+            pgrx_uuid_from_id(tenant_id),
+        )?)),
+    };
+
+    let superseded_by = match update_obj.get("superseded_by") {
+        None => None,
+        Some(v) if v.is_null() => Some(None),
+        Some(v) => match v.as_str().and_then(|s| Uuid::parse_str(s).ok()) {
+            Some(uuid) => Some(Some(ArtifactId::new(uuid))),
+            None => {
+                return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                    field: "superseded_by".to_string(),
+                    reason: format!("expected an artifact UUID or null, got {}", v),
+                }))
+            }
+        },
+    };
+
+    let metadata = update_obj
+        .get("metadata")
+        .map(|v| if v.is_null() { None } else { Some(v.clone()) });
+
+    // Check if any fields are being updated
+    if content.is_none() && embedding.is_none() && superseded_by.is_none() && metadata.is_none() {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "updates".to_string(),
+            reason: "no valid fields to update in artifact".to_string(),
+        }));
+    }
+
+    if let Some(Some(new_id)) = superseded_by {
+        if artifact_supersede_chain_reaches(new_id, id, tenant_id)? {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "superseded_by".to_string(),
+                reason: format!("artifact {} would supersede itself through {}", id, new_id),
+            }));
+        }
+    }
+
+    // Use direct heap operations instead of SPI
+    // Convert Option<Option<T>> to Option<Option<&T>> for proper type matching
+    let embedding_ref = embedding.as_ref().map(|e| e.as_ref());
+    let metadata_ref = metadata.as_ref().map(|m| m.as_ref());
+
+    let updated = artifact_heap::artifact_update_heap(
+        id,
+        content,
+        content_hash,
+        embedding_ref,
+        superseded_by,
+        metadata_ref,
+        tenant_id,
+    )?;
+    if updated {
+        artifact_bump_region_version(id, tenant_id);
+        audit_record(EntityType::Artifact, id, "update", None, tenant_id);
+    }
+    Ok(updated)
+}
+
+/// Whether following `superseded_by` links from `start` reaches `target`.
+/// The walk stops at the end of the chain or at an already visited link.
+fn artifact_supersede_chain_reaches(
+    start: ArtifactId,
+    target: ArtifactId,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    let mut visited = HashSet::new();
+    let mut current = Some(start);
+    while let Some(id) = current {
+        if id == target {
+            return Ok(true);
+        }
+        if !visited.insert(id) {
+            return Ok(false);
+        }
+        current = artifact_heap::artifact_get_heap(id, tenant_id)?
+            .and_then(|row| row.artifact.superseded_by);
+    }
+    Ok(false)
+}
+
+/// Mark `old_id` as superseded by `new_id` and record a Supersedes edge
+/// (new artifact as source, old artifact as target).
+fn artifact_supersede_checked(
+    old_id: ArtifactId,
+    new_id: ArtifactId,
+    tenant_id: TenantId,
+) -> CaliberResult<EdgeId> {
+    if old_id == new_id {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "new_id".to_string(),
+            reason: "an artifact cannot supersede itself".to_string(),
+        }));
+    }
+
+    let not_found = |id: ArtifactId| {
+        CaliberError::Storage(StorageError::NotFound {
+            entity_type: EntityType::Artifact,
+            id: id.as_uuid(),
+        })
+    };
+    let old =
+        artifact_heap::artifact_get_heap(old_id, tenant_id)?.ok_or_else(|| not_found(old_id))?;
+    let new =
+        artifact_heap::artifact_get_heap(new_id, tenant_id)?.ok_or_else(|| not_found(new_id))?;
+    for (field, id, row) in [("old_id", old_id, &old), ("new_id", new_id, &new)] {
+        if row.deleted_at.is_some() {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: field.to_string(),
+                reason: format!("artifact {} is deleted", id),
+            }));
+        }
+    }
+    let (old, new) = (old.artifact, new.artifact);
+
+    if let Some(existing) = old.superseded_by {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "old_id".to_string(),
+            reason: format!("artifact {} is already superseded by {}", old_id, existing),
+        }));
+    }
+    // Only the head of a supersession chain can supersede, which also keeps
+    // chains acyclic
+    if let Some(existing) = new.superseded_by {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "new_id".to_string(),
+            reason: format!("artifact {} is already superseded by {}", new_id, existing),
+        }));
+    }
+    if old.trajectory_id != new.trajectory_id {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "new_id".to_string(),
+            reason: format!(
+                "artifacts belong to different trajectories ({} vs {})",
+                old.trajectory_id, new.trajectory_id
+            ),
+        }));
+    }
+
+    let edge = caliber_core::Edge {
+        edge_id: EdgeId::now_v7(),
+        edge_type: EdgeType::Supersedes,
+        participants: vec![
+            EdgeParticipant {
+                entity_ref: EntityRef {
+                    entity_type: EntityType::Artifact,
+                    id: new_id.as_uuid(),
+                },
+                role: Some("source".to_string()),
+            },
+            EdgeParticipant {
+                entity_ref: EntityRef {
+                    entity_type: EntityType::Artifact,
+                    id: old_id.as_uuid(),
+                },
+                role: Some("target".to_string()),
+            },
+        ],
+        weight: None,
+        trajectory_id: Some(old.trajectory_id),
+        provenance: Provenance {
+            source_turn: 0,
+            extraction_method: ExtractionMethod::Explicit,
+            confidence: None,
+        },
+        created_at: Utc::now(),
+        metadata: None,
+    };
+
+    if !artifact_heap::artifact_update_heap(
+        old_id,
+        None,
+        None,
+        None,
+        Some(Some(new_id)),
+        None,
+        tenant_id,
+    )? {
+        return Err(not_found(old_id));
+    }
+    artifact_bump_region_version(old_id, tenant_id);
+    audit_record(EntityType::Artifact, old_id, "supersede", None, tenant_id);
+
+    edge_heap::edge_create_heap(&edge, tenant_id)
+}
+
+/// Supersede an artifact with a newer version (e.g. code patch v2 over v1).
+///
+/// Sets `old.superseded_by = new` and creates a Supersedes edge. Both
+/// artifacts must exist in the same trajectory and neither may already be
+/// superseded; self-supersession is rejected.
+#[pg_extern]
+fn caliber_artifact_supersede(
+    old_id: pgrx::Uuid,
+    new_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    match artifact_supersede_checked(
+        id_from_pgrx::<ArtifactId>(old_id),
+        id_from_pgrx::<ArtifactId>(new_id),
+        id_from_pgrx::<TenantId>(tenant_id),
+    ) {
+        Ok(_) => true,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to supersede artifact: {}", e);
+            false
+        }
+    }
+}
+
+/// Soft delete an artifact by setting its `deleted_at` tombstone.
+///
+/// The row is kept so edges and supersession chains that reference it stay
+/// intact, but queries and `caliber_artifact_get` skip it unless asked to
+/// include deleted rows. Deleting an already deleted artifact keeps its
+/// original tombstone and is not audited again. Deleted artifacts can no
+/// longer be updated or superseded.
+#[pg_extern]
+fn caliber_artifact_soft_delete(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let artifact_id = id_from_pgrx::<ArtifactId>(id);
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+
+    match artifact_heap::artifact_soft_delete_heap(artifact_id, tenant) {
+        Ok(SoftDeleteOutcome::Deleted) => {
+            artifact_bump_region_version(artifact_id, tenant);
+            audit_record(EntityType::Artifact, artifact_id, "delete", None, tenant);
+            true
+        }
+        Ok(SoftDeleteOutcome::AlreadyDeleted) => true,
+        Ok(SoftDeleteOutcome::NotFound) => false,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to soft delete artifact: {}", e);
+            false
+        }
+    }
+}
+
+/// Query artifacts by type within a trajectory.
+#[pg_extern]
+fn caliber_artifact_query_by_type(
+    trajectory_id: pgrx::Uuid,
+    artifact_type: &str,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    // Validate and convert artifact_type
+    let artifact_type_enum = match artifact_type {
+        "code" => ArtifactType::Code,
+        "document" => ArtifactType::Document,
+        "data" => ArtifactType::Data,
+        "model" => ArtifactType::Model,
+        "config" => ArtifactType::Config,
+        "log" => ArtifactType::Log,
+        "summary" => ArtifactType::Summary,
+        "decision" => ArtifactType::Decision,
+        "plan" => ArtifactType::Plan,
+        _ => {
+            pgrx::warning!("CALIBER: Invalid artifact type: {}", artifact_type);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    // Use direct heap operations instead of SPI
+    match artifact_heap::artifact_query_by_type_heap(artifact_type_enum, tenant_uuid) {
+        Ok(artifacts) => {
+            // Filter by trajectory_id and convert to JSON
+            let json_artifacts: Vec<serde_json::Value> = artifacts
+                .into_iter()
+                .filter(|row| row.artifact.trajectory_id == traj_id)
+                .map(|row| {
+                    let artifact = row.artifact;
+                    serde_json::json!({
+                        "artifact_id": artifact.artifact_id.to_string(),
+                        "trajectory_id": artifact.trajectory_id.to_string(),
+                        "scope_id": artifact.scope_id.to_string(),
+                        "artifact_type": match artifact.artifact_type {
+                            ArtifactType::Code => "code",
+                            ArtifactType::Document => "document",
+                            ArtifactType::Data => "data",
+                            ArtifactType::Model => "model",
+                            ArtifactType::Config => "config",
+                            ArtifactType::Log => "log",
+                            ArtifactType::Summary => "summary",
+                            ArtifactType::Decision => "decision",
+                            ArtifactType::Plan => "plan",
+                            ArtifactType::ErrorLog => "error_log",
+                            ArtifactType::CodePatch => "code_patch",
+                            ArtifactType::DesignDecision => "design_decision",
+                            ArtifactType::UserPreference => "user_preference",
+                            ArtifactType::Fact => "fact",
+                            ArtifactType::Constraint => "constraint",
+                            ArtifactType::ToolResult => "tool_result",
+                            ArtifactType::IntermediateOutput => "intermediate_output",
+                            ArtifactType::Custom => "custom",
+                        },
+                        "name": artifact.name,
+                        "content": artifact.content,
+                        "content_hash": hex::encode(artifact.content_hash),
+                        "embedding": artifact.embedding,
+                        "provenance": safe_to_json(&artifact.provenance),
+                        "ttl": ttl_to_str(&artifact.ttl),
+                        "created_at": artifact.created_at.to_rfc3339(),
+                        "updated_at": artifact.updated_at.to_rfc3339(),
+                        "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
+                        "metadata": artifact.metadata,
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_artifacts))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query artifacts by type: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Query artifacts by scope.
 #[pg_extern]
 fn caliber_artifact_query_by_scope(scope_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
     let scp_id = id_from_pgrx::<ScopeId>(scope_id);
@@ -1762,18 +4352,7 @@ fn caliber_artifact_query_by_scope(scope_id: pgrx::Uuid, tenant_id: pgrx::Uuid)
                         "content_hash": hex::encode(artifact.content_hash),
                         "embedding": artifact.embedding,
                         "provenance": safe_to_json(&artifact.provenance),
-                        "ttl": match artifact.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&artifact.ttl),
                         "created_at": artifact.created_at.to_rfc3339(),
                         "updated_at": artifact.updated_at.to_rfc3339(),
                         "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
@@ -1836,18 +4415,7 @@ fn caliber_artifact_query_by_trajectory(
                         "content_hash": hex::encode(artifact.content_hash),
                         "embedding": artifact.embedding,
                         "provenance": safe_to_json(&artifact.provenance),
-                        "ttl": match artifact.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&artifact.ttl),
                         "created_at": artifact.created_at.to_rfc3339(),
                         "updated_at": artifact.updated_at.to_rfc3339(),
                         "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
@@ -1910,18 +4478,7 @@ fn caliber_artifact_query_by_scope_and_tenant(
                         "content_hash": hex::encode(artifact.content_hash),
                         "embedding": artifact.embedding,
                         "provenance": safe_to_json(&artifact.provenance),
-                        "ttl": match artifact.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&artifact.ttl),
                         "created_at": artifact.created_at.to_rfc3339(),
                         "updated_at": artifact.updated_at.to_rfc3339(),
                         "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
@@ -1984,18 +4541,7 @@ fn caliber_artifact_query_by_trajectory_and_tenant(
                         "content_hash": hex::encode(artifact.content_hash),
                         "embedding": artifact.embedding,
                         "provenance": safe_to_json(&artifact.provenance),
-                        "ttl": match artifact.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&artifact.ttl),
                         "created_at": artifact.created_at.to_rfc3339(),
                         "updated_at": artifact.updated_at.to_rfc3339(),
                         "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
@@ -2014,18 +4560,355 @@ fn caliber_artifact_query_by_trajectory_and_tenant(
     }
 }
 
-// ============================================================================
-// NOTE OPERATIONS (Task 12.3)
-// ============================================================================
-
-/// Create a new note.
+/// Query artifacts of a trajectory by provenance.
+///
+/// `method` filters on extraction method (explicit, inferred, user_provided);
+/// `min_confidence` keeps artifacts whose confidence is at least the floor,
+/// excluding artifacts with no recorded confidence. Both filters are optional.
 #[pg_extern]
-fn caliber_note_create(
-    note_type: &str,
-    title: &str,
-    content: &str,
-    source_trajectory_ids: Vec<pgrx::Uuid>,
-    source_artifact_ids: Vec<pgrx::Uuid>,
+fn caliber_artifacts_by_provenance(
+    trajectory_id: pgrx::Uuid,
+    method: Option<&str>,
+    min_confidence: Option<f32>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    // Validate method - reject unknown values (REQ-12)
+    let method_filter = match method {
+        None => None,
+        Some("explicit") => Some(ExtractionMethod::Explicit),
+        Some("inferred") => Some(ExtractionMethod::Inferred),
+        Some("user_provided") => Some(ExtractionMethod::UserProvided),
+        Some(other) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "method".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: explicit, inferred, user_provided",
+                    other
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    match artifact_heap::artifact_query_by_trajectory_heap(traj_id, tenant_uuid) {
+        Ok(artifacts) => {
+            let json_artifacts: Vec<serde_json::Value> = artifacts
+                .into_iter()
+                .filter(|row| {
+                    let provenance = &row.artifact.provenance;
+                    method_filter.is_none_or(|m| provenance.extraction_method == m)
+                        && min_confidence
+                            .is_none_or(|min| provenance.confidence.is_some_and(|c| c >= min))
+                })
+                .map(|row| {
+                    let artifact = row.artifact;
+                    serde_json::json!({
+                        "artifact_id": artifact.artifact_id.to_string(),
+                        "trajectory_id": artifact.trajectory_id.to_string(),
+                        "scope_id": artifact.scope_id.to_string(),
+                        "artifact_type": artifact_heap::artifact_type_to_str(artifact.artifact_type),
+                        "name": artifact.name,
+                        "content": artifact.content,
+                        "provenance": safe_to_json(&artifact.provenance),
+                        "ttl": ttl_to_str(&artifact.ttl),
+                        "created_at": artifact.created_at.to_rfc3339(),
+                        "updated_at": artifact.updated_at.to_rfc3339(),
+                        "superseded_by": artifact.superseded_by.map(|id| id.to_string()),
+                        "metadata": artifact.metadata,
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_artifacts))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query artifacts by provenance: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+// ============================================================================
+// RESULT ENVELOPE VARIANTS
+// ============================================================================
+
+/// Build the `{ok, error, error_kind, data}` envelope returned by the
+/// `*_result` functions.
+///
+/// The plain functions collapse every failure into false/None; the envelope
+/// keeps "not found", validation and storage failures apart so clients can
+/// decide whether a retry makes sense.
+fn result_envelope(result: CaliberResult<serde_json::Value>) -> pgrx::JsonB {
+    match result {
+        Ok(data) => pgrx::JsonB(serde_json::json!({
+            "ok": true,
+            "error": null,
+            "error_kind": null,
+            "data": data,
+        })),
+        Err(e) => {
+            let error_kind = match &e {
+                CaliberError::Storage(StorageError::NotFound { .. }) => "not_found",
+                CaliberError::Storage(StorageError::AlreadyExists { .. }) => "already_exists",
+                CaliberError::Storage(_) => "storage",
+                CaliberError::Validation(_) => "validation",
+                CaliberError::Llm(_) => "llm",
+                CaliberError::Config(_) => "config",
+                CaliberError::Vector(_) => "vector",
+                CaliberError::Agent(_) => "agent",
+            };
+            pgrx::JsonB(serde_json::json!({
+                "ok": false,
+                "error": e.to_string(),
+                "error_kind": error_kind,
+                "data": null,
+            }))
+        }
+    }
+}
+
+/// Envelope for an update that reports whether a row matched: `Ok(false)`
+/// becomes a `NotFound` error and success carries the entity's current row.
+fn update_envelope(
+    updated: CaliberResult<bool>,
+    entity_type: EntityType,
+    id: pgrx::Uuid,
+    current: impl FnOnce() -> Option<pgrx::JsonB>,
+) -> pgrx::JsonB {
+    let result = updated.and_then(|found| {
+        if found {
+            Ok(current().map(|row| row.0).unwrap_or_default())
+        } else {
+            Err(CaliberError::Storage(StorageError::NotFound {
+                entity_type,
+                id: Uuid::from_bytes(*id.as_bytes()),
+            }))
+        }
+    });
+    result_envelope(result)
+}
+
+/// Create a new trajectory, returning a result envelope with the created row.
+#[pg_extern]
+fn caliber_trajectory_create_result(
+    name: &str,
+    description: Option<&str>,
+    agent_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    storage_write().record_op("trajectory_create");
+
+    let trajectory_id = TrajectoryId::now_v7();
+    let agent_entity_id = opt_id_from_pgrx::<AgentId>(agent_id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+    let result = trajectory_heap::trajectory_create_heap(
+        trajectory_id,
+        name,
+        description,
+        agent_entity_id,
+        tenant_entity_id,
+    )
+    .map(|_| {
+        audit_record(
+            EntityType::Trajectory,
+            trajectory_id,
+            "create",
+            agent_entity_id,
+            tenant_entity_id,
+        );
+        caliber_trajectory_get(pgrx_uuid_from_id(trajectory_id), tenant_id)
+            .map(|row| row.0)
+            .unwrap_or_default()
+    });
+    result_envelope(result)
+}
+
+/// Update trajectory status, returning a result envelope with the updated row.
+#[pg_extern]
+fn caliber_trajectory_set_status_result(
+    id: pgrx::Uuid,
+    status: &str,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let updated = trajectory_set_status_checked(
+        id_from_pgrx::<TrajectoryId>(id),
+        status,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+    update_envelope(updated, EntityType::Trajectory, id, || {
+        caliber_trajectory_get(id, tenant_id)
+    })
+}
+
+/// Update a trajectory (see `caliber_trajectory_update`), returning a result
+/// envelope with the updated row.
+#[pg_extern]
+fn caliber_trajectory_update_result(
+    id: pgrx::Uuid,
+    updates: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let updated = trajectory_update_checked(
+        id_from_pgrx::<TrajectoryId>(id),
+        &updates.0,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+    update_envelope(updated, EntityType::Trajectory, id, || {
+        caliber_trajectory_get(id, tenant_id)
+    })
+}
+
+/// Create a new scope, returning a result envelope with the created row.
+#[pg_extern]
+fn caliber_scope_create_result(
+    trajectory_id: pgrx::Uuid,
+    name: &str,
+    purpose: Option<&str>,
+    token_budget: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let scope_id = ScopeId::now_v7();
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let result = scope_heap::scope_create_heap(
+        scope_id,
+        id_from_pgrx::<TrajectoryId>(trajectory_id),
+        name,
+        purpose,
+        token_budget,
+        tenant_uuid,
+    )
+    .map(|_| {
+        audit_record(EntityType::Scope, scope_id, "create", None, tenant_uuid);
+        caliber_scope_get(pgrx_uuid_from_id(scope_id), tenant_id)
+            .map(|row| row.0)
+            .unwrap_or_default()
+    });
+    result_envelope(result)
+}
+
+/// Close a scope, returning a result envelope with the closed row.
+#[pg_extern]
+fn caliber_scope_close_result(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let entity_id = id_from_pgrx::<ScopeId>(id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+    let updated = scope_heap::scope_close_heap(entity_id, tenant_entity_id);
+    if let Ok(true) = updated {
+        audit_record(
+            EntityType::Scope,
+            entity_id,
+            "close",
+            None,
+            tenant_entity_id,
+        );
+    }
+    update_envelope(updated, EntityType::Scope, id, || {
+        caliber_scope_get(id, tenant_id)
+    })
+}
+
+/// Update tokens used in a scope, returning a result envelope with the
+/// updated row.
+#[pg_extern]
+fn caliber_scope_update_tokens_result(
+    id: pgrx::Uuid,
+    tokens_used: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let updated = scope_heap::scope_update_tokens_heap(
+        id_from_pgrx::<ScopeId>(id),
+        tokens_used,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+    update_envelope(updated, EntityType::Scope, id, || {
+        caliber_scope_get(id, tenant_id)
+    })
+}
+
+/// Update a scope (see `caliber_scope_update`), returning a result envelope
+/// with the updated row.
+#[pg_extern]
+fn caliber_scope_update_result(
+    id: pgrx::Uuid,
+    updates: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let updated = scope_update_checked(id, &updates.0, tenant_id);
+    update_envelope(updated, EntityType::Scope, id, || {
+        caliber_scope_get(id, tenant_id)
+    })
+}
+
+/// Create a new artifact, returning a result envelope with the created row.
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_artifact_create_result(
+    trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
+    artifact_type: &str,
+    name: &str,
+    content: &str,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    ttl: &str,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let result = artifact_create_checked(
+        id_from_pgrx::<TrajectoryId>(trajectory_id),
+        id_from_pgrx::<ScopeId>(scope_id),
+        artifact_type,
+        name,
+        content,
+        source_turn,
+        extraction_method,
+        confidence,
+        ttl,
+        None,
+        id_from_pgrx::<TenantId>(tenant_id),
+    )
+    .map(|artifact_id| {
+        caliber_artifact_get(pgrx_uuid_from_id(artifact_id), tenant_id, false)
+            .map(|row| row.0)
+            .unwrap_or_default()
+    });
+    result_envelope(result)
+}
+
+/// Update an artifact (see `caliber_artifact_update`), returning a result
+/// envelope with the updated row.
+#[pg_extern]
+fn caliber_artifact_update_result(
+    id: pgrx::Uuid,
+    updates: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let updated = artifact_update_checked(
+        id_from_pgrx::<ArtifactId>(id),
+        &updates.0,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+    update_envelope(updated, EntityType::Artifact, id, || {
+        caliber_artifact_get(id, tenant_id, false)
+    })
+}
+
+// ============================================================================
+// NOTE OPERATIONS (Task 12.3)
+// ============================================================================
+
+/// Create a new note.
+#[pg_extern]
+fn caliber_note_create(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    source_trajectory_ids: Vec<pgrx::Uuid>,
+    source_artifact_ids: Vec<pgrx::Uuid>,
     ttl: &str,
     tenant_id: pgrx::Uuid,
 ) -> Option<pgrx::Uuid> {
@@ -2035,17 +4918,16 @@ fn caliber_note_create(
     let note_id = NoteId::now_v7();
 
     // Validate note_type - reject unknown values instead of defaulting (REQ-12)
-    let note_type_enum = match note_type {
-        "insight" => NoteType::Insight,
-        "procedure" => NoteType::Procedure,
-        "fact" => NoteType::Fact,
-        "preference" => NoteType::Preference,
-        "correction" => NoteType::Correction,
-        "summary" => NoteType::Summary,
-        _ => {
+    let note_type_enum = match note_heap::note_type_from_str(note_type) {
+        Some(t) => t,
+        None => {
             let validation_err = ValidationError::InvalidValue {
                 field: "note_type".to_string(),
-                reason: format!("unknown value '{}'. Valid values: insight, procedure, fact, preference, correction, summary", note_type),
+                reason: format!(
+                    "unknown value '{}'. Valid values: {}",
+                    note_type,
+                    note_heap::NOTE_TYPES.join(", ")
+                ),
             };
             pgrx::warning!("CALIBER: {:?}", validation_err);
             return None;
@@ -2055,29 +4937,15 @@ fn caliber_note_create(
     let content_hash = compute_content_hash(content.as_bytes());
 
     // Parse TTL
-    let ttl_enum = match ttl {
-        "persistent" => TTL::Persistent,
-        "session" => TTL::Session,
-        "scope" => TTL::Scope,
-        "ephemeral" => TTL::Ephemeral,
-        "short_term" => TTL::ShortTerm,
-        "medium_term" => TTL::MediumTerm,
-        "long_term" => TTL::LongTerm,
-        "permanent" => TTL::Permanent,
-        _ => {
-            if let Some(ms) = ttl
-                .strip_prefix("duration:")
-                .and_then(|v| v.parse::<i64>().ok())
-            {
-                TTL::Duration(ms)
-            } else {
-                let validation_err = ValidationError::InvalidValue {
-                    field: "ttl".to_string(),
-                    reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
-                };
-                pgrx::warning!("CALIBER: {:?}", validation_err);
-                return None;
-            }
+    let ttl_enum = match ttl_from_str(ttl) {
+        Some(t) => t,
+        None => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "ttl".to_string(),
+                reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, max:<n>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return None;
         }
     };
 
@@ -2109,7 +4977,10 @@ fn caliber_note_create(
     });
 
     match result {
-        Ok(_) => Some(pgrx_uuid_from_id(note_id)),
+        Ok(_) => {
+            audit_record(EntityType::Note, note_id, "create", None, tenant_uuid);
+            Some(pgrx_uuid_from_id(note_id))
+        }
         Err(e) => {
             pgrx::warning!("CALIBER: Failed to insert note: {}", e);
             None
@@ -2117,90 +4988,541 @@ fn caliber_note_create(
     }
 }
 
-// Get a note by ID.
-// Updates access_count and accessed_at timestamp on each read.
-caliber_pg_get!(note, note_heap, NoteId, |row| {
-    let n = row.note;
-    serde_json::json!({
-        "note_id": n.note_id.to_string(),
-        "note_type": match n.note_type {
-            NoteType::Convention => "convention",
-            NoteType::Strategy => "strategy",
-            NoteType::Gotcha => "gotcha",
-            NoteType::Fact => "fact",
-            NoteType::Preference => "preference",
-            NoteType::Relationship => "relationship",
-            NoteType::Procedure => "procedure",
-            NoteType::Meta => "meta",
-            NoteType::Insight => "insight",
-            NoteType::Correction => "correction",
-            NoteType::Summary => "summary",
-        },
-        "title": n.title,
-        "content": n.content,
-        "content_hash": hex::encode(n.content_hash),
-        "embedding": n.embedding,
-        "source_trajectory_ids": n.source_trajectory_ids.iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<_>>(),
-        "source_artifact_ids": n.source_artifact_ids.iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<_>>(),
-        "ttl": match n.ttl {
-            TTL::Persistent => "persistent",
-            TTL::Session => "session",
-            TTL::Scope => "scope",
-            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-            TTL::Ephemeral => "ephemeral",
-            TTL::ShortTerm => "short_term",
-            TTL::MediumTerm => "medium_term",
-            TTL::LongTerm => "long_term",
-            TTL::Permanent => "permanent",
-            TTL::Max(n) => format!("max:{}", n).leak(),
-        },
-        "created_at": n.created_at.to_rfc3339(),
-        "updated_at": n.updated_at.to_rfc3339(),
-        "accessed_at": n.accessed_at.to_rfc3339(),
-        "access_count": n.access_count,
-        "superseded_by": n.superseded_by.map(|id| id.to_string()),
-        "metadata": n.metadata,
-        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-    })
-});
-
-/// Query notes by trajectory.
-/// Updates access_count and accessed_at for all returned notes.
-#[pg_extern]
-fn caliber_note_query_by_trajectory(
-    trajectory_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
-) -> pgrx::JsonB {
-    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+/// Parse a JSON array of UUID strings into typed IDs.
+fn parse_id_array<T: EntityIdType>(field: &str, ids: serde_json::Value) -> CaliberResult<Vec<T>> {
+    if ids.is_null() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_value::<Vec<Uuid>>(ids)
+        .map(|ids| ids.into_iter().map(T::new).collect())
+        .map_err(|e| {
+            CaliberError::Validation(ValidationError::InvalidValue {
+                field: field.to_string(),
+                reason: format!("expected a JSON array of UUIDs: {}", e),
+            })
+        })
+}
 
-    // Use direct heap operations instead of SPI
-    match note_heap::note_query_by_trajectory_heap(traj_id, tenant_uuid) {
-        Ok(notes) => {
-            let json_notes: Vec<serde_json::Value> = notes
-                .into_iter()
-                .map(|row| {
-                    let note = row.note;
-                    serde_json::json!({
-                        "note_id": note.note_id.to_string(),
-                        "note_type": match note.note_type {
-                            NoteType::Convention => "convention",
-                            NoteType::Strategy => "strategy",
-                            NoteType::Gotcha => "gotcha",
-                            NoteType::Fact => "fact",
-                            NoteType::Preference => "preference",
-                            NoteType::Relationship => "relationship",
-                            NoteType::Procedure => "procedure",
-                            NoteType::Meta => "meta",
-                            NoteType::Insight => "insight",
-                            NoteType::Correction => "correction",
-                            NoteType::Summary => "summary",
-                        },
-                        "title": note.title,
+/// Validate every note field and insert the note.
+#[allow(clippy::too_many_arguments)]
+fn note_create_full_checked(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    ttl: &str,
+    abstraction_level: &str,
+    source_trajectory_ids: serde_json::Value,
+    source_artifact_ids: serde_json::Value,
+    source_note_ids: serde_json::Value,
+    tenant_id: TenantId,
+) -> CaliberResult<NoteId> {
+    // Validate enum strings - reject unknown values (REQ-12)
+    let note_type_enum = note_heap::note_type_from_str(note_type).ok_or_else(|| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "note_type".to_string(),
+            reason: format!(
+                "unknown value '{}'. Valid values: {}",
+                note_type,
+                note_heap::NOTE_TYPES.join(", ")
+            ),
+        })
+    })?;
+    let ttl_enum = ttl_from_str(ttl).ok_or_else(|| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "ttl".to_string(),
+            reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, max:<n>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
+        })
+    })?;
+    let abstraction_level_enum = match abstraction_level {
+        "raw" => AbstractionLevel::Raw,
+        "summary" => AbstractionLevel::Summary,
+        "principle" => AbstractionLevel::Principle,
+        _ => {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "abstraction_level".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: raw, summary, principle",
+                    abstraction_level
+                ),
+            }));
+        }
+    };
+
+    let source_traj_ids: Vec<TrajectoryId> =
+        parse_id_array("source_trajectory_ids", source_trajectory_ids)?;
+    let source_artifact_ids: Vec<ArtifactId> =
+        parse_id_array("source_artifact_ids", source_artifact_ids)?;
+    let source_note_ids: Vec<NoteId> = parse_id_array("source_note_ids", source_note_ids)?;
+
+    let note_id = note_heap::note_create_heap(note_heap::NoteCreateParams {
+        note_id: NoteId::now_v7(),
+        note_type: note_type_enum,
+        title,
+        content,
+        content_hash: compute_content_hash(content.as_bytes()),
+        embedding: None,
+        source_trajectory_ids: &source_traj_ids,
+        source_artifact_ids: &source_artifact_ids,
+        ttl: ttl_enum,
+        abstraction_level: abstraction_level_enum,
+        source_note_ids: &source_note_ids,
+        tenant_id,
+    })?;
+    audit_record(EntityType::Note, note_id, "create", None, tenant_id);
+    Ok(note_id)
+}
+
+/// Create a note with every field `note_create_heap` accepts, e.g. a
+/// summary-level note sourced from artifacts and other notes.
+///
+/// The source ID arguments are JSON arrays of UUID strings (null = empty).
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_note_create_full(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    ttl: &str,
+    abstraction_level: &str,
+    source_trajectory_ids: pgrx::JsonB,
+    source_artifact_ids: pgrx::JsonB,
+    source_note_ids: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    // Record operation for metrics
+    storage_write().record_op("note_create");
+
+    match note_create_full_checked(
+        note_type,
+        title,
+        content,
+        ttl,
+        abstraction_level,
+        source_trajectory_ids.0,
+        source_artifact_ids.0,
+        source_note_ids.0,
+        id_from_pgrx::<TenantId>(tenant_id),
+    ) {
+        Ok(note_id) => Some(pgrx_uuid_from_id(note_id)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to create note: {}", e);
+            None
+        }
+    }
+}
+
+/// Record that a note was used, for `caliber_note_list_hot`.
+///
+/// Increments `access_count` and sets `accessed_at` without touching
+/// `updated_at`. Reads never count implicitly, so getters stay read-only;
+/// callers record an access when a note is actually put to use. Returns false
+/// if the note does not exist for the tenant.
+#[pg_extern]
+fn caliber_note_record_access(note_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    match note_heap::note_record_access_heap(
+        id_from_pgrx::<NoteId>(note_id),
+        id_from_pgrx::<TenantId>(tenant_id),
+    ) {
+        Ok(recorded) => recorded,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to record note access: {}", e);
+            false
+        }
+    }
+}
+
+// Get a note by ID.
+caliber_pg_get!(note, note_heap, NoteId, tombstoned, |row| {
+    let n = row.note;
+    serde_json::json!({
+        "note_id": n.note_id.to_string(),
+        "note_type": note_heap::note_type_to_str(n.note_type),
+        "title": n.title,
+        "content": n.content,
+        "content_hash": hex::encode(n.content_hash),
+        "embedding": n.embedding,
+        "source_trajectory_ids": n.source_trajectory_ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>(),
+        "source_artifact_ids": n.source_artifact_ids.iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>(),
+        "ttl": ttl_to_str(&n.ttl),
+        "created_at": n.created_at.to_rfc3339(),
+        "updated_at": n.updated_at.to_rfc3339(),
+        "accessed_at": n.accessed_at.to_rfc3339(),
+        "access_count": n.access_count,
+        "superseded_by": n.superseded_by.map(|id| id.to_string()),
+        "metadata": n.metadata,
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+        "deleted_at": row.deleted_at.map(|ts| ts.to_rfc3339()),
+    })
+});
+
+/// Create a new note and return the full created row as JSON.
+#[pg_extern]
+fn caliber_note_create_returning(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    source_trajectory_ids: Vec<pgrx::Uuid>,
+    source_artifact_ids: Vec<pgrx::Uuid>,
+    ttl: &str,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    let id = caliber_note_create(
+        note_type,
+        title,
+        content,
+        source_trajectory_ids,
+        source_artifact_ids,
+        ttl,
+        tenant_id,
+    )?;
+    caliber_note_get(id, tenant_id, false)
+}
+
+/// Find the live note of a tenant closest to `embedding` by cosine similarity.
+fn nearest_note_spi(
+    embedding: &EmbeddingVector,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<Option<(NoteId, f64)>> {
+    let vector_str = embedding_to_vector_str(&embedding.data);
+    let dimensions = embedding.data.len();
+    Spi::connect(|client| {
+        let mut table = client.select(
+            &format!(
+                "SELECT note_id, (1 - (embedding::vector({dims}) <=> $1::vector))::float8
+                 FROM caliber_note
+                 WHERE tenant_id = $2 AND embedding IS NOT NULL AND superseded_by IS NULL
+                   AND deleted_at IS NULL AND vector_dims(embedding) = {dims}
+                 ORDER BY embedding::vector({dims}) <=> $1::vector
+                 LIMIT 1",
+                dims = dimensions
+            ),
+            Some(1),
+            &[text_datum(&vector_str), pgrx_uuid_datum(tenant_id)],
+        )?;
+        match table.next() {
+            Some(row) => {
+                let note_id: Option<pgrx::Uuid> = row.get(1)?;
+                let similarity: Option<f64> = row.get(2)?;
+                Ok(note_id
+                    .zip(similarity)
+                    .map(|(id, sim)| (id_from_pgrx::<NoteId>(id), sim)))
+            }
+            None => Ok(None),
+        }
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Insert a note with an embedding unless a semantically equivalent note exists.
+fn note_create_dedup_semantic_checked(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    embedding: serde_json::Value,
+    threshold: f32,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<serde_json::Value> {
+    // Dedup thresholds are cosine similarities in 0.0..=1.0 (REQ-12)
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "threshold".to_string(),
+            reason: format!("must be between 0.0 and 1.0, got {}", threshold),
+        }));
+    }
+    let vector = embedding_from_json_checked(embedding, tenant_id)?;
+
+    if let Some((existing_id, similarity)) = nearest_note_spi(&vector, tenant_id)? {
+        if similarity >= f64::from(threshold) {
+            return Ok(serde_json::json!({
+                "deduped": true,
+                "existing_id": existing_id.to_string(),
+                "similarity": similarity,
+            }));
+        }
+    }
+
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+    let note_id = note_create_full_checked(
+        note_type,
+        title,
+        content,
+        "persistent",
+        "raw",
+        serde_json::Value::Null,
+        serde_json::Value::Null,
+        serde_json::Value::Null,
+        tenant,
+    )?;
+    note_heap::note_update_heap(note_heap::NoteUpdateHeapParams {
+        id: note_id,
+        tenant_id: tenant,
+        title: None,
+        content: None,
+        content_hash: None,
+        embedding: Some(Some(&vector)),
+        ttl: None,
+        abstraction_level: None,
+        superseded_by: None,
+        metadata: None,
+    })?;
+
+    Ok(serde_json::json!({
+        "deduped": false,
+        "note_id": note_id.to_string(),
+    }))
+}
+
+/// Create a persistent note unless a near-duplicate already exists.
+///
+/// Before inserting, the tenant's live notes are searched by cosine similarity
+/// to `embedding`. If the closest one reaches `threshold` the note is not
+/// created and `{deduped: true, existing_id, similarity}` is returned;
+/// otherwise the note is stored with its embedding and
+/// `{deduped: false, note_id}` is returned. Returns None if validation fails.
+#[pg_extern]
+fn caliber_note_create_dedup_semantic(
+    note_type: &str,
+    title: &str,
+    content: &str,
+    embedding: pgrx::JsonB,
+    threshold: f32,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    storage_write().record_op("note_create");
+
+    match note_create_dedup_semantic_checked(
+        note_type,
+        title,
+        content,
+        embedding.0,
+        threshold,
+        tenant_id,
+    ) {
+        Ok(result) => Some(pgrx::JsonB(result)),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to create note: {}", e);
+            None
+        }
+    }
+}
+
+/// Update a note with the provided fields.
+/// Accepts a JSON object with optional fields: content, title, embedding, ttl,
+/// abstraction_level, superseded_by, metadata.
+/// Changing content recomputes content_hash. A JSON null clears embedding,
+/// superseded_by or metadata; missing fields are left unchanged.
+/// Returns true if the note was found and updated, false otherwise.
+#[pg_extern]
+fn caliber_note_update(id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<NoteId>(id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+    let update_obj = &updates.0;
+
+    // Parse updates from JSON
+    let title = update_obj.get("title").and_then(|v| v.as_str());
+    let content = update_obj.get("content").and_then(|v| v.as_str());
+    let content_hash = content.map(|c| compute_content_hash(c.as_bytes()));
+
+    let embedding: Option<Option<EmbeddingVector>> = match update_obj.get("embedding") {
+        None => None,
+        Some(v) if v.is_null() => Some(None),
+        Some(v) => match embedding_from_json_checked(v.clone(), tenant_id) {
+            Ok(emb) => Some(Some(emb)),
+            Err(CaliberError::Validation(validation_err)) => {
+                pgrx::warning!("CALIBER: {:?}", validation_err);
+                return false;
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Invalid note embedding: {}", e);
+                return false;
+            }
+        },
+    };
+
+    // Validate TTL - reject unknown values (REQ-12)
+    let ttl = match update_obj.get("ttl").and_then(|v| v.as_str()) {
+        None => None,
+        Some(ttl) => match ttl_from_str(ttl) {
+            Some(t) => Some(t),
+            None => {
+                let validation_err = ValidationError::InvalidValue {
+                    field: "ttl".to_string(),
+                    reason: format!("unknown value '{}'. Valid values: persistent, session, scope, duration:<ms>, max:<n>, ephemeral, short_term, medium_term, long_term, permanent", ttl),
+                };
+                pgrx::warning!("CALIBER: {:?}", validation_err);
+                return false;
+            }
+        },
+    };
+
+    // Validate abstraction_level - reject unknown values (REQ-12)
+    let abstraction_level = match update_obj.get("abstraction_level").and_then(|v| v.as_str()) {
+        None => None,
+        Some("raw") => Some(AbstractionLevel::Raw),
+        Some("summary") => Some(AbstractionLevel::Summary),
+        Some("principle") => Some(AbstractionLevel::Principle),
+        Some(other) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "abstraction_level".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: raw, summary, principle",
+                    other
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return false;
+        }
+    };
+
+    let superseded_by = match update_obj.get("superseded_by") {
+        None => None,
+        Some(v) if v.is_null() => Some(None),
+        Some(v) => match v.as_str().and_then(|s| Uuid::parse_str(s).ok()) {
+            Some(uuid) => Some(Some(NoteId::new(uuid))),
+            None => {
+                let validation_err = ValidationError::InvalidValue {
+                    field: "superseded_by".to_string(),
+                    reason: format!("expected a note UUID or null, got {}", v),
+                };
+                pgrx::warning!("CALIBER: {:?}", validation_err);
+                return false;
+            }
+        },
+    };
+
+    let metadata = update_obj
+        .get("metadata")
+        .map(|v| if v.is_null() { None } else { Some(v.clone()) });
+
+    // Check if any fields are being updated
+    if title.is_none()
+        && content.is_none()
+        && embedding.is_none()
+        && ttl.is_none()
+        && abstraction_level.is_none()
+        && superseded_by.is_none()
+        && metadata.is_none()
+    {
+        pgrx::warning!("CALIBER: No valid fields to update in note");
+        return false;
+    }
+
+    // A note can only be superseded by a live note of the same tenant
+    if let Some(Some(new_id)) = superseded_by {
+        let reason = match note_heap::note_get_heap(new_id, tenant_entity_id) {
+            Ok(Some(row)) if row.deleted_at.is_none() => None,
+            Ok(Some(_)) => Some(format!("note {} is deleted", new_id)),
+            Ok(None) => Some(format!("note {} not found", new_id)),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get superseding note: {}", e);
+                return false;
+            }
+        };
+        if let Some(reason) = reason {
+            let validation_err = ValidationError::InvalidValue {
+                field: "superseded_by".to_string(),
+                reason,
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return false;
+        }
+    }
+
+    // Use direct heap operations instead of SPI
+    // Convert Option<Option<T>> to Option<Option<&T>> for proper type matching
+    let embedding_ref = embedding.as_ref().map(|e| e.as_ref());
+    let metadata_ref = metadata.as_ref().map(|m| m.as_ref());
+
+    let params = note_heap::NoteUpdateHeapParams {
+        id: entity_id,
+        tenant_id: tenant_entity_id,
+        title,
+        content,
+        content_hash,
+        embedding: embedding_ref,
+        ttl,
+        abstraction_level,
+        superseded_by,
+        metadata: metadata_ref,
+    };
+
+    match note_heap::note_update_heap(params) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Note,
+                    entity_id,
+                    "update",
+                    None,
+                    tenant_entity_id,
+                );
+            }
+            updated
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update note: {}", e);
+            false
+        }
+    }
+}
+
+/// Soft delete a note by setting its `deleted_at` tombstone.
+///
+/// The row is kept so edges and derivation chains that reference it stay
+/// intact, but queries and `caliber_note_get` skip it unless asked to include
+/// deleted rows. Deleting an already deleted note keeps its original tombstone
+/// and is not audited again.
+#[pg_extern]
+fn caliber_note_soft_delete(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let note_id = id_from_pgrx::<NoteId>(id);
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+
+    match note_heap::note_soft_delete_heap(note_id, tenant) {
+        Ok(SoftDeleteOutcome::Deleted) => {
+            audit_record(EntityType::Note, note_id, "delete", None, tenant);
+            true
+        }
+        Ok(SoftDeleteOutcome::AlreadyDeleted) => true,
+        Ok(SoftDeleteOutcome::NotFound) => false,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to soft delete note: {}", e);
+            false
+        }
+    }
+}
+
+/// Query notes by trajectory.
+#[pg_extern]
+fn caliber_note_query_by_trajectory(
+    trajectory_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Use direct heap operations instead of SPI
+    match note_heap::note_query_by_trajectory_heap(traj_id, tenant_uuid) {
+        Ok(notes) => {
+            let json_notes: Vec<serde_json::Value> = notes
+                .into_iter()
+                .map(|row| {
+                    let note = row.note;
+                    serde_json::json!({
+                        "note_id": note.note_id.to_string(),
+                        "note_type": note_heap::note_type_to_str(note.note_type),
+                        "title": note.title,
                         "content": note.content,
                         "content_hash": hex::encode(note.content_hash),
                         "embedding": note.embedding,
@@ -2210,18 +5532,7 @@ fn caliber_note_query_by_trajectory(
                         "source_artifact_ids": note.source_artifact_ids.iter()
                             .map(|id| id.to_string())
                             .collect::<Vec<_>>(),
-                        "ttl": match note.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&note.ttl),
                         "created_at": note.created_at.to_rfc3339(),
                         "updated_at": note.updated_at.to_rfc3339(),
                         "accessed_at": note.accessed_at.to_rfc3339(),
@@ -2259,19 +5570,7 @@ fn caliber_note_query_by_trajectory_and_tenant(
                     let note = row.note;
                     serde_json::json!({
                         "note_id": note.note_id.to_string(),
-                        "note_type": match note.note_type {
-                            NoteType::Convention => "convention",
-                            NoteType::Strategy => "strategy",
-                            NoteType::Gotcha => "gotcha",
-                            NoteType::Fact => "fact",
-                            NoteType::Preference => "preference",
-                            NoteType::Relationship => "relationship",
-                            NoteType::Procedure => "procedure",
-                            NoteType::Meta => "meta",
-                            NoteType::Insight => "insight",
-                            NoteType::Correction => "correction",
-                            NoteType::Summary => "summary",
-                        },
+                        "note_type": note_heap::note_type_to_str(note.note_type),
                         "title": note.title,
                         "content": note.content,
                         "content_hash": hex::encode(note.content_hash),
@@ -2282,18 +5581,7 @@ fn caliber_note_query_by_trajectory_and_tenant(
                         "source_artifact_ids": note.source_artifact_ids.iter()
                             .map(|id| id.to_string())
                             .collect::<Vec<_>>(),
-                        "ttl": match note.ttl {
-                            TTL::Persistent => "persistent",
-                            TTL::Session => "session",
-                            TTL::Scope => "scope",
-                            TTL::Duration(ms) => format!("duration:{}", ms).leak(),
-                            TTL::Ephemeral => "ephemeral",
-                            TTL::ShortTerm => "short_term",
-                            TTL::MediumTerm => "medium_term",
-                            TTL::LongTerm => "long_term",
-                            TTL::Permanent => "permanent",
-                            TTL::Max(n) => format!("max:{}", n).leak(),
-                        },
+                        "ttl": ttl_to_str(&note.ttl),
                         "created_at": note.created_at.to_rfc3339(),
                         "updated_at": note.updated_at.to_rfc3339(),
                         "accessed_at": note.accessed_at.to_rfc3339(),
@@ -2314,17 +5602,83 @@ fn caliber_note_query_by_trajectory_and_tenant(
     }
 }
 
-/// List all notes for a tenant with pagination.
+/// Column list shared by SPI note queries; pairs with `note_json_from_spi_row`.
+const NOTE_SPI_COLUMNS: &str = "note_id, note_type, title, content, content_hash, embedding, source_trajectory_ids, source_artifact_ids, ttl,
+                    created_at, updated_at, accessed_at, access_count, superseded_by, metadata, abstraction_level, source_note_ids, tenant_id,
+                    memory_category";
+
+/// Build note JSON from an SPI row selected with `NOTE_SPI_COLUMNS`.
+fn note_json_from_spi_row(row: &pgrx::spi::SpiHeapTupleData<'_>) -> serde_json::Value {
+    let note_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+    let note_type: Option<String> = row.get(2).ok().flatten();
+    let title: Option<String> = row.get(3).ok().flatten();
+    let content: Option<String> = row.get(4).ok().flatten();
+    let content_hash: Option<Vec<u8>> = row.get(5).ok().flatten();
+    let embedding: Option<Vec<f32>> = row.get(6).ok().flatten();
+    let source_trajectory_ids: Option<Vec<pgrx::Uuid>> = row.get(7).ok().flatten();
+    let source_artifact_ids: Option<Vec<pgrx::Uuid>> = row.get(8).ok().flatten();
+    let ttl: Option<String> = row.get(9).ok().flatten();
+    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+    let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
+    let accessed_at: Option<TimestampWithTimeZone> = row.get(12).ok().flatten();
+    let access_count: Option<i32> = row.get(13).ok().flatten();
+    let superseded_by: Option<pgrx::Uuid> = row.get(14).ok().flatten();
+    let metadata: Option<pgrx::JsonB> = row.get(15).ok().flatten();
+    let abstraction_level: Option<String> = row.get(16).ok().flatten();
+    let source_note_ids: Option<Vec<pgrx::Uuid>> = row.get(17).ok().flatten();
+    let tenant_id_val: Option<pgrx::Uuid> = row.get(18).ok().flatten();
+    let memory_category: Option<String> = row.get(19).ok().flatten();
+
+    serde_json::json!({
+        "note_id": note_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "note_type": note_type,
+        "title": title,
+        "content": content,
+        "content_hash": content_hash.map(hex::encode),
+        "embedding": embedding.map(|data| EmbeddingVector::new(data, "unknown".to_string())),
+        "source_trajectory_ids": source_trajectory_ids
+            .unwrap_or_default()
+            .into_iter()
+            .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+            .collect::<Vec<_>>(),
+        "source_artifact_ids": source_artifact_ids
+            .unwrap_or_default()
+            .into_iter()
+            .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+            .collect::<Vec<_>>(),
+        "ttl": ttl,
+        "created_at": created_at.map(|t| t.to_string()),
+        "updated_at": updated_at.map(|t| t.to_string()),
+        "accessed_at": accessed_at.map(|t| t.to_string()),
+        "access_count": access_count,
+        "superseded_by": superseded_by.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "metadata": metadata.map(|m| m.0),
+        "abstraction_level": abstraction_level,
+        "source_note_ids": source_note_ids
+            .unwrap_or_default()
+            .into_iter()
+            .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+            .collect::<Vec<_>>(),
+        "tenant_id": tenant_id_val.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "memory_category": memory_category,
+    })
+}
+
+/// List all notes for a tenant with pagination.
 #[pg_extern]
 fn caliber_note_list_all_by_tenant(limit: i32, offset: i32, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let table = client.select(
-            "SELECT note_id, note_type, title, content, content_hash, embedding, source_trajectory_ids, source_artifact_ids, ttl,
-                    created_at, updated_at, accessed_at, access_count, superseded_by, metadata, abstraction_level, source_note_ids, tenant_id
+    let query = format!(
+        "SELECT {}
              FROM caliber_note
-             WHERE tenant_id = $1
+             WHERE tenant_id = $1 AND deleted_at IS NULL
              ORDER BY created_at DESC
              LIMIT $2 OFFSET $3",
+        NOTE_SPI_COLUMNS
+    );
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
             None,
             &[
                 pgrx_uuid_datum(tenant_id),
@@ -2333,2341 +5687,3047 @@ fn caliber_note_list_all_by_tenant(limit: i32, offset: i32, tenant_id: pgrx::Uui
             ],
         )?;
 
-        let mut notes = Vec::new();
-        for row in table {
-            let note_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let note_type: Option<String> = row.get(2).ok().flatten();
-            let title: Option<String> = row.get(3).ok().flatten();
-            let content: Option<String> = row.get(4).ok().flatten();
-            let content_hash: Option<Vec<u8>> = row.get(5).ok().flatten();
-            let embedding: Option<Vec<f32>> = row.get(6).ok().flatten();
-            let source_trajectory_ids: Option<Vec<pgrx::Uuid>> = row.get(7).ok().flatten();
-            let source_artifact_ids: Option<Vec<pgrx::Uuid>> = row.get(8).ok().flatten();
-            let ttl: Option<String> = row.get(9).ok().flatten();
-            let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-            let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
-            let accessed_at: Option<TimestampWithTimeZone> = row.get(12).ok().flatten();
-            let access_count: Option<i32> = row.get(13).ok().flatten();
-            let superseded_by: Option<pgrx::Uuid> = row.get(14).ok().flatten();
-            let metadata: Option<pgrx::JsonB> = row.get(15).ok().flatten();
-            let abstraction_level: Option<String> = row.get(16).ok().flatten();
-            let source_note_ids: Option<Vec<pgrx::Uuid>> = row.get(17).ok().flatten();
-            let tenant_id_val: Option<pgrx::Uuid> = row.get(18).ok().flatten();
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
+    });
 
-            let json = serde_json::json!({
-                "note_id": note_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "note_type": note_type,
-                "title": title,
-                "content": content,
-                "content_hash": content_hash.map(hex::encode),
-                "embedding": embedding.map(|data| EmbeddingVector::new(data, "unknown".to_string())),
-                "source_trajectory_ids": source_trajectory_ids
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
-                    .collect::<Vec<_>>(),
-                "source_artifact_ids": source_artifact_ids
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
-                    .collect::<Vec<_>>(),
-                "ttl": ttl,
-                "created_at": created_at.map(|t| t.to_string()),
-                "updated_at": updated_at.map(|t| t.to_string()),
-                "accessed_at": accessed_at.map(|t| t.to_string()),
-                "access_count": access_count,
-                "superseded_by": superseded_by.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "metadata": metadata.map(|m| m.0),
-                "abstraction_level": abstraction_level,
-                "source_note_ids": source_note_ids
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
-                    .collect::<Vec<_>>(),
-                "tenant_id": tenant_id_val.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-            });
-            notes.push(json);
+    match result {
+        Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list notes by tenant: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
+    }
+}
+
+/// List the most frequently accessed ("hot") notes.
+/// Ordered by access_count DESC, then accessed_at DESC, optionally restricted
+/// to notes sourced from the given trajectory. Accesses are counted by
+/// `caliber_note_record_access`.
+#[pg_extern]
+fn caliber_note_list_hot(
+    limit: i32,
+    trajectory_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let query = format!(
+        "SELECT {}
+             FROM caliber_note
+             WHERE tenant_id = $1
+               AND deleted_at IS NULL
+               AND ($2::uuid IS NULL OR $2 = ANY(source_trajectory_ids))
+             ORDER BY access_count DESC, accessed_at DESC
+             LIMIT $3",
+        NOTE_SPI_COLUMNS
+    );
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                opt_id_datum(opt_id_from_pgrx::<TrajectoryId>(trajectory_id)),
+                int4_datum(limit),
+            ],
+        )?;
 
-        Ok(notes)
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
     });
 
     match result {
         Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list notes by tenant: {}", e);
+            pgrx::warning!("CALIBER: Failed to list hot notes: {}", e);
             pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-// ============================================================================
-// TURN OPERATIONS (Task 12.3)
-// ============================================================================
-
-/// Create a new turn in a scope.
-/// Verifies scope_id exists before insert.
-/// Returns error on duplicate (scope_id, sequence) via UNIQUE constraint.
-/// Returns None if role is invalid.
+/// Query notes by memory category (ephemeral, working, episodic, semantic,
+/// procedural, meta), optionally restricted to a source trajectory.
 #[pg_extern]
-fn caliber_turn_create(
-    scope_id: pgrx::Uuid,
-    sequence: i32,
-    role: &str,
-    content: &str,
-    token_count: i32,
+fn caliber_note_query_by_category(
+    category: &str,
+    trajectory_id: Option<pgrx::Uuid>,
     tenant_id: pgrx::Uuid,
-) -> Option<pgrx::Uuid> {
-    let turn_id = TurnId::now_v7();
-    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
-
-    // Validate role - reject unknown values instead of defaulting (REQ-12)
-    let turn_role = match role {
-        "user" => TurnRole::User,
-        "assistant" => TurnRole::Assistant,
-        "system" => TurnRole::System,
-        "tool" => TurnRole::Tool,
-        _ => {
+) -> pgrx::JsonB {
+    // Validate category - reject unknown values (REQ-12)
+    let category_enum = match categorize_memory(category) {
+        Some(c) => c,
+        None => {
             let validation_err = ValidationError::InvalidValue {
-                field: "role".to_string(),
+                field: "category".to_string(),
                 reason: format!(
-                    "unknown value '{}'. Valid values: user, assistant, system, tool",
-                    role
+                    "unknown value '{}'. Valid values: ephemeral, working, episodic, semantic, procedural, meta",
+                    category
                 ),
             };
             pgrx::warning!("CALIBER: {:?}", validation_err);
-            return None;
+            return pgrx::JsonB(serde_json::json!([]));
         }
     };
 
-    // Use direct heap operations instead of SPI
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let query = format!(
+        "SELECT {}
+             FROM caliber_note
+             WHERE tenant_id = $1
+               AND deleted_at IS NULL
+               AND memory_category = $2
+               AND ($3::uuid IS NULL OR $3 = ANY(source_trajectory_ids))
+             ORDER BY created_at DESC",
+        NOTE_SPI_COLUMNS
+    );
 
-    let result = turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
-        turn_id,
-        scope_id: scp_id,
-        sequence,
-        role: turn_role,
-        content,
-        token_count,
-        tool_calls: None,
-        tool_results: None,
-        tenant_id: tenant_uuid,
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                text_datum(memory_category_to_str(category_enum)),
+                opt_id_datum(opt_id_from_pgrx::<TrajectoryId>(trajectory_id)),
+            ],
+        )?;
+
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
     });
 
     match result {
-        Ok(_) => Some(pgrx_uuid_from_id(turn_id)),
+        Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to insert turn: {}", e);
-            None
+            pgrx::warning!("CALIBER: Failed to query notes by category: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-/// Get turns by scope.
+/// Query notes by note type, optionally restricted to a source trajectory.
 #[pg_extern]
-fn caliber_turn_get_by_scope(scope_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+fn caliber_notes_by_type(
+    note_type: &str,
+    trajectory_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    // Validate note type - reject unknown values (REQ-12)
+    if note_heap::note_type_from_str(note_type).is_none() {
+        let validation_err = ValidationError::InvalidValue {
+            field: "note_type".to_string(),
+            reason: format!(
+                "unknown value '{}'. Valid values: {}",
+                note_type,
+                note_heap::NOTE_TYPES.join(", ")
+            ),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return pgrx::JsonB(serde_json::json!([]));
+    }
 
-    // Use direct heap operations instead of SPI
-    match turn_heap::turn_get_by_scope_heap(scp_id, tenant_uuid) {
-        Ok(turns) => {
-            let json_turns: Vec<serde_json::Value> = turns
-                .into_iter()
-                .map(|row| {
-                    let t = row.turn;
-                    serde_json::json!({
-                        "turn_id": t.turn_id.to_string(),
-                        "scope_id": t.scope_id.to_string(),
-                        "sequence": t.sequence,
-                        "role": match t.role {
-                            TurnRole::User => "user",
-                            TurnRole::Assistant => "assistant",
-                            TurnRole::System => "system",
-                            TurnRole::Tool => "tool",
-                        },
-                        "content": t.content,
-                        "token_count": t.token_count,
-                        "created_at": t.created_at.to_rfc3339(),
-                        "tool_calls": t.tool_calls,
-                        "tool_results": t.tool_results,
-                        "metadata": t.metadata,
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-                    })
-                })
-                .collect();
+    let query = format!(
+        "SELECT {}
+             FROM caliber_note
+             WHERE tenant_id = $1
+               AND deleted_at IS NULL
+               AND note_type = $2
+               AND ($3::uuid IS NULL OR $3 = ANY(source_trajectory_ids))
+             ORDER BY created_at DESC",
+        NOTE_SPI_COLUMNS
+    );
 
-            pgrx::JsonB(serde_json::json!(json_turns))
-        }
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                text_datum(note_type),
+                opt_id_datum(opt_id_from_pgrx::<TrajectoryId>(trajectory_id)),
+            ],
+        )?;
+
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
+    });
+
+    match result {
+        Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to get turns by scope: {}", e);
+            pgrx::warning!("CALIBER: Failed to query notes by type: {}", e);
             pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-// ============================================================================
-// ADVISORY LOCK FUNCTIONS (Task 12.4)
-// Using direct LockAcquire with LOCKTAG for zero SQL overhead.
-// ============================================================================
+/// List notes derived from a source note, i.e. its summary/principle-level
+/// derivatives that list it in `source_note_ids`.
+#[pg_extern]
+fn caliber_notes_by_source_note(source_note_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let query = format!(
+        "SELECT {}
+             FROM caliber_note
+             WHERE tenant_id = $1
+               AND deleted_at IS NULL
+               AND $2 = ANY(source_note_ids)
+             ORDER BY created_at",
+        NOTE_SPI_COLUMNS
+    );
 
-/// Lock level for advisory locks.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum AdvisoryLockLevel {
-    /// Session-level lock - persists until explicit release or session ends
-    Session,
-    /// Transaction-level lock - auto-releases at transaction commit/rollback
-    Transaction,
-}
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[pgrx_uuid_datum(tenant_id), pgrx_uuid_datum(source_note_id)],
+        )?;
 
-/// Build a LOCKTAG for advisory locks from a lock key (i64).
-/// Uses LOCKTAG_ADVISORY with the database ID and key split across fields.
-#[inline]
-fn make_advisory_locktag(lock_key: i64) -> pg_sys::LOCKTAG {
-    pg_sys::LOCKTAG {
-        locktag_field1: unsafe { pg_sys::MyDatabaseId.to_u32() },
-        locktag_field2: (lock_key >> 32) as u32,
-        locktag_field3: lock_key as u32,
-        locktag_field4: 0,
-        locktag_type: pg_sys::LockTagType::LOCKTAG_ADVISORY as u8,
-        locktag_lockmethodid: pg_sys::USER_LOCKMETHOD as u8,
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
+    });
+
+    match result {
+        Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query notes by source note: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
     }
 }
 
-/// Try to acquire an advisory lock using direct LockAcquire.
-/// Returns true if the lock was acquired, false if not available.
-#[inline]
-fn try_advisory_lock(lock_key: i64, exclusive: bool, session_lock: bool) -> bool {
-    let locktag = make_advisory_locktag(lock_key);
-    let lockmode = if exclusive {
-        pg_sys::ExclusiveLock as pg_sys::LOCKMODE
-    } else {
-        pg_sys::ShareLock as pg_sys::LOCKMODE
+/// List notes at an abstraction level: raw (L0), summary (L1) or principle (L2).
+#[pg_extern]
+fn caliber_notes_by_abstraction_level(level: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    // Validate level - reject unknown values (REQ-12)
+    let level_str = match level {
+        "raw" | "summary" | "principle" => level,
+        _ => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "level".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: raw, summary, principle",
+                    level
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
     };
 
-    // dontWait=true means return immediately if lock not available
-    let result = unsafe { pg_sys::LockAcquire(&locktag, lockmode, session_lock, true) };
+    let query = format!(
+        "SELECT {}
+             FROM caliber_note
+             WHERE tenant_id = $1
+               AND deleted_at IS NULL
+               AND abstraction_level = $2
+             ORDER BY created_at DESC",
+        NOTE_SPI_COLUMNS
+    );
 
-    result == pg_sys::LockAcquireResult::LOCKACQUIRE_OK as pg_sys::LockAcquireResult::Type
-        || result
-            == pg_sys::LockAcquireResult::LOCKACQUIRE_ALREADY_HELD
-                as pg_sys::LockAcquireResult::Type
-}
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[pgrx_uuid_datum(tenant_id), text_datum(level_str)],
+        )?;
 
-/// Release an advisory lock using direct LockRelease.
-/// Returns true if the lock was released.
-#[inline]
-fn release_advisory_lock(lock_key: i64, exclusive: bool, session_lock: bool) -> bool {
-    let locktag = make_advisory_locktag(lock_key);
-    let lockmode = if exclusive {
-        pg_sys::ExclusiveLock as pg_sys::LOCKMODE
-    } else {
-        pg_sys::ShareLock as pg_sys::LOCKMODE
-    };
+        Ok(table.map(|row| note_json_from_spi_row(&row)).collect())
+    });
 
-    unsafe { pg_sys::LockRelease(&locktag, lockmode, session_lock) }
+    match result {
+        Ok(notes) => pgrx::JsonB(serde_json::json!(notes)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query notes by abstraction level: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
 }
 
-/// Acquire an advisory lock on a resource.
-/// Uses Postgres advisory locks for distributed coordination.
-/// Stores lock record in SQL table for cross-session visibility.
-///
-/// Parameters:
-/// - agent_id: The agent acquiring the lock
-/// - resource_type: Type of resource being locked
-/// - resource_id: ID of the resource being locked
-/// - timeout_ms: Lock expiration timeout in milliseconds
-/// - mode: "exclusive" or "shared"
-/// - level: "session" or "transaction" (defaults to "transaction")
+// ============================================================================
+// TURN OPERATIONS (Task 12.3)
+// ============================================================================
+
+/// Create a new turn in a scope.
+/// Verifies scope_id exists before insert.
+/// Returns None on duplicate (scope_id, sequence) or if role is invalid; use
+/// `caliber_turn_create_result` to tell the two apart.
 #[pg_extern]
-fn caliber_lock_acquire(
-    agent_id: pgrx::Uuid,
-    resource_type: &str,
-    resource_id: pgrx::Uuid,
-    timeout_ms: i64,
-    mode: &str,
-    level: Option<&str>,
+fn caliber_turn_create(
+    scope_id: pgrx::Uuid,
+    sequence: i32,
+    role: &str,
+    content: &str,
+    token_count: i32,
     tenant_id: pgrx::Uuid,
 ) -> Option<pgrx::Uuid> {
-    let agent = id_from_pgrx::<AgentId>(agent_id);
-    let resource = Uuid::from_bytes(*resource_id.as_bytes());
-    let lock_key = compute_lock_key(resource_type, resource);
+    let result = turn_create_checked(
+        id_from_pgrx::<ScopeId>(scope_id),
+        sequence,
+        role,
+        content,
+        token_count,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
 
-    let lock_mode = match mode {
-        "shared" => LockMode::Shared,
-        _ => {
-            if mode != "exclusive" {
-                pgrx::warning!(
-                    "CALIBER: Unknown lock mode '{}', defaulting to Exclusive",
-                    mode
-                );
-            }
-            LockMode::Exclusive
+    match result {
+        Ok(turn_id) => Some(pgrx_uuid_from_id(turn_id)),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(CaliberError::Storage(StorageError::AlreadyExists { id, .. })) => {
+            pgrx::warning!(
+                "CALIBER: Turn {} already exists at sequence {} in scope {}",
+                id,
+                sequence,
+                scope_id
+            );
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert turn: {}", e);
+            None
         }
+    }
+}
+
+/// Create a new turn, returning a result envelope.
+///
+/// A duplicate (scope_id, sequence) reports `error_kind: "already_exists"`
+/// with the existing turn's ID in `existing_turn_id`, so clients replaying
+/// turns can skip it instead of treating it as a failure.
+#[pg_extern]
+fn caliber_turn_create_result(
+    scope_id: pgrx::Uuid,
+    sequence: i32,
+    role: &str,
+    content: &str,
+    token_count: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let result = turn_create_checked(
+        id_from_pgrx::<ScopeId>(scope_id),
+        sequence,
+        role,
+        content,
+        token_count,
+        id_from_pgrx::<TenantId>(tenant_id),
+    );
+
+    let existing = match &result {
+        Err(CaliberError::Storage(StorageError::AlreadyExists { id, .. })) => Some(*id),
+        _ => None,
     };
+    let mut envelope = result_envelope(result.map(|turn_id| {
+        serde_json::json!({
+            "turn_id": turn_id.to_string(),
+            "scope_id": id_from_pgrx::<ScopeId>(scope_id).to_string(),
+            "sequence": sequence,
+        })
+    }));
+    if let (Some(id), Some(obj)) = (existing, envelope.0.as_object_mut()) {
+        obj.insert(
+            "existing_turn_id".to_string(),
+            serde_json::json!(id.to_string()),
+        );
+    }
+    envelope
+}
 
-    let lock_level = match level.unwrap_or("transaction") {
-        "session" => AdvisoryLockLevel::Session,
+/// Validate the turn role and insert the turn using direct heap operations.
+fn turn_create_checked(
+    scope_id: ScopeId,
+    sequence: i32,
+    role: &str,
+    content: &str,
+    token_count: i32,
+    tenant_id: TenantId,
+) -> CaliberResult<TurnId> {
+    // Validate role - reject unknown values instead of defaulting (REQ-12)
+    let turn_role = match role {
+        "user" => TurnRole::User,
+        "assistant" => TurnRole::Assistant,
+        "system" => TurnRole::System,
+        "tool" => TurnRole::Tool,
         _ => {
-            let level_str = level.unwrap_or("transaction");
-            if level_str != "transaction" {
-                pgrx::warning!(
-                    "CALIBER: Unknown lock level '{}', defaulting to Transaction",
-                    level_str
-                );
-            }
-            AdvisoryLockLevel::Transaction
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "role".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: user, assistant, system, tool",
+                    role
+                ),
+            }));
         }
     };
 
-    // Try to acquire Postgres advisory lock using direct LockAcquire
-    let exclusive = lock_mode == LockMode::Exclusive;
-    let session_lock = lock_level == AdvisoryLockLevel::Session;
-    let acquired = try_advisory_lock(lock_key, exclusive, session_lock);
+    // Use direct heap operations instead of SPI
+    let turn_id = turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
+        turn_id: TurnId::now_v7(),
+        scope_id,
+        sequence,
+        role: turn_role,
+        content,
+        token_count,
+        tool_calls: None,
+        tool_results: None,
+        tenant_id,
+    })?;
+    audit_record(EntityType::Turn, turn_id, "create", None, tenant_id);
+    Ok(turn_id)
+}
 
-    if acquired {
-        // Create lock record using direct heap operations for cross-session visibility
-        let lock_id = LockId::now_v7();
-        let now = Utc::now();
-        let expires_at = now + chrono::Duration::milliseconds(timeout_ms);
+/// Build turn JSON from a heap row.
+fn turn_row_json(row: turn_heap::TurnRow) -> serde_json::Value {
+    let t = row.turn;
+    serde_json::json!({
+        "turn_id": t.turn_id.to_string(),
+        "scope_id": t.scope_id.to_string(),
+        "sequence": t.sequence,
+        "role": match t.role {
+            TurnRole::User => "user",
+            TurnRole::Assistant => "assistant",
+            TurnRole::System => "system",
+            TurnRole::Tool => "tool",
+        },
+        "content": t.content,
+        "token_count": t.token_count,
+        "created_at": t.created_at.to_rfc3339(),
+        "tool_calls": t.tool_calls,
+        "tool_results": t.tool_results,
+        "metadata": t.metadata,
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+}
 
-        let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+/// Get turns by scope.
+#[pg_extern]
+fn caliber_turn_get_by_scope(scope_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-        let result = lock_heap::lock_acquire_heap(
-            lock_id,
-            resource_type,
-            resource,
-            agent,
-            expires_at,
-            lock_mode,
-            tenant_uuid,
-        );
+    // Use direct heap operations instead of SPI
+    match turn_heap::turn_get_by_scope_heap(scp_id, tenant_uuid) {
+        Ok(turns) => {
+            let json_turns: Vec<serde_json::Value> = turns.into_iter().map(turn_row_json).collect();
 
-        match result {
-            Ok(_) => Some(pgrx_uuid_from_id(lock_id)),
-            Err(e) => {
-                pgrx::warning!("CALIBER: {:?}", e);
-                // Release the advisory lock since we couldn't record it
-                // Only session locks need explicit release; transaction locks auto-release
-                if session_lock {
-                    release_advisory_lock(lock_key, exclusive, session_lock);
-                }
-                None
-            }
+            pgrx::JsonB(serde_json::json!(json_turns))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get turns by scope: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
-    } else {
-        None
     }
 }
 
-/// Release an advisory lock.
-/// Only works for session-level locks. Transaction locks auto-release.
+/// Get turns of a scope with sequence between `from_seq` and `to_seq`
+/// (inclusive), ordered by sequence.
 #[pg_extern]
-fn caliber_lock_release(lock_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    let lid = id_from_pgrx::<LockId>(lock_id);
+fn caliber_turn_get_range(
+    scope_id: pgrx::Uuid,
+    from_seq: i32,
+    to_seq: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Get lock info using direct heap operations
-    let lock_info = match lock_heap::lock_get_heap(lid, tenant_uuid) {
-        Ok(Some(row)) => Some((row.lock.resource_type, row.lock.resource_id, row.lock.mode)),
-        Ok(None) => None,
-        Err(e) => {
-            pgrx::warning!("CALIBER: {:?}", e);
-            None
+    // Use direct heap operations instead of SPI
+    match turn_heap::turn_get_range_heap(scp_id, from_seq, to_seq, tenant_uuid) {
+        Ok(turns) => {
+            let json_turns: Vec<serde_json::Value> = turns.into_iter().map(turn_row_json).collect();
+            pgrx::JsonB(serde_json::json!(json_turns))
         }
-    };
-
-    if let Some((resource_type, resource_id, mode)) = lock_info {
-        let lock_key = compute_lock_key(&resource_type, resource_id);
-
-        // Release Postgres advisory lock using direct LockRelease (session-level)
-        let exclusive = mode == LockMode::Exclusive;
-        release_advisory_lock(lock_key, exclusive, true); // session_lock=true
-
-        // Delete lock record using direct heap operations
-        match lock_heap::lock_release_heap(lid, tenant_uuid) {
-            Ok(deleted) => deleted,
-            Err(e) => {
-                pgrx::warning!("CALIBER: {:?}", e);
-                false
-            }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get turn range: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
-    } else {
-        // Lock not found
-        let storage_err = StorageError::NotFound {
-            entity_type: EntityType::Lock,
-            id: lid.as_uuid(),
-        };
-        pgrx::warning!("CALIBER: {:?}", storage_err);
-        false
     }
 }
 
-/// Check if a resource is locked.
+/// Get the last `n` turns of a scope, ordered by sequence.
+/// A non-positive `n` returns an empty array.
 #[pg_extern]
-fn caliber_lock_check(
-    resource_type: &str,
-    resource_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
-) -> Option<pgrx::JsonB> {
-    let resource = Uuid::from_bytes(*resource_id.as_bytes());
+fn caliber_turn_get_recent(scope_id: pgrx::Uuid, n: i32, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Query using direct heap operations
-    match lock_heap::lock_list_by_resource_heap(resource_type, resource, tenant_uuid) {
-        Ok(locks) => {
-            // Filter for non-expired locks
-            let now = Utc::now();
-            let active_locks: Vec<_> = locks
-                .into_iter()
-                .filter(|row| row.lock.expires_at > now)
-                .collect();
-
-            active_locks.first().map(|row| {
-                let lock = &row.lock;
-                pgrx::JsonB(serde_json::json!({
-                    "lock_id": lock.lock_id.to_string(),
-                    "resource_type": &lock.resource_type,
-                    "resource_id": lock.resource_id.to_string(),
-                    "holder_agent_id": lock.holder_agent_id.to_string(),
-                    "acquired_at": lock.acquired_at.to_rfc3339(),
-                    "expires_at": lock.expires_at.to_rfc3339(),
-                    "mode": match lock.mode {
-                        LockMode::Exclusive => "exclusive",
-                        LockMode::Shared => "shared",
-                    },
-                    "tenant_id": lock.tenant_id.to_string(),
-                }))
-            })
+    // Use direct heap operations instead of SPI
+    match turn_heap::turn_get_recent_heap(scp_id, n.max(0) as usize, tenant_uuid) {
+        Ok(turns) => {
+            let json_turns: Vec<serde_json::Value> = turns.into_iter().map(turn_row_json).collect();
+            pgrx::JsonB(serde_json::json!(json_turns))
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: {:?}", e);
-            None
+            pgrx::warning!("CALIBER: Failed to get recent turns: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-// Get lock by ID.
-caliber_pg_get!(lock, lock_heap, LockId, |row| {
-    let l = row.lock;
-    serde_json::json!({
-        "lock_id": l.lock_id.to_string(),
-        "resource_type": l.resource_type,
-        "resource_id": l.resource_id.to_string(),
-        "holder_agent_id": l.holder_agent_id.to_string(),
-        "acquired_at": l.acquired_at.to_rfc3339(),
-        "expires_at": l.expires_at.to_rfc3339(),
-        "mode": match l.mode {
-            LockMode::Exclusive => "exclusive",
-            LockMode::Shared => "shared",
+/// Update a turn with the provided fields.
+/// Supports partial updates to content, token_count, tool_calls, tool_results
+/// and metadata; a JSON null clears tool_calls, tool_results or metadata.
+/// This lets async tool workflows attach results to a turn created earlier.
+#[pg_extern]
+fn caliber_turn_update(turn_id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    let update_obj = &updates.0;
+
+    let content = match update_obj.get("content") {
+        None => None,
+        Some(v) => match v.as_str() {
+            Some(c) => Some(c),
+            None => {
+                pgrx::warning!("CALIBER: Invalid turn content: {}", v);
+                return false;
+            }
         },
-        "tenant_id": l.tenant_id.to_string(),
-    })
-});
+    };
 
-/// Extend a lock's expiration time by the given milliseconds.
-#[pg_extern]
-fn caliber_lock_extend(lock_id: pgrx::Uuid, additional_ms: i64, tenant_id: pgrx::Uuid) -> bool {
-    let lid = id_from_pgrx::<LockId>(lock_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-    let row = match lock_heap::lock_get_heap(lid, tenant_uuid) {
-        Ok(Some(lock_row)) => lock_row,
-        Ok(None) => return false,
-        Err(e) => {
-            pgrx::warning!("CALIBER: {:?}", e);
-            return false;
-        }
+    // Validate token_count - must be a non-negative integer (REQ-12)
+    let token_count = match update_obj.get("token_count") {
+        None => None,
+        Some(v) => match v.as_i64().and_then(|n| i32::try_from(n).ok()) {
+            Some(n) if n >= 0 => Some(n),
+            _ => {
+                let validation_err = ValidationError::InvalidValue {
+                    field: "token_count".to_string(),
+                    reason: format!("expected a non-negative integer, got {}", v),
+                };
+                pgrx::warning!("CALIBER: {:?}", validation_err);
+                return false;
+            }
+        },
     };
 
-    let new_expires_at = row.lock.expires_at + chrono::Duration::milliseconds(additional_ms);
-    match lock_heap::lock_extend_heap(lid, new_expires_at, tenant_uuid) {
-        Ok(updated) => updated,
-        Err(e) => {
-            pgrx::warning!("CALIBER: {:?}", e);
-            false
-        }
-    }
-}
-
-// List all active (non-expired) locks.
-caliber_pg_list_active!(lock, lock_heap, |row| {
-    let lock = row.lock;
-    serde_json::json!({
-        "lock_id": lock.lock_id.to_string(),
-        "resource_type": lock.resource_type,
-        "resource_id": lock.resource_id.to_string(),
-        "holder_agent_id": lock.holder_agent_id.to_string(),
-        "acquired_at": lock.acquired_at.to_rfc3339(),
-        "expires_at": lock.expires_at.to_rfc3339(),
-        "mode": match lock.mode {
-            LockMode::Exclusive => "exclusive",
-            LockMode::Shared => "shared",
-        },
-        "tenant_id": lock.tenant_id.to_string(),
-    })
-});
+    let json_field = |name: &str| {
+        update_obj
+            .get(name)
+            .map(|v| if v.is_null() { None } else { Some(v) })
+    };
+    let tool_calls = json_field("tool_calls");
+    let tool_results = json_field("tool_results");
+    let metadata = json_field("metadata");
 
-/// List all active (non-expired) locks for a tenant.
-#[pg_extern]
-fn caliber_lock_list_active_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    // Check if any fields are being updated
+    if content.is_none()
+        && token_count.is_none()
+        && tool_calls.is_none()
+        && tool_results.is_none()
+        && metadata.is_none()
+    {
+        pgrx::warning!("CALIBER: No valid fields to update in turn");
+        return false;
+    }
 
-    match lock_heap::lock_list_active_heap(tenant_uuid) {
-        Ok(locks) => {
-            let json_locks: Vec<serde_json::Value> = locks
-                .into_iter()
-                .map(|row| {
-                    let lock = row.lock;
-                    serde_json::json!({
-                        "lock_id": lock.lock_id.to_string(),
-                        "resource_type": lock.resource_type,
-                        "resource_id": lock.resource_id.to_string(),
-                        "holder_agent_id": lock.holder_agent_id.to_string(),
-                        "acquired_at": lock.acquired_at.to_rfc3339(),
-                        "expires_at": lock.expires_at.to_rfc3339(),
-                        "mode": match lock.mode {
-                            LockMode::Exclusive => "exclusive",
-                            LockMode::Shared => "shared",
-                        },
-                        "tenant_id": lock.tenant_id.to_string(),
-                    })
-                })
-                .collect();
+    // Use direct heap operations instead of SPI
+    let entity_id = id_from_pgrx::<TurnId>(turn_id);
+    let tenant_entity_id = id_from_pgrx::<TenantId>(tenant_id);
+    let params = turn_heap::TurnUpdateHeapParams {
+        turn_id: entity_id,
+        tenant_id: tenant_entity_id,
+        content,
+        token_count,
+        tool_calls,
+        tool_results,
+        metadata,
+    };
 
-            pgrx::JsonB(serde_json::json!(json_locks))
+    match turn_heap::turn_update_heap(params) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Turn,
+                    entity_id,
+                    "update",
+                    None,
+                    tenant_entity_id,
+                );
+            }
+            updated
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list active locks: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: Failed to update turn: {}", e);
+            false
         }
     }
 }
 
-// ============================================================================
-// NOTIFY-BASED MESSAGE PASSING (Task 12.5)
-// ============================================================================
-
-/// Send a message to an agent.
-/// Send a message between agents using direct heap operations.
-/// Returns None if message_type or priority is invalid.
-#[allow(clippy::too_many_arguments)]
+/// Assemble the most recent turns of a scope that fit within `max_tokens`.
+///
+/// Walks turns newest-first summing `token_count` and stops at the first turn
+/// that would exceed the budget, so the selected window is always contiguous.
+/// Selected turns are returned in chronological order together with
+/// `tokens_used`, `turns_included` and `turns_dropped`.
 #[pg_extern]
-fn caliber_message_send(
-    from_agent_id: pgrx::Uuid,
-    to_agent_id: Option<pgrx::Uuid>,
-    to_agent_type: Option<&str>,
-    message_type: &str,
-    payload: &str,
-    trajectory_id: Option<pgrx::Uuid>,
-    scope_id: Option<pgrx::Uuid>,
-    artifact_ids: Vec<pgrx::Uuid>,
-    priority: &str,
-    expires_at: Option<i64>,
+fn caliber_assemble_history(
+    scope_id: pgrx::Uuid,
+    max_tokens: i32,
     tenant_id: pgrx::Uuid,
-) -> Option<pgrx::Uuid> {
-    let from_agent = id_from_pgrx::<AgentId>(from_agent_id);
-    let to_agent = opt_id_from_pgrx::<AgentId>(to_agent_id);
-    let traj_id = trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-    let scp_id = scope_id.map(id_from_pgrx::<ScopeId>);
-    let artifact_ids: Vec<ArtifactId> = artifact_ids
-        .into_iter()
-        .map(id_from_pgrx::<ArtifactId>)
-        .collect();
-    let expires_at =
-        expires_at.and_then(|ts| chrono::DateTime::<chrono::Utc>::from_timestamp(ts, 0));
+) -> pgrx::JsonB {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Validate and convert message_type
-    let msg_type = match message_type {
-        "task_delegation" => MessageType::TaskDelegation,
-        "task_result" => MessageType::TaskResult,
-        "context_request" => MessageType::ContextRequest,
-        "context_share" => MessageType::ContextShare,
-        "coordination_signal" => MessageType::CoordinationSignal,
-        "handoff" => MessageType::Handoff,
-        "interrupt" => MessageType::Interrupt,
-        "heartbeat" => MessageType::Heartbeat,
-        _ => {
-            pgrx::warning!("CALIBER: Invalid message_type '{}'. Valid values: task_delegation, task_result, context_request, context_share, coordination_signal, handoff, interrupt, heartbeat", message_type);
-            return None;
+    let turns = match turn_heap::turn_get_by_scope_heap(scp_id, tenant_uuid) {
+        Ok(turns) => turns,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to assemble history: {}", e);
+            return pgrx::JsonB(serde_json::json!({
+                "turns": [],
+                "tokens_used": 0,
+                "turns_included": 0,
+                "turns_dropped": 0,
+            }));
         }
     };
 
-    // Validate and convert priority
-    let msg_priority = match priority {
-        "low" => MessagePriority::Low,
-        "normal" => MessagePriority::Normal,
-        "high" => MessagePriority::High,
-        "critical" => MessagePriority::Critical,
-        _ => {
-            pgrx::warning!(
-                "CALIBER: Invalid priority '{}'. Valid values: low, normal, high, critical",
-                priority
-            );
-            return None;
+    let total = turns.len();
+    let budget = i64::from(max_tokens.max(0));
+    let mut tokens_used: i64 = 0;
+    let mut selected = Vec::new();
+
+    for row in turns.into_iter().rev() {
+        let cost = i64::from(row.turn.token_count.max(0));
+        if tokens_used + cost > budget {
+            break;
         }
-    };
+        tokens_used += cost;
+        selected.push(row);
+    }
+    selected.reverse();
 
-    let message_id = MessageId::now_v7();
+    let turns_included = selected.len();
+    let json_turns: Vec<serde_json::Value> = selected.into_iter().map(turn_row_json).collect();
 
-    // Use direct heap operations instead of SPI
-    let result = message_heap::message_send_heap(message_heap::MessageSendParams {
-        message_id,
-        from_agent_id: from_agent,
-        to_agent_id: to_agent,
-        to_agent_type,
-        message_type: msg_type,
-        payload,
-        trajectory_id: traj_id,
-        scope_id: scp_id,
-        artifact_ids: &artifact_ids,
-        priority: msg_priority,
-        expires_at,
-        tenant_id: tenant_uuid,
-    });
+    pgrx::JsonB(serde_json::json!({
+        "turns": json_turns,
+        "tokens_used": tokens_used,
+        "turns_included": turns_included,
+        "turns_dropped": total - turns_included,
+    }))
+}
 
-    match result {
-        Ok(_) => {
-            // Send pg_notify for real-time delivery
-            // Determine the channel based on to_agent_id or to_agent_type
-            let channel = if let Some(agent_id) = to_agent {
-                format!("caliber_agent_{}", agent_id)
-            } else if let Some(agent_type) = to_agent_type {
-                format!("caliber_agent_type_{}", agent_type)
-            } else {
-                "caliber_agent_broadcast".to_string()
-            };
+/// Apply a JSON priorities object (`{"user": 100, "notes": 70, ...}`) on top of
+/// the given section priorities. Keys that are absent keep their current value.
+fn apply_section_priorities(
+    base: &mut caliber_core::SectionPriorities,
+    priorities: &serde_json::Value,
+) -> CaliberResult<()> {
+    let obj = match priorities {
+        serde_json::Value::Null => return Ok(()),
+        serde_json::Value::Object(obj) => obj,
+        _ => {
+            // REQ-12: Reject malformed priorities instead of silently ignoring them
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "priorities".to_string(),
+                reason: "must be a JSON object".to_string(),
+            }));
+        }
+    };
 
-            let notify_result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-                client.update(&format!("NOTIFY {}, '{}'", channel, message_id), None, &[])?;
-                Ok::<_, pgrx::spi::SpiError>(())
-            });
+    for (key, value) in obj {
+        if key == "custom" {
+            base.custom = serde_json::from_value(value.clone()).map_err(|e| {
+                CaliberError::Validation(ValidationError::InvalidValue {
+                    field: "priorities.custom".to_string(),
+                    reason: format!("expected [[name, priority], ...]: {}", e),
+                })
+            })?;
+            continue;
+        }
 
-            if let Err(e) = notify_result {
-                pgrx::warning!("CALIBER: pg_notify failed: {}", e);
+        let priority = value
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())
+            .ok_or_else(|| {
+                CaliberError::Validation(ValidationError::InvalidValue {
+                    field: format!("priorities.{}", key),
+                    reason: format!("expected an integer, got {}", value),
+                })
+            })?;
+
+        let slot = match key.as_str() {
+            "user" => &mut base.user,
+            "system" => &mut base.system,
+            "persona" => &mut base.persona,
+            "artifacts" => &mut base.artifacts,
+            "notes" => &mut base.notes,
+            "history" => &mut base.history,
+            _ => {
+                return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                    field: "priorities".to_string(),
+                    reason: format!(
+                        "unknown section '{}'. Valid sections: user, system, persona, artifacts, notes, history, custom",
+                        key
+                    ),
+                }));
             }
+        };
+        *slot = priority;
+    }
 
-            Some(pgrx_uuid_from_id(message_id))
-        }
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to send message: {}", e);
-            None
-        }
+    Ok(())
+}
+
+/// Convert a SectionType to the name used in assembled context JSON.
+fn section_type_to_str(section_type: caliber_core::SectionType) -> &'static str {
+    use caliber_core::SectionType;
+    match section_type {
+        SectionType::SystemPrompt => "system_prompt",
+        SectionType::Instructions => "instructions",
+        SectionType::Evidence => "evidence",
+        SectionType::Memory => "memory",
+        SectionType::ToolResult => "tool_result",
+        SectionType::ConversationHistory => "conversation_history",
+        SectionType::System => "system",
+        SectionType::Persona => "persona",
+        SectionType::Notes => "notes",
+        SectionType::History => "history",
+        SectionType::Artifacts => "artifacts",
+        SectionType::User => "user",
     }
 }
 
-// Get a message by ID using direct heap operations.
-caliber_pg_get!(message, message_heap, MessageId, |row| {
-    let m = row.message;
-    serde_json::json!({
-        "message_id": m.message_id.to_string(),
-        "from_agent_id": m.from_agent_id.to_string(),
-        "to_agent_id": m.to_agent_id.map(|id| id.to_string()),
-        "to_agent_type": m.to_agent_type,
-        "message_type": match m.message_type {
-            MessageType::TaskDelegation => "task_delegation",
-            MessageType::TaskResult => "task_result",
-            MessageType::ContextRequest => "context_request",
-            MessageType::ContextShare => "context_share",
-            MessageType::CoordinationSignal => "coordination_signal",
-            MessageType::Handoff => "handoff",
-            MessageType::Interrupt => "interrupt",
-            MessageType::Heartbeat => "heartbeat",
-        },
-        "payload": m.payload,
-        "trajectory_id": m.trajectory_id.map(|id| id.to_string()),
-        "scope_id": m.scope_id.map(|id| id.to_string()),
-        "artifact_ids": m.artifact_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
-        "created_at": m.created_at.to_rfc3339(),
-        "delivered_at": m.delivered_at.map(|t| t.to_rfc3339()),
-        "acknowledged_at": m.acknowledged_at.map(|t| t.to_rfc3339()),
-        "priority": match m.priority {
-            MessagePriority::Low => "low",
-            MessagePriority::Normal => "normal",
-            MessagePriority::High => "high",
-            MessagePriority::Critical => "critical",
+/// Load a scope's turns, current artifacts and its trajectory's current notes,
+/// and assemble them into a context window.
+fn assemble_context_checked(
+    scope_id: ScopeId,
+    token_budget: i32,
+    priorities: &serde_json::Value,
+    tenant_id: TenantId,
+) -> CaliberResult<serde_json::Value> {
+    let settings = ConfigSettings::load(tenant_id)?;
+    let token_budget = if token_budget > 0 {
+        token_budget
+    } else {
+        settings.token_budget
+    };
+    let mut config = create_config(token_budget, &settings)?;
+    apply_section_priorities(&mut config.section_priorities, priorities)?;
+    let assembler = caliber_core::ContextAssembler::new(config)?;
+
+    let scope = scope_heap::scope_get_heap(scope_id, tenant_id)?.ok_or(CaliberError::Storage(
+        StorageError::NotFound {
+            entity_type: EntityType::Scope,
+            id: scope_id.as_uuid(),
         },
-        "expires_at": m.expires_at.map(|t| t.to_rfc3339()),
-        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-    })
-});
+    ))?;
+    let trajectory_id = scope.scope.trajectory_id;
 
-/// Mark a message as delivered.
+    let turns: Vec<Turn> = turn_heap::turn_get_by_scope_heap(scope_id, tenant_id)?
+        .into_iter()
+        .map(|row| row.turn)
+        .collect();
+    let artifacts: Vec<Artifact> =
+        artifact_heap::artifact_query_by_scope_heap(scope_id, tenant_id)?
+            .into_iter()
+            .map(|row| row.artifact)
+            .filter(|a| a.superseded_by.is_none())
+            .collect();
+    let notes: Vec<Note> = note_heap::note_query_by_trajectory_heap(trajectory_id, tenant_id)?
+        .into_iter()
+        .map(|row| row.note)
+        .filter(|n| n.superseded_by.is_none())
+        .collect();
+
+    let pkg = caliber_core::ContextPackage::new(trajectory_id, scope_id)
+        .with_turns(turns)
+        .with_artifacts(artifacts)
+        .with_notes(notes);
+    let window = assembler.assemble(pkg)?;
+
+    let sections: Vec<serde_json::Value> = window
+        .sections
+        .iter()
+        .map(|section| {
+            serde_json::json!({
+                "section_type": section_type_to_str(section.section_type),
+                "priority": section.priority,
+                "token_count": section.token_count,
+                "content": section.content,
+                "source_ids": section
+                    .sources
+                    .iter()
+                    .filter_map(|src| src.id.map(|id| id.to_string()))
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let excluded: Vec<serde_json::Value> = window
+        .assembly_trace
+        .iter()
+        .filter(|d| d.action == caliber_core::AssemblyAction::Exclude)
+        .map(|d| {
+            serde_json::json!({
+                "section_type": d.target_type,
+                "reason": d.reason,
+            })
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "sections": sections,
+        "excluded": excluded,
+        "token_budget": window.max_tokens,
+        "tokens_used": window.used_tokens,
+        "truncated": window.truncated,
+    }))
+}
+
+/// Assemble context for a scope within `token_budget`.
+/// A non-positive `token_budget` falls back to the tenant's configured
+/// `token_budget` setting.
+///
+/// Turns, current (non-superseded) artifacts of the scope and current notes of
+/// its trajectory become sections. Sections are ordered by `priorities`
+/// (overriding the default SectionPriorities per key) and added greedily
+/// until the budget is exhausted; compressible sections are truncated to fit.
 #[pg_extern]
-fn caliber_message_mark_delivered(message_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
+fn caliber_assemble_context(
+    scope_id: pgrx::Uuid,
+    token_budget: i32,
+    priorities: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    let mid = Uuid::from_bytes(*message_id.as_bytes());
-    let now = match chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
+    match assemble_context_checked(scp_id, token_budget, &priorities.0, tenant_uuid) {
+        Ok(context) => pgrx::JsonB(context),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return false;
+            pgrx::warning!("CALIBER: Failed to assemble context: {}", e);
+            pgrx::JsonB(serde_json::json!({
+                "sections": [],
+                "excluded": [],
+                "token_budget": token_budget,
+                "tokens_used": 0,
+                "truncated": false,
+            }))
         }
-    };
-    let pg_mid = pgrx::Uuid::from_bytes(*mid.as_bytes());
+    }
+}
 
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_mid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let table = client.update(
-            "UPDATE caliber_message SET delivered_at = $1 WHERE message_id = $2 AND tenant_id = $3 AND delivered_at IS NULL",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(table.len())
-    });
+// ============================================================================
+// ADVISORY LOCK FUNCTIONS (Task 12.4)
+// Using direct LockAcquire with LOCKTAG for zero SQL overhead.
+// ============================================================================
 
-    match result {
-        Ok(len) => len > 0,
-        Err(e) => {
-            // Use EntityType::Message for proper error categorization
-            let storage_err = StorageError::UpdateFailed {
-                entity_type: EntityType::Message,
-                id: mid,
-                reason: format!("mark delivered failed: {}", e),
-            };
-            pgrx::warning!("CALIBER: {:?}", storage_err);
-            false
-        }
+/// Lock level for advisory locks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdvisoryLockLevel {
+    /// Session-level lock - persists until explicit release or session ends
+    Session,
+    /// Transaction-level lock - auto-releases at transaction commit/rollback
+    Transaction,
+}
+
+/// Build a LOCKTAG for advisory locks from a lock key (i64).
+/// Uses LOCKTAG_ADVISORY with the database ID and key split across fields.
+#[inline]
+fn make_advisory_locktag(lock_key: i64) -> pg_sys::LOCKTAG {
+    pg_sys::LOCKTAG {
+        locktag_field1: unsafe { pg_sys::MyDatabaseId.to_u32() },
+        locktag_field2: (lock_key >> 32) as u32,
+        locktag_field3: lock_key as u32,
+        locktag_field4: 0,
+        locktag_type: pg_sys::LockTagType::LOCKTAG_ADVISORY as u8,
+        locktag_lockmethodid: pg_sys::USER_LOCKMETHOD as u8,
     }
 }
 
-/// Mark a message as acknowledged using direct heap operations.
-#[pg_extern]
-fn caliber_message_mark_acknowledged(message_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    let mid = id_from_pgrx::<MessageId>(message_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+/// Outcome of a successful advisory lock acquisition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdvisoryLockAcquired {
+    /// This call took the lock.
+    New,
+    /// This session already held the lock; Postgres bumped its local hold count.
+    AlreadyHeld,
+}
 
-    // Use direct heap operations instead of SPI
-    match message_heap::message_acknowledge_heap(mid, tenant_uuid) {
-        Ok(updated) => updated,
+/// Try to acquire an advisory lock using direct LockAcquire.
+/// Returns None if the lock is not available.
+#[inline]
+fn acquire_advisory_lock(
+    lock_key: i64,
+    exclusive: bool,
+    session_lock: bool,
+) -> Option<AdvisoryLockAcquired> {
+    let locktag = make_advisory_locktag(lock_key);
+    let lockmode = if exclusive {
+        pg_sys::ExclusiveLock as pg_sys::LOCKMODE
+    } else {
+        pg_sys::ShareLock as pg_sys::LOCKMODE
+    };
+
+    // dontWait=true means return immediately if lock not available
+    let result = unsafe { pg_sys::LockAcquire(&locktag, lockmode, session_lock, true) };
+
+    if result == pg_sys::LockAcquireResult::LOCKACQUIRE_OK as pg_sys::LockAcquireResult::Type {
+        Some(AdvisoryLockAcquired::New)
+    } else if result
+        == pg_sys::LockAcquireResult::LOCKACQUIRE_ALREADY_HELD as pg_sys::LockAcquireResult::Type
+    {
+        Some(AdvisoryLockAcquired::AlreadyHeld)
+    } else {
+        None
+    }
+}
+
+/// Try to acquire an advisory lock using direct LockAcquire.
+/// Returns true if the lock was acquired, false if not available.
+#[inline]
+fn try_advisory_lock(lock_key: i64, exclusive: bool, session_lock: bool) -> bool {
+    acquire_advisory_lock(lock_key, exclusive, session_lock).is_some()
+}
+
+/// Release an advisory lock using direct LockRelease.
+/// Returns true if the lock was released.
+#[inline]
+fn release_advisory_lock(lock_key: i64, exclusive: bool, session_lock: bool) -> bool {
+    let locktag = make_advisory_locktag(lock_key);
+    let lockmode = if exclusive {
+        pg_sys::ExclusiveLock as pg_sys::LOCKMODE
+    } else {
+        pg_sys::ShareLock as pg_sys::LOCKMODE
+    };
+
+    unsafe { pg_sys::LockRelease(&locktag, lockmode, session_lock) }
+}
+
+/// Re-acquire a lock the agent already holds by bumping its acquire count.
+///
+/// Each lock record is backed by a single advisory hold, so a duplicate hold
+/// taken by this session is dropped again. A record that has expired starts
+/// counting afresh. Requesting a different mode than the one held fails; use
+/// `caliber_lock_upgrade` to go from shared to exclusive.
+fn lock_reacquire(
+    row: lock_heap::LockRow,
+    lock_mode: LockMode,
+    timeout_ms: i64,
+    advisory: AdvisoryLockAcquired,
+    lock_key: i64,
+    session_lock: bool,
+) -> Option<pgrx::Uuid> {
+    let exclusive = lock_mode == LockMode::Exclusive;
+    if advisory == AdvisoryLockAcquired::AlreadyHeld || row.lock.mode != lock_mode {
+        release_advisory_lock(lock_key, exclusive, session_lock);
+    }
+    if row.lock.mode != lock_mode {
+        pgrx::warning!(
+            "CALIBER: Agent already holds a {:?} lock on this resource",
+            row.lock.mode
+        );
+        return None;
+    }
+
+    let now = Utc::now();
+    let requested_expiry = now + chrono::Duration::milliseconds(timeout_ms);
+    let (acquire_count, expires_at) = if row.lock.expires_at > now {
+        (
+            row.acquire_count.saturating_add(1),
+            row.lock.expires_at.max(requested_expiry),
+        )
+    } else {
+        (1, requested_expiry)
+    };
+
+    match lock_heap::lock_set_acquire_count_heap(
+        row.lock.lock_id,
+        acquire_count,
+        Some(expires_at),
+        row.lock.tenant_id,
+    ) {
+        Ok(true) => Some(pgrx_uuid_from_id(row.lock.lock_id)),
+        Ok(false) => None,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to acknowledge message: {}", e);
-            false
+            pgrx::warning!("CALIBER: {:?}", e);
+            None
         }
     }
 }
 
-/// Get pending messages for an agent using direct heap operations.
-/// Returns messages where delivered_at IS NULL and not expired, ordered by priority.
-/// Note: agent_type is kept for API compatibility but not currently used in filtering.
+/// Acquire an advisory lock on a resource.
+/// Uses Postgres advisory locks for distributed coordination.
+/// Stores lock record in SQL table for cross-session visibility.
+///
+/// Parameters:
+/// - agent_id: The agent acquiring the lock
+/// - resource_type: Type of resource being locked
+/// - resource_id: ID of the resource being locked
+/// - timeout_ms: Lock expiration timeout in milliseconds
+/// - mode: "exclusive" or "shared"
+/// - level: "session" or "transaction" (defaults to "transaction")
 #[pg_extern]
-fn caliber_message_get_pending(
+fn caliber_lock_acquire(
     agent_id: pgrx::Uuid,
-    _agent_type: &str,
+    resource_type: &str,
+    resource_id: pgrx::Uuid,
+    timeout_ms: i64,
+    mode: &str,
+    level: Option<&str>,
     tenant_id: pgrx::Uuid,
-) -> pgrx::JsonB {
-    let aid = id_from_pgrx::<AgentId>(agent_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+) -> Option<pgrx::Uuid> {
+    let agent = id_from_pgrx::<AgentId>(agent_id);
+    let resource = Uuid::from_bytes(*resource_id.as_bytes());
+    let lock_key = compute_lock_key(resource_type, resource);
 
-    // Use direct heap operations to get messages for this agent
-    let messages_result = message_heap::message_list_for_agent_heap(aid, tenant_uuid);
+    let lock_mode = match mode {
+        "shared" => LockMode::Shared,
+        _ => {
+            if mode != "exclusive" {
+                pgrx::warning!(
+                    "CALIBER: Unknown lock mode '{}', defaulting to Exclusive",
+                    mode
+                );
+            }
+            LockMode::Exclusive
+        }
+    };
 
-    match messages_result {
-        Ok(messages) => {
-            let now = Utc::now();
+    let lock_level = match level.unwrap_or("transaction") {
+        "session" => AdvisoryLockLevel::Session,
+        _ => {
+            let level_str = level.unwrap_or("transaction");
+            if level_str != "transaction" {
+                pgrx::warning!(
+                    "CALIBER: Unknown lock level '{}', defaulting to Transaction",
+                    level_str
+                );
+            }
+            AdvisoryLockLevel::Transaction
+        }
+    };
 
-            // Filter for pending messages (delivered_at IS NULL and not expired)
-            let mut pending: Vec<_> = messages
-                .into_iter()
-                .filter(|row| {
-                    row.message.delivered_at.is_none()
-                        && row
-                            .message
-                            .expires_at
-                            .is_none_or(|expires_at| expires_at > now)
-                })
-                .collect();
+    // Try to acquire Postgres advisory lock using direct LockAcquire
+    let exclusive = lock_mode == LockMode::Exclusive;
+    let session_lock = lock_level == AdvisoryLockLevel::Session;
+    let advisory = acquire_advisory_lock(lock_key, exclusive, session_lock)?;
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-            // Sort by priority (critical > high > normal > low) then by created_at
-            pending.sort_by(|a, b| {
-                let priority_order = |p: &MessagePriority| match p {
-                    MessagePriority::Critical => 1,
-                    MessagePriority::High => 2,
-                    MessagePriority::Normal => 3,
-                    MessagePriority::Low => 4,
-                };
+    // Reentrant acquire: the agent already has a record for this resource
+    let existing = match lock_heap::lock_list_by_resource_heap(resource_type, resource, tenant_uuid)
+    {
+        Ok(rows) => rows
+            .into_iter()
+            .find(|row| row.lock.holder_agent_id == agent),
+        Err(e) => {
+            pgrx::warning!("CALIBER: {:?}", e);
+            release_advisory_lock(lock_key, exclusive, session_lock);
+            return None;
+        }
+    };
+    if let Some(row) = existing {
+        return lock_reacquire(row, lock_mode, timeout_ms, advisory, lock_key, session_lock);
+    }
 
-                priority_order(&a.message.priority)
-                    .cmp(&priority_order(&b.message.priority))
-                    .then_with(|| a.message.created_at.cmp(&b.message.created_at))
-            });
+    // Create lock record using direct heap operations for cross-session visibility
+    let lock_id = LockId::now_v7();
+    let now = Utc::now();
+    let expires_at = now + chrono::Duration::milliseconds(timeout_ms);
 
-            // Convert to JSON
-            let json_messages: Vec<serde_json::Value> = pending
-                .into_iter()
-                .map(|row| {
-                    let m = row.message;
-                    serde_json::json!({
-                        "message_id": m.message_id.to_string(),
-                        "from_agent_id": m.from_agent_id.to_string(),
-                        "to_agent_id": m.to_agent_id.map(|id| id.to_string()),
-                        "to_agent_type": m.to_agent_type,
-                        "message_type": match m.message_type {
-                            MessageType::TaskDelegation => "task_delegation",
-                            MessageType::TaskResult => "task_result",
-                            MessageType::ContextRequest => "context_request",
-                            MessageType::ContextShare => "context_share",
-                            MessageType::CoordinationSignal => "coordination_signal",
-                            MessageType::Handoff => "handoff",
-                            MessageType::Interrupt => "interrupt",
-                            MessageType::Heartbeat => "heartbeat",
-                        },
-                        "payload": m.payload,
-                        "trajectory_id": m.trajectory_id.map(|id| id.to_string()),
-                        "scope_id": m.scope_id.map(|id| id.to_string()),
-                        "artifact_ids": m.artifact_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
-                        "created_at": m.created_at.to_rfc3339(),
-                        "delivered_at": m.delivered_at.map(|t| t.to_rfc3339()),
-                        "acknowledged_at": m.acknowledged_at.map(|t| t.to_rfc3339()),
-                        "priority": match m.priority {
-                            MessagePriority::Low => "low",
-                            MessagePriority::Normal => "normal",
-                            MessagePriority::High => "high",
-                            MessagePriority::Critical => "critical",
-                        },
-                        "expires_at": m.expires_at.map(|t| t.to_rfc3339()),
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-                    })
-                })
-                .collect();
+    let result = lock_heap::lock_acquire_heap(
+        lock_id,
+        resource_type,
+        resource,
+        agent,
+        expires_at,
+        lock_mode,
+        tenant_uuid,
+    );
 
-            pgrx::JsonB(serde_json::json!(json_messages))
+    match result {
+        Ok(_) => {
+            audit_record(
+                EntityType::Lock,
+                lock_id,
+                "acquire",
+                Some(agent),
+                tenant_uuid,
+            );
+            Some(pgrx_uuid_from_id(lock_id))
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to get pending messages: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: {:?}", e);
+            // Release the advisory lock since we couldn't record it
+            // Only session locks need explicit release; transaction locks auto-release
+            if session_lock {
+                release_advisory_lock(lock_key, exclusive, session_lock);
+            }
+            None
         }
     }
 }
 
-/// List messages using filter JSON.
-#[pg_extern]
-fn caliber_message_list(filters: pgrx::JsonB) -> pgrx::JsonB {
-    message_list_internal(&filters.0, None)
-}
-
-/// List messages using filter JSON with tenant isolation.
+/// Release an advisory lock.
+/// Only works for session-level locks. Transaction locks auto-release.
 #[pg_extern]
-fn caliber_message_list_by_tenant(filters: pgrx::JsonB) -> pgrx::JsonB {
-    let tenant_id = filters
-        .0
-        .get("tenant_id")
-        .and_then(|v| v.as_str())
-        .and_then(|s| Uuid::parse_str(s).ok());
-    message_list_internal(&filters.0, tenant_id)
-}
+fn caliber_lock_release(lock_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let lid = id_from_pgrx::<LockId>(lock_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-fn message_list_internal(filters: &serde_json::Value, tenant_id: Option<Uuid>) -> pgrx::JsonB {
-    let parse_uuid = |key: &str| {
-        filters
-            .get(key)
-            .and_then(|v| v.as_str())
-            .and_then(|s| Uuid::parse_str(s).ok())
+    // Get lock info using direct heap operations
+    let lock_info = match lock_heap::lock_get_heap(lid, tenant_uuid) {
+        Ok(Some(row)) => Some((
+            row.lock.resource_type,
+            row.lock.resource_id,
+            row.lock.mode,
+            row.lock.holder_agent_id,
+            row.acquire_count,
+        )),
+        Ok(None) => None,
+        Err(e) => {
+            pgrx::warning!("CALIBER: {:?}", e);
+            None
+        }
     };
 
-    let from_agent_id = parse_uuid("from_agent_id");
-    let to_agent_id = parse_uuid("to_agent_id");
-    let trajectory_id = parse_uuid("trajectory_id");
-    let message_type = filters
-        .get("message_type")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-    let priority = filters
-        .get("priority")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-    let to_agent_type = filters
-        .get("to_agent_type")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-    let undelivered_only = filters
-        .get("undelivered_only")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
-    let unacknowledged_only = filters
-        .get("unacknowledged_only")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false);
-    let limit = filters
-        .get("limit")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(100)
-        .max(0) as usize;
-    let offset = filters
-        .get("offset")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0)
-        .max(0) as usize;
-
-    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let (sql, params) = if let Some(tenant_id) = tenant_id {
-            (
-                "SELECT message_id, from_agent_id, to_agent_id, to_agent_type, message_type, payload,
-                        trajectory_id, scope_id, artifact_ids, created_at, delivered_at, acknowledged_at,
-                        priority, expires_at, tenant_id
-                 FROM caliber_message
-                 WHERE tenant_id = $1
-                 ORDER BY created_at DESC",
-                vec![uuid_datum(tenant_id)],
-            )
-        } else {
-            (
-                "SELECT message_id, from_agent_id, to_agent_id, to_agent_type, message_type, payload,
-                        trajectory_id, scope_id, artifact_ids, created_at, delivered_at, acknowledged_at,
-                        priority, expires_at, tenant_id
-                 FROM caliber_message
-                 ORDER BY created_at DESC",
-                vec![],
-            )
-        };
-
-        let table = client.select(sql, None, &params)?;
-        let mut messages = Vec::new();
+    if let Some((resource_type, resource_id, mode, holder, acquire_count)) = lock_info {
+        // Reentrant holds: only the last release frees the lock
+        if acquire_count > 1 {
+            return match lock_heap::lock_set_acquire_count_heap(
+                lid,
+                acquire_count - 1,
+                None,
+                tenant_uuid,
+            ) {
+                Ok(updated) => updated,
+                Err(e) => {
+                    pgrx::warning!("CALIBER: {:?}", e);
+                    false
+                }
+            };
+        }
 
-        for row in table {
-            let message_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let from_agent_id_row: Option<pgrx::Uuid> = row.get(2).ok().flatten();
-            let to_agent_id_row: Option<pgrx::Uuid> = row.get(3).ok().flatten();
-            let to_agent_type_row: Option<String> = row.get(4).ok().flatten();
-            let message_type_row: Option<String> = row.get(5).ok().flatten();
-            let payload_row: Option<String> = row.get(6).ok().flatten();
-            let trajectory_id_row: Option<pgrx::Uuid> = row.get(7).ok().flatten();
-            let scope_id_row: Option<pgrx::Uuid> = row.get(8).ok().flatten();
-            let artifact_ids_row: Option<Vec<pgrx::Uuid>> = row.get(9).ok().flatten();
-            let created_at_row: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-            let delivered_at_row: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
-            let acknowledged_at_row: Option<TimestampWithTimeZone> = row.get(12).ok().flatten();
-            let priority_row: Option<String> = row.get(13).ok().flatten();
-            let expires_at_row: Option<TimestampWithTimeZone> = row.get(14).ok().flatten();
-            let tenant_id_row: Option<pgrx::Uuid> = row.get(15).ok().flatten();
+        let lock_key = compute_lock_key(&resource_type, resource_id);
 
-            let from_agent_uuid = from_agent_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
-            let to_agent_uuid = to_agent_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
-            let traj_uuid = trajectory_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
+        // Release Postgres advisory lock using direct LockRelease (session-level)
+        let exclusive = mode == LockMode::Exclusive;
+        release_advisory_lock(lock_key, exclusive, true); // session_lock=true
 
-            if let Some(filter) = from_agent_id {
-                if from_agent_uuid != Some(filter) {
-                    continue;
-                }
-            }
-            if let Some(filter) = to_agent_id {
-                if to_agent_uuid != Some(filter) {
-                    continue;
-                }
-            }
-            if let Some(filter) = trajectory_id {
-                if traj_uuid != Some(filter) {
-                    continue;
-                }
-            }
-            if let Some(filter) = &message_type {
-                if message_type_row.as_deref() != Some(filter.as_str()) {
-                    continue;
-                }
-            }
-            if let Some(filter) = &priority {
-                if priority_row.as_deref() != Some(filter.as_str()) {
-                    continue;
-                }
-            }
-            if let Some(filter) = &to_agent_type {
-                if to_agent_type_row.as_deref() != Some(filter.as_str()) {
-                    continue;
+        // Delete lock record using direct heap operations
+        match lock_heap::lock_release_heap(lid, tenant_uuid) {
+            Ok(deleted) => {
+                if deleted {
+                    audit_record(EntityType::Lock, lid, "release", Some(holder), tenant_uuid);
                 }
+                deleted
             }
-            if undelivered_only && delivered_at_row.is_some() {
-                continue;
-            }
-            if unacknowledged_only && acknowledged_at_row.is_some() {
-                continue;
+            Err(e) => {
+                pgrx::warning!("CALIBER: {:?}", e);
+                false
             }
-
-            let json = serde_json::json!({
-                "message_id": message_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "from_agent_id": from_agent_uuid.map(|u| u.to_string()),
-                "to_agent_id": to_agent_uuid.map(|u| u.to_string()),
-                "to_agent_type": to_agent_type_row,
-                "message_type": message_type_row,
-                "payload": payload_row,
-                "trajectory_id": trajectory_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "scope_id": scope_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "artifact_ids": artifact_ids_row
-                    .unwrap_or_default()
-                    .into_iter()
-                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
-                    .collect::<Vec<_>>(),
-                "created_at": created_at_row.map(|t| t.to_string()),
-                "delivered_at": delivered_at_row.map(|t| t.to_string()),
-                "acknowledged_at": acknowledged_at_row.map(|t| t.to_string()),
-                "priority": priority_row,
-                "expires_at": expires_at_row.map(|t| t.to_string()),
-                "tenant_id": tenant_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-            });
-
-            messages.push(json);
         }
+    } else {
+        // Lock not found
+        let storage_err = StorageError::NotFound {
+            entity_type: EntityType::Lock,
+            id: lid.as_uuid(),
+        };
+        pgrx::warning!("CALIBER: {:?}", storage_err);
+        false
+    }
+}
 
-        Ok(messages)
-    });
+/// Check if a resource is locked.
+#[pg_extern]
+fn caliber_lock_check(
+    resource_type: &str,
+    resource_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    let resource = Uuid::from_bytes(*resource_id.as_bytes());
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    match result {
-        Ok(messages) => {
-            let sliced: Vec<serde_json::Value> =
-                messages.into_iter().skip(offset).take(limit).collect();
-            pgrx::JsonB(serde_json::json!(sliced))
+    // Query using direct heap operations
+    match lock_heap::lock_list_by_resource_heap(resource_type, resource, tenant_uuid) {
+        Ok(locks) => {
+            // Filter for non-expired locks
+            let now = Utc::now();
+            let active_locks: Vec<_> = locks
+                .into_iter()
+                .filter(|row| row.lock.expires_at > now)
+                .collect();
+
+            active_locks.first().map(|row| {
+                let lock = &row.lock;
+                pgrx::JsonB(serde_json::json!({
+                    "lock_id": lock.lock_id.to_string(),
+                    "resource_type": &lock.resource_type,
+                    "resource_id": lock.resource_id.to_string(),
+                    "holder_agent_id": lock.holder_agent_id.to_string(),
+                    "acquired_at": lock.acquired_at.to_rfc3339(),
+                    "expires_at": lock.expires_at.to_rfc3339(),
+                    "mode": match lock.mode {
+                        LockMode::Exclusive => "exclusive",
+                        LockMode::Shared => "shared",
+                    },
+                    "tenant_id": lock.tenant_id.to_string(),
+                }))
+            })
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list messages: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: {:?}", e);
+            None
         }
     }
 }
 
-// ============================================================================
-// AGENT OPERATIONS (Task 12.6)
-// ============================================================================
+// Get lock by ID.
+caliber_pg_get!(lock, lock_heap, LockId, |row| {
+    let l = row.lock;
+    serde_json::json!({
+        "lock_id": l.lock_id.to_string(),
+        "resource_type": l.resource_type,
+        "resource_id": l.resource_id.to_string(),
+        "holder_agent_id": l.holder_agent_id.to_string(),
+        "acquired_at": l.acquired_at.to_rfc3339(),
+        "expires_at": l.expires_at.to_rfc3339(),
+        "mode": match l.mode {
+            LockMode::Exclusive => "exclusive",
+            LockMode::Shared => "shared",
+        },
+        "tenant_id": l.tenant_id.to_string(),
+    })
+});
 
-/// Register a new agent.
+/// Extend a lock's expiration time by the given milliseconds.
 #[pg_extern]
-fn caliber_agent_register(
-    agent_type: &str,
-    capabilities: pgrx::JsonB,
-    tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
-    // Record operation for metrics
-    storage_write().record_op("agent_register");
-
-    let caps: Vec<String> = serde_json::from_value(capabilities.0).unwrap_or_default();
-
-    let agent = Agent::new(agent_type, caps.clone());
-    let agent_id = agent.agent_id;
-
-    // Use direct heap operations instead of SPI
+fn caliber_lock_extend(lock_id: pgrx::Uuid, additional_ms: i64, tenant_id: pgrx::Uuid) -> bool {
+    let lid = id_from_pgrx::<LockId>(lock_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let row = match lock_heap::lock_get_heap(lid, tenant_uuid) {
+        Ok(Some(lock_row)) => lock_row,
+        Ok(None) => return false,
+        Err(e) => {
+            pgrx::warning!("CALIBER: {:?}", e);
+            return false;
+        }
+    };
 
-    let result = agent_heap::agent_register_heap(
-        agent_id,
-        agent_type,
-        &caps,
-        &agent.memory_access,
-        &agent.can_delegate_to,
-        agent.reports_to,
-        tenant_uuid,
-    );
-
-    if let Err(e) = result {
-        pgrx::warning!("CALIBER: Failed to insert agent: {}", e);
+    let new_expires_at = row.lock.expires_at + chrono::Duration::milliseconds(additional_ms);
+    match lock_heap::lock_extend_heap(lid, new_expires_at, tenant_uuid) {
+        Ok(updated) => updated,
+        Err(e) => {
+            pgrx::warning!("CALIBER: {:?}", e);
+            false
+        }
     }
-
-    pgrx_uuid_from_id(agent_id)
 }
 
-// Get an agent by ID.
-caliber_pg_get!(agent, agent_heap, AgentId, |row| {
-    let a = row.agent;
-    serde_json::json!({
-        "agent_id": a.agent_id.to_string(),
-        "agent_type": a.agent_type,
-        "capabilities": a.capabilities,
-        "memory_access": serde_json::to_value(&a.memory_access).unwrap_or(serde_json::json!({})),
-        "status": match a.status {
-            AgentStatus::Idle => "idle",
-            AgentStatus::Active => "active",
-            AgentStatus::Blocked => "blocked",
-            AgentStatus::Failed => "failed",
-            AgentStatus::Offline => "offline",
-        },
-        "current_trajectory_id": a.current_trajectory_id.map(|id| id.to_string()),
-        "current_scope_id": a.current_scope_id.map(|id| id.to_string()),
-        "can_delegate_to": a.can_delegate_to,
-        "reports_to": a.reports_to.map(|id| id.to_string()),
-        "created_at": a.created_at.to_rfc3339(),
-        "last_heartbeat": a.last_heartbeat.to_rfc3339(),
-        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-    })
-});
-
-/// Update agent status.
+/// Upgrade a shared lock to exclusive without releasing it first.
+///
+/// Tries to take the exclusive advisory lock on the same resource; this only
+/// succeeds if no other session holds it shared. On success the shared advisory
+/// lock is dropped and the lock record's mode becomes exclusive. On failure the
+/// shared lock is left intact and false is returned.
 #[pg_extern]
-fn caliber_agent_set_status(agent_id: pgrx::Uuid, status: &str, tenant_id: pgrx::Uuid) -> bool {
-    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+fn caliber_lock_upgrade(lock_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let lid = id_from_pgrx::<LockId>(lock_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Validate and convert status
-    let agent_status = match status {
-        "idle" => AgentStatus::Idle,
-        "active" => AgentStatus::Active,
-        "blocked" => AgentStatus::Blocked,
-        "failed" => AgentStatus::Failed,
-        _ => {
-            pgrx::warning!("CALIBER: Invalid agent status: {}", status);
+    let lock = match lock_heap::lock_get_heap(lid, tenant_uuid) {
+        Ok(Some(row)) => row.lock,
+        Ok(None) => {
+            let storage_err = StorageError::NotFound {
+                entity_type: EntityType::Lock,
+                id: lid.as_uuid(),
+            };
+            pgrx::warning!("CALIBER: {:?}", storage_err);
+            return false;
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: {:?}", e);
             return false;
         }
     };
 
-    // Use direct heap operations instead of SPI
-    match agent_heap::agent_set_status_heap(entity_id, agent_status, tenant_uuid) {
-        Ok(updated) => updated,
+    if lock.mode == LockMode::Exclusive {
+        return true;
+    }
+
+    // Our own shared hold does not conflict; any other holder does
+    let lock_key = compute_lock_key(&lock.resource_type, lock.resource_id);
+    if !try_advisory_lock(lock_key, true, true) {
+        return false;
+    }
+
+    match lock_heap::lock_set_mode_heap(lid, LockMode::Exclusive, tenant_uuid) {
+        Ok(true) => {
+            release_advisory_lock(lock_key, false, true);
+            true
+        }
+        Ok(false) => {
+            release_advisory_lock(lock_key, true, true);
+            false
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to update agent status: {}", e);
+            pgrx::warning!("CALIBER: {:?}", e);
+            release_advisory_lock(lock_key, true, true);
             false
         }
     }
 }
 
-/// Update agent heartbeat.
+// List all active (non-expired) locks.
+caliber_pg_list_active!(lock, lock_heap, |row| {
+    let lock = row.lock;
+    serde_json::json!({
+        "lock_id": lock.lock_id.to_string(),
+        "resource_type": lock.resource_type,
+        "resource_id": lock.resource_id.to_string(),
+        "holder_agent_id": lock.holder_agent_id.to_string(),
+        "acquired_at": lock.acquired_at.to_rfc3339(),
+        "expires_at": lock.expires_at.to_rfc3339(),
+        "mode": match lock.mode {
+            LockMode::Exclusive => "exclusive",
+            LockMode::Shared => "shared",
+        },
+        "tenant_id": lock.tenant_id.to_string(),
+    })
+});
+
+/// List all active (non-expired) locks for a tenant.
 #[pg_extern]
-fn caliber_agent_heartbeat(agent_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+fn caliber_lock_list_active_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Use direct heap operations instead of SPI
-    match agent_heap::agent_heartbeat_heap(entity_id, tenant_uuid) {
-        Ok(updated) => updated,
+    match lock_heap::lock_list_active_heap(tenant_uuid) {
+        Ok(locks) => {
+            let json_locks: Vec<serde_json::Value> = locks
+                .into_iter()
+                .map(|row| {
+                    let lock = row.lock;
+                    serde_json::json!({
+                        "lock_id": lock.lock_id.to_string(),
+                        "resource_type": lock.resource_type,
+                        "resource_id": lock.resource_id.to_string(),
+                        "holder_agent_id": lock.holder_agent_id.to_string(),
+                        "acquired_at": lock.acquired_at.to_rfc3339(),
+                        "expires_at": lock.expires_at.to_rfc3339(),
+                        "mode": match lock.mode {
+                            LockMode::Exclusive => "exclusive",
+                            LockMode::Shared => "shared",
+                        },
+                        "tenant_id": lock.tenant_id.to_string(),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_locks))
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to update agent heartbeat: {}", e);
-            false
+            pgrx::warning!("CALIBER: Failed to list active locks: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-/// List agents by type.
+/// List active (non-expired) locks held by an agent.
 #[pg_extern]
-fn caliber_agent_list_by_type(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+fn caliber_lock_list_by_agent(agent_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let agent = id_from_pgrx::<AgentId>(agent_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-    // Use direct heap operations instead of SPI
-    match agent_heap::agent_list_by_type_heap(agent_type, tenant_uuid) {
-        Ok(agents) => {
-            let json_agents: Vec<serde_json::Value> = agents
+
+    match lock_heap::lock_list_by_holder_heap(agent, tenant_uuid) {
+        Ok(locks) => {
+            let json_locks: Vec<serde_json::Value> = locks
                 .into_iter()
                 .map(|row| {
-                    let agent = row.agent;
+                    let lock = row.lock;
                     serde_json::json!({
-                        "agent_id": agent.agent_id.to_string(),
-                        "agent_type": agent.agent_type,
-                        "capabilities": agent.capabilities,
-                        "memory_access": serde_json::to_value(&agent.memory_access).unwrap_or(serde_json::json!({})),
-                        "status": match agent.status {
-                            AgentStatus::Idle => "idle",
-                            AgentStatus::Active => "active",
-                            AgentStatus::Blocked => "blocked",
-                            AgentStatus::Failed => "failed",
-                            AgentStatus::Offline => "offline",
+                        "lock_id": lock.lock_id.to_string(),
+                        "resource_type": lock.resource_type,
+                        "resource_id": lock.resource_id.to_string(),
+                        "holder_agent_id": lock.holder_agent_id.to_string(),
+                        "acquired_at": lock.acquired_at.to_rfc3339(),
+                        "expires_at": lock.expires_at.to_rfc3339(),
+                        "mode": match lock.mode {
+                            LockMode::Exclusive => "exclusive",
+                            LockMode::Shared => "shared",
                         },
-                        "current_trajectory_id": agent.current_trajectory_id.map(|id| id.to_string()),
-                        "current_scope_id": agent.current_scope_id.map(|id| id.to_string()),
-                        "can_delegate_to": agent.can_delegate_to,
-                        "reports_to": agent.reports_to.map(|id| id.to_string()),
-                        "created_at": agent.created_at.to_rfc3339(),
-                        "last_heartbeat": agent.last_heartbeat.to_rfc3339(),
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                        "acquire_count": row.acquire_count,
+                        "tenant_id": lock.tenant_id.to_string(),
                     })
                 })
                 .collect();
 
-            pgrx::JsonB(serde_json::json!(json_agents))
+            pgrx::JsonB(serde_json::json!(json_locks))
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list agents by type: {}", e);
+            pgrx::warning!("CALIBER: Failed to list locks for agent: {}", e);
             pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-/// List all active agents.
-#[pg_extern]
-fn caliber_agent_list_active(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
-                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
-             FROM caliber_agent WHERE status IN ('active', 'idle') AND tenant_id = $1",
-            None,
-            &[pgrx_uuid_datum(tenant_id)],
-        );
+// ============================================================================
+// NOTIFY-BASED MESSAGE PASSING (Task 12.5)
+// ============================================================================
 
-        match result {
-            Ok(table) => {
-                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
-                    serde_json::json!({
-                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "agent_type": row.get::<String>(2).ok().flatten(),
-                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
-                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
-                        "status": row.get::<String>(5).ok().flatten(),
-                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
-                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
-                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
-                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    })
-                }).collect();
-                pgrx::JsonB(serde_json::json!(agents))
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to list active agents: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
-            }
-        }
+/// Postgres rejects NOTIFY payloads of 8000 bytes or more.
+const NOTIFY_PAYLOAD_MAX_BYTES: usize = 7999;
+
+/// Build the NOTIFY payload for a sent message so listeners can triage
+/// critical messages and interrupts without fetching them first.
+/// Falls back to the bare message ID if the JSON would exceed the limit.
+fn message_notify_payload(message_id: MessageId, message_type: &str, priority: &str) -> String {
+    let payload = serde_json::json!({
+        "message_id": message_id.to_string(),
+        "message_type": message_type,
+        "priority": priority,
     })
+    .to_string();
+
+    if payload.len() > NOTIFY_PAYLOAD_MAX_BYTES {
+        message_id.to_string()
+    } else {
+        payload
+    }
 }
 
-/// List agents by type with tenant isolation.
-#[pg_extern]
-fn caliber_agent_list_by_type_and_tenant(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
-                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
-             FROM caliber_agent
-             WHERE agent_type = $1 AND tenant_id = $2",
-            None,
-            &[
-                text_datum(agent_type),
-                pgrx_uuid_datum(tenant_id),
-            ],
-        );
+/// Channel notified for messages addressed to neither an agent nor an agent type.
+const AGENT_BROADCAST_CHANNEL: &str = "caliber_agent_broadcast";
 
-        match result {
-            Ok(table) => {
-                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
-                    serde_json::json!({
-                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "agent_type": row.get::<String>(2).ok().flatten(),
-                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
-                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
-                        "status": row.get::<String>(5).ok().flatten(),
-                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
-                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
-                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
-                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    })
-                }).collect();
-                pgrx::JsonB(serde_json::json!(agents))
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to list agents by type: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
-            }
-        }
-    })
+/// NOTIFY channel for messages addressed to a specific agent.
+fn agent_channel(agent_id: AgentId) -> String {
+    format!("caliber_agent_{}", agent_id)
 }
 
-/// List active agents for a tenant.
-#[pg_extern]
-fn caliber_agent_list_active_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
-                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
-             FROM caliber_agent
-             WHERE status IN ('active', 'idle') AND tenant_id = $1",
-            None,
-            &[pgrx_uuid_datum(tenant_id)],
-        );
-
-        match result {
-            Ok(table) => {
-                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
-                    serde_json::json!({
-                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "agent_type": row.get::<String>(2).ok().flatten(),
-                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
-                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
-                        "status": row.get::<String>(5).ok().flatten(),
-                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
-                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
-                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
-                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    })
-                }).collect();
-                pgrx::JsonB(serde_json::json!(agents))
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to list active agents: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
-            }
-        }
-    })
+/// NOTIFY channel for messages addressed to every agent of a type.
+fn agent_type_channel(agent_type: &str) -> String {
+    format!("caliber_agent_type_{}", agent_type)
 }
 
-/// List all agents for a tenant.
-#[pg_extern]
-fn caliber_agent_list_all_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
-                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
-             FROM caliber_agent
-             WHERE tenant_id = $1",
-            None,
-            &[pgrx_uuid_datum(tenant_id)],
-        );
+/// Longest channel name pg_notify accepts (NAMEDATALEN - 1 bytes).
+const NOTIFY_CHANNEL_MAX_BYTES: usize = 63;
 
-        match result {
-            Ok(table) => {
-                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
-                    serde_json::json!({
-                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "agent_type": row.get::<String>(2).ok().flatten(),
-                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
-                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
-                        "status": row.get::<String>(5).ok().flatten(),
-                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
-                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
-                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
-                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    })
-                }).collect();
-                pgrx::JsonB(serde_json::json!(agents))
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to list agents: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
-            }
-        }
-    })
+/// Validate that messages to `agent_type` can be notified.
+///
+/// pg_notify raises an error for over-long channel names, which would abort
+/// the sending transaction, so such agent types are rejected up front.
+fn validate_message_agent_type(field: String, agent_type: &str) -> Result<(), ValidationError> {
+    let channel = agent_type_channel(agent_type);
+    if channel.len() > NOTIFY_CHANNEL_MAX_BYTES {
+        return Err(ValidationError::InvalidValue {
+            field,
+            reason: format!(
+                "too long: channel '{}' exceeds {} bytes",
+                channel, NOTIFY_CHANNEL_MAX_BYTES
+            ),
+        });
+    }
+    Ok(())
 }
 
-// ============================================================================
-// DELEGATION OPERATIONS (Task 12.6)
-// ============================================================================
-
-/// Create a task delegation.
+/// Canonical NOTIFY channel for messages sent to `agent_id`.
+///
+/// The name contains hyphens, so clients must quote it: `LISTEN "<channel>"`.
+/// Messages without a recipient go to the `caliber_agent_broadcast` channel.
 #[pg_extern]
-fn caliber_delegation_create(
-    delegator_agent_id: pgrx::Uuid,
-    delegatee_agent_id: Option<pgrx::Uuid>,
-    delegatee_agent_type: Option<&str>,
-    task_description: &str,
-    parent_trajectory_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
-    let delegator = id_from_pgrx::<AgentId>(delegator_agent_id);
-    let parent_traj = id_from_pgrx::<TrajectoryId>(parent_trajectory_id);
-    let delegatee = opt_id_from_pgrx::<AgentId>(delegatee_agent_id);
+fn caliber_agent_channel(agent_id: pgrx::Uuid) -> String {
+    agent_channel(id_from_pgrx::<AgentId>(agent_id))
+}
 
-    // Generate a new delegation ID
-    let delegation_id = DelegationId::now_v7();
+/// Canonical NOTIFY channel for messages sent to all agents of `agent_type`.
+///
+/// Quote the name in `LISTEN`, since agent types are free text.
+#[pg_extern]
+fn caliber_agent_type_channel(agent_type: &str) -> String {
+    agent_type_channel(agent_type)
+}
 
-    // Use direct heap operations instead of SPI
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+/// Send pg_notify for real-time delivery of a newly stored message.
+///
+/// The channel is chosen from to_agent_id, then to_agent_type, falling back
+/// to the broadcast channel. Notification failures are logged, not raised.
+fn notify_message_sent(
+    message_id: MessageId,
+    to_agent: Option<AgentId>,
+    to_agent_type: Option<&str>,
+    message_type: &str,
+    priority: &str,
+) {
+    let channel = if let Some(agent_id) = to_agent {
+        agent_channel(agent_id)
+    } else if let Some(agent_type) = to_agent_type {
+        agent_type_channel(agent_type)
+    } else {
+        AGENT_BROADCAST_CHANNEL.to_string()
+    };
 
-    let result = delegation_heap::delegation_create_heap(delegation_heap::DelegationCreateParams {
-        delegation_id,
-        delegator_agent_id: delegator,
-        delegatee_agent_id: delegatee,
-        delegatee_agent_type,
-        task_description,
-        parent_trajectory_id: parent_traj,
-        child_trajectory_id: None,
-        shared_artifacts: &[],
-        shared_notes: &[],
-        additional_context: None,
-        constraints: None,
-        deadline: None,
-        tenant_id: tenant_uuid,
+    // Bind channel and payload: to_agent_type is free text and must
+    // never be spliced into the statement
+    let payload = message_notify_payload(message_id, message_type, priority);
+    let notify_result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        client.update(
+            "SELECT pg_notify($1, $2)",
+            None,
+            &[text_datum(&channel), text_datum(&payload)],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(())
     });
 
-    if let Err(e) = result {
-        pgrx::warning!("CALIBER: Failed to insert delegation: {}", e);
+    if let Err(e) = notify_result {
+        pgrx::warning!("CALIBER: pg_notify failed: {}", e);
     }
-
-    pgrx_uuid_from_id(delegation_id)
 }
 
-// Get a delegation by ID.
-caliber_pg_get!(delegation, delegation_heap, DelegationId, |row| {
-    let d = row.delegation;
-    serde_json::json!({
-        "delegation_id": d.delegation_id.to_string(),
-        "delegator_agent_id": d.delegator_agent_id.to_string(),
-        "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
-        "delegatee_agent_type": d.delegatee_agent_type,
-        "task_description": d.task_description,
-        "parent_trajectory_id": d.parent_trajectory_id.to_string(),
-        "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
-        "shared_artifacts": d.shared_artifacts.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
-        "shared_notes": d.shared_notes.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
-        "additional_context": d.additional_context,
-        "constraints": d.constraints,
-        "deadline": d.deadline.map(|dt| dt.to_rfc3339()),
-        "status": match d.status {
-            DelegationStatus::Pending => "pending",
-            DelegationStatus::Accepted => "accepted",
-            DelegationStatus::Rejected => "rejected",
-            DelegationStatus::InProgress => "in_progress",
-            DelegationStatus::Completed => "completed",
-            DelegationStatus::Failed => "failed",
-        },
-        "result": d.result.as_ref().map(safe_to_json),
-        "created_at": d.created_at.to_rfc3339(),
-        "accepted_at": d.accepted_at.map(|dt| dt.to_rfc3339()),
-        "completed_at": d.completed_at.map(|dt| dt.to_rfc3339()),
-        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-    })
-});
-
-/// Accept a delegation.
-///
-/// Records the accepting agent and the child trajectory created for the delegated work.
-#[pg_extern]
-fn caliber_delegation_accept(
-    delegation_id: pgrx::Uuid,
-    delegatee_agent_id: pgrx::Uuid,
-    child_trajectory_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
-) -> bool {
-    let entity_id = id_from_pgrx::<DelegationId>(delegation_id);
-    let agent_id = id_from_pgrx::<AgentId>(delegatee_agent_id);
-    let traj_id = id_from_pgrx::<TrajectoryId>(child_trajectory_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+/// Valid `message_type` values, for validation messages.
+const MESSAGE_TYPE_VALUES: &str = "task_delegation, task_result, context_request, context_share, coordination_signal, handoff, interrupt, heartbeat";
+
+/// Valid `priority` values, for validation messages.
+const MESSAGE_PRIORITY_VALUES: &str = "low, normal, high, critical";
+
+/// Parse a message type name as accepted by the message functions.
+fn message_type_from_str(message_type: &str) -> Option<MessageType> {
+    match message_type {
+        "task_delegation" => Some(MessageType::TaskDelegation),
+        "task_result" => Some(MessageType::TaskResult),
+        "context_request" => Some(MessageType::ContextRequest),
+        "context_share" => Some(MessageType::ContextShare),
+        "coordination_signal" => Some(MessageType::CoordinationSignal),
+        "handoff" => Some(MessageType::Handoff),
+        "interrupt" => Some(MessageType::Interrupt),
+        "heartbeat" => Some(MessageType::Heartbeat),
+        _ => None,
+    }
+}
 
-    // Use direct heap operations - pass all parameters
-    match delegation_heap::delegation_accept_heap(entity_id, agent_id, traj_id, tenant_uuid) {
-        Ok(updated) => updated,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to accept delegation: {}", e);
-            false
-        }
+/// Parse a message priority name as accepted by the message functions.
+fn message_priority_from_str(priority: &str) -> Option<MessagePriority> {
+    match priority {
+        "low" => Some(MessagePriority::Low),
+        "normal" => Some(MessagePriority::Normal),
+        "high" => Some(MessagePriority::High),
+        "critical" => Some(MessagePriority::Critical),
+        _ => None,
     }
 }
 
-/// Complete a delegation.
+/// Send a message to an agent.
+/// Send a message between agents using direct heap operations.
+/// Returns None if message_type or priority is invalid, or if to_agent_type
+/// is too long for a NOTIFY channel name.
+#[allow(clippy::too_many_arguments)]
 #[pg_extern]
-fn caliber_delegation_complete(
-    delegation_id: pgrx::Uuid,
-    success: bool,
-    summary: &str,
+fn caliber_message_send(
+    from_agent_id: pgrx::Uuid,
+    to_agent_id: Option<pgrx::Uuid>,
+    to_agent_type: Option<&str>,
+    message_type: &str,
+    payload: &str,
+    trajectory_id: Option<pgrx::Uuid>,
+    scope_id: Option<pgrx::Uuid>,
+    artifact_ids: Vec<pgrx::Uuid>,
+    priority: &str,
+    expires_at: Option<i64>,
     tenant_id: pgrx::Uuid,
-) -> bool {
-    let entity_id = id_from_pgrx::<DelegationId>(delegation_id);
+) -> Option<pgrx::Uuid> {
+    let from_agent = id_from_pgrx::<AgentId>(from_agent_id);
+    let to_agent = opt_id_from_pgrx::<AgentId>(to_agent_id);
+    let traj_id = trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+    let scp_id = scope_id.map(id_from_pgrx::<ScopeId>);
+    let artifact_ids: Vec<ArtifactId> = artifact_ids
+        .into_iter()
+        .map(id_from_pgrx::<ArtifactId>)
+        .collect();
+    let expires_at =
+        expires_at.and_then(|ts| chrono::DateTime::<chrono::Utc>::from_timestamp(ts, 0));
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Build DelegationResult from parameters
-    let result = if success {
-        DelegationResult {
-            status: DelegationResultStatus::Success,
-            produced_artifacts: vec![],
-            produced_notes: vec![],
-            summary: summary.to_string(),
-            error: None,
-        }
-    } else {
-        DelegationResult {
-            status: DelegationResultStatus::Failure,
-            produced_artifacts: vec![],
-            produced_notes: vec![],
-            summary: String::new(),
-            error: Some(summary.to_string()),
+    // Validate and convert message_type
+    let msg_type = match message_type_from_str(message_type) {
+        Some(t) => t,
+        None => {
+            pgrx::warning!(
+                "CALIBER: Invalid message_type '{}'. Valid values: {}",
+                message_type,
+                MESSAGE_TYPE_VALUES
+            );
+            return None;
         }
     };
 
-    // Use direct heap operations instead of SPI
-    match delegation_heap::delegation_complete_heap(entity_id, &result, tenant_uuid) {
-        Ok(updated) => updated,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to complete delegation: {}", e);
-            false
+    // Validate and convert priority
+    let msg_priority = match message_priority_from_str(priority) {
+        Some(p) => p,
+        None => {
+            pgrx::warning!(
+                "CALIBER: Invalid priority '{}'. Valid values: {}",
+                priority,
+                MESSAGE_PRIORITY_VALUES
+            );
+            return None;
+        }
+    };
+
+    // Validate to_agent_type yields a usable NOTIFY channel (REQ-12)
+    if let Some(agent_type) = to_agent_type {
+        if let Err(validation_err) =
+            validate_message_agent_type("to_agent_type".to_string(), agent_type)
+        {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return None;
         }
     }
-}
 
-/// List pending delegations for an agent type.
-#[pg_extern]
-fn caliber_delegation_list_pending(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let message_id = MessageId::now_v7();
+
     // Use direct heap operations instead of SPI
-    match delegation_heap::delegation_list_pending_heap(tenant_uuid) {
-        Ok(delegations) => {
-            // Filter by agent type (matching agent_type or wildcard "*")
-            let filtered: Vec<serde_json::Value> = delegations
-                .into_iter()
-                .filter(|row| {
-                    row.delegation.delegatee_agent_type.as_deref() == Some(agent_type)
-                        || row.delegation.delegatee_agent_type.as_deref() == Some("*")
-                })
-                .map(|row| {
-                    let d = row.delegation;
-                    serde_json::json!({
-                        "delegation_id": d.delegation_id.to_string(),
-                        "delegator_agent_id": d.delegator_agent_id.to_string(),
-                        "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
-                        "delegatee_agent_type": d.delegatee_agent_type,
-                        "task_description": d.task_description,
-                        "parent_trajectory_id": d.parent_trajectory_id.to_string(),
-                        "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
-                        "status": "pending",
-                        "created_at": d.created_at.to_rfc3339(),
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-                    })
-                })
-                .collect();
+    let result = message_heap::message_send_heap(message_heap::MessageSendParams {
+        message_id,
+        from_agent_id: from_agent,
+        to_agent_id: to_agent,
+        to_agent_type,
+        message_type: msg_type,
+        payload,
+        trajectory_id: traj_id,
+        scope_id: scp_id,
+        artifact_ids: &artifact_ids,
+        priority: msg_priority,
+        expires_at,
+        tenant_id: tenant_uuid,
+        reply_to: None,
+    });
 
-            pgrx::JsonB(serde_json::json!(filtered))
+    match result {
+        Ok(_) => {
+            audit_record(
+                EntityType::Message,
+                message_id,
+                "send",
+                Some(from_agent),
+                tenant_uuid,
+            );
+            notify_message_sent(message_id, to_agent, to_agent_type, message_type, priority);
+            Some(pgrx_uuid_from_id(message_id))
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list pending delegations: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: Failed to send message: {}", e);
+            None
         }
     }
 }
 
-// ============================================================================
-// HANDOFF OPERATIONS (Task 12.6)
-// ============================================================================
-
-/// Create an agent handoff.
-#[allow(clippy::too_many_arguments)]
+/// Reply to a message.
+///
+/// The reply is addressed to the original sender, inherits the original
+/// message's trajectory and scope, and records the original in `reply_to`.
+/// Returns None if the original message does not exist or if message_type
+/// or priority is invalid.
 #[pg_extern]
-fn caliber_handoff_create(
+fn caliber_message_reply(
+    original_message_id: pgrx::Uuid,
     from_agent_id: pgrx::Uuid,
-    to_agent_id: Option<pgrx::Uuid>,
-    to_agent_type: Option<&str>,
-    trajectory_id: pgrx::Uuid,
-    scope_id: pgrx::Uuid,
-    context_snapshot_id: pgrx::Uuid,
-    reason: &str,
+    message_type: &str,
+    payload: &str,
+    priority: &str,
     tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
+) -> Option<pgrx::Uuid> {
+    let original_id = id_from_pgrx::<MessageId>(original_message_id);
     let from_agent = id_from_pgrx::<AgentId>(from_agent_id);
-    let to_agent = opt_id_from_pgrx::<AgentId>(to_agent_id);
-    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
-    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
-    let snapshot_id = id_from_pgrx::<ArtifactId>(context_snapshot_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    let handoff_reason = match reason {
-        "capability_mismatch" => HandoffReason::CapabilityMismatch,
-        "load_balancing" => HandoffReason::LoadBalancing,
-        "specialization" => HandoffReason::Specialization,
-        "escalation" => HandoffReason::Escalation,
-        "timeout" => HandoffReason::Timeout,
-        "failure" => HandoffReason::Failure,
-        _ => {
-            if reason != "scheduled" {
-                pgrx::warning!(
-                    "CALIBER: Unknown handoff reason '{}', defaulting to Scheduled",
-                    reason
-                );
-            }
-            HandoffReason::Scheduled
+    // Validate and convert message_type
+    let msg_type = match message_type_from_str(message_type) {
+        Some(t) => t,
+        None => {
+            pgrx::warning!(
+                "CALIBER: Invalid message_type '{}'. Valid values: {}",
+                message_type,
+                MESSAGE_TYPE_VALUES
+            );
+            return None;
         }
     };
 
-    let handoff_id = HandoffId::now_v7();
+    // Validate and convert priority
+    let msg_priority = match message_priority_from_str(priority) {
+        Some(p) => p,
+        None => {
+            pgrx::warning!(
+                "CALIBER: Invalid priority '{}'. Valid values: {}",
+                priority,
+                MESSAGE_PRIORITY_VALUES
+            );
+            return None;
+        }
+    };
 
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let original = match message_heap::message_get_heap(original_id, tenant_uuid) {
+        Ok(Some(row)) => row.message,
+        Ok(None) => {
+            pgrx::warning!("CALIBER: Message {} not found for reply", original_id);
+            return None;
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get message {}: {}", original_id, e);
+            return None;
+        }
+    };
 
-    // Insert via direct heap operations
-    match handoff_heap::handoff_create_heap(handoff_heap::HandoffCreateParams {
-        handoff_id,
+    let message_id = MessageId::now_v7();
+    let to_agent = Some(original.from_agent_id);
+
+    let result = message_heap::message_send_heap(message_heap::MessageSendParams {
+        message_id,
         from_agent_id: from_agent,
         to_agent_id: to_agent,
-        to_agent_type,
-        trajectory_id: traj_id,
-        scope_id: scp_id,
-        context_snapshot_id: snapshot_id,
-        handoff_notes: "",
-        next_steps: &[],
-        blockers: &[],
-        open_questions: &[],
-        reason: handoff_reason,
+        to_agent_type: None,
+        message_type: msg_type,
+        payload,
+        trajectory_id: original.trajectory_id,
+        scope_id: original.scope_id,
+        artifact_ids: &[],
+        priority: msg_priority,
+        expires_at: None,
         tenant_id: tenant_uuid,
-    }) {
-        Ok(_) => pgrx_uuid_from_id(handoff_id),
+        reply_to: Some(original_id),
+    });
+
+    match result {
+        Ok(_) => {
+            audit_record(
+                EntityType::Message,
+                message_id,
+                "reply",
+                Some(from_agent),
+                tenant_uuid,
+            );
+            notify_message_sent(message_id, to_agent, None, message_type, priority);
+            Some(pgrx_uuid_from_id(message_id))
+        }
         Err(e) => {
-            pgrx::error!("CALIBER: Failed to insert handoff: {}", e);
+            pgrx::warning!("CALIBER: Failed to send reply: {}", e);
+            None
         }
     }
 }
 
-// Get a handoff by ID.
-caliber_pg_get!(handoff, handoff_heap, HandoffId, |row| {
-    let h = row.handoff;
-    serde_json::json!({
-        "handoff_id": h.handoff_id.to_string(),
-        "from_agent_id": h.from_agent_id.to_string(),
-        "to_agent_id": h.to_agent_id.map(|id| id.to_string()),
-        "to_agent_type": h.to_agent_type,
-        "trajectory_id": h.trajectory_id.to_string(),
-        "scope_id": h.scope_id.to_string(),
-        "context_snapshot_id": h.context_snapshot_id.map(|id| id.to_string()),
-        "handoff_notes": h.handoff_notes,
-        "next_steps": h.next_steps,
-        "blockers": h.blockers,
-        "open_questions": h.open_questions,
-        "status": match h.status {
-            HandoffStatus::Initiated => "initiated",
-            HandoffStatus::Accepted => "accepted",
-            HandoffStatus::Completed => "completed",
-            HandoffStatus::Rejected => "rejected",
-        },
-        "reason": match h.reason {
-            HandoffReason::CapabilityMismatch => "capability_mismatch",
-            HandoffReason::LoadBalancing => "load_balancing",
-            HandoffReason::Specialization => "specialization",
-            HandoffReason::Escalation => "escalation",
-            HandoffReason::Timeout => "timeout",
-            HandoffReason::Failure => "failure",
-            HandoffReason::Scheduled => "scheduled",
-        },
-        "initiated_at": h.initiated_at.to_rfc3339(),
-        "accepted_at": h.accepted_at.map(|t| t.to_rfc3339()),
-        "completed_at": h.completed_at.map(|t| t.to_rfc3339()),
-        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-    })
-});
+/// One validated entry of a `caliber_message_send_batch` call.
+struct BatchMessage {
+    to_agent_id: Option<AgentId>,
+    to_agent_type: Option<String>,
+    message_type: MessageType,
+    message_type_name: String,
+    payload: String,
+    trajectory_id: Option<TrajectoryId>,
+    scope_id: Option<ScopeId>,
+    artifact_ids: Vec<ArtifactId>,
+    priority: MessagePriority,
+    priority_name: String,
+    expires_at: Option<chrono::DateTime<chrono::Utc>>,
+}
 
-/// Accept a handoff.
+/// Validate every message spec of a batch before anything is inserted.
 ///
-/// Records the agent accepting the handoff and updates the handoff status.
+/// Specs take the `caliber_message_send` arguments as JSON fields;
+/// `message_type` and `payload` are required, `priority` defaults to "normal"
+/// and `expires_at` is Unix seconds. Errors name the offending entry's index.
+fn parse_message_batch(messages: &serde_json::Value) -> CaliberResult<Vec<BatchMessage>> {
+    let invalid = |field: String, reason: String| {
+        CaliberError::Validation(ValidationError::InvalidValue { field, reason })
+    };
+    let specs = messages
+        .as_array()
+        .ok_or_else(|| invalid("messages".to_string(), "expected a JSON array".to_string()))?;
+
+    specs
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            let field = |name: &str| format!("messages[{}].{}", i, name);
+            let text = |name: &str| spec.get(name).and_then(|v| v.as_str());
+            let uuid = |name: &str| -> CaliberResult<Option<Uuid>> {
+                match spec.get(name) {
+                    None | Some(serde_json::Value::Null) => Ok(None),
+                    Some(v) => v
+                        .as_str()
+                        .and_then(|s| Uuid::parse_str(s).ok())
+                        .map(Some)
+                        .ok_or_else(|| invalid(field(name), format!("invalid UUID {}", v))),
+                }
+            };
+
+            let message_type_name = text("message_type").unwrap_or_default();
+            let message_type = message_type_from_str(message_type_name).ok_or_else(|| {
+                invalid(
+                    field("message_type"),
+                    format!(
+                        "unknown value '{}'. Valid values: {}",
+                        message_type_name, MESSAGE_TYPE_VALUES
+                    ),
+                )
+            })?;
+
+            let priority_name = text("priority").unwrap_or("normal");
+            let priority = message_priority_from_str(priority_name).ok_or_else(|| {
+                invalid(
+                    field("priority"),
+                    format!(
+                        "unknown value '{}'. Valid values: {}",
+                        priority_name, MESSAGE_PRIORITY_VALUES
+                    ),
+                )
+            })?;
+
+            let payload = text("payload")
+                .ok_or_else(|| invalid(field("payload"), "expected a string".to_string()))?;
+
+            let artifact_ids = match spec.get("artifact_ids") {
+                None | Some(serde_json::Value::Null) => Vec::new(),
+                Some(v) => serde_json::from_value::<Vec<Uuid>>(v.clone())
+                    .map_err(|e| invalid(field("artifact_ids"), e.to_string()))?
+                    .into_iter()
+                    .map(ArtifactId::new)
+                    .collect(),
+            };
+
+            let expires_at = match spec.get("expires_at") {
+                None | Some(serde_json::Value::Null) => None,
+                Some(v) => Some(
+                    v.as_i64()
+                        .and_then(|ts| chrono::DateTime::<chrono::Utc>::from_timestamp(ts, 0))
+                        .ok_or_else(|| {
+                            invalid(field("expires_at"), format!("invalid timestamp {}", v))
+                        })?,
+                ),
+            };
+
+            let to_agent_type = text("to_agent_type");
+            if let Some(agent_type) = to_agent_type {
+                validate_message_agent_type(field("to_agent_type"), agent_type)
+                    .map_err(CaliberError::Validation)?;
+            }
+
+            Ok(BatchMessage {
+                to_agent_id: uuid("to_agent_id")?.map(AgentId::new),
+                to_agent_type: to_agent_type.map(str::to_string),
+                message_type,
+                message_type_name: message_type_name.to_string(),
+                payload: payload.to_string(),
+                trajectory_id: uuid("trajectory_id")?.map(TrajectoryId::new),
+                scope_id: uuid("scope_id")?.map(ScopeId::new),
+                artifact_ids,
+                priority,
+                priority_name: priority_name.to_string(),
+                expires_at,
+            })
+        })
+        .collect()
+}
+
+/// Send several messages from one agent in a single call.
+///
+/// `messages` is a JSON array of message specs (see `parse_message_batch`).
+/// Every spec is validated first, so one invalid entry rejects the whole
+/// batch and returns an empty array. A storage failure mid-batch raises an
+/// error, aborting the transaction so no partial batch is committed.
+/// Returns the created message IDs in input order; each message gets the
+/// same NOTIFY as `caliber_message_send`, delivered on commit.
 #[pg_extern]
-fn caliber_handoff_accept(
-    handoff_id: pgrx::Uuid,
-    accepting_agent_id: pgrx::Uuid,
+fn caliber_message_send_batch(
+    from_agent_id: pgrx::Uuid,
+    messages: pgrx::JsonB,
     tenant_id: pgrx::Uuid,
-) -> bool {
-    let id = id_from_pgrx::<HandoffId>(handoff_id);
-    let agent_id = id_from_pgrx::<AgentId>(accepting_agent_id);
+) -> pgrx::JsonB {
+    let from_agent = id_from_pgrx::<AgentId>(from_agent_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Use direct heap operations - pass accepting agent ID
-    match handoff_heap::handoff_accept_heap(id, agent_id, tenant_uuid) {
-        Ok(updated) => updated,
+    let batch = match parse_message_batch(&messages.0) {
+        Ok(batch) => batch,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to accept handoff: {}", e);
-            false
+            pgrx::warning!("CALIBER: Rejected message batch: {}", e);
+            return pgrx::JsonB(serde_json::json!([]));
         }
-    }
-}
+    };
 
-/// Complete a handoff.
-#[pg_extern]
-fn caliber_handoff_complete(handoff_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    let id = id_from_pgrx::<HandoffId>(handoff_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let mut message_ids = Vec::with_capacity(batch.len());
+    for msg in &batch {
+        let message_id = MessageId::now_v7();
+        let result = message_heap::message_send_heap(message_heap::MessageSendParams {
+            message_id,
+            from_agent_id: from_agent,
+            to_agent_id: msg.to_agent_id,
+            to_agent_type: msg.to_agent_type.as_deref(),
+            message_type: msg.message_type,
+            payload: &msg.payload,
+            trajectory_id: msg.trajectory_id,
+            scope_id: msg.scope_id,
+            artifact_ids: &msg.artifact_ids,
+            priority: msg.priority,
+            expires_at: msg.expires_at,
+            tenant_id: tenant_uuid,
+            reply_to: None,
+        });
 
-    match handoff_heap::handoff_complete_heap(id, tenant_uuid) {
-        Ok(updated) => updated,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to complete handoff: {}", e);
-            false
+        if let Err(e) = result {
+            pgrx::error!("CALIBER: Failed to send message batch: {}", e);
         }
+        audit_record(
+            EntityType::Message,
+            message_id,
+            "send",
+            Some(from_agent),
+            tenant_uuid,
+        );
+
+        notify_message_sent(
+            message_id,
+            msg.to_agent_id,
+            msg.to_agent_type.as_deref(),
+            &msg.message_type_name,
+            &msg.priority_name,
+        );
+        message_ids.push(message_id.to_string());
     }
-}
 
-// ============================================================================
-// CONFLICT OPERATIONS (Task 12.6)
-// ============================================================================
+    pgrx::JsonB(serde_json::json!(message_ids))
+}
 
-/// Create a conflict record.
-#[pg_extern]
-fn caliber_conflict_create(
-    conflict_type: &str,
-    item_a_type: &str,
-    item_a_id: pgrx::Uuid,
-    item_b_type: &str,
-    item_b_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
-) -> pgrx::Uuid {
-    let a_id = Uuid::from_bytes(*item_a_id.as_bytes());
-    let b_id = Uuid::from_bytes(*item_b_id.as_bytes());
-
-    let c_type = match conflict_type {
-        "concurrent_write" => ConflictType::ConcurrentWrite,
-        "contradicting_fact" => ConflictType::ContradictingFact,
-        "incompatible_decision" => ConflictType::IncompatibleDecision,
-        "resource_contention" => ConflictType::ResourceContention,
-        _ => {
-            if conflict_type != "goal_conflict" {
-                pgrx::warning!(
-                    "CALIBER: Unknown conflict type '{}', defaulting to GoalConflict",
-                    conflict_type
-                );
-            }
-            ConflictType::GoalConflict
-        }
-    };
-
-    let conflict = Conflict::new(c_type, item_a_type, a_id, item_b_type, b_id);
-    let conflict_id = conflict.conflict_id;
-
-    // Insert via direct heap operations (NO SQL)
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-
-    match conflict_heap::conflict_create_heap(conflict_heap::ConflictCreateParams {
-        conflict_id,
-        conflict_type: c_type,
-        item_a_type,
-        item_a_id: a_id,
-        item_b_type,
-        item_b_id: b_id,
-        agent_a_id: None,
-        agent_b_id: None,
-        trajectory_id: None,
-        tenant_id: tenant_uuid,
-    }) {
-        Ok(_) => {}
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to insert conflict: {}", e);
-        }
-    }
-
-    pgrx_uuid_from_id(conflict_id)
-}
-
-// Get a conflict by ID.
-caliber_pg_get!(conflict, conflict_heap, ConflictId, |row| {
-    let c = row.conflict;
+// Get a message by ID using direct heap operations.
+caliber_pg_get!(message, message_heap, MessageId, |row| {
+    let m = row.message;
     serde_json::json!({
-        "conflict_id": c.conflict_id.to_string(),
-        "conflict_type": match c.conflict_type {
-            ConflictType::ConcurrentWrite => "concurrent_write",
-            ConflictType::ContradictingFact => "contradicting_fact",
-            ConflictType::IncompatibleDecision => "incompatible_decision",
-            ConflictType::ResourceContention => "resource_contention",
-            ConflictType::GoalConflict => "goal_conflict",
+        "message_id": m.message_id.to_string(),
+        "from_agent_id": m.from_agent_id.to_string(),
+        "to_agent_id": m.to_agent_id.map(|id| id.to_string()),
+        "to_agent_type": m.to_agent_type,
+        "message_type": match m.message_type {
+            MessageType::TaskDelegation => "task_delegation",
+            MessageType::TaskResult => "task_result",
+            MessageType::ContextRequest => "context_request",
+            MessageType::ContextShare => "context_share",
+            MessageType::CoordinationSignal => "coordination_signal",
+            MessageType::Handoff => "handoff",
+            MessageType::Interrupt => "interrupt",
+            MessageType::Heartbeat => "heartbeat",
         },
-        "item_a_type": c.item_a_type,
-        "item_a_id": c.item_a_id.to_string(),
-        "item_b_type": c.item_b_type,
-        "item_b_id": c.item_b_id.to_string(),
-        "agent_a_id": c.agent_a_id.map(|id| id.to_string()),
-        "agent_b_id": c.agent_b_id.map(|id| id.to_string()),
-        "trajectory_id": c.trajectory_id.map(|id| id.to_string()),
-        "status": match c.status {
-            ConflictStatus::Detected => "detected",
-            ConflictStatus::Resolving => "resolving",
-            ConflictStatus::Resolved => "resolved",
-            ConflictStatus::Escalated => "escalated",
+        "payload": m.payload,
+        "trajectory_id": m.trajectory_id.map(|id| id.to_string()),
+        "scope_id": m.scope_id.map(|id| id.to_string()),
+        "artifact_ids": m.artifact_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        "created_at": m.created_at.to_rfc3339(),
+        "delivered_at": m.delivered_at.map(|t| t.to_rfc3339()),
+        "acknowledged_at": m.acknowledged_at.map(|t| t.to_rfc3339()),
+        "priority": match m.priority {
+            MessagePriority::Low => "low",
+            MessagePriority::Normal => "normal",
+            MessagePriority::High => "high",
+            MessagePriority::Critical => "critical",
         },
-        "resolution": c.resolution.as_ref().map(safe_to_json),
-        "detected_at": c.detected_at.to_rfc3339(),
-        "resolved_at": c.resolved_at.map(|t| t.to_rfc3339()),
+        "expires_at": m.expires_at.map(|t| t.to_rfc3339()),
         "tenant_id": row.tenant_id.map(|id| id.to_string()),
+        "reply_to": row.reply_to.map(|id| id.to_string()),
     })
 });
 
-/// Resolve a conflict.
+/// Mark a message as delivered.
 #[pg_extern]
-fn caliber_conflict_resolve(
-    conflict_id: pgrx::Uuid,
-    strategy: &str,
-    winner: Option<&str>,
-    reason: &str,
-    tenant_id: pgrx::Uuid,
-) -> bool {
-    use caliber_core::ConflictResolutionRecord;
-
-    let id = id_from_pgrx::<ConflictId>(conflict_id);
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+fn caliber_message_mark_delivered(message_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
 
-    // Parse strategy
-    let resolution_strategy = match strategy {
-        "last_write_wins" => ResolutionStrategy::LastWriteWins,
-        "first_write_wins" => ResolutionStrategy::FirstWriteWins,
-        "highest_confidence" => ResolutionStrategy::HighestConfidence,
-        "merge" => ResolutionStrategy::Merge,
-        "escalate" => ResolutionStrategy::Escalate,
-        "reject_both" => ResolutionStrategy::RejectBoth,
-        _ => {
-            pgrx::warning!(
-                "CALIBER: Unknown resolution strategy '{}', defaulting to Escalate",
-                strategy
-            );
-            ResolutionStrategy::Escalate
+    let mid = Uuid::from_bytes(*message_id.as_bytes());
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
         }
     };
+    let pg_mid = pgrx::Uuid::from_bytes(*mid.as_bytes());
 
-    let resolution = ConflictResolutionRecord {
-        strategy: resolution_strategy,
-        winner: winner.map(|s| s.to_string()),
-        merged_result_id: None,
-        reason: reason.to_string(),
-        resolved_by: None,
-    };
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_mid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let table = client.update(
+            "UPDATE caliber_message SET delivered_at = $1 WHERE message_id = $2 AND tenant_id = $3 AND delivered_at IS NULL",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
 
-    // Resolve via direct heap operations (NO SQL)
-    match conflict_heap::conflict_resolve_heap(id, &resolution, tenant_uuid) {
-        Ok(updated) => updated,
+    match result {
+        Ok(len) => len > 0,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to resolve conflict: {}", e);
+            // Use EntityType::Message for proper error categorization
+            let storage_err = StorageError::UpdateFailed {
+                entity_type: EntityType::Message,
+                id: mid,
+                reason: format!("mark delivered failed: {}", e),
+            };
+            pgrx::warning!("CALIBER: {:?}", storage_err);
             false
         }
     }
 }
 
-/// List unresolved conflicts.
+/// Mark a message as acknowledged using direct heap operations.
 #[pg_extern]
-fn caliber_conflict_list_unresolved(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+fn caliber_message_mark_acknowledged(message_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let mid = id_from_pgrx::<MessageId>(message_id);
     let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-    // List via direct heap operations (NO SQL)
-    match conflict_heap::conflict_list_pending_heap(tenant_uuid) {
-        Ok(conflicts) => {
-            let conflicts_json: Vec<serde_json::Value> = conflicts
-                .into_iter()
-                .map(|row| {
-                    let conflict = row.conflict;
-                    serde_json::json!({
-                        "conflict_id": conflict.conflict_id.to_string(),
-                        "conflict_type": match conflict.conflict_type {
-                            ConflictType::ConcurrentWrite => "concurrent_write",
-                            ConflictType::ContradictingFact => "contradicting_fact",
-                            ConflictType::IncompatibleDecision => "incompatible_decision",
-                            ConflictType::ResourceContention => "resource_contention",
-                            ConflictType::GoalConflict => "goal_conflict",
-                        },
-                        "item_a_type": conflict.item_a_type,
-                        "item_a_id": conflict.item_a_id.to_string(),
-                        "item_b_type": conflict.item_b_type,
-                        "item_b_id": conflict.item_b_id.to_string(),
-                        "status": match conflict.status {
-                            ConflictStatus::Detected => "detected",
-                            ConflictStatus::Resolving => "resolving",
-                            ConflictStatus::Resolved => "resolved",
-                            ConflictStatus::Escalated => "escalated",
-                        },
-                        "detected_at": conflict.detected_at.to_rfc3339(),
-                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
-                    })
-                })
-                .collect();
-            pgrx::JsonB(serde_json::json!(conflicts_json))
-        }
+
+    // Use direct heap operations instead of SPI
+    match message_heap::message_acknowledge_heap(mid, tenant_uuid) {
+        Ok(updated) => updated,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list unresolved conflicts: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: Failed to acknowledge message: {}", e);
+            false
         }
     }
 }
 
-// ============================================================================
-// VECTOR SEARCH (Task 12.3)
-// ============================================================================
-
-/// Search for similar vectors using pgvector.
-/// Returns entity IDs and similarity scores.
-/// Note: This requires pgvector extension and HNSW indexes to be created.
+/// Get pending messages for an agent using direct heap operations.
+/// Returns messages where delivered_at IS NULL and not expired, ordered by priority.
+/// Note: agent_type is kept for API compatibility but not currently used in filtering.
 #[pg_extern]
-fn caliber_vector_search(query_embedding: pgrx::JsonB, limit: i32) -> pgrx::JsonB {
-    // Parse the query embedding
-    let query: Vec<f32> = match serde_json::from_value(query_embedding.0) {
-        Ok(v) => v,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to parse query embedding: {}", e);
-            return pgrx::JsonB(serde_json::json!([]));
-        }
-    };
+fn caliber_message_get_pending(
+    agent_id: pgrx::Uuid,
+    _agent_type: &str,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let aid = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Convert to pgvector format string: '[1.0, 2.0, 3.0]'
-    let vector_str = format!(
-        "[{}]",
-        query
-            .iter()
-            .map(|f| f.to_string())
-            .collect::<Vec<_>>()
-            .join(",")
-    );
+    // Use direct heap operations to get messages for this agent
+    let messages_result = message_heap::message_list_for_agent_heap(aid, tenant_uuid);
 
-    // Search artifacts and notes using pgvector cosine similarity
-    // Using <=> operator for cosine distance (1 - similarity)
-    let results = Spi::connect(|client| {
-        let result = client.select(
-            &format!(
-                "SELECT entity_id, entity_type, 1 - (embedding <=> '{}'::vector) as similarity
-                 FROM (
-                     SELECT artifact_id as entity_id, 'artifact' as entity_type, embedding 
-                     FROM caliber_artifact WHERE embedding IS NOT NULL
-                     UNION ALL
-                     SELECT note_id as entity_id, 'note' as entity_type, embedding 
-                     FROM caliber_note WHERE embedding IS NOT NULL
-                 ) combined
-                 ORDER BY embedding <=> '{}'::vector
-                 LIMIT {}",
-                vector_str, vector_str, limit
-            ),
-            None,
-            &[],
-        );
+    match messages_result {
+        Ok(messages) => {
+            let now = Utc::now();
 
-        match result {
-            Ok(table) => {
-                let mut results = Vec::new();
-                for row in table {
-                    let entity_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                    let entity_type: Option<String> = row.get(2).ok().flatten();
-                    let similarity: Option<f64> = row.get(3).ok().flatten();
-
-                    if let (Some(eid), Some(etype), Some(sim)) =
-                        (entity_id, entity_type, similarity)
-                    {
-                        results.push(serde_json::json!({
-                            "entity_id": Uuid::from_bytes(*eid.as_bytes()).to_string(),
-                            "entity_type": etype,
-                            "similarity": sim,
-                        }));
-                    }
-                }
-                results
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Vector search failed: {}", e);
-                Vec::new()
-            }
+            // Filter for pending messages (delivered_at IS NULL and not expired)
+            let mut pending: Vec<_> = messages
+                .into_iter()
+                .filter(|row| {
+                    row.message.delivered_at.is_none()
+                        && row
+                            .message
+                            .expires_at
+                            .is_none_or(|expires_at| expires_at > now)
+                })
+                .collect();
+
+            // Sort by priority (critical > high > normal > low) then by created_at
+            pending.sort_by(|a, b| {
+                let priority_order = |p: &MessagePriority| match p {
+                    MessagePriority::Critical => 1,
+                    MessagePriority::High => 2,
+                    MessagePriority::Normal => 3,
+                    MessagePriority::Low => 4,
+                };
+
+                priority_order(&a.message.priority)
+                    .cmp(&priority_order(&b.message.priority))
+                    .then_with(|| a.message.created_at.cmp(&b.message.created_at))
+            });
+
+            // Convert to JSON
+            let json_messages: Vec<serde_json::Value> = pending
+                .into_iter()
+                .map(|row| {
+                    let m = row.message;
+                    serde_json::json!({
+                        "message_id": m.message_id.to_string(),
+                        "from_agent_id": m.from_agent_id.to_string(),
+                        "to_agent_id": m.to_agent_id.map(|id| id.to_string()),
+                        "to_agent_type": m.to_agent_type,
+                        "message_type": match m.message_type {
+                            MessageType::TaskDelegation => "task_delegation",
+                            MessageType::TaskResult => "task_result",
+                            MessageType::ContextRequest => "context_request",
+                            MessageType::ContextShare => "context_share",
+                            MessageType::CoordinationSignal => "coordination_signal",
+                            MessageType::Handoff => "handoff",
+                            MessageType::Interrupt => "interrupt",
+                            MessageType::Heartbeat => "heartbeat",
+                        },
+                        "payload": m.payload,
+                        "trajectory_id": m.trajectory_id.map(|id| id.to_string()),
+                        "scope_id": m.scope_id.map(|id| id.to_string()),
+                        "artifact_ids": m.artifact_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+                        "created_at": m.created_at.to_rfc3339(),
+                        "delivered_at": m.delivered_at.map(|t| t.to_rfc3339()),
+                        "acknowledged_at": m.acknowledged_at.map(|t| t.to_rfc3339()),
+                        "priority": match m.priority {
+                            MessagePriority::Low => "low",
+                            MessagePriority::Normal => "normal",
+                            MessagePriority::High => "high",
+                            MessagePriority::Critical => "critical",
+                        },
+                        "expires_at": m.expires_at.map(|t| t.to_rfc3339()),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                        "reply_to": row.reply_to.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_messages))
         }
-    });
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get pending messages: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
 
-    pgrx::JsonB(serde_json::json!(results))
+/// Column list shared by SPI message queries; pairs with `message_json_from_spi_row`.
+const MESSAGE_SPI_COLUMNS: &str = "message_id, from_agent_id, to_agent_id, to_agent_type, message_type, payload,
+                    trajectory_id, scope_id, artifact_ids, created_at, delivered_at, acknowledged_at,
+                    priority, expires_at, tenant_id, reply_to";
+
+/// Build message JSON from an SPI row selected with `MESSAGE_SPI_COLUMNS`.
+fn message_json_from_spi_row(row: &pgrx::spi::SpiHeapTupleData<'_>) -> serde_json::Value {
+    let message_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+    let from_agent_id: Option<pgrx::Uuid> = row.get(2).ok().flatten();
+    let to_agent_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+    let to_agent_type: Option<String> = row.get(4).ok().flatten();
+    let message_type: Option<String> = row.get(5).ok().flatten();
+    let payload: Option<String> = row.get(6).ok().flatten();
+    let trajectory_id: Option<pgrx::Uuid> = row.get(7).ok().flatten();
+    let scope_id: Option<pgrx::Uuid> = row.get(8).ok().flatten();
+    let artifact_ids: Option<Vec<pgrx::Uuid>> = row.get(9).ok().flatten();
+    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+    let delivered_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
+    let acknowledged_at: Option<TimestampWithTimeZone> = row.get(12).ok().flatten();
+    let priority: Option<String> = row.get(13).ok().flatten();
+    let expires_at: Option<TimestampWithTimeZone> = row.get(14).ok().flatten();
+    let tenant_id_val: Option<pgrx::Uuid> = row.get(15).ok().flatten();
+    let reply_to: Option<pgrx::Uuid> = row.get(16).ok().flatten();
+
+    serde_json::json!({
+        "message_id": message_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "from_agent_id": from_agent_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "to_agent_id": to_agent_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "to_agent_type": to_agent_type,
+        "message_type": message_type,
+        "payload": payload,
+        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "scope_id": scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "artifact_ids": artifact_ids
+            .unwrap_or_default()
+            .into_iter()
+            .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+            .collect::<Vec<_>>(),
+        "created_at": created_at.map(|t| t.to_string()),
+        "delivered_at": delivered_at.map(|t| t.to_string()),
+        "acknowledged_at": acknowledged_at.map(|t| t.to_string()),
+        "priority": priority,
+        "expires_at": expires_at.map(|t| t.to_string()),
+        "tenant_id": tenant_id_val.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+        "reply_to": reply_to.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+    })
 }
 
-/// Search across entities with tenant isolation.
+/// Query messages by recipient, type, minimum priority and time window.
+///
+/// Unlike `caliber_message_get_pending` this returns delivered and
+/// undelivered messages alike, newest first. `min_priority` keeps messages at
+/// or above the given level (low < normal < high < critical) and `since_ms`
+/// keeps messages created at or after that Unix timestamp in milliseconds.
 #[pg_extern]
-fn caliber_search(query: pgrx::JsonB, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let query_obj = query.0;
-    let query_text = query_obj
-        .get("query")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .trim()
-        .to_string();
-    if query_text.is_empty() {
-        return pgrx::JsonB(serde_json::json!({ "results": [], "total": 0 }));
+fn caliber_message_query(
+    to_agent_id: Option<pgrx::Uuid>,
+    message_type: Option<&str>,
+    min_priority: Option<&str>,
+    since_ms: Option<i64>,
+    limit: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    // Validate message_type - reject unknown values (REQ-12)
+    if let Some(mt) = message_type {
+        if message_type_from_str(mt).is_none() {
+            let validation_err = ValidationError::InvalidValue {
+                field: "message_type".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: {}",
+                    mt, MESSAGE_TYPE_VALUES
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
     }
 
-    let entity_types: Vec<String> = query_obj
-        .get("entity_types")
-        .and_then(|v| v.as_array())
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let limit = query_obj
-        .get("limit")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(20)
-        .max(1) as usize;
+    // Priorities are ranked so "at least" becomes a numeric comparison
+    let min_rank = match min_priority {
+        None | Some("low") => 1,
+        Some("normal") => 2,
+        Some("high") => 3,
+        Some("critical") => 4,
+        Some(other) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "min_priority".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: low, normal, high, critical",
+                    other
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
 
-    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
-    let pattern = format!("%{}%", query_text.replace('%', "\\%").replace('_', "\\_"));
+    let query = format!(
+        "SELECT {}
+             FROM caliber_message
+             WHERE tenant_id = $1
+               AND ($2::uuid IS NULL OR to_agent_id = $2)
+               AND ($3::text IS NULL OR message_type = $3)
+               AND CASE priority
+                       WHEN 'low' THEN 1
+                       WHEN 'normal' THEN 2
+                       WHEN 'high' THEN 3
+                       WHEN 'critical' THEN 4
+                   END >= $4
+               AND ($5::bigint IS NULL OR created_at >= to_timestamp($5 / 1000.0))
+             ORDER BY created_at DESC
+             LIMIT $6",
+        MESSAGE_SPI_COLUMNS
+    );
 
     let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let mut results = Vec::new();
+        let table = client.select(
+            &query,
+            None,
+            &[
+                pgrx_uuid_datum(tenant_id),
+                match to_agent_id {
+                    Some(id) => pgrx_uuid_datum(id),
+                    None => DatumWithOid::null_oid(pgrx::pg_sys::UUIDOID),
+                },
+                opt_text_datum(message_type),
+                int4_datum(min_rank),
+                match since_ms {
+                    Some(ms) => int8_datum(ms),
+                    None => DatumWithOid::null_oid(pgrx::pg_sys::INT8OID),
+                },
+                int4_datum(limit.max(0)),
+            ],
+        )?;
 
-        let include =
-            |name: &str| entity_types.is_empty() || entity_types.iter().any(|t| t == name);
+        Ok(table.map(|row| message_json_from_spi_row(&row)).collect())
+    });
 
-        if include("Trajectory") {
-            let table = client.select(
-                "SELECT trajectory_id, name, description
-                 FROM caliber_trajectory
-                 WHERE tenant_id = $1 AND (name ILIKE $2 OR description ILIKE $2)
-                 ORDER BY updated_at DESC",
-                None,
-                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
-            )?;
-            for row in table {
-                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let name: Option<String> = row.get(2).ok().flatten();
-                let description: Option<String> = row.get(3).ok().flatten();
-                let snippet = description.clone().unwrap_or_default();
-                results.push(serde_json::json!({
-                    "entity_type": "Trajectory",
-                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    "name": name.unwrap_or_default(),
-                    "snippet": snippet.chars().take(160).collect::<String>(),
-                    "score": 1.0_f32,
-                }));
-            }
+    match result {
+        Ok(messages) => pgrx::JsonB(serde_json::json!(messages)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query messages: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
+    }
+}
 
-        if include("Scope") {
-            let table = client.select(
-                "SELECT scope_id, name, purpose
-                 FROM caliber_scope
-                 WHERE tenant_id = $1 AND (name ILIKE $2 OR purpose ILIKE $2)
-                 ORDER BY created_at DESC",
-                None,
-                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
-            )?;
-            for row in table {
-                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let name: Option<String> = row.get(2).ok().flatten();
-                let purpose: Option<String> = row.get(3).ok().flatten();
-                let snippet = purpose.clone().unwrap_or_default();
-                results.push(serde_json::json!({
-                    "entity_type": "Scope",
-                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    "name": name.unwrap_or_default(),
-                    "snippet": snippet.chars().take(160).collect::<String>(),
-                    "score": 1.0_f32,
-                }));
-            }
-        }
+/// Get the full reply thread containing a message.
+///
+/// Walks `reply_to` links up to the thread root, then collects every reply
+/// beneath it. Messages are returned oldest first; an unknown message yields
+/// an empty array.
+#[pg_extern]
+fn caliber_message_thread(message_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let query = format!(
+        "WITH RECURSIVE ancestors AS (
+                 SELECT message_id, reply_to
+                 FROM caliber_message
+                 WHERE message_id = $1 AND tenant_id = $2
+                 UNION ALL
+                 SELECT m.message_id, m.reply_to
+                 FROM caliber_message m
+                 JOIN ancestors a ON m.message_id = a.reply_to
+                 WHERE m.tenant_id = $2
+             ),
+             thread AS (
+                 SELECT message_id FROM ancestors WHERE reply_to IS NULL
+                 UNION ALL
+                 SELECT m.message_id
+                 FROM caliber_message m
+                 JOIN thread t ON m.reply_to = t.message_id
+                 WHERE m.tenant_id = $2
+             )
+             SELECT {}
+             FROM caliber_message
+             WHERE message_id IN (SELECT message_id FROM thread)
+             ORDER BY created_at, message_id",
+        MESSAGE_SPI_COLUMNS
+    );
 
-        if include("Artifact") {
-            let table = client.select(
-                "SELECT artifact_id, name, content
-                 FROM caliber_artifact
-                 WHERE tenant_id = $1 AND (name ILIKE $2 OR content ILIKE $2)
-                 ORDER BY updated_at DESC",
-                None,
-                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
-            )?;
-            for row in table {
-                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let name: Option<String> = row.get(2).ok().flatten();
-                let content: Option<String> = row.get(3).ok().flatten();
-                let snippet = content.clone().unwrap_or_default();
-                results.push(serde_json::json!({
-                    "entity_type": "Artifact",
-                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    "name": name.unwrap_or_default(),
-                    "snippet": snippet.chars().take(160).collect::<String>(),
-                    "score": 1.0_f32,
-                }));
-            }
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            &query,
+            None,
+            &[pgrx_uuid_datum(message_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        Ok(table.map(|row| message_json_from_spi_row(&row)).collect())
+    });
+
+    match result {
+        Ok(messages) => pgrx::JsonB(serde_json::json!(messages)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get message thread: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
+    }
+}
 
-        if include("Note") {
-            let table = client.select(
-                "SELECT note_id, title, content
-                 FROM caliber_note
-                 WHERE tenant_id = $1 AND (title ILIKE $2 OR content ILIKE $2)
-                 ORDER BY updated_at DESC",
-                None,
-                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
-            )?;
-            for row in table {
-                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let title: Option<String> = row.get(2).ok().flatten();
-                let content: Option<String> = row.get(3).ok().flatten();
-                let snippet = content.clone().unwrap_or_default();
-                results.push(serde_json::json!({
-                    "entity_type": "Note",
-                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                    "name": title.unwrap_or_default(),
-                    "snippet": snippet.chars().take(160).collect::<String>(),
-                    "score": 1.0_f32,
-                }));
+/// List messages using filter JSON.
+#[pg_extern]
+fn caliber_message_list(filters: pgrx::JsonB) -> pgrx::JsonB {
+    message_list_internal(&filters.0, None)
+}
+
+/// List messages using filter JSON with tenant isolation.
+#[pg_extern]
+fn caliber_message_list_by_tenant(filters: pgrx::JsonB) -> pgrx::JsonB {
+    let tenant_id = filters
+        .0
+        .get("tenant_id")
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok());
+    message_list_internal(&filters.0, tenant_id)
+}
+
+fn message_list_internal(filters: &serde_json::Value, tenant_id: Option<Uuid>) -> pgrx::JsonB {
+    let parse_uuid = |key: &str| {
+        filters
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(|s| Uuid::parse_str(s).ok())
+    };
+
+    let from_agent_id = parse_uuid("from_agent_id");
+    let to_agent_id = parse_uuid("to_agent_id");
+    let trajectory_id = parse_uuid("trajectory_id");
+    let message_type = filters
+        .get("message_type")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let priority = filters
+        .get("priority")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let to_agent_type = filters
+        .get("to_agent_type")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let undelivered_only = filters
+        .get("undelivered_only")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let unacknowledged_only = filters
+        .get("unacknowledged_only")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    let limit = filters
+        .get("limit")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(100)
+        .max(0) as usize;
+    let offset = filters
+        .get("offset")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(0)
+        .max(0) as usize;
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let (sql, params) = if let Some(tenant_id) = tenant_id {
+            (
+                "SELECT message_id, from_agent_id, to_agent_id, to_agent_type, message_type, payload,
+                        trajectory_id, scope_id, artifact_ids, created_at, delivered_at, acknowledged_at,
+                        priority, expires_at, tenant_id
+                 FROM caliber_message
+                 WHERE tenant_id = $1
+                 ORDER BY created_at DESC",
+                vec![uuid_datum(tenant_id)],
+            )
+        } else {
+            (
+                "SELECT message_id, from_agent_id, to_agent_id, to_agent_type, message_type, payload,
+                        trajectory_id, scope_id, artifact_ids, created_at, delivered_at, acknowledged_at,
+                        priority, expires_at, tenant_id
+                 FROM caliber_message
+                 ORDER BY created_at DESC",
+                vec![],
+            )
+        };
+
+        let table = client.select(sql, None, &params)?;
+        let mut messages = Vec::new();
+
+        for row in table {
+            let message_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let from_agent_id_row: Option<pgrx::Uuid> = row.get(2).ok().flatten();
+            let to_agent_id_row: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+            let to_agent_type_row: Option<String> = row.get(4).ok().flatten();
+            let message_type_row: Option<String> = row.get(5).ok().flatten();
+            let payload_row: Option<String> = row.get(6).ok().flatten();
+            let trajectory_id_row: Option<pgrx::Uuid> = row.get(7).ok().flatten();
+            let scope_id_row: Option<pgrx::Uuid> = row.get(8).ok().flatten();
+            let artifact_ids_row: Option<Vec<pgrx::Uuid>> = row.get(9).ok().flatten();
+            let created_at_row: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+            let delivered_at_row: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
+            let acknowledged_at_row: Option<TimestampWithTimeZone> = row.get(12).ok().flatten();
+            let priority_row: Option<String> = row.get(13).ok().flatten();
+            let expires_at_row: Option<TimestampWithTimeZone> = row.get(14).ok().flatten();
+            let tenant_id_row: Option<pgrx::Uuid> = row.get(15).ok().flatten();
+
+            let from_agent_uuid = from_agent_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
+            let to_agent_uuid = to_agent_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
+            let traj_uuid = trajectory_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()));
+
+            if let Some(filter) = from_agent_id {
+                if from_agent_uuid != Some(filter) {
+                    continue;
+                }
+            }
+            if let Some(filter) = to_agent_id {
+                if to_agent_uuid != Some(filter) {
+                    continue;
+                }
+            }
+            if let Some(filter) = trajectory_id {
+                if traj_uuid != Some(filter) {
+                    continue;
+                }
+            }
+            if let Some(filter) = &message_type {
+                if message_type_row.as_deref() != Some(filter.as_str()) {
+                    continue;
+                }
+            }
+            if let Some(filter) = &priority {
+                if priority_row.as_deref() != Some(filter.as_str()) {
+                    continue;
+                }
+            }
+            if let Some(filter) = &to_agent_type {
+                if to_agent_type_row.as_deref() != Some(filter.as_str()) {
+                    continue;
+                }
+            }
+            if undelivered_only && delivered_at_row.is_some() {
+                continue;
             }
+            if unacknowledged_only && acknowledged_at_row.is_some() {
+                continue;
+            }
+
+            let json = serde_json::json!({
+                "message_id": message_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "from_agent_id": from_agent_uuid.map(|u| u.to_string()),
+                "to_agent_id": to_agent_uuid.map(|u| u.to_string()),
+                "to_agent_type": to_agent_type_row,
+                "message_type": message_type_row,
+                "payload": payload_row,
+                "trajectory_id": trajectory_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "scope_id": scope_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "artifact_ids": artifact_ids_row
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+                    .collect::<Vec<_>>(),
+                "created_at": created_at_row.map(|t| t.to_string()),
+                "delivered_at": delivered_at_row.map(|t| t.to_string()),
+                "acknowledged_at": acknowledged_at_row.map(|t| t.to_string()),
+                "priority": priority_row,
+                "expires_at": expires_at_row.map(|t| t.to_string()),
+                "tenant_id": tenant_id_row.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+            });
+
+            messages.push(json);
         }
 
-        Ok(results)
+        Ok(messages)
     });
 
     match result {
-        Ok(results) => {
-            let total = results.len() as i32;
-            let limited: Vec<serde_json::Value> = results.into_iter().take(limit).collect();
-            pgrx::JsonB(serde_json::json!({
-                "results": limited,
-                "total": total,
-            }))
+        Ok(messages) => {
+            let sliced: Vec<serde_json::Value> =
+                messages.into_iter().skip(offset).take(limit).collect();
+            pgrx::JsonB(serde_json::json!(sliced))
         }
         Err(e) => {
-            pgrx::warning!("CALIBER: search failed: {}", e);
-            pgrx::JsonB(serde_json::json!({ "results": [], "total": 0 }))
+            pgrx::warning!("CALIBER: Failed to list messages: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
 // ============================================================================
-// DEBUG SQL VIEWS (Task 12.7)
-// Gated behind "debug" or "pg_test" feature flag for safety
+// AGENT OPERATIONS (Task 12.6)
 // ============================================================================
 
-/// Get storage statistics for debugging.
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+/// Register a new agent.
 #[pg_extern]
-fn caliber_debug_stats() -> pgrx::JsonB {
-    pgrx::warning!("DEBUG: caliber_debug_stats called");
+fn caliber_agent_register(
+    agent_type: &str,
+    capabilities: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::Uuid {
+    // Record operation for metrics
+    storage_write().record_op("agent_register");
 
-    // Get counts from SQL tables (all entities now in SQL)
-    let counts = Spi::connect(|client| {
-        let mut counts = serde_json::Map::new();
+    let caps: Vec<String> = serde_json::from_value(capabilities.0).unwrap_or_default();
 
-        let tables = [
-            ("trajectories", "caliber_trajectory"),
-            ("scopes", "caliber_scope"),
-            ("artifacts", "caliber_artifact"),
-            ("notes", "caliber_note"),
-            ("turns", "caliber_turn"),
-            ("locks", "caliber_lock"),
-            ("messages", "caliber_message"),
-            ("agents", "caliber_agent"),
-            ("delegations", "caliber_delegation"),
-            ("handoffs", "caliber_handoff"),
-            ("conflicts", "caliber_conflict"),
-        ];
+    let agent = Agent::new(agent_type, caps.clone());
+    let agent_id = agent.agent_id;
 
-        for (name, table) in tables {
-            let result = client.select(&format!("SELECT COUNT(*) FROM {}", table), None, &[]);
-            let count = match result {
-                Ok(table) => {
-                    let table = table.first();
-                    table.get_one::<i64>().ok().flatten().unwrap_or(0)
-                }
-                Err(_) => 0,
-            };
-            counts.insert(name.to_string(), serde_json::json!(count));
-        }
+    // Use direct heap operations instead of SPI
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-        counts
+    let result = agent_heap::agent_register_heap(agent_heap::AgentRegisterParams {
+        agent_id,
+        agent_type,
+        capabilities: &caps,
+        memory_access: &agent.memory_access,
+        can_delegate_to: &agent.can_delegate_to,
+        reports_to: agent.reports_to,
+        external_key: None,
+        tenant_id: tenant_uuid,
     });
 
-    // Add operation metrics from in-memory storage
-    let ops = storage_read();
-    let ops_data: serde_json::Map<String, serde_json::Value> = ops
-        .get_ops()
-        .iter()
-        .map(|(k, v)| (k.to_string(), serde_json::json!(*v)))
-        .collect();
+    match result {
+        Ok(_) => audit_record(
+            EntityType::Agent,
+            agent_id,
+            "register",
+            Some(agent_id),
+            tenant_uuid,
+        ),
+        Err(e) => pgrx::warning!("CALIBER: Failed to insert agent: {}", e),
+    }
 
-    let mut result = serde_json::Map::new();
-    result.insert(
-        "entity_counts".to_string(),
-        serde_json::Value::Object(counts),
-    );
-    result.insert(
-        "operation_counts".to_string(),
-        serde_json::Value::Object(ops_data),
-    );
-
-    pgrx::JsonB(serde_json::Value::Object(result))
+    pgrx_uuid_from_id(agent_id)
 }
 
-/// Clear all storage (for testing).
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+/// Register an agent under a stable external key.
+///
+/// If an agent with the key already exists for the tenant, its capabilities
+/// are updated and its id is returned; otherwise a new agent is registered.
 #[pg_extern]
-fn caliber_debug_clear() -> &'static str {
-    pgrx::warning!("DEBUG: caliber_debug_clear called - clearing all storage!");
-
-    // Clear SQL tables in correct order (respecting foreign keys)
-    let _ = Spi::run("DELETE FROM caliber_turn");
-    let _ = Spi::run("DELETE FROM caliber_artifact");
-    let _ = Spi::run("DELETE FROM caliber_scope");
-    let _ = Spi::run("DELETE FROM caliber_note");
-    let _ = Spi::run("DELETE FROM caliber_message");
-    let _ = Spi::run("DELETE FROM caliber_lock");
-    let _ = Spi::run("DELETE FROM caliber_conflict");
-    let _ = Spi::run("DELETE FROM caliber_handoff");
-    let _ = Spi::run("DELETE FROM caliber_delegation");
-    let _ = Spi::run("DELETE FROM caliber_region");
-    let _ = Spi::run("DELETE FROM caliber_agent");
-    let _ = Spi::run("DELETE FROM caliber_trajectory");
+fn caliber_agent_register_idempotent(
+    external_key: &str,
+    agent_type: &str,
+    capabilities: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    // Record operation for metrics
+    storage_write().record_op("agent_register");
 
-    // Reset in-memory operation counters
-    storage_write().reset_ops();
+    if external_key.trim().is_empty() {
+        let validation_err = ValidationError::RequiredFieldMissing {
+            field: "external_key".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return None;
+    }
 
-    "Storage cleared"
-}
+    // Validate capabilities - reject malformed input rather than wiping an
+    // existing agent's capabilities (REQ-12)
+    let caps: Vec<String> = match serde_json::from_value(capabilities.0) {
+        Ok(caps) => caps,
+        Err(e) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "capabilities".to_string(),
+                reason: format!("must be a JSON array of strings: {}", e),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return None;
+        }
+    };
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-/// Dump all trajectories for debugging.
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
-#[pg_extern]
-fn caliber_debug_dump_trajectories() -> pgrx::JsonB {
-    pgrx::warning!("DEBUG: caliber_debug_dump_trajectories called");
+    let existing: Result<Option<pgrx::Uuid>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        client
+            .select(
+                "SELECT agent_id FROM caliber_agent WHERE tenant_id = $1 AND external_key = $2",
+                None,
+                &[pgrx_uuid_datum(tenant_id), text_datum(external_key)],
+            )?
+            .first()
+            .get_one::<pgrx::Uuid>()
+    });
 
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT trajectory_id, name, description, status, parent_trajectory_id, 
-                    root_trajectory_id, agent_id, created_at, updated_at, completed_at, 
-                    outcome, metadata 
-             FROM caliber_trajectory ORDER BY created_at DESC",
-            None,
-            &[],
-        );
+    match existing {
+        Ok(Some(existing_id)) => {
+            // Use direct heap operations instead of SPI
+            let existing_agent = id_from_pgrx::<AgentId>(existing_id);
+            let params = agent_heap::AgentUpdateHeapParams {
+                agent_id: existing_agent,
+                tenant_id: tenant_uuid,
+                capabilities: Some(&caps),
+                can_delegate_to: None,
+                reports_to: None,
+                memory_access: None,
+            };
+            match agent_heap::agent_update_heap(params) {
+                Ok(true) => {
+                    audit_record(
+                        EntityType::Agent,
+                        existing_agent,
+                        "update",
+                        Some(existing_agent),
+                        tenant_uuid,
+                    );
+                    Some(existing_id)
+                }
+                Ok(false) => {
+                    pgrx::warning!("CALIBER: Agent {} not found for update", existing_agent);
+                    None
+                }
+                Err(e) => {
+                    pgrx::warning!("CALIBER: Failed to update agent capabilities: {}", e);
+                    None
+                }
+            }
+        }
+        Ok(None) => {
+            let agent = Agent::new(agent_type, caps.clone());
+            let agent_id = agent.agent_id;
 
-        match result {
-            Ok(table) => {
-                let mut trajectories = Vec::new();
-                for row in table {
-                    let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                    let name: Option<String> = row.get(2).ok().flatten();
-                    let description: Option<String> = row.get(3).ok().flatten();
-                    let status: Option<String> = row.get(4).ok().flatten();
-                    let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
-                    let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
-                    let agent_id: Option<pgrx::Uuid> = row.get(7).ok().flatten();
-                    let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
-                    let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-                    let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-                    let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+            // Use direct heap operations instead of SPI
+            let result = agent_heap::agent_register_heap(agent_heap::AgentRegisterParams {
+                agent_id,
+                agent_type,
+                capabilities: &caps,
+                memory_access: &agent.memory_access,
+                can_delegate_to: &agent.can_delegate_to,
+                reports_to: agent.reports_to,
+                external_key: Some(external_key),
+                tenant_id: tenant_uuid,
+            });
 
-                    trajectories.push(serde_json::json!({
-                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "name": name,
-                        "description": description,
-                        "status": status,
-                        "parent_trajectory_id": parent_trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "root_trajectory_id": root_trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "agent_id": agent_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "created_at": created_at.map(|t| format!("{:?}", t)),
-                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
-                        "completed_at": completed_at.map(|t| format!("{:?}", t)),
-                        "outcome": outcome.map(|j| j.0),
-                        "metadata": metadata.map(|j| j.0),
-                    }));
+            match result {
+                Ok(_) => {
+                    audit_record(
+                        EntityType::Agent,
+                        agent_id,
+                        "register",
+                        Some(agent_id),
+                        tenant_uuid,
+                    );
+                    Some(pgrx_uuid_from_id(agent_id))
+                }
+                Err(e) => {
+                    pgrx::warning!("CALIBER: Failed to insert agent: {}", e);
+                    None
                 }
-                pgrx::JsonB(serde_json::json!(trajectories))
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to dump trajectories: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
             }
         }
-    })
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to look up agent by external key: {}", e);
+            None
+        }
+    }
 }
 
-/// Dump all scopes for debugging.
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+// Get an agent by ID.
+caliber_pg_get!(agent, agent_heap, AgentId, |row| {
+    let a = row.agent;
+    serde_json::json!({
+        "agent_id": a.agent_id.to_string(),
+        "agent_type": a.agent_type,
+        "capabilities": a.capabilities,
+        "memory_access": serde_json::to_value(&a.memory_access).unwrap_or(serde_json::json!({})),
+        "status": match a.status {
+            AgentStatus::Idle => "idle",
+            AgentStatus::Active => "active",
+            AgentStatus::Blocked => "blocked",
+            AgentStatus::Failed => "failed",
+            AgentStatus::Offline => "offline",
+        },
+        "current_trajectory_id": a.current_trajectory_id.map(|id| id.to_string()),
+        "current_scope_id": a.current_scope_id.map(|id| id.to_string()),
+        "can_delegate_to": a.can_delegate_to,
+        "reports_to": a.reports_to.map(|id| id.to_string()),
+        "created_at": a.created_at.to_rfc3339(),
+        "last_heartbeat": a.last_heartbeat.to_rfc3339(),
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+});
+
+/// Update an agent with the provided fields.
+/// Supports partial updates to capabilities, can_delegate_to, reports_to and memory_access.
 #[pg_extern]
-fn caliber_debug_dump_scopes() -> pgrx::JsonB {
-    pgrx::warning!("DEBUG: caliber_debug_dump_scopes called");
+fn caliber_agent_update(agent_id: pgrx::Uuid, updates: pgrx::JsonB, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let update_obj = &updates.0;
 
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT scope_id, trajectory_id, parent_scope_id, name, purpose, is_active, 
-                    created_at, closed_at, checkpoint, token_budget, tokens_used, metadata 
-             FROM caliber_scope ORDER BY created_at DESC",
-            None,
-            &[],
-        );
+    // Parse updates from JSON
+    let capabilities: Option<Vec<String>> = match update_obj.get("capabilities") {
+        None => None,
+        Some(v) => match serde_json::from_value::<Vec<String>>(v.clone()) {
+            Ok(caps) => Some(caps),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Invalid agent capabilities: {}", e);
+                return false;
+            }
+        },
+    };
 
-        match result {
-            Ok(table) => {
-                let mut scopes = Vec::new();
-                for row in table {
-                    let scope_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                    let trajectory_id: Option<pgrx::Uuid> = row.get(2).ok().flatten();
-                    let parent_scope_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
-                    let name: Option<String> = row.get(4).ok().flatten();
-                    let purpose: Option<String> = row.get(5).ok().flatten();
-                    let is_active: Option<bool> = row.get(6).ok().flatten();
-                    let created_at: Option<TimestampWithTimeZone> = row.get(7).ok().flatten();
-                    let closed_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
-                    let checkpoint: Option<pgrx::JsonB> = row.get(9).ok().flatten();
-                    let token_budget: Option<i32> = row.get(10).ok().flatten();
-                    let tokens_used: Option<i32> = row.get(11).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+    let can_delegate_to: Option<Vec<String>> = match update_obj.get("can_delegate_to") {
+        None => None,
+        Some(v) => match serde_json::from_value::<Vec<String>>(v.clone()) {
+            Ok(targets) => Some(targets),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Invalid agent can_delegate_to: {}", e);
+                return false;
+            }
+        },
+    };
 
-                    scopes.push(serde_json::json!({
-                        "scope_id": scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "parent_scope_id": parent_scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "name": name,
-                        "purpose": purpose,
-                        "is_active": is_active,
-                        "created_at": created_at.map(|t| format!("{:?}", t)),
-                        "closed_at": closed_at.map(|t| format!("{:?}", t)),
-                        "checkpoint": checkpoint.map(|j| j.0),
-                        "token_budget": token_budget,
-                        "tokens_used": tokens_used,
-                        "metadata": metadata.map(|j| j.0),
-                    }));
-                }
-                pgrx::JsonB(serde_json::json!(scopes))
+    let reports_to: Option<Option<AgentId>> = match update_obj.get("reports_to") {
+        None => None,
+        Some(v) if v.is_null() => Some(None),
+        Some(v) => match v.as_str().and_then(|s| Uuid::parse_str(s).ok()) {
+            Some(uuid) => Some(Some(AgentId::new(uuid))),
+            None => {
+                pgrx::warning!("CALIBER: Invalid agent reports_to: {}", v);
+                return false;
             }
+        },
+    };
+
+    // An agent cannot report to itself
+    if reports_to == Some(Some(entity_id)) {
+        let validation_err = ValidationError::InvalidValue {
+            field: "reports_to".to_string(),
+            reason: "agent cannot report to itself".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return false;
+    }
+
+    let memory_access: Option<MemoryAccess> = match update_obj.get("memory_access") {
+        None => None,
+        Some(v) => match serde_json::from_value::<MemoryAccess>(v.clone()) {
+            Ok(access) => Some(access),
             Err(e) => {
-                pgrx::warning!("CALIBER: Failed to dump scopes: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
+                pgrx::warning!("CALIBER: Invalid agent memory_access: {}", e);
+                return false;
+            }
+        },
+    };
+
+    // Check if any fields are being updated
+    if capabilities.is_none()
+        && can_delegate_to.is_none()
+        && reports_to.is_none()
+        && memory_access.is_none()
+    {
+        pgrx::warning!("CALIBER: No valid fields to update in agent");
+        return false;
+    }
+
+    // Use direct heap operations instead of SPI
+    let params = agent_heap::AgentUpdateHeapParams {
+        agent_id: entity_id,
+        tenant_id: tenant_uuid,
+        capabilities: capabilities.as_deref(),
+        can_delegate_to: can_delegate_to.as_deref(),
+        reports_to,
+        memory_access: memory_access.as_ref(),
+    };
+
+    match agent_heap::agent_update_heap(params) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Agent,
+                    entity_id,
+                    "update",
+                    Some(entity_id),
+                    tenant_uuid,
+                );
             }
+            updated
         }
-    })
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update agent: {}", e);
+            false
+        }
+    }
 }
 
-/// Dump all artifacts for debugging.
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+/// Update agent status.
 #[pg_extern]
-fn caliber_debug_dump_artifacts() -> pgrx::JsonB {
-    pgrx::warning!("DEBUG: caliber_debug_dump_artifacts called");
-
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT artifact_id, trajectory_id, scope_id, artifact_type, name, content, 
-                    content_hash, provenance, ttl, created_at, updated_at, superseded_by, metadata 
-             FROM caliber_artifact ORDER BY created_at DESC",
-            None,
-            &[],
-        );
+fn caliber_agent_set_status(agent_id: pgrx::Uuid, status: &str, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-        match result {
-            Ok(table) => {
-                let mut artifacts = Vec::new();
-                for row in table {
-                    let artifact_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                    let trajectory_id: Option<pgrx::Uuid> = row.get(2).ok().flatten();
-                    let scope_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
-                    let artifact_type: Option<String> = row.get(4).ok().flatten();
-                    let name: Option<String> = row.get(5).ok().flatten();
-                    let content: Option<String> = row.get(6).ok().flatten();
-                    let content_hash: Option<Vec<u8>> = row.get(7).ok().flatten();
-                    let provenance: Option<pgrx::JsonB> = row.get(8).ok().flatten();
-                    let ttl: Option<String> = row.get(9).ok().flatten();
-                    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-                    let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
-                    let superseded_by: Option<pgrx::Uuid> = row.get(12).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> = row.get(13).ok().flatten();
+    // Validate and convert status
+    let agent_status = match status {
+        "idle" => AgentStatus::Idle,
+        "active" => AgentStatus::Active,
+        "blocked" => AgentStatus::Blocked,
+        "failed" => AgentStatus::Failed,
+        _ => {
+            pgrx::warning!("CALIBER: Invalid agent status: {}", status);
+            return false;
+        }
+    };
 
-                    artifacts.push(serde_json::json!({
-                        "artifact_id": artifact_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "scope_id": scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "artifact_type": artifact_type,
-                        "name": name,
-                        "content": content,
-                        "content_hash": content_hash.map(|h| hex::encode(&h)),
-                        "provenance": provenance.map(|j| j.0),
-                        "ttl": ttl,
-                        "created_at": created_at.map(|t| format!("{:?}", t)),
-                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
-                        "superseded_by": superseded_by.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                        "metadata": metadata.map(|j| j.0),
-                    }));
-                }
-                pgrx::JsonB(serde_json::json!(artifacts))
+    // Use direct heap operations instead of SPI
+    match agent_heap::agent_set_status_heap(entity_id, agent_status, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Agent,
+                    entity_id,
+                    "set_status",
+                    Some(entity_id),
+                    tenant_uuid,
+                );
+            }
+            updated
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update agent status: {}", e);
+            false
+        }
+    }
+}
+
+/// Set the trajectory and scope an agent is currently working on.
+///
+/// Passing NULL clears the corresponding field. Returns false if the agent,
+/// trajectory or scope does not exist, or if the scope belongs to a
+/// different trajectory than the one given.
+#[pg_extern]
+fn caliber_agent_set_context(
+    agent_id: pgrx::Uuid,
+    trajectory_id: Option<pgrx::Uuid>,
+    scope_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+    let traj_id = opt_id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let scp_id = opt_id_from_pgrx::<ScopeId>(scope_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    if let Some(tid) = traj_id {
+        match trajectory_heap::trajectory_get_heap(tid, tenant_uuid) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                pgrx::warning!("CALIBER: Trajectory {} not found", tid);
+                return false;
             }
             Err(e) => {
-                pgrx::warning!("CALIBER: Failed to dump artifacts: {}", e);
-                pgrx::JsonB(serde_json::json!([]))
+                pgrx::warning!("CALIBER: Failed to get trajectory {}: {}", tid, e);
+                return false;
             }
         }
-    })
+    }
+
+    if let Some(sid) = scp_id {
+        let scope = match scope_heap::scope_get_heap(sid, tenant_uuid) {
+            Ok(Some(row)) => row.scope,
+            Ok(None) => {
+                pgrx::warning!("CALIBER: Scope {} not found", sid);
+                return false;
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get scope {}: {}", sid, e);
+                return false;
+            }
+        };
+        if let Some(tid) = traj_id {
+            if scope.trajectory_id != tid {
+                pgrx::warning!(
+                    "CALIBER: Scope {} does not belong to trajectory {}",
+                    sid,
+                    tid
+                );
+                return false;
+            }
+        }
+    }
+
+    match agent_heap::agent_set_context_heap(entity_id, traj_id, scp_id, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Agent,
+                    entity_id,
+                    "set_context",
+                    Some(entity_id),
+                    tenant_uuid,
+                );
+            }
+            updated
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update agent context: {}", e);
+            false
+        }
+    }
 }
 
-/// Dump all agents for debugging.
-#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+/// Update agent heartbeat.
 #[pg_extern]
-fn caliber_debug_dump_agents() -> pgrx::JsonB {
-    pgrx::warning!("DEBUG: caliber_debug_dump_agents called");
+fn caliber_agent_heartbeat(agent_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Use direct heap operations instead of SPI
+    match agent_heap::agent_heartbeat_heap(entity_id, tenant_uuid) {
+        Ok(updated) => updated,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update agent heartbeat: {}", e);
+            false
+        }
+    }
+}
+
+/// List agents by type.
+#[pg_extern]
+fn caliber_agent_list_by_type(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    // Use direct heap operations instead of SPI
+    match agent_heap::agent_list_by_type_heap(agent_type, tenant_uuid) {
+        Ok(agents) => {
+            let json_agents: Vec<serde_json::Value> = agents
+                .into_iter()
+                .map(|row| {
+                    let agent = row.agent;
+                    serde_json::json!({
+                        "agent_id": agent.agent_id.to_string(),
+                        "agent_type": agent.agent_type,
+                        "capabilities": agent.capabilities,
+                        "memory_access": serde_json::to_value(&agent.memory_access).unwrap_or(serde_json::json!({})),
+                        "status": match agent.status {
+                            AgentStatus::Idle => "idle",
+                            AgentStatus::Active => "active",
+                            AgentStatus::Blocked => "blocked",
+                            AgentStatus::Failed => "failed",
+                            AgentStatus::Offline => "offline",
+                        },
+                        "current_trajectory_id": agent.current_trajectory_id.map(|id| id.to_string()),
+                        "current_scope_id": agent.current_scope_id.map(|id| id.to_string()),
+                        "can_delegate_to": agent.can_delegate_to,
+                        "reports_to": agent.reports_to.map(|id| id.to_string()),
+                        "created_at": agent.created_at.to_rfc3339(),
+                        "last_heartbeat": agent.last_heartbeat.to_rfc3339(),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_agents))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list agents by type: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// List all active agents (status active or idle).
+#[pg_extern]
+fn caliber_agent_list_active(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    // Use direct heap operations instead of SPI
+    match agent_heap::agent_list_by_status_heap(
+        &[AgentStatus::Active, AgentStatus::Idle],
+        tenant_uuid,
+    ) {
+        Ok(agents) => {
+            let json_agents: Vec<serde_json::Value> = agents
+                .into_iter()
+                .map(|row| {
+                    let agent = row.agent;
+                    serde_json::json!({
+                        "agent_id": agent.agent_id.to_string(),
+                        "agent_type": agent.agent_type,
+                        "capabilities": agent.capabilities,
+                        "memory_access": serde_json::to_value(&agent.memory_access).unwrap_or(serde_json::json!({})),
+                        "status": agent_heap::agent_status_to_str(agent.status),
+                        "current_trajectory_id": agent.current_trajectory_id.map(|id| id.to_string()),
+                        "current_scope_id": agent.current_scope_id.map(|id| id.to_string()),
+                        "can_delegate_to": agent.can_delegate_to,
+                        "reports_to": agent.reports_to.map(|id| id.to_string()),
+                        "created_at": agent.created_at.to_rfc3339(),
+                        "last_heartbeat": agent.last_heartbeat.to_rfc3339(),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(json_agents))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list active agents: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// List active or idle agents whose last heartbeat is older than the tenant's
+/// `stale_threshold` (see `caliber_config_set`), least recently seen first.
+#[pg_extern]
+fn caliber_agent_list_stale(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    let settings = match ConfigSettings::load(tenant_uuid) {
+        Ok(settings) => settings,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to read config for stale agents: {}", e);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+    // A threshold too large to subtract from now leaves no agent stale
+    let cutoff = match chrono::Duration::from_std(settings.stale_threshold)
+        .ok()
+        .and_then(|threshold| Utc::now().checked_sub_signed(threshold))
+    {
+        Some(cutoff) => cutoff,
+        None => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    match agent_heap::agent_list_by_status_heap(
+        &[AgentStatus::Active, AgentStatus::Idle],
+        tenant_uuid,
+    ) {
+        Ok(agents) => {
+            let mut stale: Vec<_> = agents
+                .into_iter()
+                .map(|row| row.agent)
+                .filter(|agent| agent.last_heartbeat < cutoff)
+                .collect();
+            stale.sort_by_key(|agent| agent.last_heartbeat);
+
+            let json_agents: Vec<serde_json::Value> = stale
+                .into_iter()
+                .map(|agent| {
+                    serde_json::json!({
+                        "agent_id": agent.agent_id.to_string(),
+                        "agent_type": agent.agent_type,
+                        "status": agent_heap::agent_status_to_str(agent.status),
+                        "last_heartbeat": agent.last_heartbeat.to_rfc3339(),
+                    })
+                })
+                .collect();
+            pgrx::JsonB(serde_json::json!(json_agents))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list stale agents: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// List agents by type with tenant isolation.
+#[pg_extern]
+fn caliber_agent_list_by_type_and_tenant(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
     Spi::connect(|client| {
         let result = client.select(
-            "SELECT agent_id, agent_type, capabilities, status, created_at, last_heartbeat
-             FROM caliber_agent ORDER BY created_at",
+            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
+                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
+             FROM caliber_agent
+             WHERE agent_type = $1 AND tenant_id = $2",
             None,
-            &[],
+            &[
+                text_datum(agent_type),
+                pgrx_uuid_datum(tenant_id),
+            ],
         );
 
         match result {
@@ -4677,2569 +8737,12737 @@ fn caliber_debug_dump_agents() -> pgrx::JsonB {
                         "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
                         "agent_type": row.get::<String>(2).ok().flatten(),
                         "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
-                        "status": row.get::<String>(4).ok().flatten(),
-                        "created_at": row.get::<TimestampWithTimeZone>(5).ok().flatten().map(|t| t.to_string()),
-                        "last_heartbeat": row.get::<TimestampWithTimeZone>(6).ok().flatten().map(|t| t.to_string()),
+                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
+                        "status": row.get::<String>(5).ok().flatten(),
+                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
+                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
+                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
+                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
                     })
                 }).collect();
                 pgrx::JsonB(serde_json::json!(agents))
             }
             Err(e) => {
-                pgrx::warning!("CALIBER: Failed to dump agents: {}", e);
+                pgrx::warning!("CALIBER: Failed to list agents by type: {}", e);
                 pgrx::JsonB(serde_json::json!([]))
             }
         }
     })
 }
 
-// ============================================================================
-// ACCESS CONTROL (Task 12.3)
-// ============================================================================
-
-/// Access operation type for permission checking.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum AccessOperation {
-    Read,
-    Write,
-}
+/// List agents currently working on a trajectory.
+///
+/// Matches agents whose `current_trajectory_id` was set via
+/// `caliber_agent_set_context`, most recent heartbeat first.
+#[pg_extern]
+fn caliber_agents_on_trajectory(trajectory_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT agent_id, agent_type, status, current_scope_id, last_heartbeat
+             FROM caliber_agent
+             WHERE tenant_id = $1 AND current_trajectory_id = $2
+             ORDER BY last_heartbeat DESC",
+            None,
+            &[pgrx_uuid_datum(tenant_id), pgrx_uuid_datum(trajectory_id)],
+        );
 
-/// Enforce access control for a memory region.
-/// Returns Ok(()) if access is allowed, Err with PermissionDenied otherwise.
-fn enforce_access(
-    agent_id: Uuid,
-    region_id: Uuid,
-    operation: AccessOperation,
-) -> CaliberResult<()> {
-    // Get region config from SQL table
-    let region_config = Spi::connect(|client| {
-        use pgrx::datum::DatumWithOid;
-        let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
-        let params: &[DatumWithOid<'_>] =
-            &[unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) }];
+        match result {
+            Ok(table) => {
+                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
+                    serde_json::json!({
+                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "agent_type": row.get::<String>(2).ok().flatten(),
+                        "status": row.get::<String>(3).ok().flatten(),
+                        "current_scope_id": row.get::<pgrx::Uuid>(4).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "last_heartbeat": row.get::<TimestampWithTimeZone>(5).ok().flatten().map(|t| t.to_string()),
+                    })
+                }).collect();
+                pgrx::JsonB(serde_json::json!(agents))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to list agents on trajectory: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
+
+/// List active agents for a tenant.
+#[pg_extern]
+fn caliber_agent_list_active_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    Spi::connect(|client| {
         let result = client.select(
-            "SELECT region_id, region_type, owner_agent_id, readers, writers, require_lock
-             FROM caliber_region WHERE region_id = $1",
+            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
+                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
+             FROM caliber_agent
+             WHERE status IN ('active', 'idle') AND tenant_id = $1",
             None,
-            params,
+            &[pgrx_uuid_datum(tenant_id)],
         );
 
         match result {
-            Ok(mut table) => {
-                if let Some(row) = table.next() {
-                    let region_type: Option<String> = row.get(2).ok().flatten();
-                    let owner_agent_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
-                    // Note: team_id exists in schema but team membership lookup not yet implemented
-                    // For now, use explicit readers/writers arrays
-                    let readers: Option<Vec<pgrx::Uuid>> = row.get(4).ok().flatten();
-                    let writers: Option<Vec<pgrx::Uuid>> = row.get(5).ok().flatten();
-                    let require_lock: Option<bool> = row.get(6).ok().flatten();
+            Ok(table) => {
+                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
+                    serde_json::json!({
+                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "agent_type": row.get::<String>(2).ok().flatten(),
+                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
+                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
+                        "status": row.get::<String>(5).ok().flatten(),
+                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
+                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
+                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
+                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    })
+                }).collect();
+                pgrx::JsonB(serde_json::json!(agents))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to list active agents: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
 
-                    // Convert pgrx::Uuid to uuid::Uuid with explicit type annotations
-                    let owner_id =
-                        owner_agent_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()));
-                    let readers_vec: Vec<Uuid> = readers
-                        .unwrap_or_default()
-                        .iter()
-                        .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()))
-                        .collect();
-                    let writers_vec: Vec<Uuid> = writers
-                        .unwrap_or_default()
-                        .iter()
-                        .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()))
-                        .collect();
+/// List all agents for a tenant.
+#[pg_extern]
+fn caliber_agent_list_all_by_tenant(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT agent_id, agent_type, capabilities, memory_access, status, current_trajectory_id, current_scope_id,
+                    can_delegate_to, reports_to, created_at, last_heartbeat, tenant_id
+             FROM caliber_agent
+             WHERE tenant_id = $1",
+            None,
+            &[pgrx_uuid_datum(tenant_id)],
+        );
 
-                    Some((
-                        region_type.unwrap_or_default(),
-                        owner_id,
-                        readers_vec,
-                        writers_vec,
-                        require_lock.unwrap_or(false),
-                    ))
-                } else {
-                    None
-                }
+        match result {
+            Ok(table) => {
+                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
+                    serde_json::json!({
+                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "agent_type": row.get::<String>(2).ok().flatten(),
+                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
+                        "memory_access": row.get::<pgrx::JsonB>(4).ok().flatten().map(|j| j.0).unwrap_or(serde_json::json!({})),
+                        "status": row.get::<String>(5).ok().flatten(),
+                        "current_trajectory_id": row.get::<pgrx::Uuid>(6).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "current_scope_id": row.get::<pgrx::Uuid>(7).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "can_delegate_to": row.get::<Vec<String>>(8).ok().flatten().unwrap_or_default(),
+                        "reports_to": row.get::<pgrx::Uuid>(9).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "created_at": row.get::<TimestampWithTimeZone>(10).ok().flatten().map(|t| t.to_string()),
+                        "last_heartbeat": row.get::<TimestampWithTimeZone>(11).ok().flatten().map(|t| t.to_string()),
+                        "tenant_id": row.get::<pgrx::Uuid>(12).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    })
+                }).collect();
+                pgrx::JsonB(serde_json::json!(agents))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to list agents: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
             }
-            Err(_) => None,
         }
-    });
+    })
+}
 
-    // If region not found, deny access
-    let (region_type, owner_agent_id, readers, writers, require_lock): (
-        String,
-        Option<Uuid>,
-        Vec<Uuid>,
-        Vec<Uuid>,
-        bool,
-    ) = match region_config {
-        Some(config) => config,
-        None => {
-            return Err(CaliberError::Agent(AgentError::PermissionDenied {
-                agent_id,
-                action: format!("{:?}", operation).to_lowercase(),
-                resource: format!("region:{}", region_id),
-            }));
-        }
-    };
+// ============================================================================
+// DELEGATION OPERATIONS (Task 12.6)
+// ============================================================================
 
-    let owner_id = owner_agent_id.unwrap_or(Uuid::nil());
+/// Create a task delegation.
+#[pg_extern]
+fn caliber_delegation_create(
+    delegator_agent_id: pgrx::Uuid,
+    delegatee_agent_id: Option<pgrx::Uuid>,
+    delegatee_agent_type: Option<&str>,
+    task_description: &str,
+    parent_trajectory_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::Uuid {
+    let delegator = id_from_pgrx::<AgentId>(delegator_agent_id);
+    let parent_traj = id_from_pgrx::<TrajectoryId>(parent_trajectory_id);
+    let delegatee = opt_id_from_pgrx::<AgentId>(delegatee_agent_id);
 
-    // Check permission based on region type and operation
-    let allowed = match operation {
-        AccessOperation::Read => match region_type.as_str() {
-            "private" => agent_id == owner_id,
-            "team" => agent_id == owner_id || readers.contains(&agent_id),
-            "public" | "collaborative" => true,
-            _ => {
-                pgrx::warning!(
-                    "CALIBER: Unknown region_type '{}' in read access check, denying access",
-                    region_type
-                );
-                false
-            }
-        },
-        AccessOperation::Write => {
-            let base_allowed = match region_type.as_str() {
-                "private" => agent_id == owner_id,
-                "team" => agent_id == owner_id || writers.contains(&agent_id),
-                "public" => agent_id == owner_id,
-                "collaborative" => true,
-                _ => {
-                    pgrx::warning!(
-                        "CALIBER: Unknown region_type '{}' in write access check, denying access",
-                        region_type
-                    );
-                    false
-                }
-            };
+    // Generate a new delegation ID
+    let delegation_id = DelegationId::now_v7();
 
-            // For collaborative regions, also check if lock is held when required
-            if base_allowed && require_lock && region_type == "collaborative" {
-                // Check if agent holds a lock on this region
-                let holds_lock = Spi::connect(|client| {
-                    use pgrx::datum::DatumWithOid;
-                    let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
-                    let pg_agent_id = pgrx::Uuid::from_bytes(*agent_id.as_bytes());
-                    let params: &[DatumWithOid<'_>] = &[
-                        unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) },
-                        unsafe { DatumWithOid::new(pg_agent_id, pgrx::pg_sys::UUIDOID) },
-                    ];
-                    let result = client.select(
-                        "SELECT 1 FROM caliber_lock
-                         WHERE resource_type = 'region' AND resource_id = $1
-                         AND holder_agent_id = $2 AND expires_at > NOW()",
-                        None,
-                        params,
-                    );
-                    match result {
-                        Ok(table) => !table.is_empty(),
-                        Err(_) => false,
-                    }
-                });
+    // Use direct heap operations instead of SPI
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-                if !holds_lock {
-                    return Err(CaliberError::Agent(AgentError::LockAcquisitionFailed {
-                        resource: format!("region:{}", region_id),
-                        holder: Uuid::nil(), // Unknown holder
-                    }));
-                }
-                true
-            } else {
-                base_allowed
-            }
-        }
-    };
+    let result = delegation_heap::delegation_create_heap(delegation_heap::DelegationCreateParams {
+        delegation_id,
+        delegator_agent_id: delegator,
+        delegatee_agent_id: delegatee,
+        delegatee_agent_type,
+        task_description,
+        parent_trajectory_id: parent_traj,
+        child_trajectory_id: None,
+        shared_artifacts: &[],
+        shared_notes: &[],
+        additional_context: None,
+        constraints: None,
+        deadline: None,
+        tenant_id: tenant_uuid,
+    });
 
-    if allowed {
-        Ok(())
-    } else {
-        Err(CaliberError::Agent(AgentError::PermissionDenied {
-            agent_id,
-            action: format!("{:?}", operation).to_lowercase(),
-            resource: format!("region:{}", region_id),
-        }))
+    match result {
+        Ok(_) => audit_record(
+            EntityType::Delegation,
+            delegation_id,
+            "create",
+            Some(delegator),
+            tenant_uuid,
+        ),
+        Err(e) => pgrx::warning!("CALIBER: Failed to insert delegation: {}", e),
     }
-}
-
-/// Check if an agent has access to a memory region.
-/// Returns true if access is allowed, false otherwise.
-#[pg_extern]
-fn caliber_check_access(agent_id: pgrx::Uuid, region_id: pgrx::Uuid, access_type: &str) -> bool {
-    let aid = Uuid::from_bytes(*agent_id.as_bytes());
-    let rid = Uuid::from_bytes(*region_id.as_bytes());
 
-    let operation = match access_type {
-        "read" => AccessOperation::Read,
-        "write" => AccessOperation::Write,
-        _ => {
-            pgrx::warning!(
-                "CALIBER: Invalid access_type '{}', must be 'read' or 'write'",
-                access_type
-            );
-            return false;
-        }
-    };
+    pgrx_uuid_from_id(delegation_id)
+}
 
-    match enforce_access(aid, rid, operation) {
-        Ok(()) => true,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Access denied - {:?}", e);
-            false
-        }
+/// Database representation of a delegation status.
+fn delegation_status_str(status: DelegationStatus) -> &'static str {
+    match status {
+        DelegationStatus::Pending => "pending",
+        DelegationStatus::Accepted => "accepted",
+        DelegationStatus::Rejected => "rejected",
+        DelegationStatus::InProgress => "in_progress",
+        DelegationStatus::Completed => "completed",
+        DelegationStatus::Failed => "failed",
     }
 }
 
-/// Create a new memory region.
+// Get a delegation by ID.
+caliber_pg_get!(delegation, delegation_heap, DelegationId, |row| {
+    let d = row.delegation;
+    serde_json::json!({
+        "delegation_id": d.delegation_id.to_string(),
+        "delegator_agent_id": d.delegator_agent_id.to_string(),
+        "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
+        "delegatee_agent_type": d.delegatee_agent_type,
+        "task_description": d.task_description,
+        "parent_trajectory_id": d.parent_trajectory_id.to_string(),
+        "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
+        "shared_artifacts": d.shared_artifacts.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        "shared_notes": d.shared_notes.iter().map(|id| id.to_string()).collect::<Vec<_>>(),
+        "additional_context": d.additional_context,
+        "constraints": d.constraints,
+        "deadline": d.deadline.map(|dt| dt.to_rfc3339()),
+        "status": delegation_status_str(d.status),
+        "result": d.result.as_ref().map(safe_to_json),
+        "created_at": d.created_at.to_rfc3339(),
+        "accepted_at": d.accepted_at.map(|dt| dt.to_rfc3339()),
+        "completed_at": d.completed_at.map(|dt| dt.to_rfc3339()),
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+});
+
+/// Accept a delegation.
+///
+/// Records the accepting agent and the child trajectory created for the delegated work.
 #[pg_extern]
-fn caliber_region_create(
-    owner_agent_id: pgrx::Uuid,
-    region_type: &str,
-    team_id: Option<pgrx::Uuid>,
-    require_lock: bool,
+fn caliber_delegation_accept(
+    delegation_id: pgrx::Uuid,
+    delegatee_agent_id: pgrx::Uuid,
+    child_trajectory_id: pgrx::Uuid,
     tenant_id: pgrx::Uuid,
-) -> Option<pgrx::Uuid> {
-    use pgrx::datum::DatumWithOid;
-
-    let pg_owner = owner_agent_id;
-    let region_id = Uuid::now_v7();
-    let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
-    let now = match tuple_extract::chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return None;
-        }
-    };
+) -> bool {
+    let entity_id = id_from_pgrx::<DelegationId>(delegation_id);
+    let agent_id = id_from_pgrx::<AgentId>(delegatee_agent_id);
+    let traj_id = id_from_pgrx::<TrajectoryId>(child_trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Validate region_type
-    let valid_region_type = match region_type {
-        "private" | "team" | "public" | "collaborative" => region_type,
-        _ => {
-            pgrx::warning!("CALIBER: Invalid region_type '{}'. Valid values: private, team, public, collaborative", region_type);
-            return None;
+    // Use direct heap operations - pass all parameters
+    match delegation_heap::delegation_accept_heap(entity_id, agent_id, traj_id, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Delegation,
+                    entity_id,
+                    "accept",
+                    Some(agent_id),
+                    tenant_uuid,
+                );
+            }
+            updated
         }
-    };
-
-    // Determine default conflict resolution based on region type
-    let conflict_resolution = match valid_region_type {
-        "collaborative" => "escalate",
-        _ => "last_write_wins",
-    };
-
-    // Determine version tracking based on region type
-    let version_tracking = matches!(valid_region_type, "team" | "collaborative");
-
-    // For private regions, owner is the only reader/writer
-    let (readers, writers): (Vec<pgrx::Uuid>, Vec<pgrx::Uuid>) = if valid_region_type == "private" {
-        (vec![pg_owner], vec![pg_owner])
-    } else if valid_region_type == "public" {
-        (Vec::new(), vec![pg_owner])
-    } else {
-        (Vec::new(), Vec::new())
-    };
-
-    let result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(valid_region_type, pgrx::pg_sys::TEXTOID) },
-            unsafe { DatumWithOid::new(pg_owner, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(team_id, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(readers.clone(), pgrx::pg_sys::UUIDARRAYOID) },
-            unsafe { DatumWithOid::new(writers.clone(), pgrx::pg_sys::UUIDARRAYOID) },
-            bool_datum(require_lock),
-            unsafe { DatumWithOid::new(conflict_resolution, pgrx::pg_sys::TEXTOID) },
-            bool_datum(version_tracking),
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        client.update(
-            "INSERT INTO caliber_region (region_id, region_type, owner_agent_id, team_id,
-                                         readers, writers, require_lock, conflict_resolution,
-                                         version_tracking, created_at, updated_at, tenant_id)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(())
-    });
-
-    match result {
-        Ok(()) => Some(pg_region_id),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to create region: {}", e);
-            None
+            pgrx::warning!("CALIBER: Failed to accept delegation: {}", e);
+            false
         }
     }
 }
 
-/// Get a memory region by ID.
-#[pg_extern]
-fn caliber_region_get(region_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
-    use pgrx::datum::DatumWithOid;
-
-    let rid = Uuid::from_bytes(*region_id.as_bytes());
-    let pg_rid = pgrx::Uuid::from_bytes(*rid.as_bytes());
-
-    Spi::connect(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let result = client.select(
-            "SELECT region_id, region_type, owner_agent_id, team_id, readers, writers,
-                    require_lock, conflict_resolution, version_tracking, created_at, updated_at
-             FROM caliber_region WHERE region_id = $1 AND tenant_id = $2",
-            None,
-            params,
-        );
-
-        match result {
-            Ok(mut table) => {
-                if let Some(row) = table.next() {
-                    let region_id_val: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                    let region_type: Option<String> = row.get(2).ok().flatten();
-                    let owner_agent_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
-                    let team_id: Option<pgrx::Uuid> = row.get(4).ok().flatten();
-                    let readers: Option<Vec<pgrx::Uuid>> = row.get(5).ok().flatten();
-                    let writers: Option<Vec<pgrx::Uuid>> = row.get(6).ok().flatten();
-                    let require_lock: Option<bool> = row.get(7).ok().flatten();
-                    let conflict_resolution: Option<String> = row.get(8).ok().flatten();
-                    let version_tracking: Option<bool> = row.get(9).ok().flatten();
-                    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-                    let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
-
-                    // Convert pgrx::Uuid to uuid::Uuid strings with explicit type annotations
-                    let region_id_str = region_id_val
-                        .map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
-                    let owner_id_str = owner_agent_id
-                        .map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
-                    let team_id_str =
-                        team_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
-                    let readers_strs = readers.map(|ids: Vec<pgrx::Uuid>| {
-                        ids.iter()
-                            .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string())
-                            .collect::<Vec<_>>()
-                    });
-                    let writers_strs = writers.map(|ids: Vec<pgrx::Uuid>| {
-                        ids.iter()
-                            .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string())
-                            .collect::<Vec<_>>()
-                    });
-
-                    Some(pgrx::JsonB(serde_json::json!({
-                        "region_id": region_id_str,
-                        "region_type": region_type,
-                        "owner_agent_id": owner_id_str,
-                        "team_id": team_id_str,
-                        "readers": readers_strs,
-                        "writers": writers_strs,
-                        "require_lock": require_lock,
-                        "conflict_resolution": conflict_resolution,
-                        "version_tracking": version_tracking,
-                        "created_at": created_at.map(|t| format!("{:?}", t)),
-                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
-                    })))
-                } else {
-                    None
-                }
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to get region: {}", e);
-                None
-            }
-        }
-    })
-}
-
-/// Add a reader to a memory region.
+/// Mark an accepted delegation as in progress.
+///
+/// Only the agent that accepted the delegation can start it, and only while
+/// it is in `accepted` status.
 #[pg_extern]
-fn caliber_region_add_reader(
-    region_id: pgrx::Uuid,
-    agent_id: pgrx::Uuid,
+fn caliber_delegation_start(
+    delegation_id: pgrx::Uuid,
+    delegatee_agent_id: pgrx::Uuid,
     tenant_id: pgrx::Uuid,
 ) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
+    let entity_id = id_from_pgrx::<DelegationId>(delegation_id);
+    let agent_id = id_from_pgrx::<AgentId>(delegatee_agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    let pg_rid = region_id;
-    let pg_aid = agent_id;
-    let now = match chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return false;
+    match delegation_heap::delegation_start_heap(entity_id, agent_id, tenant_uuid) {
+        Ok(true) => {
+            audit_record(
+                EntityType::Delegation,
+                entity_id,
+                "start",
+                Some(agent_id),
+                tenant_uuid,
+            );
+            true
+        }
+        Ok(false) => {
+            pgrx::warning!(
+                "CALIBER: Delegation {} is not accepted by agent {}",
+                entity_id,
+                agent_id
+            );
+            false
         }
-    };
-
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let table = client.update(
-            "UPDATE caliber_region
-             SET readers = array_append(readers, $1), updated_at = $2
-             WHERE region_id = $3 AND tenant_id = $4 AND NOT ($1 = ANY(readers))",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(table.len())
-    });
-
-    match result {
-        Ok(len) => len > 0,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to add reader: {}", e);
+            pgrx::warning!("CALIBER: Failed to start delegation: {}", e);
             false
         }
     }
 }
 
-/// Add a writer to a memory region.
+/// Complete a delegation.
 #[pg_extern]
-fn caliber_region_add_writer(
-    region_id: pgrx::Uuid,
-    agent_id: pgrx::Uuid,
+fn caliber_delegation_complete(
+    delegation_id: pgrx::Uuid,
+    success: bool,
+    summary: &str,
     tenant_id: pgrx::Uuid,
 ) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
+    let entity_id = id_from_pgrx::<DelegationId>(delegation_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    let pg_rid = region_id;
-    let pg_aid = agent_id;
-    let now = match chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return false;
+    // Build DelegationResult from parameters
+    let result = if success {
+        DelegationResult {
+            status: DelegationResultStatus::Success,
+            produced_artifacts: vec![],
+            produced_notes: vec![],
+            summary: summary.to_string(),
+            error: None,
+        }
+    } else {
+        DelegationResult {
+            status: DelegationResultStatus::Failure,
+            produced_artifacts: vec![],
+            produced_notes: vec![],
+            summary: String::new(),
+            error: Some(summary.to_string()),
         }
     };
 
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let table = client.update(
-            "UPDATE caliber_region
-             SET writers = array_append(writers, $1), updated_at = $2
-             WHERE region_id = $3 AND tenant_id = $4 AND NOT ($1 = ANY(writers))",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(table.len())
-    });
-
-    match result {
-        Ok(len) => len > 0,
+    // Use direct heap operations instead of SPI
+    match delegation_heap::delegation_complete_heap(entity_id, &result, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Delegation,
+                    entity_id,
+                    "complete",
+                    None,
+                    tenant_uuid,
+                );
+            }
+            updated
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to add writer: {}", e);
+            pgrx::warning!("CALIBER: Failed to complete delegation: {}", e);
             false
         }
     }
 }
 
-/// Remove a reader from a memory region.
-#[pg_extern]
-fn caliber_region_remove_reader(
-    region_id: pgrx::Uuid,
-    agent_id: pgrx::Uuid,
-    tenant_id: pgrx::Uuid,
+/// Convert a pending delegation row to JSON.
+fn pending_delegation_json(row: delegation_heap::DelegationRow) -> serde_json::Value {
+    let d = row.delegation;
+    serde_json::json!({
+        "delegation_id": d.delegation_id.to_string(),
+        "delegator_agent_id": d.delegator_agent_id.to_string(),
+        "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
+        "delegatee_agent_type": d.delegatee_agent_type,
+        "task_description": d.task_description,
+        "parent_trajectory_id": d.parent_trajectory_id.to_string(),
+        "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
+        "status": "pending",
+        "created_at": d.created_at.to_rfc3339(),
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+}
+
+/// Check whether a pending delegation can be picked up by an agent with the
+/// given type and capabilities.
+///
+/// A delegation targeted at a specific agent only matches that agent.
+/// Otherwise it matches on delegatee type (or wildcard "*"), on capabilities
+/// mentioned in the task description, or on a `required_capabilities` array
+/// in constraints. Capability names are compared case-insensitively against
+/// whole words of the description, so "rust" does not match "trust".
+fn delegation_matches_agent(
+    delegation: &DelegatedTask,
+    agent_id: AgentId,
+    agent_type: &str,
+    capabilities: &[String],
 ) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
+    if let Some(target) = delegation.delegatee_agent_id {
+        return target == agent_id;
+    }
 
-    let pg_rid = region_id;
-    let pg_aid = agent_id;
-    let now = match chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
+    if matches!(delegation.delegatee_agent_type.as_deref(), Some(t) if t == agent_type || t == "*")
+    {
+        return true;
+    }
+
+    let description = delegation.task_description.to_lowercase();
+    let words: Vec<&str> = description
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let required: Vec<String> = delegation
+        .constraints
+        .as_ref()
+        .and_then(|c| c.get("required_capabilities"))
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_lowercase())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    capabilities.iter().any(|cap| {
+        let cap = cap.to_lowercase();
+        !cap.is_empty() && (words.contains(&cap.as_str()) || required.contains(&cap))
+    })
+}
+
+/// List pending delegations for an agent type.
+///
+/// Delegations targeted at a specific agent are not listed; that agent finds
+/// them with `caliber_delegation_find_for_agent`.
+#[pg_extern]
+fn caliber_delegation_list_pending(agent_type: &str, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    // Use direct heap operations instead of SPI
+    match delegation_heap::delegation_list_pending_heap(tenant_uuid) {
+        Ok(delegations) => {
+            // Filter by agent type (matching agent_type or wildcard "*")
+            let filtered: Vec<serde_json::Value> = delegations
+                .into_iter()
+                .filter(|row| {
+                    row.delegation.delegatee_agent_id.is_none()
+                        && (row.delegation.delegatee_agent_type.as_deref() == Some(agent_type)
+                            || row.delegation.delegatee_agent_type.as_deref() == Some("*"))
+                })
+                .map(pending_delegation_json)
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(filtered))
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return false;
+            pgrx::warning!("CALIBER: Failed to list pending delegations: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Find pending delegations an agent can pick up, matching on agent type or
+/// on the agent's capabilities.
+#[pg_extern]
+fn caliber_delegation_find_for_agent(agent_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let entity_id = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let agent = match agent_heap::agent_get_heap(entity_id, tenant_uuid) {
+        Ok(Some(row)) => row.agent,
+        Ok(None) => {
+            pgrx::warning!("CALIBER: Agent {} not found", entity_id);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get agent: {}", e);
+            return pgrx::JsonB(serde_json::json!([]));
         }
     };
 
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let table = client.update(
-            "UPDATE caliber_region
-             SET readers = array_remove(readers, $1), updated_at = $2
-             WHERE region_id = $3 AND tenant_id = $4",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(table.len())
-    });
+    // Use direct heap operations instead of SPI
+    match delegation_heap::delegation_list_pending_heap(tenant_uuid) {
+        Ok(delegations) => {
+            let filtered: Vec<serde_json::Value> = delegations
+                .into_iter()
+                .filter(|row| {
+                    delegation_matches_agent(
+                        &row.delegation,
+                        entity_id,
+                        &agent.agent_type,
+                        &agent.capabilities,
+                    )
+                })
+                .map(pending_delegation_json)
+                .collect();
 
-    match result {
-        Ok(len) => len > 0,
+            pgrx::JsonB(serde_json::json!(filtered))
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to remove reader: {}", e);
-            false
+            pgrx::warning!("CALIBER: Failed to list pending delegations: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-/// Remove a writer from a memory region.
+/// List delegations handed out by a delegator agent, oldest first.
+///
+/// `status` optionally restricts the result to one of pending, accepted,
+/// in_progress, completed, failed or rejected.
 #[pg_extern]
-fn caliber_region_remove_writer(
-    region_id: pgrx::Uuid,
-    agent_id: pgrx::Uuid,
+fn caliber_delegation_list_by_delegator(
+    delegator_agent_id: pgrx::Uuid,
+    status: Option<&str>,
     tenant_id: pgrx::Uuid,
-) -> bool {
-    use pgrx::datum::DatumWithOid;
-    use tuple_extract::chrono_to_timestamp;
+) -> pgrx::JsonB {
+    let delegator = id_from_pgrx::<AgentId>(delegator_agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    let pg_rid = region_id;
-    let pg_aid = agent_id;
-    let now = match chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
+    // Validate status (REQ-12)
+    let status_filter = match status {
+        None => None,
+        Some("pending") => Some(DelegationStatus::Pending),
+        Some("accepted") => Some(DelegationStatus::Accepted),
+        Some("in_progress") => Some(DelegationStatus::InProgress),
+        Some("completed") => Some(DelegationStatus::Completed),
+        Some("failed") => Some(DelegationStatus::Failed),
+        Some("rejected") => Some(DelegationStatus::Rejected),
+        Some(other) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "status".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: pending, accepted, in_progress, completed, failed, rejected",
+                    other
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    match delegation_heap::delegation_list_by_delegator_heap(delegator, tenant_uuid) {
+        Ok(delegations) => {
+            let filtered: Vec<serde_json::Value> = delegations
+                .into_iter()
+                .filter(|row| status_filter.is_none_or(|s| row.delegation.status == s))
+                .map(|row| {
+                    let d = row.delegation;
+                    serde_json::json!({
+                        "delegation_id": d.delegation_id.to_string(),
+                        "delegator_agent_id": d.delegator_agent_id.to_string(),
+                        "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
+                        "delegatee_agent_type": d.delegatee_agent_type,
+                        "task_description": d.task_description,
+                        "parent_trajectory_id": d.parent_trajectory_id.to_string(),
+                        "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
+                        "status": delegation_status_str(d.status),
+                        "result": d.result.as_ref().map(safe_to_json),
+                        "created_at": d.created_at.to_rfc3339(),
+                        "accepted_at": d.accepted_at.map(|dt| dt.to_rfc3339()),
+                        "completed_at": d.completed_at.map(|dt| dt.to_rfc3339()),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
+
+            pgrx::JsonB(serde_json::json!(filtered))
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return false;
+            pgrx::warning!("CALIBER: Failed to list delegations by delegator: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
         }
+    }
+}
+
+/// Maximum nesting depth followed by `caliber_delegation_tree`.
+const DELEGATION_TREE_MAX_DEPTH: usize = 32;
+
+/// Build the delegation nodes hanging off `trajectory_id`.
+///
+/// `by_parent` groups delegations by parent trajectory. Trajectories already
+/// on the current path are not expanded again, so a cyclic chain terminates.
+fn delegation_tree_nodes(
+    trajectory_id: TrajectoryId,
+    by_parent: &HashMap<TrajectoryId, Vec<&DelegatedTask>>,
+    path: &mut HashSet<TrajectoryId>,
+    depth: usize,
+) -> Vec<serde_json::Value> {
+    let Some(delegations) = by_parent.get(&trajectory_id) else {
+        return Vec::new();
     };
+    path.insert(trajectory_id);
 
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let params: &[DatumWithOid<'_>] = &[
-            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
-        let table = client.update(
-            "UPDATE caliber_region
-             SET writers = array_remove(writers, $1), updated_at = $2
-             WHERE region_id = $3 AND tenant_id = $4",
-            None,
-            params,
-        )?;
-        Ok::<_, pgrx::spi::SpiError>(table.len())
-    });
+    let nodes = delegations
+        .iter()
+        .map(|d| {
+            let children = match d.child_trajectory_id {
+                Some(child) if depth < DELEGATION_TREE_MAX_DEPTH && !path.contains(&child) => {
+                    delegation_tree_nodes(child, by_parent, path, depth + 1)
+                }
+                _ => Vec::new(),
+            };
+            serde_json::json!({
+                "delegation_id": d.delegation_id.to_string(),
+                "delegator_agent_id": d.delegator_agent_id.to_string(),
+                "delegatee_agent_id": d.delegatee_agent_id.map(|id| id.to_string()),
+                "delegatee_agent_type": d.delegatee_agent_type,
+                "task_description": d.task_description,
+                "parent_trajectory_id": d.parent_trajectory_id.to_string(),
+                "child_trajectory_id": d.child_trajectory_id.map(|id| id.to_string()),
+                "status": delegation_status_str(d.status),
+                "created_at": d.created_at.to_rfc3339(),
+                "completed_at": d.completed_at.map(|dt| dt.to_rfc3339()),
+                "children": children,
+            })
+        })
+        .collect();
 
-    match result {
-        Ok(len) => len > 0,
+    path.remove(&trajectory_id);
+    nodes
+}
+
+/// Trace the delegation chains that start at a trajectory.
+///
+/// Each delegation whose parent is `root_trajectory_id` becomes a node; its
+/// `children` are the delegations made from its child trajectory, and so on.
+/// Siblings are ordered oldest first.
+#[pg_extern]
+fn caliber_delegation_tree(root_trajectory_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let root = id_from_pgrx::<TrajectoryId>(root_trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let rows = match delegation_heap::delegation_list_heap(tenant_uuid) {
+        Ok(rows) => rows,
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to remove writer: {}", e);
-            false
+            pgrx::warning!("CALIBER: Failed to list delegations: {}", e);
+            return pgrx::JsonB(serde_json::json!({}));
         }
+    };
+
+    let mut by_parent: HashMap<TrajectoryId, Vec<&DelegatedTask>> = HashMap::new();
+    for row in &rows {
+        by_parent
+            .entry(row.delegation.parent_trajectory_id)
+            .or_default()
+            .push(&row.delegation);
     }
+
+    let delegations = delegation_tree_nodes(root, &by_parent, &mut HashSet::new(), 1);
+    pgrx::JsonB(serde_json::json!({
+        "trajectory_id": root.to_string(),
+        "delegations": delegations,
+    }))
 }
 
 // ============================================================================
-// EDGE OPERATIONS (Battle Intel Feature 1)
+// HANDOFF OPERATIONS (Task 12.6)
 // ============================================================================
 
-/// Create a new edge (graph relationship).
+/// Check that a handoff's context snapshot refers to saved context.
 ///
-/// Edges can be binary (2 participants) or hyperedges (N participants).
-/// Inspired by Mem0's graph-based memory for +2% retrieval improvement.
+/// A snapshot is either an artifact or a scope that has a checkpoint, in
+/// the same tenant.
+fn handoff_snapshot_exists(snapshot_id: pgrx::Uuid, tenant_id: TenantId) -> CaliberResult<bool> {
+    if artifact_heap::artifact_get_heap(id_from_pgrx::<ArtifactId>(snapshot_id), tenant_id)?
+        .is_some()
+    {
+        return Ok(true);
+    }
+    Ok(
+        scope_heap::scope_get_heap(id_from_pgrx::<ScopeId>(snapshot_id), tenant_id)?
+            .is_some_and(|row| row.scope.checkpoint.is_some()),
+    )
+}
+
+/// Create an agent handoff.
 ///
-/// # Arguments
-/// * `edge_type` - Type of relationship: supports, contradicts, supersedes,
-///   derivedfrom, relatesto, temporal, causal, synthesizedfrom, grouped, compared
-/// * `participants` - JSON array of participants with entity_type, id, and role
-/// * `weight` - Optional relationship strength 0.0-1.0
-/// * `trajectory_id` - Optional trajectory context
-/// * `source_turn` - Turn where this edge was extracted
-/// * `extraction_method` - How edge was created: explicit, inferred, userprovided
-/// * `confidence` - Optional confidence score 0.0-1.0
+/// `context_snapshot_id` must refer to an artifact or a checkpointed scope
+/// in the tenant; creation is rejected with an error otherwise.
 #[allow(clippy::too_many_arguments)]
 #[pg_extern]
-fn caliber_edge_create(
-    edge_type: &str,
-    participants: pgrx::JsonB,
-    weight: Option<f32>,
-    trajectory_id: Option<pgrx::Uuid>,
-    source_turn: i32,
-    extraction_method: &str,
-    confidence: Option<f32>,
+fn caliber_handoff_create(
+    from_agent_id: pgrx::Uuid,
+    to_agent_id: Option<pgrx::Uuid>,
+    to_agent_type: Option<&str>,
+    trajectory_id: pgrx::Uuid,
+    scope_id: pgrx::Uuid,
+    context_snapshot_id: pgrx::Uuid,
+    reason: &str,
     tenant_id: pgrx::Uuid,
-) -> Option<pgrx::Uuid> {
-    // Record operation for metrics
-    storage_write().record_op("edge_create");
-
-    let edge_id = EdgeId::now_v7();
+) -> pgrx::Uuid {
+    let from_agent = id_from_pgrx::<AgentId>(from_agent_id);
+    let to_agent = opt_id_from_pgrx::<AgentId>(to_agent_id);
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let scp_id = id_from_pgrx::<ScopeId>(scope_id);
+    let snapshot_id = id_from_pgrx::<ArtifactId>(context_snapshot_id);
 
-    // Validate edge_type - reject unknown values (REQ-12)
-    let edge_type_enum = match edge_type {
-        "supports" => EdgeType::Supports,
-        "contradicts" => EdgeType::Contradicts,
-        "supersedes" => EdgeType::Supersedes,
-        "derivedfrom" => EdgeType::DerivedFrom,
-        "relatesto" => EdgeType::RelatesTo,
-        "temporal" => EdgeType::Temporal,
-        "causal" => EdgeType::Causal,
-        "synthesizedfrom" => EdgeType::SynthesizedFrom,
-        "grouped" => EdgeType::Grouped,
-        "compared" => EdgeType::Compared,
+    let handoff_reason = match reason {
+        "capability_mismatch" => HandoffReason::CapabilityMismatch,
+        "load_balancing" => HandoffReason::LoadBalancing,
+        "specialization" => HandoffReason::Specialization,
+        "escalation" => HandoffReason::Escalation,
+        "timeout" => HandoffReason::Timeout,
+        "failure" => HandoffReason::Failure,
         _ => {
-            pgrx::warning!("CALIBER: Unknown edge_type '{}'. Valid values: supports, contradicts, supersedes, derivedfrom, relatesto, temporal, causal, synthesizedfrom, grouped, compared", edge_type);
-            return None;
+            if reason != "scheduled" {
+                pgrx::warning!(
+                    "CALIBER: Unknown handoff reason '{}', defaulting to Scheduled",
+                    reason
+                );
+            }
+            HandoffReason::Scheduled
         }
     };
 
-    // Validate extraction_method
-    let extraction_method_enum = match extraction_method {
-        "explicit" => ExtractionMethod::Explicit,
-        "inferred" => ExtractionMethod::Inferred,
-        "userprovided" => ExtractionMethod::UserProvided,
-        _ => {
-            pgrx::warning!("CALIBER: Unknown extraction_method '{}'. Valid values: explicit, inferred, userprovided", extraction_method);
-            return None;
-        }
-    };
+    let handoff_id = HandoffId::now_v7();
 
-    // Parse participants from JSON
-    let participants_vec: Vec<caliber_core::EdgeParticipant> =
-        match serde_json::from_value(participants.0) {
-            Ok(p) => p,
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to parse participants JSON: {}", e);
-                return None;
-            }
-        };
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Validate at least 2 participants
-    if participants_vec.len() < 2 {
-        pgrx::warning!("CALIBER: Edge must have at least 2 participants");
-        return None;
+    // Validate the snapshot reference (REQ-12)
+    match handoff_snapshot_exists(context_snapshot_id, tenant_uuid) {
+        Ok(true) => {}
+        Ok(false) => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "context_snapshot_id".to_string(),
+                reason: format!("{} is not an artifact or checkpointed scope", snapshot_id),
+            };
+            pgrx::error!("CALIBER: {:?}", validation_err);
+        }
+        Err(e) => {
+            pgrx::error!("CALIBER: Failed to validate handoff snapshot: {}", e);
+        }
     }
 
-    // Build Edge struct
-    let edge = caliber_core::Edge {
-        edge_id,
-        edge_type: edge_type_enum,
-        participants: participants_vec,
-        weight,
-        trajectory_id: trajectory_id.map(id_from_pgrx::<TrajectoryId>),
-        provenance: Provenance {
-            source_turn,
-            extraction_method: extraction_method_enum,
-            confidence,
-        },
-        created_at: Utc::now(),
-        metadata: None,
-    };
-
-    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
-
-    // Insert via direct heap operations (NO SQL)
-    match edge_heap::edge_create_heap(&edge, tenant_uuid) {
-        Ok(_) => Some(pgrx_uuid_from_id(edge_id)),
+    // Insert via direct heap operations
+    match handoff_heap::handoff_create_heap(handoff_heap::HandoffCreateParams {
+        handoff_id,
+        from_agent_id: from_agent,
+        to_agent_id: to_agent,
+        to_agent_type,
+        trajectory_id: traj_id,
+        scope_id: scp_id,
+        context_snapshot_id: snapshot_id,
+        handoff_notes: "",
+        next_steps: &[],
+        blockers: &[],
+        open_questions: &[],
+        reason: handoff_reason,
+        tenant_id: tenant_uuid,
+    }) {
+        Ok(_) => {
+            audit_record(
+                EntityType::Handoff,
+                handoff_id,
+                "create",
+                Some(from_agent),
+                tenant_uuid,
+            );
+            pgrx_uuid_from_id(handoff_id)
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to insert edge: {}", e);
-            None
+            pgrx::error!("CALIBER: Failed to insert handoff: {}", e);
         }
     }
 }
 
-// Get an edge by ID.
-caliber_pg_get!(edge, edge_heap, EdgeId, |row| {
-    let edge = row.edge;
+// Get a handoff by ID.
+caliber_pg_get!(handoff, handoff_heap, HandoffId, |row| {
+    let h = row.handoff;
     serde_json::json!({
-        "edge_id": edge.edge_id.to_string(),
-        "edge_type": match edge.edge_type {
-            EdgeType::Supports => "supports",
-            EdgeType::Contradicts => "contradicts",
-            EdgeType::Supersedes => "supersedes",
-            EdgeType::DerivedFrom => "derivedfrom",
-            EdgeType::RelatesTo => "relatesto",
-            EdgeType::Temporal => "temporal",
-            EdgeType::Causal => "causal",
-            EdgeType::SynthesizedFrom => "synthesizedfrom",
-            EdgeType::Grouped => "grouped",
-            EdgeType::Compared => "compared",
+        "handoff_id": h.handoff_id.to_string(),
+        "from_agent_id": h.from_agent_id.to_string(),
+        "to_agent_id": h.to_agent_id.map(|id| id.to_string()),
+        "to_agent_type": h.to_agent_type,
+        "trajectory_id": h.trajectory_id.to_string(),
+        "scope_id": h.scope_id.to_string(),
+        "context_snapshot_id": h.context_snapshot_id.map(|id| id.to_string()),
+        "handoff_notes": h.handoff_notes,
+        "next_steps": h.next_steps,
+        "blockers": h.blockers,
+        "open_questions": h.open_questions,
+        "status": match h.status {
+            HandoffStatus::Initiated => "initiated",
+            HandoffStatus::Accepted => "accepted",
+            HandoffStatus::Completed => "completed",
+            HandoffStatus::Rejected => "rejected",
         },
-        "participants": edge.participants,
-        "weight": edge.weight,
-        "trajectory_id": edge.trajectory_id.map(|id| id.to_string()),
-        "provenance": {
-            "source_turn": edge.provenance.source_turn,
-            "extraction_method": match edge.provenance.extraction_method {
-                ExtractionMethod::Explicit => "explicit",
-                ExtractionMethod::Inferred => "inferred",
-                ExtractionMethod::UserProvided => "userprovided",
-                ExtractionMethod::LlmExtraction => "llm_extraction",
-                ExtractionMethod::ToolExtraction => "tool_extraction",
-                ExtractionMethod::MemoryRecall => "memory_recall",
-                ExtractionMethod::ExternalApi => "external_api",
-                ExtractionMethod::Unknown => "unknown",
-            },
-            "confidence": edge.provenance.confidence,
+        "reason": match h.reason {
+            HandoffReason::CapabilityMismatch => "capability_mismatch",
+            HandoffReason::LoadBalancing => "load_balancing",
+            HandoffReason::Specialization => "specialization",
+            HandoffReason::Escalation => "escalation",
+            HandoffReason::Timeout => "timeout",
+            HandoffReason::Failure => "failure",
+            HandoffReason::Scheduled => "scheduled",
         },
-        "created_at": edge.created_at.to_rfc3339(),
-        "metadata": edge.metadata,
+        "initiated_at": h.initiated_at.to_rfc3339(),
+        "accepted_at": h.accepted_at.map(|t| t.to_rfc3339()),
+        "completed_at": h.completed_at.map(|t| t.to_rfc3339()),
         "tenant_id": row.tenant_id.map(|id| id.to_string()),
     })
 });
 
-/// List edges by participant with tenant isolation.
+/// List initiated handoffs directed at an agent, oldest first.
+///
+/// Handoffs initiated in the same transaction share a timestamp and are
+/// ordered by ID.
+///
+/// Matches handoffs addressed to `agent_id` directly or to its `agent_type`.
+/// This is the handoff inbox, parallel to `caliber_message_get_pending`.
 #[pg_extern]
-fn caliber_edges_by_participant_and_tenant(
-    entity_id: pgrx::Uuid,
+fn caliber_handoff_list_pending(
+    agent_id: pgrx::Uuid,
+    agent_type: &str,
     tenant_id: pgrx::Uuid,
 ) -> pgrx::JsonB {
-    let participant_id = Uuid::from_bytes(*entity_id.as_bytes());
-
-    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let table = client.select(
-            "SELECT edge_id, edge_type, participants, weight, trajectory_id, source_turn, extraction_method, confidence, created_at, metadata, tenant_id
-             FROM caliber_edge
-             WHERE tenant_id = $1",
-            None,
-            &[pgrx_uuid_datum(tenant_id)],
-        )?;
-
-        let mut edges = Vec::new();
-        for row in table {
-            let edge_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let edge_type: Option<String> = row.get(2).ok().flatten();
-            let participants: Option<pgrx::JsonB> = row.get(3).ok().flatten();
-            let weight: Option<f32> = row.get(4).ok().flatten();
-            let trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
-            let source_turn: Option<i32> = row.get(6).ok().flatten();
-            let extraction_method: Option<String> = row.get(7).ok().flatten();
-            let confidence: Option<f32> = row.get(8).ok().flatten();
-            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-            let metadata: Option<pgrx::JsonB> = row.get(10).ok().flatten();
-            let tenant_id_val: Option<pgrx::Uuid> = row.get(11).ok().flatten();
+    let aid = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-            let participants_json = participants
-                .as_ref()
-                .map(|j| j.0.clone())
-                .unwrap_or(serde_json::Value::Null);
-            let participants_vec: Vec<EdgeParticipant> =
-                serde_json::from_value(participants_json.clone()).unwrap_or_default();
+    match handoff_heap::handoff_list_initiated_heap(tenant_uuid) {
+        Ok(handoffs) => {
+            let mut pending: Vec<_> = handoffs
+                .into_iter()
+                .filter(|row| {
+                    row.handoff.to_agent_id == Some(aid)
+                        || row.handoff.to_agent_type.as_deref() == Some(agent_type)
+                })
+                .collect();
+            pending.sort_by_key(|row| (row.handoff.initiated_at, row.handoff.handoff_id.as_uuid()));
 
-            if !participants_vec
-                .iter()
-                .any(|p| p.entity_ref.id == participant_id)
-            {
-                continue;
-            }
+            let json_handoffs: Vec<serde_json::Value> = pending
+                .into_iter()
+                .map(|row| {
+                    let h = row.handoff;
+                    serde_json::json!({
+                        "handoff_id": h.handoff_id.to_string(),
+                        "from_agent_id": h.from_agent_id.to_string(),
+                        "to_agent_id": h.to_agent_id.map(|id| id.to_string()),
+                        "to_agent_type": h.to_agent_type,
+                        "trajectory_id": h.trajectory_id.to_string(),
+                        "scope_id": h.scope_id.to_string(),
+                        "context_snapshot_id": h.context_snapshot_id.map(|id| id.to_string()),
+                        "handoff_notes": h.handoff_notes,
+                        "next_steps": h.next_steps,
+                        "blockers": h.blockers,
+                        "open_questions": h.open_questions,
+                        "status": "initiated",
+                        "initiated_at": h.initiated_at.to_rfc3339(),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+                    })
+                })
+                .collect();
 
-            let json = serde_json::json!({
-                "edge_id": edge_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "edge_type": edge_type,
-                "participants": participants_json,
-                "weight": weight,
-                "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "source_turn": source_turn,
-                "extraction_method": extraction_method,
-                "confidence": confidence,
-                "created_at": created_at.map(|t| t.to_string()),
-                "metadata": metadata.map(|m| m.0),
-                "tenant_id": tenant_id_val.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
-            });
-            edges.push(json);
+            pgrx::JsonB(serde_json::json!(json_handoffs))
         }
-
-        Ok(edges)
-    });
-
-    match result {
-        Ok(edges) => pgrx::JsonB(serde_json::json!(edges)),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list edges by participant: {}", e);
+            pgrx::warning!("CALIBER: Failed to list pending handoffs: {}", e);
             pgrx::JsonB(serde_json::json!([]))
         }
     }
 }
 
-/// List edges by participant entity.
-/// Returns all edges that include the given entity as a participant.
+/// Accept a handoff.
 ///
-/// NOTE: This uses a sequential scan with JSONB containment check.
-/// For high-frequency queries, consider adding a GIN index on participants.
-/// This is NOT hot path - edge queries are analytical, not per-turn.
+/// Records the agent accepting the handoff and updates the handoff status.
 #[pg_extern]
-fn caliber_edges_by_participant(entity_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
-    let id = Uuid::from_bytes(*entity_id.as_bytes());
-
-    // Use SPI for JSONB containment query - this is analytical, not hot path
-    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let search_json = serde_json::json!([{"entity_ref": {"id": id.to_string()}}]);
+fn caliber_handoff_accept(
+    handoff_id: pgrx::Uuid,
+    accepting_agent_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    let id = id_from_pgrx::<HandoffId>(handoff_id);
+    let agent_id = id_from_pgrx::<AgentId>(accepting_agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-        let table = client.select(
-            "SELECT edge_id, edge_type, participants, weight, trajectory_id,
-                    source_turn, extraction_method, confidence, created_at, metadata
-             FROM caliber_edge
-            WHERE participants @> $1::jsonb AND tenant_id = $2",
-            None,
-            &[jsonb_datum(&search_json), pgrx_uuid_datum(tenant_id)],
-        )?;
+    // Use direct heap operations - pass accepting agent ID
+    match handoff_heap::handoff_accept_heap(id, agent_id, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(
+                    EntityType::Handoff,
+                    id,
+                    "accept",
+                    Some(agent_id),
+                    tenant_uuid,
+                );
+            }
+            updated
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to accept handoff: {}", e);
+            false
+        }
+    }
+}
 
-        let mut edges = Vec::new();
-        for row in table {
-            let edge_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let edge_type: Option<String> = row.get(2).ok().flatten();
-            let participants: Option<pgrx::JsonB> = row.get(3).ok().flatten();
-            let weight: Option<f32> = row.get(4).ok().flatten();
-            let trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
-            let source_turn: Option<i32> = row.get(6).ok().flatten();
-            let extraction_method: Option<String> = row.get(7).ok().flatten();
-            let confidence: Option<f32> = row.get(8).ok().flatten();
-            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-            let metadata: Option<pgrx::JsonB> = row.get(10).ok().flatten();
+/// Complete a handoff.
+#[pg_extern]
+fn caliber_handoff_complete(handoff_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let id = id_from_pgrx::<HandoffId>(handoff_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-            let edge_json = serde_json::json!({
-                "edge_id": edge_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "edge_type": edge_type,
-                "participants": participants.map(|j| j.0),
-                "weight": weight,
-                "trajectory_id": trajectory_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "provenance": {
-                    "source_turn": source_turn,
-                    "extraction_method": extraction_method,
-                    "confidence": confidence,
-                },
-                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
-                "metadata": metadata.map(|j| j.0),
-            });
-            edges.push(edge_json);
+    match handoff_heap::handoff_complete_heap(id, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(EntityType::Handoff, id, "complete", None, tenant_uuid);
+            }
+            updated
         }
-        Ok(edges)
-    });
-
-    match result {
-        Ok(edges) => pgrx::JsonB(serde_json::json!(edges)),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list edges by participant: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+            pgrx::warning!("CALIBER: Failed to complete handoff: {}", e);
+            false
         }
     }
 }
 
 // ============================================================================
-// SUMMARIZATION POLICY OPERATIONS (Battle Intel Feature 4)
+// CONFLICT OPERATIONS (Task 12.6)
 // ============================================================================
 
-/// Create a summarization policy.
-///
-/// Policies define when and how L0→L1→L2 abstraction transitions occur.
-/// Inspired by EVOLVE-MEM's self-improvement engine.
-///
-/// # Arguments
-/// * `name` - Policy name
-/// * `triggers` - JSON array of trigger definitions
-/// * `source_level` - Source abstraction level: raw, summary, principle
-/// * `target_level` - Target abstraction level: raw, summary, principle
-/// * `max_sources` - Maximum items to summarize at once
-/// * `create_edges` - Whether to auto-create SynthesizedFrom edges
-/// * `trajectory_id` - Optional trajectory this policy applies to
-#[allow(clippy::too_many_arguments)]
+/// Create a conflict record.
 #[pg_extern]
-fn caliber_summarization_policy_create(
-    name: &str,
-    triggers: pgrx::JsonB,
-    source_level: &str,
-    target_level: &str,
-    max_sources: i32,
-    create_edges: bool,
-    trajectory_id: Option<pgrx::Uuid>,
+fn caliber_conflict_create(
+    conflict_type: &str,
+    item_a_type: &str,
+    item_a_id: pgrx::Uuid,
+    item_b_type: &str,
+    item_b_id: pgrx::Uuid,
     tenant_id: pgrx::Uuid,
-) -> Option<pgrx::Uuid> {
-    // Record operation for metrics
-    storage_write().record_op("summarization_policy_create");
-
-    let policy_id = SummarizationPolicyId::now_v7();
-
-    // Validate abstraction levels
-    let source_level_enum = match source_level {
-        "raw" => AbstractionLevel::Raw,
-        "summary" => AbstractionLevel::Summary,
-        "principle" => AbstractionLevel::Principle,
-        _ => {
-            pgrx::warning!(
-                "CALIBER: Unknown source_level '{}'. Valid values: raw, summary, principle",
-                source_level
-            );
-            return None;
-        }
-    };
+) -> pgrx::Uuid {
+    let a_id = Uuid::from_bytes(*item_a_id.as_bytes());
+    let b_id = Uuid::from_bytes(*item_b_id.as_bytes());
 
-    let target_level_enum = match target_level {
-        "raw" => AbstractionLevel::Raw,
-        "summary" => AbstractionLevel::Summary,
-        "principle" => AbstractionLevel::Principle,
+    let c_type = match conflict_type {
+        "concurrent_write" => ConflictType::ConcurrentWrite,
+        "contradicting_fact" => ConflictType::ContradictingFact,
+        "incompatible_decision" => ConflictType::IncompatibleDecision,
+        "resource_contention" => ConflictType::ResourceContention,
         _ => {
-            pgrx::warning!(
-                "CALIBER: Unknown target_level '{}'. Valid values: raw, summary, principle",
-                target_level
-            );
-            return None;
+            if conflict_type != "goal_conflict" {
+                pgrx::warning!(
+                    "CALIBER: Unknown conflict type '{}', defaulting to GoalConflict",
+                    conflict_type
+                );
+            }
+            ConflictType::GoalConflict
         }
     };
 
-    // Validate level transition (must go upward)
-    let valid_transition = matches!(
-        (source_level_enum, target_level_enum),
-        (AbstractionLevel::Raw, AbstractionLevel::Summary)
-            | (AbstractionLevel::Raw, AbstractionLevel::Principle)
-            | (AbstractionLevel::Summary, AbstractionLevel::Principle)
-    );
+    let conflict = Conflict::new(c_type, item_a_type, a_id, item_b_type, b_id);
+    let conflict_id = conflict.conflict_id;
 
-    if !valid_transition {
-        pgrx::warning!(
-            "CALIBER: Invalid abstraction level transition. Must go from lower to higher level."
-        );
-        return None;
-    }
+    // Insert via direct heap operations (NO SQL)
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-    // Parse triggers from JSON
-    let triggers_vec: Vec<SummarizationTrigger> = match serde_json::from_value(triggers.0) {
-        Ok(t) => t,
+    match conflict_heap::conflict_create_heap(conflict_heap::ConflictCreateParams {
+        conflict_id,
+        conflict_type: c_type,
+        item_a_type,
+        item_a_id: a_id,
+        item_b_type,
+        item_b_id: b_id,
+        agent_a_id: None,
+        agent_b_id: None,
+        trajectory_id: None,
+        tenant_id: tenant_uuid,
+    }) {
+        Ok(_) => audit_record(
+            EntityType::Conflict,
+            conflict_id,
+            "create",
+            None,
+            tenant_uuid,
+        ),
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to parse triggers JSON: {}", e);
-            return None;
+            pgrx::warning!("CALIBER: Failed to insert conflict: {}", e);
         }
-    };
-
-    if triggers_vec.is_empty() {
-        pgrx::warning!("CALIBER: At least one trigger is required");
-        return None;
     }
 
-    if max_sources <= 0 {
-        pgrx::warning!("CALIBER: max_sources must be positive");
-        return None;
-    }
+    pgrx_uuid_from_id(conflict_id)
+}
 
-    // Insert via SPI (no heap operations for policies yet)
-    let traj_id = trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-    let triggers_json = serde_json::to_value(&triggers_vec).unwrap_or(serde_json::Value::Null);
+// Get a conflict by ID.
+caliber_pg_get!(conflict, conflict_heap, ConflictId, |row| {
+    let c = row.conflict;
+    serde_json::json!({
+        "conflict_id": c.conflict_id.to_string(),
+        "conflict_type": match c.conflict_type {
+            ConflictType::ConcurrentWrite => "concurrent_write",
+            ConflictType::ContradictingFact => "contradicting_fact",
+            ConflictType::IncompatibleDecision => "incompatible_decision",
+            ConflictType::ResourceContention => "resource_contention",
+            ConflictType::GoalConflict => "goal_conflict",
+        },
+        "item_a_type": c.item_a_type,
+        "item_a_id": c.item_a_id.to_string(),
+        "item_b_type": c.item_b_type,
+        "item_b_id": c.item_b_id.to_string(),
+        "agent_a_id": c.agent_a_id.map(|id| id.to_string()),
+        "agent_b_id": c.agent_b_id.map(|id| id.to_string()),
+        "trajectory_id": c.trajectory_id.map(|id| id.to_string()),
+        "status": match c.status {
+            ConflictStatus::Detected => "detected",
+            ConflictStatus::Resolving => "resolving",
+            ConflictStatus::Resolved => "resolved",
+            ConflictStatus::Escalated => "escalated",
+        },
+        "resolution": c.resolution.as_ref().map(safe_to_json),
+        "detected_at": c.detected_at.to_rfc3339(),
+        "resolved_at": c.resolved_at.map(|t| t.to_rfc3339()),
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+});
 
-    let now: TimestampWithTimeZone = match tuple_extract::chrono_to_timestamp(Utc::now()) {
-        Ok(ts) => ts,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
-            return None;
+/// Look up the confidence of a conflicting item.
+///
+/// Artifacts use `provenance.confidence`. Notes carry no provenance, so their
+/// confidence is read from a numeric `confidence` key in metadata.
+fn conflict_item_confidence(
+    item_type: &str,
+    item_id: Uuid,
+    tenant_id: TenantId,
+) -> CaliberResult<Option<f32>> {
+    match item_type {
+        "artifact" => {
+            let artifact_id = ArtifactId::new(item_id);
+            let row = artifact_heap::artifact_get_heap(artifact_id, tenant_id)?.ok_or(
+                CaliberError::Storage(StorageError::NotFound {
+                    entity_type: EntityType::Artifact,
+                    id: item_id,
+                }),
+            )?;
+            Ok(row.artifact.provenance.confidence)
         }
-    };
+        "note" => {
+            let note_id = NoteId::new(item_id);
+            let row = note_heap::note_get_heap(note_id, tenant_id)?.ok_or(
+                CaliberError::Storage(StorageError::NotFound {
+                    entity_type: EntityType::Note,
+                    id: item_id,
+                }),
+            )?;
+            Ok(row
+                .note
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("confidence"))
+                .and_then(|c| c.as_f64())
+                .map(|c| c as f32))
+        }
+        other => Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "item_type".to_string(),
+            reason: format!(
+                "highest_confidence cannot compare '{}' items. Supported: artifact, note",
+                other
+            ),
+        })),
+    }
+}
 
-    let result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        use pgrx::datum::DatumWithOid;
-        let pg_id = pgrx_uuid_from_id(policy_id);
-        let pg_traj_id = traj_id.map(pgrx_uuid_from_id);
-        let pg_tenant_id = tenant_id;
+/// Pick the winner ("a" or "b") of a conflict by comparing item confidences.
+/// Fails if either confidence is absent or both are equal.
+fn highest_confidence_winner(
+    conflict_id: ConflictId,
+    tenant_id: TenantId,
+) -> CaliberResult<&'static str> {
+    let conflict = conflict_heap::conflict_get_heap(conflict_id, tenant_id)?
+        .ok_or(CaliberError::Storage(StorageError::NotFound {
+            entity_type: EntityType::Conflict,
+            id: conflict_id.as_uuid(),
+        }))?
+        .conflict;
+
+    let missing = |side: &str| {
+        CaliberError::Validation(ValidationError::RequiredFieldMissing {
+            field: format!("item_{}.confidence", side),
+        })
+    };
+    let a = conflict_item_confidence(&conflict.item_a_type, conflict.item_a_id, tenant_id)?
+        .ok_or_else(|| missing("a"))?;
+    let b = conflict_item_confidence(&conflict.item_b_type, conflict.item_b_id, tenant_id)?
+        .ok_or_else(|| missing("b"))?;
+
+    if a > b {
+        Ok("a")
+    } else if b > a {
+        Ok("b")
+    } else {
+        Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "confidence".to_string(),
+            reason: format!("both items have confidence {}; use another strategy", a),
+        }))
+    }
+}
 
-        // Build params with proper OIDs
-        let params: Vec<DatumWithOid<'_>> = vec![
-            unsafe { DatumWithOid::new(pg_id, pgrx::pg_sys::UUIDOID) },
-            unsafe { DatumWithOid::new(name, pgrx::pg_sys::TEXTOID) },
-            unsafe {
-                DatumWithOid::new(pgrx::JsonB(triggers_json.clone()), pgrx::pg_sys::JSONBOID)
-            },
-            unsafe { DatumWithOid::new(source_level, pgrx::pg_sys::TEXTOID) },
-            unsafe { DatumWithOid::new(target_level, pgrx::pg_sys::TEXTOID) },
-            unsafe { DatumWithOid::new(max_sources, pgrx::pg_sys::INT4OID) },
-            unsafe { DatumWithOid::new(create_edges, pgrx::pg_sys::BOOLOID) },
-            match pg_traj_id {
-                Some(id) => unsafe { DatumWithOid::new(id, pgrx::pg_sys::UUIDOID) },
-                None => DatumWithOid::null_oid(pgrx::pg_sys::UUIDOID),
-            },
-            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
-            unsafe { DatumWithOid::new(pg_tenant_id, pgrx::pg_sys::UUIDOID) },
-        ];
+/// Merge the two fact artifacts of a contradicting_fact conflict.
+///
+/// Creates a fact artifact holding both contents followed by a provenance
+/// line naming the sources, the conflict and the reason, then supersedes
+/// both sources with it. Returns Ok(None) when the conflict is not between
+/// two fact artifacts, leaving the resolution as a plain record.
+fn merge_conflict_facts_checked(
+    conflict_id: ConflictId,
+    reason: &str,
+    tenant_id: TenantId,
+) -> CaliberResult<Option<ArtifactId>> {
+    let conflict = conflict_heap::conflict_get_heap(conflict_id, tenant_id)?
+        .ok_or(CaliberError::Storage(StorageError::NotFound {
+            entity_type: EntityType::Conflict,
+            id: conflict_id.as_uuid(),
+        }))?
+        .conflict;
+
+    // Merging a settled conflict would supersede its items a second time
+    if conflict.status == ConflictStatus::Resolved {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "conflict_id".to_string(),
+            reason: format!("conflict {} is already resolved", conflict_id),
+        }));
+    }
 
-        client.update(
-            "INSERT INTO caliber_summarization_policy
-             (policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
-            None,
-            &params,
-        )?;
+    if conflict.conflict_type != ConflictType::ContradictingFact
+        || conflict.item_a_type != "artifact"
+        || conflict.item_b_type != "artifact"
+    {
+        return Ok(None);
+    }
 
-        Ok(())
-    });
+    let fetch = |id: Uuid| -> CaliberResult<Option<caliber_core::Artifact>> {
+        Ok(
+            artifact_heap::artifact_get_heap(ArtifactId::new(id), tenant_id)?
+                .map(|row| row.artifact)
+                .filter(|a| a.artifact_type == ArtifactType::Fact),
+        )
+    };
+    let (a, b) = match (fetch(conflict.item_a_id)?, fetch(conflict.item_b_id)?) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return Ok(None),
+    };
 
-    match result {
-        Ok(()) => Some(pgrx_uuid_from_id(policy_id)),
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to insert summarization policy: {}", e);
-            None
+    // Check what artifact_supersede_checked would reject before creating
+    // the merged artifact, so a refused merge leaves nothing behind
+    for source in [&a, &b] {
+        if let Some(existing) = source.superseded_by {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "item_id".to_string(),
+                reason: format!(
+                    "artifact {} is already superseded by {}",
+                    source.artifact_id, existing
+                ),
+            }));
         }
     }
-}
+    if a.trajectory_id != b.trajectory_id {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "item_b_id".to_string(),
+            reason: format!(
+                "artifacts belong to different trajectories ({} vs {})",
+                a.trajectory_id, b.trajectory_id
+            ),
+        }));
+    }
 
-/// Get a summarization policy by ID.
-/// NOTE: Policy reads are config/admin operations, not hot path.
-#[pg_extern]
-fn caliber_summarization_policy_get(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
-    let result: Result<Option<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let mut table = client.select(
-            "SELECT policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id
-             FROM caliber_summarization_policy
-             WHERE policy_id = $1 AND tenant_id = $2",
-            None,
-            &[pgrx_uuid_datum(id), pgrx_uuid_datum(tenant_id)],
-        )?;
+    let name = format!("Merged: {} / {}", a.name, b.name);
+    let content = format!(
+        "{}\n\n{}\n\nMerged from artifacts {} and {} to resolve conflict {}: {}",
+        a.content, b.content, a.artifact_id, b.artifact_id, conflict_id, reason
+    );
+    // A merge of contradicting facts is no more certain than its weakest source
+    let confidence = match (a.provenance.confidence, b.provenance.confidence) {
+        (Some(ca), Some(cb)) => Some(ca.min(cb)),
+        (ca, cb) => ca.or(cb),
+    };
+    let provenance = Provenance {
+        source_turn: a.provenance.source_turn.max(b.provenance.source_turn),
+        extraction_method: ExtractionMethod::Inferred,
+        confidence,
+    };
 
-        // Iterate and take first row
-        if let Some(row) = table.next() {
-            let policy_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let name: Option<String> = row.get(2).ok().flatten();
-            let triggers: Option<pgrx::JsonB> = row.get(3).ok().flatten();
-            let source_level: Option<String> = row.get(4).ok().flatten();
-            let target_level: Option<String> = row.get(5).ok().flatten();
-            let max_sources: Option<i32> = row.get(6).ok().flatten();
-            let create_edges: Option<bool> = row.get(7).ok().flatten();
-            let trajectory_id: Option<pgrx::Uuid> = row.get(8).ok().flatten();
-            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-            let tenant_id: Option<pgrx::Uuid> = row.get(10).ok().flatten();
+    let merged_id = artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
+        artifact_id: ArtifactId::now_v7(),
+        trajectory_id: a.trajectory_id,
+        scope_id: a.scope_id,
+        artifact_type: ArtifactType::Fact,
+        name: &name,
+        content: &content,
+        content_hash: compute_content_hash(content.as_bytes()),
+        embedding: None,
+        provenance: &provenance,
+        ttl: a.ttl.clone(),
+        tenant_id,
+    })?;
+    audit_record(EntityType::Artifact, merged_id, "create", None, tenant_id);
 
-            let json = serde_json::json!({
-                "policy_id": policy_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "name": name,
-                "triggers": triggers.map(|j| j.0),
-                "source_level": source_level,
-                "target_level": target_level,
-                "max_sources": max_sources,
-                "create_edges": create_edges,
-                "trajectory_id": trajectory_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
-                "tenant_id": tenant_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-            });
-            return Ok(Some(json));
-        }
-        Ok(None)
-    });
+    artifact_supersede_checked(a.artifact_id, merged_id, tenant_id)?;
+    artifact_supersede_checked(b.artifact_id, merged_id, tenant_id)?;
 
-    match result {
-        Ok(Some(json)) => Some(pgrx::JsonB(json)),
-        Ok(None) => None,
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to get summarization policy: {}", e);
-            None
-        }
-    }
+    Ok(Some(merged_id))
 }
 
-/// List summarization policies for a trajectory.
-/// NOTE: Policy listing is config/admin operation, not hot path.
+/// Resolve a conflict.
+///
+/// With `highest_confidence` the winner is chosen by comparing the items'
+/// confidences and the `winner` argument is ignored; resolution fails if
+/// either confidence is absent. With `merge` on a contradicting_fact
+/// conflict between two fact artifacts, a merged artifact supersedes both
+/// and is recorded as `merged_result_id`.
 #[pg_extern]
-fn caliber_summarization_policies_by_trajectory(
-    trajectory_id: pgrx::Uuid,
+fn caliber_conflict_resolve(
+    conflict_id: pgrx::Uuid,
+    strategy: &str,
+    winner: Option<&str>,
+    reason: &str,
     tenant_id: pgrx::Uuid,
-) -> pgrx::JsonB {
-    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
-        let table = client.select(
-            "SELECT policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id
-             FROM caliber_summarization_policy
-             WHERE trajectory_id = $1 AND tenant_id = $2
-             ORDER BY created_at DESC",
-            None,
-            &[pgrx_uuid_datum(trajectory_id), pgrx_uuid_datum(tenant_id)],
-        )?;
+) -> bool {
+    use caliber_core::ConflictResolutionRecord;
 
-        let mut policies = Vec::new();
-        for row in table {
-            let policy_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-            let name: Option<String> = row.get(2).ok().flatten();
-            let triggers: Option<pgrx::JsonB> = row.get(3).ok().flatten();
-            let source_level: Option<String> = row.get(4).ok().flatten();
-            let target_level: Option<String> = row.get(5).ok().flatten();
-            let max_sources: Option<i32> = row.get(6).ok().flatten();
-            let create_edges: Option<bool> = row.get(7).ok().flatten();
-            let traj_id: Option<pgrx::Uuid> = row.get(8).ok().flatten();
-            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-            let tenant_id: Option<pgrx::Uuid> = row.get(10).ok().flatten();
+    let id = id_from_pgrx::<ConflictId>(conflict_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-            let json = serde_json::json!({
-                "policy_id": policy_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "name": name,
-                "triggers": triggers.map(|j| j.0),
-                "source_level": source_level,
-                "target_level": target_level,
-                "max_sources": max_sources,
-                "create_edges": create_edges,
-                "trajectory_id": traj_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
-                "tenant_id": tenant_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
-            });
-            policies.push(json);
+    // Parse strategy
+    let resolution_strategy = match strategy {
+        "last_write_wins" => ResolutionStrategy::LastWriteWins,
+        "first_write_wins" => ResolutionStrategy::FirstWriteWins,
+        "highest_confidence" => ResolutionStrategy::HighestConfidence,
+        "merge" => ResolutionStrategy::Merge,
+        "escalate" => ResolutionStrategy::Escalate,
+        "reject_both" => ResolutionStrategy::RejectBoth,
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Unknown resolution strategy '{}', defaulting to Escalate",
+                strategy
+            );
+            ResolutionStrategy::Escalate
         }
-        Ok(policies)
-    });
+    };
 
-    match result {
-        Ok(policies) => pgrx::JsonB(serde_json::json!(policies)),
-        Err(e) => {
-            pgrx::warning!("CALIBER: Failed to list summarization policies: {}", e);
-            pgrx::JsonB(serde_json::json!([]))
+    let winner = if resolution_strategy == ResolutionStrategy::HighestConfidence {
+        match highest_confidence_winner(id, tenant_uuid) {
+            Ok(w) => Some(w.to_string()),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Cannot resolve by highest confidence: {}", e);
+                return false;
+            }
         }
-    }
-}
+    } else {
+        winner.map(|s| s.to_string())
+    };
 
-/// Delete a summarization policy.
-/// NOTE: Policy deletion is config/admin operation, not hot path.
-#[pg_extern]
-fn caliber_summarization_policy_delete(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
-    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
-        let table = client.update(
-            "DELETE FROM caliber_summarization_policy WHERE policy_id = $1 AND tenant_id = $2",
-            None,
-            &[pgrx_uuid_datum(id), pgrx_uuid_datum(tenant_id)],
-        )?;
-        Ok(table.len())
-    });
+    let merged_result_id = if resolution_strategy == ResolutionStrategy::Merge {
+        match merge_conflict_facts_checked(id, reason, tenant_uuid) {
+            Ok(merged) => merged.map(|merged_id| merged_id.as_uuid()),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Cannot merge conflicting facts: {}", e);
+                return false;
+            }
+        }
+    } else {
+        None
+    };
 
-    match result {
-        Ok(count) => count > 0,
+    let resolution = ConflictResolutionRecord {
+        strategy: resolution_strategy,
+        winner,
+        merged_result_id,
+        reason: reason.to_string(),
+        resolved_by: None,
+    };
+
+    // Resolve via direct heap operations (NO SQL)
+    match conflict_heap::conflict_resolve_heap(id, &resolution, tenant_uuid) {
+        Ok(updated) => {
+            if updated {
+                audit_record(EntityType::Conflict, id, "resolve", None, tenant_uuid);
+            }
+            updated
+        }
         Err(e) => {
-            pgrx::warning!("CALIBER: Failed to delete summarization policy: {}", e);
+            pgrx::warning!("CALIBER: Failed to resolve conflict: {}", e);
             false
         }
     }
 }
 
-// ============================================================================
-// STORAGE TRAIT IMPLEMENTATION (Task 12.3)
-// ============================================================================
-// Provides unified storage interface. Trajectory methods use SPI,
-// other entity types delegate to direct heap operations for performance.
-
-/// PostgreSQL storage implementation.
-/// Provides StorageTrait interface - trajectory via SPI, others via heap operations.
-pub struct PgStorage;
+/// Look up the creation time of a conflicting artifact or note.
+fn conflict_item_created_at(
+    item_type: &str,
+    item_id: Uuid,
+    tenant_id: TenantId,
+) -> CaliberResult<chrono::DateTime<Utc>> {
+    match item_type {
+        "artifact" => artifact_heap::artifact_get_heap(ArtifactId::new(item_id), tenant_id)?
+            .map(|row| row.artifact.created_at)
+            .ok_or(CaliberError::Storage(StorageError::NotFound {
+                entity_type: EntityType::Artifact,
+                id: item_id,
+            })),
+        "note" => note_heap::note_get_heap(NoteId::new(item_id), tenant_id)?
+            .map(|row| row.note.created_at)
+            .ok_or(CaliberError::Storage(StorageError::NotFound {
+                entity_type: EntityType::Note,
+                id: item_id,
+            })),
+        other => Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "item_type".to_string(),
+            reason: format!(
+                "last_write_wins cannot compare '{}' items. Supported: artifact, note",
+                other
+            ),
+        })),
+    }
+}
 
-impl StorageTrait for PgStorage {
-    fn trajectory_insert(&self, t: &Trajectory) -> CaliberResult<()> {
-        // Insert into SQL table via SPI
-        Spi::connect_mut(|client| {
-            // Check if trajectory already exists
-            let exists_result = client.select(
-                "SELECT 1 FROM caliber_trajectory WHERE trajectory_id = $1",
-                None,
-                &[id_datum(t.trajectory_id)],
-            );
+/// Pick the winner ("a" or "b") of a conflict by comparing item creation
+/// times. Fails if both items were created at the same instant.
+fn last_write_winner(conflict: &Conflict, tenant_id: TenantId) -> CaliberResult<&'static str> {
+    let a = conflict_item_created_at(&conflict.item_a_type, conflict.item_a_id, tenant_id)?;
+    let b = conflict_item_created_at(&conflict.item_b_type, conflict.item_b_id, tenant_id)?;
 
-            if let Ok(table) = exists_result {
-                if !table.is_empty() {
-                    return Err(CaliberError::Storage(StorageError::InsertFailed {
-                        entity_type: EntityType::Trajectory,
-                        reason: "already exists".to_string(),
-                    }));
-                }
-            }
-
-            let status_str = match t.status {
-                TrajectoryStatus::Active => "active",
-                TrajectoryStatus::Completed => "completed",
-                TrajectoryStatus::Failed => "failed",
-                TrajectoryStatus::Suspended => "suspended",
-            };
+    if a > b {
+        Ok("a")
+    } else if b > a {
+        Ok("b")
+    } else {
+        Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "created_at".to_string(),
+            reason: "both items were written at the same time; use another strategy".to_string(),
+        }))
+    }
+}
 
-            let outcome_json = t
-                .outcome
-                .as_ref()
-                .map(|o| serde_json::to_value(o).unwrap_or(serde_json::Value::Null));
-            let metadata_json = t
-                .metadata
-                .as_ref()
-                .map(|m| serde_json::to_value(m).unwrap_or(serde_json::Value::Null));
+/// Read a region's configured conflict_resolution strategy.
+fn region_conflict_resolution(
+    region_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<Option<String>> {
+    Spi::connect(|client| {
+        client
+            .select(
+                "SELECT conflict_resolution FROM caliber_region
+                 WHERE region_id = $1 AND tenant_id = $2",
+                None,
+                &[pgrx_uuid_datum(region_id), pgrx_uuid_datum(tenant_id)],
+            )?
+            .first()
+            .get_one::<String>()
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
 
-            let created_at_datum = timestamp_datum(t.created_at)?;
-            let updated_at_datum = timestamp_datum(t.updated_at)?;
-            let completed_at_datum = opt_timestamp_datum(t.completed_at)?;
+/// Apply a region's conflict_resolution strategy to a conflict.
+/// Returns Ok(false) if the conflict does not exist or is already settled.
+fn conflict_auto_resolve_checked(
+    conflict_id: ConflictId,
+    region_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<bool> {
+    use caliber_core::ConflictResolutionRecord;
 
-            client
-                .update(
-                    "INSERT INTO caliber_trajectory (trajectory_id, name, description, status,
-                 parent_trajectory_id, root_trajectory_id, agent_id, created_at, updated_at,
-                 completed_at, outcome, metadata)
-                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
-                    None,
-                    &[
-                        id_datum(t.trajectory_id),
-                        text_datum(&t.name),
-                        opt_text_datum(t.description.as_deref()),
-                        text_datum(status_str),
-                        opt_id_datum(t.parent_trajectory_id),
-                        opt_id_datum(t.root_trajectory_id),
-                        opt_id_datum(t.agent_id),
-                        created_at_datum,
-                        updated_at_datum,
-                        completed_at_datum,
-                        opt_jsonb_datum(outcome_json.as_ref()),
-                        opt_jsonb_datum(metadata_json.as_ref()),
-                    ],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
 
-            Ok(())
+    let strategy = region_conflict_resolution(region_id, tenant_id)?.ok_or_else(|| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "region_id".to_string(),
+            reason: format!("region {} not found", region_id),
         })
+    })?;
+
+    let conflict = match conflict_heap::conflict_get_heap(conflict_id, tenant_uuid)? {
+        Some(row) => row.conflict,
+        None => return Ok(false),
+    };
+    if matches!(
+        conflict.status,
+        ConflictStatus::Resolved | ConflictStatus::Escalated
+    ) {
+        return Ok(false);
     }
 
-    fn trajectory_get(&self, id: Uuid) -> CaliberResult<Option<Trajectory>> {
-        Spi::connect(|client| {
-            let mut result = client
-                .select(
-                    "SELECT trajectory_id, name, description, status, parent_trajectory_id,
-                        root_trajectory_id, agent_id, created_at, updated_at, completed_at,
-                        outcome, metadata
-                 FROM caliber_trajectory WHERE trajectory_id = $1",
+    let reason = format!("auto-resolved by region {} policy {}", region_id, strategy);
+    let (resolution_strategy, winner) = match strategy.as_str() {
+        "last_write_wins" => (
+            ResolutionStrategy::LastWriteWins,
+            last_write_winner(&conflict, tenant_uuid)?,
+        ),
+        "highest_confidence" => (
+            ResolutionStrategy::HighestConfidence,
+            highest_confidence_winner(conflict_id, tenant_uuid)?,
+        ),
+        "escalate" => {
+            let resolution = ConflictResolutionRecord::automatic(
+                ResolutionStrategy::Escalate,
+                &format!("escalated by region {} policy", region_id),
+            );
+            let escalated =
+                conflict_heap::conflict_escalate_heap(conflict_id, &resolution, tenant_uuid)?;
+            if escalated {
+                audit_record(
+                    EntityType::Conflict,
+                    conflict_id,
+                    "escalate",
                     None,
-                    &[uuid_datum(id)],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
-
-            // Iterate over result rows (SpiTupleTable is iterable)
-            if let Some(row) = result.next() {
-                let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let name: Option<String> = row.get(2).ok().flatten();
-                let description: Option<String> = row.get(3).ok().flatten();
-                let status_str: Option<String> = row.get(4).ok().flatten();
-                let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
-                let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
-                let agent_id_val: Option<pgrx::Uuid> = row.get(7).ok().flatten();
-                let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
-                let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-                let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-                let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
-                let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+                    tenant_uuid,
+                );
+            }
+            return Ok(escalated);
+        }
+        other => {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "conflict_resolution".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: last_write_wins, highest_confidence, escalate",
+                    other
+                ),
+            }));
+        }
+    };
 
-                let status = match status_str.as_deref() {
-                    Some("active") => TrajectoryStatus::Active,
-                    Some("completed") => TrajectoryStatus::Completed,
-                    Some("failed") => TrajectoryStatus::Failed,
-                    Some("suspended") => TrajectoryStatus::Suspended,
-                    _ => {
-                        pgrx::warning!(
-                            "CALIBER: Unknown trajectory status '{:?}', defaulting to Active",
-                            status_str
-                        );
-                        TrajectoryStatus::Active
-                    }
-                };
+    let resolution = ConflictResolutionRecord {
+        strategy: resolution_strategy,
+        winner: Some(winner.to_string()),
+        merged_result_id: None,
+        reason,
+        resolved_by: None,
+    };
+    let resolved = conflict_heap::conflict_resolve_heap(conflict_id, &resolution, tenant_uuid)?;
+    if resolved {
+        audit_record(
+            EntityType::Conflict,
+            conflict_id,
+            "resolve",
+            None,
+            tenant_uuid,
+        );
+    }
+    Ok(resolved)
+}
 
-                let traj_id = trajectory_id
-                    .map(id_from_pgrx::<TrajectoryId>)
-                    .unwrap_or(TrajectoryId::new(id));
-                let parent_id = parent_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-                let root_id = root_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-                let agent_id = agent_id_val.map(id_from_pgrx::<AgentId>);
+/// Resolve a conflict using the conflict_resolution strategy of a region.
+///
+/// last_write_wins picks the item created most recently and
+/// highest_confidence compares the items' confidences (see
+/// `caliber_conflict_resolve`); both record the winner and resolve the
+/// conflict. escalate marks the conflict escalated. Returns false if the
+/// region or conflict is missing, the conflict is already resolved or
+/// escalated, or the strategy cannot choose a winner.
+#[pg_extern]
+fn caliber_conflict_auto_resolve(
+    conflict_id: pgrx::Uuid,
+    region_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    let id = id_from_pgrx::<ConflictId>(conflict_id);
 
-                return Ok(Some(Trajectory {
-                    trajectory_id: traj_id,
-                    name: name.unwrap_or_default(),
-                    description,
-                    status,
-                    parent_trajectory_id: parent_id,
-                    root_trajectory_id: root_id,
-                    agent_id,
-                    created_at: created_at
-                        .map(tuple_extract::timestamp_to_chrono)
-                        .unwrap_or_else(Utc::now),
-                    updated_at: updated_at
-                        .map(tuple_extract::timestamp_to_chrono)
-                        .unwrap_or_else(Utc::now),
-                    completed_at: completed_at.map(tuple_extract::timestamp_to_chrono),
-                    outcome: outcome.and_then(|j| serde_json::from_value(j.0).ok()),
-                    metadata: metadata.and_then(|j| serde_json::from_value(j.0).ok()),
-                }));
-            }
-            Ok(None)
-        })
+    match conflict_auto_resolve_checked(id, region_id, tenant_id) {
+        Ok(applied) => applied,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to auto-resolve conflict: {}", e);
+            false
+        }
     }
+}
 
-    fn trajectory_update(&self, id: Uuid, update: TrajectoryUpdate) -> CaliberResult<()> {
-        Spi::connect_mut(|client| {
-            // First check if trajectory exists
-            let exists_result = client
-                .select(
-                    "SELECT 1 FROM caliber_trajectory WHERE trajectory_id = $1",
-                    None,
-                    &[uuid_datum(id)],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
+/// List unresolved conflicts.
+#[pg_extern]
+fn caliber_conflict_list_unresolved(tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+    // List via direct heap operations (NO SQL)
+    match conflict_heap::conflict_list_pending_heap(tenant_uuid) {
+        Ok(conflicts) => {
+            let conflicts_json: Vec<serde_json::Value> = conflicts
+                .into_iter()
+                .map(|row| {
+                    let conflict = row.conflict;
+                    serde_json::json!({
+                        "conflict_id": conflict.conflict_id.to_string(),
+                        "conflict_type": match conflict.conflict_type {
+                            ConflictType::ConcurrentWrite => "concurrent_write",
+                            ConflictType::ContradictingFact => "contradicting_fact",
+                            ConflictType::IncompatibleDecision => "incompatible_decision",
+                            ConflictType::ResourceContention => "resource_contention",
+                            ConflictType::GoalConflict => "goal_conflict",
+                        },
+                        "item_a_type": conflict.item_a_type,
+                        "item_a_id": conflict.item_a_id.to_string(),
+                        "item_b_type": conflict.item_b_type,
+                        "item_b_id": conflict.item_b_id.to_string(),
+                        "status": match conflict.status {
+                            ConflictStatus::Detected => "detected",
+                            ConflictStatus::Resolving => "resolving",
+                            ConflictStatus::Resolved => "resolved",
+                            ConflictStatus::Escalated => "escalated",
+                        },
+                        "detected_at": conflict.detected_at.to_rfc3339(),
+                        "tenant_id": row.tenant_id.map(|id| id.to_string()),
                     })
-                })?;
+                })
+                .collect();
+            pgrx::JsonB(serde_json::json!(conflicts_json))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list unresolved conflicts: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
 
-            if exists_result.is_empty() {
-                return Err(CaliberError::Storage(StorageError::NotFound {
-                    entity_type: EntityType::Trajectory,
-                    id,
-                }));
-            }
+/// Parse an optional conflict status filter, rejecting unknown values.
+fn parse_conflict_status_filter(status: Option<&str>) -> Result<Option<ConflictStatus>, ()> {
+    match status {
+        None => Ok(None),
+        Some("detected") => Ok(Some(ConflictStatus::Detected)),
+        Some("resolving") => Ok(Some(ConflictStatus::Resolving)),
+        Some("resolved") => Ok(Some(ConflictStatus::Resolved)),
+        Some("escalated") => Ok(Some(ConflictStatus::Escalated)),
+        Some(other) => {
+            // Validate status - reject unknown values (REQ-12)
+            let validation_err = ValidationError::InvalidValue {
+                field: "status".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: detected, resolving, resolved, escalated",
+                    other
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            Err(())
+        }
+    }
+}
 
-            let now = Utc::now();
+/// Build conflict JSON for rows matching an optional status, oldest first.
+fn conflict_rows_json(
+    mut rows: Vec<conflict_heap::ConflictRow>,
+    status: Option<ConflictStatus>,
+) -> serde_json::Value {
+    rows.retain(|row| status.is_none_or(|s| row.conflict.status == s));
+    rows.sort_by(|a, b| a.conflict.detected_at.cmp(&b.conflict.detected_at));
 
-            // Build dynamic update query based on what fields are provided
-            if let Some(status) = update.status {
-                let status_str = match status {
-                    TrajectoryStatus::Active => "active",
-                    TrajectoryStatus::Completed => "completed",
-                    TrajectoryStatus::Failed => "failed",
-                    TrajectoryStatus::Suspended => "suspended",
-                };
-                let updated_at_datum = timestamp_datum(now)?;
-                client.update(
-                    "UPDATE caliber_trajectory SET status = $1, updated_at = $2 WHERE trajectory_id = $3",
-                    None,
-                    &[
-                        text_datum(status_str),
-                        updated_at_datum,
-                        uuid_datum(id),
-                    ],
-                ).map_err(|e| CaliberError::Storage(StorageError::SpiError { reason: e.to_string() }))?;
+    let conflicts: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|row| {
+            let c = row.conflict;
+            serde_json::json!({
+                "conflict_id": c.conflict_id.to_string(),
+                "conflict_type": match c.conflict_type {
+                    ConflictType::ConcurrentWrite => "concurrent_write",
+                    ConflictType::ContradictingFact => "contradicting_fact",
+                    ConflictType::IncompatibleDecision => "incompatible_decision",
+                    ConflictType::ResourceContention => "resource_contention",
+                    ConflictType::GoalConflict => "goal_conflict",
+                },
+                "item_a_type": c.item_a_type,
+                "item_a_id": c.item_a_id.to_string(),
+                "item_b_type": c.item_b_type,
+                "item_b_id": c.item_b_id.to_string(),
+                "agent_a_id": c.agent_a_id.map(|id| id.to_string()),
+                "agent_b_id": c.agent_b_id.map(|id| id.to_string()),
+                "trajectory_id": c.trajectory_id.map(|id| id.to_string()),
+                "status": match c.status {
+                    ConflictStatus::Detected => "detected",
+                    ConflictStatus::Resolving => "resolving",
+                    ConflictStatus::Resolved => "resolved",
+                    ConflictStatus::Escalated => "escalated",
+                },
+                "resolution": c.resolution.as_ref().map(safe_to_json),
+                "detected_at": c.detected_at.to_rfc3339(),
+                "resolved_at": c.resolved_at.map(|t| t.to_rfc3339()),
+                "tenant_id": row.tenant_id.map(|id| id.to_string()),
+            })
+        })
+        .collect();
+
+    serde_json::json!(conflicts)
+}
+
+/// List conflicts in a trajectory, optionally filtered by status.
+#[pg_extern]
+fn caliber_conflict_list_by_trajectory(
+    trajectory_id: pgrx::Uuid,
+    status: Option<&str>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let status_filter = match parse_conflict_status_filter(status) {
+        Ok(filter) => filter,
+        Err(()) => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    match conflict_heap::conflict_list_by_trajectory_heap(traj_id, tenant_uuid) {
+        Ok(rows) => pgrx::JsonB(conflict_rows_json(rows, status_filter)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list conflicts by trajectory: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// List conflicts where an agent is on either side, optionally filtered by status.
+#[pg_extern]
+fn caliber_conflict_list_by_agent(
+    agent_id: pgrx::Uuid,
+    status: Option<&str>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let aid = id_from_pgrx::<AgentId>(agent_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let status_filter = match parse_conflict_status_filter(status) {
+        Ok(filter) => filter,
+        Err(()) => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    match conflict_heap::conflict_list_by_agent_heap(aid, tenant_uuid) {
+        Ok(rows) => pgrx::JsonB(conflict_rows_json(rows, status_filter)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list conflicts by agent: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Check whether two artifacts carry different `polarity` values in their metadata.
+fn artifacts_have_opposite_polarity(a: &Artifact, b: &Artifact) -> bool {
+    let polarity = |artifact: &Artifact| {
+        artifact
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get("polarity"))
+            .filter(|p| !p.is_null())
+            .cloned()
+    };
+    match (polarity(a), polarity(b)) {
+        (Some(pa), Some(pb)) => pa != pb,
+        _ => false,
+    }
+}
+
+/// Detect contradicting facts and constraints within a trajectory.
+///
+/// Compares every pair of live `fact`/`constraint` artifacts that have
+/// embeddings. A pair whose cosine similarity exceeds `threshold` is a
+/// contradiction when their metadata `polarity` differs or a `contradicts`
+/// edge already links them. A `contradicting_fact` conflict is created for
+/// each pair that does not already have one. `threshold` defaults to the
+/// configured `contradiction_threshold`. Returns the conflicts created.
+#[pg_extern]
+fn caliber_detect_contradictions(
+    trajectory_id: pgrx::Uuid,
+    threshold: Option<f32>,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let threshold = match threshold {
+        Some(threshold) => threshold,
+        None => match ConfigSettings::load(tenant_uuid) {
+            Ok(settings) => settings.contradiction_threshold,
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to read config: {}", e);
+                return pgrx::JsonB(serde_json::json!([]));
+            }
+        },
+    };
+    if !(0.0..=1.0).contains(&threshold) {
+        let validation_err = ValidationError::InvalidValue {
+            field: "threshold".to_string(),
+            reason: format!("must be between 0.0 and 1.0, got {}", threshold),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return pgrx::JsonB(serde_json::json!([]));
+    }
+
+    match detect_contradictions(traj_id, threshold, tenant_uuid) {
+        Ok(created) => pgrx::JsonB(serde_json::json!(created)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to detect contradictions: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Find contradicting artifact pairs and record conflicts for new ones.
+fn detect_contradictions(
+    traj_id: TrajectoryId,
+    threshold: f32,
+    tenant_uuid: TenantId,
+) -> CaliberResult<Vec<serde_json::Value>> {
+    let artifacts: Vec<Artifact> =
+        artifact_heap::artifact_query_by_trajectory_heap(traj_id, tenant_uuid)?
+            .into_iter()
+            .map(|row| row.artifact)
+            .filter(|a| {
+                matches!(
+                    a.artifact_type,
+                    ArtifactType::Fact | ArtifactType::Constraint
+                ) && a.embedding.is_some()
+                    && a.superseded_by.is_none()
+            })
+            .collect();
+
+    // Unordered pairs already linked by a contradicts edge
+    let ordered = |a: Uuid, b: Uuid| if a < b { (a, b) } else { (b, a) };
+    let mut contradicts_edges: HashSet<(Uuid, Uuid)> = HashSet::new();
+    for row in edge_heap::edge_query_by_type_heap(EdgeType::Contradicts, tenant_uuid)? {
+        let ids: Vec<Uuid> = row
+            .edge
+            .participants
+            .iter()
+            .map(|p| p.entity_ref.id)
+            .collect();
+        for (i, a) in ids.iter().enumerate() {
+            for b in &ids[i + 1..] {
+                contradicts_edges.insert(ordered(*a, *b));
             }
+        }
+    }
 
-            if let Some(metadata) = update.metadata {
-                let metadata_json =
-                    serde_json::to_value(&metadata).unwrap_or(serde_json::Value::Null);
-                let updated_at_datum = timestamp_datum(now)?;
-                client.update(
-                    "UPDATE caliber_trajectory SET metadata = $1, updated_at = $2 WHERE trajectory_id = $3",
-                    None,
-                    &[
-                        jsonb_datum(&metadata_json),
-                        updated_at_datum,
-                        uuid_datum(id),
-                    ],
-                ).map_err(|e| CaliberError::Storage(StorageError::SpiError { reason: e.to_string() }))?;
+    // Pairs that already have a contradicting_fact conflict
+    let existing: HashSet<(Uuid, Uuid)> =
+        conflict_heap::conflict_list_by_trajectory_heap(traj_id, tenant_uuid)?
+            .into_iter()
+            .filter(|row| row.conflict.conflict_type == ConflictType::ContradictingFact)
+            .map(|row| ordered(row.conflict.item_a_id, row.conflict.item_b_id))
+            .collect();
+
+    let mut created = Vec::new();
+    for (i, a) in artifacts.iter().enumerate() {
+        for b in &artifacts[i + 1..] {
+            let (emb_a, emb_b) = match (&a.embedding, &b.embedding) {
+                (Some(ea), Some(eb)) => (ea, eb),
+                _ => continue,
+            };
+            // Embeddings from different models are not comparable
+            let similarity = match emb_a.cosine_similarity(emb_b) {
+                Ok(sim) => sim,
+                Err(_) => continue,
+            };
+            if similarity <= threshold {
+                continue;
             }
 
-            Ok(())
-        })
+            let pair = ordered(a.artifact_id.as_uuid(), b.artifact_id.as_uuid());
+            if existing.contains(&pair) {
+                continue;
+            }
+            let reason = if artifacts_have_opposite_polarity(a, b) {
+                "polarity"
+            } else if contradicts_edges.contains(&pair) {
+                "contradicts_edge"
+            } else {
+                continue;
+            };
+
+            let conflict_id = ConflictId::now_v7();
+            conflict_heap::conflict_create_heap(conflict_heap::ConflictCreateParams {
+                conflict_id,
+                conflict_type: ConflictType::ContradictingFact,
+                item_a_type: "artifact",
+                item_a_id: a.artifact_id.as_uuid(),
+                item_b_type: "artifact",
+                item_b_id: b.artifact_id.as_uuid(),
+                agent_a_id: None,
+                agent_b_id: None,
+                trajectory_id: Some(traj_id),
+                tenant_id: tenant_uuid,
+            })?;
+            audit_record(
+                EntityType::Conflict,
+                conflict_id,
+                "create",
+                None,
+                tenant_uuid,
+            );
+
+            created.push(serde_json::json!({
+                "conflict_id": conflict_id.to_string(),
+                "item_a_id": a.artifact_id.to_string(),
+                "item_b_id": b.artifact_id.to_string(),
+                "similarity": similarity,
+                "reason": reason,
+            }));
+        }
     }
 
-    fn trajectory_list_by_status(
-        &self,
-        status: TrajectoryStatus,
-    ) -> CaliberResult<Vec<Trajectory>> {
-        let status_str = match status {
-            TrajectoryStatus::Active => "active",
-            TrajectoryStatus::Completed => "completed",
-            TrajectoryStatus::Failed => "failed",
-            TrajectoryStatus::Suspended => "suspended",
-        };
+    Ok(created)
+}
 
-        Spi::connect(|client| {
-            let result = client
-                .select(
-                    "SELECT trajectory_id, name, description, status, parent_trajectory_id,
-                        root_trajectory_id, agent_id, created_at, updated_at, completed_at,
-                        outcome, metadata
-                 FROM caliber_trajectory WHERE status = $1 ORDER BY created_at DESC",
-                    None,
-                    &[text_datum(status_str)],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
+// ============================================================================
+// VECTOR SEARCH (Task 12.3)
+// ============================================================================
 
-            let mut trajectories = Vec::new();
-            for row in result {
-                let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
-                let name: Option<String> = row.get(2).ok().flatten();
-                let description: Option<String> = row.get(3).ok().flatten();
-                let status_str_val: Option<String> = row.get(4).ok().flatten();
-                let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
-                let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
-                let agent_id_val: Option<pgrx::Uuid> = row.get(7).ok().flatten();
-                let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
-                let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
-                let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
-                let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
-                let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+/// Operator and similarity expression for cosine search, the default metric.
+const COSINE_METRIC_SQL: (&str, &str) = ("<=>", "1 - (embedding <=> $1::vector)");
 
-                let traj_status = match status_str_val.as_deref() {
-                    Some("active") => TrajectoryStatus::Active,
-                    Some("completed") => TrajectoryStatus::Completed,
-                    Some("failed") => TrajectoryStatus::Failed,
-                    Some("suspended") => TrajectoryStatus::Suspended,
-                    _ => {
-                        pgrx::warning!(
-                            "CALIBER: Unknown trajectory status '{:?}', defaulting to Active",
-                            status_str_val
-                        );
-                        TrajectoryStatus::Active
-                    }
-                };
+/// Map a distance metric name to its pgvector operator and a similarity
+/// expression over that operator (higher is more similar).
+///
+/// - `cosine`: `<=>`, similarity = 1 - distance
+/// - `l2`: `<->`, similarity = 1 / (1 + distance)
+/// - `ip`: `<#>` (negative inner product), similarity = raw inner product
+fn vector_metric_sql(metric: &str) -> Option<(&'static str, &'static str)> {
+    match metric {
+        "cosine" => Some(COSINE_METRIC_SQL),
+        "l2" => Some(("<->", "1 / (1 + (embedding <-> $1::vector))")),
+        "ip" => Some(("<#>", "(embedding <#> $1::vector) * -1")),
+        _ => None,
+    }
+}
 
-                if let Some(tid) = trajectory_id {
-                    let parent_id = parent_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-                    let root_id = root_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
-                    let agent_id = agent_id_val.map(id_from_pgrx::<AgentId>);
+/// A single artifact or note returned by vector search.
+struct VectorSearchHit {
+    entity_id: Uuid,
+    entity_type: String,
+    similarity: f64,
+    /// created_at as seconds since the Unix epoch.
+    created_epoch: f64,
+}
 
-                    trajectories.push(Trajectory {
-                        trajectory_id: id_from_pgrx::<TrajectoryId>(tid),
-                        name: name.unwrap_or_default(),
-                        description,
-                        status: traj_status,
-                        parent_trajectory_id: parent_id,
-                        root_trajectory_id: root_id,
-                        agent_id,
-                        created_at: created_at
-                            .map(tuple_extract::timestamp_to_chrono)
-                            .unwrap_or_else(Utc::now),
-                        updated_at: updated_at
-                            .map(tuple_extract::timestamp_to_chrono)
-                            .unwrap_or_else(Utc::now),
-                        completed_at: completed_at.map(tuple_extract::timestamp_to_chrono),
-                        outcome: outcome.and_then(|j| serde_json::from_value(j.0).ok()),
-                        metadata: metadata.and_then(|j| serde_json::from_value(j.0).ok()),
-                    });
-                }
+/// Parse a JSON array of floats into query embedding values.
+fn parse_query_embedding(query_embedding: serde_json::Value) -> Option<Vec<f32>> {
+    match serde_json::from_value(query_embedding) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to parse query embedding: {}", e);
+            None
+        }
+    }
+}
+
+/// Format embedding values as pgvector text: '[1.0,2.0,3.0]'.
+/// The result is always bound as a query parameter, never spliced into SQL.
+fn embedding_to_vector_str(values: &[f32]) -> String {
+    format!(
+        "[{}]",
+        values
+            .iter()
+            .map(|f| f.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+/// Search artifacts and notes using the given pgvector distance operator.
+///
+/// The query vector and limit are bound as parameters; only the operator,
+/// similarity expression from `vector_metric_sql` and the query's dimension
+/// are formatted into the SQL. Rows are compared as `embedding::vector(n)`
+/// restricted to `vector_dims(embedding) = n`, the expression and predicate
+/// indexed by `caliber_create_vector_index`, so rows of another dimension are
+/// skipped rather than failing the search.
+/// When `tenant_id` is set, only that tenant's rows are searched.
+fn vector_search_hits(
+    query: &[f32],
+    limit: i32,
+    operator: &str,
+    similarity_expr: &str,
+    tenant_id: Option<TenantId>,
+) -> CaliberResult<Vec<VectorSearchHit>> {
+    let vector_str = embedding_to_vector_str(query);
+    Spi::connect(|client| {
+        let table = client.select(
+            &format!(
+                "SELECT entity_id, entity_type, ({sim})::float8 as similarity,
+                        extract(epoch from created_at)::float8 as created_epoch
+                 FROM (
+                     SELECT artifact_id as entity_id, 'artifact' as entity_type,
+                            embedding::vector({dims}) as embedding, created_at
+                     FROM caliber_artifact WHERE embedding IS NOT NULL AND deleted_at IS NULL
+                       AND vector_dims(embedding) = {dims}
+                       AND ($3::uuid IS NULL OR tenant_id = $3)
+                     UNION ALL
+                     SELECT note_id as entity_id, 'note' as entity_type,
+                            embedding::vector({dims}) as embedding, created_at
+                     FROM caliber_note WHERE embedding IS NOT NULL AND deleted_at IS NULL
+                       AND vector_dims(embedding) = {dims}
+                       AND ($3::uuid IS NULL OR tenant_id = $3)
+                 ) combined
+                 ORDER BY embedding {op} $1::vector
+                 LIMIT $2",
+                sim = similarity_expr,
+                op = operator,
+                dims = query.len()
+            ),
+            None,
+            &[
+                text_datum(&vector_str),
+                int4_datum(limit),
+                opt_id_datum(tenant_id),
+            ],
+        )?;
+
+        let mut hits = Vec::new();
+        for row in table {
+            let entity_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let entity_type: Option<String> = row.get(2).ok().flatten();
+            let similarity: Option<f64> = row.get(3).ok().flatten();
+            let created_epoch: Option<f64> = row.get(4).ok().flatten();
+
+            if let (Some(eid), Some(etype), Some(sim)) = (entity_id, entity_type, similarity) {
+                hits.push(VectorSearchHit {
+                    entity_id: Uuid::from_bytes(*eid.as_bytes()),
+                    entity_type: etype,
+                    similarity: sim,
+                    created_epoch: created_epoch.unwrap_or(0.0),
+                });
             }
-            Ok(trajectories)
+        }
+        Ok(hits)
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
         })
+    })
+}
+
+/// Run `vector_search_hits`, logging and returning no hits on failure.
+fn vector_search_hits_or_warn(
+    query: &[f32],
+    limit: i32,
+    operator: &str,
+    similarity_expr: &str,
+    tenant_id: Option<TenantId>,
+) -> Vec<VectorSearchHit> {
+    match vector_search_hits(query, limit, operator, similarity_expr, tenant_id) {
+        Ok(hits) => hits,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Vector search failed: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Search for similar vectors using pgvector.
+/// Returns entity IDs and similarity scores.
+/// The metric selects the distance operator: "cosine" (default), "l2" or "ip".
+/// Only embeddings with the query's dimension are compared.
+/// Note: This requires the pgvector extension; create an index with
+/// `caliber_create_vector_index` so large cosine searches avoid sequential scans.
+#[pg_extern]
+fn caliber_vector_search(
+    query_embedding: pgrx::JsonB,
+    limit: i32,
+    metric: default!(&str, "'cosine'"),
+) -> pgrx::JsonB {
+    // Validate metric - reject unknown values (REQ-12)
+    let (operator, similarity_expr) = match vector_metric_sql(metric) {
+        Some(sql) => sql,
+        None => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "metric".to_string(),
+                reason: format!("unknown value '{}'. Valid values: cosine, l2, ip", metric),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    let query = match parse_query_embedding(query_embedding.0) {
+        Some(v) => v,
+        None => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    let results: Vec<serde_json::Value> =
+        vector_search_hits_or_warn(&query, limit, operator, similarity_expr, None)
+            .into_iter()
+            .map(|hit| {
+                serde_json::json!({
+                    "entity_id": hit.entity_id.to_string(),
+                    "entity_type": hit.entity_type,
+                    "similarity": hit.similarity,
+                })
+            })
+            .collect();
+
+    pgrx::JsonB(serde_json::json!(results))
+}
+
+/// Tables with an `embedding` column that `caliber_create_vector_index` may index.
+const VECTOR_INDEX_TABLES: &[&str] = &["caliber_artifact", "caliber_note"];
+
+/// Reject tables outside `VECTOR_INDEX_TABLES` before their names reach SQL.
+fn check_vector_index_table(table: &str) -> CaliberResult<()> {
+    if VECTOR_INDEX_TABLES.contains(&table) {
+        return Ok(());
+    }
+    Err(CaliberError::Validation(ValidationError::InvalidValue {
+        field: "table".to_string(),
+        reason: format!(
+            "unknown value '{}'. Valid values: {}",
+            table,
+            VECTOR_INDEX_TABLES.join(", ")
+        ),
+    }))
+}
+
+/// Distinct dimensions of the embeddings stored in an allowlisted table.
+fn stored_embedding_dimensions_spi(table: &str) -> CaliberResult<Vec<i32>> {
+    check_vector_index_table(table)?;
+    Spi::connect(|client| {
+        let table = client.select(
+            &format!(
+                "SELECT DISTINCT vector_dims(embedding) FROM {} WHERE embedding IS NOT NULL ORDER BY 1",
+                table
+            ),
+            None,
+            &[],
+        )?;
+        let mut dimensions = Vec::new();
+        for row in table {
+            if let Some(dims) = row.get::<i32>(1)? {
+                dimensions.push(dims);
+            }
+        }
+        Ok(dimensions)
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Build the CREATE INDEX statement for a cosine vector index.
+///
+/// The `embedding` column is an untyped `vector`, which pgvector cannot index,
+/// so the index is built on the expression `embedding::vector(<dimensions>)`
+/// and restricted to rows of that dimension. Only allowlisted table names and
+/// validated integers are formatted into the SQL. Options left as None fall
+/// back to pgvector's defaults (m = 16, ef_construction = 64, lists = 100).
+fn vector_index_sql(
+    table: &str,
+    index_type: &str,
+    dimensions: i32,
+    m: Option<i32>,
+    ef_construction: Option<i32>,
+    lists: Option<i32>,
+) -> CaliberResult<String> {
+    let invalid = |field: &str, reason: String| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: field.to_string(),
+            reason,
+        })
+    };
+
+    check_vector_index_table(table)?;
+
+    if dimensions <= 0 {
+        return Err(invalid("dimensions", "must be positive".to_string()));
+    }
+
+    let options = match index_type {
+        "hnsw" => {
+            if lists.is_some() {
+                return Err(invalid(
+                    "lists",
+                    "only applies to ivfflat indexes".to_string(),
+                ));
+            }
+            if let Some(m) = m {
+                if !(2..=100).contains(&m) {
+                    return Err(invalid(
+                        "m",
+                        format!("must be between 2 and 100, got {}", m),
+                    ));
+                }
+            }
+            if let Some(ef) = ef_construction {
+                if !(4..=1000).contains(&ef) {
+                    return Err(invalid(
+                        "ef_construction",
+                        format!("must be between 4 and 1000, got {}", ef),
+                    ));
+                }
+                let effective_m = m.unwrap_or(16);
+                if ef < 2 * effective_m {
+                    return Err(invalid(
+                        "ef_construction",
+                        format!("must be at least twice m ({}), got {}", effective_m, ef),
+                    ));
+                }
+            }
+            let mut options = Vec::new();
+            if let Some(m) = m {
+                options.push(format!("m = {}", m));
+            }
+            if let Some(ef) = ef_construction {
+                options.push(format!("ef_construction = {}", ef));
+            }
+            options
+        }
+        "ivfflat" => {
+            if m.is_some() {
+                return Err(invalid("m", "only applies to hnsw indexes".to_string()));
+            }
+            if ef_construction.is_some() {
+                return Err(invalid(
+                    "ef_construction",
+                    "only applies to hnsw indexes".to_string(),
+                ));
+            }
+            match lists {
+                Some(lists) if !(1..=32768).contains(&lists) => {
+                    return Err(invalid(
+                        "lists",
+                        format!("must be between 1 and 32768, got {}", lists),
+                    ));
+                }
+                Some(lists) => vec![format!("lists = {}", lists)],
+                None => Vec::new(),
+            }
+        }
+        other => {
+            return Err(invalid(
+                "index_type",
+                format!("unknown value '{}'. Valid values: hnsw, ivfflat", other),
+            ));
+        }
+    };
+
+    let with = if options.is_empty() {
+        String::new()
+    } else {
+        format!(" WITH ({})", options.join(", "))
+    };
+    Ok(format!(
+        "CREATE INDEX IF NOT EXISTS idx_{}_embedding_{}_{} ON {} USING {} ((embedding::vector({})) vector_cosine_ops){} WHERE vector_dims(embedding) = {}",
+        table.trim_start_matches("caliber_"),
+        index_type,
+        dimensions,
+        table,
+        index_type,
+        dimensions,
+        with,
+        dimensions
+    ))
+}
+
+/// Create a cosine-distance vector index on an embedding column.
+///
+/// `table` is caliber_artifact or caliber_note and `index_type` is hnsw or
+/// ivfflat. `m` and `ef_construction` tune hnsw indexes and `lists` tunes
+/// ivfflat ones; passing an option for the other index type is rejected.
+/// pgvector only indexes vectors of a fixed dimension, so one index is built
+/// per dimension stored in the table, each covering `embedding::vector(n)` for
+/// rows with `vector_dims(embedding) = n`. Vector searches compare rows through
+/// the same expression and predicate. Indexes are named
+/// `idx_<entity>_embedding_<index_type>_<n>` and creating them again is a no-op.
+/// Returns false when the table holds no embeddings yet.
+#[pg_extern]
+fn caliber_create_vector_index(
+    table: &str,
+    index_type: &str,
+    m: Option<i32>,
+    ef_construction: Option<i32>,
+    lists: Option<i32>,
+) -> bool {
+    let dimensions = match stored_embedding_dimensions_spi(table) {
+        Ok(dimensions) => dimensions,
+        Err(e) => {
+            pgrx::warning!("CALIBER: {}", e);
+            return false;
+        }
+    };
+
+    if dimensions.is_empty() {
+        let validation_err = ValidationError::InvalidValue {
+            field: "table".to_string(),
+            reason: format!("{} has no embeddings to index", table),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return false;
+    }
+
+    let mut statements = Vec::with_capacity(dimensions.len());
+    for dims in dimensions {
+        match vector_index_sql(table, index_type, dims, m, ef_construction, lists) {
+            Ok(sql) => statements.push(sql),
+            Err(e) => {
+                pgrx::warning!("CALIBER: {}", e);
+                return false;
+            }
+        }
+    }
+
+    for sql in statements {
+        if let Err(e) = Spi::run(&sql) {
+            pgrx::warning!("CALIBER: Failed to create vector index: {}", e);
+            return false;
+        }
+    }
+    true
+}
+
+/// Number of vector candidates fetched per requested hybrid search result,
+/// so recency can promote items that fall just outside the top similarity hits.
+const HYBRID_SEARCH_CANDIDATE_FACTOR: i32 = 4;
+
+/// Search for similar vectors, blending cosine similarity with recency.
+///
+/// score = similarity * (1 - w) + recency * w, where recency is created_at
+/// normalized to 0..=1 over the candidate window and w is clamped to 0..=1.
+#[pg_extern]
+fn caliber_hybrid_search(
+    query_embedding: pgrx::JsonB,
+    limit: i32,
+    recency_weight: f32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let query = match parse_query_embedding(query_embedding.0) {
+        Some(v) => v,
+        None => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    let weight = if recency_weight.is_nan() {
+        0.0
+    } else {
+        f64::from(recency_weight.clamp(0.0, 1.0))
+    };
+    let limit = limit.max(0);
+    let candidate_limit = limit.saturating_mul(HYBRID_SEARCH_CANDIDATE_FACTOR);
+
+    let (operator, similarity_expr) = COSINE_METRIC_SQL;
+    let hits = vector_search_hits_or_warn(
+        &query,
+        candidate_limit,
+        operator,
+        similarity_expr,
+        Some(id_from_pgrx(tenant_id)),
+    );
+
+    // Normalize created_at over the candidate window
+    let oldest = hits
+        .iter()
+        .map(|h| h.created_epoch)
+        .fold(f64::INFINITY, f64::min);
+    let newest = hits
+        .iter()
+        .map(|h| h.created_epoch)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = newest - oldest;
+
+    let mut scored: Vec<(f64, f64, VectorSearchHit)> = hits
+        .into_iter()
+        .map(|hit| {
+            let recency = if span > 0.0 {
+                (hit.created_epoch - oldest) / span
+            } else {
+                1.0
+            };
+            let score = hit.similarity * (1.0 - weight) + recency * weight;
+            (score, recency, hit)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(limit as usize);
+
+    let results: Vec<serde_json::Value> = scored
+        .into_iter()
+        .map(|(score, recency, hit)| {
+            serde_json::json!({
+                "entity_id": hit.entity_id.to_string(),
+                "entity_type": hit.entity_type,
+                "similarity": hit.similarity,
+                "recency_score": recency,
+                "score": score,
+            })
+        })
+        .collect();
+
+    pgrx::JsonB(serde_json::json!(results))
+}
+
+/// Default Reciprocal Rank Fusion constant.
+const RRF_DEFAULT_K: i32 = 60;
+
+/// Run a cosine vector search for each query embedding and fuse the ranked
+/// results with Reciprocal Rank Fusion: score += 1 / (k + rank).
+/// Non-positive k defaults to 60.
+#[pg_extern]
+fn caliber_search_rrf(query_embeddings: pgrx::JsonB, limit: i32, k: i32) -> pgrx::JsonB {
+    let queries: Vec<serde_json::Value> = match serde_json::from_value(query_embeddings.0) {
+        Ok(v) => v,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to parse query embeddings: {}", e);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    let k = if k > 0 { k } else { RRF_DEFAULT_K };
+    let limit = limit.max(0);
+    let (operator, similarity_expr) = COSINE_METRIC_SQL;
+
+    // Fused scores keyed by entity, keeping first-seen order for stable ties
+    let mut fused: Vec<(Uuid, String, f64)> = Vec::new();
+    let mut positions: HashMap<Uuid, usize> = HashMap::new();
+
+    for query in queries {
+        let query = match parse_query_embedding(query) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let hits = vector_search_hits_or_warn(&query, limit, operator, similarity_expr, None);
+        for (rank, hit) in hits.into_iter().enumerate() {
+            let contribution = 1.0 / (f64::from(k) + (rank + 1) as f64);
+            match positions.get(&hit.entity_id) {
+                Some(&pos) => fused[pos].2 += contribution,
+                None => {
+                    positions.insert(hit.entity_id, fused.len());
+                    fused.push((hit.entity_id, hit.entity_type, contribution));
+                }
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.2.total_cmp(&a.2));
+    fused.truncate(limit as usize);
+
+    let results: Vec<serde_json::Value> = fused
+        .into_iter()
+        .map(|(entity_id, entity_type, score)| {
+            serde_json::json!({
+                "entity_id": entity_id.to_string(),
+                "entity_type": entity_type,
+                "score": score,
+            })
+        })
+        .collect();
+
+    pgrx::JsonB(serde_json::json!(results))
+}
+
+/// A current artifact or note eligible for relevance-based injection.
+struct RelevantCandidate {
+    entity_id: Uuid,
+    entity_type: String,
+    content: String,
+    similarity: f64,
+}
+
+/// Find the scope's current artifacts and its trajectory's current notes whose
+/// cosine similarity to the query is at least `threshold`, most similar first.
+fn relevant_candidates(
+    query: &[f32],
+    scope_id: ScopeId,
+    trajectory_id: TrajectoryId,
+    threshold: f64,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<RelevantCandidate>> {
+    let vector_str = embedding_to_vector_str(query);
+    Spi::connect(|client| {
+        let table = client
+            .select(
+                &format!(
+                    "SELECT entity_id, entity_type, content, similarity FROM (
+                         SELECT artifact_id as entity_id, 'artifact' as entity_type, content,
+                                (1 - (embedding::vector({dims}) <=> $1::vector))::float8 as similarity
+                         FROM caliber_artifact
+                         WHERE scope_id = $2 AND tenant_id = $4
+                           AND embedding IS NOT NULL AND superseded_by IS NULL
+                           AND deleted_at IS NULL AND vector_dims(embedding) = {dims}
+                         UNION ALL
+                         SELECT note_id as entity_id, 'note' as entity_type, content,
+                                (1 - (embedding::vector({dims}) <=> $1::vector))::float8 as similarity
+                         FROM caliber_note
+                         WHERE $3 = ANY(source_trajectory_ids) AND tenant_id = $4
+                           AND embedding IS NOT NULL AND superseded_by IS NULL
+                           AND deleted_at IS NULL AND vector_dims(embedding) = {dims}
+                     ) combined
+                     WHERE similarity >= $5
+                     ORDER BY similarity DESC",
+                    dims = query.len()
+                ),
+                None,
+                &[
+                    text_datum(&vector_str),
+                    uuid_datum(scope_id.as_uuid()),
+                    uuid_datum(trajectory_id.as_uuid()),
+                    uuid_datum(tenant_id.as_uuid()),
+                    float8_datum(threshold),
+                ],
+            )
+            .map_err(|e| {
+                CaliberError::Storage(StorageError::SpiError {
+                    reason: e.to_string(),
+                })
+            })?;
+
+        let mut candidates = Vec::new();
+        for row in table {
+            let entity_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let entity_type: Option<String> = row.get(2).ok().flatten();
+            let content: Option<String> = row.get(3).ok().flatten();
+            let similarity: Option<f64> = row.get(4).ok().flatten();
+
+            if let (Some(eid), Some(etype), Some(content), Some(sim)) =
+                (entity_id, entity_type, content, similarity)
+            {
+                candidates.push(RelevantCandidate {
+                    entity_id: Uuid::from_bytes(*eid.as_bytes()),
+                    entity_type: etype,
+                    content,
+                    similarity: sim,
+                });
+            }
+        }
+        Ok(candidates)
+    })
+}
+
+/// Runtime for the DSL `inject ... mode: relevant(threshold)` construct.
+///
+/// Runs a cosine vector search over the source scope's current artifacts and
+/// its trajectory's current notes, keeps hits with similarity >= `threshold`
+/// and fills up to `max_tokens` in similarity order. Hits that do not fit in
+/// the remaining budget are skipped so smaller relevant items can still fit.
+#[pg_extern]
+fn caliber_inject_relevant(
+    source_scope: pgrx::Uuid,
+    query_embedding: pgrx::JsonB,
+    threshold: f32,
+    max_tokens: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let empty = || {
+        pgrx::JsonB(serde_json::json!({
+            "items": [],
+            "tokens_used": 0,
+            "candidates": 0,
+        }))
+    };
+
+    // Relevance thresholds are similarities in 0.0..=1.0 (REQ-12)
+    if !(0.0..=1.0).contains(&threshold) {
+        let validation_err = ValidationError::InvalidValue {
+            field: "threshold".to_string(),
+            reason: format!("must be between 0.0 and 1.0, got {}", threshold),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return empty();
+    }
+
+    let scope_id = id_from_pgrx::<ScopeId>(source_scope);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    let query = match parse_query_embedding(query_embedding.0) {
+        Some(v) => v,
+        None => return empty(),
+    };
+
+    let trajectory_id = match scope_heap::scope_get_heap(scope_id, tenant_uuid) {
+        Ok(Some(row)) => row.scope.trajectory_id,
+        Ok(None) => {
+            pgrx::warning!("CALIBER: Scope {} not found for injection", scope_id);
+            return empty();
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get scope for injection: {}", e);
+            return empty();
+        }
+    };
+
+    let candidates = match relevant_candidates(
+        &query,
+        scope_id,
+        trajectory_id,
+        f64::from(threshold),
+        tenant_uuid,
+    ) {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Relevant injection search failed: {}", e);
+            return empty();
+        }
+    };
+
+    let budget = max_tokens.max(0);
+    let candidate_count = candidates.len();
+    let mut tokens_used = 0;
+    let mut items = Vec::new();
+
+    for candidate in candidates {
+        let token_count = caliber_core::estimate_tokens(&candidate.content);
+        if tokens_used + token_count > budget {
+            continue;
+        }
+        tokens_used += token_count;
+        items.push(serde_json::json!({
+            "entity_id": candidate.entity_id.to_string(),
+            "entity_type": candidate.entity_type,
+            "similarity": candidate.similarity,
+            "token_count": token_count,
+            "content": candidate.content,
+        }));
+    }
+
+    pgrx::JsonB(serde_json::json!({
+        "items": items,
+        "tokens_used": tokens_used,
+        "candidates": candidate_count,
+    }))
+}
+
+/// Search across entities with tenant isolation.
+#[pg_extern]
+fn caliber_search(query: pgrx::JsonB, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let query_obj = query.0;
+    let query_text = query_obj
+        .get("query")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .trim()
+        .to_string();
+    if query_text.is_empty() {
+        return pgrx::JsonB(serde_json::json!({ "results": [], "total": 0 }));
+    }
+
+    let entity_types: Vec<String> = query_obj
+        .get("entity_types")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let limit = query_obj
+        .get("limit")
+        .and_then(|v| v.as_i64())
+        .unwrap_or(20)
+        .max(1) as usize;
+
+    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+    let pattern = format!("%{}%", query_text.replace('%', "\\%").replace('_', "\\_"));
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let mut results = Vec::new();
+
+        let include =
+            |name: &str| entity_types.is_empty() || entity_types.iter().any(|t| t == name);
+
+        if include("Trajectory") {
+            let table = client.select(
+                "SELECT trajectory_id, name, description
+                 FROM caliber_trajectory
+                 WHERE tenant_id = $1 AND (name ILIKE $2 OR description ILIKE $2)
+                 ORDER BY updated_at DESC",
+                None,
+                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
+            )?;
+            for row in table {
+                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let name: Option<String> = row.get(2).ok().flatten();
+                let description: Option<String> = row.get(3).ok().flatten();
+                let snippet = description.clone().unwrap_or_default();
+                results.push(serde_json::json!({
+                    "entity_type": "Trajectory",
+                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    "name": name.unwrap_or_default(),
+                    "snippet": snippet.chars().take(160).collect::<String>(),
+                    "score": 1.0_f32,
+                }));
+            }
+        }
+
+        if include("Scope") {
+            let table = client.select(
+                "SELECT scope_id, name, purpose
+                 FROM caliber_scope
+                 WHERE tenant_id = $1 AND (name ILIKE $2 OR purpose ILIKE $2)
+                 ORDER BY created_at DESC",
+                None,
+                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
+            )?;
+            for row in table {
+                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let name: Option<String> = row.get(2).ok().flatten();
+                let purpose: Option<String> = row.get(3).ok().flatten();
+                let snippet = purpose.clone().unwrap_or_default();
+                results.push(serde_json::json!({
+                    "entity_type": "Scope",
+                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    "name": name.unwrap_or_default(),
+                    "snippet": snippet.chars().take(160).collect::<String>(),
+                    "score": 1.0_f32,
+                }));
+            }
+        }
+
+        if include("Artifact") {
+            let table = client.select(
+                "SELECT artifact_id, name, content
+                 FROM caliber_artifact
+                 WHERE tenant_id = $1 AND deleted_at IS NULL
+                   AND (name ILIKE $2 OR content ILIKE $2)
+                 ORDER BY updated_at DESC",
+                None,
+                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
+            )?;
+            for row in table {
+                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let name: Option<String> = row.get(2).ok().flatten();
+                let content: Option<String> = row.get(3).ok().flatten();
+                let snippet = content.clone().unwrap_or_default();
+                results.push(serde_json::json!({
+                    "entity_type": "Artifact",
+                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    "name": name.unwrap_or_default(),
+                    "snippet": snippet.chars().take(160).collect::<String>(),
+                    "score": 1.0_f32,
+                }));
+            }
+        }
+
+        if include("Note") {
+            let table = client.select(
+                "SELECT note_id, title, content
+                 FROM caliber_note
+                 WHERE tenant_id = $1 AND deleted_at IS NULL
+                   AND (title ILIKE $2 OR content ILIKE $2)
+                 ORDER BY updated_at DESC",
+                None,
+                &[uuid_datum(tenant_uuid), text_datum(&pattern)],
+            )?;
+            for row in table {
+                let id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let title: Option<String> = row.get(2).ok().flatten();
+                let content: Option<String> = row.get(3).ok().flatten();
+                let snippet = content.clone().unwrap_or_default();
+                results.push(serde_json::json!({
+                    "entity_type": "Note",
+                    "id": id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                    "name": title.unwrap_or_default(),
+                    "snippet": snippet.chars().take(160).collect::<String>(),
+                    "score": 1.0_f32,
+                }));
+            }
+        }
+
+        Ok(results)
+    });
+
+    match result {
+        Ok(results) => {
+            let total = results.len() as i32;
+            let limited: Vec<serde_json::Value> = results.into_iter().take(limit).collect();
+            pgrx::JsonB(serde_json::json!({
+                "results": limited,
+                "total": total,
+            }))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: search failed: {}", e);
+            pgrx::JsonB(serde_json::json!({ "results": [], "total": 0 }))
+        }
+    }
+}
+
+/// A single artifact or note returned by full-text search.
+struct TextSearchHit {
+    entity_id: Uuid,
+    entity_type: String,
+    name: Option<String>,
+    content: Option<String>,
+    rank: f64,
+}
+
+/// Rank artifacts and notes against a `websearch_to_tsquery` query.
+///
+/// The expression `to_tsvector('english', content)` must match the V19
+/// indexes or the planner falls back to a sequential scan.
+fn text_search_hits(
+    query: &str,
+    entity_type: Option<&str>,
+    trajectory_id: Option<TrajectoryId>,
+    limit: i32,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<Vec<TextSearchHit>> {
+    Spi::connect(|client| {
+        let table = client.select(
+            "SELECT entity_id, entity_type, name, content, rank FROM (
+                 SELECT a.artifact_id AS entity_id, 'artifact' AS entity_type, a.name,
+                        a.content, ts_rank(to_tsvector('english', a.content), q)::float8 AS rank
+                 FROM caliber_artifact a, websearch_to_tsquery('english', $1) q
+                 WHERE a.tenant_id = $2
+                   AND a.deleted_at IS NULL
+                   AND ($3::uuid IS NULL OR a.trajectory_id = $3)
+                   AND ($4::text IS NULL OR $4 = 'artifact')
+                   AND to_tsvector('english', a.content) @@ q
+                 UNION ALL
+                 SELECT n.note_id, 'note', n.title,
+                        n.content, ts_rank(to_tsvector('english', n.content), q)::float8
+                 FROM caliber_note n, websearch_to_tsquery('english', $1) q
+                 WHERE n.tenant_id = $2
+                   AND n.deleted_at IS NULL
+                   AND ($3::uuid IS NULL OR $3 = ANY(n.source_trajectory_ids))
+                   AND ($4::text IS NULL OR $4 = 'note')
+                   AND to_tsvector('english', n.content) @@ q
+             ) hits
+             ORDER BY rank DESC, entity_id
+             LIMIT $5",
+            None,
+            &[
+                text_datum(query),
+                pgrx_uuid_datum(tenant_id),
+                opt_id_datum(trajectory_id),
+                opt_text_datum(entity_type),
+                int4_datum(limit.max(0)),
+            ],
+        )?;
+
+        let mut hits = Vec::new();
+        for row in table {
+            let entity_id: Option<pgrx::Uuid> = row.get(1)?;
+            let entity_type: Option<String> = row.get(2)?;
+            let rank: Option<f64> = row.get(5)?;
+            if let (Some(eid), Some(etype)) = (entity_id, entity_type) {
+                hits.push(TextSearchHit {
+                    entity_id: Uuid::from_bytes(*eid.as_bytes()),
+                    entity_type: etype,
+                    name: row.get(3)?,
+                    content: row.get(4)?,
+                    rank: rank.unwrap_or(0.0),
+                });
+            }
+        }
+        Ok(hits)
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Ranked full-text search over artifact and note content.
+///
+/// The query uses `websearch_to_tsquery` syntax (quoted phrases, `or`, `-term`),
+/// so arbitrary agent input never raises a tsquery syntax error. `entity_type`
+/// restricts results to "artifact" or "note"; `trajectory_id` matches an
+/// artifact's trajectory or any of a note's source trajectories.
+#[pg_extern]
+fn caliber_text_search(
+    query: &str,
+    entity_type: Option<&str>,
+    trajectory_id: Option<pgrx::Uuid>,
+    limit: i32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    // Validate entity_type - reject unknown values (REQ-12)
+    if let Some(kind) = entity_type {
+        if kind != "artifact" && kind != "note" {
+            let validation_err = ValidationError::InvalidValue {
+                field: "entity_type".to_string(),
+                reason: format!("unknown value '{}'. Valid values: artifact, note", kind),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    }
+    if query.trim().is_empty() {
+        return pgrx::JsonB(serde_json::json!([]));
+    }
+
+    let traj_id = opt_id_from_pgrx::<TrajectoryId>(trajectory_id);
+
+    match text_search_hits(query, entity_type, traj_id, limit, tenant_id) {
+        Ok(hits) => {
+            let results: Vec<serde_json::Value> = hits
+                .into_iter()
+                .map(|hit| {
+                    serde_json::json!({
+                        "entity_id": hit.entity_id.to_string(),
+                        "entity_type": hit.entity_type,
+                        "name": hit.name,
+                        "content": hit.content,
+                        "rank": hit.rank,
+                    })
+                })
+                .collect();
+            pgrx::JsonB(serde_json::json!(results))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Text search failed: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Combine full-text rank and cosine similarity into one ranking.
+///
+/// score = vector * alpha + text * (1 - alpha), with alpha clamped to 0..=1.
+/// Text rank is normalized by the best rank among the text candidates and
+/// cosine similarity is clamped to 0..=1; an item found by only one search
+/// scores 0 on the other.
+#[pg_extern]
+fn caliber_search_hybrid_text_vector(
+    query_text: &str,
+    query_embedding: pgrx::JsonB,
+    limit: i32,
+    alpha: f32,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let query = match parse_query_embedding(query_embedding.0) {
+        Some(v) => v,
+        None => return pgrx::JsonB(serde_json::json!([])),
+    };
+
+    let alpha = if alpha.is_nan() {
+        0.5
+    } else {
+        f64::from(alpha.clamp(0.0, 1.0))
+    };
+    let limit = limit.max(0);
+    let candidate_limit = limit.saturating_mul(HYBRID_SEARCH_CANDIDATE_FACTOR);
+
+    let (operator, similarity_expr) = COSINE_METRIC_SQL;
+    let vector_hits = vector_search_hits_or_warn(
+        &query,
+        candidate_limit,
+        operator,
+        similarity_expr,
+        Some(id_from_pgrx(tenant_id)),
+    );
+    let text_hits = if query_text.trim().is_empty() {
+        Vec::new()
+    } else {
+        match text_search_hits(query_text, None, None, candidate_limit, tenant_id) {
+            Ok(hits) => hits,
+            Err(e) => {
+                pgrx::warning!("CALIBER: Text search failed: {}", e);
+                Vec::new()
+            }
+        }
+    };
+
+    let best_rank = text_hits.iter().map(|h| h.rank).fold(0.0, f64::max);
+
+    // entity_id -> (entity_type, vector score, text score)
+    let mut combined: std::collections::HashMap<Uuid, (String, f64, f64)> =
+        std::collections::HashMap::new();
+    for hit in vector_hits {
+        combined.insert(
+            hit.entity_id,
+            (hit.entity_type, hit.similarity.clamp(0.0, 1.0), 0.0),
+        );
+    }
+    for hit in text_hits {
+        let text_score = if best_rank > 0.0 {
+            hit.rank / best_rank
+        } else {
+            0.0
+        };
+        combined
+            .entry(hit.entity_id)
+            .or_insert_with(|| (hit.entity_type, 0.0, 0.0))
+            .2 = text_score;
+    }
+
+    let mut scored: Vec<(f64, Uuid, String, f64, f64)> = combined
+        .into_iter()
+        .map(|(id, (entity_type, vector, text))| {
+            (
+                vector * alpha + text * (1.0 - alpha),
+                id,
+                entity_type,
+                vector,
+                text,
+            )
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    scored.truncate(limit as usize);
+
+    let results: Vec<serde_json::Value> = scored
+        .into_iter()
+        .map(|(score, id, entity_type, vector, text)| {
+            serde_json::json!({
+                "entity_id": id.to_string(),
+                "entity_type": entity_type,
+                "vector_score": vector,
+                "text_score": text,
+                "score": score,
+            })
+        })
+        .collect();
+
+    pgrx::JsonB(serde_json::json!(results))
+}
+
+/// Read the tenant's configured embedding dimension, if one has been set.
+fn embedding_dimension_spi(tenant_id: pgrx::Uuid) -> CaliberResult<Option<i32>> {
+    Spi::connect(|client| {
+        let mut table = client.select(
+            "SELECT dimensions FROM caliber_embedding_setting WHERE tenant_id = $1",
+            Some(1),
+            &[pgrx_uuid_datum(tenant_id)],
+        )?;
+        match table.next() {
+            Some(row) => row.get::<i32>(1),
+            None => Ok(None),
+        }
+    })
+    .map_err(|e: pgrx::spi::SpiError| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Configure the embedding dimension expected for a tenant.
+/// Vectors written through `caliber_embedding_set` must match it.
+#[pg_extern]
+fn caliber_embedding_dimension_set(dimensions: i32, tenant_id: pgrx::Uuid) -> bool {
+    // Validate dimensions - reject non-positive values (REQ-12)
+    if dimensions <= 0 {
+        let validation_err = ValidationError::InvalidValue {
+            field: "dimensions".to_string(),
+            reason: "must be positive".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return false;
+    }
+
+    let result = Spi::connect_mut(|client| {
+        client.update(
+            "INSERT INTO caliber_embedding_setting (tenant_id, dimensions)
+             VALUES ($1, $2)
+             ON CONFLICT (tenant_id) DO UPDATE SET
+                 dimensions = EXCLUDED.dimensions,
+                 updated_at = NOW()",
+            None,
+            &[pgrx_uuid_datum(tenant_id), int4_datum(dimensions)],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(())
+    });
+
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to set embedding dimension: {}", e);
+            false
+        }
+    }
+}
+
+/// Get the embedding dimension configured for a tenant, or NULL if unset.
+#[pg_extern]
+fn caliber_embedding_dimension_get(tenant_id: pgrx::Uuid) -> Option<i32> {
+    match embedding_dimension_spi(tenant_id) {
+        Ok(dimensions) => dimensions,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to read embedding dimension: {}", e);
+            None
+        }
+    }
+}
+
+/// Parse an embedding, validating it against the tenant's configured dimension.
+///
+/// Accepts a JSON array of floats, stored with model id "unknown", or an
+/// `EmbeddingVector` object whose `dimensions` must match its data.
+fn embedding_from_json_checked(
+    embedding: serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<EmbeddingVector> {
+    let invalid = |reason: String| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: "embedding".to_string(),
+            reason,
+        })
+    };
+    let (data, model_id) = if embedding.is_object() {
+        let vector: EmbeddingVector = serde_json::from_value(embedding)
+            .map_err(|e| invalid(format!("expected an embedding object: {}", e)))?;
+        if usize::try_from(vector.dimensions).ok() != Some(vector.data.len()) {
+            return Err(invalid(format!(
+                "dimensions {} does not match {} values",
+                vector.dimensions,
+                vector.data.len()
+            )));
+        }
+        (vector.data, vector.model_id)
+    } else {
+        let data: Vec<f32> = serde_json::from_value(embedding)
+            .map_err(|e| invalid(format!("expected a JSON array of floats: {}", e)))?;
+        (data, "unknown".to_string())
+    };
+    if data.is_empty() {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "embedding".to_string(),
+            reason: "must not be empty".to_string(),
+        }));
+    }
+    if data.iter().any(|v| !v.is_finite()) {
+        return Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "embedding".to_string(),
+            reason: "must contain only finite values".to_string(),
+        }));
+    }
+
+    // Validate length against the configured dimension (REQ-12)
+    if let Some(expected) = embedding_dimension_spi(tenant_id)? {
+        if data.len() != expected as usize {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "embedding".to_string(),
+                reason: format!("expected {} dimensions, got {}", expected, data.len()),
+            }));
+        }
+    }
+
+    Ok(EmbeddingVector::new(data, model_id))
+}
+
+fn embedding_set_checked(
+    entity_type: &str,
+    id: pgrx::Uuid,
+    embedding: serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<bool> {
+    let vector = embedding_from_json_checked(embedding, tenant_id)?;
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+
+    match entity_type {
+        "artifact" => artifact_heap::artifact_update_heap(
+            id_from_pgrx::<ArtifactId>(id),
+            None,
+            None,
+            Some(Some(&vector)),
+            None,
+            None,
+            tenant,
+        ),
+        "note" => note_heap::note_update_heap(note_heap::NoteUpdateHeapParams {
+            id: id_from_pgrx::<NoteId>(id),
+            tenant_id: tenant,
+            title: None,
+            content: None,
+            content_hash: None,
+            embedding: Some(Some(&vector)),
+            ttl: None,
+            abstraction_level: None,
+            superseded_by: None,
+            metadata: None,
+        }),
+        _ => Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "entity_type".to_string(),
+            reason: format!(
+                "unknown value '{}'. Valid values: artifact, note",
+                entity_type
+            ),
+        })),
+    }
+}
+
+/// Store an embedding on an artifact or note.
+///
+/// `entity_type` is "artifact" or "note"; `embedding` is a JSON array of
+/// floats. If the tenant has a configured dimension (see
+/// `caliber_embedding_dimension_set`), vectors of any other length are rejected.
+/// Returns false if the entity does not exist or validation fails.
+#[pg_extern]
+fn caliber_embedding_set(
+    entity_type: &str,
+    id: pgrx::Uuid,
+    embedding: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    match embedding_set_checked(entity_type, id, embedding.0, tenant_id) {
+        Ok(updated) => updated,
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            false
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to set embedding: {}", e);
+            false
+        }
+    }
+}
+
+fn embedding_get_checked(
+    entity_type: &str,
+    id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<Option<EmbeddingVector>> {
+    let tenant = id_from_pgrx::<TenantId>(tenant_id);
+    match entity_type {
+        "artifact" => Ok(
+            artifact_heap::artifact_get_heap(id_from_pgrx::<ArtifactId>(id), tenant)?
+                .and_then(|row| row.artifact.embedding),
+        ),
+        "note" => Ok(
+            note_heap::note_get_heap(id_from_pgrx::<NoteId>(id), tenant)?
+                .and_then(|row| row.note.embedding),
+        ),
+        _ => Err(CaliberError::Validation(ValidationError::InvalidValue {
+            field: "entity_type".to_string(),
+            reason: format!(
+                "unknown value '{}'. Valid values: artifact, note",
+                entity_type
+            ),
+        })),
+    }
+}
+
+/// Read back the embedding stored on an artifact or note as a plain JSON
+/// array of floats. Returns None if the entity does not exist or has no
+/// embedding.
+#[pg_extern]
+fn caliber_embedding_get(
+    entity_type: &str,
+    id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    match embedding_get_checked(entity_type, id, tenant_id) {
+        Ok(embedding) => embedding.map(|e| pgrx::JsonB(serde_json::json!(e.data))),
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            None
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get embedding: {}", e);
+            None
+        }
+    }
+}
+
+// ============================================================================
+// INDEX MAINTENANCE
+// ============================================================================
+
+/// Heap-backed tables whose indexes are maintained by `index_ops` and may be
+/// rebuilt with `caliber_reindex`.
+const REINDEX_TABLES: &[&str] = &[
+    "caliber_trajectory",
+    "caliber_scope",
+    "caliber_artifact",
+    "caliber_note",
+    "caliber_turn",
+    "caliber_agent",
+    "caliber_lock",
+    "caliber_message",
+    "caliber_delegation",
+    "caliber_handoff",
+    "caliber_conflict",
+    "caliber_edge",
+];
+
+/// List the indexes on caliber tables with their validity and size.
+///
+/// Returns `[{index_name, table_name, valid, size_bytes}]` ordered by table
+/// and index name. An index left invalid by a failed build reports
+/// `valid: false` and should be rebuilt with `caliber_reindex`.
+#[pg_extern]
+fn caliber_index_status() -> pgrx::JsonB {
+    let result = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT ic.relname::TEXT, tc.relname::TEXT, i.indisvalid,
+                    pg_relation_size(i.indexrelid)
+             FROM pg_index i
+             JOIN pg_class ic ON ic.oid = i.indexrelid
+             JOIN pg_class tc ON tc.oid = i.indrelid
+             WHERE tc.relname LIKE 'caliber\\_%' AND pg_table_is_visible(tc.oid)
+             ORDER BY tc.relname, ic.relname",
+            None,
+            &[],
+        )?;
+
+        let mut indexes = Vec::new();
+        for row in table {
+            indexes.push(serde_json::json!({
+                "index_name": row.get::<String>(1)?,
+                "table_name": row.get::<String>(2)?,
+                "valid": row.get::<bool>(3)?,
+                "size_bytes": row.get::<i64>(4)?,
+            }));
+        }
+        Ok::<_, pgrx::spi::SpiError>(indexes)
+    });
+
+    match result {
+        Ok(indexes) => pgrx::JsonB(serde_json::json!(indexes)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query index status: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Rebuild every index on a caliber table, e.g. after a bulk
+/// `caliber_trajectory_import`.
+///
+/// Only the heap-backed entity tables are accepted; anything else is
+/// rejected without running any SQL.
+#[pg_extern]
+fn caliber_reindex(table: &str) -> bool {
+    if !REINDEX_TABLES.contains(&table) {
+        // REQ-12: Reject tables outside the allowlist before building DDL
+        pgrx::warning!(
+            "CALIBER: {}",
+            CaliberError::Validation(ValidationError::InvalidValue {
+                field: "table".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: {}",
+                    table,
+                    REINDEX_TABLES.join(", ")
+                ),
+            })
+        );
+        return false;
+    }
+
+    match Spi::run(&format!("REINDEX TABLE {}", table)) {
+        Ok(()) => true,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to reindex {}: {}", table, e);
+            false
+        }
+    }
+}
+
+// ============================================================================
+// OPERATIONAL STATS
+// ============================================================================
+
+/// Operational snapshot across all tenants, available without the debug
+/// feature.
+///
+/// Returns `{entity_counts, active: {trajectories, scopes, agents},
+/// unresolved_conflicts, active_locks, pending_messages,
+/// oldest_pending_message_age_secs}`. Soft-deleted artifacts and notes are
+/// not counted; a message is pending until delivered or expired.
+#[pg_extern]
+fn caliber_stats() -> pgrx::JsonB {
+    let result = Spi::connect(|client| {
+        let row = client
+            .select(
+                "SELECT (SELECT COUNT(*) FROM caliber_trajectory),
+                        (SELECT COUNT(*) FROM caliber_scope),
+                        (SELECT COUNT(*) FROM caliber_artifact WHERE deleted_at IS NULL),
+                        (SELECT COUNT(*) FROM caliber_note WHERE deleted_at IS NULL),
+                        (SELECT COUNT(*) FROM caliber_turn),
+                        (SELECT COUNT(*) FROM caliber_agent),
+                        (SELECT COUNT(*) FROM caliber_lock),
+                        (SELECT COUNT(*) FROM caliber_message),
+                        (SELECT COUNT(*) FROM caliber_delegation),
+                        (SELECT COUNT(*) FROM caliber_handoff),
+                        (SELECT COUNT(*) FROM caliber_conflict),
+                        (SELECT COUNT(*) FROM caliber_trajectory WHERE status = 'active'),
+                        (SELECT COUNT(*) FROM caliber_scope WHERE is_active),
+                        (SELECT COUNT(*) FROM caliber_agent WHERE status = 'active'),
+                        (SELECT COUNT(*) FROM caliber_conflict WHERE status <> 'resolved'),
+                        (SELECT COUNT(*) FROM caliber_lock WHERE expires_at > NOW()),
+                        pending.count,
+                        EXTRACT(EPOCH FROM NOW() - pending.oldest)::float8
+                 FROM (SELECT COUNT(*) AS count, MIN(created_at) AS oldest
+                       FROM caliber_message
+                       WHERE delivered_at IS NULL
+                         AND (expires_at IS NULL OR expires_at > NOW())) pending",
+                None,
+                &[],
+            )?
+            .first();
+
+        Ok::<_, pgrx::spi::SpiError>(serde_json::json!({
+            "entity_counts": {
+                "trajectories": row.get::<i64>(1)?.unwrap_or(0),
+                "scopes": row.get::<i64>(2)?.unwrap_or(0),
+                "artifacts": row.get::<i64>(3)?.unwrap_or(0),
+                "notes": row.get::<i64>(4)?.unwrap_or(0),
+                "turns": row.get::<i64>(5)?.unwrap_or(0),
+                "agents": row.get::<i64>(6)?.unwrap_or(0),
+                "locks": row.get::<i64>(7)?.unwrap_or(0),
+                "messages": row.get::<i64>(8)?.unwrap_or(0),
+                "delegations": row.get::<i64>(9)?.unwrap_or(0),
+                "handoffs": row.get::<i64>(10)?.unwrap_or(0),
+                "conflicts": row.get::<i64>(11)?.unwrap_or(0),
+            },
+            "active": {
+                "trajectories": row.get::<i64>(12)?.unwrap_or(0),
+                "scopes": row.get::<i64>(13)?.unwrap_or(0),
+                "agents": row.get::<i64>(14)?.unwrap_or(0),
+            },
+            "unresolved_conflicts": row.get::<i64>(15)?.unwrap_or(0),
+            "active_locks": row.get::<i64>(16)?.unwrap_or(0),
+            "pending_messages": row.get::<i64>(17)?.unwrap_or(0),
+            "oldest_pending_message_age_secs": row.get::<f64>(18)?,
+        }))
+    });
+
+    match result {
+        Ok(stats) => pgrx::JsonB(stats),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to collect stats: {}", e);
+            pgrx::JsonB(serde_json::json!({}))
+        }
+    }
+}
+
+// ============================================================================
+// DEBUG SQL VIEWS (Task 12.7)
+// Gated behind "debug" or "pg_test" feature flag for safety
+// ============================================================================
+
+/// Get storage statistics for debugging.
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_stats() -> pgrx::JsonB {
+    pgrx::warning!("DEBUG: caliber_debug_stats called");
+
+    // Get counts from SQL tables (all entities now in SQL)
+    let counts = Spi::connect(|client| {
+        let mut counts = serde_json::Map::new();
+
+        let tables = [
+            ("trajectories", "caliber_trajectory"),
+            ("scopes", "caliber_scope"),
+            ("artifacts", "caliber_artifact"),
+            ("notes", "caliber_note"),
+            ("turns", "caliber_turn"),
+            ("locks", "caliber_lock"),
+            ("messages", "caliber_message"),
+            ("agents", "caliber_agent"),
+            ("delegations", "caliber_delegation"),
+            ("handoffs", "caliber_handoff"),
+            ("conflicts", "caliber_conflict"),
+        ];
+
+        for (name, table) in tables {
+            let result = client.select(&format!("SELECT COUNT(*) FROM {}", table), None, &[]);
+            let count = match result {
+                Ok(table) => {
+                    let table = table.first();
+                    table.get_one::<i64>().ok().flatten().unwrap_or(0)
+                }
+                Err(_) => 0,
+            };
+            counts.insert(name.to_string(), serde_json::json!(count));
+        }
+
+        counts
+    });
+
+    // Add operation metrics from in-memory storage
+    let ops = storage_read();
+    let ops_data: serde_json::Map<String, serde_json::Value> = ops
+        .get_ops()
+        .iter()
+        .map(|(k, v)| (k.to_string(), serde_json::json!(*v)))
+        .collect();
+
+    let mut result = serde_json::Map::new();
+    result.insert(
+        "entity_counts".to_string(),
+        serde_json::Value::Object(counts),
+    );
+    result.insert(
+        "operation_counts".to_string(),
+        serde_json::Value::Object(ops_data),
+    );
+
+    pgrx::JsonB(serde_json::Value::Object(result))
+}
+
+/// Clear all storage (for testing).
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_clear() -> &'static str {
+    pgrx::warning!("DEBUG: caliber_debug_clear called - clearing all storage!");
+
+    // Clear SQL tables in correct order (respecting foreign keys)
+    let _ = Spi::run("DELETE FROM caliber_turn");
+    let _ = Spi::run("DELETE FROM caliber_artifact");
+    let _ = Spi::run("DELETE FROM caliber_scope");
+    let _ = Spi::run("DELETE FROM caliber_note");
+    let _ = Spi::run("DELETE FROM caliber_message");
+    let _ = Spi::run("DELETE FROM caliber_lock");
+    let _ = Spi::run("DELETE FROM caliber_conflict");
+    let _ = Spi::run("DELETE FROM caliber_handoff");
+    let _ = Spi::run("DELETE FROM caliber_delegation");
+    let _ = Spi::run("DELETE FROM caliber_region");
+    let _ = Spi::run("DELETE FROM caliber_agent");
+    let _ = Spi::run("DELETE FROM caliber_trajectory");
+
+    // Reset in-memory operation counters
+    storage_write().reset_ops();
+
+    "Storage cleared"
+}
+
+/// Dump all trajectories for debugging.
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_dump_trajectories() -> pgrx::JsonB {
+    pgrx::warning!("DEBUG: caliber_debug_dump_trajectories called");
+
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT trajectory_id, name, description, status, parent_trajectory_id, 
+                    root_trajectory_id, agent_id, created_at, updated_at, completed_at, 
+                    outcome, metadata 
+             FROM caliber_trajectory ORDER BY created_at DESC",
+            None,
+            &[],
+        );
+
+        match result {
+            Ok(table) => {
+                let mut trajectories = Vec::new();
+                for row in table {
+                    let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                    let name: Option<String> = row.get(2).ok().flatten();
+                    let description: Option<String> = row.get(3).ok().flatten();
+                    let status: Option<String> = row.get(4).ok().flatten();
+                    let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
+                    let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
+                    let agent_id: Option<pgrx::Uuid> = row.get(7).ok().flatten();
+                    let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
+                    let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+                    let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+                    let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+
+                    trajectories.push(serde_json::json!({
+                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "name": name,
+                        "description": description,
+                        "status": status,
+                        "parent_trajectory_id": parent_trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "root_trajectory_id": root_trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "agent_id": agent_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "created_at": created_at.map(|t| format!("{:?}", t)),
+                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
+                        "completed_at": completed_at.map(|t| format!("{:?}", t)),
+                        "outcome": outcome.map(|j| j.0),
+                        "metadata": metadata.map(|j| j.0),
+                    }));
+                }
+                pgrx::JsonB(serde_json::json!(trajectories))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to dump trajectories: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
+
+/// Dump all scopes for debugging.
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_dump_scopes() -> pgrx::JsonB {
+    pgrx::warning!("DEBUG: caliber_debug_dump_scopes called");
+
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT scope_id, trajectory_id, parent_scope_id, name, purpose, is_active, 
+                    created_at, closed_at, checkpoint, token_budget, tokens_used, metadata 
+             FROM caliber_scope ORDER BY created_at DESC",
+            None,
+            &[],
+        );
+
+        match result {
+            Ok(table) => {
+                let mut scopes = Vec::new();
+                for row in table {
+                    let scope_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                    let trajectory_id: Option<pgrx::Uuid> = row.get(2).ok().flatten();
+                    let parent_scope_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+                    let name: Option<String> = row.get(4).ok().flatten();
+                    let purpose: Option<String> = row.get(5).ok().flatten();
+                    let is_active: Option<bool> = row.get(6).ok().flatten();
+                    let created_at: Option<TimestampWithTimeZone> = row.get(7).ok().flatten();
+                    let closed_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
+                    let checkpoint: Option<pgrx::JsonB> = row.get(9).ok().flatten();
+                    let token_budget: Option<i32> = row.get(10).ok().flatten();
+                    let tokens_used: Option<i32> = row.get(11).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+
+                    scopes.push(serde_json::json!({
+                        "scope_id": scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "parent_scope_id": parent_scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "name": name,
+                        "purpose": purpose,
+                        "is_active": is_active,
+                        "created_at": created_at.map(|t| format!("{:?}", t)),
+                        "closed_at": closed_at.map(|t| format!("{:?}", t)),
+                        "checkpoint": checkpoint.map(|j| j.0),
+                        "token_budget": token_budget,
+                        "tokens_used": tokens_used,
+                        "metadata": metadata.map(|j| j.0),
+                    }));
+                }
+                pgrx::JsonB(serde_json::json!(scopes))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to dump scopes: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
+
+/// Dump all artifacts for debugging.
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_dump_artifacts() -> pgrx::JsonB {
+    pgrx::warning!("DEBUG: caliber_debug_dump_artifacts called");
+
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT artifact_id, trajectory_id, scope_id, artifact_type, name, content, 
+                    content_hash, provenance, ttl, created_at, updated_at, superseded_by, metadata 
+             FROM caliber_artifact ORDER BY created_at DESC",
+            None,
+            &[],
+        );
+
+        match result {
+            Ok(table) => {
+                let mut artifacts = Vec::new();
+                for row in table {
+                    let artifact_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                    let trajectory_id: Option<pgrx::Uuid> = row.get(2).ok().flatten();
+                    let scope_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+                    let artifact_type: Option<String> = row.get(4).ok().flatten();
+                    let name: Option<String> = row.get(5).ok().flatten();
+                    let content: Option<String> = row.get(6).ok().flatten();
+                    let content_hash: Option<Vec<u8>> = row.get(7).ok().flatten();
+                    let provenance: Option<pgrx::JsonB> = row.get(8).ok().flatten();
+                    let ttl: Option<String> = row.get(9).ok().flatten();
+                    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+                    let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
+                    let superseded_by: Option<pgrx::Uuid> = row.get(12).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> = row.get(13).ok().flatten();
+
+                    artifacts.push(serde_json::json!({
+                        "artifact_id": artifact_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "scope_id": scope_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "artifact_type": artifact_type,
+                        "name": name,
+                        "content": content,
+                        "content_hash": content_hash.map(|h| hex::encode(&h)),
+                        "provenance": provenance.map(|j| j.0),
+                        "ttl": ttl,
+                        "created_at": created_at.map(|t| format!("{:?}", t)),
+                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
+                        "superseded_by": superseded_by.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "metadata": metadata.map(|j| j.0),
+                    }));
+                }
+                pgrx::JsonB(serde_json::json!(artifacts))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to dump artifacts: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
+
+/// Dump all agents for debugging.
+#[cfg(any(test, feature = "debug", feature = "pg_test"))]
+#[pg_extern]
+fn caliber_debug_dump_agents() -> pgrx::JsonB {
+    pgrx::warning!("DEBUG: caliber_debug_dump_agents called");
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT agent_id, agent_type, capabilities, status, created_at, last_heartbeat
+             FROM caliber_agent ORDER BY created_at",
+            None,
+            &[],
+        );
+
+        match result {
+            Ok(table) => {
+                let agents: Vec<serde_json::Value> = table.into_iter().map(|row| {
+                    serde_json::json!({
+                        "agent_id": row.get::<pgrx::Uuid>(1).ok().flatten().map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                        "agent_type": row.get::<String>(2).ok().flatten(),
+                        "capabilities": row.get::<Vec<String>>(3).ok().flatten().unwrap_or_default(),
+                        "status": row.get::<String>(4).ok().flatten(),
+                        "created_at": row.get::<TimestampWithTimeZone>(5).ok().flatten().map(|t| t.to_string()),
+                        "last_heartbeat": row.get::<TimestampWithTimeZone>(6).ok().flatten().map(|t| t.to_string()),
+                    })
+                }).collect();
+                pgrx::JsonB(serde_json::json!(agents))
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to dump agents: {}", e);
+                pgrx::JsonB(serde_json::json!([]))
+            }
+        }
+    })
+}
+
+// ============================================================================
+// ACCESS CONTROL (Task 12.3)
+// ============================================================================
+
+/// Access operation type for permission checking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessOperation {
+    Read,
+    Write,
+}
+
+/// Region permission rules by type, before any lock requirement.
+fn region_permits(
+    region_type: &str,
+    owner_id: Uuid,
+    readers: &[Uuid],
+    writers: &[Uuid],
+    agent_id: Uuid,
+    operation: AccessOperation,
+) -> bool {
+    match operation {
+        AccessOperation::Read => match region_type {
+            "private" => agent_id == owner_id,
+            "team" => agent_id == owner_id || readers.contains(&agent_id),
+            "public" | "collaborative" => true,
+            _ => {
+                pgrx::warning!(
+                    "CALIBER: Unknown region_type '{}' in read access check, denying access",
+                    region_type
+                );
+                false
+            }
+        },
+        AccessOperation::Write => match region_type {
+            "private" => agent_id == owner_id,
+            "team" => agent_id == owner_id || writers.contains(&agent_id),
+            "public" => agent_id == owner_id,
+            "collaborative" => true,
+            _ => {
+                pgrx::warning!(
+                    "CALIBER: Unknown region_type '{}' in write access check, denying access",
+                    region_type
+                );
+                false
+            }
+        },
+    }
+}
+
+/// Check if an agent holds an unexpired lock on a region.
+fn agent_holds_region_lock(agent_id: Uuid, region_id: Uuid) -> bool {
+    Spi::connect(|client| {
+        use pgrx::datum::DatumWithOid;
+        let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
+        let pg_agent_id = pgrx::Uuid::from_bytes(*agent_id.as_bytes());
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(pg_agent_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let result = client.select(
+            "SELECT 1 FROM caliber_lock
+             WHERE resource_type = 'region' AND resource_id = $1
+             AND holder_agent_id = $2 AND expires_at > NOW()",
+            None,
+            params,
+        );
+        match result {
+            Ok(table) => !table.is_empty(),
+            Err(_) => false,
+        }
+    })
+}
+
+/// Enforce access control for a memory region.
+/// Returns Ok(()) if access is allowed, Err with PermissionDenied otherwise.
+fn enforce_access(
+    agent_id: Uuid,
+    region_id: Uuid,
+    operation: AccessOperation,
+) -> CaliberResult<()> {
+    // Get region config from SQL table
+    let region_config = Spi::connect(|client| {
+        use pgrx::datum::DatumWithOid;
+        let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
+        let params: &[DatumWithOid<'_>] =
+            &[unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) }];
+        let result = client.select(
+            "SELECT region_id, region_type, owner_agent_id, readers, writers, require_lock
+             FROM caliber_region WHERE region_id = $1",
+            None,
+            params,
+        );
+
+        match result {
+            Ok(mut table) => {
+                if let Some(row) = table.next() {
+                    let region_type: Option<String> = row.get(2).ok().flatten();
+                    let owner_agent_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+                    // Note: team_id exists in schema but team membership lookup not yet implemented
+                    // For now, use explicit readers/writers arrays
+                    let readers: Option<Vec<pgrx::Uuid>> = row.get(4).ok().flatten();
+                    let writers: Option<Vec<pgrx::Uuid>> = row.get(5).ok().flatten();
+                    let require_lock: Option<bool> = row.get(6).ok().flatten();
+
+                    // Convert pgrx::Uuid to uuid::Uuid with explicit type annotations
+                    let owner_id =
+                        owner_agent_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()));
+                    let readers_vec: Vec<Uuid> = readers
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()))
+                        .collect();
+                    let writers_vec: Vec<Uuid> = writers
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()))
+                        .collect();
+
+                    Some((
+                        region_type.unwrap_or_default(),
+                        owner_id,
+                        readers_vec,
+                        writers_vec,
+                        require_lock.unwrap_or(false),
+                    ))
+                } else {
+                    None
+                }
+            }
+            Err(_) => None,
+        }
+    });
+
+    // If region not found, deny access
+    let (region_type, owner_agent_id, readers, writers, require_lock): (
+        String,
+        Option<Uuid>,
+        Vec<Uuid>,
+        Vec<Uuid>,
+        bool,
+    ) = match region_config {
+        Some(config) => config,
+        None => {
+            return Err(CaliberError::Agent(AgentError::PermissionDenied {
+                agent_id,
+                action: format!("{:?}", operation).to_lowercase(),
+                resource: format!("region:{}", region_id),
+            }));
+        }
+    };
+
+    let owner_id = owner_agent_id.unwrap_or(Uuid::nil());
+
+    // Check permission based on region type and operation
+    let base_allowed = region_permits(
+        &region_type,
+        owner_id,
+        &readers,
+        &writers,
+        agent_id,
+        operation,
+    );
+
+    // For collaborative regions, also check if lock is held when required
+    let allowed = if base_allowed
+        && operation == AccessOperation::Write
+        && require_lock
+        && region_type == "collaborative"
+    {
+        if !agent_holds_region_lock(agent_id, region_id) {
+            return Err(CaliberError::Agent(AgentError::LockAcquisitionFailed {
+                resource: format!("region:{}", region_id),
+                holder: Uuid::nil(), // Unknown holder
+            }));
+        }
+        true
+    } else {
+        base_allowed
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(CaliberError::Agent(AgentError::PermissionDenied {
+            agent_id,
+            action: format!("{:?}", operation).to_lowercase(),
+            resource: format!("region:{}", region_id),
+        }))
+    }
+}
+
+/// Check if an agent has access to a memory region.
+/// Returns true if access is allowed, false otherwise.
+#[pg_extern]
+fn caliber_check_access(agent_id: pgrx::Uuid, region_id: pgrx::Uuid, access_type: &str) -> bool {
+    let aid = Uuid::from_bytes(*agent_id.as_bytes());
+    let rid = Uuid::from_bytes(*region_id.as_bytes());
+
+    let operation = match access_type {
+        "read" => AccessOperation::Read,
+        "write" => AccessOperation::Write,
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Invalid access_type '{}', must be 'read' or 'write'",
+                access_type
+            );
+            return false;
+        }
+    };
+
+    match enforce_access(aid, rid, operation) {
+        Ok(()) => true,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Access denied - {:?}", e);
+            false
+        }
+    }
+}
+
+/// List the regions an agent can read or write.
+///
+/// Applies the same rules as `caliber_check_access`, including the lock
+/// requirement for writes to collaborative regions, so every region returned
+/// would pass that check.
+#[pg_extern]
+fn caliber_regions_for_agent(
+    agent_id: pgrx::Uuid,
+    access_type: &str,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let aid = Uuid::from_bytes(*agent_id.as_bytes());
+
+    let operation = match access_type {
+        "read" => AccessOperation::Read,
+        "write" => AccessOperation::Write,
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Invalid access_type '{}', must be 'read' or 'write'",
+                access_type
+            );
+            return pgrx::JsonB(serde_json::json!([]));
+        }
+    };
+
+    let to_uuids = |ids: Option<Vec<pgrx::Uuid>>| -> Vec<Uuid> {
+        ids.unwrap_or_default()
+            .iter()
+            .map(|u| Uuid::from_bytes(*u.as_bytes()))
+            .collect()
+    };
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT region_id, region_type, owner_agent_id, team_id, readers, writers,
+                    require_lock, conflict_resolution, version_tracking
+             FROM caliber_region WHERE tenant_id = $1
+             ORDER BY created_at",
+            None,
+            &[pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        let mut regions = Vec::new();
+        for row in table {
+            let region_id = match row.get::<pgrx::Uuid>(1)? {
+                Some(id) => Uuid::from_bytes(*id.as_bytes()),
+                None => continue,
+            };
+            let region_type: String = row.get(2)?.unwrap_or_default();
+            let owner_id = row
+                .get::<pgrx::Uuid>(3)?
+                .map(|u| Uuid::from_bytes(*u.as_bytes()))
+                .unwrap_or(Uuid::nil());
+            let team_id: Option<pgrx::Uuid> = row.get(4)?;
+            let readers = to_uuids(row.get(5)?);
+            let writers = to_uuids(row.get(6)?);
+            let require_lock: bool = row.get(7)?.unwrap_or(false);
+            let conflict_resolution: Option<String> = row.get(8)?;
+            let version_tracking: Option<bool> = row.get(9)?;
+
+            if !region_permits(&region_type, owner_id, &readers, &writers, aid, operation) {
+                continue;
+            }
+            if operation == AccessOperation::Write
+                && require_lock
+                && region_type == "collaborative"
+                && !agent_holds_region_lock(aid, region_id)
+            {
+                continue;
+            }
+
+            regions.push(serde_json::json!({
+                "region_id": region_id.to_string(),
+                "region_type": region_type,
+                "owner_agent_id": owner_id.to_string(),
+                "team_id": team_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "readers": readers.iter().map(|u| u.to_string()).collect::<Vec<_>>(),
+                "writers": writers.iter().map(|u| u.to_string()).collect::<Vec<_>>(),
+                "require_lock": require_lock,
+                "conflict_resolution": conflict_resolution,
+                "version_tracking": version_tracking,
+            }));
+        }
+
+        Ok(regions)
+    });
+
+    match result {
+        Ok(regions) => pgrx::JsonB(serde_json::json!(regions)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list regions for agent: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Create a new memory region.
+///
+/// Artifacts belong to a region by carrying its id as a `region_id` string in
+/// their metadata (set with `caliber_artifact_update`, or copied when a scope
+/// is cloned or a trajectory imported). Creating, updating, superseding or
+/// soft-deleting such an artifact bumps the region's version when
+/// `version_tracking` is on; see `caliber_region_version`.
+#[pg_extern]
+fn caliber_region_create(
+    owner_agent_id: pgrx::Uuid,
+    region_type: &str,
+    team_id: Option<pgrx::Uuid>,
+    require_lock: bool,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    use pgrx::datum::DatumWithOid;
+
+    let pg_owner = owner_agent_id;
+    let region_id = Uuid::now_v7();
+    let pg_region_id = pgrx::Uuid::from_bytes(*region_id.as_bytes());
+    let now = match tuple_extract::chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return None;
+        }
+    };
+
+    // Validate region_type
+    let valid_region_type = match region_type {
+        "private" | "team" | "public" | "collaborative" => region_type,
+        _ => {
+            pgrx::warning!("CALIBER: Invalid region_type '{}'. Valid values: private, team, public, collaborative", region_type);
+            return None;
+        }
+    };
+
+    // Determine default conflict resolution based on region type
+    let conflict_resolution = match valid_region_type {
+        "collaborative" => "escalate",
+        _ => "last_write_wins",
+    };
+
+    // Determine version tracking based on region type
+    let version_tracking = matches!(valid_region_type, "team" | "collaborative");
+
+    // For private regions, owner is the only reader/writer
+    let (readers, writers): (Vec<pgrx::Uuid>, Vec<pgrx::Uuid>) = if valid_region_type == "private" {
+        (vec![pg_owner], vec![pg_owner])
+    } else if valid_region_type == "public" {
+        (Vec::new(), vec![pg_owner])
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    let result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_region_id, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(valid_region_type, pgrx::pg_sys::TEXTOID) },
+            unsafe { DatumWithOid::new(pg_owner, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(team_id, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(readers.clone(), pgrx::pg_sys::UUIDARRAYOID) },
+            unsafe { DatumWithOid::new(writers.clone(), pgrx::pg_sys::UUIDARRAYOID) },
+            bool_datum(require_lock),
+            unsafe { DatumWithOid::new(conflict_resolution, pgrx::pg_sys::TEXTOID) },
+            bool_datum(version_tracking),
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        client.update(
+            "INSERT INTO caliber_region (region_id, region_type, owner_agent_id, team_id,
+                                         readers, writers, require_lock, conflict_resolution,
+                                         version_tracking, created_at, updated_at, tenant_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(())
+    });
+
+    match result {
+        Ok(()) => Some(pg_region_id),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to create region: {}", e);
+            None
+        }
+    }
+}
+
+/// Get a memory region by ID.
+#[pg_extern]
+fn caliber_region_get(region_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
+    use pgrx::datum::DatumWithOid;
+
+    let rid = Uuid::from_bytes(*region_id.as_bytes());
+    let pg_rid = pgrx::Uuid::from_bytes(*rid.as_bytes());
+
+    Spi::connect(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let result = client.select(
+            "SELECT region_id, region_type, owner_agent_id, team_id, readers, writers,
+                    require_lock, conflict_resolution, version_tracking, created_at, updated_at,
+                    version
+             FROM caliber_region WHERE region_id = $1 AND tenant_id = $2",
+            None,
+            params,
+        );
+
+        match result {
+            Ok(mut table) => {
+                if let Some(row) = table.next() {
+                    let region_id_val: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                    let region_type: Option<String> = row.get(2).ok().flatten();
+                    let owner_agent_id: Option<pgrx::Uuid> = row.get(3).ok().flatten();
+                    let team_id: Option<pgrx::Uuid> = row.get(4).ok().flatten();
+                    let readers: Option<Vec<pgrx::Uuid>> = row.get(5).ok().flatten();
+                    let writers: Option<Vec<pgrx::Uuid>> = row.get(6).ok().flatten();
+                    let require_lock: Option<bool> = row.get(7).ok().flatten();
+                    let conflict_resolution: Option<String> = row.get(8).ok().flatten();
+                    let version_tracking: Option<bool> = row.get(9).ok().flatten();
+                    let created_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+                    let updated_at: Option<TimestampWithTimeZone> = row.get(11).ok().flatten();
+                    let version: Option<i64> = row.get(12).ok().flatten();
+
+                    // Convert pgrx::Uuid to uuid::Uuid strings with explicit type annotations
+                    let region_id_str = region_id_val
+                        .map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
+                    let owner_id_str = owner_agent_id
+                        .map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
+                    let team_id_str =
+                        team_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string());
+                    let readers_strs = readers.map(|ids: Vec<pgrx::Uuid>| {
+                        ids.iter()
+                            .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string())
+                            .collect::<Vec<_>>()
+                    });
+                    let writers_strs = writers.map(|ids: Vec<pgrx::Uuid>| {
+                        ids.iter()
+                            .map(|u: &pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string())
+                            .collect::<Vec<_>>()
+                    });
+
+                    Some(pgrx::JsonB(serde_json::json!({
+                        "region_id": region_id_str,
+                        "region_type": region_type,
+                        "owner_agent_id": owner_id_str,
+                        "team_id": team_id_str,
+                        "readers": readers_strs,
+                        "writers": writers_strs,
+                        "require_lock": require_lock,
+                        "conflict_resolution": conflict_resolution,
+                        "version_tracking": version_tracking,
+                        "created_at": created_at.map(|t| format!("{:?}", t)),
+                        "updated_at": updated_at.map(|t| format!("{:?}", t)),
+                        "version": version,
+                    })))
+                } else {
+                    None
+                }
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get region: {}", e);
+                None
+            }
+        }
+    })
+}
+
+/// Update a memory region's policy.
+/// Supports partial updates to require_lock, conflict_resolution and version_tracking.
+#[pg_extern]
+fn caliber_region_update(
+    region_id: pgrx::Uuid,
+    updates: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
+    let update_obj = &updates.0;
+
+    // Validate boolean flags - reject non-boolean values (REQ-12)
+    let flag = |field: &str| match update_obj.get(field) {
+        None => Ok(None),
+        Some(v) => v
+            .as_bool()
+            .map(Some)
+            .ok_or_else(|| ValidationError::InvalidValue {
+                field: field.to_string(),
+                reason: format!("expected a boolean, got {}", v),
+            }),
+    };
+    let (require_lock, version_tracking) = match (flag("require_lock"), flag("version_tracking")) {
+        (Ok(require_lock), Ok(version_tracking)) => (require_lock, version_tracking),
+        (Err(validation_err), _) | (_, Err(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return false;
+        }
+    };
+    let conflict_resolution = match update_obj.get("conflict_resolution") {
+        None => None,
+        Some(v) => match v.as_str() {
+            Some(s @ ("last_write_wins" | "highest_confidence" | "escalate")) => Some(s),
+            _ => {
+                // Validate conflict_resolution - reject unknown values (REQ-12)
+                let validation_err = ValidationError::InvalidValue {
+                    field: "conflict_resolution".to_string(),
+                    reason: format!(
+                        "unknown value {}. Valid values: last_write_wins, highest_confidence, escalate",
+                        v
+                    ),
+                };
+                pgrx::warning!("CALIBER: {:?}", validation_err);
+                return false;
+            }
+        },
+    };
+
+    if require_lock.is_none() && version_tracking.is_none() && conflict_resolution.is_none() {
+        return false;
+    }
+
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
+        }
+    };
+
+    // Build dynamic UPDATE query based on provided fields
+    let mut set_clauses: Vec<String> = Vec::new();
+    let mut params: Vec<DatumWithOid<'_>> = Vec::new();
+
+    if let Some(v) = require_lock {
+        params.push(bool_datum(v));
+        set_clauses.push(format!("require_lock = ${}", params.len()));
+    }
+    if let Some(v) = conflict_resolution {
+        params.push(text_datum(v));
+        set_clauses.push(format!("conflict_resolution = ${}", params.len()));
+    }
+    if let Some(v) = version_tracking {
+        params.push(bool_datum(v));
+        set_clauses.push(format!("version_tracking = ${}", params.len()));
+    }
+    params.push(unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) });
+    set_clauses.push(format!("updated_at = ${}", params.len()));
+
+    let query = format!(
+        "UPDATE caliber_region SET {} WHERE region_id = ${} AND tenant_id = ${}",
+        set_clauses.join(", "),
+        params.len() + 1,
+        params.len() + 2
+    );
+    params.push(pgrx_uuid_datum(region_id));
+    params.push(pgrx_uuid_datum(tenant_id));
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let table = client.update(&query, None, &params)?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to update region: {}", e);
+            false
+        }
+    }
+}
+
+/// Bump the version of a version-tracked region.
+///
+/// Returns the new version, or None if the region does not exist, belongs to
+/// another tenant or does not track versions.
+fn region_bump_version(region_id: Uuid, tenant_id: TenantId) -> CaliberResult<Option<i64>> {
+    Spi::connect_mut(|client| {
+        let mut table = client.update(
+            "UPDATE caliber_region
+             SET version = version + 1, updated_at = NOW()
+             WHERE region_id = $1 AND tenant_id = $2 AND version_tracking
+             RETURNING version",
+            None,
+            &[uuid_datum(region_id), id_datum(tenant_id)],
+        )?;
+        match table.next() {
+            Some(row) => row.get::<i64>(1),
+            None => Ok(None),
+        }
+    })
+    .map_err(|e| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Bump the version of the region named by the `region_id` key of an
+/// artifact's metadata, if any.
+fn metadata_bump_region_version(metadata: Option<&serde_json::Value>, tenant_id: TenantId) {
+    let region_id = metadata
+        .and_then(|m| m.get("region_id"))
+        .and_then(|v| v.as_str())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    if let Some(region_id) = region_id {
+        if let Err(e) = region_bump_version(region_id, tenant_id) {
+            pgrx::warning!("CALIBER: Failed to bump region version: {}", e);
+        }
+    }
+}
+
+/// Bump the version of the region an artifact belongs to, if any.
+///
+/// Artifacts are placed in a region via a `region_id` key in their metadata.
+fn artifact_bump_region_version(artifact_id: ArtifactId, tenant_id: TenantId) {
+    match artifact_heap::artifact_get_heap(artifact_id, tenant_id) {
+        Ok(Some(row)) => metadata_bump_region_version(row.artifact.metadata.as_ref(), tenant_id),
+        Ok(None) => {}
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to load artifact for region version: {}", e);
+        }
+    }
+}
+
+/// Get the current version of a memory region.
+///
+/// The version increases on every artifact write within a version-tracked
+/// region, so agents can compare it before and after their own work to
+/// detect concurrent modifications.
+#[pg_extern]
+fn caliber_region_version(region_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<i64> {
+    let result: Result<Option<i64>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let mut table = client.select(
+            "SELECT version FROM caliber_region WHERE region_id = $1 AND tenant_id = $2",
+            None,
+            &[pgrx_uuid_datum(region_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        match table.next() {
+            Some(row) => row.get::<i64>(1),
+            None => Ok(None),
+        }
+    });
+
+    match result {
+        Ok(version) => version,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get region version: {}", e);
+            None
+        }
+    }
+}
+
+/// Delete a memory region.
+///
+/// Refuses while active locks are held on the region unless `force` is set,
+/// in which case those locks are released first. Returns false if the region
+/// does not exist.
+#[pg_extern]
+fn caliber_region_delete(region_id: pgrx::Uuid, force: bool, tenant_id: pgrx::Uuid) -> bool {
+    let rid = Uuid::from_bytes(*region_id.as_bytes());
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    if caliber_region_get(region_id, tenant_id).is_none() {
+        return false;
+    }
+
+    let locks = match lock_heap::lock_list_by_resource_heap("region", rid, tenant_uuid) {
+        Ok(locks) => locks,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list region locks: {}", e);
+            return false;
+        }
+    };
+
+    let now = Utc::now();
+    let active_locks: Vec<LockId> = locks
+        .iter()
+        .filter(|row| row.lock.expires_at > now)
+        .map(|row| row.lock.lock_id)
+        .collect();
+
+    if !active_locks.is_empty() && !force {
+        pgrx::warning!(
+            "CALIBER: Region {} has {} active lock(s); pass force to release them",
+            rid,
+            active_locks.len()
+        );
+        return false;
+    }
+
+    for lock_id in active_locks {
+        if !caliber_lock_release(pgrx_uuid_from_id(lock_id), tenant_id) {
+            pgrx::warning!(
+                "CALIBER: Failed to release lock {} on region {}",
+                lock_id,
+                rid
+            );
+            return false;
+        }
+    }
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let table = client.update(
+            "DELETE FROM caliber_region WHERE region_id = $1 AND tenant_id = $2",
+            None,
+            &[pgrx_uuid_datum(region_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to delete region: {}", e);
+            false
+        }
+    }
+}
+
+/// Add a reader to a memory region.
+#[pg_extern]
+fn caliber_region_add_reader(
+    region_id: pgrx::Uuid,
+    agent_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
+    let pg_rid = region_id;
+    let pg_aid = agent_id;
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
+        }
+    };
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let table = client.update(
+            "UPDATE caliber_region
+             SET readers = array_append(readers, $1), updated_at = $2
+             WHERE region_id = $3 AND tenant_id = $4 AND NOT ($1 = ANY(readers))",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to add reader: {}", e);
+            false
+        }
+    }
+}
+
+/// Add a writer to a memory region.
+#[pg_extern]
+fn caliber_region_add_writer(
+    region_id: pgrx::Uuid,
+    agent_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
+    let pg_rid = region_id;
+    let pg_aid = agent_id;
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
+        }
+    };
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let table = client.update(
+            "UPDATE caliber_region
+             SET writers = array_append(writers, $1), updated_at = $2
+             WHERE region_id = $3 AND tenant_id = $4 AND NOT ($1 = ANY(writers))",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to add writer: {}", e);
+            false
+        }
+    }
+}
+
+/// Remove a reader from a memory region.
+#[pg_extern]
+fn caliber_region_remove_reader(
+    region_id: pgrx::Uuid,
+    agent_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
+    let pg_rid = region_id;
+    let pg_aid = agent_id;
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
+        }
+    };
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let table = client.update(
+            "UPDATE caliber_region
+             SET readers = array_remove(readers, $1), updated_at = $2
+             WHERE region_id = $3 AND tenant_id = $4",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to remove reader: {}", e);
+            false
+        }
+    }
+}
+
+/// Remove a writer from a memory region.
+#[pg_extern]
+fn caliber_region_remove_writer(
+    region_id: pgrx::Uuid,
+    agent_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    use pgrx::datum::DatumWithOid;
+    use tuple_extract::chrono_to_timestamp;
+
+    let pg_rid = region_id;
+    let pg_aid = agent_id;
+    let now = match chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return false;
+        }
+    };
+
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let params: &[DatumWithOid<'_>] = &[
+            unsafe { DatumWithOid::new(pg_aid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_rid, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+        let table = client.update(
+            "UPDATE caliber_region
+             SET writers = array_remove(writers, $1), updated_at = $2
+             WHERE region_id = $3 AND tenant_id = $4",
+            None,
+            params,
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(len) => len > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to remove writer: {}", e);
+            false
+        }
+    }
+}
+
+// ============================================================================
+// EDGE OPERATIONS (Battle Intel Feature 1)
+// ============================================================================
+
+/// Valid edge_type values, as accepted by the caliber_edge CHECK constraint.
+const VALID_EDGE_TYPES: &str =
+    "supports, contradicts, supersedes, derivedfrom, relatesto, temporal, causal, synthesizedfrom, grouped, compared";
+
+/// Parse an edge type from its storage string.
+fn edge_type_from_str(edge_type: &str) -> Option<EdgeType> {
+    match edge_type {
+        "supports" => Some(EdgeType::Supports),
+        "contradicts" => Some(EdgeType::Contradicts),
+        "supersedes" => Some(EdgeType::Supersedes),
+        "derivedfrom" => Some(EdgeType::DerivedFrom),
+        "relatesto" => Some(EdgeType::RelatesTo),
+        "temporal" => Some(EdgeType::Temporal),
+        "causal" => Some(EdgeType::Causal),
+        "synthesizedfrom" => Some(EdgeType::SynthesizedFrom),
+        "grouped" => Some(EdgeType::Grouped),
+        "compared" => Some(EdgeType::Compared),
+        _ => None,
+    }
+}
+
+/// Create a new edge (graph relationship).
+///
+/// Edges can be binary (2 participants) or hyperedges (N participants).
+/// Inspired by Mem0's graph-based memory for +2% retrieval improvement.
+///
+/// # Arguments
+/// * `edge_type` - Type of relationship: supports, contradicts, supersedes,
+///   derivedfrom, relatesto, temporal, causal, synthesizedfrom, grouped, compared
+/// * `participants` - JSON array of participants with entity_type, id, and role
+/// * `weight` - Optional relationship strength 0.0-1.0
+/// * `trajectory_id` - Optional trajectory context
+/// * `source_turn` - Turn where this edge was extracted
+/// * `extraction_method` - How edge was created: explicit, inferred, userprovided
+/// * `confidence` - Optional confidence score 0.0-1.0
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_edge_create(
+    edge_type: &str,
+    participants: pgrx::JsonB,
+    weight: Option<f32>,
+    trajectory_id: Option<pgrx::Uuid>,
+    source_turn: i32,
+    extraction_method: &str,
+    confidence: Option<f32>,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    // Record operation for metrics
+    storage_write().record_op("edge_create");
+
+    let edge_id = EdgeId::now_v7();
+
+    // Validate edge_type - reject unknown values (REQ-12)
+    let edge_type_enum = match edge_type_from_str(edge_type) {
+        Some(t) => t,
+        None => {
+            pgrx::warning!(
+                "CALIBER: Unknown edge_type '{}'. Valid values: {}",
+                edge_type,
+                VALID_EDGE_TYPES
+            );
+            return None;
+        }
+    };
+
+    // Validate extraction_method
+    let extraction_method_enum = match extraction_method {
+        "explicit" => ExtractionMethod::Explicit,
+        "inferred" => ExtractionMethod::Inferred,
+        "userprovided" => ExtractionMethod::UserProvided,
+        _ => {
+            pgrx::warning!("CALIBER: Unknown extraction_method '{}'. Valid values: explicit, inferred, userprovided", extraction_method);
+            return None;
+        }
+    };
+
+    // Parse participants from JSON
+    let participants_vec: Vec<caliber_core::EdgeParticipant> =
+        match serde_json::from_value(participants.0) {
+            Ok(p) => p,
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to parse participants JSON: {}", e);
+                return None;
+            }
+        };
+
+    // Validate at least 2 participants
+    if participants_vec.len() < 2 {
+        pgrx::warning!("CALIBER: Edge must have at least 2 participants");
+        return None;
+    }
+
+    // Build Edge struct
+    let edge = caliber_core::Edge {
+        edge_id,
+        edge_type: edge_type_enum,
+        participants: participants_vec,
+        weight,
+        trajectory_id: trajectory_id.map(id_from_pgrx::<TrajectoryId>),
+        provenance: Provenance {
+            source_turn,
+            extraction_method: extraction_method_enum,
+            confidence,
+        },
+        created_at: Utc::now(),
+        metadata: None,
+    };
+
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Insert via direct heap operations (NO SQL)
+    match edge_heap::edge_create_heap(&edge, tenant_uuid) {
+        Ok(_) => {
+            audit_record(EntityType::Edge, edge_id, "create", None, tenant_uuid);
+            Some(pgrx_uuid_from_id(edge_id))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert edge: {}", e);
+            None
+        }
+    }
+}
+
+/// Build edge JSON from a heap row.
+fn edge_row_json(row: edge_heap::EdgeRow) -> serde_json::Value {
+    let edge = row.edge;
+    serde_json::json!({
+        "edge_id": edge.edge_id.to_string(),
+        "edge_type": match edge.edge_type {
+            EdgeType::Supports => "supports",
+            EdgeType::Contradicts => "contradicts",
+            EdgeType::Supersedes => "supersedes",
+            EdgeType::DerivedFrom => "derivedfrom",
+            EdgeType::RelatesTo => "relatesto",
+            EdgeType::Temporal => "temporal",
+            EdgeType::Causal => "causal",
+            EdgeType::SynthesizedFrom => "synthesizedfrom",
+            EdgeType::Grouped => "grouped",
+            EdgeType::Compared => "compared",
+        },
+        "participants": edge.participants,
+        "weight": edge.weight,
+        "trajectory_id": edge.trajectory_id.map(|id| id.to_string()),
+        "provenance": {
+            "source_turn": edge.provenance.source_turn,
+            "extraction_method": match edge.provenance.extraction_method {
+                ExtractionMethod::Explicit => "explicit",
+                ExtractionMethod::Inferred => "inferred",
+                ExtractionMethod::UserProvided => "userprovided",
+                ExtractionMethod::LlmExtraction => "llm_extraction",
+                ExtractionMethod::ToolExtraction => "tool_extraction",
+                ExtractionMethod::MemoryRecall => "memory_recall",
+                ExtractionMethod::ExternalApi => "external_api",
+                ExtractionMethod::Unknown => "unknown",
+            },
+            "confidence": edge.provenance.confidence,
+        },
+        "created_at": edge.created_at.to_rfc3339(),
+        "metadata": edge.metadata,
+        "tenant_id": row.tenant_id.map(|id| id.to_string()),
+    })
+}
+
+// Get an edge by ID.
+caliber_pg_get!(edge, edge_heap, EdgeId, |row| edge_row_json(row));
+
+/// List the edges scoped to a trajectory, oldest first.
+/// Uses the edge trajectory index, so exporting one task's knowledge graph
+/// does not scan every edge of the tenant.
+#[pg_extern]
+fn caliber_edges_by_trajectory(trajectory_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Use direct heap operations instead of SPI
+    match edge_heap::edge_query_by_trajectory_heap(traj_id, tenant_uuid) {
+        Ok(mut edges) => {
+            edges.sort_by_key(|row| row.edge.created_at);
+            let json_edges: Vec<serde_json::Value> = edges.into_iter().map(edge_row_json).collect();
+            pgrx::JsonB(serde_json::json!(json_edges))
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list edges by trajectory: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Check whether an edge of `edge_type` exists whose participant set is
+/// exactly `participant_ids` (a JSON array of UUID strings, order-insensitive).
+///
+/// Uses the participants GIN index for containment and compares the
+/// participant count so supersets don't match.
+#[pg_extern]
+fn caliber_edge_exists(
+    edge_type: &str,
+    participant_ids: pgrx::JsonB,
+    tenant_id: pgrx::Uuid,
+) -> bool {
+    // Validate edge_type - reject unknown values (REQ-12)
+    if edge_type_from_str(edge_type).is_none() {
+        pgrx::warning!(
+            "CALIBER: Unknown edge_type '{}'. Valid values: {}",
+            edge_type,
+            VALID_EDGE_TYPES
+        );
+        return false;
+    }
+
+    let mut ids: Vec<Uuid> = match serde_json::from_value(participant_ids.0) {
+        Ok(ids) => ids,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to parse participant ids: {}", e);
+            return false;
+        }
+    };
+    ids.sort();
+    ids.dedup();
+    if ids.is_empty() {
+        return false;
+    }
+
+    let containment: Vec<serde_json::Value> = ids
+        .iter()
+        .map(|id| serde_json::json!({"entity_ref": {"id": id.to_string()}}))
+        .collect();
+    let containment = serde_json::Value::Array(containment);
+    let count = ids.len() as i32;
+
+    let result: Result<bool, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT 1 FROM caliber_edge
+             WHERE edge_type = $1
+               AND participants @> $2::jsonb
+               AND jsonb_array_length(participants) = $3
+               AND tenant_id = $4
+             LIMIT 1",
+            None,
+            &[
+                text_datum(edge_type),
+                jsonb_datum(&containment),
+                int4_datum(count),
+                pgrx_uuid_datum(tenant_id),
+            ],
+        )?;
+        Ok(!table.is_empty())
+    });
+
+    match result {
+        Ok(exists) => exists,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to check edge existence: {}", e);
+            false
+        }
+    }
+}
+
+/// List edges by participant with tenant isolation.
+#[pg_extern]
+fn caliber_edges_by_participant_and_tenant(
+    entity_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let participant_id = Uuid::from_bytes(*entity_id.as_bytes());
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT edge_id, edge_type, participants, weight, trajectory_id, source_turn, extraction_method, confidence, created_at, metadata, tenant_id
+             FROM caliber_edge
+             WHERE tenant_id = $1",
+            None,
+            &[pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        let mut edges = Vec::new();
+        for row in table {
+            let edge_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let edge_type: Option<String> = row.get(2).ok().flatten();
+            let participants: Option<pgrx::JsonB> = row.get(3).ok().flatten();
+            let weight: Option<f32> = row.get(4).ok().flatten();
+            let trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
+            let source_turn: Option<i32> = row.get(6).ok().flatten();
+            let extraction_method: Option<String> = row.get(7).ok().flatten();
+            let confidence: Option<f32> = row.get(8).ok().flatten();
+            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+            let metadata: Option<pgrx::JsonB> = row.get(10).ok().flatten();
+            let tenant_id_val: Option<pgrx::Uuid> = row.get(11).ok().flatten();
+
+            let participants_json = participants
+                .as_ref()
+                .map(|j| j.0.clone())
+                .unwrap_or(serde_json::Value::Null);
+            let participants_vec: Vec<EdgeParticipant> =
+                serde_json::from_value(participants_json.clone()).unwrap_or_default();
+
+            if !participants_vec
+                .iter()
+                .any(|p| p.entity_ref.id == participant_id)
+            {
+                continue;
+            }
+
+            let json = serde_json::json!({
+                "edge_id": edge_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "edge_type": edge_type,
+                "participants": participants_json,
+                "weight": weight,
+                "trajectory_id": trajectory_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "source_turn": source_turn,
+                "extraction_method": extraction_method,
+                "confidence": confidence,
+                "created_at": created_at.map(|t| t.to_string()),
+                "metadata": metadata.map(|m| m.0),
+                "tenant_id": tenant_id_val.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+            });
+            edges.push(json);
+        }
+
+        Ok(edges)
+    });
+
+    match result {
+        Ok(edges) => pgrx::JsonB(serde_json::json!(edges)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list edges by participant: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// List edges by participant entity.
+/// Returns all edges that include the given entity as a participant.
+///
+/// NOTE: This uses a sequential scan with JSONB containment check.
+/// For high-frequency queries, consider adding a GIN index on participants.
+/// This is NOT hot path - edge queries are analytical, not per-turn.
+#[pg_extern]
+fn caliber_edges_by_participant(entity_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let id = Uuid::from_bytes(*entity_id.as_bytes());
+
+    // Use SPI for JSONB containment query - this is analytical, not hot path
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let search_json = serde_json::json!([{"entity_ref": {"id": id.to_string()}}]);
+
+        let table = client.select(
+            "SELECT edge_id, edge_type, participants, weight, trajectory_id,
+                    source_turn, extraction_method, confidence, created_at, metadata
+             FROM caliber_edge
+            WHERE participants @> $1::jsonb AND tenant_id = $2",
+            None,
+            &[jsonb_datum(&search_json), pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        let mut edges = Vec::new();
+        for row in table {
+            let edge_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let edge_type: Option<String> = row.get(2).ok().flatten();
+            let participants: Option<pgrx::JsonB> = row.get(3).ok().flatten();
+            let weight: Option<f32> = row.get(4).ok().flatten();
+            let trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
+            let source_turn: Option<i32> = row.get(6).ok().flatten();
+            let extraction_method: Option<String> = row.get(7).ok().flatten();
+            let confidence: Option<f32> = row.get(8).ok().flatten();
+            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+            let metadata: Option<pgrx::JsonB> = row.get(10).ok().flatten();
+
+            let edge_json = serde_json::json!({
+                "edge_id": edge_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "edge_type": edge_type,
+                "participants": participants.map(|j| j.0),
+                "weight": weight,
+                "trajectory_id": trajectory_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "provenance": {
+                    "source_turn": source_turn,
+                    "extraction_method": extraction_method,
+                    "confidence": confidence,
+                },
+                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
+                "metadata": metadata.map(|j| j.0),
+            });
+            edges.push(edge_json);
+        }
+        Ok(edges)
+    });
+
+    match result {
+        Ok(edges) => pgrx::JsonB(serde_json::json!(edges)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list edges by participant: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Aggregate edge weights between an entity and each of its neighbors.
+///
+/// Returns `[{neighbor_id, edge_count, total_weight, max_weight}]` over all
+/// edges touching the entity, ordered by total_weight descending. Edges
+/// without a weight count as 0.0. This is a lightweight relevance signal for
+/// graph-boosted retrieval that avoids a full traversal.
+#[pg_extern]
+fn caliber_edge_neighbor_scores(entity_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let id = Uuid::from_bytes(*entity_id.as_bytes());
+    let search_json = serde_json::json!([{"entity_ref": {"id": id.to_string()}}]);
+
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        // One row per (edge, neighbor) so repeated participants don't double count
+        let table = client.select(
+            "SELECT neighbor_id, COUNT(*)::int8 as edge_count,
+                    SUM(weight)::float8 as total_weight, MAX(weight)::float8 as max_weight
+             FROM (
+                 SELECT DISTINCT e.edge_id, p->'entity_ref'->>'id' as neighbor_id,
+                        COALESCE(e.weight, 0.0) as weight
+                 FROM caliber_edge e, jsonb_array_elements(e.participants) p
+                 WHERE e.participants @> $1::jsonb AND e.tenant_id = $2
+                   AND p->'entity_ref'->>'id' <> $3
+             ) pairs
+             GROUP BY neighbor_id
+             ORDER BY total_weight DESC, edge_count DESC",
+            None,
+            &[
+                jsonb_datum(&search_json),
+                pgrx_uuid_datum(tenant_id),
+                text_datum(&id.to_string()),
+            ],
+        )?;
+
+        let mut scores = Vec::new();
+        for row in table {
+            let neighbor_id: Option<String> = row.get(1).ok().flatten();
+            let edge_count: Option<i64> = row.get(2).ok().flatten();
+            let total_weight: Option<f64> = row.get(3).ok().flatten();
+            let max_weight: Option<f64> = row.get(4).ok().flatten();
+
+            if let Some(neighbor_id) = neighbor_id {
+                scores.push(serde_json::json!({
+                    "neighbor_id": neighbor_id,
+                    "edge_count": edge_count.unwrap_or(0),
+                    "total_weight": total_weight.unwrap_or(0.0),
+                    "max_weight": max_weight.unwrap_or(0.0),
+                }));
+            }
+        }
+        Ok(scores)
+    });
+
+    match result {
+        Ok(scores) => pgrx::JsonB(serde_json::json!(scores)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to score edge neighbors: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+// ============================================================================
+// SUMMARIZATION POLICY OPERATIONS (Battle Intel Feature 4)
+// ============================================================================
+
+/// Create a summarization policy.
+///
+/// Policies define when and how L0→L1→L2 abstraction transitions occur.
+/// Inspired by EVOLVE-MEM's self-improvement engine.
+///
+/// # Arguments
+/// * `name` - Policy name
+/// * `triggers` - JSON array of trigger definitions
+/// * `source_level` - Source abstraction level: raw, summary, principle
+/// * `target_level` - Target abstraction level: raw, summary, principle
+/// * `max_sources` - Maximum items to summarize at once
+/// * `create_edges` - Whether to auto-create SynthesizedFrom edges
+/// * `trajectory_id` - Optional trajectory this policy applies to
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+fn caliber_summarization_policy_create(
+    name: &str,
+    triggers: pgrx::JsonB,
+    source_level: &str,
+    target_level: &str,
+    max_sources: i32,
+    create_edges: bool,
+    trajectory_id: Option<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    // Record operation for metrics
+    storage_write().record_op("summarization_policy_create");
+
+    let policy_id = SummarizationPolicyId::now_v7();
+
+    // Validate abstraction levels
+    let source_level_enum = match source_level {
+        "raw" => AbstractionLevel::Raw,
+        "summary" => AbstractionLevel::Summary,
+        "principle" => AbstractionLevel::Principle,
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Unknown source_level '{}'. Valid values: raw, summary, principle",
+                source_level
+            );
+            return None;
+        }
+    };
+
+    let target_level_enum = match target_level {
+        "raw" => AbstractionLevel::Raw,
+        "summary" => AbstractionLevel::Summary,
+        "principle" => AbstractionLevel::Principle,
+        _ => {
+            pgrx::warning!(
+                "CALIBER: Unknown target_level '{}'. Valid values: raw, summary, principle",
+                target_level
+            );
+            return None;
+        }
+    };
+
+    // Validate level transition (must go upward)
+    let valid_transition = matches!(
+        (source_level_enum, target_level_enum),
+        (AbstractionLevel::Raw, AbstractionLevel::Summary)
+            | (AbstractionLevel::Raw, AbstractionLevel::Principle)
+            | (AbstractionLevel::Summary, AbstractionLevel::Principle)
+    );
+
+    if !valid_transition {
+        pgrx::warning!(
+            "CALIBER: Invalid abstraction level transition. Must go from lower to higher level."
+        );
+        return None;
+    }
+
+    // Parse triggers from JSON
+    let triggers_vec: Vec<SummarizationTrigger> = match serde_json::from_value(triggers.0) {
+        Ok(t) => t,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to parse triggers JSON: {}", e);
+            return None;
+        }
+    };
+
+    if triggers_vec.is_empty() {
+        pgrx::warning!("CALIBER: At least one trigger is required");
+        return None;
+    }
+
+    if max_sources <= 0 {
+        pgrx::warning!("CALIBER: max_sources must be positive");
+        return None;
+    }
+
+    // Insert via SPI (no heap operations for policies yet)
+    let traj_id = trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+    let triggers_json = serde_json::to_value(&triggers_vec).unwrap_or(serde_json::Value::Null);
+
+    let now: TimestampWithTimeZone = match tuple_extract::chrono_to_timestamp(Utc::now()) {
+        Ok(ts) => ts,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to convert timestamp: {}", e);
+            return None;
+        }
+    };
+
+    let result: Result<(), pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        use pgrx::datum::DatumWithOid;
+        let pg_id = pgrx_uuid_from_id(policy_id);
+        let pg_traj_id = traj_id.map(pgrx_uuid_from_id);
+        let pg_tenant_id = tenant_id;
+
+        // Build params with proper OIDs
+        let params: Vec<DatumWithOid<'_>> = vec![
+            unsafe { DatumWithOid::new(pg_id, pgrx::pg_sys::UUIDOID) },
+            unsafe { DatumWithOid::new(name, pgrx::pg_sys::TEXTOID) },
+            unsafe {
+                DatumWithOid::new(pgrx::JsonB(triggers_json.clone()), pgrx::pg_sys::JSONBOID)
+            },
+            unsafe { DatumWithOid::new(source_level, pgrx::pg_sys::TEXTOID) },
+            unsafe { DatumWithOid::new(target_level, pgrx::pg_sys::TEXTOID) },
+            unsafe { DatumWithOid::new(max_sources, pgrx::pg_sys::INT4OID) },
+            unsafe { DatumWithOid::new(create_edges, pgrx::pg_sys::BOOLOID) },
+            match pg_traj_id {
+                Some(id) => unsafe { DatumWithOid::new(id, pgrx::pg_sys::UUIDOID) },
+                None => DatumWithOid::null_oid(pgrx::pg_sys::UUIDOID),
+            },
+            unsafe { DatumWithOid::new(now, pgrx::pg_sys::TIMESTAMPTZOID) },
+            unsafe { DatumWithOid::new(pg_tenant_id, pgrx::pg_sys::UUIDOID) },
+        ];
+
+        client.update(
+            "INSERT INTO caliber_summarization_policy
+             (policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            None,
+            &params,
+        )?;
+
+        Ok(())
+    });
+
+    match result {
+        Ok(()) => Some(pgrx_uuid_from_id(policy_id)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert summarization policy: {}", e);
+            None
+        }
+    }
+}
+
+/// Get a summarization policy by ID.
+/// NOTE: Policy reads are config/admin operations, not hot path.
+#[pg_extern]
+fn caliber_summarization_policy_get(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
+    let result: Result<Option<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let mut table = client.select(
+            "SELECT policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id
+             FROM caliber_summarization_policy
+             WHERE policy_id = $1 AND tenant_id = $2",
+            None,
+            &[pgrx_uuid_datum(id), pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        // Iterate and take first row
+        if let Some(row) = table.next() {
+            let policy_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let name: Option<String> = row.get(2).ok().flatten();
+            let triggers: Option<pgrx::JsonB> = row.get(3).ok().flatten();
+            let source_level: Option<String> = row.get(4).ok().flatten();
+            let target_level: Option<String> = row.get(5).ok().flatten();
+            let max_sources: Option<i32> = row.get(6).ok().flatten();
+            let create_edges: Option<bool> = row.get(7).ok().flatten();
+            let trajectory_id: Option<pgrx::Uuid> = row.get(8).ok().flatten();
+            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+            let tenant_id: Option<pgrx::Uuid> = row.get(10).ok().flatten();
+
+            let json = serde_json::json!({
+                "policy_id": policy_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "name": name,
+                "triggers": triggers.map(|j| j.0),
+                "source_level": source_level,
+                "target_level": target_level,
+                "max_sources": max_sources,
+                "create_edges": create_edges,
+                "trajectory_id": trajectory_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
+                "tenant_id": tenant_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+            });
+            return Ok(Some(json));
+        }
+        Ok(None)
+    });
+
+    match result {
+        Ok(Some(json)) => Some(pgrx::JsonB(json)),
+        Ok(None) => None,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get summarization policy: {}", e);
+            None
+        }
+    }
+}
+
+/// List summarization policies for a trajectory.
+/// NOTE: Policy listing is config/admin operation, not hot path.
+#[pg_extern]
+fn caliber_summarization_policies_by_trajectory(
+    trajectory_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT policy_id, name, triggers, source_level, target_level, max_sources, create_edges, trajectory_id, created_at, tenant_id
+             FROM caliber_summarization_policy
+             WHERE trajectory_id = $1 AND tenant_id = $2
+             ORDER BY created_at DESC",
+            None,
+            &[pgrx_uuid_datum(trajectory_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        let mut policies = Vec::new();
+        for row in table {
+            let policy_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let name: Option<String> = row.get(2).ok().flatten();
+            let triggers: Option<pgrx::JsonB> = row.get(3).ok().flatten();
+            let source_level: Option<String> = row.get(4).ok().flatten();
+            let target_level: Option<String> = row.get(5).ok().flatten();
+            let max_sources: Option<i32> = row.get(6).ok().flatten();
+            let create_edges: Option<bool> = row.get(7).ok().flatten();
+            let traj_id: Option<pgrx::Uuid> = row.get(8).ok().flatten();
+            let created_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+            let tenant_id: Option<pgrx::Uuid> = row.get(10).ok().flatten();
+
+            let json = serde_json::json!({
+                "policy_id": policy_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "name": name,
+                "triggers": triggers.map(|j| j.0),
+                "source_level": source_level,
+                "target_level": target_level,
+                "max_sources": max_sources,
+                "create_edges": create_edges,
+                "trajectory_id": traj_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "created_at": created_at.map(|t: TimestampWithTimeZone| t.to_string()),
+                "tenant_id": tenant_id.map(|u: pgrx::Uuid| Uuid::from_bytes(*u.as_bytes()).to_string()),
+            });
+            policies.push(json);
+        }
+        Ok(policies)
+    });
+
+    match result {
+        Ok(policies) => pgrx::JsonB(serde_json::json!(policies)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to list summarization policies: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Delete a summarization policy.
+/// NOTE: Policy deletion is config/admin operation, not hot path.
+#[pg_extern]
+fn caliber_summarization_policy_delete(id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let result: Result<usize, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let table = client.update(
+            "DELETE FROM caliber_summarization_policy WHERE policy_id = $1 AND tenant_id = $2",
+            None,
+            &[pgrx_uuid_datum(id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        Ok(table.len())
+    });
+
+    match result {
+        Ok(count) => count > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to delete summarization policy: {}", e);
+            false
+        }
+    }
+}
+
+/// Trajectory counters that summarization triggers are evaluated against.
+struct SummarizationStats {
+    /// Highest token usage percentage across the trajectory's active scopes.
+    max_dosage_percent: f64,
+    turn_count: i64,
+    artifact_count: i64,
+    closed_scope_count: i64,
+}
+
+/// Check whether a single trigger fires for the given trajectory stats.
+/// ScopeClose only fires for scopes closed since the policy's last
+/// evaluation. Manual triggers never fire during evaluation.
+fn summarization_trigger_fired(
+    trigger: &SummarizationTrigger,
+    stats: &SummarizationStats,
+    closed_scopes_seen: i64,
+) -> bool {
+    match trigger {
+        SummarizationTrigger::DosageThreshold { percent } => {
+            stats.max_dosage_percent >= f64::from(*percent)
+        }
+        SummarizationTrigger::ScopeClose => stats.closed_scope_count > closed_scopes_seen,
+        SummarizationTrigger::TurnCount { count } => stats.turn_count >= i64::from(*count),
+        SummarizationTrigger::ArtifactCount { count } => stats.artifact_count >= i64::from(*count),
+        SummarizationTrigger::Manual => false,
+    }
+}
+
+/// Evaluate a trajectory's summarization policies.
+///
+/// Checks each policy's triggers against the trajectory's scope token usage,
+/// turn count, artifact count and closed scopes. Returns the policies that are
+/// due, with the triggers that fired and up to `max_sources` candidate notes
+/// at the policy's source level (plus artifacts when summarizing raw content).
+///
+/// Evaluation consumes scope close events: each policy records the number of
+/// closed scopes it has seen, so ScopeClose fires once per newly closed scope.
+#[pg_extern]
+fn caliber_summarization_evaluate(trajectory_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect_mut(|client| {
+        let stats_row = client
+            .select(
+                "SELECT
+                     (SELECT COALESCE(MAX(tokens_used::float8 * 100 / NULLIF(token_budget, 0)), 0)
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2 AND is_active),
+                     (SELECT COUNT(*)
+                        FROM caliber_turn t
+                        JOIN caliber_scope s ON s.scope_id = t.scope_id
+                        WHERE s.trajectory_id = $1 AND s.tenant_id = $2),
+                     (SELECT COUNT(*)
+                        FROM caliber_artifact
+                        WHERE trajectory_id = $1 AND tenant_id = $2 AND superseded_by IS NULL
+                          AND deleted_at IS NULL),
+                     (SELECT COUNT(*)
+                        FROM caliber_scope
+                        WHERE trajectory_id = $1 AND tenant_id = $2 AND NOT is_active)",
+                None,
+                &[pgrx_uuid_datum(trajectory_id), pgrx_uuid_datum(tenant_id)],
+            )?
+            .first();
+
+        let stats = SummarizationStats {
+            max_dosage_percent: stats_row.get::<f64>(1)?.unwrap_or(0.0),
+            turn_count: stats_row.get::<i64>(2)?.unwrap_or(0),
+            artifact_count: stats_row.get::<i64>(3)?.unwrap_or(0),
+            closed_scope_count: stats_row.get::<i64>(4)?.unwrap_or(0),
+        };
+
+        let policies = client.select(
+            "SELECT policy_id, name, triggers, source_level, target_level, max_sources,
+                    closed_scopes_seen
+             FROM caliber_summarization_policy
+             WHERE trajectory_id = $1 AND tenant_id = $2
+             ORDER BY created_at",
+            None,
+            &[pgrx_uuid_datum(trajectory_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+
+        let mut due = Vec::new();
+        for row in policies {
+            let policy_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+            let name: Option<String> = row.get(2).ok().flatten();
+            let triggers: Option<pgrx::JsonB> = row.get(3).ok().flatten();
+            let source_level: Option<String> = row.get(4).ok().flatten();
+            let target_level: Option<String> = row.get(5).ok().flatten();
+            let max_sources: i32 = row.get(6).ok().flatten().unwrap_or(10);
+            let closed_scopes_seen: i64 = row.get(7).ok().flatten().unwrap_or(0);
+
+            let (policy_id, source_level) = match (policy_id, source_level) {
+                (Some(id), Some(level)) => (id, level),
+                _ => continue,
+            };
+
+            let triggers: Vec<SummarizationTrigger> =
+                match triggers.map(|j| serde_json::from_value(j.0)).transpose() {
+                    Ok(t) => t.unwrap_or_default(),
+                    Err(e) => {
+                        pgrx::warning!(
+                            "CALIBER: Skipping summarization policy {} with invalid triggers: {}",
+                            Uuid::from_bytes(*policy_id.as_bytes()),
+                            e
+                        );
+                        continue;
+                    }
+                };
+
+            let fired: Vec<String> = triggers
+                .iter()
+                .filter(|t| summarization_trigger_fired(t, &stats, closed_scopes_seen))
+                .map(|t| t.to_string())
+                .collect();
+            if fired.is_empty() {
+                continue;
+            }
+
+            let candidate_notes: Vec<String> = client
+                .select(
+                    "SELECT note_id
+                     FROM caliber_note
+                     WHERE tenant_id = $1
+                       AND $2 = ANY(source_trajectory_ids)
+                       AND abstraction_level = $3
+                       AND superseded_by IS NULL
+                       AND deleted_at IS NULL
+                     ORDER BY created_at
+                     LIMIT $4",
+                    None,
+                    &[
+                        pgrx_uuid_datum(tenant_id),
+                        pgrx_uuid_datum(trajectory_id),
+                        text_datum(&source_level),
+                        int4_datum(max_sources),
+                    ],
+                )?
+                .filter_map(|r| r.get::<pgrx::Uuid>(1).ok().flatten())
+                .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+                .collect();
+
+            // Artifacts are raw material, so only raw-level policies consume them
+            let candidate_artifacts: Vec<String> = if source_level == "raw" {
+                client
+                    .select(
+                        "SELECT artifact_id
+                         FROM caliber_artifact
+                         WHERE tenant_id = $1
+                           AND trajectory_id = $2
+                           AND superseded_by IS NULL
+                           AND deleted_at IS NULL
+                         ORDER BY created_at
+                         LIMIT $3",
+                        None,
+                        &[
+                            pgrx_uuid_datum(tenant_id),
+                            pgrx_uuid_datum(trajectory_id),
+                            int4_datum(max_sources),
+                        ],
+                    )?
+                    .filter_map(|r| r.get::<pgrx::Uuid>(1).ok().flatten())
+                    .map(|u| Uuid::from_bytes(*u.as_bytes()).to_string())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            due.push(serde_json::json!({
+                "policy_id": Uuid::from_bytes(*policy_id.as_bytes()).to_string(),
+                "name": name,
+                "source_level": source_level,
+                "target_level": target_level,
+                "fired_triggers": fired,
+                "candidate_note_ids": candidate_notes,
+                "candidate_artifact_ids": candidate_artifacts,
+            }));
+        }
+
+        // Consume the scope close events this evaluation has seen
+        client.update(
+            "UPDATE caliber_summarization_policy
+             SET closed_scopes_seen = $3
+             WHERE trajectory_id = $1 AND tenant_id = $2 AND closed_scopes_seen <> $3",
+            None,
+            &[
+                pgrx_uuid_datum(trajectory_id),
+                pgrx_uuid_datum(tenant_id),
+                int8_datum(stats.closed_scope_count),
+            ],
+        )?;
+        Ok(due)
+    });
+
+    match result {
+        Ok(due) => pgrx::JsonB(serde_json::json!(due)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to evaluate summarization policies: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Apply a summarization policy by writing a summary note.
+///
+/// Creates a note at the policy's target level whose `source_note_ids` are the
+/// given sources, and links it to each source with a SynthesizedFrom edge when
+/// the policy has `create_edges` set. All sources must be notes at the
+/// policy's source level. Failing to create an edge raises an error, rolling
+/// back the summary note with it.
+#[pg_extern]
+fn caliber_summarization_apply(
+    policy_id: pgrx::Uuid,
+    source_ids: pgrx::JsonB,
+    summary_content: &str,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    // Load the policy (config read, not hot path)
+    let policy: Result<Option<(String, String, String, i32, bool)>, pgrx::spi::SpiError> =
+        Spi::connect(|client| {
+            let mut table = client.select(
+                "SELECT name, source_level, target_level, max_sources, create_edges
+                 FROM caliber_summarization_policy
+                 WHERE policy_id = $1 AND tenant_id = $2",
+                None,
+                &[pgrx_uuid_datum(policy_id), pgrx_uuid_datum(tenant_id)],
+            )?;
+            match table.next() {
+                Some(row) => Ok(Some((
+                    row.get::<String>(1)?.unwrap_or_default(),
+                    row.get::<String>(2)?.unwrap_or_default(),
+                    row.get::<String>(3)?.unwrap_or_default(),
+                    row.get::<i32>(4)?.unwrap_or(10),
+                    row.get::<bool>(5)?.unwrap_or(true),
+                ))),
+                None => Ok(None),
+            }
+        });
+
+    let (name, source_level, target_level, max_sources, create_edges) = match policy {
+        Ok(Some(p)) => p,
+        Ok(None) => {
+            pgrx::warning!(
+                "CALIBER: Summarization policy {} not found",
+                Uuid::from_bytes(*policy_id.as_bytes())
+            );
+            return None;
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to get summarization policy: {}", e);
+            return None;
+        }
+    };
+
+    let (source_level, target_level) = match (
+        source_level.parse::<AbstractionLevel>(),
+        target_level.parse::<AbstractionLevel>(),
+    ) {
+        (Ok(s), Ok(t)) => (s, t),
+        _ => {
+            pgrx::warning!("CALIBER: Summarization policy has invalid abstraction levels");
+            return None;
+        }
+    };
+
+    // Parse source note IDs
+    let source_note_ids: Vec<NoteId> = match serde_json::from_value::<Vec<Uuid>>(source_ids.0) {
+        Ok(ids) => ids.into_iter().map(NoteId::new).collect(),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to parse source_ids JSON: {}", e);
+            return None;
+        }
+    };
+
+    if source_note_ids.is_empty() {
+        pgrx::warning!("CALIBER: At least one source is required");
+        return None;
+    }
+    if source_note_ids.len() > max_sources.max(0) as usize {
+        pgrx::warning!(
+            "CALIBER: {} sources exceeds policy max_sources of {}",
+            source_note_ids.len(),
+            max_sources
+        );
+        return None;
+    }
+
+    // Validate sources exist at the policy's source level
+    let mut source_traj_ids: Vec<TrajectoryId> = Vec::new();
+    for source_id in &source_note_ids {
+        let source = match note_heap::note_get_heap(*source_id, tenant_uuid) {
+            Ok(Some(row)) => row.note,
+            Ok(None) => {
+                pgrx::warning!("CALIBER: Source note {} not found", source_id);
+                return None;
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get source note: {}", e);
+                return None;
+            }
+        };
+
+        if source.abstraction_level != source_level {
+            let validation_err = ValidationError::InvalidValue {
+                field: "source_ids".to_string(),
+                reason: format!(
+                    "note {} is at level '{}', policy expects '{}'",
+                    source_id, source.abstraction_level, source_level
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return None;
+        }
+
+        for traj_id in source.source_trajectory_ids {
+            if !source_traj_ids.contains(&traj_id) {
+                source_traj_ids.push(traj_id);
+            }
+        }
+    }
+
+    // Use direct heap operations instead of SPI
+    let note_id = NoteId::now_v7();
+    let result = note_heap::note_create_heap(note_heap::NoteCreateParams {
+        note_id,
+        note_type: NoteType::Summary,
+        title: &name,
+        content: summary_content,
+        content_hash: compute_content_hash(summary_content.as_bytes()),
+        embedding: None,
+        source_trajectory_ids: &source_traj_ids,
+        source_artifact_ids: &[],
+        ttl: TTL::Persistent,
+        abstraction_level: target_level,
+        source_note_ids: &source_note_ids,
+        tenant_id: tenant_uuid,
+    });
+
+    if let Err(e) = result {
+        pgrx::warning!("CALIBER: Failed to insert summary note: {}", e);
+        return None;
+    }
+
+    if create_edges {
+        for source_id in &source_note_ids {
+            let edge = Edge {
+                edge_id: EdgeId::now_v7(),
+                edge_type: EdgeType::SynthesizedFrom,
+                participants: vec![
+                    EdgeParticipant {
+                        entity_ref: EntityRef {
+                            entity_type: EntityType::Note,
+                            id: note_id.as_uuid(),
+                        },
+                        role: Some("summary".to_string()),
+                    },
+                    EdgeParticipant {
+                        entity_ref: EntityRef {
+                            entity_type: EntityType::Note,
+                            id: source_id.as_uuid(),
+                        },
+                        role: Some("source".to_string()),
+                    },
+                ],
+                weight: None,
+                trajectory_id: source_traj_ids.first().copied(),
+                provenance: Provenance {
+                    source_turn: 0,
+                    extraction_method: ExtractionMethod::Inferred,
+                    confidence: None,
+                },
+                created_at: Utc::now(),
+                metadata: None,
+            };
+
+            // Abort the transaction so the summary is never left without
+            // its provenance edges
+            if let Err(e) = edge_heap::edge_create_heap(&edge, tenant_uuid) {
+                pgrx::error!("CALIBER: Failed to insert SynthesizedFrom edge: {}", e);
+            }
+        }
+    }
+
+    Some(pgrx_uuid_from_id(note_id))
+}
+
+// ============================================================================
+// SCHEDULED POLICY OPERATIONS
+// ============================================================================
+
+/// Register a schedule-triggered policy.
+///
+/// `schedule` is the `Trigger::Schedule` expression (cron or duration), stored
+/// verbatim for the external scheduler. `actions` is a JSON array of the
+/// policy's actions. `next_run_ms` is the first due time in Unix milliseconds.
+#[pg_extern]
+fn caliber_scheduled_policy_create(
+    name: &str,
+    schedule: &str,
+    actions: pgrx::JsonB,
+    next_run_ms: i64,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::Uuid> {
+    // Validate inputs (REQ-12)
+    let validation_err = if schedule.trim().is_empty() {
+        Some(("schedule", "must not be empty"))
+    } else if !actions.0.is_array() {
+        Some(("actions", "must be a JSON array"))
+    } else if next_run_ms < 0 {
+        Some(("next_run_ms", "must be non-negative"))
+    } else {
+        None
+    };
+    if let Some((field, reason)) = validation_err {
+        let validation_err = ValidationError::InvalidValue {
+            field: field.to_string(),
+            reason: reason.to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return None;
+    }
+
+    let policy_id = caliber_new_id();
+    let result = Spi::connect_mut(|client| {
+        client.update(
+            "INSERT INTO caliber_scheduled_policy
+                 (policy_id, tenant_id, name, schedule, actions, next_run_at)
+             VALUES ($1, $2, $3, $4, $5, to_timestamp($6 / 1000.0))",
+            None,
+            &[
+                pgrx_uuid_datum(policy_id),
+                pgrx_uuid_datum(tenant_id),
+                text_datum(name),
+                text_datum(schedule),
+                jsonb_datum(&actions.0),
+                int8_datum(next_run_ms),
+            ],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(())
+    });
+
+    match result {
+        Ok(()) => Some(policy_id),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to insert scheduled policy: {}", e);
+            None
+        }
+    }
+}
+
+/// List scheduled policies whose next run time is at or before `now_ms`
+/// (Unix milliseconds), most overdue first. Times are returned in milliseconds.
+#[pg_extern]
+fn caliber_policies_due(now_ms: i64, tenant_id: pgrx::Uuid) -> pgrx::JsonB {
+    let result: Result<Vec<serde_json::Value>, pgrx::spi::SpiError> = Spi::connect(|client| {
+        let table = client.select(
+            "SELECT policy_id, name, schedule, actions,
+                    (EXTRACT(EPOCH FROM next_run_at) * 1000)::bigint,
+                    (EXTRACT(EPOCH FROM last_run_at) * 1000)::bigint
+             FROM caliber_scheduled_policy
+             WHERE tenant_id = $1 AND next_run_at <= to_timestamp($2 / 1000.0)
+             ORDER BY next_run_at, policy_id",
+            None,
+            &[pgrx_uuid_datum(tenant_id), int8_datum(now_ms)],
+        )?;
+
+        let mut policies = Vec::new();
+        for row in table {
+            let policy_id: Option<pgrx::Uuid> = row.get(1)?;
+            let name: Option<String> = row.get(2)?;
+            let schedule: Option<String> = row.get(3)?;
+            let actions: Option<pgrx::JsonB> = row.get(4)?;
+            let next_run_ms: Option<i64> = row.get(5)?;
+            let last_run_ms: Option<i64> = row.get(6)?;
+            policies.push(serde_json::json!({
+                "policy_id": policy_id.map(|u| Uuid::from_bytes(*u.as_bytes()).to_string()),
+                "name": name,
+                "schedule": schedule,
+                "actions": actions.map(|j| j.0),
+                "next_run_ms": next_run_ms,
+                "last_run_ms": last_run_ms,
+            }));
+        }
+        Ok(policies)
+    });
+
+    match result {
+        Ok(policies) => pgrx::JsonB(serde_json::json!(policies)),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to query due policies: {}", e);
+            pgrx::JsonB(serde_json::json!([]))
+        }
+    }
+}
+
+/// Record that a scheduled policy ran and set its next due time
+/// (Unix milliseconds). Returns false if the policy does not exist.
+#[pg_extern]
+fn caliber_policy_mark_run(policy_id: pgrx::Uuid, next_run_ms: i64, tenant_id: pgrx::Uuid) -> bool {
+    if next_run_ms < 0 {
+        let validation_err = ValidationError::InvalidValue {
+            field: "next_run_ms".to_string(),
+            reason: "must be non-negative".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return false;
+    }
+
+    let result = Spi::connect_mut(|client| {
+        let table = client.update(
+            "UPDATE caliber_scheduled_policy
+             SET last_run_at = NOW(),
+                 next_run_at = to_timestamp($1 / 1000.0),
+                 updated_at = NOW()
+             WHERE policy_id = $2 AND tenant_id = $3",
+            None,
+            &[
+                int8_datum(next_run_ms),
+                pgrx_uuid_datum(policy_id),
+                pgrx_uuid_datum(tenant_id),
+            ],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(updated) => updated > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to mark scheduled policy run: {}", e);
+            false
+        }
+    }
+}
+
+/// Remove a scheduled policy. Returns false if it does not exist.
+#[pg_extern]
+fn caliber_scheduled_policy_delete(policy_id: pgrx::Uuid, tenant_id: pgrx::Uuid) -> bool {
+    let result = Spi::connect_mut(|client| {
+        let table = client.update(
+            "DELETE FROM caliber_scheduled_policy WHERE policy_id = $1 AND tenant_id = $2",
+            None,
+            &[pgrx_uuid_datum(policy_id), pgrx_uuid_datum(tenant_id)],
+        )?;
+        Ok::<_, pgrx::spi::SpiError>(table.len())
+    });
+
+    match result {
+        Ok(deleted) => deleted > 0,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to delete scheduled policy: {}", e);
+            false
+        }
+    }
+}
+
+// ============================================================================
+// RETENTION AND PRUNE ENFORCEMENT
+// ============================================================================
+
+/// Table, id column and trajectory filter for a memory table subject to
+/// `Retention::Max`. `$1` is the trajectory id and `$2` the tenant id.
+/// Tombstoned artifacts and notes do not count towards the limit.
+/// The boolean marks tables with a self-referencing superseded_by column.
+fn retention_table_sql(
+    memory_table: &str,
+) -> Option<(&'static str, &'static str, &'static str, bool)> {
+    match memory_table {
+        "artifact" => Some((
+            "caliber_artifact",
+            "artifact_id",
+            "trajectory_id = $1 AND tenant_id = $2 AND deleted_at IS NULL",
+            true,
+        )),
+        "note" => Some((
+            "caliber_note",
+            "note_id",
+            "$1 = ANY(source_trajectory_ids) AND tenant_id = $2 AND deleted_at IS NULL",
+            true,
+        )),
+        "turn" => Some((
+            "caliber_turn",
+            "turn_id",
+            "scope_id IN (SELECT scope_id FROM caliber_scope
+                          WHERE trajectory_id = $1 AND tenant_id = $2)
+             AND tenant_id = $2",
+            false,
+        )),
+        _ => None,
+    }
+}
+
+/// Delete the given rows from a memory table, returning how many were removed.
+/// For tables with a self-referencing superseded_by column, references to the
+/// deleted rows are cleared first so the foreign key does not block the delete.
+fn delete_rows_spi(
+    table: &str,
+    id_column: &str,
+    has_superseded_by: bool,
+    ids: Vec<pgrx::Uuid>,
+    tenant_id: pgrx::Uuid,
+) -> Result<i64, pgrx::spi::SpiError> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+
+    Spi::connect_mut(|client| {
+        if has_superseded_by {
+            client.update(
+                &format!(
+                    "UPDATE {table} SET superseded_by = NULL
+                     WHERE superseded_by = ANY($1) AND tenant_id = $2"
+                ),
+                None,
+                &[
+                    unsafe { DatumWithOid::new(ids.clone(), pgrx::pg_sys::UUIDARRAYOID) },
+                    pgrx_uuid_datum(tenant_id),
+                ],
+            )?;
+        }
+
+        let deleted = client.update(
+            &format!("DELETE FROM {table} WHERE {id_column} = ANY($1) AND tenant_id = $2"),
+            None,
+            &[
+                unsafe { DatumWithOid::new(ids, pgrx::pg_sys::UUIDARRAYOID) },
+                pgrx_uuid_datum(tenant_id),
+            ],
+        )?;
+        Ok(deleted.len() as i64)
+    })
+}
+
+/// Enforce `Retention::Max` for a memory table within a trajectory.
+///
+/// Keeps the newest `max` entries (by created_at) and deletes the rest.
+/// `memory_table` is "artifact", "note" or "turn". References to pruned
+/// rows via superseded_by are cleared first. Returns the number of rows pruned.
+#[pg_extern]
+fn caliber_enforce_retention_max(
+    memory_table: &str,
+    trajectory_id: pgrx::Uuid,
+    max: i32,
+    tenant_id: pgrx::Uuid,
+) -> i64 {
+    // Validate memory_table - reject unknown values (REQ-12)
+    let (table, id_column, filter, has_superseded_by) = match retention_table_sql(memory_table) {
+        Some(sql) => sql,
+        None => {
+            let validation_err = ValidationError::InvalidValue {
+                field: "memory_table".to_string(),
+                reason: format!(
+                    "unknown value '{}'. Valid values: artifact, note, turn",
+                    memory_table
+                ),
+            };
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            return 0;
+        }
+    };
+    if max < 0 {
+        let validation_err = ValidationError::InvalidValue {
+            field: "max".to_string(),
+            reason: "must be non-negative".to_string(),
+        };
+        pgrx::warning!("CALIBER: {:?}", validation_err);
+        return 0;
+    }
+
+    // Collect the ids to prune once so every statement below agrees on them
+    let result = Spi::connect(|client| {
+        client
+            .select(
+                &format!(
+                    "SELECT {id_column} FROM {table}
+                     WHERE {filter}
+                     ORDER BY created_at DESC, {id_column} DESC
+                     OFFSET $3"
+                ),
+                None,
+                &[
+                    pgrx_uuid_datum(trajectory_id),
+                    pgrx_uuid_datum(tenant_id),
+                    int8_datum(i64::from(max)),
+                ],
+            )
+            .map(|rows| {
+                rows.filter_map(|row| row.get::<pgrx::Uuid>(1).ok().flatten())
+                    .collect::<Vec<_>>()
+            })
+    })
+    .and_then(|pruned| delete_rows_spi(table, id_column, has_superseded_by, pruned, tenant_id));
+
+    match result {
+        Ok(pruned) => pruned,
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to enforce retention on {}: {}", table, e);
+            0
+        }
+    }
+}
+
+/// SQL type of a column that prune criteria may reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterColumnType {
+    Uuid,
+    Text,
+    Int,
+    Timestamp,
+}
+
+impl FilterColumnType {
+    fn sql_type(self) -> &'static str {
+        match self {
+            FilterColumnType::Uuid => "uuid",
+            FilterColumnType::Text => "text",
+            FilterColumnType::Int => "bigint",
+            FilterColumnType::Timestamp => "timestamptz",
+        }
+    }
+}
+
+/// A caliber table that `caliber_prune` may delete from, with the columns
+/// criteria are allowed to reference.
+struct PruneTable {
+    table: &'static str,
+    id_column: &'static str,
+    has_superseded_by: bool,
+    columns: &'static [(&'static str, FilterColumnType)],
+}
+
+const PRUNE_TABLES: &[PruneTable] = &[
+    PruneTable {
+        table: "caliber_artifact",
+        id_column: "artifact_id",
+        has_superseded_by: true,
+        columns: &[
+            ("artifact_id", FilterColumnType::Uuid),
+            ("trajectory_id", FilterColumnType::Uuid),
+            ("scope_id", FilterColumnType::Uuid),
+            ("artifact_type", FilterColumnType::Text),
+            ("name", FilterColumnType::Text),
+            ("content", FilterColumnType::Text),
+            ("ttl", FilterColumnType::Text),
+            ("memory_category", FilterColumnType::Text),
+            ("superseded_by", FilterColumnType::Uuid),
+            ("created_at", FilterColumnType::Timestamp),
+            ("updated_at", FilterColumnType::Timestamp),
+        ],
+    },
+    PruneTable {
+        table: "caliber_note",
+        id_column: "note_id",
+        has_superseded_by: true,
+        columns: &[
+            ("note_id", FilterColumnType::Uuid),
+            ("note_type", FilterColumnType::Text),
+            ("title", FilterColumnType::Text),
+            ("content", FilterColumnType::Text),
+            ("ttl", FilterColumnType::Text),
+            ("abstraction_level", FilterColumnType::Text),
+            ("memory_category", FilterColumnType::Text),
+            ("access_count", FilterColumnType::Int),
+            ("superseded_by", FilterColumnType::Uuid),
+            ("created_at", FilterColumnType::Timestamp),
+            ("updated_at", FilterColumnType::Timestamp),
+            ("accessed_at", FilterColumnType::Timestamp),
+        ],
+    },
+    PruneTable {
+        table: "caliber_turn",
+        id_column: "turn_id",
+        has_superseded_by: false,
+        columns: &[
+            ("turn_id", FilterColumnType::Uuid),
+            ("scope_id", FilterColumnType::Uuid),
+            ("sequence", FilterColumnType::Int),
+            ("role", FilterColumnType::Text),
+            ("content", FilterColumnType::Text),
+            ("token_count", FilterColumnType::Int),
+            ("created_at", FilterColumnType::Timestamp),
+        ],
+    },
+];
+
+/// A bound parameter produced by `filter_to_sql`. Values are passed as text
+/// and cast to the column type in SQL after being validated in Rust.
+enum FilterParam {
+    Text(String),
+    TextArray(Vec<String>),
+}
+
+fn filter_error(field: &str, reason: impl Into<String>) -> CaliberError {
+    CaliberError::Validation(ValidationError::InvalidValue {
+        field: field.to_string(),
+        reason: reason.into(),
+    })
+}
+
+/// Validate a scalar filter value against a column type and render it as the
+/// text form bound into SQL.
+fn filter_value_to_text(
+    field: &str,
+    column_type: FilterColumnType,
+    value: &caliber_dsl::FilterValue,
+) -> CaliberResult<String> {
+    use caliber_dsl::FilterValue;
+
+    match (column_type, value) {
+        (FilterColumnType::Text, FilterValue::String(s)) => Ok(s.clone()),
+        (FilterColumnType::Text, FilterValue::Number(n)) => Ok(n.to_string()),
+        (FilterColumnType::Text, FilterValue::Bool(b)) => Ok(b.to_string()),
+        (FilterColumnType::Uuid, FilterValue::String(s)) => Uuid::parse_str(s)
+            .map(|id| id.to_string())
+            .map_err(|e| filter_error(field, format!("invalid UUID '{}': {}", s, e))),
+        (FilterColumnType::Int, FilterValue::Number(n)) if n.is_finite() && n.fract() == 0.0 => {
+            Ok((*n as i64).to_string())
+        }
+        (FilterColumnType::Timestamp, FilterValue::String(s)) => {
+            chrono::DateTime::parse_from_rfc3339(s)
+                .map(|ts| ts.to_rfc3339())
+                .map_err(|e| {
+                    filter_error(field, format!("invalid RFC 3339 timestamp '{}': {}", s, e))
+                })
+        }
+        (_, other) => Err(filter_error(
+            field,
+            format!(
+                "value {:?} is not valid for a {} column",
+                other,
+                column_type.sql_type()
+            ),
+        )),
+    }
+}
+
+/// Translate a DSL `FilterExpr` into a parameterized SQL predicate.
+///
+/// Field names must appear in `columns`; they are the only identifiers
+/// spliced into the SQL. Every value is bound as a parameter, numbered from
+/// `param_offset + 1` in the order pushed onto `params`.
+fn filter_to_sql(
+    expr: &caliber_dsl::FilterExpr,
+    columns: &[(&'static str, FilterColumnType)],
+    param_offset: usize,
+    params: &mut Vec<FilterParam>,
+) -> CaliberResult<String> {
+    use caliber_dsl::{CompareOp, FilterExpr, FilterValue};
+
+    match expr {
+        FilterExpr::And(exprs) | FilterExpr::Or(exprs) => {
+            let (joiner, empty) = match expr {
+                FilterExpr::And(_) => (" AND ", "TRUE"),
+                _ => (" OR ", "FALSE"),
+            };
+            if exprs.is_empty() {
+                return Ok(empty.to_string());
+            }
+            let parts = exprs
+                .iter()
+                .map(|e| {
+                    filter_to_sql(e, columns, param_offset, params).map(|sql| format!("({})", sql))
+                })
+                .collect::<CaliberResult<Vec<_>>>()?;
+            Ok(parts.join(joiner))
+        }
+        FilterExpr::Not(inner) => Ok(format!(
+            "NOT ({})",
+            filter_to_sql(inner, columns, param_offset, params)?
+        )),
+        FilterExpr::Comparison { field, op, value } => {
+            let column_type = columns
+                .iter()
+                .find(|(name, _)| *name == field.as_str())
+                .map(|(_, ty)| *ty)
+                .ok_or_else(|| filter_error(field, "unknown or non-filterable column"))?;
+            let column = field.as_str();
+
+            match (op, value) {
+                (CompareOp::Eq, FilterValue::Null) => return Ok(format!("{column} IS NULL")),
+                (CompareOp::Ne, FilterValue::Null) => return Ok(format!("{column} IS NOT NULL")),
+                (_, FilterValue::Null) => {
+                    return Err(filter_error(field, "null only supports == and !="));
+                }
+                (_, FilterValue::CurrentTrajectory | FilterValue::CurrentScope) => {
+                    return Err(filter_error(
+                        field,
+                        "current_trajectory/current_scope need a policy execution context",
+                    ));
+                }
+                _ => {}
+            }
+
+            let sql_op = match op {
+                CompareOp::Eq => "=",
+                CompareOp::Ne => "<>",
+                CompareOp::Gt => ">",
+                CompareOp::Lt => "<",
+                CompareOp::Ge => ">=",
+                CompareOp::Le => "<=",
+                CompareOp::Contains | CompareOp::Regex | CompareOp::In => "",
+            };
+            let placeholder = param_offset + params.len() + 1;
+            let sql_type = column_type.sql_type();
+
+            match (op, value) {
+                (CompareOp::In, FilterValue::Array(values)) => {
+                    let values = values
+                        .iter()
+                        .map(|v| filter_value_to_text(field, column_type, v))
+                        .collect::<CaliberResult<Vec<_>>>()?;
+                    params.push(FilterParam::TextArray(values));
+                    Ok(format!("{column} = ANY(${placeholder}::{sql_type}[])"))
+                }
+                (CompareOp::In, _) => Err(filter_error(field, "in requires an array value")),
+                (_, FilterValue::Array(_)) => Err(filter_error(
+                    field,
+                    "array values are only supported with in",
+                )),
+                (CompareOp::Contains | CompareOp::Regex, _)
+                    if column_type != FilterColumnType::Text =>
+                {
+                    Err(filter_error(
+                        field,
+                        "contains and regex require a text column",
+                    ))
+                }
+                (CompareOp::Contains, _) => {
+                    params.push(FilterParam::Text(filter_value_to_text(
+                        field,
+                        column_type,
+                        value,
+                    )?));
+                    Ok(format!("strpos({column}, ${placeholder}) > 0"))
+                }
+                (CompareOp::Regex, _) => {
+                    params.push(FilterParam::Text(filter_value_to_text(
+                        field,
+                        column_type,
+                        value,
+                    )?));
+                    Ok(format!("{column} ~ ${placeholder}"))
+                }
+                (_, FilterValue::Now) if column_type == FilterColumnType::Timestamp => {
+                    Ok(format!("{column} {sql_op} NOW()"))
+                }
+                (_, FilterValue::Now) => {
+                    Err(filter_error(field, "now requires a timestamp column"))
+                }
+                _ => {
+                    params.push(FilterParam::Text(filter_value_to_text(
+                        field,
+                        column_type,
+                        value,
+                    )?));
+                    Ok(format!("{column} {sql_op} ${placeholder}::{sql_type}"))
+                }
+            }
+        }
+    }
+}
+
+fn prune_checked(
+    target_table: &str,
+    criteria: serde_json::Value,
+    tenant_id: pgrx::Uuid,
+) -> CaliberResult<i64> {
+    // Validate target_table - reject tables outside the allowlist (REQ-12)
+    let target = PRUNE_TABLES
+        .iter()
+        .find(|t| t.table == target_table)
+        .ok_or_else(|| {
+            let valid: Vec<&str> = PRUNE_TABLES.iter().map(|t| t.table).collect();
+            filter_error(
+                "target_table",
+                format!(
+                    "unknown value '{}'. Valid values: {}",
+                    target_table,
+                    valid.join(", ")
+                ),
+            )
+        })?;
+    let criteria: caliber_dsl::FilterExpr = serde_json::from_value(criteria)
+        .map_err(|e| filter_error("criteria", format!("invalid filter expression: {}", e)))?;
+
+    // $1 is the tenant id; filter parameters follow
+    let mut params = Vec::new();
+    let predicate = filter_to_sql(&criteria, target.columns, 1, &mut params)?;
+    let query = format!(
+        "SELECT {} FROM {} WHERE tenant_id = $1 AND ({})",
+        target.id_column, target.table, predicate
+    );
+
+    let mut args = vec![pgrx_uuid_datum(tenant_id)];
+    for param in params {
+        args.push(match param {
+            FilterParam::Text(s) => unsafe { DatumWithOid::new(s, pgrx::pg_sys::TEXTOID) },
+            FilterParam::TextArray(v) => unsafe {
+                DatumWithOid::new(v, pgrx::pg_sys::TEXTARRAYOID)
+            },
+        });
+    }
+
+    Spi::connect(|client| {
+        client.select(&query, None, &args).map(|rows| {
+            rows.filter_map(|row| row.get::<pgrx::Uuid>(1).ok().flatten())
+                .collect::<Vec<_>>()
+        })
+    })
+    .and_then(|ids| {
+        delete_rows_spi(
+            target.table,
+            target.id_column,
+            target.has_superseded_by,
+            ids,
+            tenant_id,
+        )
+    })
+    .map_err(|e| {
+        CaliberError::Storage(StorageError::SpiError {
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Execute a DSL `prune` action: delete rows from an allowlisted caliber
+/// table that match a serialized `FilterExpr`.
+///
+/// `target_table` is one of caliber_artifact, caliber_note or caliber_turn.
+/// Criteria fields are restricted to known columns and all values are bound
+/// as parameters. Returns the number of rows deleted.
+#[pg_extern]
+fn caliber_prune(target_table: &str, criteria: pgrx::JsonB, tenant_id: pgrx::Uuid) -> i64 {
+    match prune_checked(target_table, criteria.0, tenant_id) {
+        Ok(pruned) => pruned,
+        Err(CaliberError::Validation(validation_err)) => {
+            pgrx::warning!("CALIBER: {:?}", validation_err);
+            0
+        }
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to prune {}: {}", target_table, e);
+            0
+        }
+    }
+}
+
+// ============================================================================
+// TRAJECTORY EXPORT / IMPORT
+// ============================================================================
+
+/// Format version of trajectory export documents.
+const TRAJECTORY_EXPORT_VERSION: i32 = 1;
+
+/// Serialize an entity and replace its `content_hash` byte array with hex.
+fn export_entity_json<T: Serialize>(entity: &T, content_hash: &[u8]) -> serde_json::Value {
+    let mut value = safe_to_json(entity);
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(
+            "content_hash".to_string(),
+            serde_json::Value::String(hex::encode(content_hash)),
+        );
+    }
+    value
+}
+
+/// Export a trajectory with all of its scopes, artifacts, notes, turns and
+/// edges as a single portable JSON document.
+///
+/// Entities are serialized in their core serde shape, with content hashes as
+/// hex. The document can be restored with `caliber_trajectory_import`.
+#[pg_extern]
+fn caliber_trajectory_export(
+    trajectory_id: pgrx::Uuid,
+    tenant_id: pgrx::Uuid,
+) -> Option<pgrx::JsonB> {
+    let traj_id = id_from_pgrx::<TrajectoryId>(trajectory_id);
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    match build_trajectory_export(traj_id, tenant_uuid) {
+        Ok(doc) => doc.map(pgrx::JsonB),
+        Err(e) => {
+            pgrx::warning!("CALIBER: Failed to export trajectory: {}", e);
+            None
+        }
+    }
+}
+
+/// Assemble a trajectory export document from the heap getters.
+fn build_trajectory_export(
+    traj_id: TrajectoryId,
+    tenant_uuid: TenantId,
+) -> CaliberResult<Option<serde_json::Value>> {
+    // Use direct heap operations instead of SPI
+    let trajectory = match trajectory_heap::trajectory_get_heap(traj_id, tenant_uuid)? {
+        Some(row) => row.trajectory,
+        None => return Ok(None),
+    };
+
+    let scopes = scope_heap::scope_list_by_trajectory_heap(traj_id, tenant_uuid)?;
+
+    let mut turns = Vec::new();
+    for row in &scopes {
+        for turn_row in turn_heap::turn_get_by_scope_heap(row.scope.scope_id, tenant_uuid)? {
+            turns.push(safe_to_json(&turn_row.turn));
+        }
+    }
+
+    let artifacts: Vec<serde_json::Value> =
+        artifact_heap::artifact_query_by_trajectory_heap(traj_id, tenant_uuid)?
+            .iter()
+            .map(|row| export_entity_json(&row.artifact, &row.artifact.content_hash))
+            .collect();
+
+    let notes: Vec<serde_json::Value> =
+        note_heap::note_query_by_trajectory_heap(traj_id, tenant_uuid)?
+            .iter()
+            .map(|row| export_entity_json(&row.note, &row.note.content_hash))
+            .collect();
+
+    let edges: Vec<serde_json::Value> =
+        edge_heap::edge_query_by_trajectory_heap(traj_id, tenant_uuid)?
+            .iter()
+            .map(|row| safe_to_json(&row.edge))
+            .collect();
+
+    let scopes: Vec<serde_json::Value> =
+        scopes.iter().map(|row| safe_to_json(&row.scope)).collect();
+
+    Ok(Some(serde_json::json!({
+        "version": TRAJECTORY_EXPORT_VERSION,
+        "exported_at": Utc::now().to_rfc3339(),
+        "trajectory": safe_to_json(&trajectory),
+        "scopes": scopes,
+        "artifacts": artifacts,
+        "notes": notes,
+        "turns": turns,
+        "edges": edges,
+    })))
+}
+
+/// Old→new ID mapping used while importing a trajectory export document.
+///
+/// Only IDs owned by the document are registered; references to entities
+/// outside it (agents, other trajectories) pass through unchanged.
+struct ImportIdMap {
+    remap: bool,
+    ids: HashMap<Uuid, Uuid>,
+}
+
+impl ImportIdMap {
+    fn new(remap: bool) -> Self {
+        Self {
+            remap,
+            ids: HashMap::new(),
+        }
+    }
+
+    /// Register an ID owned by the document and return its new value.
+    fn register<T: EntityIdType>(&mut self, id: T) -> T {
+        let old = id.as_uuid();
+        let new = if self.remap { Uuid::now_v7() } else { old };
+        T::new(*self.ids.entry(old).or_insert(new))
+    }
+
+    /// Look up the new value of an ID, passing unknown IDs through.
+    fn get<T: EntityIdType>(&self, id: T) -> T {
+        T::new(self.ids.get(&id.as_uuid()).copied().unwrap_or(id.as_uuid()))
+    }
+}
+
+/// Deserialize an exported entity, restoring its hex `content_hash` and
+/// verifying it against the content.
+fn import_hashed_entity<T: serde::de::DeserializeOwned>(
+    mut value: serde_json::Value,
+    entity: &str,
+) -> CaliberResult<T> {
+    let invalid = |reason: String| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: format!("{}.content_hash", entity),
+            reason,
+        })
+    };
+
+    let content = value
+        .get("content")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let expected = compute_content_hash(content.as_bytes());
+
+    if let Some(hash_hex) = value.get("content_hash").and_then(|v| v.as_str()) {
+        let decoded = hex::decode(hash_hex).map_err(|e| invalid(e.to_string()))?;
+        if decoded.as_slice() != expected.as_slice() {
+            return Err(invalid("hash does not match content".to_string()));
+        }
+    }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("content_hash".to_string(), serde_json::json!(expected));
+    }
+
+    serde_json::from_value(value).map_err(|e| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: entity.to_string(),
+            reason: e.to_string(),
+        })
+    })
+}
+
+/// Extract the raw entity list stored under an export document field.
+fn import_entity_list(doc: &serde_json::Value, field: &str) -> Vec<serde_json::Value> {
+    doc.get(field)
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Import a trajectory export document produced by `caliber_trajectory_export`.
+///
+/// When `remap_ids` is true every entity gets a fresh ID and internal
+/// references (scope→trajectory, artifact→scope, note sources, edge
+/// participants) are rewritten; when false IDs are preserved and an existing
+/// trajectory with the same ID is an error. Any failure aborts the whole
+/// import. Returns `{trajectory_id, id_map}` where `id_map` maps old→new IDs.
+#[pg_extern]
+fn caliber_trajectory_import(
+    doc: pgrx::JsonB,
+    remap_ids: bool,
+    tenant_id: pgrx::Uuid,
+) -> pgrx::JsonB {
+    let tenant_uuid = id_from_pgrx::<TenantId>(tenant_id);
+
+    match import_trajectory_document(&doc.0, remap_ids, tenant_uuid) {
+        Ok(result) => pgrx::JsonB(result),
+        // Abort so a partial import is rolled back
+        Err(e) => pgrx::error!("CALIBER: Failed to import trajectory: {}", e),
+    }
+}
+
+/// Insert a trajectory and its children from an export document.
+fn import_trajectory_document(
+    doc: &serde_json::Value,
+    remap_ids: bool,
+    tenant_uuid: TenantId,
+) -> CaliberResult<serde_json::Value> {
+    let parse_err = |field: &str, e: serde_json::Error| {
+        CaliberError::Validation(ValidationError::InvalidValue {
+            field: field.to_string(),
+            reason: e.to_string(),
+        })
+    };
+
+    let trajectory: Trajectory =
+        serde_json::from_value(doc.get("trajectory").cloned().unwrap_or_default())
+            .map_err(|e| parse_err("trajectory", e))?;
+    let scopes: Vec<Scope> =
+        serde_json::from_value(serde_json::json!(import_entity_list(doc, "scopes")))
+            .map_err(|e| parse_err("scopes", e))?;
+    let turns: Vec<Turn> =
+        serde_json::from_value(serde_json::json!(import_entity_list(doc, "turns")))
+            .map_err(|e| parse_err("turns", e))?;
+    let edges: Vec<Edge> =
+        serde_json::from_value(serde_json::json!(import_entity_list(doc, "edges")))
+            .map_err(|e| parse_err("edges", e))?;
+    let artifacts: Vec<Artifact> = import_entity_list(doc, "artifacts")
+        .into_iter()
+        .map(|v| import_hashed_entity(v, "artifact"))
+        .collect::<CaliberResult<_>>()?;
+    let notes: Vec<Note> = import_entity_list(doc, "notes")
+        .into_iter()
+        .map(|v| import_hashed_entity(v, "note"))
+        .collect::<CaliberResult<_>>()?;
+
+    if !remap_ids
+        && trajectory_heap::trajectory_get_heap(trajectory.trajectory_id, tenant_uuid)?.is_some()
+    {
+        return Err(CaliberError::Validation(
+            ValidationError::ConstraintViolation {
+                constraint: "caliber_trajectory_pkey".to_string(),
+                reason: format!("trajectory {} already exists", trajectory.trajectory_id),
+            },
+        ));
+    }
+
+    // Register every ID the document owns before rewriting references
+    let mut ids = ImportIdMap::new(remap_ids);
+    let traj_id = ids.register(trajectory.trajectory_id);
+    for s in &scopes {
+        ids.register(s.scope_id);
+    }
+    for t in &turns {
+        ids.register(t.turn_id);
+    }
+    for a in &artifacts {
+        ids.register(a.artifact_id);
+    }
+    for n in &notes {
+        ids.register(n.note_id);
+    }
+    for e in &edges {
+        ids.register(e.edge_id);
+    }
+
+    // Use direct heap operations instead of SPI
+    trajectory_heap::trajectory_create_heap(
+        traj_id,
+        &trajectory.name,
+        trajectory.description.as_deref(),
+        trajectory.agent_id,
+        tenant_uuid,
+    )?;
+    trajectory_heap::trajectory_update_heap(trajectory_heap::TrajectoryUpdateHeapParams {
+        id: traj_id,
+        tenant_id: tenant_uuid,
+        name: None,
+        description: None,
+        status: Some(trajectory.status),
+        parent_trajectory_id: Some(trajectory.parent_trajectory_id.map(|id| ids.get(id))),
+        root_trajectory_id: Some(trajectory.root_trajectory_id.map(|id| ids.get(id))),
+        agent_id: None,
+        outcome: Some(trajectory.outcome.as_ref()),
+        metadata: Some(trajectory.metadata.as_ref()),
+    })?;
+
+    for scope in &scopes {
+        let scope_id = ids.get(scope.scope_id);
+        scope_heap::scope_create_heap(
+            scope_id,
+            ids.get(scope.trajectory_id),
+            &scope.name,
+            scope.purpose.as_deref(),
+            scope.token_budget,
+            tenant_uuid,
+        )?;
+        scope_heap::scope_update_tokens_heap(scope_id, scope.tokens_used, tenant_uuid)?;
+        if !scope.is_active {
+            scope_heap::scope_close_heap(scope_id, tenant_uuid)?;
+        }
+    }
+
+    // Parents may reference scopes created later in the loop above
+    for scope in &scopes {
+        let updates = serde_json::json!({
+            "parent_scope_id": scope.parent_scope_id.map(|id| ids.get(id).to_string()),
+            "closed_at": scope.closed_at.map(|ts| ts.to_rfc3339()),
+            "checkpoint": scope.checkpoint,
+            "metadata": scope.metadata,
+        });
+        if !caliber_scope_update(
+            pgrx_uuid_from_id(ids.get(scope.scope_id)),
+            pgrx::JsonB(updates),
+            pgrx_uuid_from_id(tenant_uuid),
+        ) {
+            return Err(CaliberError::Validation(ValidationError::InvalidValue {
+                field: "scope".to_string(),
+                reason: format!("failed to restore scope {}", scope.scope_id),
+            }));
+        }
+    }
+
+    for turn in &turns {
+        turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
+            turn_id: ids.get(turn.turn_id),
+            scope_id: ids.get(turn.scope_id),
+            sequence: turn.sequence,
+            role: turn.role,
+            content: &turn.content,
+            token_count: turn.token_count,
+            tool_calls: turn.tool_calls.as_ref(),
+            tool_results: turn.tool_results.as_ref(),
+            tenant_id: tenant_uuid,
+        })?;
+    }
+
+    for artifact in &artifacts {
+        let artifact_id = ids.get(artifact.artifact_id);
+        artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
+            artifact_id,
+            trajectory_id: ids.get(artifact.trajectory_id),
+            scope_id: ids.get(artifact.scope_id),
+            artifact_type: artifact.artifact_type,
+            name: &artifact.name,
+            content: &artifact.content,
+            content_hash: artifact.content_hash,
+            embedding: artifact.embedding.as_ref(),
+            provenance: &artifact.provenance,
+            ttl: artifact.ttl.clone(),
+            tenant_id: tenant_uuid,
+        })?;
+        artifact_heap::artifact_update_heap(
+            artifact_id,
+            None,
+            None,
+            None,
+            Some(artifact.superseded_by.map(|id| ids.get(id))),
+            Some(artifact.metadata.as_ref()),
+            tenant_uuid,
+        )?;
+        metadata_bump_region_version(artifact.metadata.as_ref(), tenant_uuid);
+    }
+
+    for note in &notes {
+        let note_id = ids.get(note.note_id);
+        let source_trajectory_ids: Vec<TrajectoryId> = note
+            .source_trajectory_ids
+            .iter()
+            .map(|id| ids.get(*id))
+            .collect();
+        let source_artifact_ids: Vec<ArtifactId> = note
+            .source_artifact_ids
+            .iter()
+            .map(|id| ids.get(*id))
+            .collect();
+        let source_note_ids: Vec<NoteId> =
+            note.source_note_ids.iter().map(|id| ids.get(*id)).collect();
+
+        note_heap::note_create_heap(note_heap::NoteCreateParams {
+            note_id,
+            note_type: note.note_type,
+            title: &note.title,
+            content: &note.content,
+            content_hash: note.content_hash,
+            embedding: note.embedding.as_ref(),
+            source_trajectory_ids: &source_trajectory_ids,
+            source_artifact_ids: &source_artifact_ids,
+            ttl: note.ttl.clone(),
+            abstraction_level: note.abstraction_level,
+            source_note_ids: &source_note_ids,
+            tenant_id: tenant_uuid,
+        })?;
+        note_heap::note_update_heap(note_heap::NoteUpdateHeapParams {
+            id: note_id,
+            tenant_id: tenant_uuid,
+            title: None,
+            content: None,
+            content_hash: None,
+            embedding: None,
+            ttl: None,
+            abstraction_level: None,
+            superseded_by: Some(note.superseded_by.map(|id| ids.get(id))),
+            metadata: Some(note.metadata.as_ref()),
+        })?;
+    }
+
+    for edge in &edges {
+        let mut edge = edge.clone();
+        edge.edge_id = ids.get(edge.edge_id);
+        edge.trajectory_id = edge.trajectory_id.map(|id| ids.get(id));
+        for participant in &mut edge.participants {
+            participant.entity_ref.id = ids
+                .ids
+                .get(&participant.entity_ref.id)
+                .copied()
+                .unwrap_or(participant.entity_ref.id);
+        }
+        edge_heap::edge_create_heap(&edge, tenant_uuid)?;
+    }
+
+    let id_map: serde_json::Map<String, serde_json::Value> = ids
+        .ids
+        .iter()
+        .map(|(old, new)| (old.to_string(), serde_json::json!(new.to_string())))
+        .collect();
+
+    Ok(serde_json::json!({
+        "trajectory_id": traj_id.to_string(),
+        "id_map": id_map,
+    }))
+}
+
+// ============================================================================
+// STORAGE TRAIT IMPLEMENTATION (Task 12.3)
+// ============================================================================
+// Provides unified storage interface. Trajectory methods use SPI,
+// other entity types delegate to direct heap operations for performance.
+
+/// PostgreSQL storage implementation.
+/// Provides StorageTrait interface - trajectory via SPI, others via heap operations.
+pub struct PgStorage;
+
+impl StorageTrait for PgStorage {
+    fn trajectory_insert(&self, t: &Trajectory) -> CaliberResult<()> {
+        // Insert into SQL table via SPI
+        Spi::connect_mut(|client| {
+            // Check if trajectory already exists
+            let exists_result = client.select(
+                "SELECT 1 FROM caliber_trajectory WHERE trajectory_id = $1",
+                None,
+                &[id_datum(t.trajectory_id)],
+            );
+
+            if let Ok(table) = exists_result {
+                if !table.is_empty() {
+                    return Err(CaliberError::Storage(StorageError::InsertFailed {
+                        entity_type: EntityType::Trajectory,
+                        reason: "already exists".to_string(),
+                    }));
+                }
+            }
+
+            let status_str = match t.status {
+                TrajectoryStatus::Active => "active",
+                TrajectoryStatus::Completed => "completed",
+                TrajectoryStatus::Failed => "failed",
+                TrajectoryStatus::Suspended => "suspended",
+            };
+
+            let outcome_json = t
+                .outcome
+                .as_ref()
+                .map(|o| serde_json::to_value(o).unwrap_or(serde_json::Value::Null));
+            let metadata_json = t
+                .metadata
+                .as_ref()
+                .map(|m| serde_json::to_value(m).unwrap_or(serde_json::Value::Null));
+
+            let created_at_datum = timestamp_datum(t.created_at)?;
+            let updated_at_datum = timestamp_datum(t.updated_at)?;
+            let completed_at_datum = opt_timestamp_datum(t.completed_at)?;
+
+            client
+                .update(
+                    "INSERT INTO caliber_trajectory (trajectory_id, name, description, status,
+                 parent_trajectory_id, root_trajectory_id, agent_id, created_at, updated_at,
+                 completed_at, outcome, metadata)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+                    None,
+                    &[
+                        id_datum(t.trajectory_id),
+                        text_datum(&t.name),
+                        opt_text_datum(t.description.as_deref()),
+                        text_datum(status_str),
+                        opt_id_datum(t.parent_trajectory_id),
+                        opt_id_datum(t.root_trajectory_id),
+                        opt_id_datum(t.agent_id),
+                        created_at_datum,
+                        updated_at_datum,
+                        completed_at_datum,
+                        opt_jsonb_datum(outcome_json.as_ref()),
+                        opt_jsonb_datum(metadata_json.as_ref()),
+                    ],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            Ok(())
+        })
+    }
+
+    fn trajectory_get(&self, id: Uuid) -> CaliberResult<Option<Trajectory>> {
+        Spi::connect(|client| {
+            let mut result = client
+                .select(
+                    "SELECT trajectory_id, name, description, status, parent_trajectory_id,
+                        root_trajectory_id, agent_id, created_at, updated_at, completed_at,
+                        outcome, metadata
+                 FROM caliber_trajectory WHERE trajectory_id = $1",
+                    None,
+                    &[uuid_datum(id)],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            // Iterate over result rows (SpiTupleTable is iterable)
+            if let Some(row) = result.next() {
+                let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let name: Option<String> = row.get(2).ok().flatten();
+                let description: Option<String> = row.get(3).ok().flatten();
+                let status_str: Option<String> = row.get(4).ok().flatten();
+                let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
+                let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
+                let agent_id_val: Option<pgrx::Uuid> = row.get(7).ok().flatten();
+                let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
+                let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+                let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+                let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
+                let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+
+                let status = match status_str.as_deref() {
+                    Some("active") => TrajectoryStatus::Active,
+                    Some("completed") => TrajectoryStatus::Completed,
+                    Some("failed") => TrajectoryStatus::Failed,
+                    Some("suspended") => TrajectoryStatus::Suspended,
+                    _ => {
+                        pgrx::warning!(
+                            "CALIBER: Unknown trajectory status '{:?}', defaulting to Active",
+                            status_str
+                        );
+                        TrajectoryStatus::Active
+                    }
+                };
+
+                let traj_id = trajectory_id
+                    .map(id_from_pgrx::<TrajectoryId>)
+                    .unwrap_or(TrajectoryId::new(id));
+                let parent_id = parent_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+                let root_id = root_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+                let agent_id = agent_id_val.map(id_from_pgrx::<AgentId>);
+
+                return Ok(Some(Trajectory {
+                    trajectory_id: traj_id,
+                    name: name.unwrap_or_default(),
+                    description,
+                    status,
+                    parent_trajectory_id: parent_id,
+                    root_trajectory_id: root_id,
+                    agent_id,
+                    created_at: created_at
+                        .map(tuple_extract::timestamp_to_chrono)
+                        .unwrap_or_else(Utc::now),
+                    updated_at: updated_at
+                        .map(tuple_extract::timestamp_to_chrono)
+                        .unwrap_or_else(Utc::now),
+                    completed_at: completed_at.map(tuple_extract::timestamp_to_chrono),
+                    outcome: outcome.and_then(|j| serde_json::from_value(j.0).ok()),
+                    metadata: metadata.and_then(|j| serde_json::from_value(j.0).ok()),
+                }));
+            }
+            Ok(None)
+        })
+    }
+
+    fn trajectory_update(&self, id: Uuid, update: TrajectoryUpdate) -> CaliberResult<()> {
+        Spi::connect_mut(|client| {
+            // First check if trajectory exists
+            let exists_result = client
+                .select(
+                    "SELECT 1 FROM caliber_trajectory WHERE trajectory_id = $1",
+                    None,
+                    &[uuid_datum(id)],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            if exists_result.is_empty() {
+                return Err(CaliberError::Storage(StorageError::NotFound {
+                    entity_type: EntityType::Trajectory,
+                    id,
+                }));
+            }
+
+            let now = Utc::now();
+
+            // Build dynamic update query based on what fields are provided
+            if let Some(status) = update.status {
+                let status_str = match status {
+                    TrajectoryStatus::Active => "active",
+                    TrajectoryStatus::Completed => "completed",
+                    TrajectoryStatus::Failed => "failed",
+                    TrajectoryStatus::Suspended => "suspended",
+                };
+                let updated_at_datum = timestamp_datum(now)?;
+                client.update(
+                    "UPDATE caliber_trajectory SET status = $1, updated_at = $2 WHERE trajectory_id = $3",
+                    None,
+                    &[
+                        text_datum(status_str),
+                        updated_at_datum,
+                        uuid_datum(id),
+                    ],
+                ).map_err(|e| CaliberError::Storage(StorageError::SpiError { reason: e.to_string() }))?;
+            }
+
+            if let Some(metadata) = update.metadata {
+                let metadata_json =
+                    serde_json::to_value(&metadata).unwrap_or(serde_json::Value::Null);
+                let updated_at_datum = timestamp_datum(now)?;
+                client.update(
+                    "UPDATE caliber_trajectory SET metadata = $1, updated_at = $2 WHERE trajectory_id = $3",
+                    None,
+                    &[
+                        jsonb_datum(&metadata_json),
+                        updated_at_datum,
+                        uuid_datum(id),
+                    ],
+                ).map_err(|e| CaliberError::Storage(StorageError::SpiError { reason: e.to_string() }))?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn trajectory_list_by_status(
+        &self,
+        status: TrajectoryStatus,
+    ) -> CaliberResult<Vec<Trajectory>> {
+        let status_str = match status {
+            TrajectoryStatus::Active => "active",
+            TrajectoryStatus::Completed => "completed",
+            TrajectoryStatus::Failed => "failed",
+            TrajectoryStatus::Suspended => "suspended",
+        };
+
+        Spi::connect(|client| {
+            let result = client
+                .select(
+                    "SELECT trajectory_id, name, description, status, parent_trajectory_id,
+                        root_trajectory_id, agent_id, created_at, updated_at, completed_at,
+                        outcome, metadata
+                 FROM caliber_trajectory WHERE status = $1 ORDER BY created_at DESC",
+                    None,
+                    &[text_datum(status_str)],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            let mut trajectories = Vec::new();
+            for row in result {
+                let trajectory_id: Option<pgrx::Uuid> = row.get(1).ok().flatten();
+                let name: Option<String> = row.get(2).ok().flatten();
+                let description: Option<String> = row.get(3).ok().flatten();
+                let status_str_val: Option<String> = row.get(4).ok().flatten();
+                let parent_trajectory_id: Option<pgrx::Uuid> = row.get(5).ok().flatten();
+                let root_trajectory_id: Option<pgrx::Uuid> = row.get(6).ok().flatten();
+                let agent_id_val: Option<pgrx::Uuid> = row.get(7).ok().flatten();
+                let created_at: Option<TimestampWithTimeZone> = row.get(8).ok().flatten();
+                let updated_at: Option<TimestampWithTimeZone> = row.get(9).ok().flatten();
+                let completed_at: Option<TimestampWithTimeZone> = row.get(10).ok().flatten();
+                let outcome: Option<pgrx::JsonB> = row.get(11).ok().flatten();
+                let metadata: Option<pgrx::JsonB> = row.get(12).ok().flatten();
+
+                let traj_status = match status_str_val.as_deref() {
+                    Some("active") => TrajectoryStatus::Active,
+                    Some("completed") => TrajectoryStatus::Completed,
+                    Some("failed") => TrajectoryStatus::Failed,
+                    Some("suspended") => TrajectoryStatus::Suspended,
+                    _ => {
+                        pgrx::warning!(
+                            "CALIBER: Unknown trajectory status '{:?}', defaulting to Active",
+                            status_str_val
+                        );
+                        TrajectoryStatus::Active
+                    }
+                };
+
+                if let Some(tid) = trajectory_id {
+                    let parent_id = parent_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+                    let root_id = root_trajectory_id.map(id_from_pgrx::<TrajectoryId>);
+                    let agent_id = agent_id_val.map(id_from_pgrx::<AgentId>);
+
+                    trajectories.push(Trajectory {
+                        trajectory_id: id_from_pgrx::<TrajectoryId>(tid),
+                        name: name.unwrap_or_default(),
+                        description,
+                        status: traj_status,
+                        parent_trajectory_id: parent_id,
+                        root_trajectory_id: root_id,
+                        agent_id,
+                        created_at: created_at
+                            .map(tuple_extract::timestamp_to_chrono)
+                            .unwrap_or_else(Utc::now),
+                        updated_at: updated_at
+                            .map(tuple_extract::timestamp_to_chrono)
+                            .unwrap_or_else(Utc::now),
+                        completed_at: completed_at.map(tuple_extract::timestamp_to_chrono),
+                        outcome: outcome.and_then(|j| serde_json::from_value(j.0).ok()),
+                        metadata: metadata.and_then(|j| serde_json::from_value(j.0).ok()),
+                    });
+                }
+            }
+            Ok(trajectories)
+        })
+    }
+
+    fn scope_insert(&self, s: &Scope) -> CaliberResult<()> {
+        // Check if already exists using heap module
+        if scope_heap::scope_get_heap(s.scope_id, TenantId::nil())?.is_some() {
+            return Err(CaliberError::Storage(StorageError::InsertFailed {
+                entity_type: EntityType::Scope,
+                reason: "already exists".to_string(),
+            }));
+        }
+        scope_heap::scope_create_heap(
+            s.scope_id,
+            s.trajectory_id,
+            &s.name,
+            s.purpose.as_deref(),
+            s.token_budget,
+            TenantId::nil(),
+        )?;
+        Ok(())
+    }
+
+    fn scope_get(&self, id: Uuid) -> CaliberResult<Option<Scope>> {
+        scope_heap::scope_get_heap(ScopeId::new(id), TenantId::nil()).map(|row| row.map(Into::into))
+    }
+
+    fn scope_get_current(&self, trajectory_id: Uuid) -> CaliberResult<Option<Scope>> {
+        // Get all scopes for trajectory and find the active one with latest created_at
+        let scopes = scope_heap::scope_list_by_trajectory_heap(
+            TrajectoryId::new(trajectory_id),
+            TenantId::nil(),
+        )?;
+        Ok(scopes
+            .into_iter()
+            .map(Into::into)
+            .filter(|s: &Scope| s.is_active)
+            .max_by_key(|s| s.created_at))
+    }
+
+    fn scope_update(&self, id: Uuid, update: ScopeUpdate) -> CaliberResult<()> {
+        // Verify scope exists
+        let scope_id = ScopeId::new(id);
+        let existing = scope_heap::scope_get_heap(scope_id, TenantId::nil())?;
+        if existing.is_none() {
+            return Err(CaliberError::Storage(StorageError::NotFound {
+                entity_type: EntityType::Scope,
+                id,
+            }));
+        }
+
+        // For now, handle close specifically (most common update)
+        if update.is_active == Some(false) {
+            scope_heap::scope_close_heap(scope_id, TenantId::nil())?;
+        }
+        // Token updates via dedicated function
+        if let Some(tokens) = update.tokens_used {
+            scope_heap::scope_update_tokens_heap(scope_id, tokens, TenantId::nil())?;
+        }
+        Ok(())
+    }
+
+    fn scope_list_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Scope>> {
+        scope_heap::scope_list_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn artifact_insert(&self, a: &Artifact) -> CaliberResult<()> {
+        // Check if already exists using heap module
+        if artifact_heap::artifact_get_heap(a.artifact_id, TenantId::nil())?.is_some() {
+            return Err(CaliberError::Storage(StorageError::InsertFailed {
+                entity_type: EntityType::Artifact,
+                reason: "already exists".to_string(),
+            }));
+        }
+        artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
+            artifact_id: a.artifact_id,
+            trajectory_id: a.trajectory_id,
+            scope_id: a.scope_id,
+            artifact_type: a.artifact_type,
+            name: &a.name,
+            content: &a.content,
+            content_hash: a.content_hash,
+            embedding: a.embedding.as_ref(),
+            provenance: &a.provenance,
+            ttl: a.ttl.clone(),
+            tenant_id: TenantId::nil(),
+        })?;
+        Ok(())
+    }
+
+    fn artifact_get(&self, id: Uuid) -> CaliberResult<Option<Artifact>> {
+        artifact_heap::artifact_get_heap(ArtifactId::new(id), TenantId::nil())
+            .map(|row| row.filter(|row| row.deleted_at.is_none()).map(Into::into))
+    }
+
+    fn artifact_query_by_type(
+        &self,
+        trajectory_id: Uuid,
+        artifact_type: ArtifactType,
+    ) -> CaliberResult<Vec<Artifact>> {
+        // Get all artifacts of type and filter by trajectory
+        let traj_id = TrajectoryId::new(trajectory_id);
+        let artifacts = artifact_heap::artifact_query_by_type_heap(artifact_type, TenantId::nil())?
+            .into_iter()
+            .map(Into::into)
+            .filter(|a: &Artifact| a.trajectory_id == traj_id)
+            .collect();
+        Ok(artifacts)
+    }
+
+    fn artifact_query_by_scope(&self, scope_id: Uuid) -> CaliberResult<Vec<Artifact>> {
+        artifact_heap::artifact_query_by_scope_heap(ScopeId::new(scope_id), TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn artifact_update(&self, id: Uuid, update: ArtifactUpdate) -> CaliberResult<()> {
+        // Compute content hash if content is being updated
+        let content_hash = update
+            .content
+            .as_ref()
+            .map(|c| compute_content_hash(c.as_bytes()));
+        // Convert embedding to the nested Option format (Some(Some(x)) = update, Some(None) = set to null, None = don't update)
+        let embedding_opt = update.embedding.as_ref().map(Some);
+        // Same for superseded_by
+        let superseded_opt = update.superseded_by.map(|s| Some(ArtifactId::new(s)));
+
+        artifact_heap::artifact_update_heap(
+            ArtifactId::new(id),
+            update.content.as_deref(),
+            content_hash,
+            embedding_opt,
+            superseded_opt,
+            None, // metadata not in ArtifactUpdate struct
+            TenantId::nil(),
+        )?;
+        Ok(())
+    }
+
+    fn note_insert(&self, n: &Note) -> CaliberResult<()> {
+        // Check if already exists using heap module
+        if note_heap::note_get_heap(n.note_id, TenantId::nil())?.is_some() {
+            return Err(CaliberError::Storage(StorageError::InsertFailed {
+                entity_type: EntityType::Note,
+                reason: "already exists".to_string(),
+            }));
+        }
+        note_heap::note_create_heap(note_heap::NoteCreateParams {
+            note_id: n.note_id,
+            note_type: n.note_type,
+            title: &n.title,
+            content: &n.content,
+            content_hash: n.content_hash,
+            embedding: n.embedding.as_ref(),
+            source_trajectory_ids: &n.source_trajectory_ids,
+            source_artifact_ids: &n.source_artifact_ids,
+            ttl: n.ttl.clone(),
+            abstraction_level: n.abstraction_level,
+            source_note_ids: &n.source_note_ids,
+            tenant_id: TenantId::nil(),
+        })?;
+        Ok(())
+    }
+
+    fn note_get(&self, id: Uuid) -> CaliberResult<Option<Note>> {
+        note_heap::note_get_heap(NoteId::new(id), TenantId::nil())
+            .map(|row| row.filter(|row| row.deleted_at.is_none()).map(Into::into))
+    }
+
+    fn note_query_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Note>> {
+        note_heap::note_query_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn note_update(&self, id: Uuid, update: NoteUpdate) -> CaliberResult<()> {
+        // Compute content hash if content is being updated
+        let content_hash = update
+            .content
+            .as_ref()
+            .map(|c| compute_content_hash(c.as_bytes()));
+        // Convert embedding to the nested Option format (Some(Some(x)) = update, Some(None) = set to null, None = don't update)
+        let embedding_opt = update.embedding.as_ref().map(Some);
+        // Same for superseded_by
+        let superseded_opt = update.superseded_by.map(|s| Some(NoteId::new(s)));
+
+        note_heap::note_update_heap(note_heap::NoteUpdateHeapParams {
+            id: NoteId::new(id),
+            tenant_id: TenantId::nil(),
+            title: None,
+            content: update.content.as_deref(),
+            content_hash,
+            embedding: embedding_opt,
+            ttl: None,
+            abstraction_level: None,
+            superseded_by: superseded_opt,
+            metadata: None, // metadata not in NoteUpdate struct
+        })?;
+        Ok(())
+    }
+
+    fn turn_insert(&self, t: &Turn) -> CaliberResult<()> {
+        turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
+            turn_id: t.turn_id,
+            scope_id: t.scope_id,
+            sequence: t.sequence,
+            role: t.role,
+            content: &t.content,
+            token_count: t.token_count,
+            tool_calls: t.tool_calls.as_ref(),
+            tool_results: t.tool_results.as_ref(),
+            tenant_id: TenantId::nil(),
+        })?;
+        Ok(())
+    }
+
+    fn turn_get_by_scope(&self, scope_id: Uuid) -> CaliberResult<Vec<Turn>> {
+        turn_heap::turn_get_by_scope_heap(ScopeId::new(scope_id), TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn vector_search(
+        &self,
+        query: &EmbeddingVector,
+        limit: i32,
+    ) -> CaliberResult<Vec<(Uuid, f32)>> {
+        // Shares the parameterized artifact/note search with caliber_vector_search
+        let (operator, similarity_expr) = COSINE_METRIC_SQL;
+        let hits = vector_search_hits(&query.data, limit, operator, similarity_expr, None)?;
+        Ok(hits
+            .into_iter()
+            .map(|hit| (hit.entity_id, hit.similarity as f32))
+            .collect())
+    }
+
+    // === Edge Operations (Battle Intel Feature 1) ===
+
+    fn edge_insert(&self, e: &Edge) -> CaliberResult<()> {
+        edge_heap::edge_create_heap(e, TenantId::nil())?;
+        Ok(())
+    }
+
+    fn edge_get(&self, id: Uuid) -> CaliberResult<Option<Edge>> {
+        edge_heap::edge_get_heap(EdgeId::new(id), TenantId::nil()).map(|row| row.map(Into::into))
+    }
+
+    fn edge_query_by_type(&self, edge_type: EdgeType) -> CaliberResult<Vec<Edge>> {
+        edge_heap::edge_query_by_type_heap(edge_type, TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn edge_query_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Edge>> {
+        edge_heap::edge_query_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
+            .map(|rows| rows.into_iter().map(Into::into).collect())
+    }
+
+    fn edge_query_by_participant(&self, entity_id: Uuid) -> CaliberResult<Vec<Edge>> {
+        // Query edges and filter by participant - uses SPI since we need JSON filtering
+        Spi::connect(|client| {
+            let result = client
+                .select(
+                    "SELECT edge_id, edge_type, participants, weight, trajectory_id,
+                        source_turn, extraction_method, confidence, created_at, metadata
+                 FROM caliber_edge
+                 WHERE participants @> $1::jsonb",
+                    None,
+                    &[text_datum(&format!(r#"[{{"id":"{}"}}]"#, entity_id))],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            let mut edges = Vec::new();
+            for row in result {
+                // Parse each row into Edge struct
+                if let Ok(Some(edge_id_pg)) = row.get::<pgrx::Uuid>(1) {
+                    let edge_id = EdgeId::new(Uuid::from_bytes(*edge_id_pg.as_bytes()));
+                    if let Some(edge_row) = edge_heap::edge_get_heap(edge_id, TenantId::nil())? {
+                        edges.push(edge_row.into());
+                    }
+                }
+            }
+            Ok(edges)
+        })
+    }
+
+    // === Note Abstraction Level Queries (Battle Intel Feature 2) ===
+
+    fn note_query_by_abstraction_level(&self, level: AbstractionLevel) -> CaliberResult<Vec<Note>> {
+        let level_str = match level {
+            AbstractionLevel::Raw => "raw",
+            AbstractionLevel::Summary => "summary",
+            AbstractionLevel::Principle => "principle",
+        };
+
+        Spi::connect(|client| {
+            let result = client
+                .select(
+                    "SELECT note_id FROM caliber_note
+                     WHERE abstraction_level = $1 AND deleted_at IS NULL",
+                    None,
+                    &[text_datum(level_str)],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            let mut notes = Vec::new();
+            for row in result {
+                if let Ok(Some(note_id_pg)) = row.get::<pgrx::Uuid>(1) {
+                    let note_id = NoteId::new(Uuid::from_bytes(*note_id_pg.as_bytes()));
+                    if let Some(note_row) = note_heap::note_get_heap(note_id, TenantId::nil())? {
+                        notes.push(note_row.into());
+                    }
+                }
+            }
+            Ok(notes)
+        })
+    }
+
+    fn note_query_by_source_note(&self, source_note_id: Uuid) -> CaliberResult<Vec<Note>> {
+        Spi::connect(|client| {
+            let result = client
+                .select(
+                    "SELECT note_id FROM caliber_note
+                     WHERE $1 = ANY(source_note_ids) AND deleted_at IS NULL",
+                    None,
+                    &[uuid_datum(source_note_id)],
+                )
+                .map_err(|e| {
+                    CaliberError::Storage(StorageError::SpiError {
+                        reason: e.to_string(),
+                    })
+                })?;
+
+            let mut notes = Vec::new();
+            for row in result {
+                if let Ok(Some(note_id_pg)) = row.get::<pgrx::Uuid>(1) {
+                    let note_id = NoteId::new(Uuid::from_bytes(*note_id_pg.as_bytes()));
+                    if let Some(note_row) = note_heap::note_get_heap(note_id, TenantId::nil())? {
+                        notes.push(note_row.into());
+                    }
+                }
+            }
+            Ok(notes)
+        })
+    }
+}
+
+// ============================================================================
+// TENANT OPERATIONS
+// ============================================================================
+
+/// Check if an email domain is a public domain (gmail, outlook, etc.).
+/// Public domain users get personal tenants instead of joining a corporate tenant.
+#[pg_extern]
+fn caliber_is_public_email_domain(domain: &str) -> bool {
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT EXISTS (SELECT 1 FROM caliber_public_email_domain WHERE domain = $1)",
+            None,
+            &[text_datum(domain)],
+        );
+
+        match result {
+            Ok(table) => table
+                .first()
+                .get_one::<bool>()
+                .unwrap_or(Some(false))
+                .unwrap_or(false),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to check public domain: {}", e);
+                false
+            }
+        }
+    })
+}
+
+/// Create a new tenant.
+/// Returns the tenant_id of the created tenant.
+#[pg_extern]
+fn caliber_tenant_create(
+    name: &str,
+    domain: Option<&str>,
+    workos_organization_id: Option<&str>,
+) -> pgrx::Uuid {
+    let tenant_id = TenantId::now_v7();
+
+    Spi::connect_mut(|client| {
+        let result = client.update(
+            "INSERT INTO caliber_tenant (tenant_id, name, domain, workos_organization_id)
+             VALUES ($1, $2, $3, $4)",
+            None,
+            &[
+                id_datum(tenant_id),
+                text_datum(name),
+                opt_text_datum(domain),
+                opt_text_datum(workos_organization_id),
+            ],
+        );
+
+        if let Err(e) = result {
+            pgrx::warning!("CALIBER: Failed to create tenant: {}", e);
+        }
+    });
+
+    pgrx_uuid_from_id(tenant_id)
+}
+
+/// Get a tenant by domain.
+/// Returns JSON object with tenant details or null if not found.
+#[pg_extern]
+fn caliber_tenant_get_by_domain(domain: &str) -> Option<pgrx::JsonB> {
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT tenant_id, name, domain, workos_organization_id, status,
+                    created_at, updated_at, metadata
+             FROM caliber_tenant WHERE domain = $1",
+            None,
+            &[text_datum(domain)],
+        );
+
+        match result {
+            Ok(table) => {
+                let first_row = table.first();
+                if let Ok(Some(tenant_id)) = first_row.get::<pgrx::Uuid>(1) {
+                    let name: String = first_row
+                        .get::<&str>(2)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("")
+                        .to_string();
+                    let domain: Option<String> = first_row
+                        .get::<&str>(3)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let workos_org: Option<String> = first_row
+                        .get::<&str>(4)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let status: String = first_row
+                        .get::<&str>(5)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("active")
+                        .to_string();
+                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
+                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> =
+                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+
+                    Some(pgrx::JsonB(serde_json::json!({
+                        "tenant_id": Uuid::from_bytes(*tenant_id.as_bytes()).to_string(),
+                        "name": name,
+                        "domain": domain,
+                        "workos_organization_id": workos_org,
+                        "status": status,
+                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "metadata": metadata.map(|m| m.0),
+                    })))
+                } else {
+                    None
+                }
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get tenant by domain: {}", e);
+                None
+            }
+        }
+    })
+}
+
+/// Get a tenant by WorkOS organization ID.
+/// Returns JSON object with tenant details or null if not found.
+#[pg_extern]
+fn caliber_tenant_get_by_workos_org(workos_organization_id: &str) -> Option<pgrx::JsonB> {
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT tenant_id, name, domain, workos_organization_id, status,
+                    created_at, updated_at, metadata
+             FROM caliber_tenant WHERE workos_organization_id = $1",
+            None,
+            &[text_datum(workos_organization_id)],
+        );
+
+        match result {
+            Ok(table) => {
+                let first_row = table.first();
+                if let Ok(Some(tenant_id)) = first_row.get::<pgrx::Uuid>(1) {
+                    let name: String = first_row
+                        .get::<&str>(2)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("")
+                        .to_string();
+                    let domain: Option<String> = first_row
+                        .get::<&str>(3)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let workos_org: Option<String> = first_row
+                        .get::<&str>(4)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let status: String = first_row
+                        .get::<&str>(5)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("active")
+                        .to_string();
+                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
+                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> =
+                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+
+                    Some(pgrx::JsonB(serde_json::json!({
+                        "tenant_id": Uuid::from_bytes(*tenant_id.as_bytes()).to_string(),
+                        "name": name,
+                        "domain": domain,
+                        "workos_organization_id": workos_org,
+                        "status": status,
+                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "metadata": metadata.map(|m| m.0),
+                    })))
+                } else {
+                    None
+                }
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get tenant by WorkOS org: {}", e);
+                None
+            }
+        }
+    })
+}
+
+/// Upsert a tenant member (insert or update on conflict).
+/// If the member already exists for this tenant+user_id, updates their info.
+/// Returns the member_id.
+#[pg_extern]
+fn caliber_tenant_member_upsert(
+    tenant_id: pgrx::Uuid,
+    user_id: &str,
+    email: &str,
+    role: &str,
+    first_name: Option<&str>,
+    last_name: Option<&str>,
+) -> pgrx::Uuid {
+    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+    let member_id = Uuid::now_v7();
+
+    Spi::connect_mut(|client| {
+        let result = client.update(
+            "INSERT INTO caliber_tenant_member (member_id, tenant_id, user_id, email, role, first_name, last_name)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (tenant_id, user_id) DO UPDATE SET
+                 email = EXCLUDED.email,
+                 role = EXCLUDED.role,
+                 first_name = EXCLUDED.first_name,
+                 last_name = EXCLUDED.last_name,
+                 last_seen_at = NOW()",
+            None,
+            &[
+                uuid_datum(member_id),
+                uuid_datum(tenant_uuid),
+                text_datum(user_id),
+                text_datum(email),
+                text_datum(role),
+                opt_text_datum(first_name),
+                opt_text_datum(last_name),
+            ],
+        );
+
+        if let Err(e) = result {
+            pgrx::warning!("CALIBER: Failed to upsert tenant member: {}", e);
+        }
+    });
+
+    pgrx::Uuid::from_bytes(*member_id.as_bytes())
+}
+
+/// Count the number of members in a tenant.
+/// Used to determine if a new member should be admin (first member) or regular member.
+#[pg_extern]
+fn caliber_tenant_member_count(tenant_id: pgrx::Uuid) -> i32 {
+    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT COUNT(*)::INTEGER FROM caliber_tenant_member WHERE tenant_id = $1",
+            None,
+            &[uuid_datum(tenant_uuid)],
+        );
+
+        match result {
+            Ok(table) => table
+                .first()
+                .get_one::<i32>()
+                .unwrap_or(Some(0))
+                .unwrap_or(0),
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to count tenant members: {}", e);
+                0
+            }
+        }
+    })
+}
+
+/// Get a tenant by ID.
+/// Returns JSON object with tenant details or null if not found.
+#[pg_extern]
+fn caliber_tenant_get(tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
+    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+
+    Spi::connect(|client| {
+        let result = client.select(
+            "SELECT tenant_id, name, domain, workos_organization_id, status,
+                    created_at, updated_at, metadata
+             FROM caliber_tenant WHERE tenant_id = $1",
+            None,
+            &[uuid_datum(tenant_uuid)],
+        );
+
+        match result {
+            Ok(table) => {
+                let first_row = table.first();
+                if let Ok(Some(id)) = first_row.get::<pgrx::Uuid>(1) {
+                    let name: String = first_row
+                        .get::<&str>(2)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("")
+                        .to_string();
+                    let domain: Option<String> = first_row
+                        .get::<&str>(3)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let workos_org: Option<String> = first_row
+                        .get::<&str>(4)
+                        .ok()
+                        .flatten()
+                        .map(|s| s.to_string());
+                    let status: String = first_row
+                        .get::<&str>(5)
+                        .ok()
+                        .flatten()
+                        .unwrap_or("active")
+                        .to_string();
+                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
+                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
+                    let metadata: Option<pgrx::JsonB> =
+                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+
+                    Some(pgrx::JsonB(serde_json::json!({
+                        "tenant_id": Uuid::from_bytes(*id.as_bytes()).to_string(),
+                        "name": name,
+                        "domain": domain,
+                        "workos_organization_id": workos_org,
+                        "status": status,
+                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
+                        "metadata": metadata.map(|m| m.0),
+                    })))
+                } else {
+                    None
+                }
+            }
+            Err(e) => {
+                pgrx::warning!("CALIBER: Failed to get tenant: {}", e);
+                None
+            }
+        }
+    })
+}
+
+// ============================================================================
+// UNIT TESTS
+// ============================================================================
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sql_statements() {
+        let sql = "-- header; not a statement\n\
+                   CREATE TABLE t (a TEXT DEFAULT 'x;y');\n\
+                   /* block; comment */\n\
+                   CREATE FUNCTION f() RETURNS INT AS $$ SELECT 1; $$ LANGUAGE SQL;\n\
+                   DO $body$ BEGIN PERFORM 1; END $body$;\n\
+                   SELECT $1::text;\n\
+                   -- trailing comment only\n";
+        let statements = split_sql_statements(sql);
+        assert_eq!(statements.len(), 4, "{:?}", statements);
+        assert!(statements[0].ends_with("DEFAULT 'x;y')"));
+        assert!(statements[1].contains("SELECT 1; $$"));
+        assert!(statements[2].starts_with("DO $body$"));
+        assert_eq!(statements[3], "SELECT $1::text");
+    }
+}
+
+// ============================================================================
+// PGRX INTEGRATION TESTS (Task 12.8)
+// ============================================================================
+
+#[cfg(feature = "pg_test")]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    fn test_tenant_id() -> pgrx::Uuid {
+        crate::caliber_new_id()
+    }
+
+    #[pg_test]
+    fn test_caliber_version() {
+        let version = crate::caliber_version();
+        assert!(!version.is_empty());
+    }
+
+    #[pg_test]
+    fn test_caliber_new_id() {
+        let id1 = crate::caliber_new_id();
+        let id2 = crate::caliber_new_id();
+        // IDs should be unique
+        assert_ne!(id1, id2);
+    }
+
+    #[pg_test]
+    fn test_caliber_id_timestamp() {
+        assert!(crate::caliber_id_timestamp(crate::caliber_new_id()).is_some());
+
+        let known = pgrx::Uuid::from_bytes(
+            *uuid::Uuid::parse_str("017f22e2-79b0-7cc3-98c4-dc0c0c07398f")
+                .expect("uuid")
+                .as_bytes(),
+        );
+        let expected = chrono::DateTime::parse_from_rfc3339("2022-02-22T19:22:22Z")
+            .expect("timestamp")
+            .with_timezone(&chrono::Utc);
+        assert_eq!(
+            crate::caliber_id_timestamp(known),
+            Some(crate::tuple_extract::chrono_to_timestamp(expected).expect("convert"))
+        );
+
+        // Non-v7 IDs carry no timestamp
+        let v4 = pgrx::Uuid::from_bytes(*uuid::Uuid::new_v4().as_bytes());
+        assert_eq!(crate::caliber_id_timestamp(v4), None);
+    }
+
+    #[pg_test]
+    fn test_trajectory_lifecycle() {
+        // Clear storage first
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        // Create trajectory
+        let traj_id = crate::caliber_trajectory_create(
+            "Test Trajectory",
+            Some("Test description"),
+            None,
+            tenant_id,
+        );
+
+        // Get trajectory
+        let traj = crate::caliber_trajectory_get(traj_id, tenant_id);
+        assert!(traj.is_some());
+
+        // Update status
+        let updated = crate::caliber_trajectory_set_status(traj_id, "completed", tenant_id);
+        assert_eq!(updated, Some(true));
+
+        // Verify status change
+        let traj = crate::caliber_trajectory_get(traj_id, tenant_id);
+        assert!(traj.is_some());
+    }
+
+    #[pg_test]
+    fn test_audit_query() {
+        let tenant_id = test_tenant_id();
+
+        let agent_id =
+            crate::caliber_agent_register("planner", pgrx::JsonB(serde_json::json!([])), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Audited", None, Some(agent_id), tenant_id);
+        assert!(crate::caliber_trajectory_update(
+            traj_id,
+            pgrx::JsonB(serde_json::json!({"name": "Audited (renamed)"})),
+            tenant_id
+        ));
+        assert_eq!(
+            crate::caliber_trajectory_set_status(traj_id, "completed", tenant_id),
+            Some(true)
+        );
+        // A rejected mutation leaves no trace
+        assert_eq!(
+            crate::caliber_trajectory_set_status(traj_id, "done", tenant_id),
+            None
+        );
+
+        let history = crate::caliber_audit_query(traj_id, tenant_id).0;
+        let history = history.as_array().expect("should be an array");
+        let operations: Vec<&str> = history
+            .iter()
+            .filter_map(|entry| entry["operation"].as_str())
+            .collect();
+        assert_eq!(operations, vec!["create", "update", "set_status"]);
+        assert_eq!(history[0]["entity_type"], "Trajectory");
+        assert_eq!(history[0]["entity_id"], traj_id.to_string());
+        assert_eq!(history[0]["agent_id"], agent_id.to_string());
+        assert!(history[1]["agent_id"].is_null());
+        assert!(history[0]["at"].is_string());
+
+        let agent_history = crate::caliber_audit_query(agent_id, tenant_id).0;
+        assert_eq!(agent_history[0]["operation"], "register");
+
+        // Result envelope variants are audited like their plain counterparts
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let closed = crate::caliber_scope_close_result(scope_id, tenant_id).0;
+        assert_eq!(closed["ok"], true);
+        let scope_history = crate::caliber_audit_query(scope_id, tenant_id).0;
+        let scope_operations: Vec<&str> = scope_history
+            .as_array()
+            .expect("should be an array")
+            .iter()
+            .filter_map(|entry| entry["operation"].as_str())
+            .collect();
+        assert_eq!(scope_operations, vec!["create", "close"]);
+
+        let other_tenant = crate::caliber_new_id();
+        let hidden = crate::caliber_audit_query(traj_id, other_tenant).0;
+        assert_eq!(hidden.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_scope_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        // Create trajectory first
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+
+        // Create scope
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+
+        // Get scope
+        let scope = crate::caliber_scope_get(scope_id, tenant_id);
+        assert!(scope.is_some());
+
+        // Get current scope
+        let current = crate::caliber_scope_get_current(traj_id, tenant_id);
+        assert!(current.is_some());
+
+        // Close scope
+        let closed = crate::caliber_scope_close(scope_id, tenant_id);
+        assert!(closed);
+    }
+
+    #[pg_test]
+    fn test_scope_update() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        // Create trajectory first
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+
+        // Create scope
+        let scope_id = crate::caliber_scope_create(
+            traj_id,
+            "Test Scope",
+            Some("Initial purpose"),
+            8000,
+            tenant_id,
+        );
+
+        // Update scope with various fields
+        let updates = pgrx::JsonB(serde_json::json!({
+            "name": "Updated Scope",
+            "purpose": "Updated purpose",
+            "tokens_used": 100,
+            "metadata": {"key": "value"}
+        }));
+        let updated = crate::caliber_scope_update(scope_id, updates, tenant_id);
+        assert!(updated);
+
+        // Get scope and verify updates
+        let scope = crate::caliber_scope_get(scope_id, tenant_id);
+        assert!(scope.is_some());
+        let scope_data = scope.unwrap().0;
+        assert_eq!(scope_data["name"].as_str(), Some("Updated Scope"));
+        assert_eq!(scope_data["purpose"].as_str(), Some("Updated purpose"));
+        assert_eq!(scope_data["tokens_used"].as_i64(), Some(100));
+        assert!(scope_data["metadata"].is_object());
+
+        // Update with null values
+        let null_updates = pgrx::JsonB(serde_json::json!({
+            "purpose": null,
+            "metadata": null
+        }));
+        let updated_null = crate::caliber_scope_update(scope_id, null_updates, tenant_id);
+        assert!(updated_null);
+
+        // Verify null updates
+        let scope_after_null = crate::caliber_scope_get(scope_id, tenant_id);
+        assert!(scope_after_null.is_some());
+        let scope_null_data = scope_after_null.unwrap().0;
+        assert!(scope_null_data["purpose"].is_null());
+        assert!(scope_null_data["metadata"].is_null());
+    }
+
+    #[pg_test]
+    fn test_scope_clone() {
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Branching", None, None, tenant_id);
+        let scope_id =
+            crate::caliber_scope_create(traj_id, "main", Some("explore"), 5000, tenant_id);
+        crate::caliber_turn_create(scope_id, 1, "user", "Try approach A", 40, tenant_id)
+            .expect("turn should be created");
+        crate::caliber_turn_create(scope_id, 2, "assistant", "Approach A works", 60, tenant_id)
+            .expect("turn should be created");
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "finding",
+            "A is viable",
+            2,
+            "explicit",
+            Some(0.9),
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+        assert!(crate::caliber_scope_update_tokens(scope_id, 100, tenant_id));
+
+        let empty = crate::caliber_scope_clone(scope_id, "empty branch", false, tenant_id)
+            .expect("scope should be cloned");
+        let empty_scope = crate::caliber_scope_get(empty, tenant_id)
+            .expect("clone should exist")
+            .0;
+        assert_eq!(empty_scope["name"], "empty branch");
+        assert_eq!(empty_scope["purpose"], "explore");
+        assert_eq!(empty_scope["token_budget"], 5000);
+        assert_eq!(empty_scope["tokens_used"], 0);
+        assert_eq!(empty_scope["trajectory_id"], traj_id.to_string());
+        let empty_turns = crate::caliber_turn_get_by_scope(empty, tenant_id).0;
+        assert_eq!(empty_turns.as_array().map(|a| a.len()), Some(0));
+
+        let branch = crate::caliber_scope_clone(scope_id, "branch B", true, tenant_id)
+            .expect("scope should be cloned");
+        assert_ne!(branch, scope_id);
+        let branch_scope = crate::caliber_scope_get(branch, tenant_id)
+            .expect("clone should exist")
+            .0;
+        assert_eq!(branch_scope["tokens_used"], 100);
+
+        let turns = crate::caliber_turn_get_by_scope(branch, tenant_id).0;
+        let turns = turns.as_array().expect("should be an array");
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[1]["content"], "Approach A works");
+        assert_eq!(turns[1]["scope_id"], branch.to_string());
+
+        let artifacts = crate::caliber_artifact_query_by_scope(branch, tenant_id).0;
+        let artifacts = artifacts.as_array().expect("should be an array");
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0]["content"], "A is viable");
+        assert_ne!(artifacts[0]["artifact_id"], artifact_id.to_string());
+
+        // The source scope is untouched
+        let source_turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id).0;
+        assert_eq!(source_turns.as_array().map(|a| a.len()), Some(2));
+
+        let missing = crate::caliber_scope_clone(crate::caliber_new_id(), "x", true, tenant_id);
+        assert!(missing.is_none());
+    }
+
+    #[pg_test]
+    fn test_artifact_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+
+        // Create artifact
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Test Artifact",
+            "Test content",
+            0,
+            "explicit",
+            Some(0.9),
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        // Get artifact
+        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, false);
+        assert!(artifact.is_some());
+
+        // Query by type
+        let artifacts = crate::caliber_artifact_query_by_type(traj_id, "fact", tenant_id);
+        let arr: Vec<serde_json::Value> = serde_json::from_value(artifacts.0).unwrap();
+        assert!(!arr.is_empty());
+    }
+
+    #[pg_test]
+    fn test_artifact_update() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Test Artifact",
+            "Original content",
+            0,
+            "explicit",
+            Some(0.9),
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        let original_hash = crate::caliber_artifact_get(artifact_id, tenant_id, false)
+            .unwrap()
+            .0["content_hash"]
+            .clone();
+
+        // Update content and metadata
+        let updates = pgrx::JsonB(serde_json::json!({
+            "content": "Updated content",
+            "metadata": {"edited": true}
+        }));
+        assert!(crate::caliber_artifact_update(
+            artifact_id,
+            updates,
+            tenant_id
+        ));
+
+        let artifact_data = crate::caliber_artifact_get(artifact_id, tenant_id, false)
+            .unwrap()
+            .0;
+        assert_eq!(artifact_data["content"].as_str(), Some("Updated content"));
+        assert_ne!(artifact_data["content_hash"], original_hash);
+        assert!(artifact_data["metadata"].is_object());
+
+        // Clear metadata with null
+        let null_updates = pgrx::JsonB(serde_json::json!({ "metadata": null }));
+        assert!(crate::caliber_artifact_update(
+            artifact_id,
+            null_updates,
+            tenant_id
+        ));
+
+        let artifact_after_null = crate::caliber_artifact_get(artifact_id, tenant_id, false)
+            .unwrap()
+            .0;
+        assert!(artifact_after_null["metadata"].is_null());
+
+        // Empty update is rejected
+        let empty = pgrx::JsonB(serde_json::json!({}));
+        assert!(!crate::caliber_artifact_update(
+            artifact_id,
+            empty,
+            tenant_id
+        ));
+
+        // A malformed superseded_by is rejected instead of clearing the link
+        let bad_link = pgrx::JsonB(serde_json::json!({ "superseded_by": "not-a-uuid" }));
+        assert!(!crate::caliber_artifact_update(
+            artifact_id,
+            bad_link,
+            tenant_id
+        ));
+    }
+
+    #[pg_test]
+    fn test_artifact_duration_ttl_roundtrip() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Expiring Artifact",
+            "Test content",
+            0,
+            "explicit",
+            None,
+            "duration:60000",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        // Duration TTLs serialize to owned strings on every read path
+        for _ in 0..3 {
+            let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, false)
+                .unwrap()
+                .0;
+            assert_eq!(artifact["ttl"].as_str(), Some("duration:60000"));
+        }
+
+        let by_scope: Vec<serde_json::Value> =
+            serde_json::from_value(crate::caliber_artifact_query_by_scope(scope_id, tenant_id).0)
+                .unwrap();
+        assert_eq!(by_scope[0]["ttl"].as_str(), Some("duration:60000"));
+    }
+
+    #[pg_test]
+    fn test_create_returning() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj = crate::caliber_trajectory_create_returning("Test", None, None, tenant_id)
+            .expect("trajectory should be created")
+            .0;
+        assert_eq!(traj["name"].as_str(), Some("Test"));
+        assert!(traj["created_at"].is_string());
+        assert!(traj["updated_at"].is_string());
+
+        let traj_uuid = uuid::Uuid::parse_str(traj["trajectory_id"].as_str().unwrap()).unwrap();
+        let traj_id = pgrx::Uuid::from_bytes(*traj_uuid.as_bytes());
+        let scope =
+            crate::caliber_scope_create_returning(traj_id, "Test Scope", None, 8000, tenant_id)
+                .expect("scope should be created")
+                .0;
+        assert_eq!(scope["token_budget"].as_i64(), Some(8000));
+        assert!(scope["created_at"].is_string());
+
+        let note = crate::caliber_note_create_returning(
+            "fact",
+            "Test Note",
+            "Test content",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created")
+        .0;
+        assert_eq!(note["title"].as_str(), Some("Test Note"));
+        assert!(note["updated_at"].is_string());
+        assert_eq!(note["access_count"], 0);
+
+        // Invalid input still yields None
+        assert!(crate::caliber_note_create_returning(
+            "bogus",
+            "Test Note",
+            "Test content",
+            vec![],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .is_none());
+    }
+
+    #[pg_test]
+    fn test_note_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+
+        // Create note
+        let note_id = crate::caliber_note_create(
+            "fact",
+            "Test Note",
+            "Test content",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+
+        // Get note
+        let note = crate::caliber_note_get(note_id, tenant_id, false);
+        assert!(note.is_some());
+
+        // Query by trajectory
+        let notes = crate::caliber_note_query_by_trajectory(traj_id, tenant_id);
+        let arr: Vec<serde_json::Value> = serde_json::from_value(notes.0).unwrap();
+        assert!(!arr.is_empty());
+    }
+
+    #[pg_test]
+    fn test_note_update() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let note_id = crate::caliber_note_create(
+            "fact",
+            "Test Note",
+            "Test content",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+
+        let updates = pgrx::JsonB(serde_json::json!({
+            "title": "Edited Note",
+            "content": "Edited content",
+            "ttl": "long_term",
+            "abstraction_level": "summary"
+        }));
+        assert!(crate::caliber_note_update(note_id, updates, tenant_id));
+
+        let note_data = crate::caliber_note_get(note_id, tenant_id, false)
+            .unwrap()
+            .0;
+        assert_eq!(note_data["title"].as_str(), Some("Edited Note"));
+        assert_eq!(note_data["content"].as_str(), Some("Edited content"));
+        assert_eq!(note_data["ttl"].as_str(), Some("long_term"));
+
+        // Unknown values are rejected
+        let bad_level = pgrx::JsonB(serde_json::json!({ "abstraction_level": "L9" }));
+        assert!(!crate::caliber_note_update(note_id, bad_level, tenant_id));
+        let bad_ttl = pgrx::JsonB(serde_json::json!({ "ttl": "forever" }));
+        assert!(!crate::caliber_note_update(note_id, bad_ttl, tenant_id));
+        let bad_link = pgrx::JsonB(serde_json::json!({ "superseded_by": 42 }));
+        assert!(!crate::caliber_note_update(note_id, bad_link, tenant_id));
+    }
+
+    #[pg_test]
+    fn test_note_create_dedup_semantic() {
+        let tenant_id = test_tenant_id();
+
+        let existing = crate::caliber_note_create(
+            "fact",
+            "Build tool",
+            "The project builds with cargo",
+            vec![],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+        assert!(crate::caliber_embedding_set(
+            "note",
+            existing,
+            pgrx::JsonB(serde_json::json!([1.0, 0.0, 0.0])),
+            tenant_id
+        ));
+
+        let deduped = crate::caliber_note_create_dedup_semantic(
+            "fact",
+            "Build system",
+            "Cargo is used to build the project",
+            pgrx::JsonB(serde_json::json!([0.99, 0.05, 0.0])),
+            0.95,
+            tenant_id,
+        )
+        .expect("dedup should succeed")
+        .0;
+        assert_eq!(deduped["deduped"], true);
+        assert_eq!(deduped["existing_id"], existing.to_string());
+
+        let created = crate::caliber_note_create_dedup_semantic(
+            "fact",
+            "Test runner",
+            "Tests run with cargo pgrx test",
+            pgrx::JsonB(serde_json::json!([0.0, 1.0, 0.0])),
+            0.95,
+            tenant_id,
+        )
+        .expect("note should be created")
+        .0;
+        assert_eq!(created["deduped"], false);
+        let note_id = created["note_id"].as_str().expect("note_id should be set");
+        let note_id = pgrx::Uuid::from_bytes(*uuid::Uuid::parse_str(note_id).unwrap().as_bytes());
+        let embedding = crate::caliber_embedding_get("note", note_id, tenant_id)
+            .expect("embedding should be stored");
+        assert_eq!(embedding.0, serde_json::json!([0.0, 1.0, 0.0]));
+
+        let bad_threshold = crate::caliber_note_create_dedup_semantic(
+            "fact",
+            "Anything",
+            "Anything",
+            pgrx::JsonB(serde_json::json!([0.0, 0.0, 1.0])),
+            1.5,
+            tenant_id,
+        );
+        assert!(bad_threshold.is_none());
+    }
+
+    #[pg_test]
+    fn test_note_list_hot() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_a = crate::caliber_trajectory_create("A", None, None, tenant_id);
+        let traj_b = crate::caliber_trajectory_create("B", None, None, tenant_id);
+        let create_note = |traj: pgrx::Uuid, title: &str| {
+            crate::caliber_note_create(
+                "fact",
+                title,
+                "content",
+                vec![traj],
+                vec![],
+                "persistent",
+                tenant_id,
+            )
+            .expect("note should be created")
+        };
+        let note_a = create_note(traj_a, "Note A");
+        let note_b = create_note(traj_b, "Note B");
+        let note_c = create_note(traj_b, "Note C");
+
+        // Plain reads do not count as accesses
+        crate::caliber_note_get(note_c, tenant_id, false).expect("note C exists");
+        crate::caliber_note_query_by_trajectory(traj_b, tenant_id);
+
+        // B is used twice, A once, C never
+        assert!(crate::caliber_note_record_access(note_b, tenant_id));
+        assert!(crate::caliber_note_record_access(note_b, tenant_id));
+        assert!(crate::caliber_note_record_access(note_a, tenant_id));
+        assert!(!crate::caliber_note_record_access(note_a, test_tenant_id()));
+
+        let hot: Vec<serde_json::Value> =
+            serde_json::from_value(crate::caliber_note_list_hot(10, None, tenant_id).0).unwrap();
+        let ids: Vec<&str> = hot.iter().filter_map(|n| n["note_id"].as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![note_b.to_string(), note_a.to_string(), note_c.to_string()]
+        );
+        assert_eq!(hot[0]["access_count"], 2);
+        assert_eq!(hot[1]["access_count"], 1);
+        assert_eq!(hot[2]["access_count"], 0);
+
+        let top: Vec<serde_json::Value> =
+            serde_json::from_value(crate::caliber_note_list_hot(1, None, tenant_id).0).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0]["title"].as_str(), Some("Note B"));
+
+        let scoped: Vec<serde_json::Value> =
+            serde_json::from_value(crate::caliber_note_list_hot(10, Some(traj_a), tenant_id).0)
+                .unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0]["title"].as_str(), Some("Note A"));
+    }
+
+    #[pg_test]
+    fn test_note_query_by_category() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        crate::caliber_note_create(
+            "procedure",
+            "How to deploy",
+            "Run the pipeline",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+
+        let procedural: Vec<serde_json::Value> = serde_json::from_value(
+            crate::caliber_note_query_by_category("procedural", Some(traj_id), tenant_id).0,
+        )
+        .unwrap();
+        assert_eq!(procedural.len(), 1);
+        assert_eq!(
+            procedural[0]["memory_category"].as_str(),
+            Some("procedural")
+        );
+
+        let semantic: Vec<serde_json::Value> = serde_json::from_value(
+            crate::caliber_note_query_by_category("semantic", None, tenant_id).0,
+        )
+        .unwrap();
+        assert!(semantic.is_empty());
+
+        // Unknown category yields an empty result
+        let unknown: Vec<serde_json::Value> = serde_json::from_value(
+            crate::caliber_note_query_by_category("bogus", None, tenant_id).0,
+        )
+        .unwrap();
+        assert!(unknown.is_empty());
+    }
+
+    #[pg_test]
+    fn test_note_type_round_trip() {
+        let tenant_id = test_tenant_id();
+        let note_id = crate::caliber_note_create(
+            "convention",
+            "Naming",
+            "Use snake_case for SQL identifiers",
+            vec![],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("convention notes should be accepted");
+        let note = crate::caliber_note_get(note_id, tenant_id, false).expect("note exists");
+        assert_eq!(note.0["note_type"], "convention");
+
+        for note_type in crate::note_heap::NOTE_TYPES {
+            let id = crate::caliber_note_create(
+                note_type,
+                note_type,
+                note_type,
+                vec![],
+                vec![],
+                "persistent",
+                tenant_id,
+            )
+            .expect("every note type should be accepted");
+            let note = crate::caliber_note_get(id, tenant_id, false).expect("note exists");
+            assert_eq!(note.0["note_type"], *note_type);
+        }
+
+        assert!(crate::caliber_note_create(
+            "bogus",
+            "Bogus",
+            "Bogus",
+            vec![],
+            vec![],
+            "persistent",
+            tenant_id
+        )
+        .is_none());
+    }
+
+    #[pg_test]
+    fn test_notes_by_type() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Prefs", None, None, tenant_id);
+        let other_traj = crate::caliber_trajectory_create("Other", None, None, tenant_id);
+        for (note_type, title, traj) in [
+            ("preference", "Tabs", traj_id),
+            ("preference", "Dark mode", other_traj),
+            ("fact", "Rust edition", traj_id),
+        ] {
+            crate::caliber_note_create(
+                note_type,
+                title,
+                title,
+                vec![traj],
+                vec![],
+                "persistent",
+                tenant_id,
+            )
+            .expect("note should be created");
+        }
+
+        let all = crate::caliber_notes_by_type("preference", None, tenant_id);
+        assert_eq!(all.0.as_array().map(Vec::len), Some(2));
+
+        let scoped = crate::caliber_notes_by_type("preference", Some(traj_id), tenant_id);
+        let scoped = scoped.0.as_array().cloned().unwrap_or_default();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0]["title"], "Tabs");
+
+        let conventions = crate::caliber_notes_by_type("convention", None, tenant_id);
+        assert_eq!(conventions.0, serde_json::json!([]));
+        let unknown = crate::caliber_notes_by_type("bogus", None, tenant_id);
+        assert_eq!(unknown.0, serde_json::json!([]));
+    }
+
+    #[pg_test]
+    fn test_turn_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+
+        // Create turns
+        let _turn1 = crate::caliber_turn_create(scope_id, 1, "user", "Hello", 5, tenant_id);
+        let _turn2 =
+            crate::caliber_turn_create(scope_id, 2, "assistant", "Hi there!", 10, tenant_id);
+
+        // Get turns by scope
+        let turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id);
+        let arr: Vec<serde_json::Value> = serde_json::from_value(turns.0).unwrap();
+        assert_eq!(arr.len(), 2);
+    }
+
+    #[pg_test]
+    fn test_turn_update() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+        let turn_id = crate::caliber_turn_create(scope_id, 1, "tool", "pending", 1, tenant_id)
+            .expect("turn should be created");
+
+        let results = serde_json::json!([{"call_id": "c1", "output": "42"}]);
+        assert!(crate::caliber_turn_update(
+            turn_id,
+            pgrx::JsonB(serde_json::json!({
+                "content": "done",
+                "token_count": 3,
+                "tool_results": results,
+            })),
+            tenant_id,
+        ));
+
+        let turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id).0;
+        assert_eq!(turns[0]["content"], "done");
+        assert_eq!(turns[0]["token_count"], 3);
+        assert_eq!(turns[0]["tool_results"], results);
+        assert!(turns[0]["tool_calls"].is_null());
+
+        // Null clears a JSON field; empty or invalid updates are rejected
+        assert!(crate::caliber_turn_update(
+            turn_id,
+            pgrx::JsonB(serde_json::json!({ "tool_results": null })),
+            tenant_id,
+        ));
+        let turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id).0;
+        assert!(turns[0]["tool_results"].is_null());
+        assert!(!crate::caliber_turn_update(
+            turn_id,
+            pgrx::JsonB(serde_json::json!({})),
+            tenant_id
+        ));
+        assert!(!crate::caliber_turn_update(
+            turn_id,
+            pgrx::JsonB(serde_json::json!({ "token_count": -1 })),
+            tenant_id,
+        ));
+    }
+
+    #[pg_test]
+    fn test_agent_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        // Register agent
+        let caps = pgrx::JsonB(serde_json::json!(["rust", "python"]));
+        let agent_id = crate::caliber_agent_register("coder", caps, tenant_id);
+
+        // Get agent
+        let agent = crate::caliber_agent_get(agent_id, tenant_id);
+        assert!(agent.is_some());
+
+        // Update status
+        let updated = crate::caliber_agent_set_status(agent_id, "active", tenant_id);
+        assert!(updated);
+
+        // Heartbeat
+        let heartbeat = crate::caliber_agent_heartbeat(agent_id, tenant_id);
+        assert!(heartbeat);
+
+        // List by type
+        let agents = crate::caliber_agent_list_by_type("coder", tenant_id);
+        let arr: Vec<serde_json::Value> = serde_json::from_value(agents.0).unwrap();
+        assert!(!arr.is_empty());
+    }
+
+    #[pg_test]
+    fn test_agent_update() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!(["rust"]));
+        let agent_id = crate::caliber_agent_register("coder", caps, tenant_id);
+        let lead_id =
+            crate::caliber_agent_register("lead", pgrx::JsonB(serde_json::json!([])), tenant_id);
+
+        let updates = pgrx::JsonB(serde_json::json!({
+            "capabilities": ["rust", "sql"],
+            "can_delegate_to": ["reviewer"],
+            "reports_to": lead_id.to_string(),
+        }));
+        assert!(crate::caliber_agent_update(agent_id, updates, tenant_id));
+
+        let agent = crate::caliber_agent_get(agent_id, tenant_id).expect("agent should exist");
+        assert_eq!(agent.0["capabilities"], serde_json::json!(["rust", "sql"]));
+        assert_eq!(agent.0["can_delegate_to"], serde_json::json!(["reviewer"]));
+        assert_eq!(
+            agent.0["reports_to"],
+            serde_json::json!(lead_id.to_string())
+        );
+
+        // Self-reporting is rejected
+        let self_cycle = pgrx::JsonB(serde_json::json!({ "reports_to": agent_id.to_string() }));
+        assert!(!crate::caliber_agent_update(
+            agent_id, self_cycle, tenant_id
+        ));
+
+        // Empty update is rejected
+        assert!(!crate::caliber_agent_update(
+            agent_id,
+            pgrx::JsonB(serde_json::json!({})),
+            tenant_id
+        ));
+    }
+
+    #[pg_test]
+    fn test_agent_register_idempotent() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let first = crate::caliber_agent_register_idempotent(
+            "worker-1",
+            "coder",
+            pgrx::JsonB(serde_json::json!(["rust"])),
+            tenant_id,
+        )
+        .expect("agent should be registered");
+
+        // Re-registering with the same key returns the same agent
+        let second = crate::caliber_agent_register_idempotent(
+            "worker-1",
+            "coder",
+            pgrx::JsonB(serde_json::json!(["rust", "sql"])),
+            tenant_id,
+        )
+        .expect("agent should be found");
+        assert_eq!(first, second);
+
+        let agent = crate::caliber_agent_get(first, tenant_id).expect("agent should exist");
+        assert_eq!(agent.0["capabilities"], serde_json::json!(["rust", "sql"]));
+
+        // Malformed capabilities are rejected and leave the agent untouched
+        let malformed = crate::caliber_agent_register_idempotent(
+            "worker-1",
+            "coder",
+            pgrx::JsonB(serde_json::json!({"rust": true})),
+            tenant_id,
+        );
+        assert!(malformed.is_none());
+        let agent = crate::caliber_agent_get(first, tenant_id).expect("agent should exist");
+        assert_eq!(agent.0["capabilities"], serde_json::json!(["rust", "sql"]));
+
+        let other = crate::caliber_agent_register_idempotent(
+            "worker-2",
+            "coder",
+            pgrx::JsonB(serde_json::json!([])),
+            tenant_id,
+        );
+        assert_ne!(other, Some(first));
+    }
+
+    #[pg_test]
+    fn test_message_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let caps_value = serde_json::json!([]);
+        let agent1 =
+            crate::caliber_agent_register("sender", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let agent2 = crate::caliber_agent_register("receiver", pgrx::JsonB(caps_value), tenant_id);
+
+        // Send message
+        let msg_id = crate::caliber_message_send(
+            agent1,
+            Some(agent2),
+            None,
+            "heartbeat",
+            "{}",
+            None,
+            None,
+            vec![],
+            "normal",
+            None,
+            tenant_id,
+        )
+        .expect("message should be sent");
+
+        // Get message
+        let msg = crate::caliber_message_get(msg_id, tenant_id);
+        assert!(msg.is_some());
+
+        // Mark delivered
+        let delivered = crate::caliber_message_mark_delivered(msg_id, tenant_id);
+        assert!(delivered);
+
+        // Mark acknowledged
+        let acked = crate::caliber_message_mark_acknowledged(msg_id, tenant_id);
+        assert!(acked);
+    }
+
+    #[pg_test]
+    fn test_message_notify_payload() {
+        use caliber_core::EntityIdType;
+
+        let message_id = caliber_core::MessageId::now_v7();
+        let payload = crate::message_notify_payload(message_id, "interrupt", "critical");
+        let parsed: serde_json::Value = serde_json::from_str(&payload).expect("payload is JSON");
+        assert_eq!(parsed["message_id"], message_id.to_string());
+        assert_eq!(parsed["message_type"], "interrupt");
+        assert_eq!(parsed["priority"], "critical");
+
+        // Oversized payloads fall back to the bare message ID
+        let huge = "x".repeat(crate::NOTIFY_PAYLOAD_MAX_BYTES);
+        let fallback = crate::message_notify_payload(message_id, &huge, "low");
+        assert_eq!(fallback, message_id.to_string());
+    }
+
+    #[pg_test]
+    fn test_agent_channel_names() {
+        let agent_id = pgrx::Uuid::from_bytes([0x11; 16]);
+        assert_eq!(
+            crate::caliber_agent_channel(agent_id),
+            "caliber_agent_11111111-1111-1111-1111-111111111111"
+        );
+        assert_eq!(
+            crate::caliber_agent_type_channel("coder"),
+            "caliber_agent_type_coder"
+        );
+        assert_eq!(crate::AGENT_BROADCAST_CHANNEL, "caliber_agent_broadcast");
+    }
+
+    #[pg_test]
+    fn test_message_send_hostile_agent_type() {
+        let tenant_id = test_tenant_id();
+        let caps_value = serde_json::json!([]);
+        let sender = crate::caliber_agent_register("sender", pgrx::JsonB(caps_value), tenant_id);
+
+        // A crafted agent type must not break out of the notification
+        let msg_id = crate::caliber_message_send(
+            sender,
+            None,
+            Some("x'; DROP TABLE caliber_message; --"),
+            "heartbeat",
+            "{}",
+            None,
+            None,
+            vec![],
+            "normal",
+            None,
+            tenant_id,
+        )
+        .expect("message should be sent");
+
+        assert!(crate::caliber_message_get(msg_id, tenant_id).is_some());
+    }
+
+    #[pg_test]
+    fn test_message_send_rejects_unnotifiable_agent_type() {
+        let tenant_id = test_tenant_id();
+        let sender =
+            crate::caliber_agent_register("sender", pgrx::JsonB(serde_json::json!([])), tenant_id);
+        let long_type = "a".repeat(64);
+        let send = |agent_type: &str| {
+            crate::caliber_message_send(
+                sender,
+                None,
+                Some(agent_type),
+                "heartbeat",
+                "{}",
+                None,
+                None,
+                vec![],
+                "normal",
+                None,
+                tenant_id,
+            )
+        };
+
+        // The channel name would exceed pg_notify's 63-byte limit
+        assert!(send(&long_type).is_none());
+        let batch = crate::caliber_message_send_batch(
+            sender,
+            pgrx::JsonB(serde_json::json!([
+                {"to_agent_type": long_type, "message_type": "heartbeat", "payload": "{}"}
+            ])),
+            tenant_id,
+        );
+        assert_eq!(batch.0, serde_json::json!([]));
+
+        // The longest accepted type still sends
+        let max_type = "a".repeat(crate::NOTIFY_CHANNEL_MAX_BYTES - "caliber_agent_type_".len());
+        assert!(send(&max_type).is_some());
+    }
+
+    #[pg_test]
+    fn test_message_send_batch() {
+        let tenant_id = test_tenant_id();
+        let caps = || pgrx::JsonB(serde_json::json!([]));
+        let planner = crate::caliber_agent_register("planner", caps(), tenant_id);
+        let worker_a = crate::caliber_agent_register("worker", caps(), tenant_id);
+        let worker_b = crate::caliber_agent_register("worker", caps(), tenant_id);
+
+        let sent = crate::caliber_message_send_batch(
+            planner,
+            pgrx::JsonB(serde_json::json!([
+                {
+                    "to_agent_id": worker_a.to_string(),
+                    "message_type": "task_delegation",
+                    "payload": "part 1",
+                    "priority": "high",
+                },
+                {
+                    "to_agent_id": worker_b.to_string(),
+                    "message_type": "task_delegation",
+                    "payload": "part 2",
+                },
+            ])),
+            tenant_id,
+        );
+        let ids = sent.0.as_array().expect("should be an array");
+        assert_eq!(ids.len(), 2);
+
+        let inbox = crate::caliber_message_get_pending(worker_a, "worker", tenant_id).0;
+        assert_eq!(inbox[0]["message_id"], ids[0]);
+        assert_eq!(inbox[0]["priority"], "high");
+        assert_eq!(inbox[0]["payload"], "part 1");
+
+        // One bad entry rejects the whole batch
+        let rejected = crate::caliber_message_send_batch(
+            planner,
+            pgrx::JsonB(serde_json::json!([
+                { "to_agent_id": worker_a.to_string(), "message_type": "task_delegation", "payload": "ok" },
+                { "to_agent_id": worker_b.to_string(), "message_type": "gossip", "payload": "bad" },
+            ])),
+            tenant_id,
+        );
+        assert_eq!(rejected.0, serde_json::json!([]));
+        let inbox = crate::caliber_message_get_pending(worker_a, "worker", tenant_id).0;
+        assert_eq!(inbox.as_array().map(|a| a.len()), Some(1));
+    }
+
+    #[pg_test]
+    fn test_message_query() {
+        let tenant_id = test_tenant_id();
+
+        let caps_value = serde_json::json!([]);
+        let sender =
+            crate::caliber_agent_register("sender", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let receiver =
+            crate::caliber_agent_register("receiver", pgrx::JsonB(caps_value), tenant_id);
+
+        let send = |message_type: &str, priority: &str| {
+            crate::caliber_message_send(
+                sender,
+                Some(receiver),
+                None,
+                message_type,
+                "{}",
+                None,
+                None,
+                vec![],
+                priority,
+                None,
+                tenant_id,
+            )
+            .expect("message should be sent")
+        };
+        let heartbeat = send("heartbeat", "low");
+        send("interrupt", "critical");
+        send("context_share", "high");
+        assert!(crate::caliber_message_mark_delivered(heartbeat, tenant_id));
+
+        // Delivered messages are included
+        let all = crate::caliber_message_query(Some(receiver), None, None, None, 10, tenant_id);
+        assert_eq!(all.0.as_array().map(|a| a.len()), Some(3));
+
+        let urgent =
+            crate::caliber_message_query(Some(receiver), None, Some("high"), None, 10, tenant_id);
+        assert_eq!(urgent.0.as_array().map(|a| a.len()), Some(2));
+
+        let by_type = crate::caliber_message_query(
+            None,
+            Some("interrupt"),
+            Some("critical"),
+            Some(0),
+            10,
+            tenant_id,
+        );
+        assert_eq!(by_type.0.as_array().map(|a| a.len()), Some(1));
+        assert_eq!(by_type.0[0]["message_type"], "interrupt");
+
+        let limited = crate::caliber_message_query(None, None, None, None, 1, tenant_id);
+        assert_eq!(limited.0.as_array().map(|a| a.len()), Some(1));
+
+        let future = crate::caliber_message_query(
+            None,
+            None,
+            None,
+            Some(chrono::Utc::now().timestamp_millis() + 60_000),
+            10,
+            tenant_id,
+        );
+        assert_eq!(future.0.as_array().map(|a| a.len()), Some(0));
+
+        let invalid = crate::caliber_message_query(None, Some("bogus"), None, None, 10, tenant_id);
+        assert_eq!(invalid.0.as_array().map(|a| a.len()), Some(0));
+        let invalid = crate::caliber_message_query(None, None, Some("urgent"), None, 10, tenant_id);
+        assert_eq!(invalid.0.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_delegation_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let caps_value = serde_json::json!([]);
+        let delegator =
+            crate::caliber_agent_register("planner", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let delegatee = crate::caliber_agent_register("coder", pgrx::JsonB(caps_value), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Parent Task", None, None, tenant_id);
+
+        // Create delegation
+        let delegation_id = crate::caliber_delegation_create(
+            delegator,
+            Some(delegatee),
+            None,
+            "Implement feature X",
+            traj_id,
+            tenant_id,
+        );
+
+        // Get delegation
+        let delegation = crate::caliber_delegation_get(delegation_id, tenant_id);
+        assert!(delegation.is_some());
+
+        // Accept delegation
+        let child_traj = crate::caliber_trajectory_create("Child Task", None, None, tenant_id);
+        let accepted =
+            crate::caliber_delegation_accept(delegation_id, delegatee, child_traj, tenant_id);
+        assert!(accepted);
+
+        // Only the accepted delegatee can start it, and only once
+        assert!(!crate::caliber_delegation_start(
+            delegation_id,
+            delegator,
+            tenant_id
+        ));
+        assert!(crate::caliber_delegation_start(
+            delegation_id,
+            delegatee,
+            tenant_id
+        ));
+        assert!(!crate::caliber_delegation_start(
+            delegation_id,
+            delegatee,
+            tenant_id
+        ));
+        let started = crate::caliber_delegation_get(delegation_id, tenant_id)
+            .expect("delegation should exist");
+        assert_eq!(started.0["status"], "in_progress");
+
+        // Complete delegation
+        let completed = crate::caliber_delegation_complete(delegation_id, true, "Done!", tenant_id);
+        assert!(completed);
+    }
+
+    #[pg_test]
+    fn test_delegation_find_for_agent() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let delegator =
+            crate::caliber_agent_register("planner", pgrx::JsonB(serde_json::json!([])), tenant_id);
+        let worker = crate::caliber_agent_register(
+            "generalist",
+            pgrx::JsonB(serde_json::json!(["Rust"])),
+            tenant_id,
+        );
+        let traj_id = crate::caliber_trajectory_create("Parent Task", None, None, tenant_id);
+
+        // Matches on capability mentioned in the task description
+        crate::caliber_delegation_create(
+            delegator,
+            None,
+            Some("coder"),
+            "Port the parser to rust",
+            traj_id,
+            tenant_id,
+        );
+        // Matches neither type nor capability
+        crate::caliber_delegation_create(
+            delegator,
+            None,
+            Some("writer"),
+            "Draft release notes",
+            traj_id,
+            tenant_id,
+        );
+
+        // Capabilities match whole words only
+        crate::caliber_delegation_create(
+            delegator,
+            None,
+            Some("auditor"),
+            "Review trust boundaries",
+            traj_id,
+            tenant_id,
+        );
+        // Matches on required_capabilities in constraints
+        let required = crate::caliber_delegation_create(
+            delegator,
+            None,
+            Some("reviewer"),
+            "Review the borrow checker changes",
+            traj_id,
+            tenant_id,
+        );
+        Spi::run(&format!(
+            "UPDATE caliber_delegation SET constraints = '{{\"required_capabilities\": [\"rust\"]}}' WHERE delegation_id = '{}'",
+            required
+        ))
+        .expect("constraints should be set");
+
+        // Targeted delegations only match their target agent
+        let other = crate::caliber_agent_register(
+            "generalist",
+            pgrx::JsonB(serde_json::json!(["Rust"])),
+            tenant_id,
+        );
+        let targeted_other = crate::caliber_delegation_create(
+            delegator,
+            Some(other),
+            Some("generalist"),
+            "Fix the rust lints",
+            traj_id,
+            tenant_id,
+        );
+        crate::caliber_delegation_create(
+            delegator,
+            Some(worker),
+            None,
+            "Update the changelog",
+            traj_id,
+            tenant_id,
+        );
+
+        let found = crate::caliber_delegation_find_for_agent(worker, tenant_id);
+        let arr = found.0.as_array().expect("should be an array");
+        let mut descriptions: Vec<&str> = arr
+            .iter()
+            .filter_map(|d| d["task_description"].as_str())
+            .collect();
+        descriptions.sort();
+        assert_eq!(
+            descriptions,
+            vec![
+                "Port the parser to rust",
+                "Review the borrow checker changes",
+                "Update the changelog"
+            ]
+        );
+
+        // Targeted delegations are hidden from type listings and cannot be
+        // accepted by another agent
+        let listed = crate::caliber_delegation_list_pending("generalist", tenant_id).0;
+        assert_eq!(listed.as_array().map(|a| a.len()), Some(0));
+        let child = crate::caliber_trajectory_create("Child Task", None, None, tenant_id);
+        assert!(!crate::caliber_delegation_accept(
+            targeted_other,
+            worker,
+            child,
+            tenant_id
+        ));
+        assert!(crate::caliber_delegation_accept(
+            targeted_other,
+            other,
+            child,
+            tenant_id
+        ));
+    }
+
+    #[pg_test]
+    fn test_delegation_tree() {
+        let tenant_id = test_tenant_id();
+
+        let caps = || pgrx::JsonB(serde_json::json!([]));
+        let planner = crate::caliber_agent_register("planner", caps(), tenant_id);
+        let coder = crate::caliber_agent_register("coder", caps(), tenant_id);
+        let tester = crate::caliber_agent_register("tester", caps(), tenant_id);
+        let root = crate::caliber_trajectory_create("Root Task", None, None, tenant_id);
+        let child = crate::caliber_trajectory_create("Child Task", None, None, tenant_id);
+        let grandchild = crate::caliber_trajectory_create("Grandchild Task", None, None, tenant_id);
+
+        let top = crate::caliber_delegation_create(
+            planner,
+            Some(coder),
+            None,
+            "Build the feature",
+            root,
+            tenant_id,
+        );
+        assert!(crate::caliber_delegation_accept(
+            top, coder, child, tenant_id
+        ));
+        let nested = crate::caliber_delegation_create(
+            coder,
+            Some(tester),
+            None,
+            "Test the feature",
+            child,
+            tenant_id,
+        );
+        assert!(crate::caliber_delegation_accept(
+            nested, tester, grandchild, tenant_id
+        ));
+        // Delegating back into the root must not loop forever
+        let back = crate::caliber_delegation_create(
+            tester,
+            Some(planner),
+            None,
+            "Review the plan",
+            grandchild,
+            tenant_id,
+        );
+        assert!(crate::caliber_delegation_accept(
+            back, planner, root, tenant_id
+        ));
+
+        let tree = crate::caliber_delegation_tree(root, tenant_id).0;
+        assert_eq!(tree["trajectory_id"], root.to_string());
+        let top_nodes = tree["delegations"].as_array().expect("should be an array");
+        assert_eq!(top_nodes.len(), 1);
+        assert_eq!(top_nodes[0]["delegation_id"], top.to_string());
+        assert_eq!(top_nodes[0]["status"], "accepted");
+
+        let second = top_nodes[0]["children"]
+            .as_array()
+            .expect("should be an array");
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0]["delegation_id"], nested.to_string());
+
+        let third = second[0]["children"]
+            .as_array()
+            .expect("should be an array");
+        assert_eq!(third.len(), 1);
+        assert_eq!(third[0]["status"], "accepted");
+        assert_eq!(third[0]["children"].as_array().map(|a| a.len()), Some(0));
+
+        let leaf = crate::caliber_delegation_tree(grandchild, tenant_id).0;
+        assert_eq!(leaf["delegations"].as_array().map(|a| a.len()), Some(1));
+    }
+
+    #[pg_test]
+    fn test_delegation_list_by_delegator() {
+        let tenant_id = test_tenant_id();
+
+        let caps = || pgrx::JsonB(serde_json::json!([]));
+        let planner = crate::caliber_agent_register("planner", caps(), tenant_id);
+        let other = crate::caliber_agent_register("planner", caps(), tenant_id);
+        let coder = crate::caliber_agent_register("coder", caps(), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Parent Task", None, None, tenant_id);
+
+        let first = crate::caliber_delegation_create(
+            planner,
+            Some(coder),
+            None,
+            "Write the parser",
+            traj_id,
+            tenant_id,
+        );
+        crate::caliber_delegation_create(
+            planner,
+            None,
+            Some("coder"),
+            "Write the printer",
+            traj_id,
+            tenant_id,
+        );
+        crate::caliber_delegation_create(
+            other,
+            None,
+            Some("coder"),
+            "Unrelated",
+            traj_id,
+            tenant_id,
+        );
+
+        let child_traj = crate::caliber_trajectory_create("Child Task", None, None, tenant_id);
+        assert!(crate::caliber_delegation_accept(
+            first, coder, child_traj, tenant_id
+        ));
+
+        let all = crate::caliber_delegation_list_by_delegator(planner, None, tenant_id).0;
+        let all = all.as_array().expect("should be an array");
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0]["task_description"], "Write the parser");
+        assert_eq!(all[0]["status"], "accepted");
+
+        let pending =
+            crate::caliber_delegation_list_by_delegator(planner, Some("pending"), tenant_id).0;
+        let pending = pending.as_array().expect("should be an array");
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0]["task_description"], "Write the printer");
+
+        let invalid =
+            crate::caliber_delegation_list_by_delegator(planner, Some("done"), tenant_id).0;
+        assert_eq!(invalid.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_handoff_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let caps_value = serde_json::json!([]);
+        let agent1 =
+            crate::caliber_agent_register("generalist", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let agent2 =
+            crate::caliber_agent_register("specialist", pgrx::JsonB(caps_value), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Task", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 8000, tenant_id);
+        let snapshot_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "summary",
+            "Context snapshot",
+            "Working context for the handoff",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("snapshot artifact should be created");
+
+        // Create handoff
+        let handoff_id = crate::caliber_handoff_create(
+            agent1,
+            Some(agent2),
+            None,
+            traj_id,
+            scope_id,
+            snapshot_id,
+            "specialization",
+            tenant_id,
+        );
+
+        // Get handoff
+        let handoff = crate::caliber_handoff_get(handoff_id, tenant_id);
+        assert!(handoff.is_some());
+
+        // Accept handoff
+        let accepted = crate::caliber_handoff_accept(handoff_id, agent2, tenant_id);
+        assert!(accepted);
+
+        // Complete handoff
+        let completed = crate::caliber_handoff_complete(handoff_id, tenant_id);
+        assert!(completed);
+    }
+
+    #[pg_test]
+    fn test_handoff_list_pending() {
+        let tenant_id = test_tenant_id();
+
+        let caps = || pgrx::JsonB(serde_json::json!([]));
+        let sender = crate::caliber_agent_register("generalist", caps(), tenant_id);
+        let receiver = crate::caliber_agent_register("specialist", caps(), tenant_id);
+        let bystander = crate::caliber_agent_register("writer", caps(), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Task", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 8000, tenant_id);
+        let snapshot_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "summary",
+            "Context snapshot",
+            "Working context",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("snapshot artifact should be created");
+
+        let handoff = |to_agent: Option<pgrx::Uuid>, to_type: Option<&str>| {
+            crate::caliber_handoff_create(
+                sender,
+                to_agent,
+                to_type,
+                traj_id,
+                scope_id,
+                snapshot_id,
+                "specialization",
+                tenant_id,
+            )
+        };
+        let direct = handoff(Some(receiver), None);
+        let by_type = handoff(None, Some("specialist"));
+        let accepted = handoff(Some(receiver), None);
+        handoff(Some(bystander), None);
+        assert!(crate::caliber_handoff_accept(accepted, receiver, tenant_id));
+
+        let pending = crate::caliber_handoff_list_pending(receiver, "specialist", tenant_id).0;
+        let ids: Vec<String> = pending
+            .as_array()
+            .expect("should be an array")
+            .iter()
+            .filter_map(|h| h["handoff_id"].as_str().map(str::to_string))
+            .collect();
+        assert_eq!(ids, vec![direct.to_string(), by_type.to_string()]);
+    }
+
+    #[pg_test]
+    fn test_handoff_snapshot_validation() {
+        use caliber_core::TenantId;
+
+        let tenant_id = test_tenant_id();
+        let tenant = crate::id_from_pgrx::<TenantId>(tenant_id);
+
+        let traj_id = crate::caliber_trajectory_create("Task", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 8000, tenant_id);
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "summary",
+            "Context snapshot",
+            "Working context",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        let exists = |id| crate::handoff_snapshot_exists(id, tenant).expect("lookup should work");
+        assert!(exists(artifact_id));
+        // Random ids and scopes without a checkpoint are dangling
+        assert!(!exists(crate::caliber_new_id()));
+        assert!(!exists(scope_id));
+    }
+
+    #[pg_test]
+    fn test_conflict_lifecycle() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+
+        let artifact_a = crate::caliber_new_id();
+        let artifact_b = crate::caliber_new_id();
+
+        // Create conflict
+        let conflict_id = crate::caliber_conflict_create(
+            "contradicting_fact",
+            "artifact",
+            artifact_a,
+            "artifact",
+            artifact_b,
+            tenant_id,
+        );
+
+        // Get conflict
+        let conflict = crate::caliber_conflict_get(conflict_id, tenant_id);
+        assert!(conflict.is_some());
+
+        // List unresolved
+        let unresolved = crate::caliber_conflict_list_unresolved(tenant_id);
+        let arr: Vec<serde_json::Value> = serde_json::from_value(unresolved.0).unwrap();
+        assert!(!arr.is_empty());
+
+        // Resolve conflict
+        // highest_confidence needs real items; these ids don't exist
+        let resolved = crate::caliber_conflict_resolve(
+            conflict_id,
+            "last_write_wins",
+            Some("a"),
+            "Artifact A was written last",
+            tenant_id,
+        );
+        assert!(resolved);
+    }
+
+    #[pg_test]
+    fn test_conflict_resolve_highest_confidence() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Confidence", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create = |name: &str, confidence: Option<f32>| {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                name,
+                name,
+                0,
+                "explicit",
+                confidence,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created")
+        };
+        let artifact_a = create("A", Some(0.9));
+        let artifact_b = create("B", Some(0.4));
+        let artifact_c = create("C", None);
+
+        // Caller passes no winner; A wins on confidence
+        let conflict_id = crate::caliber_conflict_create(
+            "contradicting_fact",
+            "artifact",
+            artifact_a,
+            "artifact",
+            artifact_b,
+            tenant_id,
+        );
+        assert!(crate::caliber_conflict_resolve(
+            conflict_id,
+            "highest_confidence",
+            None,
+            "auto",
+            tenant_id,
+        ));
+        let conflict = crate::caliber_conflict_get(conflict_id, tenant_id).expect("conflict");
+        assert_eq!(conflict.0["resolution"]["winner"], "a");
+
+        // Missing confidence refuses to resolve
+        let conflict_id = crate::caliber_conflict_create(
+            "contradicting_fact",
+            "artifact",
+            artifact_a,
+            "artifact",
+            artifact_c,
+            tenant_id,
+        );
+        assert!(!crate::caliber_conflict_resolve(
+            conflict_id,
+            "highest_confidence",
+            Some("b"),
+            "auto",
+            tenant_id,
+        ));
+    }
+
+    #[pg_test]
+    fn test_conflict_resolve_merge_facts() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Merge", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create = |name: &str, content: &str, confidence: f32| {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                name,
+                content,
+                0,
+                "explicit",
+                Some(confidence),
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created")
+        };
+        let fact_a = create("Port A", "The service listens on 8080", 0.9);
+        let fact_b = create("Port B", "The service listens on 9090", 0.6);
+
+        let conflict_id = crate::caliber_conflict_create(
+            "contradicting_fact",
+            "artifact",
+            fact_a,
+            "artifact",
+            fact_b,
+            tenant_id,
+        );
+        assert!(crate::caliber_conflict_resolve(
+            conflict_id,
+            "merge",
+            None,
+            "both ports are configured",
+            tenant_id,
+        ));
+
+        let conflict = crate::caliber_conflict_get(conflict_id, tenant_id).expect("conflict");
+        let merged_id = conflict.0["resolution"]["merged_result_id"]
+            .as_str()
+            .expect("merged_result_id should be set")
+            .to_string();
+
+        let a = crate::caliber_artifact_get(fact_a, tenant_id, false).expect("artifact a");
+        let b = crate::caliber_artifact_get(fact_b, tenant_id, false).expect("artifact b");
+        assert_eq!(a.0["superseded_by"], merged_id.as_str());
+        assert_eq!(b.0["superseded_by"], merged_id.as_str());
+
+        let merged = crate::caliber_artifact_query_by_type(traj_id, "fact", tenant_id).0;
+        let merged = merged
+            .as_array()
+            .expect("should be an array")
+            .iter()
+            .find(|art| art["artifact_id"] == merged_id.as_str())
+            .expect("merged artifact should exist")
+            .clone();
+        let content = merged["content"].as_str().unwrap_or_default();
+        assert!(content.contains("8080") && content.contains("9090"));
+
+        // A resolved conflict cannot be merged again
+        assert!(!crate::caliber_conflict_resolve(
+            conflict_id,
+            "merge",
+            None,
+            "merge again",
+            tenant_id,
+        ));
+        let conflict = crate::caliber_conflict_get(conflict_id, tenant_id).expect("conflict");
+        assert_eq!(
+            conflict.0["resolution"]["merged_result_id"],
+            merged_id.as_str()
+        );
+        assert_eq!(
+            conflict.0["resolution"]["reason"],
+            "both ports are configured"
+        );
+        assert!(content.contains(&fact_a.to_string()));
+        assert!(merged["superseded_by"].is_null());
+
+        let merged_uuid = uuid::Uuid::parse_str(&merged_id).expect("merged id");
+        let history =
+            crate::caliber_audit_query(pgrx::Uuid::from_bytes(*merged_uuid.as_bytes()), tenant_id)
+                .0;
+        assert_eq!(history[0]["operation"], "create");
+    }
+
+    #[pg_test]
+    fn test_conflict_auto_resolve() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let owner = crate::caliber_agent_register("owner", caps, tenant_id);
+        let region_id = crate::caliber_region_create(owner, "team", None, false, tenant_id)
+            .expect("region should be created");
+        let traj_id = crate::caliber_trajectory_create("Auto", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create = |name: &str, confidence: f32| {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                name,
+                name,
+                0,
+                "explicit",
+                Some(confidence),
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created")
+        };
+        let older = create("Older", 0.9);
+        let newer = create("Newer", 0.4);
+        let conflict = || {
+            crate::caliber_conflict_create(
+                "contradicting_fact",
+                "artifact",
+                older,
+                "artifact",
+                newer,
+                tenant_id,
+            )
+        };
+
+        // Team regions default to last_write_wins. Rows from one transaction
+        // share created_at, so the tie is refused rather than guessed
+        let tied = conflict();
+        assert!(!crate::caliber_conflict_auto_resolve(
+            tied, region_id, tenant_id
+        ));
+
+        // Once the items' write times differ, the later write wins
+        Spi::run(&format!(
+            "UPDATE caliber_artifact SET created_at = created_at - interval '1 hour' WHERE artifact_id = '{}'",
+            older
+        ))
+        .expect("backdate artifact");
+        let by_time = conflict();
+        assert!(crate::caliber_conflict_auto_resolve(
+            by_time, region_id, tenant_id
+        ));
+        let resolved = crate::caliber_conflict_get(by_time, tenant_id).expect("conflict");
+        assert_eq!(resolved.0["status"], "resolved");
+        assert_eq!(resolved.0["resolution"]["winner"], "b");
+
+        assert!(crate::caliber_region_update(
+            region_id,
+            pgrx::JsonB(serde_json::json!({"conflict_resolution": "highest_confidence"})),
+            tenant_id,
+        ));
+        let by_confidence = conflict();
+        assert!(crate::caliber_conflict_auto_resolve(
+            by_confidence,
+            region_id,
+            tenant_id
+        ));
+        let resolved = crate::caliber_conflict_get(by_confidence, tenant_id).expect("conflict");
+        assert_eq!(resolved.0["status"], "resolved");
+        assert_eq!(resolved.0["resolution"]["winner"], "a");
+
+        // Settled conflicts are left alone
+        assert!(!crate::caliber_conflict_auto_resolve(
+            by_confidence,
+            region_id,
+            tenant_id
+        ));
+
+        assert!(crate::caliber_region_update(
+            region_id,
+            pgrx::JsonB(serde_json::json!({"conflict_resolution": "escalate"})),
+            tenant_id,
+        ));
+        let escalated = conflict();
+        assert!(crate::caliber_conflict_auto_resolve(
+            escalated, region_id, tenant_id
+        ));
+        let conflict_json = crate::caliber_conflict_get(escalated, tenant_id).expect("conflict");
+        assert_eq!(conflict_json.0["status"], "escalated");
+        assert!(conflict_json.0["resolved_at"].is_null());
+    }
+
+    #[pg_test]
+    fn test_vector_search_rejects_unknown_metric() {
+        let query = pgrx::JsonB(serde_json::json!([0.1, 0.2, 0.3]));
+        let results = crate::caliber_vector_search(query, 10, "manhattan");
+        assert_eq!(results.0, serde_json::json!([]));
+    }
+
+    #[pg_test]
+    fn test_create_vector_index() {
+        let tenant_id = test_tenant_id();
+        let create = crate::caliber_create_vector_index;
+
+        // Without stored embeddings there is nothing to index
+        Spi::run("UPDATE caliber_note SET embedding = NULL").expect("clear note embeddings");
+        assert!(!create("caliber_note", "hnsw", None, None, None));
+
+        let traj_id = crate::caliber_trajectory_create("Indexed", None, None, tenant_id);
+        let note_id = crate::caliber_note_create(
+            "fact",
+            "Embedded",
+            "Indexed note",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+        assert!(crate::caliber_embedding_set(
+            "note",
+            note_id,
+            pgrx::JsonB(serde_json::json!([1.0, 0.0, 0.0])),
+            tenant_id
+        ));
+
+        // Invalid parameters are rejected before any DDL runs
+        assert!(!create("caliber_turn", "hnsw", None, None, None));
+        assert!(!create("caliber_note", "btree", None, None, None));
+        assert!(!create("caliber_note", "hnsw", None, None, Some(100)));
+        assert!(!create("caliber_note", "ivfflat", Some(16), None, None));
+        assert!(!create("caliber_note", "hnsw", Some(1), None, None));
+        assert!(!create("caliber_note", "hnsw", Some(32), Some(40), None));
+
+        // The untyped embedding column is indexed through a vector(3) cast,
+        // the same expression vector search orders by
+        assert!(create("caliber_note", "hnsw", Some(16), Some(64), None));
+        let exists = Spi::get_one::<bool>(
+            "SELECT EXISTS (SELECT 1 FROM pg_indexes WHERE indexname = 'idx_note_embedding_hnsw_3')",
+        )
+        .expect("query pg_indexes");
+        assert_eq!(exists, Some(true));
+
+        // Repeat calls are idempotent
+        assert!(create("caliber_note", "hnsw", Some(16), Some(64), None));
+
+        // Search compares through the indexed expression
+        let results = crate::caliber_vector_search(
+            pgrx::JsonB(serde_json::json!([1.0, 0.0, 0.0])),
+            10,
+            "cosine",
+        );
+        let hits = results.0.as_array().expect("search results array");
+        assert!(hits
+            .iter()
+            .any(|hit| hit["entity_id"] == note_id.to_string()));
+    }
+
+    #[pg_test]
+    fn test_index_status_and_reindex() {
+        let status = crate::caliber_index_status();
+        let indexes = status.0.as_array().expect("index status array");
+        let trajectory_pk = indexes
+            .iter()
+            .find(|idx| idx["index_name"] == "caliber_trajectory_pkey")
+            .expect("trajectory primary key listed");
+        assert_eq!(trajectory_pk["table_name"], "caliber_trajectory");
+        assert_eq!(trajectory_pk["valid"], true);
+        assert!(trajectory_pk["size_bytes"].as_i64().unwrap_or(-1) >= 0);
+
+        assert!(crate::caliber_reindex("caliber_trajectory"));
+        assert!(!crate::caliber_reindex("caliber_config"));
+        assert!(!crate::caliber_reindex(
+            "caliber_note; DROP TABLE caliber_note"
+        ));
     }
 
-    fn scope_insert(&self, s: &Scope) -> CaliberResult<()> {
-        // Check if already exists using heap module
-        if scope_heap::scope_get_heap(s.scope_id, TenantId::nil())?.is_some() {
-            return Err(CaliberError::Storage(StorageError::InsertFailed {
-                entity_type: EntityType::Scope,
-                reason: "already exists".to_string(),
-            }));
-        }
-        scope_heap::scope_create_heap(
-            s.scope_id,
-            s.trajectory_id,
-            &s.name,
-            s.purpose.as_deref(),
-            s.token_budget,
-            TenantId::nil(),
-        )?;
-        Ok(())
+    #[pg_test]
+    fn test_hybrid_search_recency_and_tenant_isolation() {
+        let tenant_id = test_tenant_id();
+        let other_tenant = test_tenant_id();
+
+        let create_embedded_note = |tenant: pgrx::Uuid, embedding: serde_json::Value| {
+            let traj_id = crate::caliber_trajectory_create("Hybrid", None, None, tenant);
+            let note_id = crate::caliber_note_create(
+                "fact",
+                "Embedded",
+                "Vector only",
+                vec![traj_id],
+                vec![],
+                "persistent",
+                tenant,
+            )
+            .expect("note should be created");
+            assert!(crate::caliber_embedding_set(
+                "note",
+                note_id,
+                pgrx::JsonB(embedding),
+                tenant
+            ));
+            note_id
+        };
+
+        let older = create_embedded_note(tenant_id, serde_json::json!([1.0, 0.0, 0.0]));
+        let newer = create_embedded_note(tenant_id, serde_json::json!([0.9, 0.3, 0.0]));
+        let foreign = create_embedded_note(other_tenant, serde_json::json!([1.0, 0.0, 0.0]));
+        Spi::run(&format!(
+            "UPDATE caliber_note SET created_at = created_at - interval '1 day' WHERE note_id = '{}'",
+            older
+        ))
+        .expect("backdate note");
+
+        let ranked = |recency_weight: f32| -> Vec<String> {
+            let query = pgrx::JsonB(serde_json::json!([1.0, 0.0, 0.0]));
+            crate::caliber_hybrid_search(query, 10, recency_weight, tenant_id)
+                .0
+                .as_array()
+                .expect("results should be an array")
+                .iter()
+                .filter_map(|r| r["entity_id"].as_str().map(str::to_string))
+                .collect()
+        };
+
+        // Similarity alone favours the older exact match
+        assert_eq!(ranked(0.0), vec![older.to_string(), newer.to_string()]);
+        // Full recency weight promotes the newer note
+        assert_eq!(ranked(1.0), vec![newer.to_string(), older.to_string()]);
+        assert!(!ranked(0.5).contains(&foreign.to_string()));
     }
 
-    fn scope_get(&self, id: Uuid) -> CaliberResult<Option<Scope>> {
-        scope_heap::scope_get_heap(ScopeId::new(id), TenantId::nil()).map(|row| row.map(Into::into))
+    #[pg_test]
+    fn test_search_rrf_skips_invalid_queries() {
+        let queries = pgrx::JsonB(serde_json::json!(["not a vector", {"also": "invalid"}]));
+        let results = crate::caliber_search_rrf(queries, 10, 0);
+        assert_eq!(results.0, serde_json::json!([]));
     }
 
-    fn scope_get_current(&self, trajectory_id: Uuid) -> CaliberResult<Option<Scope>> {
-        // Get all scopes for trajectory and find the active one with latest created_at
-        let scopes = scope_heap::scope_list_by_trajectory_heap(
-            TrajectoryId::new(trajectory_id),
-            TenantId::nil(),
-        )?;
-        Ok(scopes
-            .into_iter()
-            .map(Into::into)
-            .filter(|s: &Scope| s.is_active)
-            .max_by_key(|s| s.created_at))
+    #[pg_test]
+    fn test_search_rrf_fuses_rankings() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Fusion", None, None, tenant_id);
+        let create_embedded_note = |title: &str, embedding: serde_json::Value| {
+            let note_id = crate::caliber_note_create(
+                "fact",
+                title,
+                title,
+                vec![traj_id],
+                vec![],
+                "persistent",
+                tenant_id,
+            )
+            .expect("note should be created");
+            assert!(crate::caliber_embedding_set(
+                "note",
+                note_id,
+                pgrx::JsonB(embedding),
+                tenant_id
+            ));
+            note_id.to_string()
+        };
+        let x = create_embedded_note("X", serde_json::json!([1.0, 0.0, 0.0]));
+        let y = create_embedded_note("Y", serde_json::json!([0.0, 1.0, 0.0]));
+        let z = create_embedded_note("Z", serde_json::json!([0.7, 0.7, 0.0]));
+
+        // First ranking: X, Z, Y. Second ranking: Z, Y, X.
+        let queries = pgrx::JsonB(serde_json::json!([[1.0, 0.0, 0.0], [0.6, 0.8, 0.0]]));
+        let results = crate::caliber_search_rrf(queries, 3, 0).0;
+        let results = results.as_array().expect("results should be an array");
+        let ids: Vec<&str> = results
+            .iter()
+            .filter_map(|r| r["entity_id"].as_str())
+            .collect();
+        // Z: 1/61 + 1/62, X: 1/61 + 1/63, Y: 1/63 + 1/62
+        assert_eq!(ids, vec![z.as_str(), x.as_str(), y.as_str()]);
+        let top_score = results[0]["score"].as_f64().expect("score");
+        assert!((top_score - (1.0 / 61.0 + 1.0 / 62.0)).abs() < 1e-9);
     }
 
-    fn scope_update(&self, id: Uuid, update: ScopeUpdate) -> CaliberResult<()> {
-        // Verify scope exists
-        let scope_id = ScopeId::new(id);
-        let existing = scope_heap::scope_get_heap(scope_id, TenantId::nil())?;
-        if existing.is_none() {
-            return Err(CaliberError::Storage(StorageError::NotFound {
-                entity_type: EntityType::Scope,
-                id,
-            }));
-        }
+    #[pg_test]
+    fn test_text_search() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Search", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 8000, tenant_id);
 
-        // For now, handle close specifically (most common update)
-        if update.is_active == Some(false) {
-            scope_heap::scope_close_heap(scope_id, TenantId::nil())?;
-        }
-        // Token updates via dedicated function
-        if let Some(tokens) = update.tokens_used {
-            scope_heap::scope_update_tokens_heap(scope_id, tokens, TenantId::nil())?;
-        }
-        Ok(())
-    }
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "error_log",
+            "Build failure",
+            "Compilation stopped with error E0432 in the parser module",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+        let note_id = crate::caliber_note_create(
+            "insight",
+            "Import errors",
+            "E0432 usually means a missing use statement",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
 
-    fn scope_list_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Scope>> {
-        scope_heap::scope_list_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
-    }
+        let ids = |results: pgrx::JsonB| -> Vec<String> {
+            results
+                .0
+                .as_array()
+                .expect("should be an array")
+                .iter()
+                .filter_map(|hit| hit["entity_id"].as_str().map(str::to_string))
+                .collect()
+        };
 
-    fn artifact_insert(&self, a: &Artifact) -> CaliberResult<()> {
-        // Check if already exists using heap module
-        if artifact_heap::artifact_get_heap(a.artifact_id, TenantId::nil())?.is_some() {
-            return Err(CaliberError::Storage(StorageError::InsertFailed {
-                entity_type: EntityType::Artifact,
-                reason: "already exists".to_string(),
-            }));
-        }
-        artifact_heap::artifact_create_heap(artifact_heap::ArtifactCreateParams {
-            artifact_id: a.artifact_id,
-            trajectory_id: a.trajectory_id,
-            scope_id: a.scope_id,
-            artifact_type: a.artifact_type,
-            name: &a.name,
-            content: &a.content,
-            content_hash: a.content_hash,
-            embedding: a.embedding.as_ref(),
-            provenance: &a.provenance,
-            ttl: a.ttl.clone(),
-            tenant_id: TenantId::nil(),
-        })?;
-        Ok(())
+        let all = ids(crate::caliber_text_search(
+            "E0432", None, None, 10, tenant_id,
+        ));
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&artifact_id.to_string()));
+        assert!(all.contains(&note_id.to_string()));
+
+        let notes = ids(crate::caliber_text_search(
+            "E0432",
+            Some("note"),
+            Some(traj_id),
+            10,
+            tenant_id,
+        ));
+        assert_eq!(notes, vec![note_id.to_string()]);
+
+        let miss = crate::caliber_text_search("E9999", None, None, 10, tenant_id);
+        assert_eq!(miss.0, serde_json::json!([]));
+        let invalid = crate::caliber_text_search("E0432", Some("turn"), None, 10, tenant_id);
+        assert_eq!(invalid.0, serde_json::json!([]));
     }
 
-    fn artifact_get(&self, id: Uuid) -> CaliberResult<Option<Artifact>> {
-        artifact_heap::artifact_get_heap(ArtifactId::new(id), TenantId::nil())
-            .map(|row| row.map(Into::into))
+    #[pg_test]
+    fn test_search_hybrid_text_vector() {
+        let tenant_id = test_tenant_id();
+
+        let invalid = crate::caliber_search_hybrid_text_vector(
+            "E0432",
+            pgrx::JsonB(serde_json::json!("not a vector")),
+            10,
+            0.5,
+            tenant_id,
+        );
+        assert_eq!(invalid.0, serde_json::json!([]));
+
+        let traj_id = crate::caliber_trajectory_create("Hybrid", None, None, tenant_id);
+        let note_id = crate::caliber_note_create(
+            "insight",
+            "Import errors",
+            "E0432 usually means a missing use statement",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+
+        // With alpha 0 only the text rank counts, so the unembedded note leads
+        let results = crate::caliber_search_hybrid_text_vector(
+            "E0432",
+            pgrx::JsonB(serde_json::json!([0.1, 0.2, 0.3])),
+            10,
+            0.0,
+            tenant_id,
+        );
+        let top = &results.0[0];
+        assert_eq!(top["entity_id"], note_id.to_string());
+        assert_eq!(top["text_score"], 1.0);
+        assert_eq!(top["score"], 1.0);
     }
 
-    fn artifact_query_by_type(
-        &self,
-        trajectory_id: Uuid,
-        artifact_type: ArtifactType,
-    ) -> CaliberResult<Vec<Artifact>> {
-        // Get all artifacts of type and filter by trajectory
-        let traj_id = TrajectoryId::new(trajectory_id);
-        let artifacts = artifact_heap::artifact_query_by_type_heap(artifact_type, TenantId::nil())?
-            .into_iter()
-            .map(Into::into)
-            .filter(|a: &Artifact| a.trajectory_id == traj_id)
+    #[pg_test]
+    fn test_search_hybrid_text_vector_tenant_isolation() {
+        let tenant_id = test_tenant_id();
+        let other_tenant = test_tenant_id();
+
+        let create_embedded_note = |tenant: pgrx::Uuid, embedding: serde_json::Value| {
+            let traj_id = crate::caliber_trajectory_create("Isolation", None, None, tenant);
+            let note_id = crate::caliber_note_create(
+                "fact",
+                "Embedded",
+                "Vector only",
+                vec![traj_id],
+                vec![],
+                "persistent",
+                tenant,
+            )
+            .expect("note should be created");
+            assert!(crate::caliber_embedding_set(
+                "note",
+                note_id,
+                pgrx::JsonB(embedding),
+                tenant
+            ));
+            note_id
+        };
+
+        let own = create_embedded_note(tenant_id, serde_json::json!([1.0, 0.0, 0.0]));
+        let foreign = create_embedded_note(other_tenant, serde_json::json!([0.99, 0.01, 0.0]));
+
+        // With alpha 1 only the vector similarity counts
+        let results = crate::caliber_search_hybrid_text_vector(
+            "",
+            pgrx::JsonB(serde_json::json!([0.99, 0.01, 0.0])),
+            10,
+            1.0,
+            tenant_id,
+        );
+        let ids: Vec<&str> = results
+            .0
+            .as_array()
+            .expect("results should be an array")
+            .iter()
+            .filter_map(|r| r["entity_id"].as_str())
             .collect();
-        Ok(artifacts)
+        let own = own.to_string();
+        assert!(ids.contains(&own.as_str()));
+        assert!(!ids.contains(&foreign.to_string().as_str()));
     }
 
-    fn artifact_query_by_scope(&self, scope_id: Uuid) -> CaliberResult<Vec<Artifact>> {
-        artifact_heap::artifact_query_by_scope_heap(ScopeId::new(scope_id), TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
+    #[pg_test]
+    fn test_inject_relevant_rejects_bad_threshold() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Inject", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let query = pgrx::JsonB(serde_json::json!([0.1, 0.2, 0.3]));
+
+        let results = crate::caliber_inject_relevant(scope_id, query, 1.5, 100, tenant_id);
+        assert_eq!(results.0["items"], serde_json::json!([]));
+        assert_eq!(results.0["tokens_used"], 0);
     }
 
-    fn artifact_update(&self, id: Uuid, update: ArtifactUpdate) -> CaliberResult<()> {
-        // Compute content hash if content is being updated
-        let content_hash = update
-            .content
-            .as_ref()
-            .map(|c| compute_content_hash(c.as_bytes()));
-        // Convert embedding to the nested Option format (Some(Some(x)) = update, Some(None) = set to null, None = don't update)
-        let embedding_opt = update.embedding.as_ref().map(Some);
-        // Same for superseded_by
-        let superseded_opt = update.superseded_by.map(|s| Some(ArtifactId::new(s)));
+    #[pg_test]
+    fn test_inject_relevant_threshold_and_budget() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Inject", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create = |content: &str, embedding: serde_json::Value| {
+            let artifact_id = crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                "Fact",
+                content,
+                0,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+            assert!(crate::caliber_embedding_set(
+                "artifact",
+                artifact_id,
+                pgrx::JsonB(embedding),
+                tenant_id
+            ));
+            artifact_id.to_string()
+        };
+        let short_content = "The cache is enabled";
+        let long_content = "The cache is enabled in production and staging, \
+                            with a one hour expiry on every entry it stores";
+        let closest = create(short_content, serde_json::json!([1.0, 0.0, 0.0]));
+        let close = create(long_content, serde_json::json!([0.9, 0.3, 0.0]));
+        let unrelated = create("Deploys run nightly", serde_json::json!([0.0, 1.0, 0.0]));
+
+        let inject = |max_tokens: i32| {
+            let query = pgrx::JsonB(serde_json::json!([1.0, 0.0, 0.0]));
+            crate::caliber_inject_relevant(scope_id, query, 0.5, max_tokens, tenant_id).0
+        };
+        let ids = |result: &serde_json::Value| -> Vec<String> {
+            result["items"]
+                .as_array()
+                .expect("items array")
+                .iter()
+                .filter_map(|i| i["entity_id"].as_str().map(str::to_string))
+                .collect()
+        };
 
-        artifact_heap::artifact_update_heap(
-            ArtifactId::new(id),
-            update.content.as_deref(),
-            content_hash,
-            embedding_opt,
-            superseded_opt,
-            None, // metadata not in ArtifactUpdate struct
-            TenantId::nil(),
-        )?;
-        Ok(())
+        // The unrelated artifact falls below the threshold
+        let all = inject(10_000);
+        assert_eq!(all["candidates"], 2);
+        assert_eq!(ids(&all), vec![closest.clone(), close]);
+        assert!(!ids(&all).contains(&unrelated));
+
+        // A budget that only fits the short artifact truncates the set
+        let short_tokens = caliber_core::estimate_tokens(short_content);
+        let truncated = inject(short_tokens);
+        assert_eq!(truncated["candidates"], 2);
+        assert_eq!(ids(&truncated), vec![closest]);
+        assert_eq!(truncated["tokens_used"], short_tokens);
     }
 
-    fn note_insert(&self, n: &Note) -> CaliberResult<()> {
-        // Check if already exists using heap module
-        if note_heap::note_get_heap(n.note_id, TenantId::nil())?.is_some() {
-            return Err(CaliberError::Storage(StorageError::InsertFailed {
-                entity_type: EntityType::Note,
-                reason: "already exists".to_string(),
-            }));
-        }
-        note_heap::note_create_heap(note_heap::NoteCreateParams {
-            note_id: n.note_id,
-            note_type: n.note_type,
-            title: &n.title,
-            content: &n.content,
-            content_hash: n.content_hash,
-            embedding: n.embedding.as_ref(),
-            source_trajectory_ids: &n.source_trajectory_ids,
-            source_artifact_ids: &n.source_artifact_ids,
-            ttl: n.ttl.clone(),
-            abstraction_level: n.abstraction_level,
-            source_note_ids: &n.source_note_ids,
-            tenant_id: TenantId::nil(),
-        })?;
-        Ok(())
-    }
+    #[pg_test]
+    fn test_artifact_create_dedup() {
+        crate::caliber_debug_clear();
 
-    fn note_get(&self, id: Uuid) -> CaliberResult<Option<Note>> {
-        note_heap::note_get_heap(NoteId::new(id), TenantId::nil()).map(|row| row.map(Into::into))
-    }
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Dedup", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+
+        let create = |content: &str| {
+            crate::caliber_artifact_create_dedup(
+                traj_id,
+                scope_id,
+                "fact",
+                "Fact",
+                content,
+                1,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+        };
 
-    fn note_query_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Note>> {
-        note_heap::note_query_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
+        let first = create("The sky is blue").expect("artifact should be created");
+        let duplicate = create("The sky is blue").expect("duplicate should resolve");
+        let other = create("Grass is green").expect("artifact should be created");
+
+        assert_eq!(first, duplicate);
+        assert_ne!(first, other);
     }
 
-    fn note_update(&self, id: Uuid, update: NoteUpdate) -> CaliberResult<()> {
-        // Compute content hash if content is being updated
-        let content_hash = update
-            .content
-            .as_ref()
-            .map(|c| compute_content_hash(c.as_bytes()));
-        // Convert embedding to the nested Option format (Some(Some(x)) = update, Some(None) = set to null, None = don't update)
-        let embedding_opt = update.embedding.as_ref().map(Some);
-        // Same for superseded_by
-        let superseded_opt = update.superseded_by.map(|s| Some(NoteId::new(s)));
+    #[pg_test]
+    fn test_content_hash() {
+        assert_eq!(
+            crate::caliber_content_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Hash", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Hashed",
+            "hash me",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
 
-        note_heap::note_update_heap(
-            NoteId::new(id),
-            update.content.as_deref(),
-            content_hash,
-            embedding_opt,
-            superseded_opt,
-            None, // metadata not in NoteUpdate struct
-            TenantId::nil(),
-        )?;
-        Ok(())
+        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, false).expect("exists");
+        assert_eq!(
+            artifact.0["content_hash"],
+            crate::caliber_content_hash("hash me")
+        );
     }
 
-    fn turn_insert(&self, t: &Turn) -> CaliberResult<()> {
-        turn_heap::turn_create_heap(turn_heap::TurnCreateParams {
-            turn_id: t.turn_id,
-            scope_id: t.scope_id,
-            sequence: t.sequence,
-            role: t.role,
-            content: &t.content,
-            token_count: t.token_count,
-            tool_calls: t.tool_calls.as_ref(),
-            tool_results: t.tool_results.as_ref(),
-            tenant_id: TenantId::nil(),
-        })?;
-        Ok(())
-    }
+    #[pg_test]
+    fn test_artifact_find_by_hash() {
+        crate::caliber_debug_clear();
 
-    fn turn_get_by_scope(&self, scope_id: Uuid) -> CaliberResult<Vec<Turn>> {
-        turn_heap::turn_get_by_scope_heap(ScopeId::new(scope_id), TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
+        let tenant_id = test_tenant_id();
+        let traj_a = crate::caliber_trajectory_create("A", None, None, tenant_id);
+        let scope_a = crate::caliber_scope_create(traj_a, "Scope", None, 1000, tenant_id);
+        let traj_b = crate::caliber_trajectory_create("B", None, None, tenant_id);
+        let scope_b = crate::caliber_scope_create(traj_b, "Scope", None, 1000, tenant_id);
+
+        for (traj, scope) in [(traj_a, scope_a), (traj_b, scope_b)] {
+            crate::caliber_artifact_create(
+                traj,
+                scope,
+                "fact",
+                "Shared",
+                "shared content",
+                1,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+        }
+
+        let hash = hex::encode(caliber_core::compute_content_hash(b"shared content"));
+        let found = crate::caliber_artifact_find_by_hash(&hash, tenant_id);
+        assert_eq!(found.0.as_array().map(|a| a.len()), Some(2));
+
+        // Malformed hashes are rejected
+        let invalid = crate::caliber_artifact_find_by_hash("abc123", tenant_id);
+        assert_eq!(invalid.0, serde_json::json!([]));
     }
 
-    fn vector_search(
-        &self,
-        query: &EmbeddingVector,
-        limit: i32,
-    ) -> CaliberResult<Vec<(Uuid, f32)>> {
-        // Vector search requires scanning all artifacts and notes with embeddings.
-        // In production, this would use pgvector's index-based search via SPI.
-        // For now, use a hybrid approach - query via SPI with vector operators.
+    #[pg_test]
+    fn test_summarization_evaluate() {
+        crate::caliber_debug_clear();
 
-        Spi::connect(|client| {
-            let mut results: Vec<(Uuid, f32)> = Vec::new();
-
-            // Use pgvector's cosine similarity operator if available
-            // Query format: embedding <=> $1 returns cosine distance
-            let query_vec: Vec<f32> = query.data.clone();
-            let query_str = format!(
-                "[{}]",
-                query_vec
-                    .iter()
-                    .map(|f| f.to_string())
-                    .collect::<Vec<_>>()
-                    .join(",")
-            );
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Summarize", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+
+        let policy_id = crate::caliber_summarization_policy_create(
+            "evaluate-artifact-count",
+            pgrx::JsonB(serde_json::json!([{ "ArtifactCount": { "count": 2 } }])),
+            "raw",
+            "summary",
+            5,
+            true,
+            Some(traj_id),
+            tenant_id,
+        )
+        .expect("policy should be created");
 
-            // Search artifacts with embeddings
-            let artifact_query = format!(
-                "SELECT artifact_id, 1 - (embedding <=> '{}') as similarity \
-                 FROM caliber_artifact \
-                 WHERE embedding IS NOT NULL \
-                 ORDER BY embedding <=> '{}' \
-                 LIMIT {}",
-                query_str, query_str, limit
-            );
+        // One artifact: not yet due
+        crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "A",
+            "first",
+            1,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        );
+        let due = crate::caliber_summarization_evaluate(traj_id, tenant_id);
+        assert_eq!(due.0, serde_json::json!([]));
 
-            if let Ok(artifact_results) = client.select(&artifact_query, None, &[]) {
-                for row in artifact_results {
-                    if let (Ok(Some(id)), Ok(Some(sim))) =
-                        (row.get::<pgrx::Uuid>(1), row.get::<f32>(2))
-                    {
-                        results.push((Uuid::from_bytes(*id.as_bytes()), sim));
-                    }
-                }
-            }
+        // Second artifact crosses the threshold
+        crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "B",
+            "second",
+            1,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        );
+        let due = crate::caliber_summarization_evaluate(traj_id, tenant_id);
+        let arr = due.0.as_array().expect("should be an array");
+        assert_eq!(arr.len(), 1);
+        assert_eq!(
+            arr[0]["policy_id"],
+            serde_json::json!(policy_id.to_string())
+        );
+        assert_eq!(
+            arr[0]["candidate_artifact_ids"].as_array().map(|a| a.len()),
+            Some(2)
+        );
+    }
 
-            // Search notes with embeddings
-            let note_query = format!(
-                "SELECT note_id, 1 - (embedding <=> '{}') as similarity \
-                 FROM caliber_note \
-                 WHERE embedding IS NOT NULL \
-                 ORDER BY embedding <=> '{}' \
-                 LIMIT {}",
-                query_str, query_str, limit
-            );
+    #[pg_test]
+    fn test_summarization_evaluate_scope_close_fires_once() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Summarize", None, None, tenant_id);
+        let first = crate::caliber_scope_create(traj_id, "First", None, 1000, tenant_id);
+        let second = crate::caliber_scope_create(traj_id, "Second", None, 1000, tenant_id);
+
+        crate::caliber_summarization_policy_create(
+            "evaluate-scope-close",
+            pgrx::JsonB(serde_json::json!(["ScopeClose"])),
+            "raw",
+            "summary",
+            5,
+            true,
+            Some(traj_id),
+            tenant_id,
+        )
+        .expect("policy should be created");
 
-            if let Ok(note_results) = client.select(&note_query, None, &[]) {
-                for row in note_results {
-                    if let (Ok(Some(id)), Ok(Some(sim))) =
-                        (row.get::<pgrx::Uuid>(1), row.get::<f32>(2))
-                    {
-                        results.push((Uuid::from_bytes(*id.as_bytes()), sim));
-                    }
-                }
-            }
+        assert_eq!(
+            crate::caliber_summarization_evaluate(traj_id, tenant_id).0,
+            serde_json::json!([])
+        );
 
-            // Sort combined results by similarity
-            results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-            results.truncate(limit as usize);
+        // A closed scope fires the trigger once, not on every evaluation
+        assert!(crate::caliber_scope_close(first, tenant_id));
+        let due = crate::caliber_summarization_evaluate(traj_id, tenant_id).0;
+        assert_eq!(due.as_array().map(|a| a.len()), Some(1));
+        assert_eq!(due[0]["fired_triggers"], serde_json::json!(["ScopeClose"]));
+        assert_eq!(
+            crate::caliber_summarization_evaluate(traj_id, tenant_id).0,
+            serde_json::json!([])
+        );
 
-            Ok(results)
-        })
+        assert!(crate::caliber_scope_close(second, tenant_id));
+        let due = crate::caliber_summarization_evaluate(traj_id, tenant_id).0;
+        assert_eq!(due.as_array().map(|a| a.len()), Some(1));
     }
 
-    // === Edge Operations (Battle Intel Feature 1) ===
+    #[pg_test]
+    fn test_summarization_apply() {
+        crate::caliber_debug_clear();
 
-    fn edge_insert(&self, e: &Edge) -> CaliberResult<()> {
-        edge_heap::edge_create_heap(e, TenantId::nil())?;
-        Ok(())
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Summarize", None, None, tenant_id);
+
+        let policy_id = crate::caliber_summarization_policy_create(
+            "apply-raw-to-summary",
+            pgrx::JsonB(serde_json::json!(["Manual"])),
+            "raw",
+            "summary",
+            5,
+            true,
+            Some(traj_id),
+            tenant_id,
+        )
+        .expect("policy should be created");
+
+        let sources: Vec<pgrx::Uuid> = ["first fact", "second fact"]
+            .iter()
+            .map(|content| {
+                crate::caliber_note_create(
+                    "fact",
+                    "Fact",
+                    content,
+                    vec![traj_id],
+                    vec![],
+                    "persistent",
+                    tenant_id,
+                )
+                .expect("note should be created")
+            })
+            .collect();
+        let source_json =
+            serde_json::json!(sources.iter().map(|id| id.to_string()).collect::<Vec<_>>());
+
+        let summary_id = crate::caliber_summarization_apply(
+            policy_id,
+            pgrx::JsonB(source_json.clone()),
+            "Both facts hold",
+            tenant_id,
+        )
+        .expect("summary should be created");
+
+        let summary =
+            crate::caliber_note_get(summary_id, tenant_id, false).expect("summary should exist");
+        assert_eq!(summary.0["note_type"], "summary");
+
+        // Each source now lists the summary as a derivative
+        let derived = crate::caliber_notes_by_source_note(sources[0], tenant_id);
+        let derived = derived.0.as_array().expect("should be an array");
+        assert_eq!(derived.len(), 1);
+        assert_eq!(
+            derived[0]["note_id"],
+            serde_json::json!(summary_id.to_string())
+        );
+        assert_eq!(derived[0]["abstraction_level"], "summary");
+
+        // The policy creates a SynthesizedFrom edge from the summary to each source
+        for source in &sources {
+            assert!(crate::caliber_edge_exists(
+                "synthesizedfrom",
+                pgrx::JsonB(serde_json::json!([
+                    summary_id.to_string(),
+                    source.to_string()
+                ])),
+                tenant_id
+            ));
+        }
+        let edges = crate::caliber_edges_by_participant(summary_id, tenant_id).0;
+        assert_eq!(edges.as_array().map(|a| a.len()), Some(sources.len()));
+
+        let summaries = crate::caliber_notes_by_abstraction_level("summary", tenant_id);
+        assert_eq!(summaries.0.as_array().map(|a| a.len()), Some(1));
+        let invalid = crate::caliber_notes_by_abstraction_level("L9", tenant_id);
+        assert_eq!(invalid.0, serde_json::json!([]));
+
+        // Summaries are not valid sources for a raw-level policy
+        let again = crate::caliber_summarization_apply(
+            policy_id,
+            pgrx::JsonB(serde_json::json!([summary_id.to_string()])),
+            "Summary of a summary",
+            tenant_id,
+        );
+        assert!(again.is_none());
     }
 
-    fn edge_get(&self, id: Uuid) -> CaliberResult<Option<Edge>> {
-        edge_heap::edge_get_heap(EdgeId::new(id), TenantId::nil()).map(|row| row.map(Into::into))
+    #[pg_test]
+    fn test_task_start() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let started =
+            crate::caliber_task_start("Task", Some("Do it"), None, "Main", 4000, tenant_id);
+
+        let traj_id = uuid::Uuid::parse_str(started.0["trajectory_id"].as_str().unwrap()).unwrap();
+        let scope_id = uuid::Uuid::parse_str(started.0["scope_id"].as_str().unwrap()).unwrap();
+
+        let scope =
+            crate::caliber_scope_get(pgrx::Uuid::from_bytes(*scope_id.as_bytes()), tenant_id)
+                .expect("scope should exist");
+        assert_eq!(
+            scope.0["trajectory_id"],
+            serde_json::json!(traj_id.to_string())
+        );
+        assert_eq!(scope.0["token_budget"], 4000);
     }
 
-    fn edge_query_by_type(&self, edge_type: EdgeType) -> CaliberResult<Vec<Edge>> {
-        edge_heap::edge_query_by_type_heap(edge_type, TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
+    #[pg_test]
+    fn test_scope_create_exclusive() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Scopes", None, None, tenant_id);
+
+        let first =
+            crate::caliber_scope_create_exclusive(traj_id, "First", None, 1000, true, tenant_id)
+                .expect("scope should be created");
+        let second =
+            crate::caliber_scope_create_exclusive(traj_id, "Second", None, 1000, true, tenant_id)
+                .expect("scope should be created");
+
+        let first_scope = crate::caliber_scope_get(first, tenant_id).expect("scope should exist");
+        let second_scope = crate::caliber_scope_get(second, tenant_id).expect("scope should exist");
+        assert_eq!(first_scope.0["is_active"], false);
+        assert_eq!(second_scope.0["is_active"], true);
+
+        let current = crate::caliber_scope_get_current(traj_id, tenant_id).expect("current scope");
+        assert_eq!(current.0["scope_id"], serde_json::json!(second.to_string()));
+
+        // An invalid budget is rejected before the active scope is closed
+        assert!(
+            crate::caliber_scope_create_exclusive(traj_id, "Third", None, 0, true, tenant_id)
+                .is_none()
+        );
+        let second_scope = crate::caliber_scope_get(second, tenant_id).expect("scope should exist");
+        assert_eq!(second_scope.0["is_active"], true);
     }
 
-    fn edge_query_by_trajectory(&self, trajectory_id: Uuid) -> CaliberResult<Vec<Edge>> {
-        edge_heap::edge_query_by_trajectory_heap(TrajectoryId::new(trajectory_id), TenantId::nil())
-            .map(|rows| rows.into_iter().map(Into::into).collect())
+    #[pg_test]
+    fn test_scope_add_tokens() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Budget", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 100, tenant_id);
+
+        let status = crate::caliber_scope_add_tokens(scope_id, 60, true, tenant_id)
+            .expect("scope should exist");
+        assert_eq!(status.0["tokens_used"], 60);
+        assert_eq!(status.0["remaining"], 40);
+        assert_eq!(status.0["over_budget"], false);
+
+        let status = crate::caliber_scope_add_tokens(scope_id, 60, true, tenant_id)
+            .expect("scope should exist");
+        assert_eq!(status.0["tokens_used"], 120);
+        assert_eq!(status.0["remaining"], -20);
+        assert_eq!(status.0["over_budget"], true);
     }
 
-    fn edge_query_by_participant(&self, entity_id: Uuid) -> CaliberResult<Vec<Edge>> {
-        // Query edges and filter by participant - uses SPI since we need JSON filtering
-        Spi::connect(|client| {
-            let result = client
-                .select(
-                    "SELECT edge_id, edge_type, participants, weight, trajectory_id,
-                        source_turn, extraction_method, confidence, created_at, metadata
-                 FROM caliber_edge
-                 WHERE participants @> $1::jsonb",
-                    None,
-                    &[text_datum(&format!(r#"[{{"id":"{}"}}]"#, entity_id))],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
+    #[pg_test]
+    fn test_scope_recount_tokens() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Recount", None, None, tenant_id);
+        let first = crate::caliber_scope_create(traj_id, "First", None, 1000, tenant_id);
+        let second = crate::caliber_scope_create(traj_id, "Second", None, 1000, tenant_id);
+        crate::caliber_turn_create(first, 1, "user", "Hello", 5, tenant_id);
+        crate::caliber_turn_create(first, 2, "assistant", "Hi there!", 10, tenant_id);
+        crate::caliber_turn_create(second, 1, "user", "Again", 7, tenant_id);
+
+        // A drifted counter is overwritten with the turn total
+        assert!(crate::caliber_scope_update_tokens(first, 999, tenant_id));
+        assert_eq!(
+            crate::caliber_scope_recount_tokens(first, tenant_id),
+            Some(15)
+        );
+        let scope = crate::caliber_scope_get(first, tenant_id).expect("scope should exist");
+        assert_eq!(scope.0["tokens_used"], 15);
 
-            let mut edges = Vec::new();
-            for row in result {
-                // Parse each row into Edge struct
-                if let Ok(Some(edge_id_pg)) = row.get::<pgrx::Uuid>(1) {
-                    let edge_id = EdgeId::new(Uuid::from_bytes(*edge_id_pg.as_bytes()));
-                    if let Some(edge_row) = edge_heap::edge_get_heap(edge_id, TenantId::nil())? {
-                        edges.push(edge_row.into());
-                    }
-                }
-            }
-            Ok(edges)
-        })
+        assert_eq!(
+            crate::caliber_trajectory_total_tokens(traj_id, tenant_id),
+            Some(22)
+        );
+
+        let missing = pgrx::Uuid::from_bytes([0u8; 16]);
+        assert_eq!(
+            crate::caliber_scope_recount_tokens(missing, tenant_id),
+            None
+        );
+        assert_eq!(
+            crate::caliber_trajectory_total_tokens(missing, tenant_id),
+            None
+        );
     }
 
-    // === Note Abstraction Level Queries (Battle Intel Feature 2) ===
+    #[pg_test]
+    fn test_trajectory_suspend_resume() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Long task", None, None, tenant_id);
 
-    fn note_query_by_abstraction_level(&self, level: AbstractionLevel) -> CaliberResult<Vec<Note>> {
-        let level_str = match level {
-            AbstractionLevel::Raw => "raw",
-            AbstractionLevel::Summary => "summary",
-            AbstractionLevel::Principle => "principle",
-        };
+        assert!(!crate::caliber_trajectory_suspend(traj_id, "  ", tenant_id));
+        // Not suspended yet
+        assert!(!crate::caliber_trajectory_resume(traj_id, tenant_id));
 
-        Spi::connect(|client| {
-            let result = client
-                .select(
-                    "SELECT note_id FROM caliber_note WHERE abstraction_level = $1",
-                    None,
-                    &[text_datum(level_str)],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
+        assert!(crate::caliber_trajectory_suspend(
+            traj_id,
+            "waiting on review",
+            tenant_id
+        ));
+        let traj = crate::caliber_trajectory_get(traj_id, tenant_id).expect("trajectory exists");
+        assert_eq!(traj.0["status"], "suspended");
+        assert_eq!(traj.0["metadata"]["suspend_reason"], "waiting on review");
+        assert!(traj.0["metadata"]["suspended_at"].is_string());
+        // Already suspended
+        assert!(!crate::caliber_trajectory_suspend(
+            traj_id, "again", tenant_id
+        ));
+
+        assert!(crate::caliber_trajectory_resume(traj_id, tenant_id));
+        let traj = crate::caliber_trajectory_get(traj_id, tenant_id).expect("trajectory exists");
+        assert_eq!(traj.0["status"], "active");
+        assert!(traj.0["metadata"].get("suspend_reason").is_none());
+        assert!(traj.0["metadata"]["resumed_at"].is_string());
+
+        assert_eq!(
+            crate::caliber_trajectory_set_status(traj_id, "completed", tenant_id),
+            Some(true)
+        );
+        assert!(!crate::caliber_trajectory_suspend(
+            traj_id, "too late", tenant_id
+        ));
+        assert!(!crate::caliber_trajectory_resume(traj_id, tenant_id));
 
-            let mut notes = Vec::new();
-            for row in result {
-                if let Ok(Some(note_id_pg)) = row.get::<pgrx::Uuid>(1) {
-                    let note_id = NoteId::new(Uuid::from_bytes(*note_id_pg.as_bytes()));
-                    if let Some(note_row) = note_heap::note_get_heap(note_id, TenantId::nil())? {
-                        notes.push(note_row.into());
-                    }
-                }
-            }
-            Ok(notes)
-        })
+        let missing = pgrx::Uuid::from_bytes(*uuid::Uuid::now_v7().as_bytes());
+        assert!(!crate::caliber_trajectory_resume(missing, tenant_id));
     }
 
-    fn note_query_by_source_note(&self, source_note_id: Uuid) -> CaliberResult<Vec<Note>> {
-        Spi::connect(|client| {
-            let result = client
-                .select(
-                    "SELECT note_id FROM caliber_note WHERE $1 = ANY(source_note_ids)",
-                    None,
-                    &[uuid_datum(source_note_id)],
-                )
-                .map_err(|e| {
-                    CaliberError::Storage(StorageError::SpiError {
-                        reason: e.to_string(),
-                    })
-                })?;
+    #[pg_test]
+    fn test_trajectory_complete() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Finish me", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Result",
+            "The answer is 42",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
 
-            let mut notes = Vec::new();
-            for row in result {
-                if let Ok(Some(note_id_pg)) = row.get::<pgrx::Uuid>(1) {
-                    let note_id = NoteId::new(Uuid::from_bytes(*note_id_pg.as_bytes()));
-                    if let Some(note_row) = note_heap::note_get_heap(note_id, TenantId::nil())? {
-                        notes.push(note_row.into());
-                    }
-                }
-            }
-            Ok(notes)
-        })
+        assert!(!crate::caliber_trajectory_complete(
+            traj_id, "done", "Finished", tenant_id
+        ));
+
+        assert!(crate::caliber_trajectory_complete(
+            traj_id, "success", "Finished", tenant_id
+        ));
+        let traj = crate::caliber_trajectory_get(traj_id, tenant_id).expect("trajectory exists");
+        assert_eq!(traj.0["status"], "completed");
+        assert!(traj.0["completed_at"].is_string());
+        assert_eq!(traj.0["outcome"]["status"], "Success");
+        assert_eq!(traj.0["outcome"]["summary"], "Finished");
+        let artifact_uuid = uuid::Uuid::from_bytes(*artifact_id.as_bytes());
+        assert_eq!(
+            traj.0["outcome"]["produced_artifacts"],
+            serde_json::json!([artifact_uuid.to_string()])
+        );
+        // Already finished
+        assert!(!crate::caliber_trajectory_complete(
+            traj_id, "partial", "Again", tenant_id
+        ));
+
+        let failed_id = crate::caliber_trajectory_create("Doomed", None, None, tenant_id);
+        assert!(crate::caliber_trajectory_complete(
+            failed_id, "failure", "Gave up", tenant_id
+        ));
+        let failed =
+            crate::caliber_trajectory_get(failed_id, tenant_id).expect("trajectory exists");
+        assert_eq!(failed.0["status"], "failed");
+        assert_eq!(failed.0["outcome"]["status"], "Failure");
     }
-}
 
-// ============================================================================
-// TENANT OPERATIONS
-// ============================================================================
+    #[pg_test]
+    fn test_trajectory_export() {
+        crate::caliber_debug_clear();
+
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Export", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        crate::caliber_turn_create(scope_id, 1, "user", "Hello", 5, tenant_id);
+        crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Fact",
+            "exported content",
+            1,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        let doc = crate::caliber_trajectory_export(traj_id, tenant_id).expect("export");
+        assert_eq!(doc.0["scopes"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(doc.0["turns"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(doc.0["artifacts"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(
+            doc.0["artifacts"][0]["content_hash"],
+            serde_json::json!(hex::encode(caliber_core::compute_content_hash(
+                b"exported content"
+            )))
+        );
+    }
+
+    #[pg_test]
+    fn test_trajectory_import() {
+        crate::caliber_debug_clear();
 
-/// Check if an email domain is a public domain (gmail, outlook, etc.).
-/// Public domain users get personal tenants instead of joining a corporate tenant.
-#[pg_extern]
-fn caliber_is_public_email_domain(domain: &str) -> bool {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT EXISTS (SELECT 1 FROM caliber_public_email_domain WHERE domain = $1)",
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Import", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        crate::caliber_turn_create(scope_id, 1, "user", "Hello", 5, tenant_id);
+        crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Fact",
+            "imported content",
+            1,
+            "explicit",
             None,
-            &[text_datum(domain)],
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        let doc = crate::caliber_trajectory_export(traj_id, tenant_id).expect("export");
+        let result = crate::caliber_trajectory_import(doc, true, tenant_id);
+
+        let new_traj_str = result.0["trajectory_id"].as_str().expect("trajectory_id");
+        assert_ne!(new_traj_str, traj_id.to_string());
+        let new_scope_str = result.0["id_map"][scope_id.to_string()]
+            .as_str()
+            .expect("scope should be remapped");
+        assert_ne!(new_scope_str, scope_id.to_string());
+
+        let new_traj_id =
+            pgrx::Uuid::from_bytes(*uuid::Uuid::parse_str(new_traj_str).unwrap().as_bytes());
+        let copy = crate::caliber_trajectory_export(new_traj_id, tenant_id).expect("export copy");
+        assert_eq!(copy.0["scopes"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(copy.0["turns"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(copy.0["artifacts"].as_array().map(|a| a.len()), Some(1));
+        assert_eq!(
+            copy.0["artifacts"][0]["scope_id"],
+            serde_json::json!(new_scope_str)
         );
+        assert_eq!(copy.0["artifacts"][0]["content"], "imported content");
+    }
 
-        match result {
-            Ok(table) => table
-                .first()
-                .get_one::<bool>()
-                .unwrap_or(Some(false))
-                .unwrap_or(false),
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to check public domain: {}", e);
-                false
-            }
-        }
-    })
-}
+    #[pg_test]
+    fn test_trajectory_list_by_timerange() {
+        let tenant_id = test_tenant_id();
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        crate::caliber_trajectory_create("Active", None, None, tenant_id);
+        let done = crate::caliber_trajectory_create("Done", None, None, tenant_id);
+        assert_eq!(
+            crate::caliber_trajectory_set_status(done, "completed", tenant_id),
+            Some(true)
+        );
 
-/// Create a new tenant.
-/// Returns the tenant_id of the created tenant.
-#[pg_extern]
-fn caliber_tenant_create(
-    name: &str,
-    domain: Option<&str>,
-    workos_organization_id: Option<&str>,
-) -> pgrx::Uuid {
-    let tenant_id = TenantId::now_v7();
+        let names = |result: pgrx::JsonB| -> Vec<String> {
+            result
+                .0
+                .as_array()
+                .expect("array")
+                .iter()
+                .filter_map(|t| t["name"].as_str())
+                .filter(|n| *n == "Active" || *n == "Done")
+                .map(str::to_string)
+                .collect()
+        };
 
-    Spi::connect_mut(|client| {
-        let result = client.update(
-            "INSERT INTO caliber_tenant (tenant_id, name, domain, workos_organization_id)
-             VALUES ($1, $2, $3, $4)",
+        let window = (now_ms - 60_000, now_ms + 60_000);
+        let all = crate::caliber_trajectory_list_by_timerange(window.0, window.1, None, tenant_id);
+        assert_eq!(names(all), vec!["Active", "Done"]);
+
+        let completed = crate::caliber_trajectory_list_by_timerange(
+            window.0,
+            window.1,
+            Some("completed"),
+            tenant_id,
+        );
+        assert_eq!(names(completed), vec!["Done"]);
+
+        // Window entirely before creation
+        let earlier = crate::caliber_trajectory_list_by_timerange(
+            now_ms - 120_000,
+            now_ms - 60_000,
             None,
-            &[
-                id_datum(tenant_id),
-                text_datum(name),
-                opt_text_datum(domain),
-                opt_text_datum(workos_organization_id),
-            ],
+            tenant_id,
+        );
+        assert!(names(earlier).is_empty());
+
+        // Inverted windows and unknown statuses are rejected
+        let inverted =
+            crate::caliber_trajectory_list_by_timerange(window.1, window.0, None, tenant_id);
+        assert_eq!(inverted.0, serde_json::json!([]));
+        let bogus = crate::caliber_trajectory_list_by_timerange(
+            window.0,
+            window.1,
+            Some("bogus"),
+            tenant_id,
         );
+        assert_eq!(bogus.0, serde_json::json!([]));
+    }
 
-        if let Err(e) = result {
-            pgrx::warning!("CALIBER: Failed to create tenant: {}", e);
-        }
-    });
+    #[pg_test]
+    fn test_trajectory_stats() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Stats", None, None, tenant_id);
+        let scope_a = crate::caliber_scope_create(traj_id, "A", None, 1000, tenant_id);
+        let scope_b = crate::caliber_scope_create(traj_id, "B", None, 500, tenant_id);
+        crate::caliber_scope_update_tokens(scope_a, 300, tenant_id);
+        crate::caliber_scope_close(scope_b, tenant_id);
+        crate::caliber_turn_create(scope_a, 1, "user", "Hello", 5, tenant_id);
+        crate::caliber_turn_create(scope_a, 2, "assistant", "Hi", 3, tenant_id);
+
+        let stats = crate::caliber_trajectory_stats(traj_id, tenant_id).0;
+        assert_eq!(stats["scope_count"], 2);
+        assert_eq!(stats["active_scopes"], 1);
+        assert_eq!(stats["artifact_count"], 0);
+        assert_eq!(stats["note_count"], 0);
+        assert_eq!(stats["turn_count"], 2);
+        assert_eq!(stats["total_tokens_used"], 300);
+        assert_eq!(stats["total_token_budget"], 1500);
+
+        // Other tenants see nothing
+        let other = crate::caliber_trajectory_stats(traj_id, test_tenant_id()).0;
+        assert_eq!(other["scope_count"], 0);
+    }
 
-    pgrx_uuid_from_id(tenant_id)
-}
+    #[pg_test]
+    fn test_stats() {
+        let before = crate::caliber_stats().0;
 
-/// Get a tenant by domain.
-/// Returns JSON object with tenant details or null if not found.
-#[pg_extern]
-fn caliber_tenant_get_by_domain(domain: &str) -> Option<pgrx::JsonB> {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT tenant_id, name, domain, workos_organization_id, status,
-                    created_at, updated_at, metadata
-             FROM caliber_tenant WHERE domain = $1",
-            None,
-            &[text_datum(domain)],
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Ops", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        crate::caliber_turn_create(scope_id, 1, "user", "Hello", 5, tenant_id);
+
+        let after = crate::caliber_stats().0;
+        let delta = |path: &[&str]| {
+            let get = |stats: &serde_json::Value| {
+                path.iter()
+                    .fold(stats, |v, key| &v[*key])
+                    .as_i64()
+                    .expect("count")
+            };
+            get(&after) - get(&before)
+        };
+        assert_eq!(delta(&["entity_counts", "trajectories"]), 1);
+        assert_eq!(delta(&["entity_counts", "scopes"]), 1);
+        assert_eq!(delta(&["entity_counts", "turns"]), 1);
+        assert_eq!(delta(&["active", "trajectories"]), 1);
+        assert_eq!(delta(&["active", "scopes"]), 1);
+        assert_eq!(delta(&["unresolved_conflicts"]), 0);
+        // An age is only reported while messages are pending
+        assert_eq!(
+            after["oldest_pending_message_age_secs"].is_null(),
+            after["pending_messages"] == 0
         );
+    }
 
-        match result {
-            Ok(table) => {
-                let first_row = table.first();
-                if let Ok(Some(tenant_id)) = first_row.get::<pgrx::Uuid>(1) {
-                    let name: String = first_row
-                        .get::<&str>(2)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("")
-                        .to_string();
-                    let domain: Option<String> = first_row
-                        .get::<&str>(3)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let workos_org: Option<String> = first_row
-                        .get::<&str>(4)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let status: String = first_row
-                        .get::<&str>(5)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("active")
-                        .to_string();
-                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
-                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> =
-                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+    #[pg_test]
+    fn test_agent_list_active() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let idle = crate::caliber_agent_register("worker", caps, tenant_id);
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let active = crate::caliber_agent_register("worker", caps, tenant_id);
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let failed = crate::caliber_agent_register("worker", caps, tenant_id);
+        assert!(crate::caliber_agent_set_status(active, "active", tenant_id));
+        assert!(crate::caliber_agent_set_status(failed, "failed", tenant_id));
+
+        let listed = crate::caliber_agent_list_active(tenant_id).0;
+        let ids: Vec<&str> = listed
+            .as_array()
+            .expect("array")
+            .iter()
+            .filter_map(|a| a["agent_id"].as_str())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&idle.to_string().as_str()));
+        assert!(ids.contains(&active.to_string().as_str()));
+    }
 
-                    Some(pgrx::JsonB(serde_json::json!({
-                        "tenant_id": Uuid::from_bytes(*tenant_id.as_bytes()).to_string(),
-                        "name": name,
-                        "domain": domain,
-                        "workos_organization_id": workos_org,
-                        "status": status,
-                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "metadata": metadata.map(|m| m.0),
-                    })))
-                } else {
-                    None
-                }
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to get tenant by domain: {}", e);
-                None
-            }
+    #[pg_test]
+    fn test_agent_list_stale() {
+        let tenant_id = test_tenant_id();
+        let caps = || pgrx::JsonB(serde_json::json!([]));
+        let quiet = crate::caliber_agent_register("worker", caps(), tenant_id);
+        let failed = crate::caliber_agent_register("worker", caps(), tenant_id);
+        crate::caliber_agent_register("worker", caps(), tenant_id);
+        assert!(crate::caliber_agent_set_status(failed, "failed", tenant_id));
+        Spi::run(&format!(
+            "UPDATE caliber_agent SET last_heartbeat = NOW() - INTERVAL '2 hours'
+             WHERE agent_id IN ('{}', '{}')",
+            quiet, failed
+        ))
+        .expect("age heartbeats");
+
+        // Default threshold is one hour; failed agents are not reported
+        let stale = crate::caliber_agent_list_stale(tenant_id).0;
+        let ids: Vec<&str> = stale
+            .as_array()
+            .expect("array")
+            .iter()
+            .filter_map(|a| a["agent_id"].as_str())
+            .collect();
+        assert_eq!(ids, vec![quiet.to_string().as_str()]);
+
+        assert!(crate::caliber_config_set(
+            "stale_threshold",
+            pgrx::JsonB(serde_json::json!(86400)),
+            tenant_id
+        ));
+        let stale = crate::caliber_agent_list_stale(tenant_id).0;
+        assert_eq!(stale, serde_json::json!([]));
+    }
+
+    #[pg_test]
+    fn test_region_update() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let owner = crate::caliber_agent_register("owner", caps, tenant_id);
+        let region_id = crate::caliber_region_create(owner, "team", None, false, tenant_id)
+            .expect("region should be created");
+
+        let updated = crate::caliber_region_update(
+            region_id,
+            pgrx::JsonB(serde_json::json!({
+                "require_lock": true,
+                "conflict_resolution": "highest_confidence",
+            })),
+            tenant_id,
+        );
+        assert!(updated);
+
+        let region = crate::caliber_region_get(region_id, tenant_id)
+            .expect("region")
+            .0;
+        assert_eq!(region["require_lock"], true);
+        assert_eq!(region["conflict_resolution"], "highest_confidence");
+        // Untouched fields keep their creation defaults
+        assert_eq!(region["version_tracking"], true);
+
+        let invalid = crate::caliber_region_update(
+            region_id,
+            pgrx::JsonB(serde_json::json!({"conflict_resolution": "coin_flip"})),
+            tenant_id,
+        );
+        assert!(!invalid);
+        for flags in [
+            serde_json::json!({"require_lock": "false"}),
+            serde_json::json!({"version_tracking": 0}),
+        ] {
+            assert!(!crate::caliber_region_update(
+                region_id,
+                pgrx::JsonB(flags),
+                tenant_id
+            ));
         }
-    })
-}
+        let empty =
+            crate::caliber_region_update(region_id, pgrx::JsonB(serde_json::json!({})), tenant_id);
+        assert!(!empty);
+    }
 
-/// Get a tenant by WorkOS organization ID.
-/// Returns JSON object with tenant details or null if not found.
-#[pg_extern]
-fn caliber_tenant_get_by_workos_org(workos_organization_id: &str) -> Option<pgrx::JsonB> {
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT tenant_id, name, domain, workos_organization_id, status,
-                    created_at, updated_at, metadata
-             FROM caliber_tenant WHERE workos_organization_id = $1",
+    #[pg_test]
+    fn test_region_delete() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let owner = crate::caliber_agent_register("owner", caps, tenant_id);
+        let region_id = crate::caliber_region_create(owner, "collaborative", None, true, tenant_id)
+            .expect("region should be created");
+
+        let lock_id = crate::caliber_lock_acquire(
+            owner,
+            "region",
+            region_id,
+            60_000,
+            "exclusive",
             None,
-            &[text_datum(workos_organization_id)],
-        );
+            tenant_id,
+        )
+        .expect("lock should be acquired");
 
-        match result {
-            Ok(table) => {
-                let first_row = table.first();
-                if let Ok(Some(tenant_id)) = first_row.get::<pgrx::Uuid>(1) {
-                    let name: String = first_row
-                        .get::<&str>(2)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("")
-                        .to_string();
-                    let domain: Option<String> = first_row
-                        .get::<&str>(3)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let workos_org: Option<String> = first_row
-                        .get::<&str>(4)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let status: String = first_row
-                        .get::<&str>(5)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("active")
-                        .to_string();
-                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
-                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> =
-                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+        // Active lock blocks a plain delete
+        assert!(!crate::caliber_region_delete(region_id, false, tenant_id));
+        assert!(crate::caliber_region_get(region_id, tenant_id).is_some());
 
-                    Some(pgrx::JsonB(serde_json::json!({
-                        "tenant_id": Uuid::from_bytes(*tenant_id.as_bytes()).to_string(),
-                        "name": name,
-                        "domain": domain,
-                        "workos_organization_id": workos_org,
-                        "status": status,
-                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "metadata": metadata.map(|m| m.0),
-                    })))
-                } else {
-                    None
-                }
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to get tenant by WorkOS org: {}", e);
-                None
-            }
+        // Forced delete releases the lock first
+        assert!(crate::caliber_region_delete(region_id, true, tenant_id));
+        assert!(crate::caliber_region_get(region_id, tenant_id).is_none());
+        assert!(crate::caliber_lock_get(lock_id, tenant_id).is_none());
+
+        // Missing region
+        assert!(!crate::caliber_region_delete(region_id, true, tenant_id));
+    }
+
+    #[pg_test]
+    fn test_regions_for_agent() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let owner = crate::caliber_agent_register("owner", caps, tenant_id);
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let member = crate::caliber_agent_register("member", caps, tenant_id);
+
+        let private = crate::caliber_region_create(owner, "private", None, false, tenant_id)
+            .expect("region should be created");
+        let team = crate::caliber_region_create(owner, "team", None, false, tenant_id)
+            .expect("region should be created");
+        let public = crate::caliber_region_create(owner, "public", None, false, tenant_id)
+            .expect("region should be created");
+        assert!(crate::caliber_region_add_reader(team, member, tenant_id));
+
+        let ids = |listed: pgrx::JsonB| -> Vec<String> {
+            listed
+                .0
+                .as_array()
+                .expect("array")
+                .iter()
+                .filter_map(|r| r["region_id"].as_str().map(|s| s.to_string()))
+                .collect()
+        };
+
+        let readable = ids(crate::caliber_regions_for_agent(member, "read", tenant_id));
+        assert!(!readable.contains(&private.to_string()));
+        assert!(readable.contains(&team.to_string()));
+        assert!(readable.contains(&public.to_string()));
+
+        let writable = ids(crate::caliber_regions_for_agent(member, "write", tenant_id));
+        assert!(writable.is_empty());
+
+        // Results agree with caliber_check_access
+        for region in [private, team, public] {
+            assert_eq!(
+                readable.contains(&region.to_string()),
+                crate::caliber_check_access(member, region, "read")
+            );
         }
-    })
-}
 
-/// Upsert a tenant member (insert or update on conflict).
-/// If the member already exists for this tenant+user_id, updates their info.
-/// Returns the member_id.
-#[pg_extern]
-fn caliber_tenant_member_upsert(
-    tenant_id: pgrx::Uuid,
-    user_id: &str,
-    email: &str,
-    role: &str,
-    first_name: Option<&str>,
-    last_name: Option<&str>,
-) -> pgrx::Uuid {
-    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
-    let member_id = Uuid::now_v7();
+        let owned = ids(crate::caliber_regions_for_agent(owner, "write", tenant_id));
+        assert_eq!(owned.len(), 3);
+
+        let invalid = crate::caliber_regions_for_agent(owner, "admin", tenant_id);
+        assert_eq!(invalid.0.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_region_version() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let owner = crate::caliber_agent_register("owner", caps, tenant_id);
+        let tracked = crate::caliber_region_create(owner, "collaborative", None, false, tenant_id)
+            .expect("region should be created");
+        let untracked = crate::caliber_region_create(owner, "private", None, false, tenant_id)
+            .expect("region should be created");
+        assert_eq!(crate::caliber_region_version(tracked, tenant_id), Some(0));
+
+        let traj_id = crate::caliber_trajectory_create("Versions", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create_in_region = |region: pgrx::Uuid| {
+            let artifact_id = crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                "Fact",
+                "shared content",
+                1,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+            let metadata = serde_json::json!({"metadata": {"region_id": region.to_string()}});
+            assert!(crate::caliber_artifact_update(
+                artifact_id,
+                pgrx::JsonB(metadata),
+                tenant_id
+            ));
+            artifact_id
+        };
+
+        let artifact_id = create_in_region(tracked);
+        assert_eq!(crate::caliber_region_version(tracked, tenant_id), Some(1));
+        assert!(crate::caliber_artifact_update(
+            artifact_id,
+            pgrx::JsonB(serde_json::json!({"content": "edited"})),
+            tenant_id
+        ));
+        assert_eq!(crate::caliber_region_version(tracked, tenant_id), Some(2));
+
+        // Cloning the scope creates a copy of the artifact in the same region
+        assert!(crate::caliber_scope_clone(scope_id, "Clone", true, tenant_id).is_some());
+        assert_eq!(crate::caliber_region_version(tracked, tenant_id), Some(3));
+
+        // Regions without version tracking keep their version
+        create_in_region(untracked);
+        assert_eq!(crate::caliber_region_version(untracked, tenant_id), Some(0));
+
+        // Another tenant's artifact cannot bump this tenant's region
+        let other_tenant = test_tenant_id();
+        let other_traj = crate::caliber_trajectory_create("Other", None, None, other_tenant);
+        let other_scope =
+            crate::caliber_scope_create(other_traj, "Scope", None, 1000, other_tenant);
+        let foreign = crate::caliber_artifact_create(
+            other_traj,
+            other_scope,
+            "fact",
+            "Fact",
+            "foreign content",
+            1,
+            "explicit",
+            None,
+            "persistent",
+            other_tenant,
+        )
+        .expect("artifact should be created");
+        let metadata = serde_json::json!({"metadata": {"region_id": tracked.to_string()}});
+        assert!(crate::caliber_artifact_update(
+            foreign,
+            pgrx::JsonB(metadata),
+            other_tenant
+        ));
+        assert_eq!(crate::caliber_region_version(tracked, tenant_id), Some(3));
+    }
 
-    Spi::connect_mut(|client| {
-        let result = client.update(
-            "INSERT INTO caliber_tenant_member (member_id, tenant_id, user_id, email, role, first_name, last_name)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)
-             ON CONFLICT (tenant_id, user_id) DO UPDATE SET
-                 email = EXCLUDED.email,
-                 role = EXCLUDED.role,
-                 first_name = EXCLUDED.first_name,
-                 last_name = EXCLUDED.last_name,
-                 last_seen_at = NOW()",
+    #[pg_test]
+    fn test_conflict_list_by_trajectory_and_agent() {
+        use caliber_core::{ConflictId, ConflictType, EntityIdType, TenantId, TrajectoryId};
+
+        let tenant_id = test_tenant_id();
+        let caps_value = serde_json::json!([]);
+        let agent_a =
+            crate::caliber_agent_register("writer", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let agent_b =
+            crate::caliber_agent_register("writer", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let bystander = crate::caliber_agent_register("writer", pgrx::JsonB(caps_value), tenant_id);
+        let traj_id = crate::caliber_trajectory_create("Conflicts", None, None, tenant_id);
+        let other_traj = crate::caliber_trajectory_create("Other", None, None, tenant_id);
+
+        let create = |trajectory: pgrx::Uuid| {
+            let conflict_id = ConflictId::now_v7();
+            crate::conflict_heap::conflict_create_heap(
+                crate::conflict_heap::ConflictCreateParams {
+                    conflict_id,
+                    conflict_type: ConflictType::ConcurrentWrite,
+                    item_a_type: "artifact",
+                    item_a_id: uuid::Uuid::now_v7(),
+                    item_b_type: "artifact",
+                    item_b_id: uuid::Uuid::now_v7(),
+                    agent_a_id: Some(crate::id_from_pgrx(agent_a)),
+                    agent_b_id: Some(crate::id_from_pgrx(agent_b)),
+                    trajectory_id: Some(crate::id_from_pgrx::<TrajectoryId>(trajectory)),
+                    tenant_id: crate::id_from_pgrx::<TenantId>(tenant_id),
+                },
+            )
+            .expect("conflict should be created");
+            crate::pgrx_uuid_from_id(conflict_id)
+        };
+        let resolved = create(traj_id);
+        create(traj_id);
+        create(other_traj);
+        assert!(crate::caliber_conflict_resolve(
+            resolved,
+            "last_write_wins",
             None,
-            &[
-                uuid_datum(member_id),
-                uuid_datum(tenant_uuid),
-                text_datum(user_id),
-                text_datum(email),
-                text_datum(role),
-                opt_text_datum(first_name),
-                opt_text_datum(last_name),
-            ],
+            "newer",
+            tenant_id
+        ));
+
+        let len = |v: pgrx::JsonB| v.0.as_array().map(|a| a.len());
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_trajectory(
+                traj_id, None, tenant_id
+            )),
+            Some(2)
         );
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_trajectory(
+                traj_id,
+                Some("resolved"),
+                tenant_id
+            )),
+            Some(1)
+        );
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_agent(
+                agent_b, None, tenant_id
+            )),
+            Some(3)
+        );
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_agent(
+                agent_a,
+                Some("detected"),
+                tenant_id
+            )),
+            Some(2)
+        );
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_agent(
+                bystander, None, tenant_id
+            )),
+            Some(0)
+        );
+        assert_eq!(
+            len(crate::caliber_conflict_list_by_agent(
+                agent_a,
+                Some("bogus"),
+                tenant_id
+            )),
+            Some(0)
+        );
+    }
 
-        if let Err(e) = result {
-            pgrx::warning!("CALIBER: Failed to upsert tenant member: {}", e);
+    #[pg_test]
+    fn test_detect_contradictions() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Facts", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+
+        let fact = |content: &str, data: Vec<f32>, polarity: &str| {
+            let artifact_id = crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                "Fact",
+                content,
+                1,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+            let embedding = caliber_core::EmbeddingVector::new(data, "test".to_string());
+            assert!(crate::caliber_artifact_update(
+                artifact_id,
+                pgrx::JsonB(serde_json::json!({
+                    "embedding": embedding,
+                    "metadata": {"polarity": polarity},
+                })),
+                tenant_id
+            ));
+            artifact_id
+        };
+        let a = fact("The cache is enabled", vec![1.0, 0.0, 0.0], "positive");
+        let b = fact("The cache is not enabled", vec![0.99, 0.1, 0.0], "negative");
+        fact("Deploys run nightly", vec![0.0, 1.0, 0.0], "negative");
+
+        let created = crate::caliber_detect_contradictions(traj_id, Some(0.9), tenant_id).0;
+        let created = created.as_array().expect("array");
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0]["reason"], "polarity");
+        let pair = [
+            created[0]["item_a_id"].as_str().unwrap_or_default(),
+            created[0]["item_b_id"].as_str().unwrap_or_default(),
+        ];
+        assert!(pair.contains(&a.to_string().as_str()));
+        assert!(pair.contains(&b.to_string().as_str()));
+
+        // Existing conflicts are not duplicated
+        let again = crate::caliber_detect_contradictions(traj_id, None, tenant_id).0;
+        assert_eq!(again.as_array().map(|a| a.len()), Some(0));
+        let conflicts = crate::caliber_conflict_list_by_trajectory(traj_id, None, tenant_id).0;
+        assert_eq!(conflicts.as_array().map(|a| a.len()), Some(1));
+        assert_eq!(conflicts[0]["conflict_type"], "contradicting_fact");
+
+        let invalid = crate::caliber_detect_contradictions(traj_id, Some(1.5), tenant_id).0;
+        assert_eq!(invalid.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_detect_contradictions_uses_configured_threshold() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Facts", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+
+        for (content, data, polarity) in [
+            ("The cache is enabled", vec![1.0, 0.0, 0.0], "positive"),
+            ("The cache is not enabled", vec![0.7, 0.7, 0.0], "negative"),
+        ] {
+            let artifact_id = crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                "Fact",
+                content,
+                1,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+            let embedding = caliber_core::EmbeddingVector::new(data, "test".to_string());
+            assert!(crate::caliber_artifact_update(
+                artifact_id,
+                pgrx::JsonB(serde_json::json!({
+                    "embedding": embedding,
+                    "metadata": {"polarity": polarity},
+                })),
+                tenant_id
+            ));
         }
-    });
 
-    pgrx::Uuid::from_bytes(*member_id.as_bytes())
-}
+        // Similarity ~0.71 is below the default threshold of 0.8
+        let created = crate::caliber_detect_contradictions(traj_id, None, tenant_id).0;
+        assert_eq!(created.as_array().map(|a| a.len()), Some(0));
+
+        assert!(crate::caliber_config_set(
+            "contradiction_threshold",
+            pgrx::JsonB(serde_json::json!(0.5)),
+            tenant_id
+        ));
+        let created = crate::caliber_detect_contradictions(traj_id, None, tenant_id).0;
+        assert_eq!(created.as_array().map(|a| a.len()), Some(1));
+        assert_eq!(created[0]["reason"], "polarity");
+    }
 
-/// Count the number of members in a tenant.
-/// Used to determine if a new member should be admin (first member) or regular member.
-#[pg_extern]
-fn caliber_tenant_member_count(tenant_id: pgrx::Uuid) -> i32 {
-    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+    #[pg_test]
+    fn test_message_reply_and_thread() {
+        let tenant_id = test_tenant_id();
 
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT COUNT(*)::INTEGER FROM caliber_tenant_member WHERE tenant_id = $1",
+        let caps_value = serde_json::json!([]);
+        let asker =
+            crate::caliber_agent_register("asker", pgrx::JsonB(caps_value.clone()), tenant_id);
+        let helper = crate::caliber_agent_register("helper", pgrx::JsonB(caps_value), tenant_id);
+
+        let request = crate::caliber_message_send(
+            asker,
+            Some(helper),
             None,
-            &[uuid_datum(tenant_uuid)],
+            "context_request",
+            "{}",
+            None,
+            None,
+            vec![],
+            "normal",
+            None,
+            tenant_id,
+        )
+        .expect("request should be sent");
+
+        let reply = crate::caliber_message_reply(
+            request,
+            helper,
+            "context_share",
+            "{\"answer\": 42}",
+            "high",
+            tenant_id,
+        )
+        .expect("reply should be sent");
+        let follow_up =
+            crate::caliber_message_reply(reply, asker, "task_result", "{}", "normal", tenant_id)
+                .expect("follow-up should be sent");
+
+        // Replies go back to the original sender
+        let reply_json = crate::caliber_message_get(reply, tenant_id).expect("reply should exist");
+        assert_eq!(reply_json.0["to_agent_id"], asker.to_string());
+        assert_eq!(reply_json.0["reply_to"], request.to_string());
+
+        // The whole chain is returned from any message in it
+        let thread = crate::caliber_message_thread(reply, tenant_id);
+        let ids: Vec<String> = thread
+            .0
+            .as_array()
+            .expect("thread should be an array")
+            .iter()
+            .filter_map(|m| m["message_id"].as_str().map(str::to_string))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                request.to_string(),
+                reply.to_string(),
+                follow_up.to_string()
+            ]
         );
 
-        match result {
-            Ok(table) => table
-                .first()
-                .get_one::<i32>()
-                .unwrap_or(Some(0))
-                .unwrap_or(0),
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to count tenant members: {}", e);
-                0
-            }
-        }
-    })
-}
+        let unknown = uuid::Uuid::now_v7();
+        assert!(crate::caliber_message_reply(
+            pgrx::Uuid::from_bytes(*unknown.as_bytes()),
+            helper,
+            "task_result",
+            "{}",
+            "normal",
+            tenant_id,
+        )
+        .is_none());
+    }
 
-/// Get a tenant by ID.
-/// Returns JSON object with tenant details or null if not found.
-#[pg_extern]
-fn caliber_tenant_get(tenant_id: pgrx::Uuid) -> Option<pgrx::JsonB> {
-    let tenant_uuid = Uuid::from_bytes(*tenant_id.as_bytes());
+    #[pg_test]
+    fn test_agent_set_context() {
+        let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let agent_id = crate::caliber_agent_register("worker", caps, tenant_id);
 
-    Spi::connect(|client| {
-        let result = client.select(
-            "SELECT tenant_id, name, domain, workos_organization_id, status,
-                    created_at, updated_at, metadata
-             FROM caliber_tenant WHERE tenant_id = $1",
+        let trajectory_id = crate::caliber_trajectory_create("context task", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(trajectory_id, "work", None, 1000, tenant_id);
+        let other_trajectory =
+            crate::caliber_trajectory_create("other task", None, None, tenant_id);
+
+        assert!(crate::caliber_agent_set_context(
+            agent_id,
+            Some(trajectory_id),
+            Some(scope_id),
+            tenant_id
+        ));
+        let agent = crate::caliber_agent_get(agent_id, tenant_id).expect("agent should exist");
+        assert_eq!(agent.0["current_trajectory_id"], trajectory_id.to_string());
+        assert_eq!(agent.0["current_scope_id"], scope_id.to_string());
+
+        // Scope from another trajectory and unknown ids are rejected
+        assert!(!crate::caliber_agent_set_context(
+            agent_id,
+            Some(other_trajectory),
+            Some(scope_id),
+            tenant_id
+        ));
+        let unknown = uuid::Uuid::now_v7();
+        assert!(!crate::caliber_agent_set_context(
+            agent_id,
+            Some(pgrx::Uuid::from_bytes(*unknown.as_bytes())),
             None,
-            &[uuid_datum(tenant_uuid)],
+            tenant_id
+        ));
+
+        // NULL clears both fields
+        assert!(crate::caliber_agent_set_context(
+            agent_id, None, None, tenant_id
+        ));
+        let agent = crate::caliber_agent_get(agent_id, tenant_id).expect("agent should exist");
+        assert!(agent.0["current_trajectory_id"].is_null());
+        assert!(agent.0["current_scope_id"].is_null());
+    }
+
+    #[pg_test]
+    fn test_agents_on_trajectory() {
+        let tenant_id = test_tenant_id();
+        let trajectory_id = crate::caliber_trajectory_create("shared task", None, None, tenant_id);
+
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let engaged = crate::caliber_agent_register("worker", caps, tenant_id);
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let elsewhere = crate::caliber_agent_register("worker", caps, tenant_id);
+        assert!(crate::caliber_agent_set_context(
+            engaged,
+            Some(trajectory_id),
+            None,
+            tenant_id
+        ));
+        assert!(crate::caliber_agent_set_status(
+            engaged, "active", tenant_id
+        ));
+
+        let agents = crate::caliber_agents_on_trajectory(trajectory_id, tenant_id).0;
+        let agents = agents.as_array().expect("array");
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0]["agent_id"], engaged.to_string());
+        assert_eq!(agents[0]["status"], "active");
+        assert!(agents[0]["last_heartbeat"].is_string());
+        assert_ne!(agents[0]["agent_id"], elsewhere.to_string());
+
+        // Clearing the context removes the agent from the listing
+        assert!(crate::caliber_agent_set_context(
+            engaged, None, None, tenant_id
+        ));
+        let agents = crate::caliber_agents_on_trajectory(trajectory_id, tenant_id).0;
+        assert_eq!(agents.as_array().map(|a| a.len()), Some(0));
+    }
+
+    #[pg_test]
+    fn test_result_envelopes() {
+        let tenant_id = test_tenant_id();
+
+        let created = crate::caliber_trajectory_create_result("envelope", None, None, tenant_id).0;
+        assert_eq!(created["ok"], true);
+        assert!(created["error"].is_null());
+        assert_eq!(created["data"]["name"], "envelope");
+        let trajectory_id = pgrx::Uuid::from_bytes(
+            *uuid::Uuid::parse_str(created["data"]["trajectory_id"].as_str().unwrap())
+                .unwrap()
+                .as_bytes(),
         );
 
-        match result {
-            Ok(table) => {
-                let first_row = table.first();
-                if let Ok(Some(id)) = first_row.get::<pgrx::Uuid>(1) {
-                    let name: String = first_row
-                        .get::<&str>(2)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("")
-                        .to_string();
-                    let domain: Option<String> = first_row
-                        .get::<&str>(3)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let workos_org: Option<String> = first_row
-                        .get::<&str>(4)
-                        .ok()
-                        .flatten()
-                        .map(|s| s.to_string());
-                    let status: String = first_row
-                        .get::<&str>(5)
-                        .ok()
-                        .flatten()
-                        .unwrap_or("active")
-                        .to_string();
-                    let created_at = first_row.get::<TimestampWithTimeZone>(6).ok().flatten();
-                    let updated_at = first_row.get::<TimestampWithTimeZone>(7).ok().flatten();
-                    let metadata: Option<pgrx::JsonB> =
-                        first_row.get::<pgrx::JsonB>(8).ok().flatten();
+        let scope =
+            crate::caliber_scope_create_result(trajectory_id, "work", None, 100, tenant_id).0;
+        assert_eq!(scope["ok"], true);
+        let scope_id = pgrx::Uuid::from_bytes(
+            *uuid::Uuid::parse_str(scope["data"]["scope_id"].as_str().unwrap())
+                .unwrap()
+                .as_bytes(),
+        );
 
-                    Some(pgrx::JsonB(serde_json::json!({
-                        "tenant_id": Uuid::from_bytes(*id.as_bytes()).to_string(),
-                        "name": name,
-                        "domain": domain,
-                        "workos_organization_id": workos_org,
-                        "status": status,
-                        "created_at": created_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "updated_at": updated_at.map(|t| tuple_extract::timestamp_to_chrono(t).to_rfc3339()),
-                        "metadata": metadata.map(|m| m.0),
-                    })))
-                } else {
-                    None
-                }
-            }
-            Err(e) => {
-                pgrx::warning!("CALIBER: Failed to get tenant: {}", e);
-                None
-            }
+        // Validation failures are reported as such, not as missing rows
+        let bad_status =
+            crate::caliber_trajectory_set_status_result(trajectory_id, "bogus", tenant_id).0;
+        assert_eq!(bad_status["ok"], false);
+        assert_eq!(bad_status["error_kind"], "validation");
+        let empty_update = crate::caliber_scope_update_result(
+            scope_id,
+            pgrx::JsonB(serde_json::json!({})),
+            tenant_id,
+        )
+        .0;
+        assert_eq!(empty_update["error_kind"], "validation");
+        let bad_artifact = crate::caliber_artifact_create_result(
+            trajectory_id,
+            scope_id,
+            "not_a_type",
+            "a",
+            "content",
+            1,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .0;
+        assert_eq!(bad_artifact["error_kind"], "validation");
+
+        // Unknown ids are reported as not found
+        let unknown = uuid::Uuid::now_v7();
+        let missing = crate::caliber_scope_close_result(
+            pgrx::Uuid::from_bytes(*unknown.as_bytes()),
+            tenant_id,
+        )
+        .0;
+        assert_eq!(missing["ok"], false);
+        assert_eq!(missing["error_kind"], "not_found");
+
+        let closed = crate::caliber_scope_close_result(scope_id, tenant_id).0;
+        assert_eq!(closed["ok"], true);
+        assert_eq!(closed["data"]["is_active"], false);
+
+        let completed =
+            crate::caliber_trajectory_set_status_result(trajectory_id, "completed", tenant_id).0;
+        assert_eq!(completed["ok"], true);
+        assert_eq!(completed["data"]["status"], "completed");
+    }
+
+    #[pg_test]
+    fn test_turn_create_duplicate_sequence() {
+        let tenant_id = test_tenant_id();
+        let trajectory_id = crate::caliber_trajectory_create("replay", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(trajectory_id, "work", None, 1000, tenant_id);
+
+        let first = crate::caliber_turn_create_result(scope_id, 1, "user", "hi", 1, tenant_id).0;
+        assert_eq!(first["ok"], true);
+        let first_id = first["data"]["turn_id"].clone();
+
+        // Replaying the same sequence is recognizable as a conflict
+        let replay = crate::caliber_turn_create_result(scope_id, 1, "user", "hi", 1, tenant_id).0;
+        assert_eq!(replay["ok"], false);
+        assert_eq!(replay["error_kind"], "already_exists");
+        assert_eq!(replay["existing_turn_id"], first_id);
+        assert!(crate::caliber_turn_create(scope_id, 1, "user", "hi", 1, tenant_id).is_none());
+
+        // ...and distinct from a validation failure
+        let invalid =
+            crate::caliber_turn_create_result(scope_id, 2, "narrator", "hi", 1, tenant_id).0;
+        assert_eq!(invalid["error_kind"], "validation");
+        assert!(invalid.get("existing_turn_id").is_none());
+
+        // Another tenant hitting the same sequence never sees the turn's ID
+        let other_tenant = test_tenant_id();
+        let foreign =
+            crate::caliber_turn_create_result(scope_id, 1, "user", "hi", 1, other_tenant).0;
+        assert_eq!(foreign["ok"], false);
+        assert_eq!(foreign["error_kind"], "storage");
+        assert!(foreign.get("existing_turn_id").is_none());
+        assert!(!foreign
+            .to_string()
+            .contains(first_id.as_str().unwrap_or_default()));
+
+        let turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id).0;
+        assert_eq!(turns.as_array().map(|a| a.len()), Some(1));
+    }
+
+    #[pg_test]
+    fn test_turn_get_range_and_recent() {
+        let tenant_id = test_tenant_id();
+        let trajectory_id = crate::caliber_trajectory_create("window", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(trajectory_id, "chat", None, 1000, tenant_id);
+        // Insert out of order to make sure results follow sequence, not insertion
+        for seq in [3, 1, 5, 2, 4] {
+            assert!(
+                crate::caliber_turn_create(scope_id, seq, "user", "msg", 1, tenant_id).is_some()
+            );
         }
-    })
-}
 
-// ============================================================================
-// PGRX INTEGRATION TESTS (Task 12.8)
-// ============================================================================
+        let sequences = |turns: pgrx::JsonB| -> Vec<i64> {
+            turns
+                .0
+                .as_array()
+                .expect("array")
+                .iter()
+                .filter_map(|t| t["sequence"].as_i64())
+                .collect()
+        };
+
+        let range = crate::caliber_turn_get_range(scope_id, 2, 4, tenant_id);
+        assert_eq!(sequences(range), vec![2, 3, 4]);
+        let empty = crate::caliber_turn_get_range(scope_id, 6, 9, tenant_id);
+        assert!(sequences(empty).is_empty());
+        let inverted = crate::caliber_turn_get_range(scope_id, 4, 2, tenant_id);
+        assert!(sequences(inverted).is_empty());
+
+        let recent = crate::caliber_turn_get_recent(scope_id, 2, tenant_id);
+        assert_eq!(sequences(recent), vec![4, 5]);
+        let all = crate::caliber_turn_get_recent(scope_id, i32::MAX, tenant_id);
+        assert_eq!(sequences(all), vec![1, 2, 3, 4, 5]);
+        let none = crate::caliber_turn_get_recent(scope_id, 0, tenant_id);
+        assert!(sequences(none).is_empty());
+    }
+
+    #[pg_test]
+    fn test_assemble_history() {
+        let tenant_id = test_tenant_id();
+        let trajectory_id = crate::caliber_trajectory_create("history", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(trajectory_id, "chat", None, 1000, tenant_id);
+        for (seq, tokens) in [(1, 40), (2, 30), (3, 20), (4, 10)] {
+            assert!(
+                crate::caliber_turn_create(scope_id, seq, "user", "msg", tokens, tenant_id)
+                    .is_some()
+            );
+        }
+
+        let history = crate::caliber_assemble_history(scope_id, 65, tenant_id).0;
+        let sequences: Vec<i64> = history["turns"]
+            .as_array()
+            .expect("turns array")
+            .iter()
+            .filter_map(|t| t["sequence"].as_i64())
+            .collect();
+        assert_eq!(sequences, vec![2, 3, 4]);
+        assert_eq!(history["tokens_used"], 60);
+        assert_eq!(history["turns_included"], 3);
+        assert_eq!(history["turns_dropped"], 1);
+
+        let empty = crate::caliber_assemble_history(scope_id, 5, tenant_id).0;
+        assert_eq!(empty["turns_included"], 0);
+        assert_eq!(empty["turns_dropped"], 4);
+    }
+
+    #[pg_test]
+    fn test_assemble_context_priorities() {
+        let tenant_id = test_tenant_id();
+        let trajectory_id = crate::caliber_trajectory_create("context", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(trajectory_id, "chat", None, 1000, tenant_id);
+        assert!(
+            crate::caliber_turn_create(scope_id, 1, "user", "What changed?", 4, tenant_id)
+                .is_some()
+        );
+        assert!(crate::caliber_note_create(
+            "fact",
+            "Deploys",
+            "Deploys happen on Tuesdays",
+            vec![trajectory_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .is_some());
+
+        // History outranks notes when requested
+        let priorities = pgrx::JsonB(serde_json::json!({"history": 95, "notes": 10}));
+        let context = crate::caliber_assemble_context(scope_id, 1000, priorities, tenant_id).0;
+        let section_types: Vec<&str> = context["sections"]
+            .as_array()
+            .expect("sections array")
+            .iter()
+            .filter_map(|s| s["section_type"].as_str())
+            .collect();
+        assert_eq!(section_types, vec!["conversation_history", "notes"]);
+        assert!(context["tokens_used"].as_i64().unwrap_or(0) > 0);
+
+        // Unknown section names are rejected
+        let bad = pgrx::JsonB(serde_json::json!({"bogus": 1}));
+        let rejected = crate::caliber_assemble_context(scope_id, 1000, bad, tenant_id).0;
+        assert_eq!(rejected["sections"], serde_json::json!([]));
+    }
+
+    #[pg_test]
+    fn test_merge_metadata() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Merge", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+
+        let first = pgrx::JsonB(serde_json::json!({"owner": "a", "stage": 1}));
+        let merged = crate::caliber_trajectory_merge_metadata(traj_id, first, tenant_id)
+            .expect("trajectory exists");
+        assert_eq!(merged.0, serde_json::json!({"owner": "a", "stage": 1}));
+
+        // Other keys survive; null deletes
+        let second = pgrx::JsonB(serde_json::json!({"stage": 2, "owner": null}));
+        let merged = crate::caliber_trajectory_merge_metadata(traj_id, second, tenant_id)
+            .expect("trajectory exists");
+        assert_eq!(merged.0, serde_json::json!({"stage": 2}));
+
+        let patch = pgrx::JsonB(serde_json::json!({"focus": "tests"}));
+        let merged =
+            crate::caliber_scope_merge_metadata(scope_id, patch, tenant_id).expect("scope exists");
+        assert_eq!(merged.0["focus"], "tests");
+
+        let not_object = pgrx::JsonB(serde_json::json!(["focus"]));
+        assert!(crate::caliber_scope_merge_metadata(scope_id, not_object, tenant_id).is_none());
+
+        let missing = pgrx::Uuid::from_bytes(*uuid::Uuid::now_v7().as_bytes());
+        let patch = pgrx::JsonB(serde_json::json!({"k": 1}));
+        assert!(crate::caliber_trajectory_merge_metadata(missing, patch, tenant_id).is_none());
+    }
+
+    #[pg_test]
+    fn test_trajectory_set_metadata_path() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Path", None, None, tenant_id);
+        let existing = pgrx::JsonB(serde_json::json!({"owner": "a"}));
+        assert!(crate::caliber_trajectory_merge_metadata(traj_id, existing, tenant_id).is_some());
+
+        let path = pgrx::JsonB(serde_json::json!(["review", "checks", "lint"]));
+        let value = pgrx::JsonB(serde_json::json!("passed"));
+        assert!(crate::caliber_trajectory_set_metadata_path(
+            traj_id, path, value, tenant_id
+        ));
+
+        let path = pgrx::JsonB(serde_json::json!(["review", "checks", "tests"]));
+        let value = pgrx::JsonB(serde_json::json!(false));
+        assert!(crate::caliber_trajectory_set_metadata_path(
+            traj_id, path, value, tenant_id
+        ));
+
+        let merged = crate::caliber_trajectory_merge_metadata(
+            traj_id,
+            pgrx::JsonB(serde_json::json!({})),
+            tenant_id,
+        )
+        .expect("trajectory exists");
+        assert_eq!(
+            merged.0,
+            serde_json::json!({
+                "owner": "a",
+                "review": {"checks": {"lint": "passed", "tests": false}},
+            })
+        );
+
+        // A scalar in the middle of the path is rejected, not replaced
+        let through_scalar = pgrx::JsonB(serde_json::json!(["owner", "name"]));
+        assert!(!crate::caliber_trajectory_set_metadata_path(
+            traj_id,
+            through_scalar,
+            pgrx::JsonB(serde_json::json!("b")),
+            tenant_id
+        ));
+        let unchanged = crate::caliber_trajectory_merge_metadata(
+            traj_id,
+            pgrx::JsonB(serde_json::json!({})),
+            tenant_id,
+        )
+        .expect("trajectory exists");
+        assert_eq!(unchanged.0["owner"], serde_json::json!("a"));
 
-#[cfg(feature = "pg_test")]
-#[pgrx::pg_schema]
-mod tests {
-    use pgrx::prelude::*;
+        let empty_path = pgrx::JsonB(serde_json::json!([]));
+        assert!(!crate::caliber_trajectory_set_metadata_path(
+            traj_id,
+            empty_path,
+            pgrx::JsonB(serde_json::json!(1)),
+            tenant_id
+        ));
+    }
 
-    fn test_tenant_id() -> pgrx::Uuid {
-        crate::caliber_new_id()
+    #[pg_test]
+    fn test_edge_exists() {
+        let tenant_id = test_tenant_id();
+        let a = uuid::Uuid::now_v7();
+        let b = uuid::Uuid::now_v7();
+        let c = uuid::Uuid::now_v7();
+        let participants = pgrx::JsonB(serde_json::json!([
+            {"entity_ref": {"entity_type": "Note", "id": a.to_string()}, "role": "source"},
+            {"entity_ref": {"entity_type": "Note", "id": b.to_string()}, "role": "target"},
+        ]));
+        assert!(crate::caliber_edge_create(
+            "supports",
+            participants,
+            None,
+            None,
+            0,
+            "inferred",
+            None,
+            tenant_id,
+        )
+        .is_some());
+
+        // Order-insensitive exact match
+        let ids = |list: &[uuid::Uuid]| {
+            pgrx::JsonB(serde_json::json!(list
+                .iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<_>>()))
+        };
+        assert!(crate::caliber_edge_exists(
+            "supports",
+            ids(&[b, a]),
+            tenant_id
+        ));
+        // Subsets, supersets and other types don't match
+        assert!(!crate::caliber_edge_exists(
+            "supports",
+            ids(&[a]),
+            tenant_id
+        ));
+        assert!(!crate::caliber_edge_exists(
+            "supports",
+            ids(&[a, b, c]),
+            tenant_id
+        ));
+        assert!(!crate::caliber_edge_exists(
+            "contradicts",
+            ids(&[a, b]),
+            tenant_id
+        ));
+        assert!(!crate::caliber_edge_exists(
+            "bogus",
+            ids(&[a, b]),
+            tenant_id
+        ));
     }
 
     #[pg_test]
-    fn test_caliber_version() {
-        let version = crate::caliber_version();
-        assert!(!version.is_empty());
+    fn test_edges_by_trajectory() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Graph", None, None, tenant_id);
+        let other_traj = crate::caliber_trajectory_create("Other", None, None, tenant_id);
+        let edge = |trajectory_id: Option<pgrx::Uuid>| {
+            let participants = pgrx::JsonB(serde_json::json!([
+                {"entity_ref": {"entity_type": "Note", "id": uuid::Uuid::now_v7().to_string()}, "role": "source"},
+                {"entity_ref": {"entity_type": "Note", "id": uuid::Uuid::now_v7().to_string()}, "role": "target"},
+            ]));
+            crate::caliber_edge_create(
+                "supports",
+                participants,
+                None,
+                trajectory_id,
+                0,
+                "explicit",
+                None,
+                tenant_id,
+            )
+            .expect("edge should be created")
+        };
+        let first = edge(Some(traj_id));
+        let second = edge(Some(traj_id));
+        edge(Some(other_traj));
+        edge(None);
+
+        let edges = crate::caliber_edges_by_trajectory(traj_id, tenant_id).0;
+        let ids: Vec<&str> = edges
+            .as_array()
+            .expect("should be an array")
+            .iter()
+            .filter_map(|e| e["edge_id"].as_str())
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&first.to_string().as_str()));
+        assert!(ids.contains(&second.to_string().as_str()));
+        assert_eq!(edges[0]["edge_type"], "supports");
+        assert_eq!(edges[0]["trajectory_id"], traj_id.to_string());
     }
 
     #[pg_test]
-    fn test_caliber_new_id() {
-        let id1 = crate::caliber_new_id();
-        let id2 = crate::caliber_new_id();
-        // IDs should be unique
-        assert_ne!(id1, id2);
+    fn test_edge_neighbor_scores() {
+        let tenant_id = test_tenant_id();
+        let a = uuid::Uuid::now_v7();
+        let b = uuid::Uuid::now_v7();
+        let c = uuid::Uuid::now_v7();
+        let edge = |x: uuid::Uuid, y: uuid::Uuid, weight: f32| {
+            let participants = pgrx::JsonB(serde_json::json!([
+                {"entity_ref": {"entity_type": "Note", "id": x.to_string()}, "role": null},
+                {"entity_ref": {"entity_type": "Note", "id": y.to_string()}, "role": null},
+            ]));
+            crate::caliber_edge_create(
+                "relatesto",
+                participants,
+                Some(weight),
+                None,
+                0,
+                "explicit",
+                None,
+                tenant_id,
+            )
+        };
+        assert!(edge(a, b, 0.5).is_some());
+        assert!(edge(b, a, 0.25).is_some());
+        assert!(edge(a, c, 0.9).is_some());
+
+        let entity_id = pgrx::Uuid::from_bytes(*a.as_bytes());
+        let scores = crate::caliber_edge_neighbor_scores(entity_id, tenant_id).0;
+        let scores = scores.as_array().expect("scores array");
+        assert_eq!(scores.len(), 2);
+        assert_eq!(scores[0]["neighbor_id"], c.to_string());
+        assert_eq!(scores[1]["neighbor_id"], b.to_string());
+        assert_eq!(scores[1]["edge_count"], 2);
+        assert_eq!(scores[1]["total_weight"], 0.75);
+        assert_eq!(scores[1]["max_weight"], 0.5);
     }
 
     #[pg_test]
-    fn test_trajectory_lifecycle() {
-        // Clear storage first
-        crate::caliber_debug_clear();
+    fn test_artifacts_by_provenance() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Provenance", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let create = |name: &str, method: &str, confidence: Option<f32>| {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                name,
+                name,
+                0,
+                method,
+                confidence,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created")
+        };
+        create("high", "inferred", Some(0.95));
+        create("low", "inferred", Some(0.3));
+        create("user", "user_provided", None);
+
+        let names = |json: pgrx::JsonB| -> Vec<String> {
+            let mut names: Vec<String> = json
+                .0
+                .as_array()
+                .expect("array")
+                .iter()
+                .filter_map(|a| a["name"].as_str().map(String::from))
+                .collect();
+            names.sort();
+            names
+        };
+
+        let confident = crate::caliber_artifacts_by_provenance(traj_id, None, Some(0.9), tenant_id);
+        assert_eq!(names(confident), vec!["high"]);
+        let user =
+            crate::caliber_artifacts_by_provenance(traj_id, Some("user_provided"), None, tenant_id);
+        assert_eq!(names(user), vec!["user"]);
+        let all = crate::caliber_artifacts_by_provenance(traj_id, None, None, tenant_id);
+        assert_eq!(names(all).len(), 3);
+        let bad = crate::caliber_artifacts_by_provenance(traj_id, Some("guess"), None, tenant_id);
+        assert!(names(bad).is_empty());
+    }
+
+    #[pg_test]
+    fn test_artifact_supersede() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Supersede", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let other_traj = crate::caliber_trajectory_create("Other", None, None, tenant_id);
+        let other_scope = crate::caliber_scope_create(other_traj, "Scope", None, 1000, tenant_id);
+        let create = |traj, scope, name: &str| {
+            crate::caliber_artifact_create(
+                traj,
+                scope,
+                "code_patch",
+                name,
+                name,
+                0,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created")
+        };
+        let v1 = create(traj_id, scope_id, "patch v1");
+        let v2 = create(traj_id, scope_id, "patch v2");
+        let v3 = create(traj_id, scope_id, "patch v3");
+        let foreign = create(other_traj, other_scope, "foreign");
+
+        assert!(!crate::caliber_artifact_supersede(v1, v1, tenant_id));
+        assert!(!crate::caliber_artifact_supersede(v1, foreign, tenant_id));
+        assert!(crate::caliber_artifact_supersede(v1, v2, tenant_id));
+        // Already superseded
+        assert!(!crate::caliber_artifact_supersede(v1, v3, tenant_id));
+        // Superseded artifacts cannot supersede others, nor close a cycle
+        assert!(!crate::caliber_artifact_supersede(v3, v1, tenant_id));
+        assert!(!crate::caliber_artifact_supersede(v2, v1, tenant_id));
+        let v2_uuid = uuid::Uuid::from_bytes(*v2.as_bytes());
+        let v1_uuid = uuid::Uuid::from_bytes(*v1.as_bytes());
+        assert!(!crate::caliber_artifact_update(
+            v2,
+            pgrx::JsonB(serde_json::json!({"superseded_by": v1_uuid.to_string()})),
+            tenant_id
+        ));
+
+        let old = crate::caliber_artifact_get(v1, tenant_id, false).expect("artifact exists");
+        assert_eq!(old.0["superseded_by"], v2_uuid.to_string());
+        let head = crate::caliber_artifact_get(v2, tenant_id, false).expect("artifact exists");
+        assert!(head.0["superseded_by"].is_null());
+
+        let ids = pgrx::JsonB(serde_json::json!([
+            v1_uuid.to_string(),
+            v2_uuid.to_string()
+        ]));
+        assert!(crate::caliber_edge_exists("supersedes", ids, tenant_id));
+    }
 
+    #[pg_test]
+    fn test_soft_delete() {
         let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Forget", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Stale fact",
+            "No longer true",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+        let note_id = crate::caliber_note_create(
+            "insight",
+            "Stale insight",
+            "No longer true",
+            vec![traj_id],
+            vec![artifact_id],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
 
-        // Create trajectory
-        let traj_id = crate::caliber_trajectory_create(
-            "Test Trajectory",
-            Some("Test description"),
+        let replacement = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Current fact",
+            "True now",
+            0,
+            "explicit",
             None,
+            "persistent",
             tenant_id,
-        );
+        )
+        .expect("artifact should be created");
 
-        // Get trajectory
-        let traj = crate::caliber_trajectory_get(traj_id, tenant_id);
-        assert!(traj.is_some());
+        assert!(crate::caliber_artifact_soft_delete(artifact_id, tenant_id));
+        assert!(crate::caliber_note_soft_delete(note_id, tenant_id));
+        // Deleting again keeps the tombstone and is audited only once
+        assert!(crate::caliber_artifact_soft_delete(artifact_id, tenant_id));
+        assert!(crate::caliber_note_soft_delete(note_id, tenant_id));
+        for id in [artifact_id, note_id] {
+            let history = crate::caliber_audit_query(id, tenant_id).0;
+            let deletes = history
+                .as_array()
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .filter(|e| e["operation"] == "delete")
+                        .count()
+                })
+                .unwrap_or_default();
+            assert_eq!(deletes, 1);
+        }
 
-        // Update status
-        let updated = crate::caliber_trajectory_set_status(traj_id, "completed", tenant_id);
-        assert_eq!(updated, Some(true));
+        // Tombstoned notes can be neither updated nor used to supersede
+        assert!(!crate::caliber_note_update(
+            note_id,
+            pgrx::JsonB(serde_json::json!({"content": "Revived"})),
+            tenant_id
+        ));
+        let current_note = crate::caliber_note_create(
+            "insight",
+            "Current insight",
+            "True now",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
+        assert!(!crate::caliber_note_update(
+            current_note,
+            pgrx::JsonB(serde_json::json!({"superseded_by": note_id.to_string()})),
+            tenant_id
+        ));
+
+        // Tombstoned artifacts can be neither updated nor superseded
+        assert!(!crate::caliber_artifact_update(
+            artifact_id,
+            pgrx::JsonB(serde_json::json!({"content": "Revived"})),
+            tenant_id
+        ));
+        assert!(!crate::caliber_artifact_supersede(
+            artifact_id,
+            replacement,
+            tenant_id
+        ));
+        assert!(!crate::caliber_artifact_supersede(
+            replacement,
+            artifact_id,
+            tenant_id
+        ));
+
+        assert!(crate::caliber_artifact_get(artifact_id, tenant_id, false).is_none());
+        assert!(crate::caliber_note_get(note_id, tenant_id, false).is_none());
+        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, true)
+            .expect("tombstoned artifact is still stored");
+        assert!(artifact.0["deleted_at"].is_string());
+        let note = crate::caliber_note_get(note_id, tenant_id, true)
+            .expect("tombstoned note is still stored");
+        assert!(note.0["deleted_at"].is_string());
+
+        let artifacts = crate::caliber_artifact_query_by_scope(scope_id, tenant_id);
+        let artifacts = artifacts.0.as_array().cloned().unwrap_or_default();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0]["artifact_id"], replacement.to_string());
+        let notes = crate::caliber_note_query_by_trajectory(traj_id, tenant_id);
+        let notes = notes.0.as_array().cloned().unwrap_or_default();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0]["note_id"], current_note.to_string());
 
-        // Verify status change
-        let traj = crate::caliber_trajectory_get(traj_id, tenant_id);
-        assert!(traj.is_some());
+        let missing = pgrx::Uuid::from_bytes(*uuid::Uuid::now_v7().as_bytes());
+        assert!(!crate::caliber_artifact_soft_delete(missing, tenant_id));
+        assert!(!crate::caliber_note_soft_delete(missing, tenant_id));
     }
 
     #[pg_test]
-    fn test_scope_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_note_create_full() {
         let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Summaries", None, None, tenant_id);
+        let traj_uuid = uuid::Uuid::from_bytes(*traj_id.as_bytes());
+        let artifact_uuid = uuid::Uuid::now_v7();
 
-        // Create trajectory first
-        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let raw = crate::caliber_note_create_full(
+            "fact",
+            "Raw",
+            "Raw observation",
+            "persistent",
+            "raw",
+            pgrx::JsonB(serde_json::json!([traj_uuid.to_string()])),
+            pgrx::JsonB(serde_json::Value::Null),
+            pgrx::JsonB(serde_json::Value::Null),
+            tenant_id,
+        )
+        .expect("raw note should be created");
+        let raw_uuid = uuid::Uuid::from_bytes(*raw.as_bytes());
+
+        let summary = crate::caliber_note_create_full(
+            "summary",
+            "Summary",
+            "Summarized observations",
+            "long_term",
+            "summary",
+            pgrx::JsonB(serde_json::json!([traj_uuid.to_string()])),
+            pgrx::JsonB(serde_json::json!([artifact_uuid.to_string()])),
+            pgrx::JsonB(serde_json::json!([raw_uuid.to_string()])),
+            tenant_id,
+        )
+        .expect("summary note should be created");
 
-        // Create scope
-        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
+        let note = crate::caliber_note_get(summary, tenant_id, false).expect("note exists");
+        assert_eq!(note.0["note_type"], "summary");
+        assert_eq!(
+            note.0["source_artifact_ids"],
+            serde_json::json!([artifact_uuid.to_string()])
+        );
 
-        // Get scope
-        let scope = crate::caliber_scope_get(scope_id, tenant_id);
-        assert!(scope.is_some());
+        // Unknown enum strings and malformed id arrays are rejected
+        let empty = || pgrx::JsonB(serde_json::json!([]));
+        assert!(crate::caliber_note_create_full(
+            "fact",
+            "Bad",
+            "Bad",
+            "persistent",
+            "abstract",
+            empty(),
+            empty(),
+            empty(),
+            tenant_id,
+        )
+        .is_none());
+        assert!(crate::caliber_note_create_full(
+            "fact",
+            "Bad",
+            "Bad",
+            "persistent",
+            "raw",
+            pgrx::JsonB(serde_json::json!(["not-a-uuid"])),
+            empty(),
+            empty(),
+            tenant_id,
+        )
+        .is_none());
+    }
 
-        // Get current scope
-        let current = crate::caliber_scope_get_current(traj_id, tenant_id);
-        assert!(current.is_some());
+    #[pg_test]
+    fn test_artifact_create_full() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Full", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
 
-        // Close scope
-        let closed = crate::caliber_scope_close(scope_id, tenant_id);
-        assert!(closed);
+        let artifact_id = crate::caliber_artifact_create_full(
+            traj_id,
+            scope_id,
+            "fact",
+            "Embedded",
+            "Has an embedding",
+            "short_term",
+            7,
+            "inferred",
+            Some(0.6),
+            Some(pgrx::JsonB(serde_json::json!([0.1, 0.2, 0.3]))),
+            tenant_id,
+        )
+        .expect("artifact should be created");
+
+        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, false).expect("exists");
+        assert_eq!(artifact.0["provenance"]["source_turn"], 7);
+        assert_eq!(artifact.0["ttl"], "short_term");
+        assert_eq!(artifact.0["embedding"]["dimensions"], 3);
+
+        let bad_embedding = crate::caliber_artifact_create_full(
+            traj_id,
+            scope_id,
+            "fact",
+            "Bad",
+            "Bad embedding",
+            "persistent",
+            0,
+            "explicit",
+            None,
+            Some(pgrx::JsonB(serde_json::json!(["x"]))),
+            tenant_id,
+        );
+        assert!(bad_embedding.is_none());
     }
 
     #[pg_test]
-    fn test_scope_update() {
-        crate::caliber_debug_clear();
-
+    fn test_artifact_create_batch() {
         let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Batch", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
 
-        // Create trajectory first
-        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
+        let result = crate::caliber_artifact_create_batch(
+            traj_id,
+            scope_id,
+            pgrx::JsonB(serde_json::json!([
+                {"artifact_type": "fact", "name": "First", "content": "One"},
+                {"artifact_type": "bogus", "name": "Bad type", "content": "Two"},
+                {"artifact_type": "code_patch", "name": "Patch", "content": "Three", "ttl": "short_term"},
+                {"name": "Missing type", "content": "Four"},
+                "not an object",
+            ])),
+            tenant_id,
+        );
+        let ids = result.0.as_array().expect("array");
+        assert_eq!(ids.len(), 5);
+        assert!(ids[1].is_null());
+        assert!(ids[3].is_null());
+        assert!(ids[4].is_null());
+
+        let first = pgrx::Uuid::from_bytes(
+            *uuid::Uuid::parse_str(ids[0].as_str().expect("id"))
+                .expect("uuid")
+                .as_bytes(),
+        );
+        let artifact = crate::caliber_artifact_get(first, tenant_id, false).expect("exists");
+        assert_eq!(artifact.0["name"], "First");
+        assert_eq!(artifact.0["ttl"], "persistent");
+        assert_eq!(
+            artifact.0["scope_id"],
+            uuid::Uuid::from_bytes(*scope_id.as_bytes()).to_string()
+        );
 
-        // Create scope
-        let scope_id = crate::caliber_scope_create(
+        let patch = pgrx::Uuid::from_bytes(
+            *uuid::Uuid::parse_str(ids[2].as_str().expect("id"))
+                .expect("uuid")
+                .as_bytes(),
+        );
+        let artifact = crate::caliber_artifact_get(patch, tenant_id, false).expect("exists");
+        assert_eq!(artifact.0["ttl"], "short_term");
+        assert_ne!(ids[0], ids[2]);
+
+        let not_array = crate::caliber_artifact_create_batch(
             traj_id,
-            "Test Scope",
-            Some("Initial purpose"),
-            8000,
+            scope_id,
+            pgrx::JsonB(serde_json::json!({"artifact_type": "fact"})),
             tenant_id,
         );
+        assert_eq!(not_array.0, serde_json::json!([]));
+    }
 
-        // Update scope with various fields
-        let updates = pgrx::JsonB(serde_json::json!({
-            "name": "Updated Scope",
-            "purpose": "Updated purpose",
-            "tokens_used": 100,
-            "metadata": {"key": "value"}
-        }));
-        let updated = crate::caliber_scope_update(scope_id, updates, tenant_id);
-        assert!(updated);
+    #[pg_test]
+    fn test_embedding_set_validates_dimension() {
+        let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Embed", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        let artifact_id = crate::caliber_artifact_create(
+            traj_id,
+            scope_id,
+            "fact",
+            "Fact",
+            "Content",
+            0,
+            "explicit",
+            None,
+            "persistent",
+            tenant_id,
+        )
+        .expect("artifact should be created");
+        let note_id = crate::caliber_note_create(
+            "fact",
+            "Note",
+            "Content",
+            vec![traj_id],
+            vec![],
+            "persistent",
+            tenant_id,
+        )
+        .expect("note should be created");
 
-        // Get scope and verify updates
-        let scope = crate::caliber_scope_get(scope_id, tenant_id);
-        assert!(scope.is_some());
-        let scope_data = scope.unwrap().0;
-        assert_eq!(scope_data["name"].as_str(), Some("Updated Scope"));
-        assert_eq!(scope_data["purpose"].as_str(), Some("Updated purpose"));
-        assert_eq!(scope_data["tokens_used"].as_i64(), Some(100));
-        assert!(scope_data["metadata"].is_object());
+        // No dimension configured: any non-empty vector is accepted
+        assert_eq!(crate::caliber_embedding_dimension_get(tenant_id), None);
+        assert!(crate::caliber_embedding_set(
+            "artifact",
+            artifact_id,
+            pgrx::JsonB(serde_json::json!([0.1, 0.2])),
+            tenant_id,
+        ));
 
-        // Update with null values
-        let null_updates = pgrx::JsonB(serde_json::json!({
-            "purpose": null,
-            "metadata": null
-        }));
-        let updated_null = crate::caliber_scope_update(scope_id, null_updates, tenant_id);
-        assert!(updated_null);
+        assert!(!crate::caliber_embedding_dimension_set(0, tenant_id));
+        assert!(crate::caliber_embedding_dimension_set(3, tenant_id));
+        assert_eq!(crate::caliber_embedding_dimension_get(tenant_id), Some(3));
 
-        // Verify null updates
-        let scope_after_null = crate::caliber_scope_get(scope_id, tenant_id);
-        assert!(scope_after_null.is_some());
-        let scope_null_data = scope_after_null.unwrap().0;
-        assert!(scope_null_data["purpose"].is_null());
-        assert!(scope_null_data["metadata"].is_null());
+        assert!(!crate::caliber_embedding_set(
+            "artifact",
+            artifact_id,
+            pgrx::JsonB(serde_json::json!([0.1, 0.2])),
+            tenant_id,
+        ));
+        assert!(crate::caliber_embedding_set(
+            "artifact",
+            artifact_id,
+            pgrx::JsonB(serde_json::json!([0.1, 0.2, 0.3])),
+            tenant_id,
+        ));
+        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id, false).expect("exists");
+        assert_eq!(artifact.0["embedding"]["dimensions"], 3);
+
+        assert!(crate::caliber_embedding_set(
+            "note",
+            note_id,
+            pgrx::JsonB(serde_json::json!([0.4, 0.5, 0.6])),
+            tenant_id,
+        ));
+        let note = crate::caliber_note_get(note_id, tenant_id, false).expect("exists");
+        assert_eq!(note.0["embedding"]["dimensions"], 3);
+
+        // Creates and updates that carry an embedding are validated the same way
+        let short = caliber_core::EmbeddingVector::new(vec![0.1, 0.2], "test".to_string());
+        assert!(!crate::caliber_artifact_update(
+            artifact_id,
+            pgrx::JsonB(serde_json::json!({ "embedding": short })),
+            tenant_id
+        ));
+        assert!(!crate::caliber_note_update(
+            note_id,
+            pgrx::JsonB(serde_json::json!({ "embedding": [0.1, 0.2] })),
+            tenant_id
+        ));
+        let full = caliber_core::EmbeddingVector::new(vec![0.3, 0.2, 0.1], "test".to_string());
+        assert!(crate::caliber_note_update(
+            note_id,
+            pgrx::JsonB(serde_json::json!({ "embedding": full })),
+            tenant_id
+        ));
+        let note = crate::caliber_note_get(note_id, tenant_id, false).expect("exists");
+        assert_eq!(note.0["embedding"]["model_id"], "test");
+        assert!(crate::caliber_artifact_create_full(
+            traj_id,
+            scope_id,
+            "fact",
+            "Short",
+            "Short embedding",
+            "persistent",
+            0,
+            "explicit",
+            None,
+            Some(pgrx::JsonB(serde_json::json!([0.1, 0.2]))),
+            tenant_id,
+        )
+        .is_none());
+
+        assert!(!crate::caliber_embedding_set(
+            "turn",
+            note_id,
+            pgrx::JsonB(serde_json::json!([0.4, 0.5, 0.6])),
+            tenant_id,
+        ));
+        assert!(!crate::caliber_embedding_set(
+            "note",
+            crate::caliber_new_id(),
+            pgrx::JsonB(serde_json::json!([0.4, 0.5, 0.6])),
+            tenant_id,
+        ));
     }
 
     #[pg_test]
-    fn test_artifact_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_embedding_get() {
         let tenant_id = test_tenant_id();
-
-        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
-        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
-
-        // Create artifact
+        let traj_id = crate::caliber_trajectory_create("Embed", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
         let artifact_id = crate::caliber_artifact_create(
             traj_id,
             scope_id,
             "fact",
-            "Test Artifact",
-            "Test content",
+            "Fact",
+            "Content",
             0,
             "explicit",
-            Some(0.9),
+            None,
             "persistent",
             tenant_id,
         )
         .expect("artifact should be created");
 
-        // Get artifact
-        let artifact = crate::caliber_artifact_get(artifact_id, tenant_id);
-        assert!(artifact.is_some());
+        assert!(crate::caliber_embedding_get("artifact", artifact_id, tenant_id).is_none());
 
-        // Query by type
-        let artifacts = crate::caliber_artifact_query_by_type(traj_id, "fact", tenant_id);
-        let arr: Vec<serde_json::Value> = serde_json::from_value(artifacts.0).unwrap();
-        assert!(!arr.is_empty());
+        assert!(crate::caliber_embedding_set(
+            "artifact",
+            artifact_id,
+            pgrx::JsonB(serde_json::json!([0.25, 0.5, 0.75])),
+            tenant_id,
+        ));
+        let embedding = crate::caliber_embedding_get("artifact", artifact_id, tenant_id)
+            .expect("embedding should be stored");
+        assert_eq!(embedding.0, serde_json::json!([0.25, 0.5, 0.75]));
+
+        assert!(crate::caliber_embedding_get("note", artifact_id, tenant_id).is_none());
+        assert!(crate::caliber_embedding_get("turn", artifact_id, tenant_id).is_none());
     }
 
     #[pg_test]
-    fn test_note_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_enforce_retention_max() {
         let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Retention", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        for i in 0..4 {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                "fact",
+                &format!("Fact {}", i),
+                &format!("Content {}", i),
+                0,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+        }
 
-        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
-
-        // Create note
-        let note_id = crate::caliber_note_create(
-            "fact",
-            "Test Note",
-            "Test content",
-            vec![traj_id],
-            vec![],
-            "persistent",
-            tenant_id,
-        )
-        .expect("note should be created");
-
-        // Get note
-        let note = crate::caliber_note_get(note_id, tenant_id);
-        assert!(note.is_some());
+        assert_eq!(
+            crate::caliber_enforce_retention_max("artifact", traj_id, 2, tenant_id),
+            2
+        );
+        let remaining = crate::caliber_artifact_query_by_trajectory(traj_id, tenant_id);
+        assert_eq!(remaining.0.as_array().map(|a| a.len()), Some(2));
 
-        // Query by trajectory
-        let notes = crate::caliber_note_query_by_trajectory(traj_id, tenant_id);
-        let arr: Vec<serde_json::Value> = serde_json::from_value(notes.0).unwrap();
-        assert!(!arr.is_empty());
+        // Already within bounds: nothing to prune
+        assert_eq!(
+            crate::caliber_enforce_retention_max("artifact", traj_id, 2, tenant_id),
+            0
+        );
+        assert_eq!(
+            crate::caliber_enforce_retention_max("region", traj_id, 0, tenant_id),
+            0
+        );
+        assert_eq!(
+            crate::caliber_enforce_retention_max("artifact", traj_id, -1, tenant_id),
+            0
+        );
     }
 
     #[pg_test]
-    fn test_turn_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_prune_by_filter() {
         let tenant_id = test_tenant_id();
+        let traj_id = crate::caliber_trajectory_create("Prune", None, None, tenant_id);
+        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 1000, tenant_id);
+        for (artifact_type, name) in [
+            ("fact", "Keep"),
+            ("error_log", "Drop 1"),
+            ("error_log", "Drop 2"),
+        ] {
+            crate::caliber_artifact_create(
+                traj_id,
+                scope_id,
+                artifact_type,
+                name,
+                "Content",
+                0,
+                "explicit",
+                None,
+                "persistent",
+                tenant_id,
+            )
+            .expect("artifact should be created");
+        }
 
-        let traj_id = crate::caliber_trajectory_create("Test", None, None, tenant_id);
-        let scope_id = crate::caliber_scope_create(traj_id, "Test Scope", None, 8000, tenant_id);
-
-        // Create turns
-        let _turn1 = crate::caliber_turn_create(scope_id, 1, "user", "Hello", 5, tenant_id);
-        let _turn2 =
-            crate::caliber_turn_create(scope_id, 2, "assistant", "Hi there!", 10, tenant_id);
-
-        // Get turns by scope
-        let turns = crate::caliber_turn_get_by_scope(scope_id, tenant_id);
-        let arr: Vec<serde_json::Value> = serde_json::from_value(turns.0).unwrap();
-        assert_eq!(arr.len(), 2);
+        let criteria = serde_json::json!({
+            "And": [
+                {"Comparison": {"field": "trajectory_id", "op": "Eq",
+                                "value": {"String": traj_id.to_string()}}},
+                {"Comparison": {"field": "artifact_type", "op": "In",
+                                "value": {"Array": [{"String": "error_log"}]}}}
+            ]
+        });
+        assert_eq!(
+            crate::caliber_prune("caliber_artifact", pgrx::JsonB(criteria), tenant_id),
+            2
+        );
+        let remaining = crate::caliber_artifact_query_by_trajectory(traj_id, tenant_id);
+        assert_eq!(remaining.0.as_array().map(|a| a.len()), Some(1));
+
+        // Unknown tables and columns are rejected without deleting anything
+        let all = serde_json::json!({"And": []});
+        assert_eq!(
+            crate::caliber_prune("caliber_tenant", pgrx::JsonB(all), tenant_id),
+            0
+        );
+        let bad_column = serde_json::json!({
+            "Comparison": {"field": "name; DROP TABLE caliber_artifact", "op": "Eq",
+                           "value": {"String": "x"}}
+        });
+        assert_eq!(
+            crate::caliber_prune("caliber_artifact", pgrx::JsonB(bad_column), tenant_id),
+            0
+        );
+        let remaining = crate::caliber_artifact_query_by_trajectory(traj_id, tenant_id);
+        assert_eq!(remaining.0.as_array().map(|a| a.len()), Some(1));
     }
 
     #[pg_test]
-    fn test_agent_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_scheduled_policy_due_and_mark_run() {
         let tenant_id = test_tenant_id();
-
-        // Register agent
-        let caps = pgrx::JsonB(serde_json::json!(["rust", "python"]));
-        let agent_id = crate::caliber_agent_register("coder", caps, tenant_id);
-
-        // Get agent
-        let agent = crate::caliber_agent_get(agent_id, tenant_id);
-        assert!(agent.is_some());
-
-        // Update status
-        let updated = crate::caliber_agent_set_status(agent_id, "active", tenant_id);
-        assert!(updated);
-
-        // Heartbeat
-        let heartbeat = crate::caliber_agent_heartbeat(agent_id, tenant_id);
-        assert!(heartbeat);
-
-        // List by type
-        let agents = crate::caliber_agent_list_by_type("coder", tenant_id);
-        let arr: Vec<serde_json::Value> = serde_json::from_value(agents.0).unwrap();
-        assert!(!arr.is_empty());
+        let actions =
+            serde_json::json!([{"Prune": {"target": "caliber_turn", "criteria": {"And": []}}}]);
+        let early = crate::caliber_scheduled_policy_create(
+            "nightly",
+            "0 0 * * *",
+            pgrx::JsonB(actions.clone()),
+            1_000,
+            tenant_id,
+        )
+        .expect("policy should be created");
+        let late = crate::caliber_scheduled_policy_create(
+            "weekly",
+            "7d",
+            pgrx::JsonB(actions.clone()),
+            5_000,
+            tenant_id,
+        )
+        .expect("policy should be created");
+        assert!(crate::caliber_scheduled_policy_create(
+            "bad",
+            "",
+            pgrx::JsonB(actions),
+            0,
+            tenant_id
+        )
+        .is_none());
+
+        let due = crate::caliber_policies_due(2_000, tenant_id);
+        let due = due.0.as_array().expect("array");
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0]["policy_id"], early.to_string());
+        assert_eq!(due[0]["next_run_ms"], 1_000);
+        assert!(due[0]["last_run_ms"].is_null());
+
+        assert!(crate::caliber_policy_mark_run(early, 10_000, tenant_id));
+        let due = crate::caliber_policies_due(6_000, tenant_id);
+        let due = due.0.as_array().expect("array");
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0]["policy_id"], late.to_string());
+
+        assert!(crate::caliber_scheduled_policy_delete(late, tenant_id));
+        assert!(!crate::caliber_policy_mark_run(late, 10_000, tenant_id));
+        assert_eq!(
+            crate::caliber_policies_due(6_000, test_tenant_id()).0,
+            serde_json::json!([])
+        );
     }
 
     #[pg_test]
-    fn test_message_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_lock_upgrade() {
         let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let agent = crate::caliber_agent_register("reader", caps, tenant_id);
+        let resource_id = crate::caliber_new_id();
 
-        let caps_value = serde_json::json!([]);
-        let agent1 =
-            crate::caliber_agent_register("sender", pgrx::JsonB(caps_value.clone()), tenant_id);
-        let agent2 = crate::caliber_agent_register("receiver", pgrx::JsonB(caps_value), tenant_id);
-
-        // Send message
-        let msg_id = crate::caliber_message_send(
-            agent1,
-            Some(agent2),
-            None,
-            "heartbeat",
-            "{}",
-            None,
-            None,
-            vec![],
-            "normal",
-            None,
+        let lock_id = crate::caliber_lock_acquire(
+            agent,
+            "artifact",
+            resource_id,
+            60_000,
+            "shared",
+            Some("session"),
             tenant_id,
         )
-        .expect("message should be sent");
+        .expect("shared lock should be acquired");
 
-        // Get message
-        let msg = crate::caliber_message_get(msg_id, tenant_id);
-        assert!(msg.is_some());
+        assert!(crate::caliber_lock_upgrade(lock_id, tenant_id));
+        let lock = crate::caliber_lock_get(lock_id, tenant_id).expect("lock exists");
+        assert_eq!(lock.0["mode"], "exclusive");
 
-        // Mark delivered
-        let delivered = crate::caliber_message_mark_delivered(msg_id, tenant_id);
-        assert!(delivered);
+        // Upgrading an exclusive lock is a no-op; unknown locks fail
+        assert!(crate::caliber_lock_upgrade(lock_id, tenant_id));
+        assert!(!crate::caliber_lock_upgrade(
+            crate::caliber_new_id(),
+            tenant_id
+        ));
 
-        // Mark acknowledged
-        let acked = crate::caliber_message_mark_acknowledged(msg_id, tenant_id);
-        assert!(acked);
+        assert!(crate::caliber_lock_release(lock_id, tenant_id));
     }
 
     #[pg_test]
-    fn test_delegation_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_lock_reentrant_acquire() {
         let tenant_id = test_tenant_id();
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let agent = crate::caliber_agent_register("nested", caps, tenant_id);
+        let resource_id = crate::caliber_new_id();
+
+        let acquire = |mode: &str| {
+            crate::caliber_lock_acquire(
+                agent,
+                "scope",
+                resource_id,
+                60_000,
+                mode,
+                Some("session"),
+                tenant_id,
+            )
+        };
+        let first = acquire("exclusive").expect("lock should be acquired");
+        let second = acquire("exclusive").expect("reentrant acquire should succeed");
+        assert_eq!(first, second);
 
-        let caps_value = serde_json::json!([]);
-        let delegator =
-            crate::caliber_agent_register("planner", pgrx::JsonB(caps_value.clone()), tenant_id);
-        let delegatee = crate::caliber_agent_register("coder", pgrx::JsonB(caps_value), tenant_id);
-        let traj_id = crate::caliber_trajectory_create("Parent Task", None, None, tenant_id);
-
-        // Create delegation
-        let delegation_id = crate::caliber_delegation_create(
-            delegator,
-            Some(delegatee),
-            None,
-            "Implement feature X",
-            traj_id,
-            tenant_id,
-        );
-
-        // Get delegation
-        let delegation = crate::caliber_delegation_get(delegation_id, tenant_id);
-        assert!(delegation.is_some());
+        // A different mode is not a reentrant acquire
+        assert!(acquire("shared").is_none());
 
-        // Accept delegation
-        let child_traj = crate::caliber_trajectory_create("Child Task", None, None, tenant_id);
-        let accepted =
-            crate::caliber_delegation_accept(delegation_id, delegatee, child_traj, tenant_id);
-        assert!(accepted);
+        // First release only drops the nested hold
+        assert!(crate::caliber_lock_release(first, tenant_id));
+        assert!(crate::caliber_lock_get(first, tenant_id).is_some());
 
-        // Complete delegation
-        let completed = crate::caliber_delegation_complete(delegation_id, true, "Done!", tenant_id);
-        assert!(completed);
+        assert!(crate::caliber_lock_release(first, tenant_id));
+        assert!(crate::caliber_lock_get(first, tenant_id).is_none());
+        assert!(!crate::caliber_lock_release(first, tenant_id));
     }
 
     #[pg_test]
-    fn test_handoff_lifecycle() {
-        crate::caliber_debug_clear();
-
+    fn test_lock_list_by_agent() {
         let tenant_id = test_tenant_id();
-
-        let caps_value = serde_json::json!([]);
-        let agent1 =
-            crate::caliber_agent_register("generalist", pgrx::JsonB(caps_value.clone()), tenant_id);
-        let agent2 =
-            crate::caliber_agent_register("specialist", pgrx::JsonB(caps_value), tenant_id);
-        let traj_id = crate::caliber_trajectory_create("Task", None, None, tenant_id);
-        let scope_id = crate::caliber_scope_create(traj_id, "Scope", None, 8000, tenant_id);
-        let snapshot_id = crate::caliber_new_id();
-
-        // Create handoff
-        let handoff_id = crate::caliber_handoff_create(
-            agent1,
-            Some(agent2),
-            None,
-            traj_id,
-            scope_id,
-            snapshot_id,
-            "specialization",
+        let caps = pgrx::JsonB(serde_json::json!([]));
+        let holder = crate::caliber_agent_register("holder", caps.clone(), tenant_id);
+        let other = crate::caliber_agent_register("other", caps, tenant_id);
+
+        let lock_id = crate::caliber_lock_acquire(
+            holder,
+            "trajectory",
+            crate::caliber_new_id(),
+            60_000,
+            "exclusive",
+            Some("session"),
             tenant_id,
-        );
-
-        // Get handoff
-        let handoff = crate::caliber_handoff_get(handoff_id, tenant_id);
-        assert!(handoff.is_some());
+        )
+        .expect("lock should be acquired");
+        crate::caliber_lock_acquire(
+            other,
+            "trajectory",
+            crate::caliber_new_id(),
+            60_000,
+            "exclusive",
+            Some("session"),
+            tenant_id,
+        )
+        .expect("lock should be acquired");
 
-        // Accept handoff
-        let accepted = crate::caliber_handoff_accept(handoff_id, agent2, tenant_id);
-        assert!(accepted);
+        let held = crate::caliber_lock_list_by_agent(holder, tenant_id);
+        let held = held.0.as_array().expect("array");
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0]["lock_id"], lock_id.to_string());
 
-        // Complete handoff
-        let completed = crate::caliber_handoff_complete(handoff_id, tenant_id);
-        assert!(completed);
+        assert_eq!(
+            crate::caliber_lock_list_by_agent(holder, test_tenant_id()).0,
+            serde_json::json!([])
+        );
     }
 
     #[pg_test]
-    fn test_conflict_lifecycle() {
-        crate::caliber_debug_clear();
+    fn test_schema_version_recorded() {
+        assert_eq!(
+            crate::caliber_schema_version(),
+            Some(crate::SCHEMA_VERSION.to_string())
+        );
+    }
 
-        let tenant_id = test_tenant_id();
+    #[pg_test]
+    fn test_init_refuses_newer_schema() {
+        let newer = crate::SCHEMA_VERSION + 1;
+        Spi::run(&format!(
+            "INSERT INTO caliber_schema_version (version, description, checksum) \
+             VALUES ({}, 'from the future', 'test')",
+            newer
+        ))
+        .expect("insert newer schema version");
+
+        assert_eq!(crate::caliber_schema_version(), Some(newer.to_string()));
+        let result = crate::caliber_init().0;
+        assert_eq!(result["success"], false);
+        assert!(
+            result["message"].as_str().unwrap_or("").contains("refused"),
+            "unexpected result: {}",
+            result
+        );
+    }
 
-        let artifact_a = crate::caliber_new_id();
-        let artifact_b = crate::caliber_new_id();
+    #[pg_test]
+    fn test_init_reports_statements() {
+        let result = crate::caliber_init().0;
+        assert_eq!(result["success"], true, "init failed: {}", result);
+        assert_eq!(result["failed"], 0);
+        let statements = result["statements"].as_array().expect("statements array");
+        assert_eq!(
+            statements.len(),
+            crate::split_sql_statements(crate::BOOTSTRAP_SQL).len()
+        );
+        assert!(statements.iter().all(|s| s["ok"] == true));
+    }
 
-        // Create conflict
-        let conflict_id = crate::caliber_conflict_create(
-            "contradicting_fact",
-            "artifact",
-            artifact_a,
-            "artifact",
-            artifact_b,
-            tenant_id,
+    #[pg_test]
+    fn test_drop_schema_requires_confirmation() {
+        let refused = crate::caliber_drop_schema(false);
+        assert!(
+            refused.contains("refused"),
+            "unexpected result: {}",
+            refused
         );
+        assert!(crate::caliber_schema_exists());
 
-        // Get conflict
-        let conflict = crate::caliber_conflict_get(conflict_id, tenant_id);
-        assert!(conflict.is_some());
+        let report = crate::caliber_drop_schema(true);
+        assert!(
+            report.starts_with("CALIBER schema dropped"),
+            "unexpected result: {}",
+            report
+        );
+        assert!(report.contains("TABLE caliber_trajectory"));
+        assert!(!crate::caliber_schema_exists());
+        assert_eq!(crate::caliber_schema_version(), None);
+    }
 
-        // List unresolved
-        let unresolved = crate::caliber_conflict_list_unresolved(tenant_id);
-        let arr: Vec<serde_json::Value> = serde_json::from_value(unresolved.0).unwrap();
-        assert!(!arr.is_empty());
+    #[pg_test]
+    fn test_config_set_and_get() {
+        let tenant_id = test_tenant_id();
 
-        // Resolve conflict
-        let resolved = crate::caliber_conflict_resolve(
-            conflict_id,
-            "highest_confidence",
-            Some("a"),
-            "Artifact A has higher confidence",
-            tenant_id,
+        assert_eq!(
+            crate::caliber_config_get("contradiction_threshold", tenant_id).map(|v| v.0),
+            Some(serde_json::json!(0.8))
         );
-        assert!(resolved);
+        assert_eq!(
+            crate::caliber_config_get("stale_threshold", tenant_id).map(|v| v.0),
+            Some(serde_json::json!(3600))
+        );
+        assert!(crate::caliber_config_get("embedding_dimension", tenant_id).is_none());
+        assert!(crate::caliber_config_get("unknown", tenant_id).is_none());
+
+        assert!(crate::caliber_config_set(
+            "contradiction_threshold",
+            pgrx::JsonB(serde_json::json!(0.5)),
+            tenant_id
+        ));
+        assert!(crate::caliber_config_set(
+            "token_budget",
+            pgrx::JsonB(serde_json::json!(1200)),
+            tenant_id
+        ));
+        assert!(crate::caliber_config_set(
+            "embedding_dimension",
+            pgrx::JsonB(serde_json::json!(3)),
+            tenant_id
+        ));
+        assert_eq!(
+            crate::caliber_config_get("contradiction_threshold", tenant_id).map(|v| v.0),
+            Some(serde_json::json!(0.5))
+        );
+        assert_eq!(
+            crate::caliber_config_get("token_budget", tenant_id).map(|v| v.0),
+            Some(serde_json::json!(1200))
+        );
+        assert_eq!(crate::caliber_embedding_dimension_get(tenant_id), Some(3));
+
+        // Invalid values and unknown keys are rejected
+        assert!(!crate::caliber_config_set(
+            "contradiction_threshold",
+            pgrx::JsonB(serde_json::json!(1.5)),
+            tenant_id
+        ));
+        assert!(!crate::caliber_config_set(
+            "token_budget",
+            pgrx::JsonB(serde_json::json!("lots")),
+            tenant_id
+        ));
+        assert!(!crate::caliber_config_set(
+            "unknown",
+            pgrx::JsonB(serde_json::json!(1)),
+            tenant_id
+        ));
     }
 
     #[pg_test]
@@ -21,14 +21,16 @@ use crate::index_ops::{
     IndexScanner,
 };
 use crate::tuple_extract::{
-    chrono_to_timestamp, extract_text, extract_timestamp, extract_uuid, extract_values_and_nulls,
-    string_to_datum, timestamp_to_chrono, uuid_to_datum,
+    chrono_to_timestamp, extract_i32, extract_text, extract_timestamp, extract_uuid,
+    extract_values_and_nulls, i32_to_datum, string_to_datum, timestamp_to_chrono, uuid_to_datum,
 };
 use std::ptr;
 
 /// Lock row wrapper for LockData (which already contains tenant_id).
 pub struct LockRow {
     pub lock: LockData,
+    /// Reentrant acquisitions by the holder; the lock is freed when it reaches zero.
+    pub acquire_count: i32,
 }
 
 impl From<LockRow> for LockData {
@@ -84,6 +86,7 @@ pub fn lock_acquire_heap(
     values[lock::MODE as usize - 1] = string_to_datum(mode_str);
 
     values[lock::TENANT_ID as usize - 1] = uuid_to_datum(tenant_id.as_uuid());
+    values[lock::ACQUIRE_COUNT as usize - 1] = i32_to_datum(1);
 
     let tuple = form_tuple(&rel, &values, &nulls)?;
     let _tid = unsafe { insert_tuple(&rel, tuple)? };
@@ -205,6 +208,40 @@ pub fn lock_list_by_resource_heap(
     Ok(results)
 }
 
+/// List active (non-expired) locks held by an agent using direct heap operations.
+pub fn lock_list_by_holder_heap(
+    holder_agent_id: AgentId,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<LockRow>> {
+    let rel = open_relation(lock::TABLE_NAME, HeapLockMode::AccessShare)?;
+    let index_rel = open_index(lock::HOLDER_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(holder_agent_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let tuple_desc = rel.tuple_desc();
+    let now = chrono::Utc::now();
+    let mut results = Vec::new();
+
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_lock(tuple, tuple_desc) }?;
+        if row.lock.tenant_id.as_uuid() == tenant_id.as_uuid() && row.lock.expires_at > now {
+            results.push(row);
+        }
+    }
+
+    Ok(results)
+}
+
 /// List all active (non-expired) locks using a heap scan.
 pub fn lock_list_active_heap(tenant_id: TenantId) -> CaliberResult<Vec<LockRow>> {
     let rel = open_relation(lock::TABLE_NAME, HeapLockMode::AccessShare)?;
@@ -307,7 +344,10 @@ unsafe fn tuple_to_lock(
         })
     })?;
 
+    let acquire_count = extract_i32(tuple, tuple_desc, lock::ACQUIRE_COUNT)?.unwrap_or(1);
+
     Ok(LockRow {
+        acquire_count,
         lock: LockData {
             lock_id: LockId::new(lock_id),
             tenant_id: TenantId::new(tenant_id),
@@ -321,12 +361,13 @@ unsafe fn tuple_to_lock(
     })
 }
 
-/// Extend a lock's expiration time using direct heap operations.
-/// Uses extract_values_and_nulls to read existing tuple and update expires_at.
-pub fn lock_extend_heap(
+/// Rewrite a lock tuple in place using direct heap operations.
+/// `apply` edits the extracted values/nulls. Returns false if the lock does
+/// not exist for this tenant.
+fn lock_update_heap(
     lock_id: LockId,
-    new_expires_at: chrono::DateTime<chrono::Utc>,
     tenant_id: TenantId,
+    apply: impl FnOnce(&mut [pg_sys::Datum], &mut [bool]) -> CaliberResult<()>,
 ) -> CaliberResult<bool> {
     use crate::heap_ops::update_tuple;
 
@@ -359,18 +400,7 @@ pub fn lock_extend_heap(
 
         // Extract existing values using extract_values_and_nulls
         let (mut values, mut nulls) = unsafe { extract_values_and_nulls(tuple, tuple_desc) }?;
-
-        // Update expires_at
-        values[lock::EXPIRES_AT as usize - 1] = chrono_to_timestamp(new_expires_at)?
-            .into_datum()
-            .ok_or_else(|| {
-            CaliberError::Storage(StorageError::UpdateFailed {
-                entity_type: EntityType::Lock,
-                id: lock_id.as_uuid(),
-                reason: "Failed to convert expires_at to datum".to_string(),
-            })
-        })?;
-        nulls[lock::EXPIRES_AT as usize - 1] = false;
+        apply(&mut values, &mut nulls)?;
 
         // Form and update tuple
         let new_tuple = form_tuple(&rel, &values, &nulls)?;
@@ -382,6 +412,73 @@ pub fn lock_extend_heap(
     }
 }
 
+/// Convert an expiry timestamp to a datum for a lock update.
+fn expires_at_datum(
+    lock_id: LockId,
+    expires_at: chrono::DateTime<chrono::Utc>,
+) -> CaliberResult<pg_sys::Datum> {
+    chrono_to_timestamp(expires_at)?
+        .into_datum()
+        .ok_or_else(|| {
+            CaliberError::Storage(StorageError::UpdateFailed {
+                entity_type: EntityType::Lock,
+                id: lock_id.as_uuid(),
+                reason: "Failed to convert expires_at to datum".to_string(),
+            })
+        })
+}
+
+/// Extend a lock's expiration time using direct heap operations.
+/// Uses extract_values_and_nulls to read existing tuple and update expires_at.
+pub fn lock_extend_heap(
+    lock_id: LockId,
+    new_expires_at: chrono::DateTime<chrono::Utc>,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    lock_update_heap(lock_id, tenant_id, |values, nulls| {
+        values[lock::EXPIRES_AT as usize - 1] = expires_at_datum(lock_id, new_expires_at)?;
+        nulls[lock::EXPIRES_AT as usize - 1] = false;
+        Ok(())
+    })
+}
+
+/// Change a lock's mode using direct heap operations.
+/// Returns false if the lock does not exist for this tenant.
+pub fn lock_set_mode_heap(
+    lock_id: LockId,
+    mode: LockMode,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    let mode_str = match mode {
+        LockMode::Exclusive => "exclusive",
+        LockMode::Shared => "shared",
+    };
+    lock_update_heap(lock_id, tenant_id, |values, nulls| {
+        values[lock::MODE as usize - 1] = string_to_datum(mode_str);
+        nulls[lock::MODE as usize - 1] = false;
+        Ok(())
+    })
+}
+
+/// Set a lock's reentrant acquire count, optionally moving its expiry in the
+/// same tuple update. Returns false if the lock does not exist for this tenant.
+pub fn lock_set_acquire_count_heap(
+    lock_id: LockId,
+    acquire_count: i32,
+    new_expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    tenant_id: TenantId,
+) -> CaliberResult<bool> {
+    lock_update_heap(lock_id, tenant_id, |values, nulls| {
+        values[lock::ACQUIRE_COUNT as usize - 1] = i32_to_datum(acquire_count);
+        nulls[lock::ACQUIRE_COUNT as usize - 1] = false;
+        if let Some(expires_at) = new_expires_at {
+            values[lock::EXPIRES_AT as usize - 1] = expires_at_datum(lock_id, expires_at)?;
+            nulls[lock::EXPIRES_AT as usize - 1] = false;
+        }
+        Ok(())
+    })
+}
+
 // ============================================================================
 // PROPERTY-BASED TESTS
 // ============================================================================
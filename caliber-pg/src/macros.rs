@@ -37,6 +37,8 @@
 ///
 /// Takes entity name, heap module, entity ID type, and a closure that builds JSON from the row.
 /// The closure receives the full row (with tenant_id) and returns serde_json::Value.
+/// Passing `tombstoned` after the ID type adds an `include_deleted` flag for
+/// entities whose rows carry a soft delete `deleted_at`.
 #[macro_export]
 macro_rules! caliber_pg_get {
     ($entity:ident, $heap_mod:ident, $id_ty:ty, |$row:ident| $json_expr:expr) => {
@@ -62,6 +64,32 @@ macro_rules! caliber_pg_get {
             }
         }
     };
+    // Soft-deletable entities: rows whose `deleted_at` tombstone is set are
+    // hidden unless the caller passes `include_deleted`.
+    ($entity:ident, $heap_mod:ident, $id_ty:ty, tombstoned, |$row:ident| $json_expr:expr) => {
+        paste::paste! {
+            #[pg_extern]
+            fn [<caliber_ $entity _get>](
+                id: pgrx::Uuid,
+                tenant_id: pgrx::Uuid,
+                include_deleted: default!(bool, false),
+            ) -> Option<pgrx::JsonB> {
+                let entity_id: $id_ty = $crate::id_from_pgrx(id);
+                let tenant_uuid = $crate::id_from_pgrx::<caliber_core::TenantId>(tenant_id);
+
+                match $heap_mod::[<$entity _get_heap>](entity_id, tenant_uuid) {
+                    Ok(Some($row)) if include_deleted || $row.deleted_at.is_none() => {
+                        Some(pgrx::JsonB($json_expr))
+                    }
+                    Ok(_) => None,
+                    Err(e) => {
+                        pgrx::warning!("CALIBER: {} get failed: {}", stringify!($entity), e);
+                        None
+                    }
+                }
+            }
+        }
+    };
 }
 
 /// Generate a `caliber_{entity}_list_active` function for entities with active/inactive state.
@@ -30,6 +30,8 @@ use crate::tuple_extract::{
 pub struct MessageRow {
     pub message: AgentMessage,
     pub tenant_id: Option<TenantId>,
+    /// Message this one answers, if it is a reply.
+    pub reply_to: Option<MessageId>,
 }
 
 impl From<MessageRow> for AgentMessage {
@@ -65,6 +67,7 @@ pub struct MessageSendParams<'a> {
     pub priority: MessagePriority,
     pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
     pub tenant_id: TenantId,
+    pub reply_to: Option<MessageId>,
 }
 
 pub fn message_send_heap(params: MessageSendParams<'_>) -> CaliberResult<MessageId> {
@@ -81,6 +84,7 @@ pub fn message_send_heap(params: MessageSendParams<'_>) -> CaliberResult<Message
         priority,
         expires_at,
         tenant_id,
+        reply_to,
     } = params;
     let rel = open_relation(message::TABLE_NAME, HeapLockMode::RowExclusive)?;
     validate_message_relation(&rel)?;
@@ -182,6 +186,12 @@ pub fn message_send_heap(params: MessageSendParams<'_>) -> CaliberResult<Message
     // Set tenant_id
     values[message::TENANT_ID as usize - 1] = uuid_to_datum(tenant_id.as_uuid());
 
+    // Set optional reply_to
+    match reply_to {
+        Some(id) => values[message::REPLY_TO as usize - 1] = uuid_to_datum(id.as_uuid()),
+        None => nulls[message::REPLY_TO as usize - 1] = true,
+    }
+
     let tuple = form_tuple(&rel, &values, &nulls)?;
     let _tid = unsafe { insert_tuple(&rel, tuple)? };
     unsafe { update_indexes_for_insert(&rel, tuple, &values, &nulls)? };
@@ -467,6 +477,7 @@ unsafe fn tuple_to_message(
         extract_timestamp(tuple, tuple_desc, message::EXPIRES_AT)?.map(timestamp_to_chrono);
 
     let tenant_id = extract_uuid(tuple, tuple_desc, message::TENANT_ID)?.map(TenantId::new);
+    let reply_to = extract_uuid(tuple, tuple_desc, message::REPLY_TO)?.map(MessageId::new);
 
     Ok(MessageRow {
         message: AgentMessage {
@@ -486,6 +497,7 @@ unsafe fn tuple_to_message(
             expires_at,
         },
         tenant_id,
+        reply_to,
     })
 }
 
@@ -668,6 +680,7 @@ mod tests {
                             priority,
                             expires_at,
                             tenant_id,
+                            reply_to: None,
                         });
                         prop_assert!(result.is_ok(), "Insert should succeed: {:?}", result.err());
                         prop_assert_eq!(result.unwrap(), message_id);
@@ -807,6 +820,7 @@ mod tests {
                             priority,
                             expires_at,
                             tenant_id,
+                            reply_to: None,
                         });
                         prop_assert!(insert_result.is_ok(), "Insert should succeed");
 
@@ -913,6 +927,7 @@ mod tests {
                             priority,
                             expires_at,
                             tenant_id,
+                            reply_to: None,
                         });
                         prop_assert!(insert_result.is_ok(), "Insert should succeed");
 
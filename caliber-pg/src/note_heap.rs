@@ -9,19 +9,22 @@
 //! - `note_get_heap` - Get a note by ID
 //! - `note_query_by_trajectory_heap` - Query notes by source trajectory
 //! - `note_update_heap` - Update note fields
+//! - `note_record_access_heap` - Count a read of a note
+//! - `note_soft_delete_heap` - Tombstone a note
 
 use pgrx::pg_sys;
 use pgrx::prelude::*;
 
 use caliber_core::{
-    AbstractionLevel, ArtifactId, CaliberError, CaliberResult, ContentHash, EmbeddingVector,
-    EntityIdType, EntityType, Note, NoteId, NoteType, StorageError, TenantId, TrajectoryId, TTL,
+    ttl_from_str, ttl_to_str, AbstractionLevel, ArtifactId, CaliberError, CaliberResult,
+    ContentHash, EmbeddingVector, EntityIdType, EntityType, Note, NoteId, NoteType, StorageError,
+    TenantId, TrajectoryId, TTL,
 };
 
 use crate::column_maps::note;
 use crate::heap_ops::{
     current_timestamp, form_tuple, get_active_snapshot, insert_tuple, open_relation,
-    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as LockMode,
+    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as LockMode, SoftDeleteOutcome,
 };
 use crate::index_ops::{
     init_scan_key, open_index, operator_oids, update_indexes_for_insert, BTreeStrategy,
@@ -38,6 +41,8 @@ use crate::tuple_extract::{
 pub struct NoteRow {
     pub note: Note,
     pub tenant_id: Option<TenantId>,
+    /// Soft delete tombstone; tombstoned notes are skipped by queries.
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl From<NoteRow> for Note {
@@ -158,7 +163,7 @@ pub fn note_create_heap(params: NoteCreateParams<'_>) -> CaliberResult<NoteId> {
     }
 
     // Column 9: ttl (TEXT, NOT NULL)
-    values[note::TTL as usize - 1] = string_to_datum(ttl_to_str(ttl));
+    values[note::TTL as usize - 1] = string_to_datum(&ttl_to_str(&ttl));
 
     // Column 10: created_at (TIMESTAMPTZ, NOT NULL)
     values[note::CREATED_AT as usize - 1] = now_datum;
@@ -193,6 +198,14 @@ pub fn note_create_heap(params: NoteCreateParams<'_>) -> CaliberResult<NoteId> {
     // Column 18: tenant_id (UUID, NOT NULL)
     values[note::TENANT_ID as usize - 1] = uuid_to_datum(tenant_id.as_uuid());
 
+    // Column 19: memory_category (TEXT, nullable) - derived from note type
+    values[note::MEMORY_CATEGORY as usize - 1] = string_to_datum(crate::memory_category_to_str(
+        crate::note_type_memory_category(note_type),
+    ));
+
+    // Column 20: deleted_at (TIMESTAMPTZ, nullable) - not tombstoned
+    nulls[note::DELETED_AT as usize - 1] = true;
+
     // Form the heap tuple
     let tuple = form_tuple(&rel, &values, &nulls)?;
 
@@ -296,8 +309,9 @@ pub fn note_query_by_trajectory_heap(
         if let Some(ids) = source_ids {
             if ids.contains(&trajectory_id.as_uuid()) {
                 let row = unsafe { tuple_to_note(tuple, tuple_desc) }?;
-                // Enforce TTL - skip expired notes
+                // Enforce TTL and tombstones - skip expired and deleted notes
                 if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid())
+                    && row.deleted_at.is_none()
                     && !is_note_expired(&row.note.ttl, row.note.created_at)
                 {
                     results.push(row);
@@ -309,13 +323,30 @@ pub fn note_query_by_trajectory_heap(
     Ok(results)
 }
 
+/// Parameters for updating a note.
+pub struct NoteUpdateHeapParams<'a> {
+    pub id: NoteId,
+    pub tenant_id: TenantId,
+    pub title: Option<&'a str>,
+    pub content: Option<&'a str>,
+    pub content_hash: Option<ContentHash>,
+    pub embedding: Option<Option<&'a EmbeddingVector>>,
+    pub ttl: Option<TTL>,
+    pub abstraction_level: Option<AbstractionLevel>,
+    pub superseded_by: Option<Option<NoteId>>,
+    pub metadata: Option<Option<&'a serde_json::Value>>,
+}
+
 /// Update a note using direct heap operations.
 ///
 /// # Arguments
 /// * `id` - The note ID to update
+/// * `title` - Optional new title
 /// * `content` - Optional new content
 /// * `content_hash` - Optional new content hash (required if content changes)
 /// * `embedding` - Optional new embedding (Some(None) to clear)
+/// * `ttl` - Optional new time-to-live setting
+/// * `abstraction_level` - Optional new abstraction level
 /// * `superseded_by` - Optional superseding note ID
 /// * `metadata` - Optional metadata (Some(None) to clear)
 ///
@@ -326,15 +357,19 @@ pub fn note_query_by_trajectory_heap(
 ///
 /// # Requirements
 /// - 4.4: Uses simple_heap_update instead of SPI UPDATE
-pub fn note_update_heap(
-    id: NoteId,
-    content: Option<&str>,
-    content_hash: Option<ContentHash>,
-    embedding: Option<Option<&EmbeddingVector>>,
-    superseded_by: Option<Option<NoteId>>,
-    metadata: Option<Option<&serde_json::Value>>,
-    tenant_id: TenantId,
-) -> CaliberResult<bool> {
+pub fn note_update_heap(params: NoteUpdateHeapParams<'_>) -> CaliberResult<bool> {
+    let NoteUpdateHeapParams {
+        id,
+        tenant_id,
+        title,
+        content,
+        content_hash,
+        embedding,
+        ttl,
+        abstraction_level,
+        superseded_by,
+        metadata,
+    } = params;
     // Open relation with RowExclusive lock for writes
     let rel = open_relation(note::TABLE_NAME, LockMode::RowExclusive)?;
 
@@ -380,7 +415,20 @@ pub fn note_update_heap(
     // Extract current values and nulls
     let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
 
+    // Tombstoned notes are read-only
+    if !nulls[note::DELETED_AT as usize - 1] {
+        return Err(CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Note,
+            id: id.as_uuid(),
+            reason: "note is deleted".to_string(),
+        }));
+    }
+
     // Apply updates
+    if let Some(new_title) = title {
+        values[note::TITLE as usize - 1] = string_to_datum(new_title);
+    }
+
     if let Some(new_content) = content {
         values[note::CONTENT as usize - 1] = string_to_datum(new_content);
     }
@@ -401,6 +449,15 @@ pub fn note_update_heap(
         }
     }
 
+    if let Some(new_ttl) = ttl {
+        values[note::TTL as usize - 1] = string_to_datum(&ttl_to_str(&new_ttl));
+    }
+
+    if let Some(new_level) = abstraction_level {
+        values[note::ABSTRACTION_LEVEL as usize - 1] =
+            string_to_datum(abstraction_level_to_str(new_level));
+    }
+
     if let Some(new_superseded) = superseded_by {
         match new_superseded {
             Some(s) => {
@@ -448,6 +505,140 @@ pub fn note_update_heap(
     Ok(true)
 }
 
+/// Record a read of a note: increments `access_count` and sets `accessed_at`
+/// to now, leaving `updated_at` untouched.
+///
+/// # Returns
+/// * `Ok(true)` - If the note exists and the access was recorded
+/// * `Ok(false)` - If no note with that ID exists for this tenant
+/// * `Err(CaliberError)` - On failure
+pub fn note_record_access_heap(id: NoteId, tenant_id: TenantId) -> CaliberResult<bool> {
+    let rel = open_relation(note::TABLE_NAME, LockMode::RowExclusive)?;
+    let index_rel = open_index(note::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+
+    let tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Note,
+            id: id.as_uuid(),
+            reason: "Failed to get TID of existing tuple".to_string(),
+        })
+    })?;
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, note::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(false);
+    }
+
+    let access_count = unsafe { extract_i32(old_tuple, tuple_desc, note::ACCESS_COUNT)? };
+    let (mut values, nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+
+    values[note::ACCESS_COUNT as usize - 1] =
+        i32_to_datum(access_count.unwrap_or(0).saturating_add(1));
+    values[note::ACCESSED_AT as usize - 1] = timestamp_to_pgrx(current_timestamp())?
+        .into_datum()
+        .ok_or_else(|| {
+        CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Note,
+            id: id.as_uuid(),
+            reason: "Failed to convert timestamp to datum".to_string(),
+        })
+    })?;
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    unsafe { update_tuple(&rel, &tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+
+    Ok(true)
+}
+
+/// Tombstone a note using direct heap operations.
+///
+/// Sets `deleted_at` (and `updated_at`) to now. A note that is already
+/// tombstoned keeps its original `deleted_at`.
+///
+/// # Returns
+/// * `Ok(SoftDeleteOutcome::Deleted)` - If the note was live and is now tombstoned
+/// * `Ok(SoftDeleteOutcome::AlreadyDeleted)` - If the note was already tombstoned
+/// * `Ok(SoftDeleteOutcome::NotFound)` - If no note with that ID exists for this tenant
+/// * `Err(CaliberError)` - On failure
+pub fn note_soft_delete_heap(id: NoteId, tenant_id: TenantId) -> CaliberResult<SoftDeleteOutcome> {
+    let rel = open_relation(note::TABLE_NAME, LockMode::RowExclusive)?;
+    let index_rel = open_index(note::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(SoftDeleteOutcome::NotFound),
+    };
+
+    let tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Note,
+            id: id.as_uuid(),
+            reason: "Failed to get TID of existing tuple".to_string(),
+        })
+    })?;
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, note::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(SoftDeleteOutcome::NotFound);
+    }
+
+    let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+    if !nulls[note::DELETED_AT as usize - 1] {
+        return Ok(SoftDeleteOutcome::AlreadyDeleted);
+    }
+
+    let now_datum = timestamp_to_pgrx(current_timestamp())?
+        .into_datum()
+        .ok_or_else(|| {
+            CaliberError::Storage(StorageError::UpdateFailed {
+                entity_type: EntityType::Note,
+                id: id.as_uuid(),
+                reason: "Failed to convert timestamp to datum".to_string(),
+            })
+        })?;
+    values[note::DELETED_AT as usize - 1] = now_datum;
+    nulls[note::DELETED_AT as usize - 1] = false;
+    values[note::UPDATED_AT as usize - 1] = now_datum;
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    unsafe { update_tuple(&rel, &tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+
+    Ok(SoftDeleteOutcome::Deleted)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
@@ -467,8 +658,24 @@ fn validate_note_relation(rel: &HeapRelation) -> CaliberResult<()> {
     Ok(())
 }
 
+/// Note type names as stored in `caliber_note.note_type`, in the order
+/// listed in validation messages.
+pub const NOTE_TYPES: &[&str] = &[
+    "insight",
+    "procedure",
+    "fact",
+    "preference",
+    "correction",
+    "summary",
+    "convention",
+    "strategy",
+    "gotcha",
+    "relationship",
+    "meta",
+];
+
 /// Convert a NoteType enum to its string representation.
-fn note_type_to_str(t: NoteType) -> &'static str {
+pub fn note_type_to_str(t: NoteType) -> &'static str {
     match t {
         // Core note types
         NoteType::Convention => "convention",
@@ -486,77 +693,41 @@ fn note_type_to_str(t: NoteType) -> &'static str {
     }
 }
 
-/// Parse a note type string to NoteType enum.
-fn str_to_note_type(s: &str) -> NoteType {
+/// Parse a note type string, returning None for unknown values.
+pub fn note_type_from_str(s: &str) -> Option<NoteType> {
     match s {
         // Core note types
-        "convention" => NoteType::Convention,
-        "strategy" => NoteType::Strategy,
-        "gotcha" => NoteType::Gotcha,
-        "fact" => NoteType::Fact,
-        "preference" => NoteType::Preference,
-        "relationship" => NoteType::Relationship,
-        "procedure" => NoteType::Procedure,
-        "meta" => NoteType::Meta,
+        "convention" => Some(NoteType::Convention),
+        "strategy" => Some(NoteType::Strategy),
+        "gotcha" => Some(NoteType::Gotcha),
+        "fact" => Some(NoteType::Fact),
+        "preference" => Some(NoteType::Preference),
+        "relationship" => Some(NoteType::Relationship),
+        "procedure" => Some(NoteType::Procedure),
+        "meta" => Some(NoteType::Meta),
         // Extended note types
-        "insight" => NoteType::Insight,
-        "correction" => NoteType::Correction,
-        "summary" => NoteType::Summary,
-        // Default fallback - use Meta for unknown types
-        _ => {
-            if s != "meta" {
-                pgrx::warning!("CALIBER: Unknown note type '{}', defaulting to Meta", s);
-            }
-            NoteType::Meta
-        }
+        "insight" => Some(NoteType::Insight),
+        "correction" => Some(NoteType::Correction),
+        "summary" => Some(NoteType::Summary),
+        _ => None,
     }
 }
 
-/// Convert a TTL enum to its string representation.
-/// Returns a static string for simple variants, or a leaked string for Duration.
-fn ttl_to_str(ttl: TTL) -> &'static str {
-    match ttl {
-        // Canonical variants
-        TTL::Persistent => "persistent",
-        TTL::Session => "session",
-        TTL::Scope => "scope",
-        TTL::Duration(ms) => Box::leak(format!("duration:{}", ms).into_boxed_str()),
-        // Semantic aliases
-        TTL::Ephemeral => "ephemeral",
-        TTL::ShortTerm => "short_term",
-        TTL::MediumTerm => "medium_term",
-        TTL::LongTerm => "long_term",
-        TTL::Permanent => "permanent",
-        TTL::Max(n) => Box::leak(format!("max:{}", n).into_boxed_str()),
-    }
+/// Parse a stored note type string to NoteType enum.
+fn str_to_note_type(s: &str) -> NoteType {
+    // Default fallback - use Meta for unknown types
+    note_type_from_str(s).unwrap_or_else(|| {
+        pgrx::warning!("CALIBER: Unknown note type '{}', defaulting to Meta", s);
+        NoteType::Meta
+    })
 }
 
 /// Parse a TTL string to TTL enum.
 fn str_to_ttl(s: &str) -> TTL {
-    match s {
-        // Canonical variants
-        "persistent" => TTL::Persistent,
-        "session" => TTL::Session,
-        "scope" => TTL::Scope,
-        // Semantic aliases
-        "ephemeral" => TTL::Ephemeral,
-        "short_term" => TTL::ShortTerm,
-        "medium_term" => TTL::MediumTerm,
-        "long_term" => TTL::LongTerm,
-        "permanent" => TTL::Permanent,
-        // Duration parsing
-        s if s.starts_with("duration:") => {
-            let ms_str = &s[9..];
-            ms_str
-                .parse::<i64>()
-                .map(TTL::Duration)
-                .unwrap_or(TTL::Session)
-        }
-        _ => {
-            pgrx::warning!("CALIBER: Unknown TTL value '{}', defaulting to Session", s);
-            TTL::Session
-        }
-    }
+    ttl_from_str(s).unwrap_or_else(|| {
+        pgrx::warning!("CALIBER: Unknown TTL value '{}', defaulting to Session", s);
+        TTL::Session
+    })
 }
 
 // ============================================================================
@@ -751,6 +922,8 @@ unsafe fn tuple_to_note(
         .collect();
 
     let tenant_id = extract_uuid(tuple, tuple_desc, note::TENANT_ID)?.map(TenantId::new);
+    let deleted_at =
+        extract_timestamp(tuple, tuple_desc, note::DELETED_AT)?.map(timestamp_to_chrono);
 
     Ok(NoteRow {
         note: Note {
@@ -773,6 +946,7 @@ unsafe fn tuple_to_note(
             source_note_ids,
         },
         tenant_id,
+        deleted_at,
     })
 }
 
@@ -807,6 +981,11 @@ mod tests {
             Just(NoteType::Preference),
             Just(NoteType::Correction),
             Just(NoteType::Summary),
+            Just(NoteType::Convention),
+            Just(NoteType::Strategy),
+            Just(NoteType::Gotcha),
+            Just(NoteType::Relationship),
+            Just(NoteType::Meta),
         ]
     }
 
@@ -220,6 +220,7 @@ fn artifact_row_into_artifact() {
     let row = ArtifactRow {
         artifact: artifact.clone(),
         tenant_id: Some(sample_tenant_id(99)),
+        deleted_at: None,
     };
     let converted: Artifact = row.into();
     assert_eq!(converted, artifact);
@@ -231,6 +232,7 @@ fn note_row_into_note() {
     let row = NoteRow {
         note: note.clone(),
         tenant_id: Some(sample_tenant_id(99)),
+        deleted_at: None,
     };
     let converted: Note = row.into();
     assert_eq!(converted, note);
@@ -282,7 +284,10 @@ fn lock_row_into_lock() {
         expires_at: now + chrono::Duration::seconds(1000),
         mode: LockMode::Exclusive,
     };
-    let row = LockRow { lock: lock.clone() };
+    let row = LockRow {
+        lock: lock.clone(),
+        acquire_count: 1,
+    };
     let converted: LockData = row.into();
     assert_eq!(converted, lock);
 }
@@ -298,6 +303,7 @@ fn message_row_into_message() {
     let row = MessageRow {
         message: message.clone(),
         tenant_id: Some(sample_tenant_id(99)),
+        reply_to: None,
     };
     let converted: AgentMessage = row.into();
     assert_eq!(converted, message);
@@ -6,7 +6,11 @@
 //! # Operations
 //!
 //! - `turn_create_heap` - Insert a new turn
+//! - `turn_find_by_sequence_heap` - Find the turn at a sequence within a scope
 //! - `turn_get_by_scope_heap` - Get turns by scope ID (ordered by sequence)
+//! - `turn_get_range_heap` - Get turns within a sequence range
+//! - `turn_get_recent_heap` - Get the last N turns of a scope
+//! - `turn_update_heap` - Update turn content and tool fields
 
 use pgrx::pg_sys;
 use pgrx::prelude::*;
@@ -19,15 +23,16 @@ use caliber_core::{
 use crate::column_maps::turn;
 use crate::heap_ops::{
     current_timestamp, form_tuple, get_active_snapshot, insert_tuple, open_relation,
-    timestamp_to_pgrx, HeapRelation, PgLockMode as LockMode,
+    timestamp_to_pgrx, update_tuple, HeapRelation, PgLockMode as LockMode,
 };
 use crate::index_ops::{
     init_scan_key, open_index, operator_oids, update_indexes_for_insert, BTreeStrategy,
     IndexScanner,
 };
 use crate::tuple_extract::{
-    extract_i32, extract_jsonb, extract_text, extract_timestamp, extract_uuid, i32_to_datum,
-    json_to_datum, string_to_datum, timestamp_to_chrono, uuid_to_datum,
+    extract_i32, extract_jsonb, extract_text, extract_timestamp, extract_uuid,
+    extract_values_and_nulls, i32_to_datum, json_to_datum, string_to_datum, timestamp_to_chrono,
+    uuid_to_datum,
 };
 
 /// Turn row with tenant ownership metadata.
@@ -56,7 +61,9 @@ impl From<TurnRow> for Turn {
 ///
 /// # Returns
 /// * `Ok(TurnId)` - The turn ID on success
-/// * `Err(CaliberError)` - On failure
+/// * `Err(StorageError::AlreadyExists)` - If the scope already has a turn at
+///   `sequence`; the error carries the existing turn's ID
+/// * `Err(CaliberError)` - On other failures
 ///
 /// # Requirements
 /// - 5.1: Uses heap_form_tuple and simple_heap_insert instead of SPI
@@ -91,6 +98,25 @@ pub fn turn_create_heap(params: TurnCreateParams<'_>) -> CaliberResult<TurnId> {
     // Validate relation schema matches expectations
     validate_turn_relation(&rel)?;
 
+    // Check UNIQUE (scope_id, sequence) up front: a violation raised by the
+    // index insert would abort the transaction instead of returning an error.
+    // Only a turn of the caller's own tenant is reported by ID.
+    if let Some((existing, existing_tenant)) = turn_find_by_sequence_heap(scope_id, sequence)? {
+        if existing_tenant == Some(tenant_id) {
+            return Err(CaliberError::Storage(StorageError::AlreadyExists {
+                entity_type: EntityType::Turn,
+                id: existing.as_uuid(),
+            }));
+        }
+        return Err(CaliberError::Storage(StorageError::InsertFailed {
+            entity_type: EntityType::Turn,
+            reason: format!(
+                "scope {} already has a turn at sequence {}",
+                scope_id, sequence
+            ),
+        }));
+    }
+
     // Get current transaction timestamp for created_at
     let now = current_timestamp();
     let now_datum = timestamp_to_pgrx(now)?.into_datum().ok_or_else(|| {
@@ -157,6 +183,55 @@ pub fn turn_create_heap(params: TurnCreateParams<'_>) -> CaliberResult<TurnId> {
     Ok(turn_id)
 }
 
+/// Find the turn at a sequence number within a scope using direct heap
+/// operations.
+///
+/// Not tenant-filtered: it backs the `UNIQUE (scope_id, sequence)` check,
+/// which applies regardless of tenant. Returns the turn's ID together with
+/// its tenant so callers can avoid exposing another tenant's turn.
+pub fn turn_find_by_sequence_heap(
+    scope_id: ScopeId,
+    sequence: i32,
+) -> CaliberResult<Option<(TurnId, Option<TenantId>)>> {
+    let rel = open_relation(turn::TABLE_NAME, LockMode::AccessShare)?;
+    let index_rel = open_index(turn::SCOPE_SEQ_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_keys: [pg_sys::ScanKeyData; 2] = [
+        pg_sys::ScanKeyData::default(),
+        pg_sys::ScanKeyData::default(),
+    ];
+
+    init_scan_key(
+        &mut scan_keys[0],
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(scope_id.as_uuid()),
+    );
+
+    init_scan_key(
+        &mut scan_keys[1],
+        2,
+        BTreeStrategy::Equal,
+        operator_oids::INT4_EQ,
+        i32_to_datum(sequence),
+    );
+
+    let mut scanner =
+        unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 2, scan_keys.as_mut_ptr()) };
+
+    match scanner.next() {
+        Some(tuple) => {
+            let tuple_desc = rel.tuple_desc();
+            let turn_id = unsafe { extract_uuid(tuple, tuple_desc, turn::TURN_ID)? };
+            let tenant_id = unsafe { extract_uuid(tuple, tuple_desc, turn::TENANT_ID)? };
+            Ok(turn_id.map(|id| (TurnId::new(id), tenant_id.map(TenantId::new))))
+        }
+        None => Ok(None),
+    }
+}
+
 /// Get turns by scope ID using direct heap operations.
 ///
 /// Returns turns ordered by sequence number.
@@ -213,6 +288,212 @@ pub fn turn_get_by_scope_heap(
     Ok(results)
 }
 
+/// Get turns whose sequence lies in `[from_seq, to_seq]` using direct heap
+/// operations.
+///
+/// Scans the (scope_id, sequence) index with the sequence bounds as scan
+/// keys, so only turns inside the range are visited, in sequence order.
+pub fn turn_get_range_heap(
+    scope_id: ScopeId,
+    from_seq: i32,
+    to_seq: i32,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<TurnRow>> {
+    let rel = open_relation(turn::TABLE_NAME, LockMode::AccessShare)?;
+    let index_rel = open_index(turn::SCOPE_SEQ_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_keys: [pg_sys::ScanKeyData; 3] = [
+        pg_sys::ScanKeyData::default(),
+        pg_sys::ScanKeyData::default(),
+        pg_sys::ScanKeyData::default(),
+    ];
+
+    init_scan_key(
+        &mut scan_keys[0],
+        1, // First column of index (scope_id)
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(scope_id.as_uuid()),
+    );
+
+    init_scan_key(
+        &mut scan_keys[1],
+        2, // Second column of index (sequence)
+        BTreeStrategy::GreaterOrEqual,
+        operator_oids::INT4_GE,
+        i32_to_datum(from_seq),
+    );
+
+    init_scan_key(
+        &mut scan_keys[2],
+        2,
+        BTreeStrategy::LessOrEqual,
+        operator_oids::INT4_LE,
+        i32_to_datum(to_seq),
+    );
+
+    let mut scanner =
+        unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 3, scan_keys.as_mut_ptr()) };
+
+    let tuple_desc = rel.tuple_desc();
+    let mut results = Vec::new();
+
+    for tuple in &mut scanner {
+        let row = unsafe { tuple_to_turn(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) == Some(tenant_id.as_uuid()) {
+            results.push(row);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Upper bound on the up-front allocation in `turn_get_recent_heap`, so a
+/// huge `n` from the caller does not reserve memory for turns that don't exist.
+const RECENT_TURNS_MAX_PREALLOC: usize = 1024;
+
+/// Get the last `n` turns of a scope, in sequence order, using direct heap
+/// operations.
+///
+/// Scans the (scope_id, sequence) index backward from the newest turn and
+/// stops once `n` turns have been collected.
+pub fn turn_get_recent_heap(
+    scope_id: ScopeId,
+    n: usize,
+    tenant_id: TenantId,
+) -> CaliberResult<Vec<TurnRow>> {
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    let rel = open_relation(turn::TABLE_NAME, LockMode::AccessShare)?;
+    let index_rel = open_index(turn::SCOPE_SEQ_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1, // First column of index (scope_id)
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(scope_id.as_uuid()),
+    );
+
+    let scanner =
+        unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) }.backward();
+
+    let tuple_desc = rel.tuple_desc();
+    let mut results = Vec::with_capacity(n.min(RECENT_TURNS_MAX_PREALLOC));
+
+    for tuple in scanner {
+        let row = unsafe { tuple_to_turn(tuple, tuple_desc) }?;
+        if row.tenant_id.map(|t| t.as_uuid()) != Some(tenant_id.as_uuid()) {
+            continue;
+        }
+        results.push(row);
+        if results.len() == n {
+            break;
+        }
+    }
+
+    // Collected newest first
+    results.reverse();
+    Ok(results)
+}
+
+/// Partial update of a turn's content and tool fields.
+///
+/// Fields left as `None` are unchanged; `Some(None)` clears a JSONB column.
+pub struct TurnUpdateHeapParams<'a> {
+    pub turn_id: TurnId,
+    pub tenant_id: TenantId,
+    pub content: Option<&'a str>,
+    pub token_count: Option<i32>,
+    pub tool_calls: Option<Option<&'a serde_json::Value>>,
+    pub tool_results: Option<Option<&'a serde_json::Value>>,
+    pub metadata: Option<Option<&'a serde_json::Value>>,
+}
+
+/// Update a turn using direct heap operations.
+///
+/// Returns `Ok(false)` if the turn does not exist or belongs to another tenant.
+pub fn turn_update_heap(params: TurnUpdateHeapParams<'_>) -> CaliberResult<bool> {
+    let TurnUpdateHeapParams {
+        turn_id,
+        tenant_id,
+        content,
+        token_count,
+        tool_calls,
+        tool_results,
+        metadata,
+    } = params;
+
+    let rel = open_relation(turn::TABLE_NAME, LockMode::RowExclusive)?;
+    validate_turn_relation(&rel)?;
+    let index_rel = open_index(turn::PK_INDEX)?;
+    let snapshot = get_active_snapshot();
+
+    let mut scan_key = pg_sys::ScanKeyData::default();
+    init_scan_key(
+        &mut scan_key,
+        1,
+        BTreeStrategy::Equal,
+        operator_oids::UUID_EQ,
+        uuid_to_datum(turn_id.as_uuid()),
+    );
+
+    let mut scanner = unsafe { IndexScanner::new(&rel, &index_rel, snapshot, 1, &mut scan_key) };
+
+    let old_tuple = match scanner.next() {
+        Some(t) => t,
+        None => return Ok(false),
+    };
+
+    let tuple_desc = rel.tuple_desc();
+    let existing_tenant = unsafe { extract_uuid(old_tuple, tuple_desc, turn::TENANT_ID)? };
+    if existing_tenant != Some(tenant_id.as_uuid()) {
+        return Ok(false);
+    }
+    let (mut values, mut nulls) = unsafe { extract_values_and_nulls(old_tuple, tuple_desc) }?;
+
+    if let Some(new_content) = content {
+        values[turn::CONTENT as usize - 1] = string_to_datum(new_content);
+    }
+
+    if let Some(new_count) = token_count {
+        values[turn::TOKEN_COUNT as usize - 1] = i32_to_datum(new_count);
+    }
+
+    for (column, update) in [
+        (turn::TOOL_CALLS, tool_calls),
+        (turn::TOOL_RESULTS, tool_results),
+        (turn::METADATA, metadata),
+    ] {
+        match update {
+            Some(Some(value)) => {
+                values[column as usize - 1] = json_to_datum(value);
+                nulls[column as usize - 1] = false;
+            }
+            Some(None) => nulls[column as usize - 1] = true,
+            None => {}
+        }
+    }
+
+    let new_tuple = form_tuple(&rel, &values, &nulls)?;
+    let old_tid = scanner.current_tid().ok_or_else(|| {
+        CaliberError::Storage(StorageError::UpdateFailed {
+            entity_type: EntityType::Turn,
+            id: turn_id.as_uuid(),
+            reason: "Failed to get TID of existing tuple".to_string(),
+        })
+    })?;
+
+    unsafe { update_tuple(&rel, &old_tid, new_tuple)? };
+    unsafe { update_indexes_for_insert(&rel, new_tuple, &values, &nulls)? };
+    Ok(true)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================